@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use console::style;
+use dialoguer::{Confirm, Input, Select};
+use dinoe_core::agent::{ApprovalHandler, DenyApprover};
+use dinoe_core::tools::UserInputHandler;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const ARGS_PREVIEW_MAX: usize = 2_000;
+
+/// Who answers approvals and `ask_user` questions.
+#[derive(Clone)]
+pub struct Prompts {
+    pub approver: Arc<dyn ApprovalHandler>,
+    /// Without a handler, `ask_user` tells the model nobody can answer.
+    pub user_input: Option<Arc<dyn UserInputHandler>>,
+}
+
+impl Prompts {
+    /// Asks on the terminal.
+    pub fn console() -> Self {
+        Self {
+            approver: Arc::new(ConsoleApprover::new()),
+            user_input: Some(Arc::new(ConsoleUserInput::new())),
+        }
+    }
+
+    /// Nobody is at the terminal: mutating calls that need approval are refused.
+    pub fn none() -> Self {
+        Self {
+            approver: Arc::new(DenyApprover),
+            user_input: None,
+        }
+    }
+}
+
+pub struct ConsoleApprover {
+    prompt_lock: Mutex<()>,
+}
+
+impl ConsoleApprover {
+    pub fn new() -> Self {
+        Self {
+            prompt_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalHandler for ConsoleApprover {
+    async fn approve(&self, tool_name: &str, args: &serde_json::Value) -> bool {
+        let _guard = self.prompt_lock.lock().await;
+
+        let rendered = render_args(args);
+        let tool_name = tool_name.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            eprintln!();
+            eprintln!(
+                "  {} {}",
+                style("?").yellow().bold(),
+                style(format!("Approve tool call: {tool_name}")).bold()
+            );
+            for line in rendered.lines() {
+                eprintln!("    {}", style(line).dim());
+            }
+
+            Confirm::new()
+                .with_prompt("  Run it?")
+                .default(false)
+                .interact()
+                .unwrap_or(false)
+        })
+        .await
+        .unwrap_or(false)
+    }
+}
+
+pub struct ConsoleUserInput {
+    prompt_lock: Mutex<()>,
+}
+
+impl ConsoleUserInput {
+    pub fn new() -> Self {
+        Self {
+            prompt_lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl UserInputHandler for ConsoleUserInput {
+    async fn ask(&self, question: &str, options: &[String]) -> Option<String> {
+        let _guard = self.prompt_lock.lock().await;
+
+        let question = question.to_string();
+        let options = options.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            eprintln!();
+            eprintln!(
+                "  {} {}",
+                style("?").cyan().bold(),
+                style(&question).bold()
+            );
+
+            if !options.is_empty() {
+                let mut items = options.clone();
+                items.push("Something else...".to_string());
+                let choice = Select::new()
+                    .items(&items)
+                    .default(0)
+                    .interact_opt()
+                    .ok()??;
+                if choice < options.len() {
+                    return Some(options[choice].clone());
+                }
+            }
+
+            Input::<String>::new()
+                .with_prompt("  Answer")
+                .allow_empty(true)
+                .interact_text()
+                .ok()
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+}
+
+fn render_args(args: &serde_json::Value) -> String {
+    let rendered = match args {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => format!("{key}: {s}"),
+                other => format!("{key}: {other}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    };
+
+    if rendered.chars().count() > ARGS_PREVIEW_MAX {
+        let truncated: String = rendered.chars().take(ARGS_PREVIEW_MAX).collect();
+        format!("{truncated}\n... [truncated]")
+    } else {
+        rendered
+    }
+}
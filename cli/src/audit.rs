@@ -0,0 +1,92 @@
+use anyhow::Result;
+use chrono::{Duration, Local};
+use console::style;
+use dinoe_core::audit::{self, AuditQuery};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct AuditArgs {
+    /// Only show this session (see --sessions)
+    #[arg(long)]
+    session: Option<String>,
+    /// Only show calls of this tool
+    #[arg(long)]
+    tool: Option<String>,
+    /// Only show calls from the last N hours
+    #[arg(long)]
+    hours: Option<i64>,
+    /// Only show failed calls
+    #[arg(long)]
+    failed: bool,
+    /// Maximum number of calls to show
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+    /// Show per-tool totals instead of individual calls
+    #[arg(long)]
+    stats: bool,
+    /// List recorded sessions
+    #[arg(long)]
+    sessions: bool,
+}
+
+pub fn handle_command(args: AuditArgs) -> Result<()> {
+    let dir = audit::audit_dir();
+
+    if args.sessions {
+        let sessions = audit::sessions(&dir)?;
+        if sessions.is_empty() {
+            println!("{} No audit logs in {}", style("!").yellow(), dir.display());
+        }
+        for session in sessions {
+            println!("{}", session);
+        }
+        return Ok(());
+    }
+
+    let query = AuditQuery {
+        session: args.session,
+        tool: args.tool,
+        since: args.hours.map(|h| Local::now() - Duration::hours(h)),
+        failed_only: args.failed,
+        limit: if args.stats { None } else { Some(args.limit) },
+    };
+    let records = audit::query(&dir, &query)?;
+    if records.is_empty() {
+        println!("{} No matching tool calls", style("!").yellow());
+        return Ok(());
+    }
+
+    if args.stats {
+        println!(
+            "{:<24} {:>8} {:>8} {:>12}",
+            "tool", "calls", "failed", "avg ms"
+        );
+        for stat in audit::stats(&records) {
+            println!(
+                "{:<24} {:>8} {:>8} {:>12}",
+                stat.tool,
+                stat.calls,
+                stat.failures,
+                stat.total_duration_ms / stat.calls as u64
+            );
+        }
+        return Ok(());
+    }
+
+    for record in records {
+        let status = if record.success {
+            style("ok").green()
+        } else {
+            style("failed").red()
+        };
+        println!(
+            "{} {} {} {}ms args:{} [{}]",
+            record.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            style(&record.tool).bold(),
+            status,
+            record.duration_ms,
+            record.args_sha256.get(..12).unwrap_or(&record.args_sha256),
+            style(&record.session).dim()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::{Result, bail};
+use console::style;
+use dinoe_core::config;
+use dinoe_core::keychain;
+use std::io::{IsTerminal, Read};
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum AuthCommands {
+    /// Store a provider's API key in the system keychain
+    Set {
+        /// openai, openrouter, zai or glm
+        provider: String,
+    },
+    /// Delete a provider's API key from the system keychain
+    Remove { provider: String },
+}
+
+pub fn handle_command(command: AuthCommands) -> Result<()> {
+    match command {
+        AuthCommands::Set { provider } => {
+            let provider = provider.to_lowercase();
+            // Piped keys keep them out of the shell history.
+            let key = if std::io::stdin().is_terminal() {
+                dialoguer::Password::new()
+                    .with_prompt(format!("{provider} API key"))
+                    .interact()?
+            } else {
+                let mut key = String::new();
+                std::io::stdin().read_to_string(&mut key)?;
+                key
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                bail!("API key cannot be empty");
+            }
+            keychain::set(&provider, key)?;
+            println!(
+                "{} Stored the {} API key in the system keychain",
+                style("✓").green(),
+                provider
+            );
+            if config::config_exists()
+                && let Ok(config) = config::load_config()
+                && !config.api_key.is_empty()
+                && keychain::provider_name(&config) == provider
+            {
+                println!(
+                    "{} config.toml still has an api_key, which is used first. Remove it with: dinoe config set api_key ''",
+                    style("!").yellow()
+                );
+            }
+            Ok(())
+        }
+        AuthCommands::Remove { provider } => {
+            let provider = provider.to_lowercase();
+            if keychain::remove(&provider)? {
+                println!(
+                    "{} Removed the {} API key from the system keychain",
+                    style("✓").green(),
+                    provider
+                );
+            } else {
+                println!(
+                    "{} No {} API key in the keychain",
+                    style("!").yellow(),
+                    provider
+                );
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use dinoe_core::agent::{AgentEvent, TurnOutcome};
+use dinoe_core::config::{self, Config};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc;
+
+use crate::render::{self, EventPrinter};
+use crate::serve::Server;
+
+const SOCKET_FILE: &str = "dinoe.sock";
+
+/// A turn a client asks for, the first line of JSON on a connection.
+#[derive(Serialize, Deserialize)]
+struct Request {
+    message: String,
+    /// The session to continue; a new one when unset.
+    session: Option<String>,
+    /// The client's `fingerprint`.
+    config: u64,
+}
+
+/// What the daemon answers, one line of JSON each. The last is `Done`, `Error` or
+/// `Refused`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Reply {
+    Event {
+        event: AgentEvent,
+    },
+    Done {
+        session: String,
+        outcome: String,
+        text: Option<String>,
+        error: Option<String>,
+    },
+    Error {
+        message: String,
+    },
+    /// The client runs the turn itself.
+    Refused {
+        reason: String,
+    },
+}
+
+pub(crate) fn socket_path() -> PathBuf {
+    config::get_data_dir().join(SOCKET_FILE)
+}
+
+/// A hash of the settings, workspace and project a turn runs with. The daemon only takes
+/// turns from clients with its own, so flags and project configs are respected, and a
+/// different build of dinoe runs its turns itself.
+fn fingerprint(config: &Config) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    serde_json::to_string(config)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    config.workspace_dir.hash(&mut hasher);
+    config.project_dir.hash(&mut hasher);
+    hasher.finish()
+}
+
+async fn send(writer: &mut (impl AsyncWrite + Unpin), message: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// The daemon's end of the socket, which only its user may connect to. The socket file
+/// is removed when this is dropped.
+pub(crate) struct Listener {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Listener {
+    pub(crate) fn bind(path: &Path) -> Result<Self> {
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                bail!("A daemon is already listening on {}", path.display());
+            }
+            // Left behind by a daemon that did not stop cleanly.
+            std::fs::remove_file(path)?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Takes turns for clients until the daemon stops, each on its own task.
+    pub(crate) async fn serve(&self, server: Arc<Server>) {
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, _)) => {
+                    let server = server.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(stream, server).await {
+                            tracing::warn!("A daemon client failed: {e:#}");
+                        }
+                    });
+                }
+                Err(e) => tracing::warn!("Failed to accept a daemon client: {e}"),
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Runs the turn a client asks for and streams its events back. A client that hangs up,
+/// as it does on Ctrl+C, cancels the turn.
+async fn handle(stream: UnixStream, server: Arc<Server>) -> Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    let Some(line) = lines.next_line().await? else {
+        return Ok(());
+    };
+    let request: Request = serde_json::from_str(&line).context("Invalid request")?;
+    if request.config != fingerprint(&server.config()) {
+        let reason = "The daemon runs with other settings".to_string();
+        return send(&mut write, &Reply::Refused { reason }).await;
+    }
+    let opened = match &request.session {
+        Some(id) => server
+            .session(id)
+            .map(|session| (id.clone(), session))
+            .map_err(|e| e.to_string()),
+        None => server.create_session(None).map_err(|e| format!("{e:#}")),
+    };
+    let (id, session) = match opened {
+        Ok(opened) => opened,
+        Err(message) => return send(&mut write, &Reply::Error { message }).await,
+    };
+    let (events, mut received) = mpsc::unbounded_channel();
+    let turn = match server.send(&session, request.message, events) {
+        Ok(turn) => turn,
+        Err(e) => {
+            let message = e.to_string();
+            return send(&mut write, &Reply::Error { message }).await;
+        }
+    };
+
+    let mut connected = true;
+    loop {
+        tokio::select! {
+            event = received.recv() => {
+                let Some(event) = event else { break };
+                if connected && send(&mut write, &Reply::Event { event }).await.is_err() {
+                    connected = false;
+                    session.cancel();
+                }
+            }
+            _ = lines.next_line(), if connected => {
+                connected = false;
+                session.cancel();
+            }
+        }
+    }
+    let outcome = turn.await.context("The turn failed")?;
+    if !connected {
+        return Ok(());
+    }
+    let reply = Reply::Done {
+        session: id,
+        outcome: outcome.label().to_string(),
+        text: outcome.text().map(str::to_string),
+        error: match &outcome {
+            TurnOutcome::Error(e) => Some(format!("{e:#}")),
+            _ => None,
+        },
+    };
+    send(&mut write, &reply).await
+}
+
+/// Has a running daemon take the turn and prints it like a local one, returning the
+/// exit code. `None` when no daemon is listening or it will not take the turn, so the
+/// caller runs it itself.
+pub(crate) async fn chat(
+    config: &Config,
+    message: &str,
+    resume: Option<&str>,
+) -> Result<Option<u8>> {
+    let Ok(stream) = UnixStream::connect(socket_path()).await else {
+        return Ok(None);
+    };
+    let (read, mut write) = stream.into_split();
+    let request = Request {
+        message: message.to_string(),
+        session: resume.map(str::to_string),
+        config: fingerprint(config),
+    };
+    send(&mut write, &request).await?;
+
+    let mut lines = BufReader::new(read).lines();
+    let printer = EventPrinter::new();
+    loop {
+        let line = tokio::select! {
+            line = lines.next_line() => line?,
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\x1b[90mCancelled\x1b[0m");
+                return Ok(Some(crate::EXIT_CANCELLED));
+            }
+        };
+        let Some(line) = line else {
+            bail!("The daemon closed the connection");
+        };
+        let reply: Reply = serde_json::from_str(&line).context("Invalid reply from the daemon")?;
+        match reply {
+            Reply::Event { event } => printer.print(&event),
+            Reply::Refused { reason } => {
+                tracing::info!("Running the turn here: {reason}");
+                return Ok(None);
+            }
+            Reply::Error { message } => {
+                eprintln!("❌ Error: {}", message);
+                return Ok(Some(crate::EXIT_FAILED));
+            }
+            Reply::Done {
+                session,
+                outcome,
+                text,
+                error,
+            } => {
+                tracing::info!("The daemon answered in session {session}");
+                // A streamed answer has been printed already.
+                if !printer.streamed()
+                    && let Some(text) = &text
+                {
+                    render::print_separator();
+                    crate::markdown::print_markdown(text);
+                }
+                let code = match outcome.as_str() {
+                    "completed" => 0,
+                    "max_iterations" => {
+                        eprintln!(
+                            "\x1b[33m⚠ Stopped at max_iterations before a final answer\x1b[0m"
+                        );
+                        crate::EXIT_INCOMPLETE
+                    }
+                    "cancelled" => {
+                        eprintln!("\x1b[90mCancelled\x1b[0m");
+                        crate::EXIT_CANCELLED
+                    }
+                    _ => {
+                        eprintln!("❌ Error: {}", error.unwrap_or_default());
+                        crate::EXIT_FAILED
+                    }
+                };
+                return Ok(Some(code));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dinoe_core::agent::ToolRegistry;
+    use dinoe_core::testing::{MockProvider, TempWorkspace};
+
+    /// Sends `request` to `server` and collects the replies.
+    async fn ask(server: &Arc<Server>, request: &Request) -> Vec<Reply> {
+        let (client, daemon) = UnixStream::pair().unwrap();
+        tokio::spawn(handle(daemon, server.clone()));
+        let (read, mut write) = client.into_split();
+        send(&mut write, request).await.unwrap();
+        let mut lines = BufReader::new(read).lines();
+        let mut replies = Vec::new();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            replies.push(serde_json::from_str(&line).unwrap());
+        }
+        replies
+    }
+
+    #[tokio::test]
+    async fn shares_sessions_between_clients() {
+        let workspace = TempWorkspace::new();
+        let provider = Arc::new(MockProvider::new().with_text("Hi").with_text("Again"));
+        let agent = workspace
+            .agent(provider.clone(), ToolRegistry::new())
+            .with_streaming(false);
+        let server = Arc::new(
+            Server::new(Config::default(), workspace.join("sessions"), None)
+                .with_default_agent(agent),
+        );
+        let mut request = Request {
+            message: "Hello".into(),
+            session: None,
+            config: fingerprint(&server.config()),
+        };
+
+        let replies = ask(&server, &request).await;
+        assert!(matches!(replies[0], Reply::Event { .. }));
+        let Some(Reply::Done {
+            session,
+            outcome,
+            text,
+            ..
+        }) = replies.last()
+        else {
+            panic!("The turn did not finish");
+        };
+        assert_eq!(
+            (outcome.as_str(), text.as_deref()),
+            ("completed", Some("Hi"))
+        );
+
+        request.session = Some(session.clone());
+        request.message = "And now?".into();
+        let replies = ask(&server, &request).await;
+        assert!(matches!(replies.last(), Some(Reply::Done { text: Some(t), .. }) if t == "Again"));
+        let sent = &provider.requests()[1].messages;
+        assert!(sent.iter().any(|m| m.content == "Hello"));
+
+        request.config += 1;
+        let replies = ask(&server, &request).await;
+        assert!(matches!(replies[..], [Reply::Refused { .. }]));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(unix)]
+pub(crate) mod ipc;
+
+use anyhow::Result;
+use chrono::Local;
+use console::style;
+use dinoe_core::config::Config;
+use dinoe_core::scheduler::{Beat, Heartbeat};
+use dinoe_core::transcript;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::scheduler::Scheduler;
+use crate::serve::Server;
+
+/// Runs scheduled jobs and the heartbeat until stopped, without a terminal to ask on,
+/// and takes `dinoe chat -m` turns over a unix socket with its agent kept warm.
+pub async fn run(
+    config: Config,
+    mut reloads: mpsc::UnboundedReceiver<Result<Config>>,
+) -> Result<()> {
+    let heartbeat = Heartbeat::from_config(&config.heartbeat, &config.workspace_dir)?;
+    let mut scheduler = Scheduler::new(&config);
+    let server = Arc::new(Server::new(config, transcript::sessions_dir(), None));
+    server.agent(None)?;
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            while let Some(config) = reloads.recv().await {
+                match config.and_then(|config| server.reload(config)) {
+                    Ok(()) => eprintln!("Config and skills reloaded"),
+                    Err(e) => eprintln!("⚠ Keeping the previous config: {e:#}"),
+                }
+            }
+        }
+    });
+    #[cfg(unix)]
+    {
+        let listener = Arc::new(ipc::Listener::bind(&ipc::socket_path())?);
+        println!(
+            "{} Listening for dinoe chat on {}",
+            style("●").green(),
+            listener.path().display()
+        );
+        let server = server.clone();
+        tokio::spawn(async move { listener.serve(server).await });
+    }
+    let config = server.config();
+
+    match &heartbeat {
+        Some(heartbeat) => println!(
+            "{} Daemon running, heartbeat every {} min from {} (Ctrl+C to stop)",
+            style("●").green(),
+            config.heartbeat.interval_minutes,
+            heartbeat.checklist_path().display()
+        ),
+        None => println!(
+            "{} Daemon running, heartbeat off (Ctrl+C to stop)",
+            style("●").green()
+        ),
+    }
+
+    loop {
+        let (config, agent) = (server.config(), server.agent(None)?);
+        scheduler.tick(&config, &agent).await;
+
+        let mut wait = scheduler.wait();
+        if let Some(heartbeat) = &heartbeat {
+            let now = Local::now();
+            if heartbeat.is_due(now)? {
+                match heartbeat.beat(&agent, now).await {
+                    Ok(Beat::Acted(text)) => println!(
+                        "{} [{}] Heartbeat\n{}\n",
+                        style("♥").red(),
+                        now.format("%Y-%m-%d %H:%M:%S"),
+                        text
+                    ),
+                    Ok(Beat::Idle) => tracing::info!("Heartbeat: nothing to do"),
+                    Ok(Beat::Empty) => tracing::info!("Heartbeat: the checklist is empty"),
+                    Err(e) => eprintln!("{} Heartbeat failed: {:#}", style("✗").red(), e),
+                }
+            }
+            let next = heartbeat.next_run(Local::now())?;
+            if let Ok(until) = (next - Local::now()).to_std() {
+                wait = wait.min(until.max(Duration::from_secs(1)));
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("{} Daemon stopped", style("●").yellow());
+                return Ok(());
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{Result, bail};
+use console::style;
+use dinoe_core::config::Config;
+use dinoe_core::skills::{self, SkillRegistry};
+use dinoe_core::tools::Sandbox;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::approval::Prompts;
+use crate::settings;
+
+/// Checks the config, the workspace and what the installed skills need. Skills that
+/// cannot be used are warnings; the rest are errors.
+pub fn run(config: &Config) -> Result<()> {
+    let mut errors = 0;
+
+    println!("{}", style("Config").bold());
+    if let Err(e) = settings::validate() {
+        println!("{} {}", style("✗").red(), e);
+        errors += 1;
+    }
+
+    println!();
+    println!("{}", style("Workspace").bold());
+    let workspace = &config.workspace_dir;
+    if workspace.is_dir() {
+        println!("{} {}", style("✓").green(), workspace.display());
+    } else if workspace.exists() {
+        println!(
+            "{} {} is not a directory",
+            style("✗").red(),
+            workspace.display()
+        );
+        errors += 1;
+    } else {
+        println!(
+            "{} {} does not exist yet; it is created on the first run",
+            style("!").yellow(),
+            workspace.display()
+        );
+    }
+
+    println!();
+    println!("{}", style("Skills").bold());
+    let registry = SkillRegistry::load_from_config(config)?;
+    let mut skills = registry.list();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    // Which tools the agent has depends on the config, so ask the agent itself.
+    let tools: Option<Vec<String>> = crate::build_agent(config, Prompts::none())
+        .ok()
+        .map(|agent| agent.tool_specs().into_iter().map(|t| t.name).collect());
+    if skills.is_empty() {
+        println!("{} No skills enabled", style("!").yellow());
+    }
+    if skills.iter().any(|skill| !skill.tools.is_empty()) && !Sandbox::available() {
+        println!(
+            "{} bubblewrap (bwrap) is not installed, so skill tools run without a sandbox \
+             and ask before every run",
+            style("!").yellow()
+        );
+    }
+    let mut seen: HashMap<String, PathBuf> = HashMap::new();
+    for dir in skills::skill_dirs(config) {
+        for skill in crate::skills::installed_skills(&dir) {
+            if let Some(replaced) = seen.insert(skill.name.clone(), dir.clone()) {
+                println!(
+                    "{} {} in {} replaces the one in {}",
+                    style("!").yellow(),
+                    skill.name,
+                    dir.display(),
+                    replaced.display()
+                );
+            }
+        }
+    }
+    for skill in &skills {
+        let mut problems: Vec<String> = skill.unavailable(&config.model).into_iter().collect();
+        if let Some(tools) = &tools {
+            problems.extend(
+                skill
+                    .missing_tools(tools)
+                    .into_iter()
+                    .map(|tool| format!("needs the tool '{tool}', which the agent does not have")),
+            );
+        }
+        if problems.is_empty() {
+            println!("{} {}", style("✓").green(), skill.name);
+        } else {
+            println!(
+                "{} {}: {}",
+                style("!").yellow(),
+                skill.name,
+                problems.join("; ")
+            );
+        }
+    }
+
+    if errors > 0 {
+        bail!("Found {} problem(s)", errors);
+    }
+    Ok(())
+}
@@ -0,0 +1,527 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use console::style;
+use dinoe_core::agent::{AgentEvent, EventSink, TurnOutcome};
+use dinoe_core::builder::PROJECT_BOOTSTRAP;
+use dinoe_core::config::{Config, MemoryConfig};
+use dinoe_core::providers;
+use dinoe_core::skills;
+use dinoe_core::traits::{ChatMessage, ChatRequest};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::approval::Prompts;
+
+const JUDGE_PROMPT: &str = "You check whether an AI assistant's answer meets a criterion. \
+Reply with PASS or FAIL on the first line, then one sentence saying why.";
+
+#[derive(Args)]
+pub struct EvalArgs {
+    /// A YAML suite of prompts and what their answers must satisfy
+    suite: PathBuf,
+    /// Models to compare, instead of the suite's `models`
+    #[arg(long, value_delimiter = ',', value_name = "MODEL,...")]
+    models: Vec<String>,
+    /// Run the cases with the current bootstrap files and with each of `prompt_variants`
+    /// in the config, and report the cases they differ on
+    #[arg(long)]
+    compare_prompts: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Suite {
+    name: Option<String>,
+    /// Models of the configured provider; the configured model when empty.
+    #[serde(default)]
+    models: Vec<String>,
+    /// The model `judge` checks ask; the configured model when unset.
+    judge: Option<String>,
+    cases: Vec<Case>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Case {
+    name: Option<String>,
+    prompt: String,
+    /// Files written to the case's workspace before it runs, by path.
+    #[serde(default)]
+    files: BTreeMap<PathBuf, String>,
+    #[serde(default)]
+    expect: Vec<Expectation>,
+}
+
+/// One check of a case, with exactly one field set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Expectation {
+    /// The answer matches this regular expression.
+    regex: Option<String>,
+    /// This file exists in the workspace after the run.
+    file_exists: Option<PathBuf>,
+    /// The judge model agrees that the answer meets this.
+    judge: Option<String>,
+}
+
+enum Check {
+    Regex(Regex),
+    FileExists(PathBuf),
+    Judge(String),
+}
+
+impl Expectation {
+    fn check(&self) -> Result<Check> {
+        match (&self.regex, &self.file_exists, &self.judge) {
+            (Some(pattern), None, None) => Ok(Check::Regex(
+                Regex::new(pattern).with_context(|| format!("Invalid regex '{pattern}'"))?,
+            )),
+            (None, Some(path), None) => Ok(Check::FileExists(path.clone())),
+            (None, None, Some(criterion)) => Ok(Check::Judge(criterion.clone())),
+            _ => bail!("Each expectation needs exactly one of regex, file_exists and judge"),
+        }
+    }
+}
+
+impl Suite {
+    fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let suite: Suite = serde_yaml::from_str(&text)
+            .with_context(|| format!("Invalid eval suite {}", path.display()))?;
+        if suite.cases.is_empty() {
+            bail!("{} has no cases", path.display());
+        }
+        Ok(suite)
+    }
+}
+
+/// Bootstrap files written to a run's workspace, by file name.
+type Bootstrap = Vec<(String, String)>;
+
+/// The current bootstrap files, the project's in place of the workspace's, then each of
+/// `prompt_variants` with its files in their place.
+pub(crate) fn prompt_variants(config: &Config) -> Result<Vec<(String, Bootstrap)>> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    let mut current = Vec::new();
+    for name in PROJECT_BOOTSTRAP {
+        let path = config
+            .project_dir
+            .as_ref()
+            .map(|dir| dir.join(name))
+            .filter(|path| path.exists())
+            .unwrap_or_else(|| config.workspace_dir.join(name));
+        if path.exists() {
+            current.push((name.to_string(), read(&path)?));
+        }
+    }
+
+    let mut variants = vec![("current".to_string(), current.clone())];
+    for (variant, files) in &config.prompt_variants {
+        let mut bootstrap = current.clone();
+        for (name, path) in files {
+            if !PROJECT_BOOTSTRAP.contains(&name.as_str()) {
+                bail!(
+                    "{variant}: {name} is not one of {}",
+                    PROJECT_BOOTSTRAP.join(", ")
+                );
+            }
+            let content =
+                read(&config.workspace_dir.join(path)).with_context(|| variant.clone())?;
+            bootstrap.retain(|(file, _)| file != name);
+            bootstrap.push((name.clone(), content));
+        }
+        variants.push((variant.clone(), bootstrap));
+    }
+    Ok(variants)
+}
+
+/// How one case went with one model.
+struct Run {
+    passed: bool,
+    elapsed: Duration,
+    cost: Option<f64>,
+}
+
+/// A workspace for one run, deleted afterwards.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("dinoe-eval-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub async fn handle_command(args: EvalArgs, config: Config) -> Result<ExitCode> {
+    let suite = Suite::load(&args.suite)?;
+    let checks = suite
+        .cases
+        .iter()
+        .map(|case| case.expect.iter().map(Expectation::check).collect())
+        .collect::<Result<Vec<Vec<Check>>>>()?;
+    let models = match (args.models.is_empty(), suite.models.is_empty()) {
+        (false, _) => args.models.clone(),
+        (true, false) => suite.models.clone(),
+        (true, true) => vec![config.model.clone()],
+    };
+    // Without --compare-prompts the runs have no bootstrap files.
+    let prompts = if args.compare_prompts {
+        if config.prompt_variants.is_empty() {
+            bail!("No prompts to compare: add [prompt_variants.<name>] to the config");
+        }
+        prompt_variants(&config)?
+    } else {
+        vec![(String::new(), Vec::new())]
+    };
+    let judge = suite.judge.clone().unwrap_or_else(|| config.model.clone());
+    let title = suite
+        .name
+        .clone()
+        .unwrap_or_else(|| args.suite.display().to_string());
+    let names: Vec<String> = suite
+        .cases
+        .iter()
+        .enumerate()
+        .map(|(i, case)| {
+            case.name
+                .clone()
+                .unwrap_or_else(|| format!("Case {}", i + 1))
+        })
+        .collect();
+    let variants = if args.compare_prompts {
+        format!(", {} prompts", prompts.len())
+    } else {
+        String::new()
+    };
+    eprintln!(
+        "{} Evaluating {} ({} cases, {} models{})",
+        style("▶").cyan(),
+        title,
+        suite.cases.len(),
+        models.len(),
+        variants
+    );
+
+    let mut results: Vec<(String, Vec<Run>)> = Vec::new();
+    for (model, (prompt, bootstrap)) in models
+        .iter()
+        .flat_map(|model| prompts.iter().map(move |prompt| (model, prompt)))
+    {
+        let label = match (args.compare_prompts, models.len()) {
+            (false, _) => model.clone(),
+            (true, 1) => prompt.clone(),
+            (true, _) => format!("{model} {prompt}"),
+        };
+        eprintln!("\n{}", style(&label).bold());
+        let mut runs = Vec::new();
+        for ((case, checks), name) in suite.cases.iter().zip(&checks).zip(&names) {
+            let run = run_case(&config, model, &judge, case, checks, bootstrap).await;
+            let run = match run {
+                Ok((run, failures)) => {
+                    let mark = if run.passed {
+                        style("✓").green()
+                    } else {
+                        style("✗").red()
+                    };
+                    eprintln!("  {} {} {:.1}s", mark, name, run.elapsed.as_secs_f64());
+                    for failure in failures {
+                        eprintln!("      {failure}");
+                    }
+                    run
+                }
+                Err(e) => {
+                    eprintln!("  {} {}: {:#}", style("✗").red(), name, e);
+                    Run {
+                        passed: false,
+                        elapsed: Duration::ZERO,
+                        cost: None,
+                    }
+                }
+            };
+            runs.push(run);
+        }
+        results.push((label, runs));
+    }
+
+    let column = match (args.compare_prompts, models.len()) {
+        (false, _) => "model",
+        (true, 1) => "prompt",
+        (true, _) => "model prompt",
+    };
+    print_report(&title, column, &results);
+    if args.compare_prompts {
+        // Each model's prompts are compared with each other.
+        print_differences(&names, results.chunks(prompts.len()));
+    }
+    let all_passed = results.iter().flat_map(|(_, runs)| runs).all(|r| r.passed);
+    Ok(if all_passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(crate::EXIT_FAILED)
+    })
+}
+
+/// Runs `case` with `model` in a fresh workspace that still has the installed skills
+/// and has `bootstrap`, and returns how it went with the checks that failed.
+async fn run_case(
+    config: &Config,
+    model: &str,
+    judge: &str,
+    case: &Case,
+    checks: &[Check],
+    bootstrap: &[(String, String)],
+) -> Result<(Run, Vec<String>)> {
+    let workspace = ScratchDir::create()?;
+    for (name, content) in bootstrap {
+        std::fs::write(workspace.0.join(name), content)?;
+    }
+    for (path, content) in &case.files {
+        let path = workspace.0.join(path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, content)?;
+    }
+    let mut config = config.clone();
+    config.model = model.to_string();
+    config
+        .skill_dirs
+        .insert(0, skills::skills_dir(&config.workspace_dir));
+    config.workspace_dir = workspace.0.clone();
+    // Nothing of a run outlives its workspace.
+    config.memory = MemoryConfig::default();
+    config.checkpoints = false;
+    config.transcripts = false;
+    let agent = crate::build_agent(&config, Prompts::none())?;
+
+    let summary = Arc::new(Mutex::new(None));
+    let sink = {
+        let summary = summary.clone();
+        EventSink::new(move |event| {
+            if let AgentEvent::TurnSummary(turn) = event {
+                *summary.lock().unwrap() = Some(turn);
+            }
+        })
+    };
+    let started = Instant::now();
+    let outcome = agent.process_with_events(&case.prompt, vec![], sink).await;
+    let elapsed = started.elapsed();
+    let cost = summary.lock().unwrap().as_ref().and_then(|turn| {
+        let price = config.prices.get(model)?;
+        Some(price.cost(turn.input_tokens, turn.output_tokens))
+    });
+
+    let mut failures = Vec::new();
+    let answer = match &outcome {
+        TurnOutcome::Completed { text, .. } => text.as_str(),
+        TurnOutcome::Error(e) => {
+            failures.push(format!("The turn failed: {e:#}"));
+            ""
+        }
+        other => {
+            failures.push(format!("The turn ended with {}", other.label()));
+            other.text().unwrap_or_default()
+        }
+    };
+    for check in checks {
+        match check {
+            Check::Regex(re) if !re.is_match(answer) => {
+                failures.push(format!("The answer does not match /{}/", re.as_str()));
+            }
+            Check::FileExists(path) if !workspace.0.join(path).exists() => {
+                failures.push(format!("{} was not created", path.display()));
+            }
+            Check::Judge(criterion) => {
+                if let Err(reason) =
+                    ask_judge(&config, judge, &case.prompt, answer, criterion).await
+                {
+                    failures.push(format!("Judge: {reason}"));
+                }
+            }
+            _ => {}
+        }
+    }
+    let run = Run {
+        passed: failures.is_empty(),
+        elapsed,
+        cost,
+    };
+    Ok((run, failures))
+}
+
+/// Asks `model` whether `answer` meets `criterion`; the reason when it says no.
+async fn ask_judge(
+    config: &Config,
+    model: &str,
+    prompt: &str,
+    answer: &str,
+    criterion: &str,
+) -> std::result::Result<(), String> {
+    let provider = providers::create_provider(config).map_err(|e| e.to_string())?;
+    let messages = [
+        ChatMessage::system(JUDGE_PROMPT),
+        ChatMessage::user(format!(
+            "Criterion: {criterion}\n\nRequest:\n{prompt}\n\nAnswer:\n{answer}"
+        )),
+    ];
+    let request = ChatRequest {
+        messages: &messages,
+        tools: None,
+    };
+    let response = provider
+        .chat(request, model, 0.0)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+    let verdict = response.text_or_empty().trim();
+    let (first, rest) = verdict.split_once('\n').unwrap_or((verdict, ""));
+    if first.trim().to_uppercase().starts_with("PASS") {
+        Ok(())
+    } else {
+        let reason = rest.trim();
+        Err(if reason.is_empty() { first } else { reason }.to_string())
+    }
+}
+
+/// A row per entry of `results`, with `column` over their labels.
+fn print_report(title: &str, column: &str, results: &[(String, Vec<Run>)]) {
+    eprintln!("\n{} {}", style("Summary:").bold(), title);
+    let width = results
+        .iter()
+        .map(|(model, _)| model.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(column.chars().count());
+    println!(
+        "{:width$}  {:>7}  {:>6}  {:>9}  {:>9}",
+        column, "passed", "rate", "avg time", "cost"
+    );
+    for (model, runs) in results {
+        let passed = runs.iter().filter(|r| r.passed).count();
+        let rate = 100.0 * passed as f64 / runs.len().max(1) as f64;
+        let average =
+            runs.iter().map(|r| r.elapsed.as_secs_f64()).sum::<f64>() / runs.len().max(1) as f64;
+        let costs: Option<Vec<f64>> = runs.iter().map(|r| r.cost).collect();
+        let cost = match costs {
+            Some(costs) => format!("${:.4}", costs.iter().sum::<f64>()),
+            None => "–".to_string(),
+        };
+        println!(
+            "{:width$}  {:>7}  {:>5.0}%  {:>8.1}s  {:>9}",
+            model,
+            format!("{passed}/{}", runs.len()),
+            rate,
+            average,
+            cost
+        );
+    }
+}
+
+/// The cases that passed with some of the prompts of a group and failed with others.
+fn print_differences<'a>(cases: &[String], groups: impl Iterator<Item = &'a [(String, Vec<Run>)]>) {
+    eprintln!("\n{}", style("Differences:").bold());
+    let mut any = false;
+    for group in groups {
+        for (i, case) in cases.iter().enumerate() {
+            let passed = |(_, runs): &(String, Vec<Run>)| runs[i].passed;
+            if group.iter().all(passed) || !group.iter().any(passed) {
+                continue;
+            }
+            any = true;
+            let marks: Vec<String> = group
+                .iter()
+                .map(|(label, runs)| {
+                    let mark = if runs[i].passed { "✓" } else { "✗" };
+                    format!("{mark} {label}")
+                })
+                .collect();
+            println!("{}: {}", case, marks.join("  "));
+        }
+    }
+    if !any {
+        println!("Every case passed or failed with all prompts");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_suites() {
+        let suite: Suite = serde_yaml::from_str(
+            "name: PDF\nmodels: [gpt-4o, gpt-4o-mini]\ncases:\n\
+             - prompt: Extract the text of a.pdf into a.txt\n  files:\n    a.pdf: stub\n  expect:\n\
+             \x20   - regex: (?i)done\n    - file_exists: a.txt\n    - judge: Says where the text went\n",
+        )
+        .unwrap();
+        assert_eq!(suite.models, ["gpt-4o", "gpt-4o-mini"]);
+        let checks: Vec<Check> = suite.cases[0]
+            .expect
+            .iter()
+            .map(|e| e.check().unwrap())
+            .collect();
+        assert!(matches!(&checks[0], Check::Regex(re) if re.is_match("Done.")));
+        assert!(matches!(&checks[1], Check::FileExists(path) if path == Path::new("a.txt")));
+        assert!(matches!(&checks[2], Check::Judge(_)));
+
+        let both = Expectation {
+            regex: Some("x".into()),
+            judge: Some("y".into()),
+            ..Default::default()
+        };
+        assert!(both.check().is_err());
+    }
+
+    #[test]
+    fn prompt_variants_replace_bootstrap_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("SOUL.md"), "Be thorough").unwrap();
+        std::fs::write(tmp.path().join("USER.md"), "Ana").unwrap();
+        std::fs::write(tmp.path().join("terse.md"), "Be brief").unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Default::default()
+        };
+        config.prompt_variants.insert(
+            "terse".into(),
+            BTreeMap::from([("SOUL.md".into(), "terse.md".into())]),
+        );
+
+        let variants = prompt_variants(&config).unwrap();
+        let files = |i: usize| -> BTreeMap<&str, &str> {
+            variants[i]
+                .1
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str()))
+                .collect()
+        };
+        assert_eq!(variants[0].0, "current");
+        assert_eq!(files(0)["SOUL.md"], "Be thorough");
+        assert_eq!(variants[1].0, "terse");
+        assert_eq!(files(1)["SOUL.md"], "Be brief");
+        assert_eq!(files(1)["USER.md"], "Ana");
+
+        config
+            .prompt_variants
+            .get_mut("terse")
+            .unwrap()
+            .insert("NOTES.md".into(), "terse.md".into());
+        assert!(prompt_variants(&config).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use dinoe_core::config::DiscordConfig;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::{Channel, Conversation, Incoming};
+
+const GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
+const API_URL: &str = "https://discord.com/api/v10";
+/// GUILD_MESSAGES, DIRECT_MESSAGES and MESSAGE_CONTENT.
+const INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+const MAX_CHARS: usize = 2000;
+
+/// Answers direct messages and messages that mention the bot.
+pub struct Discord {
+    token: String,
+    http: reqwest::Client,
+    /// Learned when the gateway is ready.
+    user_id: Mutex<Option<String>>,
+}
+
+impl Discord {
+    pub fn from_config(config: &DiscordConfig) -> Result<Self> {
+        let token = config
+            .token
+            .clone()
+            .or_else(|| std::env::var("DISCORD_BOT_TOKEN").ok())
+            .context("Set gateways.discord.token in the config or DISCORD_BOT_TOKEN")?;
+        Ok(Self {
+            token,
+            http: reqwest::Client::new(),
+            user_id: Mutex::new(None),
+        })
+    }
+
+    async fn request(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request
+            .header("Authorization", format!("Bot {}", self.token))
+            .send()
+            .await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!("Discord returned {}: {}", status, body["message"]);
+        }
+        Ok(body)
+    }
+
+    /// The message as one for the agent, if it is meant for the bot.
+    fn incoming(&self, message: &Value) -> Option<Incoming> {
+        if message["author"]["bot"].as_bool() == Some(true) {
+            return None;
+        }
+        let me = self.user_id.lock().unwrap().clone()?;
+        let direct = message["guild_id"].is_null();
+        let mentioned = message["mentions"]
+            .as_array()
+            .is_some_and(|users| users.iter().any(|u| u["id"] == me.as_str()));
+        if !direct && !mentioned {
+            return None;
+        }
+        let text = message["content"]
+            .as_str()?
+            .replace(&format!("<@{me}>"), "")
+            .replace(&format!("<@!{me}>"), "");
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let channel = message["channel_id"].as_str()?.to_string();
+        let user = message["author"]["id"].as_str()?.to_string();
+        Some(Incoming {
+            session: format!("discord:{channel}:{user}"),
+            user,
+            text: text.to_string(),
+            reply_to: Conversation {
+                channel,
+                thread: message["id"].as_str().map(str::to_string),
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl Channel for Discord {
+    fn name(&self) -> &'static str {
+        "Discord"
+    }
+
+    fn max_chars(&self) -> usize {
+        MAX_CHARS
+    }
+
+    async fn listen(&self, messages: mpsc::Sender<Incoming>) -> Result<()> {
+        let (socket, _) = tokio_tungstenite::connect_async(GATEWAY_URL).await?;
+        let (mut write, mut read) = socket.split();
+        let identify = json!({
+            "op": 2,
+            "d": {
+                "token": self.token,
+                "intents": INTENTS,
+                "properties": { "os": std::env::consts::OS, "browser": "dinoe", "device": "dinoe" },
+            },
+        });
+        let mut heartbeat: Option<tokio::time::Interval> = None;
+        let mut sequence = Value::Null;
+        loop {
+            let beat = async {
+                match &mut heartbeat {
+                    Some(interval) => interval.tick().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let frame = tokio::select! {
+                _ = beat => {
+                    let beat = json!({ "op": 1, "d": sequence });
+                    write.send(Message::text(beat.to_string())).await?;
+                    continue;
+                }
+                frame = read.next() => frame,
+            };
+            let payload: Value = match frame {
+                Some(Ok(Message::Text(text))) => serde_json::from_str(&text)?,
+                Some(Ok(Message::Close(frame))) => bail!("Discord closed the gateway: {frame:?}"),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => return Ok(()),
+            };
+            if !payload["s"].is_null() {
+                sequence = payload["s"].clone();
+            }
+            match payload["op"].as_u64() {
+                // Hello: start the heartbeat and identify.
+                Some(10) => {
+                    let period = payload["d"]["heartbeat_interval"]
+                        .as_u64()
+                        .unwrap_or(41_250);
+                    let start = tokio::time::Instant::now() + Duration::from_millis(period);
+                    heartbeat = Some(tokio::time::interval_at(
+                        start,
+                        Duration::from_millis(period),
+                    ));
+                    write.send(Message::text(identify.to_string())).await?;
+                }
+                Some(1) => {
+                    let beat = json!({ "op": 1, "d": sequence });
+                    write.send(Message::text(beat.to_string())).await?;
+                }
+                // Reconnect, or the session is invalid.
+                Some(7 | 9) => bail!("Discord asked to reconnect"),
+                Some(0) => match payload["t"].as_str() {
+                    Some("READY") => {
+                        let id = payload["d"]["user"]["id"].as_str().map(str::to_string);
+                        *self.user_id.lock().unwrap() = id;
+                    }
+                    Some("MESSAGE_CREATE") => {
+                        if let Some(message) = self.incoming(&payload["d"]) {
+                            messages.send(message).await?;
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    async fn send(&self, to: &Conversation, text: &str) -> Result<String> {
+        let mut body = json!({ "content": text, "allowed_mentions": { "parse": [] } });
+        if let Some(message) = &to.thread {
+            body["message_reference"] =
+                json!({ "message_id": message, "fail_if_not_exists": false });
+        }
+        let url = format!("{API_URL}/channels/{}/messages", to.channel);
+        let message = self.request(self.http.post(url).json(&body)).await?;
+        message["id"]
+            .as_str()
+            .map(str::to_string)
+            .context("Discord did not return a message id")
+    }
+
+    async fn edit(&self, to: &Conversation, message: &str, text: &str) -> Result<()> {
+        let url = format!("{API_URL}/channels/{}/messages/{message}", to.channel);
+        let body = json!({ "content": text });
+        self.request(self.http.patch(url).json(&body)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_mentions_and_direct_messages() {
+        let discord = Discord {
+            token: String::new(),
+            http: reqwest::Client::new(),
+            user_id: Mutex::new(Some("42".to_string())),
+        };
+        let mention = json!({
+            "id": "7", "channel_id": "c1", "guild_id": "g1",
+            "author": { "id": "u1" },
+            "mentions": [{ "id": "42" }],
+            "content": "<@42> what's up?",
+        });
+        let incoming = discord.incoming(&mention).unwrap();
+        assert_eq!(incoming.text, "what's up?");
+        assert_eq!(incoming.session, "discord:c1:u1");
+        assert_eq!(incoming.reply_to.thread.as_deref(), Some("7"));
+
+        let chatter = json!({
+            "id": "8", "channel_id": "c1", "guild_id": "g1",
+            "author": { "id": "u1" }, "mentions": [], "content": "hello all",
+        });
+        assert!(discord.incoming(&chatter).is_none());
+        let direct = json!({
+            "id": "9", "channel_id": "d1", "author": { "id": "u1" }, "content": "hi",
+        });
+        assert!(discord.incoming(&direct).is_some());
+        let bot = json!({
+            "id": "10", "channel_id": "d1", "author": { "id": "b", "bot": true }, "content": "hi",
+        });
+        assert!(discord.incoming(&bot).is_none());
+    }
+}
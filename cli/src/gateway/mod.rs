@@ -0,0 +1,281 @@
+mod discord;
+mod slack;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{Args, ValueEnum};
+use dinoe_core::agent::{AgentEvent, AgentLoop, TurnOutcome};
+use dinoe_core::config::Config;
+use tokio::sync::{Mutex, mpsc};
+
+use crate::approval::Prompts;
+
+/// Shortest time between two edits of an answer that is streaming in.
+const EDIT_INTERVAL: Duration = Duration::from_millis(1500);
+/// Longest wait before reconnecting after the connection drops.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Posted right away and edited as the answer comes in.
+const PLACEHOLDER: &str = "_Thinking…_";
+
+/// A chat platform the agent answers on.
+#[async_trait]
+pub trait Channel: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Longest message the platform takes, in characters.
+    fn max_chars(&self) -> usize;
+
+    /// Connects and forwards messages meant for the agent until the connection ends.
+    async fn listen(&self, messages: mpsc::Sender<Incoming>) -> Result<()>;
+
+    /// Posts `text` and returns the id of the new message, for `edit`.
+    async fn send(&self, to: &Conversation, text: &str) -> Result<String>;
+
+    async fn edit(&self, to: &Conversation, message: &str, text: &str) -> Result<()>;
+}
+
+/// A message for the agent.
+#[derive(Debug)]
+pub struct Incoming {
+    /// Messages with the same key continue the same agent session.
+    pub session: String,
+    pub user: String,
+    pub text: String,
+    pub reply_to: Conversation,
+}
+
+/// Where answers go.
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub channel: String,
+    /// The thread or message to reply to, on platforms that have them.
+    pub thread: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Platform {
+    Discord,
+    Slack,
+}
+
+#[derive(Args)]
+pub struct GatewayArgs {
+    platform: Platform,
+    /// Use an agent profile from `[agents.<NAME>]` in the config
+    #[arg(long, value_name = "NAME")]
+    pub(crate) agent: Option<String>,
+}
+
+pub async fn handle_command(
+    args: GatewayArgs,
+    config: Config,
+    reloads: mpsc::UnboundedReceiver<Result<Config>>,
+) -> Result<()> {
+    let config = match &args.agent {
+        Some(name) => config.with_agent(name)?,
+        None => config,
+    };
+    let (channel, allowed_users): (Arc<dyn Channel>, _) = match args.platform {
+        Platform::Discord => (
+            Arc::new(discord::Discord::from_config(&config.gateways.discord)?),
+            config.gateways.discord.allowed_users.clone(),
+        ),
+        Platform::Slack => (
+            Arc::new(slack::Slack::from_config(&config.gateways.slack)?),
+            config.gateways.slack.allowed_users.clone(),
+        ),
+    };
+    if allowed_users.is_empty() {
+        eprintln!("⚠ No allowed_users are set: everyone who can message the bot can use the agent");
+    }
+    let agent = crate::build_agent(&config, Prompts::none())?;
+    run(channel, agent, config, allowed_users, reloads).await
+}
+
+/// Answers messages from `channel` until Ctrl+C. Each conversation is its own session,
+/// and its turns run one at a time. A reloaded config applies from each session's
+/// next turn; the connection keeps the settings it started with.
+async fn run(
+    channel: Arc<dyn Channel>,
+    mut agent: AgentLoop,
+    mut config: Config,
+    allowed_users: Vec<String>,
+    mut reloads: mpsc::UnboundedReceiver<Result<Config>>,
+) -> Result<()> {
+    let (messages_tx, mut messages) = mpsc::channel(64);
+    let listener = tokio::spawn(listen(channel.clone(), messages_tx));
+    eprintln!("dinoe is listening on {}", channel.name());
+
+    let mut sessions: HashMap<String, Arc<Mutex<AgentLoop>>> = HashMap::new();
+    loop {
+        let message = tokio::select! {
+            Some(message) = messages.recv() => message,
+            Some(reloaded) = reloads.recv() => {
+                match reloaded.and_then(|reloaded| {
+                    Ok((crate::build_agent(&reloaded, Prompts::none())?, reloaded))
+                }) {
+                    Ok((reloaded_agent, reloaded)) => {
+                        (agent, config) = (reloaded_agent, reloaded);
+                        for session in sessions.values() {
+                            let (session, agent) = (session.clone(), agent.clone());
+                            // After the turn running in the session, if any.
+                            tokio::spawn(async move {
+                                let mut current = session.lock().await;
+                                *current = crate::reload::continue_session(agent, &current);
+                            });
+                        }
+                        eprintln!("Config and skills reloaded");
+                    }
+                    Err(e) => eprintln!("⚠ Keeping the previous config: {e:#}"),
+                }
+                continue;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        };
+        if !allowed_users.is_empty() && !allowed_users.contains(&message.user) {
+            continue;
+        }
+        let session = match sessions.get(&message.session) {
+            Some(session) => session.clone(),
+            None => {
+                let agent = crate::with_session(agent.clone(), &config, None)?;
+                let session = Arc::new(Mutex::new(agent));
+                sessions.insert(message.session.clone(), session.clone());
+                session
+            }
+        };
+        let (channel, max_history) = (channel.clone(), config.max_history);
+        tokio::spawn(async move {
+            let agent = session.lock().await;
+            if let Err(e) = answer(channel.as_ref(), &agent, message, max_history).await {
+                eprintln!("⚠ Could not answer on {}: {:#}", channel.name(), e);
+            }
+        });
+    }
+    listener.abort();
+    Ok(())
+}
+
+/// Keeps the connection up, reconnecting with backoff when it drops.
+async fn listen(channel: Arc<dyn Channel>, messages: mpsc::Sender<Incoming>) {
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let connected = Instant::now();
+        match channel.listen(messages.clone()).await {
+            Ok(()) => eprintln!("{} closed the connection; reconnecting", channel.name()),
+            Err(e) => eprintln!("⚠ {} connection failed: {:#}", channel.name(), e),
+        }
+        if connected.elapsed() > MAX_BACKOFF {
+            backoff = Duration::from_secs(1);
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Runs a turn for `message`, editing a placeholder as the answer streams in.
+async fn answer(
+    channel: &dyn Channel,
+    agent: &AgentLoop,
+    message: Incoming,
+    max_history: usize,
+) -> Result<()> {
+    let to = &message.reply_to;
+    let history = crate::session_history(agent, max_history)?;
+    let posted = channel.send(to, PLACEHOLDER).await?;
+
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    let mut turn = tokio::spawn({
+        let agent = agent.clone();
+        async move {
+            agent
+                .process_with_events(&message.text, history, events_tx)
+                .await
+        }
+    });
+    let mut draft = String::new();
+    let mut edited = Instant::now();
+    let outcome = loop {
+        let event = tokio::select! {
+            outcome = &mut turn => break outcome?,
+            Some(event) = events.recv() => event,
+        };
+        let preview = match event {
+            AgentEvent::Token { text } => {
+                draft.push_str(&text);
+                draft.clone()
+            }
+            // Text before a tool call is not part of the answer.
+            AgentEvent::ToolStarted { name } => {
+                draft.clear();
+                format!("_Running {name}…_")
+            }
+            _ => continue,
+        };
+        if edited.elapsed() >= EDIT_INTERVAL {
+            let preview = split_message(&preview, channel.max_chars()).remove(0);
+            channel.edit(to, &posted, &preview).await?;
+            edited = Instant::now();
+        }
+    };
+
+    let text = match outcome {
+        TurnOutcome::Completed { text, .. } => text,
+        TurnOutcome::MaxIterations { partial } => format!(
+            "{}\n\n_Stopped at max_iterations before a final answer._",
+            partial.unwrap_or_default()
+        ),
+        TurnOutcome::Cancelled => "_Cancelled._".to_string(),
+        TurnOutcome::Error(e) => format!("Error: {e}"),
+    };
+    let mut parts = split_message(&text, channel.max_chars()).into_iter();
+    channel
+        .edit(to, &posted, &parts.next().unwrap_or_default())
+        .await?;
+    for part in parts {
+        channel.send(to, &part).await?;
+    }
+    Ok(())
+}
+
+/// Cuts `text` into messages of at most `max_chars`, at line breaks where it can.
+fn split_message(text: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = text.trim();
+    while rest.chars().count() > max_chars {
+        let end = rest
+            .char_indices()
+            .nth(max_chars)
+            .map_or(rest.len(), |(i, _)| i);
+        let cut = match rest[..end].rfind('\n') {
+            Some(newline) if newline > end / 2 => newline,
+            _ => end,
+        };
+        parts.push(rest[..cut].to_string());
+        rest = rest[cut..].trim_start();
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(rest.to_string());
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_long_messages_at_line_breaks() {
+        assert_eq!(split_message("short", 10), ["short"]);
+        assert_eq!(split_message("", 10), [""]);
+        assert_eq!(
+            split_message("first line\nsecond line", 15),
+            ["first line", "second line"]
+        );
+        assert_eq!(split_message("ééééé", 2), ["éé", "éé", "é"]);
+    }
+}
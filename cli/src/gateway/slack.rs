@@ -0,0 +1,161 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use dinoe_core::config::SlackConfig;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::{Channel, Conversation, Incoming};
+
+const API_URL: &str = "https://slack.com/api";
+/// Slack takes longer messages but recommends staying under this.
+const MAX_CHARS: usize = 4000;
+
+/// Answers direct messages and mentions over Socket Mode, so no public URL is needed.
+/// Mentions are answered in a thread, and each thread is its own session.
+pub struct Slack {
+    app_token: String,
+    bot_token: String,
+    http: reqwest::Client,
+}
+
+impl Slack {
+    pub fn from_config(config: &SlackConfig) -> Result<Self> {
+        let app_token = config
+            .app_token
+            .clone()
+            .or_else(|| std::env::var("SLACK_APP_TOKEN").ok())
+            .context("Set gateways.slack.app_token in the config or SLACK_APP_TOKEN")?;
+        let bot_token = config
+            .bot_token
+            .clone()
+            .or_else(|| std::env::var("SLACK_BOT_TOKEN").ok())
+            .context("Set gateways.slack.bot_token in the config or SLACK_BOT_TOKEN")?;
+        Ok(Self {
+            app_token,
+            bot_token,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    async fn call(&self, method: &str, token: &str, body: Value) -> Result<Value> {
+        let response: Value = self
+            .http
+            .post(format!("{API_URL}/{method}"))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if response["ok"] != true {
+            bail!("Slack {method} failed: {}", response["error"]);
+        }
+        Ok(response)
+    }
+}
+
+/// The event as a message for the agent, if it is a mention or a direct message.
+fn incoming(event: &Value) -> Option<Incoming> {
+    if !event["bot_id"].is_null() || !event["subtype"].is_null() {
+        return None;
+    }
+    let direct = event["type"] == "message" && event["channel_type"] == "im";
+    if event["type"] != "app_mention" && !direct {
+        return None;
+    }
+    let mut text = event["text"].as_str()?.trim();
+    while let Some(rest) = text.strip_prefix("<@") {
+        text = rest
+            .split_once('>')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+    if text.is_empty() {
+        return None;
+    }
+    let channel = event["channel"].as_str()?.to_string();
+    let user = event["user"].as_str()?.to_string();
+    // Direct messages are one conversation unless the user starts a thread.
+    let thread = match event["thread_ts"].as_str() {
+        Some(thread) => Some(thread),
+        None if direct => None,
+        None => event["ts"].as_str(),
+    }
+    .map(str::to_string);
+    Some(Incoming {
+        session: format!(
+            "slack:{channel}:{}",
+            thread.as_deref().unwrap_or(user.as_str())
+        ),
+        user,
+        text: text.to_string(),
+        reply_to: Conversation { channel, thread },
+    })
+}
+
+#[async_trait]
+impl Channel for Slack {
+    fn name(&self) -> &'static str {
+        "Slack"
+    }
+
+    fn max_chars(&self) -> usize {
+        MAX_CHARS
+    }
+
+    async fn listen(&self, messages: mpsc::Sender<Incoming>) -> Result<()> {
+        let connection = self
+            .call("apps.connections.open", &self.app_token, json!({}))
+            .await?;
+        let url = connection["url"]
+            .as_str()
+            .context("Slack did not return a Socket Mode URL")?;
+        let (socket, _) = tokio_tungstenite::connect_async(url).await?;
+        let (mut write, mut read) = socket.split();
+        while let Some(frame) = read.next().await {
+            let envelope: Value = match frame? {
+                Message::Text(text) => serde_json::from_str(&text)?,
+                Message::Ping(data) => {
+                    write.send(Message::Pong(data)).await?;
+                    continue;
+                }
+                Message::Close(_) => return Ok(()),
+                _ => continue,
+            };
+            if let Some(id) = envelope["envelope_id"].as_str() {
+                let ack = json!({ "envelope_id": id });
+                write.send(Message::text(ack.to_string())).await?;
+            }
+            match envelope["type"].as_str() {
+                Some("disconnect") => return Ok(()),
+                Some("events_api") => {
+                    if let Some(message) = incoming(&envelope["payload"]["event"]) {
+                        messages.send(message).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(&self, to: &Conversation, text: &str) -> Result<String> {
+        let mut body = json!({ "channel": to.channel, "text": text });
+        if let Some(thread) = &to.thread {
+            body["thread_ts"] = json!(thread);
+        }
+        let posted = self.call("chat.postMessage", &self.bot_token, body).await?;
+        posted["ts"]
+            .as_str()
+            .map(str::to_string)
+            .context("Slack did not return a message timestamp")
+    }
+
+    async fn edit(&self, to: &Conversation, message: &str, text: &str) -> Result<()> {
+        let body = json!({ "channel": to.channel, "ts": message, "text": text });
+        self.call("chat.update", &self.bot_token, body).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{Result, bail};
+use console::style;
+use dinoe_core::config::Config;
+use dinoe_core::knowledge::{self, Document};
+use std::path::Path;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct IngestArgs {
+    /// Files, directories or URLs to add (re-ingesting a source replaces it)
+    targets: Vec<String>,
+    /// List ingested sources
+    #[arg(long)]
+    list: bool,
+    /// Remove an ingested source
+    #[arg(long, value_name = "SOURCE")]
+    remove: Option<String>,
+}
+
+pub async fn handle_command(args: IngestArgs, config: &Config) -> Result<()> {
+    let index = crate::knowledge_index(config);
+
+    if args.list {
+        let sources = index.sources().await?;
+        if sources.is_empty() {
+            println!("{} Nothing ingested yet", style("!").yellow());
+        }
+        for source in sources {
+            println!(
+                "{}  {} chunks, {}",
+                style(&source.source).bold(),
+                source.chunks,
+                source.ingested_at
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(source) = args.remove {
+        if index.remove(&source).await? {
+            println!("{} Removed {}", style("✓").green(), source);
+        } else {
+            println!("{} {} is not ingested", style("!").yellow(), source);
+        }
+        return Ok(());
+    }
+
+    if args.targets.is_empty() {
+        bail!("Nothing to ingest. Pass files, directories or URLs, or use --list");
+    }
+
+    let redactor = crate::redactor(config)?;
+    for target in &args.targets {
+        let documents = if target.starts_with("http://") || target.starts_with("https://") {
+            vec![knowledge::load_url(target).await?]
+        } else {
+            knowledge::load_path(Path::new(target))?
+        };
+        if documents.is_empty() {
+            println!("{} No supported files in {}", style("!").yellow(), target);
+        }
+        for mut document in documents {
+            if let Some(redactor) = &redactor {
+                document.text = redactor.redact(&document.text).into_owned();
+            }
+            ingest(&index, &document).await;
+        }
+    }
+    Ok(())
+}
+
+async fn ingest(index: &knowledge::KnowledgeIndex, document: &Document) {
+    match index.add(document).await {
+        Ok(chunks) => println!(
+            "{} {} ({} chunks)",
+            style("✓").green(),
+            document.source,
+            chunks
+        ),
+        Err(e) => eprintln!("{} {}: {}", style("✗").red(), document.source, e),
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{ArgAction, Args};
+use dinoe_core::config::{self, TelemetryConfig};
+use dinoe_core::telemetry::{self, BoxedLayer, Telemetry};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::prelude::*;
+
+/// Daily log files kept in the logs directory before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+#[derive(Args)]
+pub struct LogArgs {
+    /// Log more: -v for info, -vv for debug, -vvv for trace
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+    /// Also log to PATH, or to a daily file in the logs directory without one
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<Option<PathBuf>>,
+}
+
+/// Flushes the log file and pending spans when dropped.
+pub struct Logging {
+    _file: Option<WorkerGuard>,
+    _telemetry: Option<Telemetry>,
+}
+
+pub fn logs_dir() -> PathBuf {
+    config::get_data_dir().join("logs")
+}
+
+/// Installs the tracing subscriber: warnings and errors from dinoe go to stderr unless
+/// `stderr` is off, more with `-v`, and spans to the collector when `telemetry` has one.
+/// `RUST_LOG` replaces the levels.
+pub fn init(args: &LogArgs, telemetry: Option<&TelemetryConfig>, stderr: bool) -> Result<Logging> {
+    let level = match args.verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    if stderr {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .without_time()
+            .with_filter(filter(level));
+        layers.push(layer.boxed());
+    }
+
+    let mut file = None;
+    if let Some(path) = &args.log_file {
+        let builder = RollingFileAppender::builder();
+        let (builder, dir) = match path {
+            Some(path) => {
+                let name = path.file_name().context("--log-file needs a file name")?;
+                let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+                (
+                    builder
+                        .rotation(Rotation::NEVER)
+                        .filename_prefix(name.to_string_lossy()),
+                    dir.map_or_else(|| PathBuf::from("."), PathBuf::from),
+                )
+            }
+            None => (
+                builder
+                    .rotation(Rotation::DAILY)
+                    .filename_prefix("dinoe")
+                    .filename_suffix("log")
+                    .max_log_files(MAX_LOG_FILES),
+                logs_dir(),
+            ),
+        };
+        // Old files are pruned before the appender creates the directory.
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let appender = builder
+            .build(&dir)
+            .with_context(|| format!("Failed to open a log file in {}", dir.display()))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        // A log file is for looking into problems afterwards, so it gets at least info.
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .with_filter(filter(level.max(LevelFilter::INFO)));
+        layers.push(layer.boxed());
+        file = Some(guard);
+    }
+
+    let telemetry = match telemetry {
+        Some(config) => telemetry::layer(config)?,
+        None => None,
+    };
+    let telemetry = telemetry.map(|(telemetry, layer)| {
+        layers.push(layer);
+        telemetry
+    });
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .context("Failed to install the tracing subscriber")?;
+    Ok(Logging {
+        _file: file,
+        _telemetry: telemetry,
+    })
+}
+
+/// dinoe's own messages at `level`, and only warnings from the libraries it uses.
+fn filter(level: LevelFilter) -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("warn,dinoe_core={level},dinoe={level}")))
+}
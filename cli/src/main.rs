@@ -1,16 +1,42 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use dinoe_core::{
-    agent, config,
-    providers,
-    tools::{ContentSearchTool, FileEditTool, FileReadTool, FileWriteTool, GitOperationsTool, GlobSearchTool, HttpRequestTool, MemoryReadTool, MemoryWriteTool, ShellTool, WebFetchTool},
-};
+use clap::{Args, Parser, Subcommand};
+use dinoe_core::{Dinoe, agent, config, providers};
+mod approval;
+mod audit;
+mod auth;
+mod daemon;
+mod doctor;
+mod eval;
+mod gateway;
+mod ingest;
+mod logging;
+mod markdown;
+mod memory;
 mod onboard;
+mod reload;
+mod render;
 mod repl;
+mod scheduler;
+mod search;
+mod serve;
+mod sessions;
+mod settings;
 mod skills;
+mod taskfile;
 mod templates;
+mod tui;
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Exit codes of `dinoe chat -m`, for scripts. 0 means the agent answered.
+const EXIT_FAILED: u8 = 1;
+const EXIT_INCOMPLETE: u8 = 3;
+const EXIT_CANCELLED: u8 = 130;
+/// Piped input is cut to this many characters.
+const MAX_PIPED_CHARS: usize = 200_000;
 
 #[derive(Parser)]
 #[command(name = "dinoe")]
@@ -18,169 +44,600 @@ use tokio::sync::mpsc;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Options that override the config file, for every command.
+#[derive(Args, Clone)]
+struct GlobalArgs {
+    /// Work in DIR instead of the configured workspace_dir
+    #[arg(long, global = true, env = "DINOE_WORKSPACE", value_name = "DIR")]
+    workspace: Option<PathBuf>,
+    /// Use MODEL instead of the configured model
+    #[arg(long, global = true, value_name = "MODEL")]
+    model: Option<String>,
+    /// Use PROVIDER instead of the configured provider
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    provider: Option<String>,
+    /// Sampling temperature instead of the configured one
+    #[arg(long, global = true, value_name = "TEMP")]
+    temperature: Option<f64>,
+    /// Add every request to the model and its answer to FILE, for --replay
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer from a recording made with --record instead of calling the model
+    #[arg(long, global = true, value_name = "FILE")]
+    replay: Option<PathBuf>,
+}
+
+impl GlobalArgs {
+    fn load_config(&self) -> Result<config::Config> {
+        let mut config = config::load_config()?;
+        if let Some(dir) = &self.workspace {
+            config.workspace_dir =
+                config::resolve_workspace_dir(dir, &std::env::current_dir()?);
+        }
+        if let Some(provider) = &self.provider
+            && config.provider.as_ref() != Some(provider)
+        {
+            // The configured endpoint and key belong to the other provider.
+            config.provider = Some(provider.clone());
+            config.base_url = None;
+            config.api_key = String::new();
+        }
+        // Flags win over agent profiles too.
+        if let Some(model) = &self.model {
+            config.model = model.clone();
+            config.agents.values_mut().for_each(|profile| profile.model = None);
+        }
+        if let Some(temperature) = self.temperature {
+            config.temperature = temperature;
+            config
+                .agents
+                .values_mut()
+                .for_each(|profile| profile.temperature = None);
+        }
+        config.recording = match (&self.record, &self.replay) {
+            (Some(path), _) => Some(providers::Recording::Record(path.clone())),
+            (_, Some(path)) => Some(providers::Recording::Replay(path.clone())),
+            _ => None,
+        };
+        Ok(config)
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    Onboard,
+    /// Set up the provider, model and workspace
+    Onboard(onboard::OnboardArgs),
     Chat {
         #[arg(short, long)]
         message: Option<String>,
+        /// Continue an earlier session
+        #[arg(long, value_name = "SESSION")]
+        resume: Option<String>,
+        /// Use an agent profile from `[agents.<NAME>]` in the config
+        #[arg(long, value_name = "NAME")]
+        agent: Option<String>,
+        /// Run the turn here even when `dinoe daemon` is running
+        #[arg(long)]
+        no_daemon: bool,
+    },
+    /// Chat in a full-screen terminal UI
+    Tui {
+        /// Continue an earlier session
+        #[arg(long, value_name = "SESSION")]
+        resume: Option<String>,
+        /// Use an agent profile from `[agents.<NAME>]` in the config
+        #[arg(long, value_name = "NAME")]
+        agent: Option<String>,
     },
     Skills {
         #[command(subcommand)]
         skill_command: skills::SkillsCommands,
     },
+    Scheduler {
+        #[command(subcommand)]
+        scheduler_command: scheduler::SchedulerCommands,
+    },
+    /// Run scheduled jobs, the HEARTBEAT.md heartbeat and a warm agent for `dinoe chat -m`
+    Daemon,
+    /// Review the tool calls the agent made
+    Audit(audit::AuditArgs),
+    Memory {
+        #[command(subcommand)]
+        memory_command: memory::MemoryCommands,
+    },
+    /// Add documents to the knowledge index the agent searches
+    Ingest(ingest::IngestArgs),
+    /// Search memories, session transcripts and workspace notes
+    Search(search::SearchArgs),
+    /// Browse, export and delete chat session transcripts
+    Sessions {
+        #[command(subcommand)]
+        sessions_command: sessions::SessionsCommands,
+    },
+    /// Serve the agent over HTTP for web frontends and remote clients
+    Serve(serve::ServeArgs),
+    /// Answer on Discord or Slack
+    Gateway(gateway::GatewayArgs),
+    /// Run the steps of a task file, for repeatable workflows in CI or cron
+    Run(taskfile::RunArgs),
+    /// Run a suite of prompts against one or more models and compare how they do
+    Eval(eval::EvalArgs),
+    /// Keep provider API keys in the system keychain
+    Auth {
+        #[command(subcommand)]
+        auth_command: auth::AuthCommands,
+    },
+    /// Show, change and check the settings in config.toml
+    Config {
+        #[command(subcommand)]
+        config_command: settings::ConfigCommands,
+    },
+    /// Check the config, the workspace and what installed skills need
+    Doctor,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
+    let global = cli.global;
 
     let command = cli.command.unwrap_or_else(|| {
         if !config::config_exists() {
-            Commands::Onboard
+            Commands::Onboard(onboard::OnboardArgs::default())
         } else {
-            Commands::Chat { message: None }
+            Commands::Chat {
+                message: None,
+                resume: None,
+                agent: None,
+                no_daemon: false,
+            }
         }
     });
+    // Spans are exported for the commands that run the agent. The TUI owns the
+    // terminal, so it only logs to --log-file, and doctor reports problems itself.
+    let telemetry = match &command {
+        Commands::Chat { .. }
+        | Commands::Tui { .. }
+        | Commands::Scheduler { .. }
+        | Commands::Daemon
+        | Commands::Serve(_)
+        | Commands::Gateway(_)
+        | Commands::Run(_)
+        | Commands::Eval(_) => Some(global.load_config()?.telemetry),
+        _ => None,
+    };
+    let stderr = !matches!(command, Commands::Tui { .. } | Commands::Doctor);
+    let _logging = logging::init(&cli.log, telemetry.as_ref(), stderr)?;
 
     match command {
-        Commands::Onboard => {
-            let onboard_config = onboard::run_onboard().map_err(|e| {
+        Commands::Onboard(
+            args @ onboard::OnboardArgs {
+                section: Some(section),
+                ..
+            },
+        ) => {
+            onboard::run_section(section, &args, &global)?;
+        }
+        Commands::Onboard(args) => {
+            let onboard_config = onboard::run_onboard(args, &global).map_err(|e| {
                 eprintln!("❌ Onboarding failed: {}", e);
                 anyhow::anyhow!("Onboarding failed: {}", e)
             })?;
             config::save_config(&onboard_config)?;
         }
         Commands::Skills { skill_command } => {
-            let config = config::load_config()?;
-            skills::handle_command(skill_command, &config.workspace_dir)?;
+            let config = global.load_config()?;
+            skills::handle_command(skill_command, &config)?;
         }
-        Commands::Chat { message } => {
-            let config = config::load_config()?;
-
-            let provider_box = providers::create_provider(&config)?;
-
-            if !config.workspace_dir.exists()
-                && let Err(e) = std::fs::create_dir_all(&config.workspace_dir)
+        Commands::Scheduler { scheduler_command } => {
+            let config = global.load_config()?;
+            scheduler::handle_command(scheduler_command, &config).await?;
+        }
+        Commands::Daemon => {
+            let config = global.load_config()?;
+            let reloads = reload::watch(global.clone(), None, &config);
+            daemon::run(config, reloads).await?;
+        }
+        Commands::Audit(args) => {
+            audit::handle_command(args)?;
+        }
+        Commands::Memory { memory_command } => {
+            let config = global.load_config()?;
+            memory::handle_command(memory_command, &config).await?;
+        }
+        Commands::Ingest(args) => {
+            let config = global.load_config()?;
+            ingest::handle_command(args, &config).await?;
+        }
+        Commands::Search(args) => {
+            let config = global.load_config()?;
+            search::handle_command(args, &config).await?;
+        }
+        Commands::Sessions { sessions_command } => {
+            sessions::handle_command(sessions_command)?;
+        }
+        Commands::Auth { auth_command } => {
+            auth::handle_command(auth_command)?;
+        }
+        Commands::Config { config_command } => {
+            settings::handle_command(config_command, &global)?;
+        }
+        Commands::Doctor => {
+            let config = global.load_config()?;
+            doctor::run(&config)?;
+        }
+        Commands::Serve(args) => {
+            let config = global.load_config()?;
+            let reloads = reload::watch(global.clone(), None, &config);
+            serve::handle_command(args, config, reloads).await?;
+        }
+        Commands::Gateway(args) => {
+            let config = global.load_config()?;
+            let reloads = reload::watch(global.clone(), args.agent.clone(), &config);
+            gateway::handle_command(args, config, reloads).await?;
+        }
+        Commands::Run(args) => {
+            let config = global.load_config()?;
+            return taskfile::handle_command(args, config).await;
+        }
+        Commands::Eval(args) => {
+            let config = global.load_config()?;
+            return eval::handle_command(args, config).await;
+        }
+        Commands::Chat {
+            message,
+            resume,
+            agent,
+            no_daemon,
+        } => {
+            let config = global.load_config()?;
+            let mut config = match &agent {
+                Some(name) => config.with_agent(name)?,
+                None => config,
+            };
+            let message = match piped_input()? {
+                Some(input) => Some(attach_input(message.as_deref(), &input)),
+                None => message,
+            };
+            let interactive = std::io::stdin().is_terminal();
+            // The daemon cannot ask for approval, so turns that may need it run here.
+            #[cfg(unix)]
+            if let Some(msg) = &message
+                && !no_daemon
+                && agent.is_none()
+                && config.recording.is_none()
+                && (!interactive || config.approval == agent::ApprovalMode::Never)
+                && let Some(code) = daemon::ipc::chat(&config, msg, resume.as_deref()).await?
             {
-                eprintln!(
-                    "❌ Error: Could not create workspace at {}: {}",
-                    config.workspace_dir.display(),
-                    e
-                );
-                eprintln!("Please check your permissions and try again.");
-                return Err(e.into());
-            }
-
-            if let Err(e) = onboard::ensure_bootstrap_files(&config.workspace_dir) {
-                eprintln!("❌ Error: Could not create bootstrap files: {}", e);
-                return Err(e);
+                return Ok(ExitCode::from(code));
             }
-
-            let memory = dinoe_core::memory::create_memory(&config.workspace_dir)?;
-            let skill_registry =
-                dinoe_core::skills::SkillRegistry::load_from_workspace(&config.workspace_dir)?;
-            let skills = skill_registry.list();
-
-            let tool_registry = Arc::new(agent::ToolRegistry::new());
-            let provider_arc: Arc<dyn dinoe_core::traits::Provider> = Arc::from(provider_box);
-
-            tool_registry.register(Box::new(FileReadTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(FileWriteTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(ShellTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(MemoryReadTool::new(memory.clone())));
-            tool_registry.register(Box::new(MemoryWriteTool::new(memory.clone())));
-            tool_registry.register(Box::new(WebFetchTool::new()));
-            tool_registry.register(Box::new(HttpRequestTool::new()));
-            tool_registry.register(Box::new(GlobSearchTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(ContentSearchTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(FileEditTool::new(&config.workspace_dir)));
-            tool_registry.register(Box::new(GitOperationsTool::new(&config.workspace_dir)));
-
-            let tool_specs = tool_registry.get_specs();
-
-            let context_builder = agent::ContextBuilder::new(&config.workspace_dir)
-                .with_memory(memory.clone())
-                .with_skills(skills)
-                .with_tool_specs(tool_specs);
-
-            let agent_loop =
-                agent::AgentLoop::new(provider_arc.clone(), context_builder, tool_registry)
-                    .with_max_iterations(config.max_iterations)
-                    .with_max_history(config.max_history)
-                    .with_model_name(config.model.clone())
-                    .with_temperature(config.temperature)
-                    .with_parallel_tools(config.parallel_tools);
-
-            let agent_loop = Arc::new(agent_loop);
+            let prompts = if interactive {
+                approval::Prompts::console()
+            } else {
+                approval::Prompts::none()
+            };
+            let agent_loop = build_agent(&config, prompts.clone())?;
+            let mut agent_loop = with_session(agent_loop, &config, resume.as_deref())?;
 
             if let Some(msg) = message {
-                println!();
-                let printer = agent::StatusPrinter::new();
-                let (status_tx, mut status_rx) = mpsc::channel::<agent::StatusUpdate>(64);
-                let agent = agent_loop.clone();
-                let msg = msg.clone();
-                let handle = tokio::spawn(async move {
-                    agent.process_with_status(&msg, Some(status_tx)).await
-                });
-
-                while let Some(status) = status_rx.recv().await {
-                    printer.print(&status);
-                }
-
-                let result = handle.await??;
-                let width = crossterm::terminal::size()
-                    .map(|(w, _)| w as usize)
-                    .unwrap_or(80);
-                let sep_width = width.min(80);
-                eprintln!("\x1b[90m{}\x1b[0m", "\u{2500}".repeat(sep_width));
-                repl::print_markdown(&result);
+                eprintln!();
+                let code = match run_turn(&agent_loop, &msg, config.max_history).await? {
+                    agent::TurnOutcome::Completed { .. } => 0,
+                    agent::TurnOutcome::MaxIterations { .. } => EXIT_INCOMPLETE,
+                    agent::TurnOutcome::Cancelled => {
+                        eprintln!("\x1b[90mCancelled\x1b[0m");
+                        EXIT_CANCELLED
+                    }
+                    agent::TurnOutcome::Error(e) => {
+                        eprintln!("❌ Error: {}", e);
+                        EXIT_FAILED
+                    }
+                };
+                finish_session(&agent_loop, &config).await;
+                return Ok(ExitCode::from(code));
             } else {
                 let mut handle = repl::start();
+                let mut reloads = reload::watch(global.clone(), agent.clone(), &config);
+                // Applied before the next command, so the notice does not land in the
+                // line being typed.
+                let mut pending = None;
 
                 loop {
-                    match handle.recv().await {
+                    let command = tokio::select! {
+                        command = handle.recv() => command,
+                        Some(reloaded) = reloads.recv() => {
+                            pending = Some(reloaded);
+                            continue;
+                        }
+                    };
+                    if let Some(reloaded) = pending.take() {
+                        let notice = "Config and skills reloaded";
+                        reload_chat(&mut agent_loop, &mut config, reloaded, &prompts, notice);
+                    }
+                    match command {
                         Some(repl::ReplCommand::Input(input)) => {
                             println!();
-                            let printer = agent::StatusPrinter::new();
-                            let (status_tx, mut status_rx) = mpsc::channel::<agent::StatusUpdate>(64);
-                            let agent = agent_loop.clone();
-                            let input_clone = input.clone();
-                            let process_handle = tokio::spawn(async move {
-                                agent.process_with_status(&input_clone, Some(status_tx)).await
-                            });
-
-                            while let Some(status) = status_rx.recv().await {
-                                printer.print(&status);
-                            }
-
-                            match process_handle.await? {
-                                Ok(response) => {
-                                    let width = crossterm::terminal::size()
-                                        .map(|(w, _)| w as usize)
-                                        .unwrap_or(80);
-                                    let sep_width = width.min(80);
-                                    eprintln!("\x1b[90m{}\x1b[0m", "\u{2500}".repeat(sep_width));
-                                    repl::print_markdown(&response);
+                            match run_turn(&agent_loop, &input, config.max_history).await {
+                                Ok(agent::TurnOutcome::Cancelled) => {
+                                    eprintln!("\x1b[90mCancelled\x1b[0m");
                                 }
-                                Err(e) => {
+                                Ok(agent::TurnOutcome::Error(e)) | Err(e) => {
                                     eprintln!("❌ Error: {}", e);
                                 }
+                                Ok(_) => {}
                             }
                             println!();
                             handle.signal_done().await;
                         }
+                        Some(repl::ReplCommand::Rewind(turns)) => {
+                            match rewind(&mut agent_loop, turns) {
+                                Ok(message) => eprintln!("\x1b[90m{message}\x1b[0m"),
+                                Err(e) => eprintln!("❌ {}", e),
+                            }
+                            handle.signal_done().await;
+                        }
+                        Some(repl::ReplCommand::Rollback) => {
+                            match rollback(&agent_loop) {
+                                Ok(message) => eprintln!("\x1b[90m{message}\x1b[0m"),
+                                Err(e) => eprintln!("❌ {}", e),
+                            }
+                            handle.signal_done().await;
+                        }
+                        Some(repl::ReplCommand::ReloadSkills) => {
+                            let current = Ok(config.clone());
+                            let notice = "Skills reloaded";
+                            reload_chat(&mut agent_loop, &mut config, current, &prompts, notice);
+                            handle.signal_done().await;
+                        }
                         Some(repl::ReplCommand::Quit) | None => {
+                            finish_session(&agent_loop, &config).await;
                             println!("\n👋 Goodbye!");
+                            if let Some(transcript) = agent_loop.transcript() {
+                                println!(
+                                    "\x1b[90mResume with: dinoe chat --resume {}\x1b[0m",
+                                    transcript.session()
+                                );
+                            }
                             break;
                         }
                     }
                 }
             }
         }
+        Commands::Tui { resume, agent } => {
+            let config = global.load_config()?;
+            let config = match &agent {
+                Some(name) => config.with_agent(name)?,
+                None => config,
+            };
+            let (prompts, requests) = tui::prompts();
+            let agent_loop = build_agent(&config, prompts.clone())?;
+            let agent_loop = with_session(agent_loop, &config, resume.as_deref())?;
+            let reloads = reload::watch(global.clone(), agent.clone(), &config);
+            tui::run(agent_loop, &config, prompts, requests, reloads).await?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Builds the chat's agent again from `reloaded`, keeping the conversation, or keeps
+/// the current agent and config when that fails.
+fn reload_chat(
+    agent_loop: &mut agent::AgentLoop,
+    config: &mut config::Config,
+    reloaded: Result<config::Config>,
+    prompts: &approval::Prompts,
+    notice: &str,
+) {
+    let rebuilt =
+        reloaded.and_then(|reloaded| Ok((build_agent(&reloaded, prompts.clone())?, reloaded)));
+    match rebuilt {
+        Ok((agent, reloaded)) => {
+            *agent_loop = reload::continue_session(agent, agent_loop);
+            *config = reloaded;
+            eprintln!("\x1b[90m{notice}\x1b[0m");
+        }
+        Err(e) => eprintln!("❌ Keeping the previous config: {:#}", e),
+    }
+}
+
+/// Records the chat in a new session, or continues `resume`, when transcripts are on.
+fn with_session(
+    agent: agent::AgentLoop,
+    config: &config::Config,
+    resume: Option<&str>,
+) -> Result<agent::AgentLoop> {
+    if !config.transcripts && resume.is_none() {
+        return Ok(agent);
+    }
+    let dir = dinoe_core::transcript::sessions_dir();
+    let transcript = match resume {
+        Some(session) => {
+            dinoe_core::transcript::load(&dir, session)?;
+            dinoe_core::transcript::Transcript::open(&dir, session)
+        }
+        None => dinoe_core::transcript::Transcript::new(&dir),
+    };
+    Ok(agent.with_transcript(Arc::new(transcript)))
+}
+
+/// Restores the workspace files to how they were before the last turn that changed them.
+fn rollback(agent: &agent::AgentLoop) -> Result<String> {
+    let Some(checkpoints) = agent.checkpoints() else {
+        anyhow::bail!("Checkpoints are off; set `checkpoints = true` in the config");
+    };
+    let Some(report) = checkpoints.rollback()? else {
+        return Ok("No workspace changes to roll back".to_string());
+    };
+    let mut message = format!(
+        "Restored the workspace to {}: {} file(s) restored, {} removed",
+        report.created.format("%H:%M:%S"),
+        report.restored.len(),
+        report.removed.len()
+    );
+    for file in report.restored.iter().chain(&report.removed).take(10) {
+        message.push_str(&format!("\n  {file}"));
+    }
+    Ok(message)
+}
+
+/// Titles and summarizes the session when it ends, if enabled.
+async fn finish_session(agent: &agent::AgentLoop, config: &config::Config) {
+    if !config.summarize_sessions {
+        return;
+    }
+    if let Err(e) = agent.finish_session().await {
+        eprintln!("⚠ Could not summarize the session: {}", e);
+    }
+}
+
+/// Runs one chat turn, printing its progress and the answer, or the partial text when it
+/// ran out of iterations. Ctrl+C cancels the turn instead of exiting.
+async fn run_turn(
+    agent: &agent::AgentLoop,
+    message: &str,
+    max_history: usize,
+) -> Result<agent::TurnOutcome> {
+    let history = session_history(agent, max_history)?;
+    let cancel = CancellationToken::new();
+    let ctrl_c = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+        })
+    };
+    let printer = Arc::new(render::EventPrinter::new());
+    let outcome = agent
+        .process_cancellable(message, history, printer.sink(), cancel)
+        .await;
+    ctrl_c.abort();
+
+    // A streamed answer has been printed already.
+    if !printer.streamed()
+        && let Some(text) = outcome.text()
+    {
+        render::print_separator();
+        markdown::print_markdown(text);
+    }
+    if let agent::TurnOutcome::MaxIterations { .. } = outcome {
+        eprintln!("\x1b[33m⚠ Stopped at max_iterations before a final answer\x1b[0m");
+    }
+    Ok(outcome)
+}
+
+/// What was piped into dinoe, when stdin is not a terminal and not empty.
+fn piped_input() -> Result<Option<String>> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Ok(None);
+    }
+    let mut bytes = Vec::new();
+    stdin.lock().read_to_end(&mut bytes)?;
+    let input = String::from_utf8_lossy(&bytes);
+    Ok((!input.trim().is_empty()).then(|| input.trim_end().to_string()))
+}
+
+/// The message with piped input attached. The end of long input is kept, since that
+/// is where logs and command output usually matter.
+fn attach_input(message: Option<&str>, input: &str) -> String {
+    let count = input.chars().count();
+    let input = if count > MAX_PIPED_CHARS {
+        let tail: String = input.chars().skip(count - MAX_PIPED_CHARS).collect();
+        format!("[first {} characters cut]\n{tail}", count - MAX_PIPED_CHARS)
+    } else {
+        input.to_string()
+    };
+    match message {
+        Some(message) => format!("{message}\n\n<stdin>\n{input}\n</stdin>"),
+        None => input,
+    }
+}
+
+/// Continues the chat on a branch of the session without its last `turns` turns.
+fn rewind(agent_loop: &mut agent::AgentLoop, turns: usize) -> Result<String> {
+    let Some(transcript) = agent_loop.transcript() else {
+        anyhow::bail!("Rewinding needs session transcripts; set `transcripts = true`");
+    };
+    let previous = transcript.session().to_string();
+    let branch = transcript.branch(turns)?;
+    let message = format!(
+        "Rewound {turns} turn(s); now on session {} (the previous branch stays in {previous})",
+        branch.session()
+    );
+    agent_loop.set_transcript(Arc::new(branch));
+    Ok(message)
+}
+
+/// Earlier messages of the current session, when transcripts are on.
+fn session_history(
+    agent: &agent::AgentLoop,
+    max_history: usize,
+) -> Result<Vec<dinoe_core::traits::ChatMessage>> {
+    match agent.transcript() {
+        Some(transcript) => transcript.history(max_history),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn redactor(config: &config::Config) -> Result<Option<Arc<dinoe_core::redact::Redactor>>> {
+    if !config.redaction.enabled {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(dinoe_core::redact::Redactor::from_config(config)?)))
+}
+
+fn open_memory(
+    config: &config::Config,
+    redactor: Option<&Arc<dinoe_core::redact::Redactor>>,
+) -> Result<Arc<dyn dinoe_core::traits::Memory>> {
+    let memory = dinoe_core::memory::create_memory_from_config(config)?;
+    Ok(match redactor {
+        Some(redactor) => Arc::new(dinoe_core::memory::RedactingMemory::new(memory, redactor.clone())),
+        None => memory,
+    })
+}
+
+fn knowledge_index(config: &config::Config) -> dinoe_core::knowledge::KnowledgeIndex {
+    let index = dinoe_core::knowledge::KnowledgeIndex::new(&config.workspace_dir);
+    match dinoe_core::memory::create_embedder(config) {
+        Some(embedder) => index.with_embedder(embedder),
+        None => index,
+    }
+}
+
+fn build_agent(config: &config::Config, prompts: approval::Prompts) -> Result<agent::AgentLoop> {
+    if !config.workspace_dir.exists()
+        && let Err(e) = std::fs::create_dir_all(&config.workspace_dir)
+    {
+        eprintln!(
+            "❌ Error: Could not create workspace at {}: {}",
+            config.workspace_dir.display(),
+            e
+        );
+        eprintln!("Please check your permissions and try again.");
+        return Err(e.into());
+    }
+
+    if let Err(e) = onboard::ensure_bootstrap_files(&config.workspace_dir) {
+        eprintln!("❌ Error: Could not create bootstrap files: {}", e);
+        return Err(e);
     }
 
-    Ok(())
+    let builder = Dinoe::builder()
+        .with_config(config.clone())
+        .with_approver(prompts.approver);
+    let builder = match prompts.user_input {
+        Some(handler) => builder.with_user_input(handler),
+        None => builder,
+    };
+    Ok(builder.build()?.into_agent())
 }
@@ -0,0 +1,305 @@
+use std::io::IsTerminal;
+use termimad::MadSkin;
+use termimad::crossterm::style::Color;
+
+const RESET: &str = "\x1b[0m";
+const KEYWORD: &str = "\x1b[35m";
+const STRING: &str = "\x1b[32m";
+const NUMBER: &str = "\x1b[33m";
+const COMMENT: &str = "\x1b[90m";
+
+fn make_skin() -> MadSkin {
+    let mut skin = MadSkin::default();
+    skin.set_headers_fg(Color::Yellow);
+    skin.bold.set_fg(Color::White);
+    skin.italic.set_fg(Color::Magenta);
+    skin.inline_code.set_fg(Color::Green);
+    skin.code_block.set_fg(Color::Green);
+    skin.code_block.left_margin = 2;
+    skin
+}
+
+fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .map(|(w, _)| w as usize)
+        .unwrap_or(80)
+}
+
+/// Renders a whole markdown document to stdout, or prints it as is when stdout is not a
+/// terminal.
+pub fn print_markdown(content: &str) {
+    if !std::io::stdout().is_terminal() {
+        println!("{}", content.trim_end());
+        return;
+    }
+    let mut stream = MarkdownStream::new(terminal_width());
+    print!("{}", stream.push(content));
+    print!("{}", stream.finish());
+}
+
+/// Renders markdown as it arrives. Text is held back until its block is complete, so
+/// paragraphs wrap and tables align; code is printed line by line.
+pub struct MarkdownStream {
+    skin: MadSkin,
+    width: usize,
+    /// The current unfinished line.
+    partial: String,
+    /// Complete lines of the current paragraph, list item or table.
+    block: Vec<String>,
+    /// The language of the open code fence, if inside one.
+    code: Option<String>,
+    /// Passes text through unrendered.
+    plain: bool,
+}
+
+impl MarkdownStream {
+    pub fn new(width: usize) -> Self {
+        Self {
+            skin: make_skin(),
+            width,
+            partial: String::new(),
+            block: Vec::new(),
+            code: None,
+            plain: false,
+        }
+    }
+
+    /// Plain text when stdout is not a terminal, so piped output has no escape codes.
+    pub fn for_stdout(width: usize) -> Self {
+        Self {
+            plain: !std::io::stdout().is_terminal(),
+            ..Self::new(width)
+        }
+    }
+
+    /// Adds streamed text and returns whatever can be rendered so far.
+    pub fn push(&mut self, text: &str) -> String {
+        if self.plain {
+            return text.to_string();
+        }
+        self.partial.push_str(text);
+        let mut out = String::new();
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            self.line(line.trim_end_matches(['\n', '\r']), &mut out);
+        }
+        out
+    }
+
+    /// Renders everything still buffered.
+    pub fn finish(&mut self) -> String {
+        let mut out = String::new();
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.line(&line, &mut out);
+        }
+        self.flush(&mut out);
+        self.code = None;
+        out
+    }
+
+    fn line(&mut self, line: &str, out: &mut String) {
+        let trimmed = line.trim_start();
+        let fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+
+        if let Some(lang) = &self.code {
+            if fence {
+                self.code = None;
+            } else {
+                out.push_str("  ");
+                out.push_str(&highlight(line, lang));
+                out.push('\n');
+            }
+            return;
+        }
+
+        if fence {
+            self.flush(out);
+            let lang = trimmed[3..].trim().to_lowercase();
+            if !lang.is_empty() {
+                out.push_str(&format!("  {COMMENT}{lang}{RESET}\n"));
+            }
+            self.code = Some(lang);
+            return;
+        }
+
+        if trimmed.is_empty() {
+            self.flush(out);
+            out.push('\n');
+            return;
+        }
+
+        let is_table = trimmed.starts_with('|');
+        let was_table = self.block.last().is_some_and(|l| l.trim_start().starts_with('|'));
+        if is_table != was_table || starts_list_item(trimmed) {
+            self.flush(out);
+        }
+        self.block.push(line.to_string());
+        if trimmed.starts_with('#') {
+            self.flush(out);
+        }
+    }
+
+    fn flush(&mut self, out: &mut String) {
+        if self.block.is_empty() {
+            return;
+        }
+        let text = self.block.join("\n");
+        self.block.clear();
+        let rendered = termimad::FmtText::from(&self.skin, &text, Some(self.width));
+        out.push_str(&rendered.to_string());
+    }
+}
+
+fn starts_list_item(line: &str) -> bool {
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && line[digits..].starts_with(". ")
+}
+
+fn keywords(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "rust" | "rs" => &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+            "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+            "type", "unsafe", "use", "where", "while",
+        ],
+        "python" | "py" => &[
+            "and", "as", "async", "await", "break", "class", "continue", "def", "elif", "else",
+            "except", "False", "finally", "for", "from", "if", "import", "in", "is", "lambda",
+            "None", "not", "or", "pass", "raise", "return", "True", "try", "while", "with",
+            "yield",
+        ],
+        "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" => &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+            "else", "export", "extends", "false", "for", "from", "function", "if", "import",
+            "in", "interface", "let", "new", "null", "of", "return", "switch", "this", "throw",
+            "true", "try", "type", "undefined", "var", "while",
+        ],
+        "go" => &[
+            "break", "case", "chan", "const", "continue", "default", "defer", "else", "false",
+            "for", "func", "go", "if", "import", "interface", "map", "nil", "package", "range",
+            "return", "select", "struct", "switch", "true", "type", "var",
+        ],
+        "sh" | "bash" | "shell" | "zsh" => &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+            "in", "local", "return", "then", "while",
+        ],
+        "sql" => &[
+            "AND", "BY", "CREATE", "DELETE", "FROM", "GROUP", "INSERT", "INTO", "JOIN", "LIMIT",
+            "NOT", "NULL", "ON", "OR", "ORDER", "SELECT", "SET", "TABLE", "UPDATE", "VALUES",
+            "WHERE",
+        ],
+        "json" | "toml" | "yaml" | "yml" => &["true", "false", "null"],
+        _ => &[],
+    }
+}
+
+fn line_comment(lang: &str) -> Option<&'static str> {
+    match lang {
+        "python" | "py" | "sh" | "bash" | "shell" | "zsh" | "toml" | "yaml" | "yml" => Some("#"),
+        "sql" | "lua" => Some("--"),
+        "" | "text" | "json" | "md" | "markdown" => None,
+        _ => Some("//"),
+    }
+}
+
+/// Colors keywords, strings, numbers and line comments. Unknown languages are left as is.
+fn highlight(line: &str, lang: &str) -> String {
+    let keywords = keywords(lang);
+    let comment = line_comment(lang);
+    if keywords.is_empty() && comment.is_none() {
+        return line.to_string();
+    }
+    // In Rust a single quote is usually a lifetime, not a string.
+    let quotes: &[char] = if matches!(lang, "rust" | "rs") {
+        &['"']
+    } else {
+        &['"', '\'', '`']
+    };
+
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if let Some(marker) = comment
+            && rest.starts_with(marker)
+        {
+            out.push_str(&format!("{COMMENT}{rest}{RESET}"));
+            break;
+        }
+        let len = if quotes.contains(&c) {
+            let end = string_end(rest, c);
+            out.push_str(&format!("{STRING}{}{RESET}", &rest[..end]));
+            end
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            if keywords.contains(&word) {
+                out.push_str(&format!("{KEYWORD}{word}{RESET}"));
+            } else if c.is_ascii_digit() {
+                out.push_str(&format!("{NUMBER}{word}{RESET}"));
+            } else {
+                out.push_str(word);
+            }
+            end
+        } else {
+            out.push(c);
+            c.len_utf8()
+        };
+        rest = &rest[len..];
+    }
+    out
+}
+
+/// The byte length of the string literal at the start of `text`, up to the end of the
+/// line when it is not closed.
+fn string_end(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + c.len_utf8();
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_blocks_and_highlights_code() {
+        let mut stream = MarkdownStream::new(80);
+        assert_eq!(stream.push("Some **bold"), "");
+        let out = stream.push("** text\n| a | b |\n");
+        assert!(out.contains("bold") && !out.contains("**"));
+        assert_eq!(stream.push("|---|---|\n| 1 | 2 |\n"), "");
+
+        let out = stream.push("\n```rust\nlet x = \"hi\"; // note\n");
+        assert!(out.contains('1') && out.contains('2'));
+        assert!(out.contains(&format!("{KEYWORD}let{RESET}")));
+        assert!(out.contains(&format!("{STRING}\"hi\"{RESET}")));
+        assert!(out.contains(&format!("{COMMENT}// note{RESET}")));
+
+        assert_eq!(stream.push("```\nDone"), "");
+        assert!(stream.finish().contains("Done"));
+    }
+
+    #[test]
+    fn leaves_unknown_languages_alone() {
+        assert_eq!(highlight("if x then y", "text"), "if x then y");
+        assert_eq!(
+            highlight("fn a<'b>()", "rust"),
+            format!("{KEYWORD}fn{RESET} a<'b>()")
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use console::style;
+use dinoe_core::config::Config;
+use dinoe_core::memory::{self, ConsolidationReport, Consolidator};
+use dinoe_core::providers;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub async fn handle_command(command: MemoryCommands, config: &Config) -> Result<()> {
+    match command {
+        MemoryCommands::Consolidate { days, dry_run } => {
+            let days = days.unwrap_or(config.memory.consolidate_after_days);
+            if dry_run {
+                return preview(config, days).await;
+            }
+            let report = consolidate(config, days).await?;
+            if report.days.is_empty() {
+                println!("{} Nothing to consolidate", style("!").yellow());
+            } else {
+                print_report(&report);
+            }
+            Ok(())
+        }
+        MemoryCommands::Export { output } => {
+            let memory = crate::open_memory(config, crate::redactor(config)?.as_ref())?;
+            match output {
+                Some(path) => {
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                    let count = memory::export_jsonl(memory.as_ref(), &mut file).await?;
+                    println!(
+                        "{} Exported {} entries to {}",
+                        style("✓").green(),
+                        count,
+                        path.display()
+                    );
+                }
+                None => {
+                    memory::export_jsonl(memory.as_ref(), &mut std::io::stdout().lock()).await?;
+                }
+            }
+            Ok(())
+        }
+        MemoryCommands::Import { input } => {
+            let memory = crate::open_memory(config, crate::redactor(config)?.as_ref())?;
+            let summary = match input {
+                Some(path) => {
+                    let file = std::fs::File::open(&path)
+                        .map_err(|e| anyhow::anyhow!("Cannot open {}: {}", path.display(), e))?;
+                    memory::import_jsonl(memory.as_ref(), BufReader::new(file)).await?
+                }
+                None => memory::import_jsonl(memory.as_ref(), std::io::stdin().lock()).await?,
+            };
+            println!(
+                "{} Imported {} entries ({} already present)",
+                style("✓").green(),
+                summary.imported,
+                summary.skipped
+            );
+            Ok(())
+        }
+    }
+}
+
+fn consolidator(config: &Config, days: u32) -> Result<Consolidator> {
+    let memory = crate::open_memory(config, crate::redactor(config)?.as_ref())?;
+    let provider = Arc::from(providers::create_provider(config)?);
+    Ok(Consolidator::new(memory, provider, &config.model).with_older_than_days(days))
+}
+
+/// Summarizes daily entries older than `days` into core memories.
+pub async fn consolidate(config: &Config, days: u32) -> Result<ConsolidationReport> {
+    consolidator(config, days)?.run().await
+}
+
+pub fn print_report(report: &ConsolidationReport) {
+    println!(
+        "{} Consolidated {} daily entries from {} day(s) into {} core memories",
+        style("✓").green(),
+        report.entries,
+        report.days.len(),
+        report.facts
+    );
+}
+
+async fn preview(config: &Config, days: u32) -> Result<()> {
+    let pending = consolidator(config, days)?.pending().await?;
+    if pending.is_empty() {
+        println!("{} Nothing to consolidate", style("!").yellow());
+    }
+    for (date, entries) in pending {
+        println!("{} ({} entries)", style(date).bold(), entries.len());
+    }
+    Ok(())
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum MemoryCommands {
+    /// Summarize old daily entries into long-term core memories
+    Consolidate {
+        /// Only consolidate days at least this old (default: memory.consolidate_after_days)
+        #[arg(long)]
+        days: Option<u32>,
+        /// Show which days would be consolidated without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write all memories as JSONL
+    Export {
+        /// File to write (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Add memories from a JSONL export, skipping ones already present
+    Import {
+        /// File to read (default: stdin)
+        input: Option<PathBuf>,
+    },
+}
@@ -3,10 +3,13 @@ use console::style;
 use dialoguer::{Input, Select};
 use dinoe_core::config::Config;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::templates::{DEFAULT_SOUL, DEFAULT_TOOLS, DEFAULT_USER};
+use crate::templates::{
+    DEFAULT_HEARTBEAT, DEFAULT_SOUL, DEFAULT_TOOLS, DEFAULT_USER, WORKSPACE_TEMPLATES,
+    WorkspaceTemplate,
+};
 
 const BANNER: &str = r"
     -------------------------------------
@@ -47,6 +50,7 @@ fn create_bootstrap_files(workspace: &Path) -> Result<()> {
     ensure_file(&workspace.join("SOUL.md"), DEFAULT_SOUL)?;
     ensure_file(&workspace.join("TOOLS.md"), DEFAULT_TOOLS)?;
     ensure_file(&workspace.join("USER.md"), DEFAULT_USER)?;
+    ensure_file(&workspace.join("HEARTBEAT.md"), DEFAULT_HEARTBEAT)?;
 
     Ok(())
 }
@@ -60,13 +64,271 @@ pub fn ensure_bootstrap_files(workspace: &Path) -> Result<()> {
     create_bootstrap_files(workspace)
 }
 
+const TEMPLATE_FILES: [&str; 4] = ["SOUL.md", "TOOLS.md", "USER.md", "HEARTBEAT.md"];
+
+enum TemplateSource<'a> {
+    BuiltIn(&'static WorkspaceTemplate),
+    Git(&'a str),
+    Dir(&'a Path),
+}
+
+/// A built-in template by name, a directory, or a git repository to clone.
+fn template_source(template: &str) -> Result<TemplateSource<'_>> {
+    if let Some(built_in) = WORKSPACE_TEMPLATES.iter().find(|t| t.name == template) {
+        Ok(TemplateSource::BuiltIn(built_in))
+    } else if template.starts_with("https://") || template.starts_with("http://") {
+        Ok(TemplateSource::Git(template))
+    } else if Path::new(template).is_dir() {
+        Ok(TemplateSource::Dir(Path::new(template)))
+    } else {
+        let names: Vec<&str> = WORKSPACE_TEMPLATES.iter().map(|t| t.name).collect();
+        anyhow::bail!(
+            "Unknown template '{}'. Use one of {}, a directory or a git URL",
+            template,
+            names.join(", ")
+        );
+    }
+}
+
+/// The bootstrap files and skills a template adds to a workspace.
+struct Template {
+    files: Vec<(&'static str, String)>,
+    skills: Vec<TemplateSkill>,
+}
+
+enum TemplateSkill {
+    /// A name and its SKILL.md.
+    BuiltIn(&'static str, &'static str),
+    /// A skill directory of a custom template, copied as it is.
+    Dir(PathBuf),
+}
+
+impl TemplateSkill {
+    fn name(&self) -> String {
+        match self {
+            TemplateSkill::BuiltIn(name, _) => name.to_string(),
+            TemplateSkill::Dir(dir) => dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+}
+
+impl From<&WorkspaceTemplate> for Template {
+    fn from(template: &WorkspaceTemplate) -> Self {
+        Template {
+            files: template
+                .files()
+                .into_iter()
+                .map(|(name, content)| (name, content.to_string()))
+                .collect(),
+            skills: template
+                .skills
+                .iter()
+                .map(|(name, skill)| TemplateSkill::BuiltIn(name, skill))
+                .collect(),
+        }
+    }
+}
+
+/// A custom template: a directory with any of SOUL.md, TOOLS.md, USER.md and
+/// HEARTBEAT.md, and skills in `skills/`.
+fn load_template(dir: &Path) -> Result<Template> {
+    let mut files = Vec::new();
+    for name in TEMPLATE_FILES {
+        let path = dir.join(name);
+        if path.is_file() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            files.push((name, content));
+        }
+    }
+    let mut skill_dirs: Vec<PathBuf> = std::fs::read_dir(dinoe_core::skills::skills_dir(dir))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("SKILL.md").is_file())
+        .collect();
+    skill_dirs.sort();
+    for skill in &skill_dirs {
+        dinoe_core::skills::load_skill(skill)
+            .with_context(|| format!("Invalid skill in {}", skill.display()))?;
+    }
+    if files.is_empty() && skill_dirs.is_empty() {
+        anyhow::bail!(
+            "{} is not a workspace template: it has no {} or skills",
+            dir.display(),
+            TEMPLATE_FILES.join(", ")
+        );
+    }
+    Ok(Template {
+        files,
+        skills: skill_dirs.into_iter().map(TemplateSkill::Dir).collect(),
+    })
+}
+
+/// Whether `content` is a bootstrap file as dinoe writes it, so a template may replace it.
+fn is_unedited(name: &str, content: &str) -> bool {
+    (name == "HEARTBEAT.md" && content == DEFAULT_HEARTBEAT)
+        || WORKSPACE_TEMPLATES
+            .iter()
+            .flat_map(|t| t.files())
+            .any(|(file, text)| file == name && text == content)
+}
+
+/// Writes the files of `template` that are missing from `workspace` or unedited, and
+/// adds the skills it does not have yet. Edited files and installed skills are kept.
+fn write_template(template: &Template, workspace: &Path) -> Result<()> {
+    std::fs::create_dir_all(workspace)?;
+    for (name, content) in &template.files {
+        let path = workspace.join(name);
+        let current = std::fs::read_to_string(&path).ok();
+        if current
+            .as_deref()
+            .is_some_and(|c| c != content && !is_unedited(name, c))
+        {
+            println!(
+                "  {} Kept {}, which has been edited",
+                style("!").yellow(),
+                name
+            );
+            continue;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("  {} {}", style("✓").green(), name);
+    }
+
+    let skills_dir = dinoe_core::skills::skills_dir(workspace);
+    for skill in &template.skills {
+        let name = skill.name();
+        let dest = skills_dir.join(&name);
+        if dest.exists() {
+            println!(
+                "  {} Kept the installed skill {}",
+                style("!").yellow(),
+                name
+            );
+            continue;
+        }
+        match skill {
+            TemplateSkill::BuiltIn(_, content) => {
+                std::fs::create_dir_all(&dest)?;
+                std::fs::write(dest.join("SKILL.md"), content)?;
+            }
+            TemplateSkill::Dir(src) => crate::skills::copy_dir_recursive(src, &dest)?,
+        }
+        println!("  {} Skill {}", style("✓").green(), name);
+        if let TemplateSkill::Dir(_) = skill {
+            crate::skills::print_permissions(&dest);
+        }
+    }
+    Ok(())
+}
+
+/// Applies a template from `template_source` to `workspace`. A git template is cloned
+/// into the cache and removed afterwards.
+fn apply_template(template: &str, workspace: &Path) -> Result<()> {
+    match template_source(template)? {
+        TemplateSource::BuiltIn(built_in) => write_template(&built_in.into(), workspace),
+        TemplateSource::Dir(dir) => write_template(&load_template(dir)?, workspace),
+        TemplateSource::Git(url) => {
+            let staging = dinoe_core::config::get_cache_dir()
+                .join(format!("template-{}", uuid::Uuid::new_v4()));
+            let result = (|| {
+                let output = std::process::Command::new("git")
+                    .args(["clone", "--depth", "1", url])
+                    .arg(&staging)
+                    .output()
+                    .context("Failed to run git")?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    anyhow::bail!("Git clone failed: {}", stderr);
+                }
+                write_template(&load_template(&staging)?, workspace)
+            })();
+            let _ = std::fs::remove_dir_all(&staging);
+            result
+        }
+    }
+}
+
+/// The template to apply, if any. Without `--template` the wizard asks, and `--yes`
+/// applies none.
+fn choose_template(args: &OnboardArgs) -> Result<Option<String>> {
+    if let Some(template) = &args.template {
+        return Ok(Some(template.clone()));
+    }
+    if args.yes {
+        return Ok(None);
+    }
+    let labels: Vec<String> = WORKSPACE_TEMPLATES
+        .iter()
+        .map(|t| format!("{:<11} {}", t.name, t.description))
+        .collect();
+    let selection = Select::new()
+        .with_prompt("Select a workspace template")
+        .items(&labels)
+        .default(0)
+        .interact()
+        .context("Failed to select template")?;
+    Ok(Some(WORKSPACE_TEMPLATES[selection].name.to_string()))
+}
+
+const PROVIDERS: [(&str, &str); 4] = [
+    ("openai", "OpenAI"),
+    ("openrouter", "OpenRouter"),
+    ("ollama", "Ollama"),
+    ("zai", "Z.AI (GLM)"),
+];
+
+/// Answers given on the command line are not asked for.
+#[derive(clap::Args, Default)]
+pub struct OnboardArgs {
+    /// Read the API key from this environment variable instead of asking
+    #[arg(long, value_name = "VAR")]
+    api_key_env: Option<String>,
+    /// Ollama base URL, or `coding` or `general` for the Z.AI endpoint
+    #[arg(long, value_name = "ENDPOINT")]
+    endpoint: Option<String>,
+    /// Ask nothing; use defaults for anything not given
+    #[arg(short, long)]
+    yes: bool,
+    /// Only change this part of the existing config
+    #[arg(long, value_enum)]
+    pub section: Option<Section>,
+    /// Set up the workspace from a template: coder, researcher, ops, writer, a directory
+    /// or a git URL
+    #[arg(long, value_name = "TEMPLATE")]
+    template: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum Section {
+    /// The model, keeping the provider
+    Model,
+    /// The provider, with its key, endpoint and model
+    Provider,
+    /// The provider's endpoint
+    Endpoint,
+    /// The workspace files and skills, from a template
+    Workspace,
+}
+
+/// The variable the provider reads its key from when none is configured.
+fn key_env_var(provider: &str) -> &'static str {
+    match provider {
+        "openrouter" => "OPENROUTER_API_KEY",
+        "zai" => "ZAI_API_KEY",
+        _ => "OPENAI_API_KEY",
+    }
+}
+
 fn setup_provider() -> Result<String> {
-    let providers = [
-        ("openai", "OpenAI"),
-        ("openrouter", "OpenRouter"),
-        ("ollama", "Ollama"),
-        ("zai", "Z.AI (GLM)"),
-    ];
+    let providers = PROVIDERS;
 
     let provider_labels: Vec<&str> = providers.iter().map(|(_, label)| *label).collect();
 
@@ -114,10 +376,7 @@ struct ModelCache {
 }
 
 fn get_cache_path() -> std::path::PathBuf {
-    dirs::cache_dir()
-        .unwrap_or_else(std::env::temp_dir)
-        .join("dinoe")
-        .join("models_cache.json")
+    dinoe_core::config::get_cache_dir().join("models_cache.json")
 }
 
 fn load_cached_models(provider: &str) -> Option<Vec<String>> {
@@ -325,6 +584,20 @@ fn setup_model_with_ollama_url(provider: &str, ollama_url: Option<&str>) -> Resu
     }
 }
 
+/// Asks for the base URL of OpenAI-compatible providers, which the wizard leaves at
+/// the provider's default.
+fn setup_base_url(provider: &str, current: Option<&str>) -> Result<String> {
+    let default = current
+        .map(str::to_string)
+        .or_else(|| base_url(provider, ""))
+        .unwrap_or_default();
+    Input::new()
+        .with_prompt("Enter the API base URL")
+        .default(default)
+        .interact_text()
+        .context("Failed to read URL")
+}
+
 fn setup_endpoint(provider: &str) -> Result<String> {
     match provider {
         "ollama" => {
@@ -367,66 +640,194 @@ fn setup_endpoint(provider: &str) -> Result<String> {
     }
 }
 
-pub fn run_onboard() -> Result<Config> {
-    println!("{}", style(BANNER).cyan().bold());
-
-    println!("  {}", style("Welcome to Dinoe!").white().bold());
-    println!(
-        "  {}",
-        style("This wizard will configure your agent in under 30 seconds.").dim()
-    );
-    println!();
+fn choose_provider(args: &OnboardArgs, global: &crate::GlobalArgs) -> Result<String> {
+    match &global.provider {
+        Some(provider) => {
+            let provider = provider.to_lowercase();
+            if !PROVIDERS.iter().any(|(name, _)| *name == provider) {
+                let names: Vec<&str> = PROVIDERS.iter().map(|(name, _)| *name).collect();
+                anyhow::bail!("Unknown provider '{}'. Available: {}", provider, names.join(", "));
+            }
+            Ok(provider)
+        }
+        None if args.yes => Ok("openai".to_string()),
+        None => setup_provider(),
+    }
+}
 
-    print_step(1, 5, "Provider Selection");
-    let provider = setup_provider()?;
+/// The key to store; empty when the provider needs none or finds it elsewhere.
+fn choose_api_key(provider: &str, args: &OnboardArgs) -> Result<String> {
+    let runtime_var = key_env_var(provider);
+    match &args.api_key_env {
+        // The provider reads this variable itself, so the key need not be stored.
+        Some(var) if provider != "ollama" && var == runtime_var => {
+            std::env::var(var).with_context(|| format!("{} is not set", var))?;
+            println!("  {} Using {} from the environment", style("✓").green(), var);
+            Ok(String::new())
+        }
+        Some(var) => std::env::var(var).with_context(|| format!("{} is not set", var)),
+        None if provider == "ollama" => Ok(String::new()),
+        None if dinoe_core::keychain::get(provider).is_some() => {
+            println!(
+                "  {} Using the key in the system keychain ({} to change it)",
+                style("✓").green(),
+                style(format!("dinoe auth set {}", provider)).cyan()
+            );
+            Ok(String::new())
+        }
+        None if args.yes => {
+            if std::env::var(runtime_var).is_err() {
+                anyhow::bail!("No API key: pass --api-key-env or set {}", runtime_var);
+            }
+            Ok(String::new())
+        }
+        None => setup_api_key(provider),
+    }
+}
 
-    print_step(2, 5, "API Key Setup");
-    let api_key = setup_api_key(&provider)?;
+fn choose_endpoint(provider: &str, args: &OnboardArgs) -> Result<String> {
+    Ok(match (&args.endpoint, provider) {
+        (Some(endpoint), _) => endpoint.clone(),
+        (None, _) if !args.yes => setup_endpoint(provider)?,
+        (None, "ollama") => "http://localhost:11434".to_string(),
+        (None, "zai") => "coding".to_string(),
+        (None, _) => String::new(),
+    })
+}
 
-    print_step(3, 5, "Endpoint Selection");
-    let endpoint = setup_endpoint(&provider)?;
-    let ollama_url = if provider == "ollama" {
-        Some(if endpoint.is_empty() { "http://localhost:11434".to_string() } else { endpoint.clone() })
-    } else {
-        None
-    };
-    let base_url = if endpoint.is_empty() {
-        match provider.as_str() {
+fn base_url(provider: &str, endpoint: &str) -> Option<String> {
+    if endpoint.is_empty() {
+        match provider {
             "openai" => Some("https://api.openai.com/v1".to_string()),
             "openrouter" => Some("https://openrouter.ai/api/v1".to_string()),
             _ => None,
         }
     } else {
-        match provider.as_str() {
-            "ollama" => Some(endpoint.clone()),
-            "zai" => Some(match endpoint.as_str() {
+        match provider {
+            "zai" => Some(match endpoint {
                 "coding" => "https://api.z.ai/api/coding/paas/v4".to_string(),
                 "general" => "https://api.z.ai/api/paas/v4".to_string(),
                 _ => String::new(),
             }),
-            _ => Some(endpoint.clone()),
+            _ => Some(endpoint.to_string()),
+        }
+    }
+}
+
+fn choose_model(
+    provider: &str,
+    base_url: Option<&str>,
+    args: &OnboardArgs,
+    global: &crate::GlobalArgs,
+) -> Result<String> {
+    let ollama_url = if provider == "ollama" {
+        Some(base_url.unwrap_or("http://localhost:11434"))
+    } else {
+        None
+    };
+    match &global.model {
+        Some(model) => Ok(model.clone()),
+        None if args.yes => Ok(get_curated_models(provider).remove(0)),
+        None => setup_model_with_ollama_url(provider, ollama_url),
+    }
+}
+
+/// Keys go to the system keychain when there is one, and to config.toml otherwise.
+/// Returns what config.toml should hold.
+fn store_api_key(provider: &str, api_key: String) -> String {
+    if api_key.is_empty() {
+        return api_key;
+    }
+    match dinoe_core::keychain::set(provider, &api_key) {
+        Ok(()) => {
+            println!("  {} API key stored in the system keychain", style("✓").green());
+            String::new()
+        }
+        Err(e) => {
+            eprintln!(
+                "  {} Warning: Could not store the API key in the system keychain: {:#}",
+                style("!").yellow(),
+                e
+            );
+            eprintln!("    The key will be saved in plain text in config.toml instead.");
+            api_key
+        }
+    }
+}
+
+pub fn run_onboard(args: OnboardArgs, global: &crate::GlobalArgs) -> Result<Config> {
+    // A mistyped template is reported before the questions.
+    if let Some(template) = &args.template {
+        template_source(template)?;
+    }
+    let step = |step, title| {
+        if !args.yes {
+            print_step(step, 5, title);
         }
     };
+    if !args.yes {
+        println!("{}", style(BANNER).cyan().bold());
+
+        println!("  {}", style("Welcome to Dinoe!").white().bold());
+        println!(
+            "  {}",
+            style("This wizard will configure your agent in under 30 seconds.").dim()
+        );
+        println!();
+    }
 
-    print_step(4, 5, "Model Selection");
-    let model = setup_model_with_ollama_url(&provider, ollama_url.as_deref())?;
+    step(1, "Provider Selection");
+    let provider = choose_provider(&args, global)?;
 
-    let config = Config {
-        api_key,
+    step(2, "API Key Setup");
+    let api_key = choose_api_key(&provider, &args)?;
+
+    step(3, "Endpoint Selection");
+    let endpoint = choose_endpoint(&provider, &args)?;
+    let base_url = base_url(&provider, &endpoint);
+
+    step(4, "Model Selection");
+    let model = choose_model(&provider, base_url.as_deref(), &args, global)?;
+
+    let mut config = Config {
+        api_key: store_api_key(&provider, api_key),
         model,
         provider: Some(provider),
         base_url,
         ..Default::default()
     };
+    if let Some(temperature) = global.temperature {
+        config.temperature = temperature;
+    }
+    if let Some(dir) = &global.workspace {
+        config.workspace_dir =
+            dinoe_core::config::resolve_workspace_dir(dir, &std::env::current_dir()?);
+    }
 
-    print_step(5, 5, "Workspace Setup");
+    step(5, "Workspace Setup");
+    let template = choose_template(&args)?;
+    if let Some(template) = &template {
+        println!(
+            "  {} Applying the {} template to {}",
+            style("→").cyan(),
+            template,
+            style(config.workspace_dir.display()).cyan()
+        );
+        if let Err(e) = apply_template(template, &config.workspace_dir) {
+            eprintln!(
+                "  {} Warning: Could not apply the template: {:#}",
+                style("!").yellow(),
+                e
+            );
+        }
+    }
     if let Err(e) = create_bootstrap_files(&config.workspace_dir) {
         eprintln!(
             "  {} Warning: Could not create bootstrap files: {}",
             style("!").yellow(),
             e
         );
-    } else {
+    } else if template.is_none() {
         println!(
             "  {} Bootstrap files created at {}",
             style("✓").green(),
@@ -468,3 +869,140 @@ pub fn run_onboard() -> Result<Config> {
 
     Ok(config)
 }
+
+/// Runs one part of the wizard and changes only the settings it covers, keeping the
+/// rest of config.toml as it is.
+pub fn run_section(section: Section, args: &OnboardArgs, global: &crate::GlobalArgs) -> Result<()> {
+    let path = dinoe_core::config::get_config_path();
+    let text = std::fs::read_to_string(&path).with_context(|| {
+        format!("Failed to read {}. Run 'dinoe onboard' first.", path.display())
+    })?;
+    let current: Config = toml::from_str(&text)
+        .with_context(|| format!("Failed to parse config from {}", path.display()))?;
+    let provider = current.provider.clone().unwrap_or_else(|| "openai".to_string());
+
+    let changes = match section {
+        Section::Model => {
+            let model = choose_model(&provider, current.base_url.as_deref(), args, global)?;
+            vec![("model", Some(model))]
+        }
+        Section::Workspace => {
+            let Some(template) = choose_template(args)? else {
+                anyhow::bail!("Pass --template to set up the workspace without asking");
+            };
+            // The workspace --workspace or a project config points at.
+            let workspace = global.load_config()?.workspace_dir;
+            apply_template(&template, &workspace)?;
+            return create_bootstrap_files(&workspace);
+        }
+        Section::Endpoint => {
+            let endpoint = match provider.as_str() {
+                "ollama" | "zai" => choose_endpoint(&provider, args)?,
+                _ => match &args.endpoint {
+                    Some(endpoint) => endpoint.clone(),
+                    None if args.yes => String::new(),
+                    None => setup_base_url(&provider, current.base_url.as_deref())?,
+                },
+            };
+            vec![("base_url", base_url(&provider, &endpoint))]
+        }
+        Section::Provider => {
+            let step = |step, title| {
+                if !args.yes {
+                    print_step(step, 4, title);
+                }
+            };
+            step(1, "Provider Selection");
+            let chosen = choose_provider(args, global)?;
+            // A key already in config.toml for the same provider is not asked for again.
+            let keep_key = chosen == provider
+                && !current.api_key.is_empty()
+                && args.api_key_env.is_none();
+            let provider = chosen;
+            step(2, "API Key Setup");
+            let api_key = if keep_key {
+                println!("  {} Keeping the key in {}", style("✓").green(), path.display());
+                current.api_key.clone()
+            } else {
+                choose_api_key(&provider, args)?
+            };
+            step(3, "Endpoint Selection");
+            let endpoint = choose_endpoint(&provider, args)?;
+            let base_url = base_url(&provider, &endpoint);
+            step(4, "Model Selection");
+            let model = choose_model(&provider, base_url.as_deref(), args, global)?;
+            // Otherwise a key in config.toml belongs to the provider being replaced.
+            let api_key = if keep_key { api_key } else { store_api_key(&provider, api_key) };
+            vec![
+                ("provider", Some(provider)),
+                ("api_key", (!api_key.is_empty()).then_some(api_key)),
+                ("base_url", base_url),
+                ("model", Some(model)),
+            ]
+        }
+    };
+    let changes: Vec<(&str, Option<toml_edit::Value>)> = changes
+        .into_iter()
+        .map(|(key, value)| (key, value.map(toml_edit::Value::from)))
+        .collect();
+    crate::settings::update(&changes)?;
+
+    println!();
+    for (key, value) in &changes {
+        match value {
+            // The key is not shown.
+            Some(_) if *key == "api_key" => println!("  {} Set api_key", style("✓").green()),
+            Some(value) => {
+                let value = value.to_string();
+                println!("  {} Set {} = {}", style("✓").green(), key, value.trim());
+            }
+            None => println!("  {} Removed {}", style("✓").green(), key),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(dir: &Path, name: &str) -> String {
+        std::fs::read_to_string(dir.join(name)).unwrap()
+    }
+
+    #[test]
+    fn templates_keep_edited_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let workspace = tmp.path().join("workspace");
+        create_bootstrap_files(&workspace).unwrap();
+        std::fs::write(workspace.join("USER.md"), "# Me\n").unwrap();
+
+        apply_template("coder", &workspace).unwrap();
+        assert!(read(&workspace, "SOUL.md").contains("pair programmer"));
+        assert_eq!(read(&workspace, "USER.md"), "# Me\n");
+        for template in &WORKSPACE_TEMPLATES {
+            let dir = tmp.path().join(template.name);
+            apply_template(template.name, &dir).unwrap();
+            for (name, _) in template.skills {
+                let skill = dinoe_core::skills::load_skill(&dir.join("skills").join(name)).unwrap();
+                assert_eq!(skill.name, *name);
+            }
+        }
+
+        let custom = tmp.path().join("custom");
+        std::fs::create_dir_all(custom.join("skills/standup")).unwrap();
+        std::fs::write(custom.join("SOUL.md"), "# Our agent\n").unwrap();
+        std::fs::write(
+            custom.join("skills/standup/SKILL.md"),
+            "---\nname: standup\ndescription: Write the stand-up notes\n---\n\nSteps\n",
+        )
+        .unwrap();
+        apply_template(custom.to_str().unwrap(), &workspace).unwrap();
+        assert_eq!(read(&workspace, "SOUL.md"), "# Our agent\n");
+        assert!(workspace.join("skills/standup/SKILL.md").exists());
+        assert!(workspace.join("skills/code-review/SKILL.md").exists());
+
+        assert!(apply_template("poet", &workspace).is_err());
+        assert!(apply_template(workspace.join("skills").to_str().unwrap(), &workspace).is_err());
+    }
+}
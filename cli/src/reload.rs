@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use dinoe_core::agent::AgentLoop;
+use dinoe_core::config::{self, Config};
+use dinoe_core::skills::{self, SkillState};
+use notify::{Event, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::GlobalArgs;
+
+/// How long to wait for more changes before reloading, as editors often save a file
+/// in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches config.toml, the project's config and bootstrap files, and the installed
+/// skills with their scripts, and loads the config again after each change, with the
+/// `profile` agent profile applied. A config that fails to load is sent as the error,
+/// so the caller can keep the one it has.
+pub fn watch(
+    global: GlobalArgs,
+    profile: Option<String>,
+    current: &Config,
+) -> mpsc::UnboundedReceiver<Result<Config>> {
+    let mut files = vec![config::get_config_path()];
+    // Project bootstrap files are only picked up when the agent is built, unlike the
+    // workspace's, which are read again every turn.
+    if let Some(project_dir) = &current.project_dir {
+        files.push(project_dir.join("config.toml"));
+        files.extend(["SOUL.md", "TOOLS.md", "USER.md"].map(|name| project_dir.join(name)));
+    }
+    files.push(SkillState::path(&current.workspace_dir));
+    files.extend(current.project_dir.as_deref().map(SkillState::path));
+    let watched = Watched {
+        files,
+        skill_dirs: skills::skill_dirs(current),
+        watching: Vec::new(),
+    };
+    watch_files(watched, move || {
+        global.load_config().and_then(|config| match &profile {
+            Some(name) => config.with_agent(name),
+            None => Ok(config),
+        })
+    })
+}
+
+/// `agent`, continuing the session `previous` was recording.
+pub fn continue_session(mut agent: AgentLoop, previous: &AgentLoop) -> AgentLoop {
+    if let Some(transcript) = previous.transcript() {
+        agent.set_transcript(transcript.clone());
+    }
+    agent
+}
+
+/// Sends what `load` returns after each change to `watched`, until the receiver is
+/// dropped.
+fn watch_files(
+    mut watched: Watched,
+    load: impl Fn() -> Result<Config> + Send + 'static,
+) -> mpsc::UnboundedReceiver<Result<Config>> {
+    let (reloads, received) = mpsc::unbounded_channel();
+    let (changes, mut changed) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        // Reading a file is not a change, and the reload itself reads them all.
+        Ok(event) if !event.kind.is_access() => {
+            let _ = changes.send(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Watching the config failed: {}", e),
+    });
+    let mut watcher = match watcher {
+        Ok(mut watcher) => {
+            watched.watch_new(&mut watcher);
+            Some(watcher)
+        }
+        Err(e) => {
+            tracing::warn!("Config changes will not be picked up: {}", e);
+            None
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            let mut paths = tokio::select! {
+                _ = reloads.closed() => break,
+                Some(paths) = changed.recv() => paths,
+            };
+            while let Ok(Some(more)) = tokio::time::timeout(DEBOUNCE, changed.recv()).await {
+                paths.extend(more);
+            }
+            if !paths.iter().any(|path| watched.covers(path)) {
+                continue;
+            }
+            if let Some(watcher) = &mut watcher {
+                watched.watch_new(watcher);
+            }
+            if reloads.send(load()).is_err() {
+                break;
+            }
+        }
+    });
+    received
+}
+
+struct Watched {
+    files: Vec<PathBuf>,
+    skill_dirs: Vec<PathBuf>,
+    /// Directories watched so far. A skills directory that does not exist yet is
+    /// watched once it does, through its parent until then.
+    watching: Vec<PathBuf>,
+}
+
+impl Watched {
+    /// A change to `path` means the config has to be loaded again.
+    fn covers(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+            || self.skill_dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
+    /// Watches the directories of the files, and each skills directory with everything
+    /// in it, that exist and are not watched yet.
+    fn watch_new(&mut self, watcher: &mut impl Watcher) {
+        let mut wanted: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+        for file in &self.files {
+            wanted.extend(
+                file.parent()
+                    .map(|dir| (dir.to_path_buf(), RecursiveMode::NonRecursive)),
+            );
+        }
+        for dir in &self.skill_dirs {
+            if dir.is_dir() {
+                wanted.push((dir.clone(), RecursiveMode::Recursive));
+            } else {
+                wanted.extend(
+                    dir.parent()
+                        .map(|parent| (parent.to_path_buf(), RecursiveMode::NonRecursive)),
+                );
+            }
+        }
+        for (dir, mode) in wanted {
+            if self.watching.contains(&dir) || !dir.is_dir() {
+                continue;
+            }
+            match watcher.watch(&dir, mode) {
+                Ok(()) => self.watching.push(dir),
+                Err(e) => tracing::warn!("Could not watch {}: {}", dir.display(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The last reload once the changes settle; one change may take a few.
+    async fn next(reloads: &mut mpsc::UnboundedReceiver<Result<Config>>) -> Result<Config> {
+        let mut last = tokio::time::timeout(Duration::from_secs(10), reloads.recv())
+            .await
+            .expect("no reload")
+            .unwrap();
+        while let Ok(Some(later)) = tokio::time::timeout(DEBOUNCE * 2, reloads.recv()).await {
+            last = later;
+        }
+        last
+    }
+
+    #[tokio::test]
+    async fn reloads_after_edits_and_keeps_the_last_good_config() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let workspace = tmp.path().join("workspace");
+        let skills = workspace.join("skills");
+        let mut config = Config {
+            workspace_dir: workspace.clone(),
+            api_key: "test".into(),
+            ..Default::default()
+        };
+        std::fs::write(&path, "model = \"first\"").unwrap();
+        let mut agent = crate::build_agent(&config, crate::approval::Prompts::none()).unwrap();
+
+        let watched = Watched {
+            files: vec![path.clone()],
+            skill_dirs: vec![skills.clone()],
+            watching: Vec::new(),
+        };
+        let load = {
+            let path = path.clone();
+            let workspace = workspace.clone();
+            move || -> Result<Config> {
+                let mut config: Config = toml::from_str(&std::fs::read_to_string(&path)?)?;
+                config.workspace_dir = workspace.clone();
+                config.api_key = "test".into();
+                Ok(config)
+            }
+        };
+        let mut reloads = watch_files(watched, load);
+
+        std::fs::write(&path, "model = \"second\"").unwrap();
+        let reloaded = next(&mut reloads).await;
+        crate::reload_chat(
+            &mut agent,
+            &mut config,
+            reloaded,
+            &crate::approval::Prompts::none(),
+            "",
+        );
+        assert_eq!(config.model, "second");
+
+        std::fs::write(&path, "model = [").unwrap();
+        let broken = next(&mut reloads).await;
+        assert!(broken.is_err());
+        crate::reload_chat(
+            &mut agent,
+            &mut config,
+            broken,
+            &crate::approval::Prompts::none(),
+            "",
+        );
+        assert_eq!(config.model, "second");
+
+        std::fs::write(&path, "model = \"third\"").unwrap();
+        assert_eq!(next(&mut reloads).await.unwrap().model, "third");
+
+        // Skill scripts count too, including in a skills directory made after the start.
+        std::fs::create_dir_all(skills.join("shout/scripts")).unwrap();
+        next(&mut reloads).await.unwrap();
+        std::fs::write(skills.join("shout/scripts/shout.sh"), "echo hi").unwrap();
+        next(&mut reloads).await.unwrap();
+
+        std::fs::write(tmp.path().join("notes.md"), "unrelated").unwrap();
+        let quiet = tokio::time::timeout(DEBOUNCE * 3, reloads.recv()).await;
+        assert!(quiet.is_err());
+    }
+
+    #[test]
+    fn reload_skills_picks_up_a_new_skill() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            api_key: "test".into(),
+            ..Default::default()
+        };
+        let prompts = crate::approval::Prompts::none();
+        let mut agent = crate::build_agent(&config, prompts.clone()).unwrap();
+        let has_tool = |agent: &AgentLoop| agent.tool_specs().iter().any(|t| t.name == "shout");
+        assert!(!has_tool(&agent));
+
+        let skill_dir = skills::skills_dir(tmp.path()).join("shout");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: shout\ndescription: Shout things\ntools:\n  - name: shout\n    \
+             description: Upper-case a text\n    command: tr a-z A-Z\n---\n# Shout\n",
+        )
+        .unwrap();
+
+        // What /reload-skills does.
+        let current = Ok(config.clone());
+        crate::reload_chat(
+            &mut agent,
+            &mut config,
+            current,
+            &prompts,
+            "Skills reloaded",
+        );
+        assert!(has_tool(&agent));
+    }
+}
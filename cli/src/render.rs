@@ -0,0 +1,134 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use dinoe_core::agent::{AgentEvent, EventSink};
+
+use crate::markdown::MarkdownStream;
+
+const STATUS_MAX: usize = 200;
+const TOOL_RESULT_MAX: usize = 200;
+
+fn truncate_preview(input: &str, max: usize) -> String {
+    let input = input.trim();
+    if input.chars().count() <= max {
+        input.to_string()
+    } else {
+        let truncated: String = input.chars().take(max - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
+/// The line between status output and the answer.
+pub fn print_separator() {
+    let width = crossterm::terminal::size()
+        .map(|(w, _)| w as usize)
+        .unwrap_or(80);
+    eprintln!("\x1b[90m{}\x1b[0m", "\u{2500}".repeat(width.min(80)));
+}
+
+/// Prints agent events as status lines on stderr, and streamed answer text as markdown
+/// on stdout. Without streaming the answer is printed by the caller.
+pub struct EventPrinter {
+    stream: Mutex<Option<MarkdownStream>>,
+    streamed: AtomicBool,
+}
+
+impl EventPrinter {
+    pub fn new() -> Self {
+        Self {
+            stream: Mutex::new(None),
+            streamed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn sink(self: &Arc<Self>) -> EventSink {
+        let printer = self.clone();
+        EventSink::new(move |event| printer.print(&event))
+    }
+
+    /// Whether any answer text was printed as it streamed in.
+    pub fn streamed(&self) -> bool {
+        self.streamed.load(Ordering::Relaxed)
+    }
+
+    fn print_token(&self, text: &str) {
+        let mut stream = self.stream.lock().unwrap();
+        let stream = stream.get_or_insert_with(|| {
+            print_separator();
+            let width = crossterm::terminal::size()
+                .map(|(w, _)| w as usize)
+                .unwrap_or(80);
+            MarkdownStream::for_stdout(width)
+        });
+        self.streamed.store(true, Ordering::Relaxed);
+        print!("{}", stream.push(text));
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Prints the rest of the streamed text before anything else is shown.
+    fn end_stream(&self) {
+        if let Some(mut stream) = self.stream.lock().unwrap().take() {
+            println!("{}", stream.finish());
+        }
+    }
+
+    pub fn print(&self, event: &AgentEvent) {
+        match event {
+            AgentEvent::Token { text } => {
+                self.print_token(text);
+                return;
+            }
+            AgentEvent::Thinking { .. } | AgentEvent::TurnSummary(_) => {}
+            _ => self.end_stream(),
+        }
+        match event {
+            AgentEvent::Status { message } => {
+                let display = truncate_preview(message, STATUS_MAX);
+                eprintln!("  \x1b[90m\u{25CB} {}\x1b[0m", display);
+            }
+            AgentEvent::ToolStarted { name } => {
+                eprintln!("  \x1b[33m\u{25CB} {}\x1b[0m", name);
+            }
+            AgentEvent::ToolFinished {
+                name,
+                success,
+                preview,
+            } => {
+                if *success {
+                    eprintln!("  \x1b[32m\u{25CF} {}\x1b[0m", name);
+                } else {
+                    eprintln!("  \x1b[31m\u{2717} {} (failed)\x1b[0m", name);
+                }
+                eprintln!(
+                    "    \x1b[90m{}\x1b[0m",
+                    truncate_preview(preview, TOOL_RESULT_MAX)
+                );
+            }
+            AgentEvent::ToolProgress { name, message } => {
+                let display = truncate_preview(message, STATUS_MAX);
+                eprintln!("    \x1b[90m\u{2026} {}: {}\x1b[0m", name, display);
+            }
+            AgentEvent::Question { question, .. } => {
+                eprintln!(
+                    "  \x1b[36m? {}\x1b[0m",
+                    truncate_preview(question, STATUS_MAX)
+                );
+            }
+            AgentEvent::TurnSummary(summary) => {
+                eprintln!("  \x1b[90m{}\x1b[0m", summary);
+            }
+            AgentEvent::Token { .. }
+            | AgentEvent::Thinking { .. }
+            | AgentEvent::TurnComplete { .. }
+            | AgentEvent::Cancelled
+            | AgentEvent::Error { .. } => {}
+        }
+    }
+}
+
+impl Default for EventPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
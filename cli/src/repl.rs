@@ -12,10 +12,17 @@ use rustyline::{
     Cmd as ReadlineCmd, CompletionType, ConditionalEventHandler, Editor, Event, EventContext,
     EventHandler, Helper, KeyCode, KeyEvent, Modifiers, RepeatCount,
 };
-use termimad::MadSkin;
 use tokio::sync::mpsc;
 
-const SLASH_COMMANDS: &[&str] = &["/help", "/quit", "/exit"];
+const SLASH_COMMANDS: &[&str] = &[
+    "/help",
+    "/undo",
+    "/rewind",
+    "/rollback",
+    "/reload-skills",
+    "/quit",
+    "/exit",
+];
 
 struct ReplHelper;
 
@@ -81,17 +88,6 @@ impl ConditionalEventHandler for EscInterruptHandler {
     }
 }
 
-fn make_skin() -> MadSkin {
-    let mut skin = MadSkin::default();
-    skin.set_headers_fg(termimad::crossterm::style::Color::Yellow);
-    skin.bold.set_fg(termimad::crossterm::style::Color::White);
-    skin.italic.set_fg(termimad::crossterm::style::Color::Magenta);
-    skin.inline_code.set_fg(termimad::crossterm::style::Color::Green);
-    skin.code_block.set_fg(termimad::crossterm::style::Color::Green);
-    skin.code_block.left_margin = 2;
-    skin
-}
-
 fn print_help() {
     let h = "\x1b[1m";
     let c = "\x1b[1;36m";
@@ -103,31 +99,42 @@ fn print_help() {
     println!();
     println!("  {h}Commands{r}");
     println!("  {c}/help{r}              {d}show this help{r}");
+    println!("  {c}/undo{r}              {d}take back the last turn{r}");
+    println!("  {c}/rewind{r} N          {d}go back N turns, keeping the old branch as a session{r}");
+    println!("  {c}/rollback{r}          {d}undo the file changes of the last turn that made any{r}");
+    println!("  {c}/reload-skills{r}     {d}load the installed skills again{r}");
     println!("  {c}/quit{r} {c}/exit{r}        {d}exit the repl{r}");
     println!();
 }
 
 fn history_path() -> std::path::PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".dinoe")
-        .join("history")
-}
-
-pub fn print_markdown(content: &str) {
-    let width = crossterm::terminal::size()
-        .map(|(w, _)| w as usize)
-        .unwrap_or(80);
-    let skin = make_skin();
-    let text = termimad::FmtText::from(&skin, content, Some(width));
-    print!("{text}");
+    dinoe_core::config::get_data_dir().join("history")
 }
 
 pub enum ReplCommand {
     Input(String),
+    /// Go back this many turns.
+    Rewind(usize),
+    /// Restore the workspace files from the last checkpoint.
+    Rollback,
+    /// Build the agent again with the skills as they are now.
+    ReloadSkills,
     Quit,
 }
 
+/// `/undo` and `/rewind [N]`; `Err` holds a usage message.
+fn parse_rewind(line: &str) -> Option<Result<usize, &'static str>> {
+    let mut words = line.split_whitespace();
+    match words.next()? {
+        "/undo" => Some(Ok(1)),
+        "/rewind" => Some(match words.next() {
+            None => Ok(1),
+            Some(n) => n.parse().map_err(|_| "Usage: /rewind N"),
+        }),
+        _ => None,
+    }
+}
+
 pub struct ReplHandle {
     input_rx: mpsc::Receiver<ReplCommand>,
     done_tx: mpsc::Sender<()>,
@@ -199,10 +206,32 @@ pub fn start() -> ReplHandle {
                             print_help();
                             continue;
                         }
+                        "/rollback" => {
+                            if input_tx.blocking_send(ReplCommand::Rollback).is_err() {
+                                break;
+                            }
+                            let _ = done_rx.blocking_recv();
+                            continue;
+                        }
+                        "/reload-skills" => {
+                            if input_tx.blocking_send(ReplCommand::ReloadSkills).is_err() {
+                                break;
+                            }
+                            let _ = done_rx.blocking_recv();
+                            continue;
+                        }
                         _ => {}
                     }
 
-                    if input_tx.blocking_send(ReplCommand::Input(line.to_string())).is_err() {
+                    let command = match parse_rewind(&line.to_lowercase()) {
+                        Some(Ok(turns)) => ReplCommand::Rewind(turns),
+                        Some(Err(usage)) => {
+                            println!("\x1b[90m{usage}\x1b[0m");
+                            continue;
+                        }
+                        None => ReplCommand::Input(line.to_string()),
+                    };
+                    if input_tx.blocking_send(command).is_err() {
                         break;
                     }
 
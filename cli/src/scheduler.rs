@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use console::style;
+use dinoe_core::agent::AgentLoop;
+use dinoe_core::config::Config;
+use dinoe_core::scheduler::JobStore;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub async fn handle_command(command: SchedulerCommands, config: &Config) -> Result<()> {
+    match command {
+        SchedulerCommands::Run { once } => run(config, once).await,
+        SchedulerCommands::List => list(config),
+    }
+}
+
+async fn run(config: &Config, once: bool) -> Result<()> {
+    let agent = crate::build_agent(config, crate::approval::Prompts::none())?;
+    let mut scheduler = Scheduler::new(config);
+
+    if !once {
+        println!(
+            "{} Scheduler running, watching {} (Ctrl+C to stop)",
+            style("●").green(),
+            scheduler.store.path().display()
+        );
+    }
+
+    loop {
+        scheduler.tick(config, &agent).await;
+        if once {
+            return Ok(());
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("{} Scheduler stopped", style("●").yellow());
+                return Ok(());
+            }
+            _ = tokio::time::sleep(scheduler.wait()) => {}
+        }
+    }
+}
+
+/// Runs due jobs and the daily memory consolidation, for `dinoe scheduler run` and
+/// `dinoe daemon`.
+pub(crate) struct Scheduler {
+    store: JobStore,
+    consolidated_on: Option<NaiveDate>,
+}
+
+impl Scheduler {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            store: JobStore::new(&config.workspace_dir),
+            consolidated_on: None,
+        }
+    }
+
+    /// Errors reading or writing the jobs are reported and the jobs tried again on the
+    /// next tick, so a bad edit to the file does not stop the scheduler.
+    pub(crate) async fn tick(&mut self, config: &Config, agent: &AgentLoop) {
+        let today = Local::now().date_naive();
+        if config.memory.consolidate_after_days > 0 && self.consolidated_on != Some(today) {
+            self.consolidated_on = Some(today);
+            match crate::memory::consolidate(config, config.memory.consolidate_after_days).await {
+                Ok(report) if !report.days.is_empty() => crate::memory::print_report(&report),
+                Ok(_) => {}
+                Err(e) => eprintln!("{} Memory consolidation failed: {}", style("✗").red(), e),
+            }
+        }
+
+        let due = match self.store.due(Local::now()) {
+            Ok(due) => due,
+            Err(e) => {
+                eprintln!(
+                    "{} Could not read the scheduled jobs: {:#}",
+                    style("✗").red(),
+                    e
+                );
+                return;
+            }
+        };
+        for job in due {
+            let started = Local::now();
+            println!(
+                "{} [{}] Running job {} '{}'",
+                style("▶").cyan(),
+                started.format("%Y-%m-%d %H:%M:%S"),
+                job.id,
+                job.name
+            );
+
+            let result = match agent.process(&job.prompt).await.into_result() {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("{} Job {} failed: {}", style("✗").red(), job.id, e);
+                    format!("Error: {e}")
+                }
+            };
+            println!("{}\n", result);
+            if let Err(e) = self.store.record_run(&job.id, started, &result) {
+                eprintln!(
+                    "{} Could not record the run of job {}: {:#}",
+                    style("✗").red(),
+                    job.id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// How long to sleep before the next tick: until the next job, but no longer than
+    /// the poll interval, so jobs added meanwhile are picked up.
+    pub(crate) fn wait(&self) -> Duration {
+        self.store
+            .next_wakeup()
+            .ok()
+            .flatten()
+            .and_then(|next| (next - Local::now()).to_std().ok())
+            .map(|until| until.clamp(Duration::from_secs(1), POLL_INTERVAL))
+            .unwrap_or(POLL_INTERVAL)
+    }
+}
+
+fn list(config: &Config) -> Result<()> {
+    let jobs = JobStore::new(&config.workspace_dir).load()?;
+    if jobs.is_empty() {
+        println!("{} No scheduled jobs", style("!").yellow());
+        println!();
+        println!("Ask the agent to schedule one, e.g.:");
+        println!("  dinoe chat -m \"Every weekday at 9:00, summarize my open TODOs\"");
+        return Ok(());
+    }
+
+    let format_time = |t: Option<chrono::DateTime<Local>>| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for job in jobs {
+        let status = if job.enabled {
+            style("active").green()
+        } else {
+            style("finished").dim()
+        };
+        println!(
+            "{} {} ({}) [{}]",
+            style(&job.id).bold(),
+            job.name,
+            job.schedule.describe(),
+            status
+        );
+        println!(
+            "  next: {}  last: {}  runs: {}",
+            format_time(job.next_run),
+            format_time(job.last_run),
+            job.runs
+        );
+    }
+    Ok(())
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum SchedulerCommands {
+    /// Run due jobs, then keep waiting for the next ones
+    Run {
+        /// Run jobs that are due now and exit
+        #[arg(long)]
+        once: bool,
+    },
+    List,
+}
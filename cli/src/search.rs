@@ -0,0 +1,106 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use console::style;
+use dinoe_core::config::Config;
+use dinoe_core::search::{self, Filter, Snippet, Source};
+use dinoe_core::transcript;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct SearchArgs {
+    /// Words to look for
+    #[arg(required = true)]
+    query: Vec<String>,
+    /// Where to look (default: everywhere)
+    #[arg(long = "in", value_enum, value_delimiter = ',', value_name = "WHERE")]
+    sources: Vec<Where>,
+    /// Only what is from this day or later
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    since: Option<NaiveDate>,
+    /// Only what is from this day or earlier
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    until: Option<NaiveDate>,
+    /// Only memories in this category, such as core or daily
+    #[arg(long)]
+    category: Option<String>,
+    /// Only what belongs to this session
+    #[arg(long, value_name = "ID")]
+    session: Option<String>,
+    /// Most results to show
+    #[arg(short = 'n', long, default_value_t = 10)]
+    limit: usize,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Where {
+    Memory,
+    Sessions,
+    Notes,
+}
+
+impl From<Where> for Source {
+    fn from(place: Where) -> Self {
+        match place {
+            Where::Memory => Source::Memory,
+            Where::Sessions => Source::Session,
+            Where::Notes => Source::Note,
+        }
+    }
+}
+
+pub async fn handle_command(args: SearchArgs, config: &Config) -> Result<()> {
+    let query = args.query.join(" ");
+    let filter = Filter {
+        sources: args.sources.into_iter().map(Source::from).collect(),
+        since: args.since,
+        until: args.until,
+        category: args.category,
+        session: args.session,
+    };
+
+    let mut documents = Vec::new();
+    if filter.includes(Source::Memory) {
+        let memory = crate::open_memory(config, crate::redactor(config)?.as_ref())?;
+        documents.extend(search::memory_documents(memory.as_ref()).await?);
+    }
+    if filter.includes(Source::Session) {
+        documents.extend(search::session_documents(&transcript::sessions_dir())?);
+    }
+    if filter.includes(Source::Note) {
+        documents.extend(search::note_documents(&config.workspace_dir));
+    }
+
+    let hits = search::search(documents, &query, &filter, args.limit);
+    if hits.is_empty() {
+        println!("{} Nothing matches '{}'", style("!").yellow(), query);
+    }
+    for hit in hits {
+        let document = &hit.document;
+        let mut details = vec![document.location.clone()];
+        details.extend(document.date.map(|d| d.to_string()));
+        details.extend(document.category.clone());
+        println!(
+            "{} {}",
+            style(format!("{:<8}", document.source.to_string())).cyan(),
+            style(details.join("  ")).dim()
+        );
+        println!("  {}\n", highlighted(&hit.snippet));
+    }
+    Ok(())
+}
+
+fn highlighted(snippet: &Snippet) -> String {
+    let mut out = String::new();
+    let mut shown = 0;
+    for range in &snippet.highlights {
+        out.push_str(&snippet.text[shown..range.start]);
+        out.push_str(
+            &style(&snippet.text[range.clone()])
+                .yellow()
+                .bold()
+                .to_string(),
+        );
+        shown = range.end;
+    }
+    out.push_str(&snippet.text[shown..]);
+    out
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dinoe_core::agent::{AgentEvent, EventSink};
+
+use super::Server;
+
+/// Upper bounds of the turn duration buckets, in seconds.
+const TURN_SECONDS_BUCKETS: [f64; 10] = [0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+/// What the server has done since it started, for `/metrics`.
+#[derive(Default)]
+pub(crate) struct Metrics(Mutex<Counts>);
+
+#[derive(Default)]
+struct Counts {
+    /// By method, route and status.
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// By outcome.
+    turns: BTreeMap<&'static str, u64>,
+    turn_seconds: Histogram,
+    /// By tool and whether the call succeeded.
+    tool_calls: BTreeMap<(String, bool), u64>,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; TURN_SECONDS_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(TURN_SECONDS_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+impl Metrics {
+    /// `events` with the turn counted on the way: its tool calls, tokens, duration and
+    /// how it ended. Max iterations count as an error, like the event the agent sends.
+    pub(crate) fn sink(self: &Arc<Self>, events: impl Into<EventSink>) -> EventSink {
+        let (metrics, events) = (self.clone(), events.into());
+        let outcome = Mutex::new("error");
+        EventSink::new(move |event: AgentEvent| {
+            let mut counts = metrics.0.lock().unwrap();
+            match &event {
+                AgentEvent::ToolFinished { name, success, .. } => {
+                    *counts
+                        .tool_calls
+                        .entry((name.clone(), *success))
+                        .or_default() += 1;
+                }
+                AgentEvent::TurnComplete { .. } => *outcome.lock().unwrap() = "completed",
+                AgentEvent::Cancelled => *outcome.lock().unwrap() = "cancelled",
+                AgentEvent::Error { .. } => *outcome.lock().unwrap() = "error",
+                AgentEvent::TurnSummary(summary) => {
+                    *counts.turns.entry(*outcome.lock().unwrap()).or_default() += 1;
+                    counts
+                        .turn_seconds
+                        .observe(summary.elapsed_ms as f64 / 1000.0);
+                    counts.input_tokens += summary.input_tokens as u64;
+                    counts.output_tokens += summary.output_tokens as u64;
+                }
+                _ => {}
+            }
+            drop(counts);
+            events.emit(event);
+        })
+    }
+
+    fn count_request(&self, key: (String, String, u16)) {
+        *self.0.lock().unwrap().requests.entry(key).or_default() += 1;
+    }
+
+    /// The metrics in the Prometheus text format.
+    fn render(&self) -> String {
+        let counts = self.0.lock().unwrap();
+        let mut out = String::new();
+        header(
+            &mut out,
+            "dinoe_http_requests_total",
+            "counter",
+            "HTTP requests by route and status.",
+        );
+        for ((method, route, status), count) in &counts.requests {
+            let labels = [
+                ("method", method.as_str()),
+                ("route", route),
+                ("status", &status.to_string()),
+            ];
+            sample(&mut out, "dinoe_http_requests_total", &labels, *count);
+        }
+        header(
+            &mut out,
+            "dinoe_turns_total",
+            "counter",
+            "Agent turns by outcome: completed, cancelled or error.",
+        );
+        for (outcome, count) in &counts.turns {
+            sample(
+                &mut out,
+                "dinoe_turns_total",
+                &[("outcome", outcome)],
+                *count,
+            );
+        }
+        header(
+            &mut out,
+            "dinoe_turn_duration_seconds",
+            "histogram",
+            "How long agent turns took.",
+        );
+        let histogram = &counts.turn_seconds;
+        for (bound, count) in TURN_SECONDS_BUCKETS.iter().zip(histogram.buckets) {
+            let le = bound.to_string();
+            sample(
+                &mut out,
+                "dinoe_turn_duration_seconds_bucket",
+                &[("le", &le)],
+                count,
+            );
+        }
+        let count = histogram.count;
+        sample(
+            &mut out,
+            "dinoe_turn_duration_seconds_bucket",
+            &[("le", "+Inf")],
+            count,
+        );
+        let _ = writeln!(out, "dinoe_turn_duration_seconds_sum {}", histogram.sum);
+        let _ = writeln!(out, "dinoe_turn_duration_seconds_count {count}");
+        header(
+            &mut out,
+            "dinoe_tool_calls_total",
+            "counter",
+            "Tool calls by tool and result: success or failure.",
+        );
+        for ((tool, success), count) in &counts.tool_calls {
+            let result = if *success { "success" } else { "failure" };
+            let labels = [("tool", tool.as_str()), ("result", result)];
+            sample(&mut out, "dinoe_tool_calls_total", &labels, *count);
+        }
+        header(
+            &mut out,
+            "dinoe_tokens_total",
+            "counter",
+            "Estimated tokens sent to and received from the model.",
+        );
+        let input = counts.input_tokens;
+        sample(
+            &mut out,
+            "dinoe_tokens_total",
+            &[("direction", "input")],
+            input,
+        );
+        let output = counts.output_tokens;
+        sample(
+            &mut out,
+            "dinoe_tokens_total",
+            &[("direction", "output")],
+            output,
+        );
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: u64) {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect();
+    let _ = writeln!(out, "{name}{{{}}} {value}", labels.join(","));
+}
+
+pub(super) async fn render(State(server): State<Arc<Server>>) -> Response {
+    let content_type = "text/plain; version=0.0.4; charset=utf-8";
+    (
+        [(header::CONTENT_TYPE, content_type)],
+        server.metrics.render(),
+    )
+        .into_response()
+}
+
+/// Counts each request by its route, so `/sessions/{id}` is one series for all sessions.
+pub(super) async fn count_requests(
+    State(server): State<Arc<Server>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+    let response = next.run(request).await;
+    let key = (method, route, response.status().as_u16());
+    server.metrics.count_request(key);
+    response
+}
@@ -0,0 +1,682 @@
+mod metrics;
+mod openai;
+mod webhooks;
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::{Path, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Args;
+use dinoe_core::agent::{AgentEvent, AgentLoop, EventSink, TurnOutcome, TurnSummary};
+use dinoe_core::config::Config;
+use dinoe_core::traits::ChatMessage;
+use dinoe_core::transcript::{self, Transcript};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::approval::Prompts;
+use metrics::Metrics;
+
+/// Events kept for `/events` subscribers that fall behind.
+const EVENT_BUFFER: usize = 256;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on, instead of `serve.host` in the config
+    #[arg(long)]
+    host: Option<String>,
+    /// Port to listen on, instead of `serve.port` in the config
+    #[arg(long)]
+    port: Option<u16>,
+    /// Agent profile for sessions that do not name one
+    #[arg(long, value_name = "NAME")]
+    agent: Option<String>,
+}
+
+pub async fn handle_command(
+    args: ServeArgs,
+    config: Config,
+    mut reloads: mpsc::UnboundedReceiver<Result<Config>>,
+) -> Result<()> {
+    let host = args.host.unwrap_or_else(|| config.serve.host.clone());
+    let port = args.port.unwrap_or(config.serve.port);
+    if let Some(name) = &args.agent {
+        config.clone().with_agent(name)?;
+    }
+    let server = Arc::new(Server::new(config, transcript::sessions_dir(), args.agent));
+    server.agent(None)?;
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            while let Some(config) = reloads.recv().await {
+                match config.and_then(|config| server.reload(config)) {
+                    Ok(()) => eprintln!("Config and skills reloaded"),
+                    Err(e) => eprintln!("⚠ Keeping the previous config: {e:#}"),
+                }
+            }
+        }
+    });
+
+    let address = format!("{host}:{port}");
+    let listener = tokio::net::TcpListener::bind(&address)
+        .await
+        .with_context(|| format!("Failed to listen on {address}"))?;
+    eprintln!("dinoe is listening on http://{address}");
+    let config = server.config();
+    if config.serve.token.is_none() && !is_loopback(&host) {
+        eprintln!("⚠ No serve.token is set: anyone who can reach this port can use the agent");
+    }
+    for hook in &config.webhooks {
+        eprintln!(
+            "Webhook {} at http://{address}/hooks/{}",
+            hook.name, hook.name
+        );
+        if hook.secret.is_none() {
+            eprintln!(
+                "⚠ Webhook {} has no secret: anyone can trigger it",
+                hook.name
+            );
+        }
+    }
+    axum::serve(listener, router(server))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+fn is_loopback(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+pub(crate) fn router(server: Arc<Server>) -> Router {
+    Router::new()
+        .route("/tools", get(list_tools))
+        .route("/sessions", get(list_sessions).post(create_session))
+        .route("/sessions/{id}", get(get_session).delete(delete_session))
+        .route("/sessions/{id}/messages", post(send_message))
+        .route("/sessions/{id}/events", get(session_events))
+        .route("/sessions/{id}/cancel", post(cancel_turn))
+        .route("/metrics", get(metrics::render))
+        .merge(openai::routes())
+        .layer(middleware::from_fn_with_state(server.clone(), authorize))
+        .merge(webhooks::routes())
+        .layer(middleware::from_fn_with_state(
+            server.clone(),
+            metrics::count_requests,
+        ))
+        .with_state(server)
+}
+
+/// Agents by profile and the sessions opened since the server started.
+pub(crate) struct Server {
+    /// Replaced when config.toml changes.
+    config: RwLock<Arc<Config>>,
+    sessions_dir: PathBuf,
+    /// The profile for sessions that do not name one.
+    default_agent: Option<String>,
+    agents: Mutex<HashMap<Option<String>, AgentLoop>>,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+    metrics: Arc<Metrics>,
+}
+
+/// A chat recorded in its own transcript. One turn runs at a time.
+pub(crate) struct Session {
+    /// The profile the session was opened with.
+    profile: Option<String>,
+    agent: Mutex<AgentLoop>,
+    /// Cancels the running turn; `None` between turns.
+    running: Mutex<Option<CancellationToken>>,
+    events: broadcast::Sender<AgentEvent>,
+}
+
+impl Server {
+    pub(crate) fn new(
+        config: Config,
+        sessions_dir: PathBuf,
+        default_agent: Option<String>,
+    ) -> Self {
+        Self {
+            config: RwLock::new(Arc::new(config)),
+            sessions_dir,
+            default_agent,
+            agents: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+            metrics: Arc::default(),
+        }
+    }
+
+    /// Uses `agent` for sessions without a profile instead of building one.
+    #[cfg(test)]
+    pub(crate) fn with_default_agent(self, agent: AgentLoop) -> Self {
+        self.agents.lock().unwrap().insert(None, agent);
+        self
+    }
+
+    pub(crate) fn config(&self) -> Arc<Config> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Switches to `config` for new turns. Turns already running finish with the
+    /// agent they started with.
+    pub(crate) fn reload(&self, config: Config) -> Result<()> {
+        let previous = std::mem::replace(&mut *self.config.write().unwrap(), Arc::new(config));
+        let agents = std::mem::take(&mut *self.agents.lock().unwrap());
+        if let Err(e) = self.agent(None) {
+            *self.config.write().unwrap() = previous;
+            *self.agents.lock().unwrap() = agents;
+            return Err(e);
+        }
+        let sessions: Vec<_> = self.sessions.lock().unwrap().values().cloned().collect();
+        for session in sessions {
+            match self.agent(session.profile.as_deref()) {
+                Ok(agent) => {
+                    let mut current = session.agent.lock().unwrap();
+                    *current = crate::reload::continue_session(agent, &current);
+                }
+                Err(e) => eprintln!("⚠ A session keeps its agent: {e:#}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// The agent for `profile`, or the default one, built on first use.
+    pub(crate) fn agent(&self, profile: Option<&str>) -> Result<AgentLoop> {
+        let key = profile
+            .or(self.default_agent.as_deref())
+            .map(str::to_string);
+        if let Some(agent) = self.agents.lock().unwrap().get(&key) {
+            return Ok(agent.clone());
+        }
+        let config = match &key {
+            Some(name) => self.config().as_ref().clone().with_agent(name)?,
+            None => self.config().as_ref().clone(),
+        };
+        let agent = crate::build_agent(&config, Prompts::none())?;
+        self.agents.lock().unwrap().insert(key, agent.clone());
+        Ok(agent)
+    }
+
+    pub(crate) fn create_session(&self, profile: Option<&str>) -> Result<(String, Arc<Session>)> {
+        let transcript = Transcript::new(&self.sessions_dir);
+        let id = transcript.session().to_string();
+        let session = Arc::new(Session::new(profile, self.agent(profile)?, transcript));
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(id.clone(), session.clone());
+        Ok((id, session))
+    }
+
+    /// An open session, or one recorded earlier, which continues with the default agent.
+    pub(crate) fn session(&self, id: &str) -> Result<Arc<Session>, ApiError> {
+        if let Some(session) = self.sessions.lock().unwrap().get(id) {
+            return Ok(session.clone());
+        }
+        transcript::load(&self.sessions_dir, id).map_err(ApiError::not_found)?;
+        let transcript = Transcript::open(&self.sessions_dir, id);
+        let session = Arc::new(Session::new(None, self.agent(None)?, transcript));
+        let mut sessions = self.sessions.lock().unwrap();
+        Ok(sessions.entry(id.to_string()).or_insert(session).clone())
+    }
+
+    /// Runs a turn of `session` that continues its history.
+    pub(crate) fn send(
+        &self,
+        session: &Arc<Session>,
+        message: String,
+        events: mpsc::UnboundedSender<AgentEvent>,
+    ) -> Result<tokio::task::JoinHandle<TurnOutcome>, ApiError> {
+        let history = crate::session_history(&session.agent(), self.config().max_history)?;
+        session.start_turn(message, history, events, &self.metrics)
+    }
+}
+
+impl Session {
+    fn new(profile: Option<&str>, mut agent: AgentLoop, transcript: Transcript) -> Self {
+        agent.set_transcript(Arc::new(transcript));
+        Self {
+            profile: profile.map(str::to_string),
+            agent: Mutex::new(agent),
+            running: Mutex::new(None),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+
+    fn agent(&self) -> AgentLoop {
+        self.agent.lock().unwrap().clone()
+    }
+
+    /// Cancels the running turn; false when there is none.
+    pub(crate) fn cancel(&self) -> bool {
+        match self.running.lock().unwrap().as_ref() {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs a turn in the background, so it finishes even when the client goes away.
+    /// Its events go to `events` and to `/events` subscribers.
+    fn start_turn(
+        self: &Arc<Self>,
+        message: String,
+        history: Vec<ChatMessage>,
+        events: mpsc::UnboundedSender<AgentEvent>,
+        metrics: &Arc<Metrics>,
+    ) -> Result<tokio::task::JoinHandle<TurnOutcome>, ApiError> {
+        let cancel = CancellationToken::new();
+        {
+            let mut running = self.running.lock().unwrap();
+            if running.is_some() {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "A turn is already running in this session",
+                ));
+            }
+            *running = Some(cancel.clone());
+        }
+        let subscribers = self.events.clone();
+        let sink = metrics.sink(EventSink::new(move |event: AgentEvent| {
+            let _ = subscribers.send(event.clone());
+            let _ = events.send(event);
+        }));
+        let (session, agent) = (self.clone(), self.agent());
+        Ok(tokio::spawn(async move {
+            let outcome = agent
+                .process_cancellable(&message, history, sink, cancel)
+                .await;
+            *session.running.lock().unwrap() = None;
+            outcome
+        }))
+    }
+}
+
+/// An error response: `{"error": {"message": ...}}`.
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub(crate) fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(error: anyhow::Error) -> Self {
+        Self::new(StatusCode::NOT_FOUND, error.to_string())
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}"))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": { "message": self.message } });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Checks the bearer token, when one is configured.
+async fn authorize(State(server): State<Arc<Server>>, request: Request, next: Next) -> Response {
+    if let Some(token) = &server.config().serve.token {
+        let given = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if given != Some(token.as_str()) {
+            return ApiError::new(StatusCode::UNAUTHORIZED, "Missing or wrong bearer token")
+                .into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// An agent event as a server-sent event named after its type.
+fn sse_event(event: &AgentEvent) -> Result<Event, Infallible> {
+    let data = serde_json::to_value(event).unwrap_or_default();
+    let name = data["type"].as_str().unwrap_or("event").to_string();
+    Ok(Event::default().event(name).data(data.to_string()))
+}
+
+fn sse(
+    events: impl Stream<Item = Result<Event, Infallible>> + Send + 'static,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn list_tools(State(server): State<Arc<Server>>) -> Result<Response, ApiError> {
+    Ok(Json(server.agent(None)?.tool_specs()).into_response())
+}
+
+async fn list_sessions(State(server): State<Arc<Server>>) -> Result<Response, ApiError> {
+    Ok(Json(transcript::list(&server.sessions_dir)?).into_response())
+}
+
+#[derive(Deserialize, Default)]
+struct CreateSession {
+    /// An agent profile from the config.
+    agent: Option<String>,
+}
+
+async fn create_session(
+    State(server): State<Arc<Server>>,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    // The body is optional.
+    let body: CreateSession = if body.iter().all(u8::is_ascii_whitespace) {
+        CreateSession::default()
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?
+    };
+    let (id, _) = server
+        .create_session(body.agent.as_deref())
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, format!("{e:#}")))?;
+    let body = serde_json::json!({ "session": id });
+    Ok((StatusCode::CREATED, Json(body)).into_response())
+}
+
+async fn get_session(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let records = match transcript::load(&server.sessions_dir, &id) {
+        Ok(records) => records,
+        // Nothing is written until the first message.
+        Err(_) if server.sessions.lock().unwrap().contains_key(&id) => Vec::new(),
+        Err(e) => return Err(ApiError::not_found(e)),
+    };
+    let body = serde_json::json!({ "session": id, "messages": records });
+    Ok(Json(body).into_response())
+}
+
+async fn delete_session(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let open = server.sessions.lock().unwrap().remove(&id);
+    if let Some(cancel) = open.as_ref().and_then(|s| s.running.lock().unwrap().take()) {
+        cancel.cancel();
+    }
+    match transcript::delete(&server.sessions_dir, &id) {
+        Ok(()) => {}
+        Err(_) if open.is_some() => {}
+        Err(e) => return Err(ApiError::not_found(e)),
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[derive(Deserialize)]
+struct SendMessage {
+    message: String,
+    /// Answer with a stream of the turn's events instead of waiting for it.
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct TurnReply {
+    session: String,
+    /// `completed`, `max_iterations`, `cancelled` or `error`.
+    outcome: &'static str,
+    text: Option<String>,
+    error: Option<String>,
+    summary: Option<TurnSummary>,
+}
+
+async fn send_message(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<String>,
+    Json(body): Json<SendMessage>,
+) -> Result<Response, ApiError> {
+    let session = server.session(&id)?;
+    let (events, mut received) = mpsc::unbounded_channel();
+    let turn = server.send(&session, body.message, events)?;
+
+    if body.stream {
+        let events = UnboundedReceiverStream::new(received).map(|event| sse_event(&event));
+        return Ok(sse(events).into_response());
+    }
+    let outcome = turn.await.context("The turn failed")?;
+    let mut summary = None;
+    while let Ok(event) = received.try_recv() {
+        if let AgentEvent::TurnSummary(turn) = event {
+            summary = Some(turn);
+        }
+    }
+    let reply = TurnReply {
+        session: id,
+        outcome: outcome.label(),
+        text: outcome.text().map(str::to_string),
+        error: match &outcome {
+            TurnOutcome::Error(e) => Some(format!("{e:#}")),
+            _ => None,
+        },
+        summary,
+    };
+    Ok(Json(reply).into_response())
+}
+
+async fn session_events(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let session = server.session(&id)?;
+    // Subscribers that fall behind skip what they missed.
+    let events = BroadcastStream::new(session.events.subscribe())
+        .filter_map(|event| event.ok())
+        .map(|event| sse_event(&event));
+    Ok(sse(events).into_response())
+}
+
+async fn cancel_turn(
+    State(server): State<Arc<Server>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let session = server.session(&id)?;
+    if session.cancel() {
+        Ok(StatusCode::ACCEPTED.into_response())
+    } else {
+        Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "No turn is running in this session",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use dinoe_core::agent::{ContextBuilder, ToolRegistry};
+    use dinoe_core::traits::{ChatRequest, ChatResponse, Provider, ProviderEvent};
+    use futures_util::stream::BoxStream;
+    use tower::ServiceExt;
+
+    struct Greeter;
+
+    #[async_trait]
+    impl Provider for Greeter {
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            let last = request.messages.last().unwrap();
+            Ok(ChatResponse {
+                text: Some(format!("hello, {}", last.content)),
+                ..Default::default()
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<BoxStream<'static, ProviderEvent>> {
+            anyhow::bail!("not streaming")
+        }
+    }
+
+    fn server(dir: &std::path::Path, token: Option<&str>) -> Arc<Server> {
+        let mut config = Config::default();
+        config.serve.token = token.map(str::to_string);
+        let agent = AgentLoop::new(
+            Arc::new(Greeter),
+            ContextBuilder::new(dir),
+            Arc::new(ToolRegistry::new()),
+        )
+        .with_streaming(false);
+        Arc::new(Server::new(config, dir.join("sessions"), None).with_default_agent(agent))
+    }
+
+    async fn call(
+        server: &Arc<Server>,
+        method: &str,
+        uri: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(body.map_or(Body::empty(), |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = router(server.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn runs_turns_in_a_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path(), Some("secret"));
+
+        let (status, created) = call(&server, "POST", "/sessions", None).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = created["session"].as_str().unwrap().to_string();
+
+        let message = serde_json::json!({ "message": "world" });
+        let uri = format!("/sessions/{id}/messages");
+        let (status, reply) = call(&server, "POST", &uri, Some(message)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(reply["outcome"], "completed");
+        assert_eq!(reply["text"], "hello, world");
+        assert_eq!(reply["summary"]["iterations"], 1);
+
+        let (_, session) = call(&server, "GET", &format!("/sessions/{id}"), None).await;
+        let roles: Vec<_> = session["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["user", "assistant"]);
+
+        let (status, _) = call(&server, "DELETE", &format!("/sessions/{id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&server, "GET", &format!("/sessions/{id}"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn answers_chat_completions() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path(), Some("secret"));
+        let request = serde_json::json!({
+            "model": "dinoe",
+            "messages": [{ "role": "user", "content": "world" }],
+        });
+        let (status, reply) = call(&server, "POST", "/v1/chat/completions", Some(request)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(reply["choices"][0]["message"]["content"], "hello, world");
+        assert_eq!(reply["choices"][0]["finish_reason"], "stop");
+
+        let request = serde_json::json!({ "model": "nobody", "messages": [] });
+        let (status, _) = call(&server, "POST", "/v1/chat/completions", Some(request)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn counts_requests_and_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path(), Some("secret"));
+        let (_, created) = call(&server, "POST", "/sessions", None).await;
+        let uri = format!(
+            "/sessions/{}/messages",
+            created["session"].as_str().unwrap()
+        );
+        let message = serde_json::json!({ "message": "world" });
+        call(&server, "POST", &uri, Some(message)).await;
+
+        let request = Request::get("/metrics")
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = router(server).oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        for line in [
+            r#"dinoe_http_requests_total{method="POST",route="/sessions",status="201"} 1"#,
+            r#"dinoe_http_requests_total{method="POST",route="/sessions/{id}/messages",status="200"} 1"#,
+            r#"dinoe_turns_total{outcome="completed"} 1"#,
+            r#"dinoe_turn_duration_seconds_bucket{le="+Inf"} 1"#,
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "{line} missing from\n{text}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path(), Some("other"));
+        let (status, body) = call(&server, "GET", "/sessions", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body["error"]["message"].is_string());
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::sse::Event;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use dinoe_core::agent::{AgentEvent, AgentLoop, TurnOutcome, TurnSummary};
+use dinoe_core::memory::WritePolicy;
+use dinoe_core::traits::ChatMessage;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+use super::{ApiError, Server, sse};
+
+/// The model name of the default agent.
+const DEFAULT_MODEL: &str = "dinoe";
+
+/// The OpenAI chat completions API, for OpenAI SDK clients and chat UIs. The model is
+/// an agent profile. Requests are stateless: the client sends the conversation each
+/// time, and neither a transcript nor the messages are saved to memory.
+pub(super) fn routes() -> Router<Arc<Server>> {
+    Router::new()
+        .route("/v1/models", get(list_models))
+        .route("/v1/chat/completions", post(chat_completions))
+}
+
+#[derive(Deserialize)]
+struct CompletionRequest {
+    model: String,
+    messages: Vec<Message>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    role: String,
+    #[serde(default)]
+    content: Content,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Content {
+    Text(String),
+    Parts(Vec<Part>),
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Content::Text(String::new())
+    }
+}
+
+/// A part of a message. Only text is used; images and audio are dropped.
+#[derive(Deserialize)]
+struct Part {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+impl Content {
+    fn into_text(self) -> String {
+        match self {
+            Content::Text(text) => text,
+            Content::Parts(parts) => parts
+                .into_iter()
+                .filter_map(|part| part.text)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+async fn list_models(State(server): State<Arc<Server>>) -> Json<Value> {
+    let models: Vec<Value> = std::iter::once(DEFAULT_MODEL)
+        .chain(server.config().agents.keys().map(String::as_str))
+        .map(|id| json!({ "id": id, "object": "model", "created": 0, "owned_by": "dinoe" }))
+        .collect();
+    Json(json!({ "object": "list", "data": models }))
+}
+
+async fn chat_completions(
+    State(server): State<Arc<Server>>,
+    Json(request): Json<CompletionRequest>,
+) -> Result<Response, ApiError> {
+    let agent = agent_for(&server, &request.model)?;
+    let (message, history) = split_messages(request.messages)?;
+    let completion = Completion::new(request.model);
+
+    if !request.stream {
+        let (events, mut received) = mpsc::unbounded_channel();
+        let outcome = agent
+            .process_with_events(&message, history, server.metrics.sink(events))
+            .await;
+        let mut summary = TurnSummary::default();
+        while let Ok(event) = received.try_recv() {
+            if let AgentEvent::TurnSummary(turn) = event {
+                summary = turn;
+            }
+        }
+        return completion.response(outcome, &summary);
+    }
+
+    let (events, received) = mpsc::unbounded_channel();
+    let cancel = CancellationToken::new();
+    let events = server.metrics.sink(events);
+    tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            agent
+                .process_cancellable(&message, history, events, cancel)
+                .await
+        }
+    });
+    // The turn is cancelled when the client goes away and the stream is dropped.
+    let guard = cancel.drop_guard();
+    let (mut streamed, mut completed) = (false, false);
+    let chunks = std::iter::once(completion.chunk(json!({ "role": "assistant" }), None))
+        .map(|chunk| Ok(Event::default().data(chunk.to_string())));
+    let events = UnboundedReceiverStream::new(received).filter_map(move |event| {
+        let _ = &guard;
+        let chunk = match event {
+            AgentEvent::Token { text } => {
+                streamed = true;
+                completion.chunk(json!({ "content": text }), None)
+            }
+            AgentEvent::TurnComplete { text } => {
+                completed = true;
+                // Without streaming from the provider, the answer comes in one piece.
+                if streamed {
+                    return None;
+                }
+                completion.chunk(json!({ "content": text }), None)
+            }
+            AgentEvent::Error { message } => json!({ "error": { "message": message } }),
+            AgentEvent::TurnSummary(_) if completed => completion.chunk(json!({}), Some("stop")),
+            _ => return None,
+        };
+        Some(Ok(Event::default().data(chunk.to_string())))
+    });
+    let done = std::iter::once(Ok(Event::default().data("[DONE]")));
+    let stream = tokio_stream::iter(chunks)
+        .chain(events)
+        .chain(tokio_stream::iter(done));
+    Ok(sse(stream).into_response())
+}
+
+/// The agent named by `model`, without saving messages to memory, as the client keeps
+/// the conversation.
+fn agent_for(server: &Server, model: &str) -> Result<AgentLoop, ApiError> {
+    let profile = match model {
+        "" | DEFAULT_MODEL => None,
+        name if server.config().agents.contains_key(name) => Some(name),
+        name => {
+            return Err(ApiError::new(
+                StatusCode::NOT_FOUND,
+                format!(
+                    "The model '{name}' does not exist; use '{DEFAULT_MODEL}' or an agent profile"
+                ),
+            ));
+        }
+    };
+    Ok(server.agent(profile)?.with_write_policy(WritePolicy::Off))
+}
+
+/// The last user message and the conversation before it. The agent brings its own
+/// system prompt; the client's system messages are kept in the history.
+fn split_messages(messages: Vec<Message>) -> Result<(String, Vec<ChatMessage>), ApiError> {
+    let mut history: Vec<ChatMessage> = messages
+        .into_iter()
+        .filter(|m| {
+            matches!(
+                m.role.as_str(),
+                "system" | "developer" | "user" | "assistant"
+            )
+        })
+        .map(|m| {
+            let content = m.content.into_text();
+            match m.role.as_str() {
+                "user" => ChatMessage::user(content),
+                "assistant" => ChatMessage::assistant(content),
+                _ => ChatMessage::system(content),
+            }
+        })
+        .collect();
+    match history.pop() {
+        Some(last) if last.role == "user" => Ok((last.content, history)),
+        _ => Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "The last message must be from the user",
+        )),
+    }
+}
+
+/// The identity shared by all chunks of one completion.
+struct Completion {
+    id: String,
+    model: String,
+    created: i64,
+}
+
+impl Completion {
+    fn new(model: String) -> Self {
+        Self {
+            id: format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+            model,
+            created: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    fn chunk(&self, delta: Value, finish_reason: Option<&str>) -> Value {
+        json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        })
+    }
+
+    fn response(self, outcome: TurnOutcome, summary: &TurnSummary) -> Result<Response, ApiError> {
+        let finish_reason = match &outcome {
+            TurnOutcome::Completed { .. } => "stop",
+            TurnOutcome::MaxIterations { .. } => "length",
+            TurnOutcome::Cancelled => {
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Turn cancelled",
+                ));
+            }
+            TurnOutcome::Error(e) => {
+                return Err(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("{e:#}"),
+                ));
+            }
+        };
+        let body = json!({
+            "id": self.id,
+            "object": "chat.completion",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": outcome.text().unwrap_or_default() },
+                "finish_reason": finish_reason,
+            }],
+            "usage": {
+                "prompt_tokens": summary.input_tokens,
+                "completion_tokens": summary.output_tokens,
+                "total_tokens": summary.input_tokens + summary.output_tokens,
+            },
+        });
+        Ok(Json(body).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_conversation() {
+        let messages: Vec<Message> = serde_json::from_value(json!([
+            { "role": "system", "content": "Be brief" },
+            { "role": "user", "content": "Hi" },
+            { "role": "assistant", "content": "Hello" },
+            { "role": "user", "content": [
+                { "type": "text", "text": "What is" },
+                { "type": "image_url", "image_url": { "url": "data:" } },
+                { "type": "text", "text": "this?" },
+            ] },
+        ]))
+        .unwrap();
+        let (message, history) = split_messages(messages).unwrap();
+        assert_eq!(message, "What is\nthis?");
+        let roles: Vec<_> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+
+        let messages = serde_json::from_value(json!([{ "role": "assistant", "content": "Hi" }]));
+        assert!(split_messages(messages.unwrap()).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use dinoe_core::agent::{AgentLoop, EventSink, TurnOutcome};
+use dinoe_core::config::WebhookConfig;
+use ring::hmac;
+use serde_json::{Value, json};
+
+use super::{ApiError, Server};
+
+/// Characters of `{{payload}}` put into the prompt.
+const MAX_PAYLOAD_CHARS: usize = 50_000;
+
+/// Webhooks run their prompt in the background and answer right away, since senders
+/// like GitHub give up after a few seconds. They check their own secret instead of
+/// `serve.token`, which senders cannot be configured to send.
+pub(super) fn routes() -> Router<Arc<Server>> {
+    Router::new().route("/hooks/{name}", post(receive))
+}
+
+async fn receive(
+    State(server): State<Arc<Server>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let config = server.config();
+    let Some(hook) = config.webhooks.iter().find(|h| h.name == name) else {
+        return Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("No webhook '{name}'"),
+        ));
+    };
+    if let Some(secret) = &hook.secret
+        && !authorized(secret, &headers, &body)
+    {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Missing or wrong webhook signature",
+        ));
+    }
+    let event = header(&headers, "x-github-event").unwrap_or_default();
+    if event == "ping" || !(hook.events.is_empty() || hook.events.iter().any(|e| e == event)) {
+        return Ok(Json(json!({ "status": "ignored" })).into_response());
+    }
+
+    let payload = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+    let prompt = render(&hook.prompt, event, &payload);
+    let agent = server.agent(hook.agent.as_deref())?;
+    let agent = crate::with_session(agent, &config, None)?;
+    let run = uuid::Uuid::new_v4().simple().to_string();
+    let events = server.metrics.sink(EventSink::none());
+    tokio::spawn(run_hook(hook.clone(), agent, prompt, events, run.clone()));
+    Ok((StatusCode::ACCEPTED, Json(json!({ "run": run }))).into_response())
+}
+
+async fn run_hook(
+    hook: WebhookConfig,
+    agent: AgentLoop,
+    prompt: String,
+    events: EventSink,
+    run: String,
+) {
+    eprintln!("▶ Webhook {} started run {run}", hook.name);
+    let outcome = agent.process_with_events(&prompt, vec![], events).await;
+    let text = match &outcome {
+        TurnOutcome::Error(e) => format!("Error: {e:#}"),
+        outcome => outcome.text().unwrap_or_default().to_string(),
+    };
+    eprintln!(
+        "■ Webhook {} run {run} ended: {}\n{text}\n",
+        hook.name,
+        outcome.label()
+    );
+    let Some(url) = &hook.post_to else {
+        return;
+    };
+    let body = json!({
+        "webhook": hook.name,
+        "run": run,
+        "outcome": outcome.label(),
+        "text": text,
+    });
+    let posted = reqwest::Client::new().post(url).json(&body).send().await;
+    if let Err(e) = posted.and_then(|response| response.error_for_status()) {
+        eprintln!(
+            "⚠ Could not post the result of webhook {}: {}",
+            hook.name, e
+        );
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// A GitHub signature of the body made with `secret`, or `secret` as a bearer token.
+fn authorized(secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    if let Some(signature) = header(headers, "x-hub-signature-256") {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        return signature
+            .strip_prefix("sha256=")
+            .and_then(decode_hex)
+            .is_some_and(|tag| hmac::verify(&key, body, &tag).is_ok());
+    }
+    header(headers, "authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| same(token.as_bytes(), secret.as_bytes()))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Compares in constant time, so the secret cannot be guessed from response times.
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Fills in the placeholders of a webhook prompt. Unknown ones are left as they are.
+fn render(template: &str, event: &str, payload: &Value) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + len + 2];
+        let key = placeholder[2..placeholder.len() - 2].trim();
+        let value = match key {
+            "event" => Some(event.to_string()),
+            "payload" => Some(shorten(
+                serde_json::to_string_pretty(payload).unwrap_or_default(),
+            )),
+            _ => key
+                .strip_prefix("payload.")
+                .and_then(|path| field(payload, path))
+                .map(|value| match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                }),
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(value.as_deref().unwrap_or(placeholder));
+        rest = &rest[start + placeholder.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The value at a dotted path such as `pull_request.head.ref` or `commits.0.message`.
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| match value {
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        value => value.get(key),
+    })
+}
+
+fn shorten(text: String) -> String {
+    match text.char_indices().nth(MAX_PAYLOAD_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n[payload cut at {MAX_PAYLOAD_CHARS} characters]",
+            &text[..end]
+        ),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_the_prompt() {
+        let payload = json!({
+            "action": "opened",
+            "pull_request": { "number": 7, "title": "Fix it" },
+            "commits": [{ "message": "first" }],
+        });
+        let prompt = render(
+            "{{event}} {{ payload.action }} #{{payload.pull_request.number}}: \
+             {{payload.pull_request.title}} ({{payload.commits.0.message}}) {{payload.nope}}",
+            "pull_request",
+            &payload,
+        );
+        assert_eq!(
+            prompt,
+            "pull_request opened #7: Fix it (first) {{payload.nope}}"
+        );
+    }
+
+    #[test]
+    fn checks_github_signatures() {
+        // RFC 4231, test case 2.
+        let hex = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-hub-signature-256",
+            format!("sha256={hex}").parse().unwrap(),
+        );
+        assert!(authorized(
+            "Jefe",
+            &headers,
+            b"what do ya want for nothing?"
+        ));
+        assert!(!authorized("Jefe", &headers, b"something else"));
+        headers.insert("x-hub-signature-256", "sha256=5bdc".parse().unwrap());
+        assert!(!authorized(
+            "Jefe",
+            &headers,
+            b"what do ya want for nothing?"
+        ));
+        headers.clear();
+        headers.insert("authorization", "Bearer Jefe".parse().unwrap());
+        assert!(authorized("Jefe", &headers, b""));
+    }
+}
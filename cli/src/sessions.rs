@@ -0,0 +1,279 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Local};
+use console::style;
+use dinoe_core::agent::AgentEvent;
+use dinoe_core::transcript::{self, TranscriptRecord};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::render::{self, EventPrinter};
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum SessionsCommands {
+    /// List chat sessions, most recent first
+    List,
+    /// Print the conversation of a session
+    Show { id: String },
+    /// Play a session back turn by turn, with its tool calls and results
+    Replay {
+        id: String,
+        /// How much faster than it happened, such as 2 or 0.5; 0 shows it all at once
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Longest pause between steps, in seconds
+        #[arg(long, default_value_t = 3.0, value_name = "SECS")]
+        max_pause: f64,
+        /// Wait for Enter before each turn instead
+        #[arg(long)]
+        step: bool,
+    },
+    /// Delete a session transcript
+    Delete { id: String },
+    /// Write a session as markdown or JSON
+    Export {
+        id: String,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+        /// File to write (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Md,
+    Json,
+}
+
+pub fn handle_command(command: SessionsCommands) -> Result<()> {
+    let dir = transcript::sessions_dir();
+    match command {
+        SessionsCommands::List => {
+            let sessions = transcript::list(&dir)?;
+            if sessions.is_empty() {
+                println!("{} No sessions in {}", style("!").yellow(), dir.display());
+            }
+            for session in sessions {
+                let updated = session
+                    .updated
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                println!(
+                    "{}  {}  {:>4} messages  {}",
+                    style(&session.session).bold(),
+                    updated,
+                    session.messages,
+                    style(&session.title).dim()
+                );
+            }
+        }
+        SessionsCommands::Show { id } => {
+            let records = transcript::load(&dir, &id)?;
+            if let Some(meta) = transcript::read_meta(&dir, &id) {
+                println!("{}\n{}\n", style(&meta.title).bold(), style(&meta.summary).dim());
+            }
+            crate::markdown::print_markdown(&transcript::to_markdown(&id, &records));
+        }
+        SessionsCommands::Replay {
+            id,
+            speed,
+            max_pause,
+            step,
+        } => {
+            if !(speed >= 0.0 && max_pause >= 0.0) {
+                bail!("--speed and --max-pause cannot be negative");
+            }
+            let records = transcript::load(&dir, &id)?;
+            if let Some(meta) = transcript::read_meta(&dir, &id) {
+                println!("{}\n{}\n", style(&meta.title).bold(), style(&meta.summary).dim());
+            }
+            let pause = |from: DateTime<Local>, to: DateTime<Local>| {
+                let waited = (to - from).to_std().unwrap_or_default().as_secs_f64();
+                if speed == 0.0 {
+                    Duration::ZERO
+                } else {
+                    Duration::from_secs_f64((waited / speed).min(max_pause))
+                }
+            };
+            replay(&records, pause, step)?;
+        }
+        SessionsCommands::Delete { id } => {
+            transcript::delete(&dir, &id)?;
+            println!("{} Deleted session {}", style("✓").green(), id);
+        }
+        SessionsCommands::Export { id, format, output } => {
+            let records = transcript::load(&dir, &id)?;
+            let text = match format {
+                ExportFormat::Md => transcript::to_markdown(&id, &records),
+                ExportFormat::Json => serde_json::to_string_pretty(&records)?,
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)?;
+                    println!(
+                        "{} Exported {} messages to {}",
+                        style("✓").green(),
+                        records.len(),
+                        path.display()
+                    );
+                }
+                None => println!("{}", text),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What replaying a session shows, in order.
+#[derive(Debug, PartialEq)]
+enum Frame {
+    /// A message from the user, which starts a turn.
+    Turn {
+        number: usize,
+        text: String,
+    },
+    /// A tool call, result or note, shown as it was during the turn.
+    Event(AgentEvent),
+    Answer(String),
+}
+
+/// The frames of a session and when each happened. The arguments of a tool call show
+/// as its progress, and a result counts as failed when its `success` is false.
+fn frames(records: &[TranscriptRecord]) -> Vec<(DateTime<Local>, Frame)> {
+    let mut frames = Vec::new();
+    let mut tools = HashMap::new();
+    let mut turns = 0;
+    for record in records {
+        let message = &record.message;
+        let mut push = |frame| frames.push((record.timestamp, frame));
+        match message.role.as_str() {
+            "user" => {
+                turns += 1;
+                push(Frame::Turn {
+                    number: turns,
+                    text: message.content.clone(),
+                });
+            }
+            "assistant" if message.tool_calls.as_ref().is_some_and(|c| !c.is_empty()) => {
+                if !message.content.trim().is_empty() {
+                    push(Frame::Event(AgentEvent::status(message.content.trim())));
+                }
+                for call in message.tool_calls.iter().flatten() {
+                    tools.insert(call.id.clone(), call.name.clone());
+                    push(Frame::Event(AgentEvent::tool_started(&call.name)));
+                    push(Frame::Event(AgentEvent::ToolProgress {
+                        name: call.name.clone(),
+                        message: call.arguments.clone(),
+                    }));
+                }
+            }
+            "assistant" => push(Frame::Answer(message.content.clone())),
+            "tool" => {
+                let id = message.tool_call_id.as_deref().unwrap_or_default();
+                let name = tools.get(id).cloned().unwrap_or_else(|| "tool".to_string());
+                let success = serde_json::from_str::<serde_json::Value>(&message.content)
+                    .ok()
+                    .and_then(|result| result["success"].as_bool())
+                    .unwrap_or(true);
+                push(Frame::Event(AgentEvent::tool_finished(
+                    &name,
+                    success,
+                    &message.content,
+                )));
+            }
+            transcript::THINKING_ROLE => {
+                let thought = format!("Thinking: {}", message.content.trim());
+                push(Frame::Event(AgentEvent::status(thought)));
+            }
+            _ => {}
+        }
+    }
+    frames
+}
+
+/// Shows `records` as the chat did, waiting `pause(previous, next)` between frames, or
+/// for Enter before each turn when `step` is set.
+fn replay(
+    records: &[TranscriptRecord],
+    pause: impl Fn(DateTime<Local>, DateTime<Local>) -> Duration,
+    step: bool,
+) -> Result<()> {
+    let printer = EventPrinter::new();
+    let mut previous = None;
+    for (time, frame) in frames(records) {
+        match previous {
+            Some(_) if step && matches!(frame, Frame::Turn { .. }) => {
+                eprint!("{}", style("Press Enter for the next turn").dim());
+                std::io::stdin().read_line(&mut String::new())?;
+            }
+            Some(previous) if !step => std::thread::sleep(pause(previous, time)),
+            _ => {}
+        }
+        previous = Some(time);
+        match frame {
+            Frame::Turn { number, text } => {
+                let heading = format!("Turn {} · {}", number, time.format("%Y-%m-%d %H:%M:%S"));
+                println!("\n{}", style(heading).dim());
+                println!("{} {}\n", style("❯").cyan().bold(), text.trim());
+            }
+            Frame::Event(event) => printer.print(&event),
+            Frame::Answer(text) => {
+                render::print_separator();
+                crate::markdown::print_markdown(&text);
+            }
+        }
+    }
+    println!("\n{}", style("End of session").dim());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dinoe_core::traits::{ChatMessage, ToolCall};
+
+    #[test]
+    fn turns_records_into_frames() {
+        let call = ToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: r#"{"command":"ls"}"#.into(),
+        };
+        let messages = [
+            ChatMessage::user("List the files"),
+            ChatMessage::assistant_with_tool_calls("", vec![call]),
+            ChatMessage::tool_result("call_1".into(), r#"{"success":false}"#),
+            ChatMessage::assistant("The listing failed"),
+        ];
+        let records: Vec<TranscriptRecord> = messages
+            .into_iter()
+            .map(|message| TranscriptRecord {
+                timestamp: Local::now(),
+                message,
+            })
+            .collect();
+        let frames: Vec<Frame> = frames(&records).into_iter().map(|(_, f)| f).collect();
+        assert_eq!(
+            frames,
+            [
+                Frame::Turn {
+                    number: 1,
+                    text: "List the files".into()
+                },
+                Frame::Event(AgentEvent::tool_started("shell")),
+                Frame::Event(AgentEvent::ToolProgress {
+                    name: "shell".into(),
+                    message: r#"{"command":"ls"}"#.into()
+                }),
+                Frame::Event(AgentEvent::tool_finished(
+                    "shell",
+                    false,
+                    r#"{"success":false}"#
+                )),
+                Frame::Answer("The listing failed".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use anyhow::{Context, Result, bail};
+use console::style;
+use dinoe_core::config::{self, Config};
+use dinoe_core::guardrails::RuleGuardrail;
+use dinoe_core::providers;
+use dinoe_core::redact::Redactor;
+use dinoe_core::scheduler::Heartbeat;
+
+use crate::GlobalArgs;
+
+/// Parts of setting names whose string values are hidden by `config show`.
+const SECRET_KEYS: &[&str] = &["key", "token", "secret", "password", "authorization"];
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum ConfigCommands {
+    /// Print the settings in effect, with secrets hidden
+    Show,
+    /// Change one setting, e.g. `memory.backend qdrant`
+    Set {
+        /// Setting name, with dots for nested tables
+        key: String,
+        /// A TOML value; text that is not one is taken as a string
+        value: String,
+    },
+    /// Open config.toml in $VISUAL or $EDITOR, then check it
+    Edit,
+    /// Check config.toml for mistakes
+    Validate,
+    /// Move ~/.dinoe to the platform's config and data directories
+    Migrate,
+    /// Let the project's .dinoe/config.toml change any setting, not only the model,
+    /// agents and prompts
+    Trust {
+        /// A directory in the project (default: the current one)
+        dir: Option<std::path::PathBuf>,
+    },
+}
+
+pub fn handle_command(command: ConfigCommands, global: &GlobalArgs) -> Result<()> {
+    match command {
+        ConfigCommands::Show => {
+            let config = global.load_config()?;
+            let mut table = toml::Table::try_from(&config)?;
+            hide_secrets(&mut table);
+            print!("{}", toml::to_string_pretty(&table)?);
+            Ok(())
+        }
+        ConfigCommands::Set { key, value } => set(&key, &value),
+        ConfigCommands::Edit => {
+            let path = config::get_config_path();
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_string());
+            // Through the shell, so an editor with arguments like `code -w` works.
+            let status = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{editor} \"$1\""))
+                .arg("sh")
+                .arg(&path)
+                .status()
+                .with_context(|| format!("Failed to start {editor}"))?;
+            if !status.success() {
+                bail!("{editor} exited with {status}");
+            }
+            validate()
+        }
+        ConfigCommands::Validate => validate(),
+        ConfigCommands::Trust { dir } => trust(dir),
+        ConfigCommands::Migrate => {
+            for (from, to) in config::migrate_legacy_dir()? {
+                println!("{} → {}", from.display(), to.display());
+            }
+            println!(
+                "{} dinoe now uses {} and {}",
+                style("✓").green(),
+                config::get_config_dir().display(),
+                config::get_data_dir().display()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Adds the project around `dir` to `trusted_projects` in the user's config.
+fn trust(dir: Option<std::path::PathBuf>) -> Result<()> {
+    let start = match dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let start = start
+        .canonicalize()
+        .with_context(|| format!("Cannot find {}", start.display()))?;
+    let Some(project_dir) = config::find_project_dir(&start) else {
+        bail!("No .dinoe/ directory at or above {}", start.display());
+    };
+    let root = project_dir.parent().unwrap_or(&project_dir).to_path_buf();
+    let text = std::fs::read_to_string(config::get_config_path()).unwrap_or_default();
+    let current: Config = toml::from_str(&text).unwrap_or_default();
+    if current.trusts(&project_dir) {
+        println!(
+            "{} {} is trusted already",
+            style("✓").green(),
+            root.display()
+        );
+        return Ok(());
+    }
+    let mut projects = toml_edit::Array::new();
+    for dir in current.trusted_projects.iter().chain([&root]) {
+        projects.push(dir.display().to_string());
+    }
+    update(&[("trusted_projects", Some(projects.into()))])?;
+    println!(
+        "{} Trusted {}: its config.toml may now change any setting",
+        style("✓").green(),
+        root.display()
+    );
+    Ok(())
+}
+
+fn set(key: &str, value: &str) -> Result<()> {
+    let value = value
+        .parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| value.into());
+    let shown = value.to_string();
+    update(&[(key, Some(value))])?;
+    println!("{} Set {} = {}", style("✓").green(), key, shown.trim());
+    Ok(())
+}
+
+/// Sets settings in config.toml, or removes those without a value, and checks the
+/// result before writing it.
+pub fn update(changes: &[(&str, Option<toml_edit::Value>)]) -> Result<()> {
+    let path = config::get_config_path();
+    let text = std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "Failed to read {}. Run 'dinoe onboard' first.",
+            path.display()
+        )
+    })?;
+    // toml_edit keeps the comments and layout of the file.
+    let mut document: toml_edit::DocumentMut = text.parse()?;
+    for (key, value) in changes {
+        let parts: Vec<&str> = key.split('.').collect();
+        let (name, tables) = parts.split_last().context("No setting name given")?;
+        let mut item = document.as_item_mut();
+        for table in tables {
+            item = &mut item[table];
+            if !item.is_none() && !item.is_table_like() {
+                bail!("'{table}' in '{key}' is not a table");
+            }
+        }
+        match value {
+            Some(value) => item[name] = toml_edit::Item::Value(value.clone()),
+            None => {
+                if let Some(table) = item.as_table_like_mut() {
+                    table.remove(name);
+                }
+            }
+        }
+    }
+
+    let text = document.to_string();
+    let keys: Vec<&str> = changes.iter().map(|(key, _)| *key).collect();
+    let updated: Config =
+        toml::from_str(&text).with_context(|| format!("Cannot set {}", keys.join(", ")))?;
+    // Other unknown settings already in the file are left to `config validate`.
+    let unknown = unknown_keys(&toml::from_str(&text)?, &updated)?;
+    if let Some(unknown) = unknown.iter().find(|u| {
+        keys.iter()
+            .any(|key| *key == u.as_str() || key.starts_with(&format!("{u}.")))
+    }) {
+        bail!("Unknown setting '{unknown}'");
+    }
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Checks the global config and the project's, reporting every problem found.
+pub(crate) fn validate() -> Result<()> {
+    let mut files = vec![config::get_config_path()];
+    if let Some(project_dir) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| config::find_project_dir(&dir))
+    {
+        files.push(project_dir.join("config.toml"));
+    }
+    let config = config::load_config()?;
+
+    let mut problems = Vec::new();
+    for file in files.iter().filter(|file| file.exists()) {
+        let table: toml::Table = toml::from_str(&std::fs::read_to_string(file)?)?;
+        for key in unknown_keys(&table, &config)? {
+            problems.push(format!("{}: unknown setting '{}'", file.display(), key));
+        }
+    }
+    if let Err(e) = providers::create_provider(&config) {
+        problems.push(format!("provider: {e:#}"));
+    }
+    if let Err(e) = Redactor::from_config(&config) {
+        problems.push(format!("redaction: {e:#}"));
+    }
+    if let Err(e) = RuleGuardrail::from_config(&config.guardrails, &config.workspace_dir) {
+        problems.push(format!("guardrails: {e:#}"));
+    }
+    if let Err(e) = Heartbeat::from_config(&config.heartbeat, &config.workspace_dir) {
+        problems.push(format!("heartbeat: {e:#}"));
+    }
+    for agent in config.agents.keys() {
+        if let Err(e) = config.clone().with_agent(agent) {
+            problems.push(format!("agents.{agent}: {e:#}"));
+        }
+    }
+    if let Err(e) = crate::eval::prompt_variants(&config) {
+        problems.push(format!("prompt_variants: {e:#}"));
+    }
+
+    if problems.is_empty() {
+        let paths: Vec<String> = files
+            .iter()
+            .filter(|file| file.exists())
+            .map(|file| file.display().to_string())
+            .collect();
+        if paths.is_empty() {
+            println!(
+                "{} The settings from the environment are valid",
+                style("✓").green()
+            );
+        } else {
+            println!("{} {} is valid", style("✓").green(), paths.join(" and "));
+        }
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{} {}", style("✗").red(), problem);
+    }
+    bail!("Found {} problem(s) in the config", problems.len())
+}
+
+/// Keys in `file` that `Config` does not have, as dotted paths.
+fn unknown_keys(file: &toml::Table, config: &Config) -> Result<Vec<String>> {
+    let mut known = toml::Table::try_from(config)?;
+    // Skipped when serializing, since it is the default.
+    known.insert("workspace_dir".into(), toml::Value::String(String::new()));
+    let mut unknown = Vec::new();
+    collect_unknown(file, &known, "", &mut unknown);
+    Ok(unknown)
+}
+
+fn collect_unknown(file: &toml::Table, known: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in file {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (value, known.get(key)) {
+            (_, None) => out.push(path),
+            (toml::Value::Table(table), Some(toml::Value::Table(known))) => {
+                collect_unknown(table, known, &path, out)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn hide_secrets(table: &mut toml::Table) {
+    for (key, value) in table.iter_mut() {
+        let key = key.to_lowercase();
+        match value {
+            toml::Value::String(text)
+                if !text.is_empty() && SECRET_KEYS.iter().any(|part| key.contains(part)) =>
+            {
+                *text = "[REDACTED]".to_string();
+            }
+            toml::Value::Table(table) => hide_secrets(table),
+            toml::Value::Array(items) => {
+                for item in items {
+                    if let toml::Value::Table(table) = item {
+                        hide_secrets(table);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_unknown_settings() {
+        let file: toml::Table = toml::from_str(
+            "model = \"gpt-4o\"\nmodle = \"typo\"\n[memory]\nbackend = \"markdown\"\nbackedn = 1\n",
+        )
+        .unwrap();
+        let config: Config = file.clone().try_into().unwrap();
+        assert_eq!(
+            unknown_keys(&file, &config).unwrap(),
+            ["memory.backedn", "modle"]
+        );
+    }
+
+    #[test]
+    fn hides_secrets() {
+        let mut table: toml::Table = toml::from_str(
+            "api_key = \"sk-1\"\nmax_history = 5\n[serve]\ntoken = \"t\"\n\
+             [[webhooks]]\nname = \"ci\"\nsecret = \"s\"\n",
+        )
+        .unwrap();
+        hide_secrets(&mut table);
+        assert_eq!(table["api_key"].as_str(), Some("[REDACTED]"));
+        assert_eq!(table["max_history"].as_integer(), Some(5));
+        assert_eq!(table["serve"]["token"].as_str(), Some("[REDACTED]"));
+        assert_eq!(table["webhooks"][0]["secret"].as_str(), Some("[REDACTED]"));
+        assert_eq!(table["webhooks"][0]["name"].as_str(), Some("ci"));
+    }
+}
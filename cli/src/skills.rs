@@ -1,33 +1,65 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use console::style;
+use dinoe_core::config::{self, Config};
 use dinoe_core::skills;
 use std::path::Path;
 
-pub fn handle_command(command: SkillsCommands, workspace_dir: &Path) -> Result<()> {
+use crate::templates::{
+    SCRIPT_NODE, SCRIPT_PYTHON, SCRIPT_SHELL, SKILL_TEMPLATE, SKILL_TOOL_TEMPLATE,
+};
+
+pub fn handle_command(command: SkillsCommands, config: &Config) -> Result<()> {
+    let workspace_dir = &config.workspace_dir;
     match command {
-        SkillsCommands::List => list_skills(workspace_dir),
-        SkillsCommands::Install { source } => install_skill(source, workspace_dir),
+        SkillsCommands::List => list_skills(config),
+        SkillsCommands::Install { source, trust } => install_skill(source, trust, workspace_dir),
         SkillsCommands::Remove { name } => remove_skill(name, workspace_dir),
+        SkillsCommands::Validate { name } => validate_skill(&name, workspace_dir),
+        SkillsCommands::Create {
+            name,
+            description,
+            script,
+        } => {
+            let dir = create_skill(
+                &name,
+                description.as_deref(),
+                script,
+                &skills::skills_dir(workspace_dir),
+            )?;
+            println!(
+                "{} Created {}",
+                style("✓").green().bold(),
+                dir.join("SKILL.md").display()
+            );
+            println!("Fill in SKILL.md, then check it with `dinoe skills validate {name}`");
+            Ok(())
+        }
+        SkillsCommands::Package { name, output } => package_skill(&name, &output, workspace_dir),
+        SkillsCommands::Enable { name } => set_enabled(&name, true, config),
+        SkillsCommands::Disable { name } => set_enabled(&name, false, config),
     }
 }
 
-fn list_skills(workspace_dir: &Path) -> Result<()> {
-    let skills_dir = skills::skills_dir(workspace_dir);
-
-    if !skills_dir.exists() {
-        println!("{} No skills directory found", style("!").yellow());
-        println!();
-        print_create_skill_help(&skills_dir);
-        return Ok(());
-    }
-
-    let entries = std::fs::read_dir(&skills_dir)?;
-    let skills: Vec<_> = entries
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().is_dir())
+fn list_skills(config: &Config) -> Result<()> {
+    let skills_dir = skills::skills_dir(&config.workspace_dir);
+    let dirs = skills::skill_dirs(config);
+    let installed: Vec<(usize, skills::Skill)> = dirs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, dir)| {
+            installed_skills(dir)
+                .into_iter()
+                .map(move |skill| (i, skill))
+        })
         .collect();
 
-    if skills.is_empty() {
+    if installed.is_empty() {
+        if !dirs.iter().any(|dir| dir.exists()) {
+            println!("{} No skills directory found", style("!").yellow());
+            println!();
+            print_create_skill_help(&skills_dir);
+            return Ok(());
+        }
         println!("{} No skills installed", style("!").yellow());
         println!();
         println!("Install a skill:");
@@ -41,41 +73,102 @@ fn list_skills(workspace_dir: &Path) -> Result<()> {
     println!(
         "{} Installed skills ({})",
         style("✓").green().bold(),
-        skills.len()
+        installed.len()
     );
     println!();
 
-    for entry in skills {
-        let skill_dir = entry.path();
-        if let Ok(skill) = skills::load_skill(&skill_dir) {
+    let registry = skills::SkillRegistry::load_from_config(config)?;
+    for (i, skill) in &installed {
+        // Directories later in the list win.
+        let status = if installed
+            .iter()
+            .any(|(j, other)| j > i && other.name == skill.name)
+        {
+            format!(" {}", style("(replaced)").yellow())
+        } else if !registry.is_enabled(&skill.name) {
+            format!(" {}", style("(disabled)").yellow())
+        } else {
+            String::new()
+        };
+        println!(
+            "  {} {}{} — {}",
+            style(&skill.name).white().bold(),
+            style(format!("v{}", skill.version)).dim(),
+            status,
+            skill.description
+        );
+
+        if dirs[*i] != skills_dir {
+            println!("    From: {}", dirs[*i].display());
+        }
+
+        if !skill.tags.is_empty() {
+            println!("    Tags:  {}", skill.tags.join(", "));
+        }
+
+        if let Some(author) = &skill.author {
+            println!("    Author: {}", author);
+        }
+
+        if let Some(license) = &skill.license {
+            println!("    License: {}", license);
+        }
+
+        let requires: Vec<&str> = skill
+            .requires
+            .tools
+            .iter()
+            .chain(&skill.requires.binaries)
+            .map(String::as_str)
+            .collect();
+        if !requires.is_empty() {
+            println!("    Requires: {}", requires.join(", "));
+        }
+
+        if let Some(reason) = skill.unavailable(&config.model) {
             println!(
-                "  {} {} — {}",
-                style(&skill.name).white().bold(),
-                style(format!("v{}", skill.version)).dim(),
-                skill.description
+                "    {} Not offered to the agent: {}",
+                style("!").yellow(),
+                reason
             );
+        }
 
-            if !skill.tags.is_empty() {
-                println!("    Tags:  {}", skill.tags.join(", "));
-            }
-
-            if let Some(author) = &skill.author {
-                println!("    Author: {}", author);
+        if !skill.tools.is_empty() {
+            let names: Vec<&str> = skill.tools.iter().map(|t| t.name.as_str()).collect();
+            println!("    Tools: {}", names.join(", "));
+            let asks = skill.permissions.summary();
+            if !asks.is_empty() {
+                println!("    Permissions: {}", asks.join(", "));
             }
-
-            println!();
         }
+
+        println!();
     }
 
     Ok(())
 }
 
-fn install_skill(source: String, workspace_dir: &Path) -> Result<()> {
+/// The skills in the subdirectories of `dir` that load.
+pub(crate) fn installed_skills(dir: &Path) -> Vec<skills::Skill> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| skills::load_skill(&entry.path()).ok())
+        .collect()
+}
+
+fn install_skill(source: String, trust: bool, workspace_dir: &Path) -> Result<()> {
     println!("{} Installing from: {}", style("→").cyan(), source);
 
     let skills_path = skills::skills_dir(workspace_dir);
     std::fs::create_dir_all(&skills_path)?;
 
+    if Path::new(&source).is_file() && (source.ends_with(".tar.gz") || source.ends_with(".tgz")) {
+        return install_package(Path::new(&source), trust, &skills_path);
+    }
+
     if source.starts_with("https://") || source.starts_with("http://") {
         let output = std::process::Command::new("git")
             .args(["clone", "--depth", "1", &source])
@@ -91,6 +184,8 @@ fn install_skill(source: String, workspace_dir: &Path) -> Result<()> {
             "{} Skill installed successfully!",
             style("✓").green().bold()
         );
+        let name = source.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        print_permissions(&skills_path.join(name.trim_end_matches(".git")));
     } else {
         let src = std::path::PathBuf::from(&source);
         if !src.exists() {
@@ -110,11 +205,112 @@ fn install_skill(source: String, workspace_dir: &Path) -> Result<()> {
             style("✓").green().bold(),
             dest.display()
         );
+        print_permissions(&dest);
+    }
+
+    Ok(())
+}
+
+/// Says what the tools of the skill in `dir` may reach beyond the workspace, so an
+/// install does not grant it unnoticed.
+pub(crate) fn print_permissions(dir: &Path) {
+    let Ok(skill) = skills::load_skill(dir) else {
+        return;
+    };
+    let asks = skill.permissions.summary();
+    if !skill.tools.is_empty() && !asks.is_empty() {
+        println!(
+            "{} Its tools may use: {}",
+            style("!").yellow(),
+            asks.join(", ")
+        );
     }
+}
+
+/// Installs a package made by `skills package` once its signature and checksums check
+/// out and its signer is trusted, or `trust` says to trust them from now on.
+fn install_package(archive: &Path, trust: bool, skills_path: &Path) -> Result<()> {
+    let staging = skills_path.join(format!(".install-{}", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let manifest = skills::unpack_package(archive, &staging)?;
+        if !is_skill_name(&manifest.name) {
+            anyhow::bail!("Invalid skill name in the package: {}", manifest.name);
+        }
+        if skills_path.join(&manifest.name).exists() {
+            anyhow::bail!(
+                "'{}' is already installed; remove it first to replace it",
+                manifest.name
+            );
+        }
+        let mut trusted = trusted_keys()?;
+        if !trusted.contains(&manifest.public_key) {
+            if !trust {
+                anyhow::bail!(
+                    "The package is signed with a key you have not trusted yet:\n  {}\n\
+                     If that is the author's key, run again with --trust",
+                    manifest.public_key
+                );
+            }
+            trusted.push(manifest.public_key.clone());
+            let path = trusted_keys_path();
+            std::fs::write(&path, trusted.join("\n") + "\n")
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        let dest = skills_path.join(&manifest.name);
+        std::fs::rename(staging.join("skill"), &dest)?;
+        Ok((manifest, dest))
+    })();
+    let _ = std::fs::remove_dir_all(&staging);
+    let (manifest, dest) = result?;
+    println!(
+        "{} Installed {} v{} to {}, signature and checksums verified",
+        style("✓").green().bold(),
+        manifest.name,
+        manifest.version,
+        dest.display()
+    );
+    print_permissions(&dest);
+    Ok(())
+}
 
+/// Writes a signed package of an installed skill, or of a skill directory.
+fn package_skill(name: &str, output: &Path, workspace_dir: &Path) -> Result<()> {
+    let dir = find_skill_dir(name, workspace_dir)?;
+    let key = skills::signing_key(&config::get_config_dir().join("skill_signing.key"))?;
+    let path = skills::package_skill(&dir, output, &key)?;
+    println!("{} Packaged {}", style("✓").green().bold(), path.display());
+    println!("Signed with the key {}", skills::package::public_key(&key));
+    println!("Others install it with `dinoe skills install <file>` and trust that key once.");
     Ok(())
 }
 
+fn trusted_keys_path() -> std::path::PathBuf {
+    config::get_config_dir().join("trusted_skill_keys")
+}
+
+/// Public keys of package signers, one per line.
+fn trusted_keys() -> Result<Vec<String>> {
+    let path = trusted_keys_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+fn is_skill_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !name.starts_with('.')
+}
+
 fn remove_skill(name: String, workspace_dir: &Path) -> Result<()> {
     if name.contains("..") || name.contains('/') || name.contains('\\') {
         anyhow::bail!("Invalid skill name: {}", name);
@@ -136,13 +332,163 @@ fn remove_skill(name: String, workspace_dir: &Path) -> Result<()> {
         anyhow::bail!("Skill not found: {}", name);
     }
 
+    let skill = skills::load_skill(&skill_path).ok();
     std::fs::remove_dir_all(&skill_path)?;
+    // Forget that it was disabled, so a later install of it starts out enabled.
+    if let Some(skill) = skill {
+        let mut state = skills::SkillState::load(workspace_dir)?;
+        if state.set_enabled(&skill.name, true) {
+            state.save(workspace_dir)?;
+        }
+    }
     println!("{} Skill '{}' removed", style("✓").green().bold(), name);
 
     Ok(())
 }
 
-fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<()> {
+/// Turns an installed skill on or off, found by its name or its directory.
+fn set_enabled(name: &str, enabled: bool, config: &Config) -> Result<()> {
+    let workspace_dir = &config.workspace_dir;
+    let skill = skills::skill_dirs(config)
+        .iter()
+        .flat_map(|dir| installed_skills(dir))
+        .find(|skill| {
+            skill.name == name
+                || skill
+                    .dir()
+                    .and_then(Path::file_name)
+                    .is_some_and(|dir| dir == name)
+        })
+        .with_context(|| format!("Skill not found: {}", name))?;
+
+    let mut state = skills::SkillState::load(workspace_dir)?;
+    let verb = if enabled { "enabled" } else { "disabled" };
+    if !state.set_enabled(&skill.name, enabled) {
+        println!(
+            "{} '{}' is already {}",
+            style("!").yellow(),
+            skill.name,
+            verb
+        );
+        return Ok(());
+    }
+    state.save(workspace_dir)?;
+    println!(
+        "{} Skill '{}' {}",
+        style("✓").green().bold(),
+        skill.name,
+        verb
+    );
+    Ok(())
+}
+
+/// An installed skill's directory, or `name` itself when it is a directory.
+fn find_skill_dir(name: &str, workspace_dir: &Path) -> Result<std::path::PathBuf> {
+    let installed = skills::skills_dir(workspace_dir).join(name);
+    if installed.is_dir() && !name.contains("..") {
+        Ok(installed)
+    } else if Path::new(name).is_dir() {
+        Ok(Path::new(name).to_path_buf())
+    } else {
+        anyhow::bail!("Skill not found: {}", name);
+    }
+}
+
+/// Checks an installed skill, or the skill in a directory while writing it.
+fn validate_skill(name: &str, workspace_dir: &Path) -> Result<()> {
+    let dir = find_skill_dir(name, workspace_dir)?;
+
+    let result = skills::validate_skill(&dir);
+    for error in &result.errors {
+        println!("{} {}", style("✗").red(), error);
+    }
+    for warning in &result.warnings {
+        println!("{} {}", style("!").yellow(), warning);
+    }
+    if !result.is_valid() {
+        anyhow::bail!("Found {} problem(s) in {}", result.errors.len(), name);
+    }
+    println!("{} {} is valid", style("✓").green().bold(), name);
+    Ok(())
+}
+
+/// Writes a new skill from the templates into `skills_dir`, with a script stub when
+/// `script` is given.
+fn create_skill(
+    name: &str,
+    description: Option<&str>,
+    script: Option<ScriptLanguage>,
+    skills_dir: &Path,
+) -> Result<std::path::PathBuf> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid skill name: {}. Use lowercase letters, digits, `-` and `_`",
+            name
+        );
+    }
+    let dir = skills_dir.join(name);
+    if dir.exists() {
+        anyhow::bail!("{} already exists", dir.display());
+    }
+
+    let tools = match script {
+        Some(language) => {
+            let tool = name.replace('-', "_");
+            let (extension, template) = match language {
+                ScriptLanguage::Python => ("py", SCRIPT_PYTHON),
+                ScriptLanguage::Shell => ("sh", SCRIPT_SHELL),
+                ScriptLanguage::Node => ("js", SCRIPT_NODE),
+            };
+            let script = format!("scripts/{tool}.{extension}");
+            let path = dir.join(&script);
+            std::fs::create_dir_all(dir.join("scripts"))?;
+            std::fs::write(
+                &path,
+                template.replace("{tool}", &tool).replace("{name}", name),
+            )
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+            }
+            SKILL_TOOL_TEMPLATE
+                .replace("{tool}", &tool)
+                .replace("{script}", &script)
+        }
+        None => String::new(),
+    };
+
+    let description = description.unwrap_or("What this skill does, and when to use it");
+    let title = name
+        .split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    // A JSON string is a YAML string, so any description survives the front matter.
+    let skill = SKILL_TEMPLATE
+        .replace("{name}", name)
+        .replace("{description}", &serde_json::to_string(description)?)
+        .replace("{title}", &title)
+        .replace("{tools}", &tools);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("SKILL.md"), skill)
+        .with_context(|| format!("Failed to write {}", dir.join("SKILL.md").display()))?;
+    Ok(dir)
+}
+
+pub(crate) fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<()> {
     std::fs::create_dir_all(dest)?;
     for entry in std::fs::read_dir(src)? {
         let entry = entry?;
@@ -158,16 +504,81 @@ fn copy_dir_recursive(src: &std::path::Path, dest: &std::path::Path) -> Result<(
 }
 
 fn print_create_skill_help(skills_dir: &std::path::Path) {
-    println!("  mkdir -p {}/my-skill", skills_dir.display());
-    println!(
-        "  echo '# My Skill' > {}/my-skill/SKILL.md",
-        skills_dir.display()
-    );
+    println!("  dinoe skills create my-skill");
+    println!();
+    println!("It goes in {}/my-skill", skills_dir.display());
 }
 
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum SkillsCommands {
     List,
-    Install { source: String },
+    /// Install from a git URL, a directory, or a package made by `skills package`
+    Install {
+        source: String,
+        /// Trust the key the package is signed with, if it is not trusted yet
+        #[arg(long)]
+        trust: bool,
+    },
     Remove { name: String },
+    /// Check a skill's SKILL.md and tools for mistakes
+    Validate {
+        /// An installed skill, or a skill directory
+        name: String,
+    },
+    /// Offer an installed skill to the agent again
+    Enable { name: String },
+    /// Keep a skill installed but stop offering it to the agent
+    Disable { name: String },
+    /// Make a signed .tar.gz of a skill for others to install
+    Package {
+        /// An installed skill, or a skill directory
+        name: String,
+        /// Directory to write the package to
+        #[arg(short, long, default_value = ".")]
+        output: std::path::PathBuf,
+    },
+    /// Start a new skill from a template
+    Create {
+        /// Directory name, also used as the skill's name
+        name: String,
+        /// What the skill does, for the front matter
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Also add a script stub in this language, declared as a tool
+        #[arg(long, value_name = "LANG")]
+        script: Option<ScriptLanguage>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ScriptLanguage {
+    Python,
+    Shell,
+    Node,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn created_skills_are_valid() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        for (name, script) in [
+            ("notes", None),
+            ("py-tool", Some(ScriptLanguage::Python)),
+            ("sh-tool", Some(ScriptLanguage::Shell)),
+            ("js-tool", Some(ScriptLanguage::Node)),
+        ] {
+            let dir = create_skill(name, Some("Says: \"hi\""), script, tmp.path()).unwrap();
+            let result = skills::validate_skill(&dir);
+            assert!(result.is_valid(), "{name}: {:?}", result.errors);
+            assert!(result.warnings.is_empty(), "{name}: {:?}", result.warnings);
+            let skill = skills::load_skill(&dir).unwrap();
+            assert_eq!(skill.description, "Says: \"hi\"");
+            assert_eq!(skill.tools.len(), usize::from(script.is_some()));
+        }
+        assert!(create_skill("notes", None, None, tmp.path()).is_err());
+        assert!(create_skill("../up", None, None, tmp.path()).is_err());
+    }
 }
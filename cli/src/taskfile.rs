@@ -0,0 +1,312 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use console::style;
+use dinoe_core::agent::TurnOutcome;
+use dinoe_core::config::Config;
+use dinoe_core::transcript::{self, Transcript};
+use serde::Deserialize;
+
+use crate::approval::Prompts;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// A YAML task file, or markdown with one `## ` section per step
+    file: PathBuf,
+    /// Use an agent profile from `[agents.<NAME>]`, instead of the task file's `agent`
+    #[arg(long, value_name = "NAME")]
+    agent: Option<String>,
+}
+
+/// Steps run in order as one session, so each step sees the ones before it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskFile {
+    name: Option<String>,
+    agent: Option<String>,
+    #[serde(default)]
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    name: Option<String>,
+    prompt: String,
+    /// Tools the step may use. All when unset.
+    tools: Option<Vec<String>>,
+    /// File the answer is written to, relative to the task file.
+    output: Option<PathBuf>,
+    /// Run the next steps even when this one fails.
+    #[serde(default)]
+    continue_on_error: bool,
+}
+
+impl TaskFile {
+    fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let file = match path.extension().and_then(|e| e.to_str()) {
+            Some("md" | "markdown") => parse_markdown(&text),
+            _ => serde_yaml::from_str(&text).map_err(Into::into),
+        }
+        .with_context(|| format!("Invalid task file {}", path.display()))?;
+        if file.steps.is_empty() {
+            bail!("{} has no steps", path.display());
+        }
+        Ok(file)
+    }
+}
+
+/// Optional YAML front matter with `name` and `agent`, then a `## ` section per step.
+/// A section may start with `tools:`, `output:` and `continue_on_error:` lines.
+fn parse_markdown(text: &str) -> Result<TaskFile> {
+    let (mut file, body) = match text.strip_prefix("---\n") {
+        Some(rest) => {
+            let (front, body) = rest
+                .split_once("\n---")
+                .context("The front matter is not closed with ---")?;
+            (serde_yaml::from_str(front)?, body)
+        }
+        None => (TaskFile::default(), text),
+    };
+    let mut sections = body.split("\n## ");
+    let preamble = sections.next().unwrap_or_default();
+    if file.name.is_none() {
+        file.name = preamble
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(|title| title.trim().to_string());
+    }
+    // A step at the very start of the body has no newline before it.
+    let first = preamble.trim_start().strip_prefix("## ");
+    for section in first.into_iter().chain(sections) {
+        let (heading, rest) = section.split_once('\n').unwrap_or((section, ""));
+        let mut step = Step {
+            name: Some(heading.trim().to_string()),
+            ..Default::default()
+        };
+        let mut lines = rest.lines().peekable();
+        while lines.next_if(|line| step_option(&mut step, line)).is_some() {}
+        step.prompt = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        if step.prompt.is_empty() {
+            bail!("Step '{}' has no prompt", heading.trim());
+        }
+        file.steps.push(step);
+    }
+    Ok(file)
+}
+
+/// Applies a `key: value` option line; `false` when it is not one.
+fn step_option(step: &mut Step, line: &str) -> bool {
+    let Some((key, value)) = line.split_once(':') else {
+        return false;
+    };
+    let value = value.trim();
+    match key.trim() {
+        "tools" => {
+            step.tools = Some(
+                value
+                    .split(',')
+                    .map(|tool| tool.trim().to_string())
+                    .filter(|tool| !tool.is_empty())
+                    .collect(),
+            )
+        }
+        "output" => step.output = Some(value.into()),
+        "continue_on_error" => step.continue_on_error = value == "true",
+        _ => return false,
+    }
+    true
+}
+
+struct StepReport {
+    name: String,
+    outcome: Option<&'static str>,
+    elapsed: Duration,
+    output: Option<PathBuf>,
+}
+
+pub async fn handle_command(args: RunArgs, config: Config) -> Result<ExitCode> {
+    let tasks = TaskFile::load(&args.file)?;
+    let config = match args.agent.as_ref().or(tasks.agent.as_ref()) {
+        Some(name) => config.with_agent(name)?,
+        None => config,
+    };
+    let prompts = if std::io::stdin().is_terminal() {
+        Prompts::console()
+    } else {
+        Prompts::none()
+    };
+    let agent = crate::build_agent(&config, prompts)?
+        .with_transcript(Arc::new(Transcript::new(transcript::sessions_dir())));
+    let known: Vec<String> = agent.tool_specs().into_iter().map(|s| s.name).collect();
+    for step in &tasks.steps {
+        for tool in step.tools.iter().flatten() {
+            if !known.contains(tool) {
+                bail!("A step lists unknown tool '{tool}'");
+            }
+        }
+    }
+    let base = args.file.parent().unwrap_or(Path::new(""));
+    let title = tasks
+        .name
+        .clone()
+        .unwrap_or_else(|| args.file.display().to_string());
+    let count = tasks.steps.len();
+    eprintln!("{} Running {} ({} steps)", style("▶").cyan(), title, count);
+
+    let mut reports = Vec::new();
+    let mut failed = false;
+    for (i, step) in tasks.steps.iter().enumerate() {
+        let name = step
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Step {}", i + 1));
+        if failed {
+            reports.push(StepReport {
+                name,
+                outcome: None,
+                elapsed: Duration::ZERO,
+                output: None,
+            });
+            continue;
+        }
+        eprintln!(
+            "\n{}",
+            style(format!("[{}/{}] {}", i + 1, count, name)).bold()
+        );
+        let step_agent = match &step.tools {
+            Some(tools) => agent.restricted_to(tools),
+            None => agent.clone(),
+        };
+        let started = Instant::now();
+        let outcome = crate::run_turn(&step_agent, &step.prompt, config.max_history)
+            .await
+            .unwrap_or_else(TurnOutcome::Error);
+        if let TurnOutcome::Error(e) = &outcome {
+            eprintln!("{} {}", style("✗").red(), e);
+        }
+        let mut report = StepReport {
+            name,
+            outcome: Some(outcome.label()),
+            elapsed: started.elapsed(),
+            output: None,
+        };
+        if let (TurnOutcome::Completed { text, .. }, Some(output)) = (&outcome, &step.output) {
+            let path = base.join(output);
+            match write_output(&path, text) {
+                Ok(()) => report.output = Some(path),
+                Err(e) => {
+                    eprintln!("{} {:#}", style("✗").red(), e);
+                    report.outcome = Some("error");
+                }
+            }
+        }
+        if report.outcome != Some("completed") && !step.continue_on_error {
+            failed = true;
+        }
+        reports.push(report);
+    }
+
+    crate::finish_session(&agent, &config).await;
+    print_report(&title, &reports);
+    if let Some(transcript) = agent.transcript() {
+        eprintln!("Session: {}", transcript.session());
+    }
+    let all_completed = reports.iter().all(|r| r.outcome == Some("completed"));
+    Ok(if all_completed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(crate::EXIT_FAILED)
+    })
+}
+
+fn write_output(path: &Path, text: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, format!("{}\n", text.trim_end()))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn print_report(title: &str, reports: &[StepReport]) {
+    eprintln!("\n{} {}", style("Summary:").bold(), title);
+    let width = reports
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for report in reports {
+        let mark = match report.outcome {
+            Some("completed") => style("✓").green(),
+            Some(_) => style("✗").red(),
+            None => style("–").dim(),
+        };
+        let mut line = format!(
+            "  {} {:width$}  {:14}",
+            mark,
+            report.name,
+            report.outcome.unwrap_or("skipped")
+        );
+        if report.outcome.is_some() {
+            line.push_str(&format!(" {:>6.1}s", report.elapsed.as_secs_f64()));
+        }
+        if let Some(output) = &report.output {
+            line.push_str(&format!("  → {}", output.display()));
+        }
+        eprintln!("{}", line.trim_end());
+    }
+    let completed = reports
+        .iter()
+        .filter(|r| r.outcome == Some("completed"))
+        .count();
+    let skipped = reports.iter().filter(|r| r.outcome.is_none()).count();
+    eprintln!(
+        "{} steps: {} completed, {} failed, {} skipped",
+        reports.len(),
+        completed,
+        reports.len() - completed - skipped,
+        skipped
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_markdown_task_files() {
+        let file = parse_markdown(
+            "---\nagent: writer\n---\n# Release notes\n\nRuns before a release.\n\n\
+             ## Collect changes\ntools: git_log, git_diff\noutput: notes/changes.md\n\n\
+             Summarize the commits since the last tag.\n\n\
+             ## Write\nWrite release notes from the summary.\n",
+        )
+        .unwrap();
+        assert_eq!(file.name.as_deref(), Some("Release notes"));
+        assert_eq!(file.agent.as_deref(), Some("writer"));
+        assert_eq!(file.steps.len(), 2);
+        let collect = &file.steps[0];
+        assert_eq!(collect.name.as_deref(), Some("Collect changes"));
+        assert_eq!(
+            collect.tools.as_deref(),
+            Some(&["git_log".to_string(), "git_diff".to_string()][..])
+        );
+        assert_eq!(
+            collect.output.as_deref(),
+            Some(Path::new("notes/changes.md"))
+        );
+        assert_eq!(collect.prompt, "Summarize the commits since the last tag.");
+        assert_eq!(file.steps[1].tools, None);
+        assert_eq!(
+            file.steps[1].prompt,
+            "Write release notes from the summary."
+        );
+    }
+}
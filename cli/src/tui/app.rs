@@ -0,0 +1,244 @@
+use dinoe_core::agent::{AgentEvent, TurnSummary};
+use dinoe_core::config::ModelPrice;
+use tokio::sync::oneshot;
+
+use super::input::Input;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+    Thinking,
+    Notice,
+    Error,
+}
+
+#[derive(Debug)]
+pub struct Entry {
+    pub role: Role,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct ToolActivity {
+    pub name: String,
+    pub state: ToolState,
+    /// The latest progress message, then the result preview.
+    pub detail: String,
+}
+
+/// A question from the agent that the user answers in the UI.
+pub enum Prompt {
+    Approve {
+        tool: String,
+        args: serde_json::Value,
+        reply: oneshot::Sender<bool>,
+    },
+    Ask {
+        question: String,
+        options: Vec<String>,
+        reply: oneshot::Sender<Option<String>>,
+    },
+}
+
+/// Tokens used by the session so far.
+#[derive(Debug, Default)]
+pub struct Usage {
+    pub turns: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+}
+
+/// Everything the UI shows.
+pub struct App {
+    pub model: String,
+    pub price: Option<ModelPrice>,
+    pub entries: Vec<Entry>,
+    pub tools: Vec<ToolActivity>,
+    pub input: Input,
+    /// Lines scrolled up from the bottom of the conversation.
+    pub scroll: usize,
+    pub usage: Usage,
+    pub status: Option<String>,
+    pub busy: bool,
+    pub prompt: Option<Prompt>,
+    /// Whether the last entry is an answer still streaming in.
+    streaming: bool,
+}
+
+impl App {
+    pub fn new(model: String, price: Option<ModelPrice>) -> Self {
+        Self {
+            model,
+            price,
+            entries: Vec::new(),
+            tools: Vec::new(),
+            input: Input::default(),
+            scroll: 0,
+            usage: Usage::default(),
+            status: None,
+            busy: false,
+            prompt: None,
+            streaming: false,
+        }
+    }
+
+    pub fn push(&mut self, role: Role, text: impl Into<String>) {
+        self.entries.push(Entry {
+            role,
+            text: text.into(),
+        });
+        self.streaming = false;
+        self.scroll = 0;
+    }
+
+    /// Starts a turn for `message`.
+    pub fn start_turn(&mut self, message: &str) {
+        self.push(Role::User, message);
+        self.tools.clear();
+        self.busy = true;
+        self.status = Some("Thinking...".to_string());
+    }
+
+    pub fn cost(&self) -> Option<f64> {
+        self.price
+            .map(|price| price.cost(self.usage.input_tokens, self.usage.output_tokens))
+    }
+
+    pub fn on_event(&mut self, event: AgentEvent) {
+        match event {
+            AgentEvent::Token { text } => {
+                if self.streaming
+                    && let Some(entry) = self.entries.last_mut()
+                {
+                    entry.text.push_str(&text);
+                } else {
+                    self.push(Role::Assistant, text);
+                    self.streaming = true;
+                }
+            }
+            AgentEvent::Thinking { text } => match self.entries.last_mut() {
+                Some(entry) if entry.role == Role::Thinking => entry.text.push_str(&text),
+                _ => self.push(Role::Thinking, text),
+            },
+            AgentEvent::Status { message } => self.status = Some(message),
+            AgentEvent::ToolStarted { name } => {
+                self.streaming = false;
+                self.status = Some(format!("Running {name}..."));
+                self.tools.push(ToolActivity {
+                    name,
+                    state: ToolState::Running,
+                    detail: String::new(),
+                });
+            }
+            AgentEvent::ToolProgress { name, message } => {
+                if let Some(tool) = self.running_tool(&name) {
+                    tool.detail = message;
+                }
+            }
+            AgentEvent::ToolFinished {
+                name,
+                success,
+                preview,
+            } => {
+                if let Some(tool) = self.running_tool(&name) {
+                    tool.state = if success {
+                        ToolState::Succeeded
+                    } else {
+                        ToolState::Failed
+                    };
+                    tool.detail = preview;
+                }
+            }
+            // The question is the turn's answer, shown when it completes.
+            AgentEvent::Question { .. } => {}
+            AgentEvent::TurnComplete { text } => {
+                // The streamed text is replaced, in case a guardrail withheld it.
+                if self.streaming
+                    && let Some(entry) = self.entries.last_mut()
+                {
+                    entry.text = text;
+                    self.streaming = false;
+                } else {
+                    self.push(Role::Assistant, text);
+                }
+            }
+            AgentEvent::Cancelled => self.push(Role::Notice, "Cancelled"),
+            AgentEvent::Error { message } => self.push(Role::Error, message),
+            AgentEvent::TurnSummary(summary) => self.finish_turn(&summary),
+        }
+    }
+
+    fn finish_turn(&mut self, summary: &TurnSummary) {
+        self.usage.turns += 1;
+        self.usage.input_tokens += summary.input_tokens;
+        self.usage.output_tokens += summary.output_tokens;
+        self.busy = false;
+        self.streaming = false;
+        self.status = None;
+    }
+
+    /// The latest call of `name` that has not finished.
+    fn running_tool(&mut self, name: &str) -> Option<&mut ToolActivity> {
+        self.tools
+            .iter_mut()
+            .rev()
+            .find(|t| t.name == name && t.state == ToolState::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_a_turn() {
+        let mut app = App::new(
+            "gpt-4o".to_string(),
+            Some(ModelPrice {
+                input: 2.0,
+                output: 8.0,
+            }),
+        );
+        app.start_turn("list files");
+        app.on_event(AgentEvent::tool_started("tree"));
+        app.on_event(AgentEvent::tool_progress("tree", "walking"));
+        assert_eq!(app.tools[0].detail, "walking");
+        app.on_event(AgentEvent::tool_finished("tree", true, "src/\nREADME.md"));
+        assert_eq!(app.tools[0].state, ToolState::Succeeded);
+
+        app.on_event(AgentEvent::Token {
+            text: "Two ".to_string(),
+        });
+        app.on_event(AgentEvent::Token {
+            text: "entries".to_string(),
+        });
+        app.on_event(AgentEvent::TurnComplete {
+            text: "Two entries".to_string(),
+        });
+        app.on_event(AgentEvent::TurnSummary(TurnSummary {
+            input_tokens: 1_000_000,
+            output_tokens: 500_000,
+            ..Default::default()
+        }));
+
+        let texts: Vec<_> = app
+            .entries
+            .iter()
+            .map(|e| (e.role, e.text.as_str()))
+            .collect();
+        assert_eq!(
+            texts,
+            [(Role::User, "list files"), (Role::Assistant, "Two entries")]
+        );
+        assert!(!app.busy);
+        assert_eq!(app.cost(), Some(6.0));
+    }
+}
@@ -0,0 +1,145 @@
+/// A multi-line text box. The cursor is a byte offset into `text`, always on a char
+/// boundary.
+#[derive(Debug, Default)]
+pub struct Input {
+    text: String,
+    cursor: usize,
+}
+
+impl Input {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Empties the box and returns what was in it.
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = self.line_start(self.cursor);
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i);
+    }
+
+    /// Moves to the line above, keeping the column where it can. `false` on the first line.
+    pub fn up(&mut self) -> bool {
+        let start = self.line_start(self.cursor);
+        if start == 0 {
+            return false;
+        }
+        let column = self.text[start..self.cursor].chars().count();
+        let above = self.line_start(start - 1);
+        self.cursor = self.offset_in_line(above, column);
+        true
+    }
+
+    /// Moves to the line below, keeping the column where it can. `false` on the last line.
+    pub fn down(&mut self) -> bool {
+        let Some(newline) = self.text[self.cursor..].find('\n') else {
+            return false;
+        };
+        let column = self.text[self.line_start(self.cursor)..self.cursor]
+            .chars()
+            .count();
+        self.cursor = self.offset_in_line(self.cursor + newline + 1, column);
+        true
+    }
+
+    /// The cursor as (line, column in chars).
+    pub fn position(&self) -> (usize, usize) {
+        let before = &self.text[..self.cursor];
+        let line = before.matches('\n').count();
+        let column = before[self.line_start(self.cursor)..].chars().count();
+        (line, column)
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn offset_in_line(&self, start: usize, column: usize) -> usize {
+        let line = self.text[start..].split('\n').next().unwrap_or_default();
+        start
+            + line
+                .char_indices()
+                .nth(column)
+                .map_or(line.len(), |(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_across_lines() {
+        let mut input = Input::default();
+        input.insert_str("héllo");
+        input.insert('\n');
+        input.insert_str("wo");
+        assert_eq!(input.position(), (1, 2));
+
+        assert!(input.up());
+        assert_eq!(input.position(), (0, 2));
+        input.backspace();
+        assert_eq!(input.text(), "hllo\nwo");
+
+        input.end();
+        assert!(input.down());
+        assert_eq!(input.position(), (1, 2));
+        assert!(!input.down());
+        input.home();
+        input.delete();
+        assert_eq!(input.take(), "hllo\no");
+        assert!(input.is_empty());
+    }
+}
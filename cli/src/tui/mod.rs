@@ -0,0 +1,369 @@
+mod app;
+mod input;
+mod ui;
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use crossterm::event::{
+    DisableBracketedPaste, EnableBracketedPaste, Event, EventStream, KeyCode, KeyEvent,
+    KeyEventKind, KeyModifiers,
+};
+use dinoe_core::agent::{AgentLoop, ApprovalHandler, TurnOutcome};
+use dinoe_core::config::Config;
+use dinoe_core::tools::UserInputHandler;
+use futures_util::StreamExt;
+use ratatui::DefaultTerminal;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::{JoinError, JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::approval::Prompts;
+use app::{App, Prompt, Role};
+
+/// Lines moved by PageUp and PageDown.
+const PAGE: usize = 10;
+
+/// Sends approvals and `ask_user` questions to the UI and waits for the answer.
+struct UiPrompter {
+    requests: mpsc::UnboundedSender<Prompt>,
+}
+
+#[async_trait]
+impl ApprovalHandler for UiPrompter {
+    async fn approve(&self, tool_name: &str, args: &serde_json::Value) -> bool {
+        let (reply, answer) = oneshot::channel();
+        let prompt = Prompt::Approve {
+            tool: tool_name.to_string(),
+            args: args.clone(),
+            reply,
+        };
+        self.requests.send(prompt).is_ok() && answer.await.unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl UserInputHandler for UiPrompter {
+    async fn ask(&self, question: &str, options: &[String]) -> Option<String> {
+        let (reply, answer) = oneshot::channel();
+        let prompt = Prompt::Ask {
+            question: question.to_string(),
+            options: options.to_vec(),
+            reply,
+        };
+        self.requests.send(prompt).ok()?;
+        answer.await.ok().flatten()
+    }
+}
+
+/// Prompt handlers for the agent, and the receiving end `run` takes.
+pub fn prompts() -> (Prompts, mpsc::UnboundedReceiver<Prompt>) {
+    let (requests, rx) = mpsc::unbounded_channel();
+    let prompter = Arc::new(UiPrompter { requests });
+    let prompts = Prompts {
+        approver: prompter.clone(),
+        user_input: Some(prompter),
+    };
+    (prompts, rx)
+}
+
+enum Action {
+    None,
+    Send(String),
+    Cancel,
+    Rollback,
+    ReloadSkills,
+    Quit,
+}
+
+struct Turn {
+    handle: JoinHandle<TurnOutcome>,
+    cancel: CancellationToken,
+}
+
+/// Resolves when the running turn ends; never without one.
+async fn finished(turn: &mut Option<Turn>) -> Result<TurnOutcome, JoinError> {
+    match turn {
+        Some(turn) => (&mut turn.handle).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Runs the chat full-screen until the user quits. `prompts` answer the agents built
+/// when the config is reloaded, and `requests` is the receiving end `prompts` returned.
+pub async fn run(
+    agent: AgentLoop,
+    config: &Config,
+    prompts: Prompts,
+    requests: mpsc::UnboundedReceiver<Prompt>,
+    reloads: mpsc::UnboundedReceiver<Result<Config>>,
+) -> Result<()> {
+    let mut agent = Arc::new(agent);
+    let mut config = config.clone();
+    let mut app = App::new(
+        config.model.clone(),
+        config.prices.get(&config.model).copied(),
+    );
+    for message in crate::session_history(&agent, config.max_history)? {
+        let role = match message.role.as_str() {
+            "user" => Role::User,
+            "assistant" if !message.content.trim().is_empty() => Role::Assistant,
+            _ => continue,
+        };
+        app.push(role, message.content);
+    }
+
+    let mut terminal = ratatui::init();
+    crossterm::execute!(std::io::stdout(), EnableBracketedPaste)?;
+    let mut session = Session {
+        agent: &mut agent,
+        config: &mut config,
+        prompts,
+        reloads,
+    };
+    let result = event_loop(&mut terminal, &mut session, &mut app, requests).await;
+    let _ = crossterm::execute!(std::io::stdout(), DisableBracketedPaste);
+    ratatui::restore();
+
+    crate::finish_session(&agent, &config).await;
+    if let Some(transcript) = agent.transcript() {
+        println!(
+            "\x1b[90mResume with: dinoe tui --resume {}\x1b[0m",
+            transcript.session()
+        );
+    }
+    result
+}
+
+/// The agent and config turns start with, replaced when the config is reloaded.
+struct Session<'a> {
+    agent: &'a mut Arc<AgentLoop>,
+    config: &'a mut Config,
+    prompts: Prompts,
+    reloads: mpsc::UnboundedReceiver<Result<Config>>,
+}
+
+impl Session<'_> {
+    /// Switches to `config` for the next turn, keeping the conversation, and says
+    /// `notice` when that worked.
+    fn reload(&mut self, config: Result<Config>, app: &mut App, notice: &str) {
+        let rebuilt = config.and_then(|config| {
+            let agent = crate::build_agent(&config, self.prompts.clone())?;
+            Ok((agent, config))
+        });
+        match rebuilt {
+            Ok((agent, config)) => {
+                *self.agent = Arc::new(crate::reload::continue_session(agent, self.agent));
+                app.model = config.model.clone();
+                app.price = config.prices.get(&config.model).copied();
+                *self.config = config;
+                app.push(Role::Notice, notice);
+            }
+            Err(e) => app.push(Role::Error, format!("Keeping the previous config: {e:#}")),
+        }
+    }
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    session: &mut Session<'_>,
+    app: &mut App,
+    mut prompts: mpsc::UnboundedReceiver<Prompt>,
+) -> Result<()> {
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    let mut terminal_events = EventStream::new();
+    let mut turn: Option<Turn> = None;
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        let action = tokio::select! {
+            Some(event) = events.recv() => {
+                app.on_event(event);
+                Action::None
+            }
+            Some(prompt) = prompts.recv() => {
+                app.prompt = Some(prompt);
+                app.scroll = 0;
+                Action::None
+            }
+            Some(config) = session.reloads.recv() => {
+                session.reload(config, app, "Config and skills reloaded");
+                Action::None
+            }
+            Some(event) = terminal_events.next() => match event? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => on_key(app, key),
+                Event::Paste(text) => {
+                    app.input.insert_str(&text.replace('\r', ""));
+                    Action::None
+                }
+                _ => Action::None,
+            },
+            result = finished(&mut turn) => {
+                turn = None;
+                if let Err(e) = result {
+                    app.push(Role::Error, format!("The turn failed: {e}"));
+                    app.busy = false;
+                }
+                Action::None
+            }
+        };
+
+        match action {
+            Action::None => {}
+            Action::Send(message) => {
+                let history = crate::session_history(session.agent, session.config.max_history)?;
+                app.start_turn(&message);
+                let cancel = CancellationToken::new();
+                let handle = tokio::spawn({
+                    let (agent, events, cancel) =
+                        (session.agent.clone(), events_tx.clone(), cancel.clone());
+                    async move {
+                        agent
+                            .process_cancellable(&message, history, events, cancel)
+                            .await
+                    }
+                });
+                turn = Some(Turn { handle, cancel });
+            }
+            Action::Cancel => {
+                // Dropping a pending prompt answers it with "no".
+                app.prompt = None;
+                if let Some(turn) = &turn {
+                    turn.cancel.cancel();
+                }
+            }
+            Action::Rollback => match crate::rollback(session.agent) {
+                Ok(message) => app.push(Role::Notice, message),
+                Err(e) => app.push(Role::Error, e.to_string()),
+            },
+            Action::ReloadSkills => {
+                let config = session.config.clone();
+                session.reload(Ok(config), app, "Skills reloaded");
+            }
+            Action::Quit => {
+                if let Some(turn) = &turn {
+                    turn.cancel.cancel();
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn on_key(app: &mut App, key: KeyEvent) -> Action {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+    if let Some(Prompt::Approve { .. }) = &app.prompt {
+        let approved = match key.code {
+            KeyCode::Char('c') if ctrl => return Action::Cancel,
+            KeyCode::Char('y' | 'Y') => true,
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => false,
+            _ => return Action::None,
+        };
+        if let Some(Prompt::Approve { tool, reply, .. }) = app.prompt.take() {
+            let verb = if approved { "Approved" } else { "Denied" };
+            app.push(Role::Notice, format!("{verb} {tool}"));
+            let _ = reply.send(approved);
+        }
+        return Action::None;
+    }
+
+    match key.code {
+        KeyCode::Char('c') if ctrl => {
+            if app.busy {
+                Action::Cancel
+            } else {
+                Action::Quit
+            }
+        }
+        KeyCode::Char('d') if ctrl && app.input.is_empty() && !app.busy => Action::Quit,
+        KeyCode::Esc if app.busy => Action::Cancel,
+        KeyCode::Enter if alt || key.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.input.insert('\n');
+            Action::None
+        }
+        KeyCode::Char('j') if ctrl => {
+            app.input.insert('\n');
+            Action::None
+        }
+        KeyCode::Enter => submit(app),
+        KeyCode::Char(c) if !ctrl => {
+            app.input.insert(c);
+            Action::None
+        }
+        KeyCode::Backspace => {
+            app.input.backspace();
+            Action::None
+        }
+        KeyCode::Delete => {
+            app.input.delete();
+            Action::None
+        }
+        KeyCode::Left => {
+            app.input.left();
+            Action::None
+        }
+        KeyCode::Right => {
+            app.input.right();
+            Action::None
+        }
+        KeyCode::Home => {
+            app.input.home();
+            Action::None
+        }
+        KeyCode::End => {
+            app.input.end();
+            Action::None
+        }
+        KeyCode::Up => {
+            if !app.input.up() {
+                app.scroll += 1;
+            }
+            Action::None
+        }
+        KeyCode::Down => {
+            if !app.input.down() {
+                app.scroll = app.scroll.saturating_sub(1);
+            }
+            Action::None
+        }
+        KeyCode::PageUp => {
+            app.scroll += PAGE;
+            Action::None
+        }
+        KeyCode::PageDown => {
+            app.scroll = app.scroll.saturating_sub(PAGE);
+            Action::None
+        }
+        _ => Action::None,
+    }
+}
+
+/// Enter: answers the pending question, or sends the message.
+fn submit(app: &mut App) -> Action {
+    if let Some(Prompt::Ask { options, .. }) = &app.prompt {
+        let text = app.input.take().trim().to_string();
+        let answer = match text.parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => options[n - 1].clone(),
+            _ => text,
+        };
+        if let Some(Prompt::Ask { reply, .. }) = app.prompt.take() {
+            app.push(Role::User, answer.clone());
+            let _ = reply.send(Some(answer));
+        }
+        return Action::None;
+    }
+    if app.busy || app.input.text().trim().is_empty() {
+        return Action::None;
+    }
+    let text = app.input.take().trim().to_string();
+    match text.as_str() {
+        "/quit" | "/exit" => Action::Quit,
+        "/rollback" => Action::Rollback,
+        "/reload-skills" => Action::ReloadSkills,
+        _ => Action::Send(text),
+    }
+}
@@ -0,0 +1,183 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+
+use super::app::{App, Prompt, Role, ToolState};
+
+/// Lines the input box grows to before it scrolls.
+const INPUT_MAX_LINES: usize = 8;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let input_height = app.input.line_count().min(INPUT_MAX_LINES) as u16 + 2;
+    let [main, input, footer] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(input_height),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [conversation, sidebar] =
+        Layout::horizontal([Constraint::Percentage(72), Constraint::Percentage(28)]).areas(main);
+
+    draw_conversation(frame, app, conversation);
+    draw_tools(frame, app, sidebar);
+    draw_input(frame, app, input);
+    draw_footer(frame, app, footer);
+}
+
+fn draw_conversation(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    for entry in &app.entries {
+        let (label, style) = match entry.role {
+            Role::User => ("you", Style::new().fg(Color::Cyan).bold()),
+            Role::Assistant => ("dinoe", Style::new().fg(Color::Green).bold()),
+            Role::Thinking => ("thinking", Style::new().fg(Color::DarkGray).italic()),
+            Role::Notice => ("", Style::new().fg(Color::DarkGray)),
+            Role::Error => ("error", Style::new().fg(Color::Red).bold()),
+        };
+        let body = match entry.role {
+            Role::Thinking | Role::Notice => Style::new().fg(Color::DarkGray),
+            Role::Error => Style::new().fg(Color::Red),
+            Role::User | Role::Assistant => Style::new(),
+        };
+        if !label.is_empty() {
+            lines.push(Line::styled(label, style));
+        }
+        lines.extend(
+            entry
+                .text
+                .lines()
+                .map(|l| Line::styled(l.to_string(), body)),
+        );
+        lines.push(Line::default());
+    }
+    if let Some(prompt) = &app.prompt {
+        lines.extend(prompt_lines(prompt));
+    }
+
+    let block = Block::bordered().title(" Conversation ");
+    let paragraph = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false });
+    let height = area.height.saturating_sub(2) as usize;
+    let total = paragraph.line_count(area.width.saturating_sub(2));
+    let bottom = total.saturating_sub(height);
+    let top = bottom.saturating_sub(app.scroll);
+    frame.render_widget(
+        paragraph
+            .block(block)
+            .scroll((top.min(u16::MAX as usize) as u16, 0)),
+        area,
+    );
+}
+
+fn prompt_lines(prompt: &Prompt) -> Vec<Line<'static>> {
+    let highlight = Style::new().fg(Color::Yellow).bold();
+    match prompt {
+        Prompt::Approve { tool, args, .. } => {
+            let mut lines = vec![Line::styled(
+                format!("Approve tool call: {tool}"),
+                highlight,
+            )];
+            if let serde_json::Value::Object(map) = args {
+                for (key, value) in map {
+                    let value = match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    for (i, line) in value.lines().enumerate() {
+                        let key = if i == 0 {
+                            format!("{key}: ")
+                        } else {
+                            "  ".to_string()
+                        };
+                        lines.push(Line::styled(
+                            format!("  {key}{line}"),
+                            Style::new().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+            }
+            lines.push(Line::styled("Run it? [y/n]", highlight));
+            lines
+        }
+        Prompt::Ask {
+            question, options, ..
+        } => {
+            let mut lines = vec![Line::styled(question.clone(), highlight)];
+            for (i, option) in options.iter().enumerate() {
+                lines.push(Line::raw(format!("  {}. {}", i + 1, option)));
+            }
+            lines.push(Line::styled(
+                "Type an answer or an option number, then Enter",
+                Style::new().fg(Color::DarkGray),
+            ));
+            lines
+        }
+    }
+}
+
+fn draw_tools(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    for tool in &app.tools {
+        let (mark, color) = match tool.state {
+            ToolState::Running => ("\u{25CB}", Color::Yellow),
+            ToolState::Succeeded => ("\u{25CF}", Color::Green),
+            ToolState::Failed => ("\u{2717}", Color::Red),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{mark} "), Style::new().fg(color)),
+            Span::raw(tool.name.clone()),
+        ]));
+        if let Some(detail) = tool.detail.lines().find(|l| !l.trim().is_empty()) {
+            lines.push(Line::styled(
+                format!("  {}", detail.trim()),
+                Style::new().fg(Color::DarkGray),
+            ));
+        }
+    }
+    let height = area.height.saturating_sub(2) as usize;
+    let skip = lines.len().saturating_sub(height);
+    let paragraph =
+        Paragraph::new(Text::from(lines.split_off(skip))).block(Block::bordered().title(" Tools "));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match &app.prompt {
+        Some(Prompt::Approve { .. }) => " y / n ",
+        Some(Prompt::Ask { .. }) => " Answer ",
+        None if app.busy => " Working (Esc to cancel) ",
+        None => " Message (Enter to send, Alt+Enter for a new line) ",
+    };
+    let (line, column) = app.input.position();
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = (line + 1).saturating_sub(height);
+    let paragraph = Paragraph::new(app.input.text())
+        .block(Block::bordered().title(title))
+        .scroll((scroll as u16, 0));
+    frame.render_widget(paragraph, area);
+    if !matches!(app.prompt, Some(Prompt::Approve { .. })) {
+        frame.set_cursor_position((
+            area.x + 1 + column as u16,
+            area.y + 1 + line.saturating_sub(scroll) as u16,
+        ));
+    }
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let mut text = format!(
+        " {} \u{00B7} {} turn(s) \u{00B7} ~{} tokens in / ~{} out",
+        app.model, app.usage.turns, app.usage.input_tokens, app.usage.output_tokens
+    );
+    if let Some(cost) = app.cost() {
+        text.push_str(&format!(" \u{00B7} ~${cost:.4}"));
+    }
+    if let Some(status) = &app.status {
+        text.push_str(&format!(" \u{00B7} {status}"));
+    }
+    text.push_str(" \u{00B7} PgUp/PgDn scroll, Ctrl+C quit");
+    frame.render_widget(
+        Paragraph::new(text).style(Style::new().fg(Color::DarkGray).add_modifier(Modifier::DIM)),
+        area,
+    );
+}
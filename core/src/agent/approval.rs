@@ -0,0 +1,97 @@
+use crate::agent::middleware::{ToolCall, ToolMiddleware};
+use crate::traits::ToolResult;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalMode {
+    #[default]
+    Never,
+    Mutating,
+    Always,
+}
+
+impl ApprovalMode {
+    pub fn requires_approval(&self, mutating: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Mutating => mutating,
+            Self::Always => true,
+        }
+    }
+}
+
+impl std::fmt::Display for ApprovalMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Never => write!(f, "never"),
+            Self::Mutating => write!(f, "mutating"),
+            Self::Always => write!(f, "always"),
+        }
+    }
+}
+
+#[async_trait]
+pub trait ApprovalHandler: Send + Sync {
+    async fn approve(&self, tool_name: &str, args: &serde_json::Value) -> bool;
+}
+
+/// Refuses every call that needs approval, for when nobody is there to ask.
+pub struct DenyApprover;
+
+#[async_trait]
+impl ApprovalHandler for DenyApprover {
+    async fn approve(&self, _tool_name: &str, _args: &serde_json::Value) -> bool {
+        false
+    }
+}
+
+pub struct ApprovalMiddleware {
+    mode: ApprovalMode,
+    handler: Arc<dyn ApprovalHandler>,
+}
+
+impl ApprovalMiddleware {
+    pub fn new(mode: ApprovalMode, handler: Arc<dyn ApprovalHandler>) -> Self {
+        Self { mode, handler }
+    }
+}
+
+#[async_trait]
+impl ToolMiddleware for ApprovalMiddleware {
+    async fn before_execute(&self, call: &mut ToolCall) -> Option<ToolResult> {
+        let needed = call.always_needs_approval || self.mode.requires_approval(call.mutating);
+        if !needed || self.handler.approve(&call.name, &call.args).await {
+            return None;
+        }
+        Some(ToolResult::error(format!(
+            "User denied execution of '{}'. Ask the user how to proceed instead of retrying.",
+            call.name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approval_mode_policy() {
+        assert!(!ApprovalMode::Never.requires_approval(true));
+        assert!(ApprovalMode::Mutating.requires_approval(true));
+        assert!(!ApprovalMode::Mutating.requires_approval(false));
+        assert!(ApprovalMode::Always.requires_approval(false));
+    }
+
+    #[test]
+    fn approval_mode_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            approval: ApprovalMode,
+        }
+        let parsed: Wrapper = toml::from_str("approval = \"mutating\"").unwrap();
+        assert_eq!(parsed.approval, ApprovalMode::Mutating);
+    }
+}
@@ -1,11 +1,19 @@
-use crate::skills::Skill;
+use crate::agent::tokens::{estimate_tokens, message_tokens, truncate_to_tokens};
+use crate::knowledge::KnowledgeIndex;
+use crate::skills::{Skill, workspace_files};
+use crate::tools::TodoList;
 use crate::traits::{ChatMessage, Memory, ToolSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-const BOOTSTRAP_MAX_CHARS: usize = 20_000;
+const SEPARATOR: &str = "\n\n---\n\n";
+/// Sections left with less room than this are dropped rather than cut to a stub.
+const MIN_SECTION_TOKENS: usize = 50;
 const MEMORY_MIN_RELEVANCE_SCORE: f64 = 0.4;
+const KNOWLEDGE_RESULTS: usize = 3;
 
 const BOOTSTRAP_FILES: &[(&str, &str)] = &[
     ("SOUL.md", "## Agent Identity (SOUL.md)"),
@@ -13,11 +21,87 @@ const BOOTSTRAP_FILES: &[(&str, &str)] = &[
     ("USER.md", "## User Context (USER.md)"),
 ];
 
+/// Optional parts of the system prompt, which can be cut to fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextSection {
+    /// SOUL.md, TOOLS.md and USER.md.
+    Bootstrap,
+    Plan,
+    Skills,
+    Memory,
+    Knowledge,
+}
+
+/// Token limits for each part of the context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextBudget {
+    /// Shared by the bootstrap files; a large file cannot take the room of a small one.
+    pub bootstrap: usize,
+    pub plan: usize,
+    pub skills: usize,
+    pub memory: usize,
+    pub knowledge: usize,
+    /// Earlier conversation messages; the newest are kept. No limit when unset.
+    pub history: Option<usize>,
+    /// Sections cut first when the system prompt is over its cap.
+    pub trim_order: Vec<ContextSection>,
+}
+
+impl Default for ContextBudget {
+    fn default() -> Self {
+        Self {
+            bootstrap: 8_000,
+            plan: 1_000,
+            skills: 2_000,
+            memory: 1_500,
+            knowledge: 3_000,
+            history: None,
+            trim_order: vec![
+                ContextSection::Knowledge,
+                ContextSection::Memory,
+                ContextSection::Skills,
+                ContextSection::Plan,
+                ContextSection::Bootstrap,
+            ],
+        }
+    }
+}
+
+impl ContextBudget {
+    fn limit(&self, section: ContextSection) -> usize {
+        match section {
+            ContextSection::Bootstrap => self.bootstrap,
+            ContextSection::Plan => self.plan,
+            ContextSection::Skills => self.skills,
+            ContextSection::Memory => self.memory,
+            ContextSection::Knowledge => self.knowledge,
+        }
+    }
+}
+
+/// `text` cut to `max_tokens`, with a note saying so.
+fn fit(text: String, max_tokens: usize) -> String {
+    const NOTE: &str = "\n\n[... truncated to fit the context budget]";
+    if estimate_tokens(&text) <= max_tokens {
+        return text;
+    }
+    let room = max_tokens.saturating_sub(estimate_tokens(NOTE));
+    format!("{}{}", truncate_to_tokens(&text, room), NOTE)
+}
+
+#[derive(Clone)]
 pub struct ContextBuilder {
     pub workspace: std::path::PathBuf,
     pub memory: Option<Arc<dyn Memory>>,
+    pub knowledge: Option<Arc<KnowledgeIndex>>,
     pub skills: Vec<Skill>,
     pub tool_specs: Vec<ToolSpec>,
+    pub max_prompt_tokens: Option<usize>,
+    pub budget: ContextBudget,
+    /// Files read instead of the workspace's SOUL.md, TOOLS.md or USER.md.
+    pub bootstrap_overrides: BTreeMap<String, PathBuf>,
 }
 
 impl ContextBuilder {
@@ -25,8 +109,12 @@ impl ContextBuilder {
         Self {
             workspace: workspace.as_ref().to_path_buf(),
             memory: None,
+            knowledge: None,
             skills: vec![],
             tool_specs: vec![],
+            max_prompt_tokens: None,
+            budget: ContextBudget::default(),
+            bootstrap_overrides: BTreeMap::new(),
         }
     }
 
@@ -35,6 +123,11 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_knowledge(mut self, knowledge: Arc<KnowledgeIndex>) -> Self {
+        self.knowledge = Some(knowledge);
+        self
+    }
+
     pub fn with_skills(mut self, skills: Vec<Skill>) -> Self {
         self.skills = skills;
         self
@@ -45,25 +138,101 @@ impl ContextBuilder {
         self
     }
 
+    /// Caps the system prompt. Sections are cut in the budget's `trim_order` before the
+    /// rest is truncated.
+    pub fn with_max_prompt_tokens(mut self, tokens: usize) -> Self {
+        self.max_prompt_tokens = Some(tokens);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: ContextBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Reads `path` in place of the bootstrap file `name`, e.g. another SOUL.md for an
+    /// agent profile. Relative paths are resolved against the workspace.
+    pub fn with_bootstrap_file(
+        mut self,
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        self.bootstrap_overrides.insert(name.into(), path.into());
+        self
+    }
+
     pub async fn build_system_prompt(&self, user_message: &str) -> String {
-        let mut parts = vec![];
+        // Sections in prompt order; `None` marks the parts that are always kept whole.
+        let mut parts: Vec<(Option<ContextSection>, String)> = vec![];
 
         if let Some(bootstrap) = self.load_bootstrap_files() {
-            parts.push(bootstrap);
+            parts.push((Some(ContextSection::Bootstrap), bootstrap));
         }
 
-        parts.push(self.get_tool_instructions());
-        parts.push(self.get_runtime_context());
+        parts.push((None, self.get_tool_instructions()));
+        parts.push((None, self.get_runtime_context()));
+
+        if let Some(todo_context) = self.get_todo_context() {
+            parts.push((Some(ContextSection::Plan), todo_context));
+        }
 
-        if let Some(skills_context) = self.get_skills_context() {
-            parts.push(skills_context);
+        if let Some(skills_context) = self.get_skills_context(user_message) {
+            parts.push((Some(ContextSection::Skills), skills_context));
         }
 
         if let Some(memory_context) = self.get_memory_context(user_message).await {
-            parts.push(memory_context);
+            parts.push((Some(ContextSection::Memory), memory_context));
         }
 
-        parts.join("\n\n---\n\n")
+        if let Some(knowledge_context) = self.get_knowledge_context(user_message).await {
+            parts.push((Some(ContextSection::Knowledge), knowledge_context));
+        }
+
+        for (section, text) in &mut parts {
+            if let Some(section) = section {
+                *text = fit(std::mem::take(text), self.budget.limit(*section));
+            }
+        }
+
+        let join = |parts: &[(Option<ContextSection>, String)]| {
+            let texts: Vec<&str> = parts
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .filter(|text| !text.is_empty())
+                .collect();
+            texts.join(SEPARATOR)
+        };
+
+        let Some(max_tokens) = self.max_prompt_tokens else {
+            return join(&parts);
+        };
+
+        let mut total = estimate_tokens(&join(&parts));
+        for section in &self.budget.trim_order {
+            if total <= max_tokens {
+                break;
+            }
+            let Some((_, text)) = parts.iter_mut().find(|(s, _)| *s == Some(*section)) else {
+                continue;
+            };
+            let tokens = estimate_tokens(text);
+            let room = max_tokens.saturating_sub(total - tokens);
+            *text = if room < MIN_SECTION_TOKENS {
+                String::new()
+            } else {
+                fit(std::mem::take(text), room)
+            };
+            total = total - tokens + estimate_tokens(text);
+        }
+
+        let prompt = join(&parts);
+        if estimate_tokens(&prompt) <= max_tokens {
+            return prompt;
+        }
+        format!(
+            "{}\n\n[... system prompt truncated to fit the model's context window]",
+            truncate_to_tokens(&prompt, max_tokens.saturating_sub(20))
+        )
     }
 
     fn get_tool_instructions(&self) -> String {
@@ -112,14 +281,39 @@ impl ContextBuilder {
         )
     }
 
-    fn get_skills_context(&self) -> Option<String> {
-        if self.skills.is_empty() {
+    fn get_todo_context(&self) -> Option<String> {
+        let list = TodoList::load(&self.workspace);
+        if list.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "## Current Plan (todo)\n\n{}\n\nKeep this plan current with the todo tool as you work.",
+            list.render()
+        ))
+    }
+
+    /// Skills without triggers, and those whose triggers match the message or the
+    /// workspace.
+    fn get_skills_context(&self, user_message: &str) -> Option<String> {
+        // Only walked when a skill asks for files.
+        let files = if self.skills.iter().any(|s| !s.triggers.files.is_empty()) {
+            workspace_files(&self.workspace)
+        } else {
+            Vec::new()
+        };
+        let skills: Vec<&Skill> = self
+            .skills
+            .iter()
+            .filter(|skill| skill.triggers.matches(user_message, &files))
+            .collect();
+        if skills.is_empty() {
             return None;
         }
 
         let mut parts = vec!["## Available Skills\n\n<available_skills>".to_string()];
 
-        for skill in &self.skills {
+        for skill in skills {
             let location = skill.location.clone().unwrap_or_else(|| {
                 self.workspace
                     .join("skills")
@@ -127,16 +321,29 @@ impl ContextBuilder {
                     .join("SKILL.md")
             });
 
+            let tools = if skill.tools.is_empty() {
+                String::new()
+            } else {
+                let names: Vec<&str> = skill.tools.iter().map(|t| t.name.as_str()).collect();
+                format!("\n    <tools>{}</tools>", names.join(", "))
+            };
             parts.push(format!(
-                "  <skill>\n    <name>{}</name>\n    <description>{}</description>\n    <location>{}</location>\n  </skill>",
+                "  <skill>\n    <name>{}</name>\n    <description>{}</description>\n    <location>{}</location>{}\n  </skill>",
                 skill.name,
                 skill.description,
-                location.display()
+                location.display(),
+                tools
             ));
         }
 
         parts.push("</available_skills>".to_string());
 
+        if self.tool_specs.iter().any(|t| t.name == "read_skill") {
+            parts.push("\nOnly the names and descriptions of skills are listed here. When a task matches a skill, call `read_skill` with its name to load the full instructions before you start, and follow them. Do not load skills the task does not need.".to_string());
+        } else {
+            parts.push("\nWhen a task matches a skill, read its SKILL.md at the location above before you start.".to_string());
+        }
+
         Some(parts.join("\n"))
     }
 
@@ -172,34 +379,83 @@ impl ContextBuilder {
         None
     }
 
-    fn load_bootstrap_files(&self) -> Option<String> {
-        let mut parts = vec![];
+    async fn get_knowledge_context(&self, user_message: &str) -> Option<String> {
+        let knowledge = self.knowledge.as_ref()?;
+        let hits = match knowledge.search(user_message, KNOWLEDGE_RESULTS).await {
+            Ok(hits) => hits,
+            Err(e) => {
+                tracing::warn!("Knowledge search failed: {}", e);
+                return None;
+            }
+        };
+        let relevant: Vec<_> = hits
+            .iter()
+            .filter(|hit| hit.score >= MEMORY_MIN_RELEVANCE_SCORE)
+            .collect();
+        if relevant.is_empty() {
+            return None;
+        }
 
-        for (filename, section_header) in BOOTSTRAP_FILES {
-            if let Ok(content) = std::fs::read_to_string(self.workspace.join(filename)) {
-                let trimmed = content.trim();
+        let mut context = String::from("## Relevant Knowledge\n");
+        for hit in relevant {
+            let _ = write!(
+                context,
+                "\n### {} ({})\n\n{}\n",
+                hit.source, hit.location, hit.text
+            );
+        }
+        Some(context)
+    }
 
-                if !trimmed.is_empty() {
-                    let content = if trimmed.chars().count() > BOOTSTRAP_MAX_CHARS {
-                        let truncated: String = trimmed.chars().take(BOOTSTRAP_MAX_CHARS).collect();
-                        format!(
-                            "{}\n\n[... truncated at {} chars — use file_read for full content]\n",
-                            truncated, BOOTSTRAP_MAX_CHARS
-                        )
-                    } else {
-                        trimmed.to_string()
-                    };
-
-                    parts.push(format!("{}\n\n{}", section_header, content));
-                }
-            }
+    fn load_bootstrap_files(&self) -> Option<String> {
+        let files: Vec<(&str, String)> = BOOTSTRAP_FILES
+            .iter()
+            .filter_map(|(filename, section_header)| {
+                let path = match self.bootstrap_overrides.get(*filename) {
+                    Some(path) => self.workspace.join(path),
+                    None => self.workspace.join(filename),
+                };
+                let content = std::fs::read_to_string(path).ok()?;
+                let trimmed = content.trim();
+                (!trimmed.is_empty()).then(|| (*section_header, trimmed.to_string()))
+            })
+            .collect();
+        if files.is_empty() {
+            return None;
         }
 
-        if parts.is_empty() {
-            None
-        } else {
-            Some(parts.join("\n\n---\n\n"))
+        // Share the budget out smallest file first, so room a small file doesn't need
+        // goes to the larger ones.
+        let mut by_size: Vec<usize> = (0..files.len()).collect();
+        by_size.sort_by_key(|&i| estimate_tokens(&files[i].1));
+        let mut limits = vec![0; files.len()];
+        let headers: usize = files
+            .iter()
+            .map(|(header, _)| estimate_tokens(header) + estimate_tokens(SEPARATOR))
+            .sum();
+        let mut remaining = self.budget.bootstrap.saturating_sub(headers);
+        for (n, &i) in by_size.iter().enumerate() {
+            let share = remaining / (files.len() - n);
+            limits[i] = estimate_tokens(&files[i].1).min(share);
+            remaining -= limits[i];
         }
+
+        let parts: Vec<String> = files
+            .into_iter()
+            .zip(limits)
+            .map(|((section_header, content), limit)| {
+                let content = if estimate_tokens(&content) > limit {
+                    format!(
+                        "{}\n\n[... truncated — use file_read for full content]",
+                        truncate_to_tokens(&content, limit.saturating_sub(20))
+                    )
+                } else {
+                    content
+                };
+                format!("{}\n\n{}", section_header, content)
+            })
+            .collect();
+        Some(parts.join(SEPARATOR))
     }
 
     pub async fn build_messages(
@@ -210,8 +466,110 @@ impl ContextBuilder {
         let mut messages = vec![ChatMessage::system(
             self.build_system_prompt(current_message).await,
         )];
-        messages.extend(history);
+        messages.extend(self.fit_history(history));
         messages.push(ChatMessage::user(current_message));
         messages
     }
+
+    /// The newest history messages within the history budget. Tool results are never
+    /// kept without the message that called them.
+    fn fit_history(&self, mut history: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let Some(limit) = self.budget.history else {
+            return history;
+        };
+        let mut total = 0;
+        let mut start = history.len();
+        while start > 0 && total + message_tokens(&history[start - 1]) <= limit {
+            total += message_tokens(&history[start - 1]);
+            start -= 1;
+        }
+        while start < history.len() && history[start].role == "tool" {
+            start += 1;
+        }
+        history.split_off(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill(name: &str) -> Skill {
+        Skill {
+            name: name.to_string(),
+            description: "Does things ".repeat(20),
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn large_bootstrap_file_does_not_crowd_out_the_rest() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("SOUL.md"), "Be concise.").unwrap();
+        std::fs::write(workspace.path().join("USER.md"), "likes tea ".repeat(5_000)).unwrap();
+
+        let builder = ContextBuilder::new(workspace.path())
+            .with_skills((0..20).map(|i| skill(&format!("skill{i}"))).collect())
+            .with_budget(ContextBudget {
+                bootstrap: 500,
+                history: Some(20),
+                ..Default::default()
+            })
+            .with_max_prompt_tokens(1_200);
+
+        let prompt = builder.build_system_prompt("hi").await;
+        assert!(prompt.contains("Be concise."));
+        assert!(prompt.contains("[... truncated — use file_read for full content]"));
+        assert!(prompt.contains("## Runtime Context"));
+        // Skills are cut before the bootstrap files.
+        assert!(prompt.contains("truncated to fit the context budget"));
+        assert!(estimate_tokens(&prompt) <= 1_200);
+
+        let history = vec![
+            ChatMessage::user("first question about the weather today"),
+            ChatMessage::assistant("first answer about the weather today"),
+            ChatMessage::user("second question"),
+            ChatMessage::assistant("second answer"),
+        ];
+        let messages = builder.build_messages(history, "third").await;
+        let contents: Vec<&str> = messages[1..].iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["second question", "second answer", "third"]);
+    }
+
+    #[tokio::test]
+    async fn bootstrap_files_can_be_replaced() {
+        let workspace = TempDir::new().unwrap();
+        std::fs::write(workspace.path().join("SOUL.md"), "Be friendly.").unwrap();
+        std::fs::write(workspace.path().join("coder.md"), "Write tests first.").unwrap();
+
+        let prompt = ContextBuilder::new(workspace.path())
+            .with_bootstrap_file("SOUL.md", "coder.md")
+            .build_system_prompt("hi")
+            .await;
+        assert!(prompt.contains("Write tests first."));
+        assert!(!prompt.contains("Be friendly."));
+    }
+
+    #[tokio::test]
+    async fn skills_with_triggers_are_listed_when_they_match() {
+        let workspace = TempDir::new().unwrap();
+        let mut pdf = skill("pdf");
+        pdf.triggers.keywords = vec!["invoice".to_string()];
+        let mut terraform = skill("terraform");
+        terraform.triggers.files = vec!["*.tf".to_string()];
+        let builder =
+            ContextBuilder::new(workspace.path()).with_skills(vec![skill("notes"), pdf, terraform]);
+
+        let prompt = builder.build_system_prompt("hi").await;
+        assert!(prompt.contains("<name>notes</name>"));
+        assert!(!prompt.contains("<name>pdf</name>"));
+        assert!(!prompt.contains("<name>terraform</name>"));
+
+        std::fs::write(workspace.path().join("main.tf"), "").unwrap();
+        let prompt = builder.build_system_prompt("Total this invoice").await;
+        assert!(prompt.contains("<name>pdf</name>"));
+        assert!(prompt.contains("<name>terraform</name>"));
+    }
 }
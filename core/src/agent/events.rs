@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc::{Sender, UnboundedSender};
+
+const TOOL_RESULT_MAX: usize = 200;
+
+pub(crate) fn truncate_preview(input: &str, max: usize) -> String {
+    let input = input.trim();
+    if input.chars().count() <= max {
+        input.to_string()
+    } else {
+        let truncated: String = input.chars().take(max - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
+/// What happens during a turn, for whoever renders it: the CLI, a server or a test.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentEvent {
+    /// A piece of the answer, when streaming is enabled.
+    Token {
+        text: String,
+    },
+    /// A piece of the model's reasoning, when streaming is enabled.
+    Thinking {
+        text: String,
+    },
+    Status {
+        message: String,
+    },
+    ToolStarted {
+        name: String,
+    },
+    ToolProgress {
+        name: String,
+        message: String,
+    },
+    ToolFinished {
+        name: String,
+        success: bool,
+        preview: String,
+    },
+    Question {
+        question: String,
+        options: Vec<String>,
+    },
+    TurnComplete {
+        text: String,
+    },
+    /// The turn was stopped before it finished.
+    Cancelled,
+    Error {
+        message: String,
+    },
+    /// Emitted last, however the turn ended.
+    TurnSummary(TurnSummary),
+}
+
+/// What a turn used. Token counts are estimates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnSummary {
+    pub iterations: usize,
+    pub max_iterations: usize,
+    pub tool_calls: usize,
+    pub failed_tool_calls: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub elapsed_ms: u64,
+}
+
+impl std::fmt::Display for TurnSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} iterations, {} tool calls",
+            self.iterations, self.max_iterations, self.tool_calls
+        )?;
+        if self.failed_tool_calls > 0 {
+            write!(f, " ({} failed)", self.failed_tool_calls)?;
+        }
+        write!(
+            f,
+            ", ~{} tokens in / ~{} out, {:.1}s",
+            self.input_tokens,
+            self.output_tokens,
+            self.elapsed_ms as f64 / 1000.0
+        )
+    }
+}
+
+impl AgentEvent {
+    pub fn status(message: impl Into<String>) -> Self {
+        AgentEvent::Status {
+            message: message.into(),
+        }
+    }
+
+    pub fn tool_started(name: impl Into<String>) -> Self {
+        AgentEvent::ToolStarted { name: name.into() }
+    }
+
+    pub fn tool_progress(name: impl Into<String>, message: impl Into<String>) -> Self {
+        AgentEvent::ToolProgress {
+            name: name.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn tool_finished(name: impl Into<String>, success: bool, result: &str) -> Self {
+        AgentEvent::ToolFinished {
+            name: name.into(),
+            success,
+            preview: truncate_preview(result, TOOL_RESULT_MAX),
+        }
+    }
+
+    pub fn question(question: impl Into<String>, options: Vec<String>) -> Self {
+        AgentEvent::Question {
+            question: question.into(),
+            options,
+        }
+    }
+}
+
+/// Receives the events of a turn. Without a sink the agent runs silently.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    sink: Option<Arc<dyn Fn(AgentEvent) + Send + Sync>>,
+}
+
+impl EventSink {
+    pub fn new(sink: impl Fn(AgentEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Some(Arc::new(sink)),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    pub fn emit(&self, event: AgentEvent) {
+        if let Some(sink) = &self.sink {
+            sink(event);
+        }
+    }
+}
+
+/// Events are dropped while the channel is full.
+impl From<Sender<AgentEvent>> for EventSink {
+    fn from(tx: Sender<AgentEvent>) -> Self {
+        Self::new(move |event| {
+            let _ = tx.try_send(event);
+        })
+    }
+}
+
+impl From<UnboundedSender<AgentEvent>> for EventSink {
+    fn from(tx: UnboundedSender<AgentEvent>) -> Self {
+        Self::new(move |event| {
+            let _ = tx.send(event);
+        })
+    }
+}
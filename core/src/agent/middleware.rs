@@ -0,0 +1,65 @@
+use crate::traits::ToolResult;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Identifies one agent turn. Middleware that keeps state per turn keys it by this, so
+/// turns running at the same time on a shared registry don't mix.
+pub type TurnId = u64;
+
+tokio::task_local! {
+    static CURRENT_TURN: TurnId;
+}
+
+static NEXT_TURN: AtomicU64 = AtomicU64::new(1);
+
+/// The turn the current task is running, if any. Nested agents run inside the turn of
+/// the agent that spawned them.
+pub fn current_turn() -> Option<TurnId> {
+    CURRENT_TURN.try_with(|turn| *turn).ok()
+}
+
+pub(crate) fn next_turn() -> TurnId {
+    NEXT_TURN.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Runs `future` as part of `turn`.
+pub(crate) async fn in_turn<F: Future>(turn: TurnId, future: F) -> F::Output {
+    CURRENT_TURN.scope(turn, future).await
+}
+
+/// A tool call as seen by middleware, after its arguments passed schema validation.
+#[derive(Debug, Clone)]
+pub struct ToolCall {
+    pub name: String,
+    pub args: Value,
+    pub mutating: bool,
+    /// Asks for approval whatever the approval mode; see
+    /// [`crate::traits::Tool::always_needs_approval`].
+    pub always_needs_approval: bool,
+    /// The turn that made the call; 0 outside of one.
+    pub turn: TurnId,
+    /// When the tool started running, or when the call was received if it never ran.
+    pub started: Instant,
+}
+
+/// Hooks around `ToolRegistry::execute`. Middleware runs in registration order for
+/// every hook; all methods default to doing nothing.
+#[async_trait]
+pub trait ToolMiddleware: Send + Sync {
+    /// Runs before the tool. May rewrite the arguments, or return a result to skip the
+    /// tool (and any later `before_execute` hooks) entirely.
+    async fn before_execute(&self, _call: &mut ToolCall) -> Option<ToolResult> {
+        None
+    }
+
+    /// Runs when the tool returns `Err`. The first middleware to return a result
+    /// replaces the default "Execution failed" error.
+    async fn on_error(&self, _call: &ToolCall, _error: &anyhow::Error) -> Option<ToolResult> {
+        None
+    }
+
+    /// Runs on every result, including short-circuited and failed calls.
+    async fn after_execute(&self, _call: &ToolCall, _result: &mut ToolResult) {}
+}
@@ -1,9 +1,16 @@
+pub mod approval;
 pub mod context;
+pub mod events;
+pub mod middleware;
+pub mod recovery;
 pub mod registry;
 pub mod runner;
-pub mod status;
+pub mod tokens;
 
-pub use context::ContextBuilder;
+pub use approval::{ApprovalHandler, ApprovalMiddleware, ApprovalMode, DenyApprover};
+pub use context::{ContextBudget, ContextBuilder, ContextSection};
+pub use events::{AgentEvent, EventSink, TurnSummary};
+pub use middleware::{ToolCall, ToolMiddleware, TurnId, current_turn};
+pub use recovery::{FailureEscalation, RecoveryPolicy};
 pub use registry::ToolRegistry;
-pub use runner::AgentLoop;
-pub use status::{StatusPrinter, StatusUpdate};
+pub use runner::{AgentLoop, TurnCancelled, TurnOutcome, TurnTimedOut};
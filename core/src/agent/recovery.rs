@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+/// What the agent does once tools keep failing in a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureEscalation {
+    /// Let the model keep trying until max_iterations.
+    Continue,
+    /// End the turn and ask the user how to proceed.
+    #[default]
+    Ask,
+    /// End the turn with an error.
+    Abort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryPolicy {
+    /// Add a hint on what to try next to failed tool results.
+    pub hints: bool,
+    /// Failed tool calls in a row, without any success in between, before escalating.
+    pub max_consecutive_failures: usize,
+    pub on_repeated_failure: FailureEscalation,
+    /// Times an empty reply is retried, with a nudge, before the turn fails.
+    pub empty_response_retries: usize,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            hints: true,
+            max_consecutive_failures: 4,
+            on_repeated_failure: FailureEscalation::Ask,
+            empty_response_retries: 2,
+        }
+    }
+}
+
+/// Sent after an empty reply. Several providers occasionally end a stream without any
+/// content; asking again usually works.
+pub(crate) const EMPTY_RESPONSE_NUDGE: &str =
+    "Your last reply was empty. Continue: answer the user, or call a tool if you need one.";
+
+/// Error fragments (lowercase) and what to do about them; the first match wins.
+/// Malformed arguments and approval denials already explain themselves.
+const HINTS: &[(&[&str], &str)] = &[
+    (
+        &[
+            "no such file",
+            "not found",
+            "does not exist",
+            "cannot find",
+            "os error 2",
+        ],
+        "The path or item does not exist. List the directory or search for it (tree, glob_search) before retrying.",
+    ),
+    (
+        &["permission denied", "os error 13", "outside the workspace"],
+        "This location is not accessible. Work inside the workspace or ask the user for access.",
+    ),
+    (
+        &["timed out", "timeout"],
+        "The call took too long. Narrow it down, e.g. a smaller command, fewer files or a shorter range.",
+    ),
+    (
+        &["old_string"],
+        "Read the file again and copy the exact text to replace, including whitespace.",
+    ),
+    (
+        &["required", "missing", "expected"],
+        "Check the tool's parameters and provide every required argument.",
+    ),
+    (
+        &["http", "connection", "dns", "network"],
+        "The remote service failed. Check the URL, or try another source instead of repeating the same request.",
+    ),
+];
+
+/// Guidance for the model after a failed tool call, based on the error text.
+pub fn hint(error: &str) -> Option<&'static str> {
+    let error = error.to_lowercase();
+    if error.starts_with("invalid_arguments") || error.starts_with("user denied") {
+        return None;
+    }
+    HINTS
+        .iter()
+        .find(|(fragments, _)| fragments.iter().any(|f| error.contains(f)))
+        .map(|(_, hint)| *hint)
+}
+
+/// Counts failed tool calls in a row during a turn.
+#[derive(Debug, Default)]
+pub struct FailureTracker {
+    consecutive: usize,
+    last_tool: String,
+    last_error: String,
+}
+
+impl FailureTracker {
+    pub fn record(&mut self, tool: &str, success: bool, error: Option<&str>) {
+        if success {
+            self.consecutive = 0;
+            return;
+        }
+        self.consecutive += 1;
+        self.last_tool = tool.to_string();
+        self.last_error = error.unwrap_or_default().to_string();
+    }
+
+    pub fn consecutive(&self) -> usize {
+        self.consecutive
+    }
+
+    /// A reply asking the user for help, used by `FailureEscalation::Ask`.
+    pub fn question(&self) -> String {
+        let error = self.last_error.lines().next().unwrap_or_default();
+        format!(
+            "I'm stuck: the last {} tool calls failed (most recently `{}`: {}). How would you like me to proceed?",
+            self.consecutive, self.last_tool, error
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_match_common_failures() {
+        assert!(
+            hint("Failed to read file: No such file or directory (os error 2)")
+                .unwrap()
+                .contains("does not exist")
+        );
+        assert_eq!(
+            hint("User denied execution of 'shell'. Ask the user..."),
+            None
+        );
+        assert!(
+            hint("Command timed out after 60s")
+                .unwrap()
+                .contains("too long")
+        );
+        assert_eq!(hint("something odd"), None);
+
+        let mut tracker = FailureTracker::default();
+        tracker.record("file_read", false, Some("not found"));
+        tracker.record("file_read", false, Some("not found"));
+        assert_eq!(tracker.consecutive(), 2);
+        assert!(tracker.question().contains("`file_read`: not found"));
+        tracker.record("tree", true, None);
+        assert_eq!(tracker.consecutive(), 0);
+    }
+}
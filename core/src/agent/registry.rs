@@ -1,8 +1,111 @@
-use crate::traits::{Tool, ToolResult, ToolSpec};
+use crate::agent::approval::{ApprovalHandler, ApprovalMiddleware, ApprovalMode};
+use crate::agent::middleware::{ToolCall, ToolMiddleware, current_turn};
+use crate::traits::{Tool, ToolProgress, ToolResult, ToolSpec};
+use serde_json::Value;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::Instrument;
+use tracing::field::Empty;
+
+#[derive(Clone)]
+struct RegisteredTool {
+    tool: Arc<dyn Tool>,
+    schema: Value,
+    validator: Option<Arc<jsonschema::Validator>>,
+}
+
+impl RegisteredTool {
+    fn new(tool: Arc<dyn Tool>) -> Self {
+        let schema = tool.parameters_schema();
+        let validator = match jsonschema::validator_for(&schema) {
+            Ok(validator) => Some(Arc::new(validator)),
+            Err(e) => {
+                tracing::warn!(
+                    tool = tool.name(),
+                    "Invalid parameters_schema, arguments will not be validated: {}",
+                    e
+                );
+                None
+            }
+        };
+        Self {
+            tool,
+            schema,
+            validator,
+        }
+    }
+
+    fn validate(&self, args: &Value) -> Result<(), String> {
+        let Some(validator) = &self.validator else {
+            return Ok(());
+        };
+        let errors: Vec<String> = validator
+            .iter_errors(args)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    format!("- {}", e)
+                } else {
+                    format!("- {}: {}", path.trim_start_matches('/'), e)
+                }
+            })
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Invalid arguments for '{}':\n{}\nExpected parameters: {}",
+            self.tool.name(),
+            errors.join("\n"),
+            self.schema
+        ))
+    }
+}
+
+/// Text-based tool call formats deliver every value as a string, so scalars and
+/// string lists are converted to the type the schema declares before validation.
+fn coerce_to_schema(args: &mut Value, schema: &Value) {
+    let (Some(args), Some(properties)) = (
+        args.as_object_mut(),
+        schema.get("properties").and_then(|p| p.as_object()),
+    ) else {
+        return;
+    };
+
+    for (key, value) in args.iter_mut() {
+        let Value::String(raw) = value else {
+            continue;
+        };
+        let Some(property) = properties.get(key) else {
+            continue;
+        };
+        let trimmed = raw.trim();
+        let coerced = match property.get("type").and_then(|t| t.as_str()) {
+            Some("integer") => trimmed.parse::<i64>().ok().map(Value::from),
+            Some("number") => trimmed.parse::<f64>().ok().map(Value::from),
+            Some("boolean") => match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Some(Value::Bool(true)),
+                "false" | "no" | "0" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            Some("array") => match serde_json::from_str::<Value>(trimmed) {
+                Ok(parsed @ Value::Array(_)) => Some(parsed),
+                _ => Some(Value::Array(vec![Value::String(raw.clone())])),
+            },
+            Some("object") => serde_json::from_str::<Value>(trimmed)
+                .ok()
+                .filter(|v| v.is_object()),
+            _ => None,
+        };
+        if let Some(coerced) = coerced {
+            *value = coerced;
+        }
+    }
+}
 
 pub struct ToolRegistry {
-    tools: Mutex<Vec<Arc<dyn Tool>>>,
+    tools: Mutex<Vec<RegisteredTool>>,
+    middleware: Vec<Arc<dyn ToolMiddleware>>,
 }
 
 impl Default for ToolRegistry {
@@ -15,34 +118,226 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: Mutex::new(Vec::new()),
+            middleware: Vec::new(),
         }
     }
 
+    pub fn with_approval(self, mode: ApprovalMode, handler: Arc<dyn ApprovalHandler>) -> Self {
+        self.with_middleware(Arc::new(ApprovalMiddleware::new(mode, handler)))
+    }
+
+    pub fn with_middleware(mut self, middleware: Arc<dyn ToolMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
     pub fn register(&self, tool: Box<dyn Tool>) {
         let mut tools = self.tools.lock().unwrap();
-        tools.push(Arc::from(tool));
+        tools.push(RegisteredTool::new(Arc::from(tool)));
+    }
+
+    pub fn tool_names(&self) -> Vec<String> {
+        let tools = self.tools.lock().unwrap();
+        tools.iter().map(|t| t.tool.name().to_string()).collect()
+    }
+
+    pub fn is_mutating(&self, name: &str) -> Option<bool> {
+        let tools = self.tools.lock().unwrap();
+        tools
+            .iter()
+            .find(|t| t.tool.name() == name)
+            .map(|t| t.tool.is_mutating())
+    }
+
+    pub fn subset(&self, names: &[String]) -> ToolRegistry {
+        let tools = self.tools.lock().unwrap();
+        ToolRegistry {
+            tools: Mutex::new(
+                tools
+                    .iter()
+                    .filter(|t| names.iter().any(|n| n == t.tool.name()))
+                    .cloned()
+                    .collect(),
+            ),
+            middleware: self.middleware.clone(),
+        }
     }
 
     pub fn get_specs(&self) -> Vec<ToolSpec> {
         let tools = self.tools.lock().unwrap();
-        tools.iter().map(|t| t.spec()).collect()
+        tools.iter().map(|t| t.tool.spec()).collect()
     }
 
     pub async fn execute(&self, name: &str, args: serde_json::Value) -> ToolResult {
-        let tool = {
+        self.execute_with_progress(name, args, ToolProgress::none()).await
+    }
+
+    pub async fn execute_with_progress(
+        &self,
+        name: &str,
+        mut args: serde_json::Value,
+        progress: ToolProgress,
+    ) -> ToolResult {
+        let registered = {
             let tools = self.tools.lock().unwrap();
-            tools.iter().find(|t| t.name() == name).cloned()
+            tools.iter().find(|t| t.tool.name() == name).cloned()
         };
 
-        match tool {
-            Some(tool) => {
-                let result = tool.execute(args).await;
-                match result {
-                    Ok(result) => result,
-                    Err(e) => ToolResult::error(format!("Execution failed: {}", e)),
+        match registered {
+            Some(registered) => {
+                coerce_to_schema(&mut args, &registered.schema);
+                if let Err(e) = registered.validate(&args) {
+                    return ToolResult::error(e);
                 }
+
+                let tool = registered.tool;
+                let mut call = ToolCall {
+                    name: name.to_string(),
+                    args,
+                    mutating: tool.is_mutating(),
+                    always_needs_approval: tool.always_needs_approval(),
+                    turn: current_turn().unwrap_or(0),
+                    started: Instant::now(),
+                };
+                let span = tracing::info_span!(
+                    "tool_call",
+                    tool = name,
+                    turn = call.turn,
+                    mutating = call.mutating,
+                    success = Empty,
+                    latency_ms = Empty,
+                );
+                let mut result = self
+                    .run(tool.as_ref(), &mut call, progress)
+                    .instrument(span.clone())
+                    .await;
+                for middleware in &self.middleware {
+                    middleware.after_execute(&call, &mut result).await;
+                }
+                span.record("success", result.success);
+                span.record("latency_ms", call.started.elapsed().as_millis() as u64);
+                result
             }
             None => ToolResult::error(format!("Tool '{}' not found", name)),
         }
     }
+
+    async fn run(
+        &self,
+        tool: &dyn Tool,
+        call: &mut ToolCall,
+        progress: ToolProgress,
+    ) -> ToolResult {
+        for middleware in &self.middleware {
+            if let Some(result) = middleware.before_execute(call).await {
+                return result;
+            }
+        }
+
+        call.started = Instant::now();
+        match tool.execute_with_progress(call.args.clone(), progress).await {
+            Ok(result) => result,
+            Err(e) => {
+                for middleware in &self.middleware {
+                    if let Some(result) = middleware.on_error(call, &e).await {
+                        return result;
+                    }
+                }
+                ToolResult::error(format!("Execution failed: {}", e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo arguments"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string" },
+                    "count": { "type": "integer", "minimum": 1 },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["text"]
+            })
+        }
+
+        async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult::success(args.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_arguments_that_do_not_match_schema() {
+        let registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+
+        let result = registry.execute("echo", json!({ "count": 0 })).await;
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("\"text\" is a required property"), "{error}");
+        assert!(error.contains("- count: 0 is less than the minimum of 1"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn coerces_string_values_from_text_tool_calls() {
+        let registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+
+        let result = registry
+            .execute("echo", json!({ "text": "hi", "count": "3", "tags": "a" }))
+            .await;
+        assert!(result.success, "{:?}", result.error);
+        let echoed: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(echoed, json!({ "text": "hi", "count": 3, "tags": ["a"] }));
+    }
+
+    struct Shout;
+
+    #[async_trait]
+    impl ToolMiddleware for Shout {
+        async fn before_execute(&self, call: &mut ToolCall) -> Option<ToolResult> {
+            let text = call.args["text"].as_str().unwrap_or_default();
+            if text == "stop" {
+                return Some(ToolResult::error("blocked"));
+            }
+            call.args["text"] = json!(text.to_uppercase());
+            None
+        }
+
+        async fn after_execute(&self, call: &ToolCall, result: &mut ToolResult) {
+            result.output = format!("{}: {}", call.name, result.output);
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_rewrites_args_and_results() {
+        let registry = ToolRegistry::new().with_middleware(Arc::new(Shout));
+        registry.register(Box::new(EchoTool));
+
+        let result = registry.execute("echo", json!({ "text": "hi" })).await;
+        assert_eq!(result.output, r#"echo: {"text":"HI"}"#);
+
+        let blocked = registry.execute("echo", json!({ "text": "stop" })).await;
+        assert!(!blocked.success);
+        assert_eq!(blocked.error.as_deref(), Some("blocked"));
+        assert_eq!(blocked.output, "echo: ");
+    }
 }
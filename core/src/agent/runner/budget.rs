@@ -0,0 +1,84 @@
+use crate::traits::ToolResult;
+
+pub const DEFAULT_MAX_RESULT_CHARS: usize = 20_000;
+pub const DEFAULT_MAX_TURN_CHARS: usize = 100_000;
+/// Results still get this much once the turn budget is spent, so the model can see
+/// what the tool returned and narrow its next call.
+const MIN_RESULT_CHARS: usize = 2_000;
+
+/// Limits how much tool output a single turn adds to `messages`. Each result is
+/// capped, and results share a budget for the whole turn.
+pub struct OutputBudget {
+    max_result_chars: usize,
+    remaining: usize,
+}
+
+impl OutputBudget {
+    pub fn new(max_result_chars: usize, max_turn_chars: usize) -> Self {
+        Self {
+            max_result_chars,
+            remaining: max_turn_chars,
+        }
+    }
+
+    pub fn apply(&mut self, tool_name: &str, result: &mut ToolResult) {
+        let limit = self
+            .max_result_chars
+            .min(self.remaining.max(MIN_RESULT_CHARS));
+        let chars = result.output.chars().count();
+        if chars > limit {
+            result.output = truncate_middle(&result.output, chars, limit, tool_name);
+        }
+        self.remaining = self.remaining.saturating_sub(chars.min(limit));
+    }
+}
+
+fn byte_offset(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Keeps the start and end of the output, where headers, errors and summaries
+/// usually are.
+fn truncate_middle(text: &str, chars: usize, limit: usize, tool_name: &str) -> String {
+    let head = limit * 2 / 3;
+    let tail = limit - head;
+    let hint = if tool_name == "file_read" {
+        "read the part you need with file_read offset/limit"
+    } else {
+        "narrow the request, e.g. file_read with offset/limit, content_search, or head/tail/grep in shell"
+    };
+    format!(
+        "{}\n\n[... {} characters omitted to fit the context window; {} ...]\n\n{}",
+        &text[..byte_offset(text, head)],
+        chars - head - tail,
+        hint,
+        &text[byte_offset(text, chars - tail)..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_each_result_and_shares_the_turn_budget() {
+        let mut budget = OutputBudget::new(3_000, 4_000);
+
+        let mut small = ToolResult::success("ok");
+        budget.apply("shell", &mut small);
+        assert_eq!(small.output, "ok");
+
+        let mut big = ToolResult::success(format!("{}END", "é".repeat(5_000)));
+        budget.apply("file_read", &mut big);
+        assert!(big.output.starts_with(&"é".repeat(2_000)));
+        assert!(big.output.ends_with("END"));
+        assert!(big.output.contains("2003 characters omitted"));
+        assert!(big.output.contains("file_read offset/limit"));
+
+        let mut late = ToolResult::success("x".repeat(2_500));
+        budget.apply("shell", &mut late);
+        assert!(late.output.contains("500 characters omitted"));
+    }
+}
@@ -1,61 +1,139 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json::json;
 
 use crate::agent::ToolRegistry;
-use crate::traits::{ToolCall, ToolResult};
+use crate::traits::{ToolCall, ToolProgress, ToolResult};
+
+/// Tool calls with unparseable arguments that end a turn.
+const MAX_MALFORMED_ARGS: usize = 3;
+const RECEIVED_PREVIEW_CHARS: usize = 300;
 
 pub struct ToolExecutor {
     tool_registry: Arc<ToolRegistry>,
+    malformed: AtomicUsize,
 }
 
 impl ToolExecutor {
     pub fn new(tool_registry: Arc<ToolRegistry>) -> Self {
-        Self { tool_registry }
+        Self {
+            tool_registry,
+            malformed: AtomicUsize::new(0),
+        }
     }
 
-    pub async fn execute(&self, tool_call: &ToolCall) -> ToolResult {
-        let args: serde_json::Value = match serde_json::from_str(&tool_call.arguments) {
-            Ok(a) => a,
-            Err(e) => {
-                return ToolResult::error(format!(
-                    "Failed to parse tool arguments for {}: {}",
-                    tool_call.name, e
-                ));
-            }
+    /// Calls so far whose arguments were not valid JSON.
+    pub fn malformed_count(&self) -> usize {
+        self.malformed.load(Ordering::Relaxed)
+    }
+
+    /// Whether the turn should give up, once no retries are left.
+    pub fn too_many_malformed(&self) -> bool {
+        self.malformed_count() >= MAX_MALFORMED_ARGS
+    }
+
+    pub async fn execute(&self, tool_call: &ToolCall, progress: ToolProgress) -> ToolResult {
+        let args = match self.parse_args(tool_call) {
+            Ok(args) => args,
+            Err(result) => return result,
         };
 
-        self.tool_registry.execute(&tool_call.name, args).await
+        self.tool_registry
+            .execute_with_progress(&tool_call.name, args, progress)
+            .await
+    }
+
+    /// Runs the calls concurrently; `progress` holds one sink per call, in order.
+    pub async fn execute_batch(
+        &self,
+        tool_calls: &[ToolCall],
+        progress: Vec<ToolProgress>,
+    ) -> Vec<ToolResult> {
+        let futures = tool_calls
+            .iter()
+            .zip(progress)
+            .map(|(tool_call, progress)| self.execute(tool_call, progress));
+        futures_util::future::join_all(futures).await
     }
 
-    pub async fn execute_batch(&self, tool_calls: &[ToolCall]) -> Vec<ToolResult> {
-        if tool_calls.len() <= 1 {
-            let mut results = Vec::with_capacity(tool_calls.len());
-            for tool_call in tool_calls {
-                results.push(self.execute(tool_call).await);
+    /// Empty arguments mean "no arguments". Anything else that is not JSON is reported
+    /// back to the model with the expected schema so it can send the call again.
+    fn parse_args(&self, tool_call: &ToolCall) -> Result<serde_json::Value, ToolResult> {
+        let raw = tool_call.arguments.trim();
+        if raw.is_empty() {
+            return Ok(json!({}));
+        }
+        serde_json::from_str(raw).map_err(|e| {
+            let attempt = self.malformed.fetch_add(1, Ordering::Relaxed) + 1;
+            let schema = self
+                .tool_registry
+                .get_specs()
+                .into_iter()
+                .find(|s| s.name == tool_call.name)
+                .map(|s| s.parameters_schema);
+            let received: String = raw.chars().take(RECEIVED_PREVIEW_CHARS).collect();
+            let details = json!({
+                "tool": tool_call.name,
+                "problem": e.to_string(),
+                "received": received,
+                "expected_schema": schema,
+                "retries_left": MAX_MALFORMED_ARGS.saturating_sub(attempt),
+                "hint": "Call the tool again with the arguments as a single valid JSON object.",
+            });
+            ToolResult {
+                success: false,
+                output: serde_json::to_string_pretty(&details).unwrap_or_default(),
+                error: Some(format!("invalid_arguments: {e}")),
             }
-            return results;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::CalcTool;
+
+    fn call(arguments: &str) -> ToolCall {
+        ToolCall {
+            id: "1".to_string(),
+            name: "calc".to_string(),
+            arguments: arguments.to_string(),
         }
+    }
 
-        let futures: Vec<_> = tool_calls
-            .iter()
-            .map(|tool_call| {
-                let registry = self.tool_registry.clone();
-                let tool_call = tool_call.clone();
-                async move {
-                    let args: serde_json::Value =
-                        match serde_json::from_str(&tool_call.arguments) {
-                            Ok(a) => a,
-                            Err(e) => {
-                                return ToolResult::error(format!(
-                                    "Failed to parse tool arguments for {}: {}",
-                                    tool_call.name, e
-                                ));
-                            }
-                        };
-                    registry.execute(&tool_call.name, args).await
-                }
-            })
-            .collect();
+    #[tokio::test]
+    async fn malformed_arguments_are_reported_to_the_model() {
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register(Box::new(CalcTool::new()));
+        let executor = ToolExecutor::new(registry);
 
-        futures_util::future::join_all(futures).await
+        let result = executor
+            .execute(&call(r#"{"expression": "1 + 1",}"#), ToolProgress::none())
+            .await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("invalid_arguments"));
+        let details: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(details["retries_left"], 2);
+        assert!(details["expected_schema"]["properties"]["expression"].is_object());
+        assert_eq!(executor.malformed_count(), 1);
+
+        let result = executor
+            .execute(&call(r#"{"expression": "1 + 1"}"#), ToolProgress::none())
+            .await;
+        assert!(result.success);
+        assert_eq!(executor.malformed_count(), 1);
+        assert!(!executor.too_many_malformed());
+
+        for retries_left in [1, 0] {
+            let result = executor
+                .execute(&call(r#"{"expression": "#), ToolProgress::none())
+                .await;
+            let details: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+            assert_eq!(details["retries_left"], retries_left);
+            assert_eq!(executor.too_many_malformed(), retries_left == 0);
+        }
+        assert_eq!(executor.malformed_count(), MAX_MALFORMED_ARGS);
     }
 }
@@ -3,54 +3,79 @@ use std::sync::Arc;
 use anyhow::Result;
 
 use crate::ChatRequest;
+use crate::agent::tokens::{estimate_tokens, message_tokens, messages_tokens, truncate_to_tokens};
 use crate::traits::{ChatMessage, Provider};
 
-const COMPACT_KEEP_RECENT: usize = 20;
-const COMPACTION_MAX_SOURCE_CHARS: usize = 12_000;
-const COMPACTION_MAX_SUMMARY_CHARS: usize = 2_000;
+const COMPACTION_MAX_SOURCE_TOKENS: usize = 3_000;
+const COMPACTION_MAX_SUMMARY_TOKENS: usize = 500;
 const SUMMARIZER_TEMPERATURE: f64 = 0.2;
 
 pub struct HistoryManager {
     provider: Arc<dyn Provider>,
     model_name: String,
-    max_history: usize,
+    /// Tokens the conversation may use; the rest of the window is left for the reply.
+    budget: usize,
 }
 
 impl HistoryManager {
-    pub fn new(provider: Arc<dyn Provider>, model_name: String, max_history: usize) -> Self {
+    pub fn new(provider: Arc<dyn Provider>, model_name: String, context_window: usize) -> Self {
         Self {
             provider,
             model_name,
-            max_history,
+            budget: context_window / 4 * 3,
         }
     }
 
+    /// Takes tokens sent outside the messages, such as tool definitions, off the budget.
+    pub fn with_reserved(mut self, tokens: usize) -> Self {
+        self.budget = self.budget.saturating_sub(tokens);
+        self
+    }
+
     pub fn should_compact(&self, messages: &[ChatMessage]) -> bool {
-        count_non_system(messages) > self.max_history
+        messages_tokens(messages) > self.budget
     }
 
+    /// Summarizes the oldest messages, keeping the newest ones that fit in half the budget.
     pub async fn compact(&self, messages: &mut Vec<ChatMessage>) -> Result<bool> {
-        let has_system = messages.first().is_some_and(|m| m.role == "system");
-        let non_system_count = count_non_system(messages);
-
-        if non_system_count <= self.max_history {
+        if !self.should_compact(messages) {
             return Ok(false);
         }
 
-        let start = if has_system { 1 } else { 0 };
-        let keep_recent = COMPACT_KEEP_RECENT.min(non_system_count);
-        let compact_count = non_system_count.saturating_sub(keep_recent);
-        if compact_count == 0 {
+        let start = usize::from(messages.first().is_some_and(|m| m.role == "system"));
+        let Some(last) = messages.last() else {
+            return Ok(false);
+        };
+        let mut compact_end = messages.len() - 1;
+        let mut kept = message_tokens(last);
+        while compact_end > start {
+            let tokens = message_tokens(&messages[compact_end - 1]);
+            if kept + tokens > self.budget / 2 {
+                break;
+            }
+            kept += tokens;
+            compact_end -= 1;
+        }
+        // Tool results must follow the assistant message that called them, so the kept
+        // part may not start with one: summarize the rest of the group too, or keep all
+        // of it when it runs to the end.
+        compact_end = match (compact_end..messages.len()).find(|&i| !is_tool_result(&messages[i])) {
+            Some(end) => end,
+            None => (start..compact_end)
+                .rev()
+                .find(|&i| !is_tool_result(&messages[i]))
+                .unwrap_or(start),
+        };
+        if compact_end <= start {
             return Ok(false);
         }
 
-        let compact_end = start + compact_count;
         let to_compact: Vec<ChatMessage> = messages[start..compact_end].to_vec();
         let transcript = build_transcript(&to_compact);
 
         let summary = match self.summarize(&transcript).await {
-            Ok(s) => truncate_with_ellipsis(&s, COMPACTION_MAX_SUMMARY_CHARS),
-            Err(_) => truncate_with_ellipsis(&transcript, COMPACTION_MAX_SUMMARY_CHARS),
+            Ok(s) => truncate_with_ellipsis(&s, COMPACTION_MAX_SUMMARY_TOKENS),
+            Err(_) => truncate_with_ellipsis(&transcript, COMPACTION_MAX_SUMMARY_TOKENS),
         };
 
         let summary_msg =
@@ -59,17 +84,33 @@ impl HistoryManager {
         Ok(true)
     }
 
+    /// Drops the oldest messages while the conversation is over budget, together with
+    /// their tool results. The last message is kept, cut short if it does not fit on its own.
     pub fn trim(&self, messages: &mut Vec<ChatMessage>) -> bool {
-        let non_system_count = count_non_system(messages);
-
-        if non_system_count <= self.max_history {
-            return false;
+        let start = usize::from(messages.first().is_some_and(|m| m.role == "system"));
+        let mut total = messages_tokens(messages);
+        let mut remove = 0;
+        while total > self.budget {
+            let end = group_end(messages, start + remove);
+            if end >= messages.len() {
+                break;
+            }
+            total -= messages_tokens(&messages[start + remove..end]);
+            remove = end - start;
         }
+        messages.drain(start..start + remove);
 
-        let has_system = messages.first().is_some_and(|m| m.role == "system");
-        let start = if has_system { 1 } else { 0 };
-        let to_remove = non_system_count.saturating_sub(self.max_history);
-        messages.drain(start..start + to_remove);
+        if total <= self.budget || messages.len() <= start {
+            return remove > 0;
+        }
+        let Some(last) = messages.last_mut() else {
+            return remove > 0;
+        };
+        let others = total - message_tokens(last);
+        let room = self
+            .budget
+            .saturating_sub(others + message_tokens(last) - estimate_tokens(&last.content));
+        last.content = truncate_with_ellipsis(&last.content, room);
         true
     }
 
@@ -108,6 +149,17 @@ impl HistoryManager {
     }
 }
 
+fn is_tool_result(message: &ChatMessage) -> bool {
+    message.role == "tool"
+}
+
+/// The index after the message at `i` and the tool results that follow it.
+fn group_end(messages: &[ChatMessage], i: usize) -> usize {
+    (i + 1..messages.len())
+        .find(|&j| !is_tool_result(&messages[j]))
+        .unwrap_or(messages.len())
+}
+
 fn build_transcript(messages: &[ChatMessage]) -> String {
     let mut transcript = String::new();
     for msg in messages {
@@ -118,28 +170,140 @@ fn build_transcript(messages: &[ChatMessage]) -> String {
         );
     }
 
-    if transcript.chars().count() > COMPACTION_MAX_SOURCE_CHARS {
-        truncate_with_ellipsis(&transcript, COMPACTION_MAX_SOURCE_CHARS)
-    } else {
-        transcript
-    }
+    truncate_with_ellipsis(&transcript, COMPACTION_MAX_SOURCE_TOKENS)
 }
 
-fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
-    let chars: Vec<char> = text.chars().collect();
-    if chars.len() <= max_chars {
+fn truncate_with_ellipsis(text: &str, max_tokens: usize) -> String {
+    let truncated = truncate_to_tokens(text, max_tokens.saturating_sub(3));
+    if truncated.len() == text.len() {
         return text.to_string();
     }
-
-    let truncated: String = chars[..max_chars.saturating_sub(3)].iter().collect();
     format!("{}...", truncated)
 }
 
-fn count_non_system(messages: &[ChatMessage]) -> usize {
-    let has_system = messages.first().is_some_and(|m| m.role == "system");
-    if has_system {
-        messages.len().saturating_sub(1)
-    } else {
-        messages.len()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{ChatResponse, ProviderEvent, ToolCall};
+    use async_trait::async_trait;
+    use futures_util::stream::BoxStream;
+
+    struct Summarizer;
+
+    #[async_trait]
+    impl Provider for Summarizer {
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            Ok(ChatResponse {
+                text: Some("- the user is planning a trip".to_string()),
+                tool_calls: Vec::new(),
+                reasoning: None,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<BoxStream<'static, ProviderEvent>> {
+            anyhow::bail!("not streaming")
+        }
+    }
+
+    #[tokio::test]
+    async fn compacts_by_tokens_and_keeps_recent_messages() {
+        let manager = HistoryManager::new(Arc::new(Summarizer), "test".to_string(), 400);
+        let mut messages = vec![ChatMessage::system("You are helpful.")];
+        for i in 0..20 {
+            messages.push(ChatMessage::user(format!(
+                "Message {i} about the trip itinerary"
+            )));
+            messages.push(ChatMessage::assistant("Noted, ".repeat(5)));
+        }
+        assert!(manager.should_compact(&messages));
+
+        assert!(manager.compact(&mut messages).await.unwrap());
+        assert!(!manager.should_compact(&messages));
+        assert_eq!(messages[0].role, "system");
+        assert!(messages[1].content.starts_with("[Compaction summary]"));
+        assert_eq!(messages.last().unwrap().content, "Noted, ".repeat(5));
+
+        let mut long = vec![
+            ChatMessage::user("word ".repeat(400)),
+            ChatMessage::user("last"),
+        ];
+        assert!(manager.trim(&mut long));
+        assert_eq!(long.len(), 1);
+
+        let mut huge = vec![ChatMessage::user("word ".repeat(1_000))];
+        assert!(manager.trim(&mut huge));
+        assert!(!manager.should_compact(&huge));
+        assert!(huge[0].content.ends_with("..."));
+    }
+
+    /// Every tool result follows the assistant message that called it.
+    fn assert_paired(messages: &[ChatMessage]) {
+        let mut calls: Vec<&str> = Vec::new();
+        for message in messages {
+            if is_tool_result(message) {
+                let id = message.tool_call_id.as_deref().unwrap();
+                assert!(calls.contains(&id), "orphaned tool result {id}");
+            } else {
+                calls = message
+                    .tool_calls
+                    .iter()
+                    .flatten()
+                    .map(|c| c.id.as_str())
+                    .collect();
+            }
+        }
+    }
+
+    fn tool_turns(count: usize) -> Vec<ChatMessage> {
+        let mut messages = vec![
+            ChatMessage::system("You are helpful."),
+            ChatMessage::user("Read the logs"),
+        ];
+        for i in 0..count {
+            let calls: Vec<ToolCall> = (0..2)
+                .map(|j| ToolCall {
+                    id: format!("{i}-{j}"),
+                    name: "file_read".to_string(),
+                    arguments: format!(r#"{{"path": "log{i}-{j}.txt"}}"#),
+                })
+                .collect();
+            messages.push(ChatMessage::assistant_with_tool_calls(
+                "Reading".to_string(),
+                calls.clone(),
+            ));
+            for call in calls {
+                messages.push(ChatMessage::tool_result(call.id, "line ".repeat(15)));
+            }
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn compaction_keeps_tool_calls_with_their_results() {
+        for window in [300, 400, 500, 600] {
+            let manager = HistoryManager::new(Arc::new(Summarizer), "test".to_string(), window);
+            for count in 3..12 {
+                let mut messages = tool_turns(count);
+                if manager.compact(&mut messages).await.unwrap() {
+                    assert!(messages[1].content.starts_with("[Compaction summary]"));
+                }
+                assert_paired(&messages);
+
+                let mut messages = tool_turns(count);
+                manager.trim(&mut messages);
+                assert_paired(&messages);
+                assert_eq!(messages[0].role, "system");
+            }
+        }
     }
 }
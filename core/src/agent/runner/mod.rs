@@ -1,38 +1,93 @@
+mod budget;
 mod detection;
 mod execution;
 mod history;
+mod outcome;
 mod parsing;
+mod stream;
 
 use std::collections::VecDeque;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::Result;
-use tokio::sync::mpsc::Sender;
-use tracing::error;
-
-use crate::ChatRequest;
-use crate::agent::status::{StatusPrinter, StatusUpdate};
-use crate::agent::{ContextBuilder, ToolRegistry};
+use tokio_util::sync::CancellationToken;
+use tracing::field::Empty;
+use tracing::{Instrument, error, info, info_span};
+
+use crate::agent::middleware::{current_turn, in_turn, next_turn};
+use crate::agent::recovery::{self, FailureEscalation, FailureTracker, RecoveryPolicy};
+use crate::agent::tokens::{context_window, estimate_tokens, messages_tokens};
+use crate::agent::{AgentEvent, ContextBuilder, EventSink, ToolRegistry, TurnSummary};
+use crate::checkpoint::Checkpoints;
+use crate::guardrails::Guardrails;
+use crate::memory::WritePolicy;
 use crate::skills::Skill;
-use crate::traits::{ChatMessage, MemoryCategory, Provider};
-
-use detection::{detect_tool_loop, deduplicate_tool_calls};
+use crate::tools::PendingQuestion;
+use crate::traits::{
+    ChatMessage, EntryMeta, MemoryCategory, MemorySource, Provider, ToolCall, ToolProgress,
+    ToolResult, ToolSpec,
+};
+use crate::transcript::{self, SessionMeta, Transcript};
+use crate::{ChatRequest, ChatResponse};
+
+use budget::{DEFAULT_MAX_RESULT_CHARS, DEFAULT_MAX_TURN_CHARS, OutputBudget};
+use detection::{deduplicate_tool_calls, detect_tool_loop};
 use execution::ToolExecutor;
 use history::HistoryManager;
 use parsing::parse_tool_calls_fallback;
+use stream::collect_stream;
+
+pub use outcome::TurnOutcome;
+
+/// Returned when a turn is stopped through its `CancellationToken`.
+#[derive(Debug)]
+pub struct TurnCancelled;
+
+impl std::fmt::Display for TurnCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Turn cancelled")
+    }
+}
+
+impl std::error::Error for TurnCancelled {}
 
-const DEFAULT_MAX_HISTORY: usize = 50;
+/// Returned when a turn runs longer than the configured turn timeout.
+#[derive(Debug)]
+pub struct TurnTimedOut(pub Duration);
 
+impl std::fmt::Display for TurnTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Turn timed out after {}s", self.0.as_secs())
+    }
+}
+
+impl std::error::Error for TurnTimedOut {}
+
+/// Runs turns against a provider. All state of a turn lives in the call, so one loop
+/// behind an `Arc` can serve concurrent turns; per-turn state in middleware is keyed by
+/// [`TurnId`](crate::agent::TurnId). The transcript is the exception: clone the loop
+/// (cheap, everything heavy is shared) and set a transcript per session.
+#[derive(Clone)]
 pub struct AgentLoop {
     provider: Arc<dyn Provider>,
     context_builder: ContextBuilder,
     tool_registry: Arc<ToolRegistry>,
     max_iterations: usize,
-    max_history: usize,
+    turn_timeout: Option<Duration>,
+    context_window: Option<usize>,
     model_name: String,
     temperature: f64,
     parallel_tools: bool,
+    streaming: bool,
+    max_tool_output: usize,
+    max_turn_tool_output: usize,
+    write_policy: Arc<WritePolicy>,
+    transcript: Option<Arc<Transcript>>,
+    recovery: RecoveryPolicy,
+    guardrails: Option<Arc<Guardrails>>,
+    save_thinking: bool,
+    checkpoints: Option<Arc<Checkpoints>>,
 }
 
 impl AgentLoop {
@@ -46,10 +101,20 @@ impl AgentLoop {
             context_builder,
             tool_registry,
             max_iterations: 20,
-            max_history: DEFAULT_MAX_HISTORY,
+            turn_timeout: None,
+            context_window: None,
             model_name: "openai/gpt-5-mini".to_string(),
             temperature: 1.0,
             parallel_tools: true,
+            streaming: false,
+            max_tool_output: DEFAULT_MAX_RESULT_CHARS,
+            max_turn_tool_output: DEFAULT_MAX_TURN_CHARS,
+            write_policy: Arc::new(WritePolicy::All),
+            transcript: None,
+            recovery: RecoveryPolicy::default(),
+            guardrails: None,
+            save_thinking: false,
+            checkpoints: None,
         }
     }
 
@@ -63,8 +128,15 @@ impl AgentLoop {
         self
     }
 
-    pub fn with_max_history(mut self, max: usize) -> Self {
-        self.max_history = max;
+    /// Wall-clock limit for one `process` call, tool calls included.
+    pub fn with_turn_timeout(mut self, timeout: Duration) -> Self {
+        self.turn_timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the model's context window (in tokens) used to decide when to compact.
+    pub fn with_context_window(mut self, tokens: usize) -> Self {
+        self.context_window = Some(tokens);
         self
     }
 
@@ -83,12 +155,148 @@ impl AgentLoop {
         self
     }
 
-    fn emit_status(status_tx: Option<&Sender<StatusUpdate>>, status: StatusUpdate) {
-        if let Some(tx) = status_tx {
-            let _ = tx.try_send(status);
-        } else {
-            StatusPrinter::new().print(&status);
+    /// Streams replies from the provider, emitting `Token` and `Thinking` events as
+    /// they arrive. Only used when the turn has an event sink.
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Caps the characters of tool output added to the conversation, per result and per turn.
+    pub fn with_tool_output_budget(mut self, per_result: usize, per_turn: usize) -> Self {
+        self.max_tool_output = per_result;
+        self.max_turn_tool_output = per_turn;
+        self
+    }
+
+    /// Which chat messages are saved to memory. Defaults to all of them.
+    pub fn with_write_policy(mut self, policy: WritePolicy) -> Self {
+        self.write_policy = Arc::new(policy);
+        self
+    }
+
+    /// Appends every message of each turn to the session transcript.
+    pub fn with_transcript(mut self, transcript: Arc<Transcript>) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    /// Also records the model's reasoning in the transcript, as `thinking` records.
+    pub fn with_thinking_in_transcript(mut self, save: bool) -> Self {
+        self.save_thinking = save;
+        self
+    }
+
+    /// How failed tool calls are handled: hints for the model, and what to do when
+    /// they keep failing.
+    pub fn with_recovery(mut self, policy: RecoveryPolicy) -> Self {
+        self.recovery = policy;
+        self
+    }
+
+    /// Checks each message and final answer. Tool calls are checked when the same
+    /// guardrails are added to the tool registry as middleware.
+    pub fn with_guardrails(mut self, guardrails: Arc<Guardrails>) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    /// Starts a new workspace checkpoint with each turn. The same checkpoints must be
+    /// added to the tool registry as middleware to take the snapshots.
+    pub fn with_checkpoints(mut self, checkpoints: Arc<Checkpoints>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    pub fn checkpoints(&self) -> Option<&Arc<Checkpoints>> {
+        self.checkpoints.as_ref()
+    }
+
+    /// Switches to another session, such as a branch made by `Transcript::branch`.
+    pub fn set_transcript(&mut self, transcript: Arc<Transcript>) {
+        self.transcript = Some(transcript);
+    }
+
+    pub fn transcript(&self) -> Option<&Arc<Transcript>> {
+        self.transcript.as_ref()
+    }
+
+    pub fn tool_specs(&self) -> Vec<ToolSpec> {
+        self.tool_registry.get_specs()
+    }
+
+    /// A copy that can only use the tools in `names`.
+    pub fn restricted_to(&self, names: &[String]) -> AgentLoop {
+        let tool_registry = Arc::new(self.tool_registry.subset(names));
+        let mut agent = self.clone();
+        agent.context_builder = agent
+            .context_builder
+            .with_tool_specs(tool_registry.get_specs());
+        agent.tool_registry = tool_registry;
+        agent
+    }
+
+    /// Titles and summarizes the session and saves the result with its transcript. The
+    /// summary is also stored in memory as one entry, unless the write policy is `Off`.
+    /// `None` when there is no transcript or nothing was said yet.
+    pub async fn finish_session(&self) -> Result<Option<SessionMeta>> {
+        let Some(session) = &self.transcript else {
+            return Ok(None);
+        };
+        let records = session.records()?;
+        if !records.iter().any(|r| r.message.role == "user") {
+            return Ok(None);
+        }
+        let meta = transcript::summarize(self.provider.as_ref(), &self.model_name, &records).await?;
+        session.write_meta(&meta)?;
+
+        if !matches!(*self.write_policy, WritePolicy::Off)
+            && let Some(memory) = &self.context_builder.memory
+        {
+            let entry = EntryMeta {
+                tags: vec!["session".to_string()],
+                source: Some(MemorySource::Agent),
+                metadata: [("session".to_string(), session.session().to_string())].into(),
+            };
+            memory
+                .store_with_meta(
+                    &format!("session_{}", session.session()),
+                    &format!("{}: {}", meta.title, meta.summary),
+                    MemoryCategory::Daily,
+                    None,
+                    &entry,
+                )
+                .await?;
         }
+        Ok(Some(meta))
+    }
+
+    fn tool_progress(events: &EventSink, tool_name: &str) -> ToolProgress {
+        let events = events.clone();
+        let tool_name = tool_name.to_string();
+        ToolProgress::new(move |message| {
+            events.emit(AgentEvent::tool_progress(&tool_name, message));
+        })
+    }
+
+    fn reflect(&self, failures: &mut FailureTracker, tool_name: &str, result: &mut ToolResult) {
+        failures.record(tool_name, result.success, result.error.as_deref());
+        if !self.recovery.hints {
+            return;
+        }
+        if let Some(error) = &mut result.error
+            && let Some(hint) = recovery::hint(error)
+        {
+            error.push_str("\nHint: ");
+            error.push_str(hint);
+        }
+    }
+
+    fn pending_question(tool_name: &str, output: &str) -> Option<PendingQuestion> {
+        if tool_name != "ask_user" {
+            return None;
+        }
+        PendingQuestion::from_output(output)
     }
 
     async fn store_message(&self, role: &str, content: &str) {
@@ -104,14 +312,28 @@ impl AgentLoop {
                 .to_string();
             let content = content.to_string();
             let role = role.to_string();
+            let policy = self.write_policy.clone();
 
             drop(tokio::spawn(async move {
+                let Some(verdict) = policy.evaluate(&role, &content).await else {
+                    return;
+                };
+                let meta = EntryMeta {
+                    tags: verdict.tags,
+                    source: Some(if role == "user" {
+                        MemorySource::User
+                    } else {
+                        MemorySource::Agent
+                    }),
+                    metadata: Default::default(),
+                };
                 if let Err(e) = memory
-                    .store(
+                    .store_with_meta(
                         &format!("msg_{}_{:x}", role, md5::compute(content.as_bytes())),
                         &content,
-                        MemoryCategory::Daily,
+                        verdict.category,
                         Some(&timestamp),
+                        &meta,
                     )
                     .await
                 {
@@ -121,136 +343,570 @@ impl AgentLoop {
         }
     }
 
-    pub async fn process(&self, message: &str) -> Result<String> {
-        self.process_with_status(message, None).await
+    async fn record(&self, messages: &[ChatMessage]) {
+        if let Some(transcript) = &self.transcript
+            && let Err(e) = transcript.append(messages).await
+        {
+            error!("Failed to write transcript: {}", e);
+        }
+    }
+
+    pub async fn process(&self, message: &str) -> TurnOutcome {
+        self.process_with_events(message, vec![], EventSink::none()).await
+    }
+
+    pub async fn process_with_history(
+        &self,
+        message: &str,
+        history: Vec<ChatMessage>,
+    ) -> TurnOutcome {
+        self.process_with_events(message, history, EventSink::none()).await
     }
 
-    pub async fn process_with_status(
+    /// Runs one turn, reporting progress to `events`. The turn ends with a
+    /// `TurnComplete`, `Cancelled` or `Error` event, followed by a `TurnSummary`.
+    pub async fn process_with_events(
         &self,
         message: &str,
-        status_tx: Option<Sender<StatusUpdate>>,
-    ) -> Result<String> {
-        let history = vec![];
-        self.process_with_history_and_status(message, history, status_tx).await
+        history: Vec<ChatMessage>,
+        events: impl Into<EventSink>,
+    ) -> TurnOutcome {
+        self.process_cancellable(message, history, events, CancellationToken::new())
+            .await
     }
 
-    pub async fn process_with_history(
+    /// Like `process_with_events`, but stops at the next model call or tool call once
+    /// `cancel` fires. In-flight requests are dropped and running commands are killed.
+    pub async fn process_cancellable(
         &self,
         message: &str,
         history: Vec<ChatMessage>,
-    ) -> Result<String> {
-        self.process_with_history_and_status(message, history, None).await
+        events: impl Into<EventSink>,
+        cancel: CancellationToken,
+    ) -> TurnOutcome {
+        let events = events.into();
+        let started = Instant::now();
+        let mut summary = TurnSummary {
+            max_iterations: self.max_iterations,
+            ..Default::default()
+        };
+        // A nested agent's tool calls count towards the turn that spawned it.
+        let (turn, outermost) = match current_turn() {
+            Some(turn) => (turn, false),
+            None => (next_turn(), true),
+        };
+        let span = info_span!(
+            "turn",
+            turn,
+            model = %self.model_name,
+            iterations = Empty,
+            tool_calls = Empty,
+            input_tokens = Empty,
+            output_tokens = Empty,
+            outcome = Empty,
+        );
+        let run = self.run_turn(message, history, &events, &cancel, &mut summary);
+        let result = in_turn(turn, async {
+            match self.turn_timeout {
+                Some(limit) => tokio::time::timeout(limit, run)
+                    .await
+                    .unwrap_or_else(|_| Err(TurnTimedOut(limit).into())),
+                None => run.await,
+            }
+        })
+        .instrument(span.clone())
+        .await;
+        if outermost {
+            if let Some(guardrails) = &self.guardrails {
+                guardrails.end_turn(turn);
+            }
+            if let Some(checkpoints) = &self.checkpoints {
+                checkpoints.end_turn(turn);
+            }
+        }
+        summary.elapsed_ms = started.elapsed().as_millis() as u64;
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(e) if e.is::<TurnCancelled>() => TurnOutcome::Cancelled,
+            Err(e) => TurnOutcome::Error(e),
+        };
+        match &outcome {
+            TurnOutcome::Completed { text, .. } => {
+                events.emit(AgentEvent::TurnComplete { text: text.clone() })
+            }
+            TurnOutcome::MaxIterations { .. } => events.emit(AgentEvent::Error {
+                message: format!(
+                    "Stopped after {} iterations without a final answer",
+                    summary.iterations
+                ),
+            }),
+            TurnOutcome::Cancelled => events.emit(AgentEvent::Cancelled),
+            TurnOutcome::Error(e) => events.emit(AgentEvent::Error {
+                message: e.to_string(),
+            }),
+        }
+        span.record("iterations", summary.iterations);
+        span.record("tool_calls", summary.tool_calls);
+        span.record("input_tokens", summary.input_tokens);
+        span.record("output_tokens", summary.output_tokens);
+        span.record("outcome", outcome.label());
+        span.in_scope(|| info!("Turn finished: {}", summary));
+        events.emit(AgentEvent::TurnSummary(summary));
+        outcome
+    }
+
+    /// The answer, or a notice in its place when a guardrail blocks it.
+    async fn guard_response(&self, text: String, events: &EventSink) -> String {
+        let Some(guardrails) = &self.guardrails else {
+            return text;
+        };
+        match guardrails.check_response(&text).await {
+            Ok(()) => text,
+            Err(reason) => {
+                let notice = format!("[Response withheld by guardrail: {}]", reason);
+                events.emit(AgentEvent::Error {
+                    message: notice.clone(),
+                });
+                notice
+            }
+        }
     }
 
-    pub async fn process_with_history_and_status(
+    async fn chat(&self, request: ChatRequest<'_>, events: &EventSink) -> Result<ChatResponse> {
+        if self.streaming && events.is_active() && self.provider.supports_streaming() {
+            let stream = self
+                .provider
+                .chat_stream(request, &self.model_name, self.temperature)
+                .await?;
+            Ok(collect_stream(stream, events).await)
+        } else {
+            self.provider
+                .chat(request, &self.model_name, self.temperature)
+                .await
+        }
+    }
+
+    async fn run_turn(
         &self,
         message: &str,
         history: Vec<ChatMessage>,
-        status_tx: Option<Sender<StatusUpdate>>,
-    ) -> Result<String> {
+        events: &EventSink,
+        cancel: &CancellationToken,
+        summary: &mut TurnSummary,
+    ) -> Result<TurnOutcome> {
+        if let Some(guardrails) = &self.guardrails
+            && let Err(reason) = guardrails.check_prompt(message).await
+        {
+            anyhow::bail!("Message blocked by guardrail: {}", reason);
+        }
         self.store_message("user", message).await;
 
         let mut messages = self.context_builder.build_messages(history, message).await;
-        let mut iterations = 0;
+        self.record(&messages[messages.len() - 1..]).await;
         let mut recent_tool_calls: VecDeque<detection::ToolCallSignature> = VecDeque::new();
         let executor = ToolExecutor::new(self.tool_registry.clone());
+        let mut budget = OutputBudget::new(self.max_tool_output, self.max_turn_tool_output);
+        let mut failures = FailureTracker::default();
+        let mut empty_retries = 0;
+        let mut thinking = Vec::new();
+        let mut partial = None;
+        let tool_tokens = estimate_tokens(
+            &serde_json::to_string(&self.tool_registry.get_specs()).unwrap_or_default(),
+        );
         let history_manager = HistoryManager::new(
             self.provider.clone(),
             self.model_name.clone(),
-            self.max_history,
-        );
+            self.context_window
+                .unwrap_or_else(|| context_window(&self.model_name)),
+        )
+        .with_reserved(tool_tokens);
 
-        Self::emit_status(status_tx.as_ref(), StatusUpdate::thinking("Processing..."));
+        events.emit(AgentEvent::status("Processing..."));
 
-        while iterations < self.max_iterations {
-            iterations += 1;
+        while summary.iterations < self.max_iterations {
+            summary.iterations += 1;
+
+            // Keep every request inside the model's window rather than letting the API reject it.
+            if history_manager.should_compact(&messages) {
+                let _ = history_manager.compact(&mut messages).await;
+                history_manager.trim(&mut messages);
+            }
 
             let tools = self.tool_registry.get_specs();
             let request = ChatRequest {
                 messages: &messages,
                 tools: if tools.is_empty() { None } else { Some(&tools) },
             };
-
-            let response = self.provider.chat(request, &self.model_name, self.temperature).await?;
+            let input_tokens = messages_tokens(&messages) + tool_tokens;
+            summary.input_tokens += input_tokens;
+
+            let span = info_span!(
+                "model_call",
+                iteration = summary.iterations,
+                input_tokens,
+                output_tokens = Empty,
+                tool_calls = Empty,
+                latency_ms = Empty,
+            );
+            let started = Instant::now();
+            let response = cancel
+                .run_until_cancelled(self.chat(request, events).instrument(span.clone()))
+                .await
+                .ok_or(TurnCancelled)??;
+            let output_tokens = estimate_tokens(response.text.as_deref().unwrap_or_default())
+                + response
+                    .tool_calls
+                    .iter()
+                    .map(|c| estimate_tokens(&c.name) + estimate_tokens(&c.arguments))
+                    .sum::<usize>();
+            summary.output_tokens += output_tokens;
+            span.record("output_tokens", output_tokens);
+            span.record("tool_calls", response.tool_calls.len());
+            span.record("latency_ms", started.elapsed().as_millis() as u64);
+
+            if let Some(reasoning) = response.reasoning.as_ref().filter(|r| !r.trim().is_empty()) {
+                if self.save_thinking {
+                    self.record(&[ChatMessage {
+                        role: transcript::THINKING_ROLE.to_string(),
+                        content: reasoning.clone(),
+                        tool_calls: None,
+                        tool_call_id: None,
+                    }])
+                    .await;
+                }
+                thinking.push(reasoning.clone());
+            }
 
             let (assistant_text, tool_calls) = if response.has_tool_calls() {
                 (
                     response.text.clone().unwrap_or_default(),
                     response.tool_calls.clone(),
                 )
-            } else if let Some(text) = &response.text {
-                parse_tool_calls_fallback(text)
             } else {
-                return Ok("No response from provider".to_string());
+                parse_tool_calls_fallback(response.text.as_deref().unwrap_or_default())
             };
 
             if tool_calls.is_empty() {
-                if !assistant_text.is_empty() {
-                    messages.push(ChatMessage::assistant(assistant_text.clone()));
-                    self.store_message("assistant", &assistant_text).await;
-                    return Ok(assistant_text);
-                } else {
-                    anyhow::bail!("Empty response from model. Please try again.");
+                if assistant_text.trim().is_empty() {
+                    if empty_retries >= self.recovery.empty_response_retries {
+                        anyhow::bail!("Empty response from model. Please try again.");
+                    }
+                    if empty_retries == 0 {
+                        messages.push(ChatMessage::user(recovery::EMPTY_RESPONSE_NUDGE));
+                    }
+                    empty_retries += 1;
+                    events.emit(AgentEvent::status("⚠ Empty response from model, retrying"));
+                    continue;
                 }
+                let assistant_text = self.guard_response(assistant_text, events).await;
+                messages.push(ChatMessage::assistant(assistant_text.clone()));
+                self.record(&messages[messages.len() - 1..]).await;
+                self.store_message("assistant", &assistant_text).await;
+                return Ok(TurnOutcome::Completed {
+                    text: assistant_text,
+                    thinking,
+                });
             }
 
             if let Some(loop_msg) = detect_tool_loop(&mut recent_tool_calls, &tool_calls) {
-                Self::emit_status(status_tx.as_ref(), StatusUpdate::status(format!("⚠ {}", loop_msg)));
+                events.emit(AgentEvent::status(format!("⚠ {}", loop_msg)));
                 anyhow::bail!("{}", loop_msg);
             }
 
             let (tool_calls, duplicates) = deduplicate_tool_calls(&tool_calls);
             for (name, _id) in &duplicates {
-                Self::emit_status(
-                    status_tx.as_ref(),
-                    StatusUpdate::status(format!(
-                        "⚠ Skipped duplicate tool call '{}' with identical arguments",
-                        name
-                    )),
-                );
+                events.emit(AgentEvent::status(format!(
+                    "⚠ Skipped duplicate tool call '{}' with identical arguments",
+                    name
+                )));
             }
 
             messages.push(ChatMessage::assistant_with_tool_calls(
                 assistant_text.clone(),
                 tool_calls.clone(),
             ));
+            let turn_start = messages.len() - 1;
 
             if !assistant_text.trim().is_empty() {
                 self.store_message("assistant", &assistant_text).await;
+                partial = Some(assistant_text.clone());
             }
 
+            let mut pending_question = None;
+            let mut finish = |tool_call: &ToolCall, mut result: ToolResult| {
+                budget.apply(&tool_call.name, &mut result);
+                self.reflect(&mut failures, &tool_call.name, &mut result);
+                summary.tool_calls += 1;
+                summary.failed_tool_calls += usize::from(!result.success);
+                let result_json = serde_json::to_string(&result).unwrap_or_default();
+                events.emit(AgentEvent::tool_finished(
+                    &tool_call.name,
+                    result.success,
+                    &result_json,
+                ));
+                if pending_question.is_none() {
+                    pending_question = Self::pending_question(&tool_call.name, &result.output);
+                }
+                messages.push(ChatMessage::tool_result(tool_call.id.clone(), result_json));
+            };
             if self.parallel_tools && tool_calls.len() > 1 {
-                let results = executor.execute_batch(&tool_calls).await;
-                for (tool_call, result) in tool_calls.iter().zip(results.iter()) {
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_started(&tool_call.name));
-                    let result_json = serde_json::to_string(&result).unwrap_or_default();
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_result(&tool_call.name, &result_json));
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_completed(&tool_call.name, result.success));
-                    messages.push(ChatMessage::tool_result(
-                        tool_call.id.clone(),
-                        result_json,
-                    ));
+                let mut progress = Vec::with_capacity(tool_calls.len());
+                for tool_call in &tool_calls {
+                    events.emit(AgentEvent::tool_started(&tool_call.name));
+                    progress.push(Self::tool_progress(events, &tool_call.name));
+                }
+                let results = cancel
+                    .run_until_cancelled(executor.execute_batch(&tool_calls, progress))
+                    .await
+                    .ok_or(TurnCancelled)?;
+                for (tool_call, result) in tool_calls.iter().zip(results) {
+                    finish(tool_call, result);
                 }
             } else {
-                for tool_call in tool_calls.clone() {
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_started(&tool_call.name));
-                    let result = executor.execute(&tool_call).await;
-                    let result_json = serde_json::to_string(&result).unwrap_or_default();
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_result(&tool_call.name, &result_json));
-                    Self::emit_status(status_tx.as_ref(), StatusUpdate::tool_completed(&tool_call.name, result.success));
-                    messages.push(ChatMessage::tool_result(
-                        tool_call.id,
-                        result_json,
-                    ));
+                for tool_call in &tool_calls {
+                    events.emit(AgentEvent::tool_started(&tool_call.name));
+                    let progress = Self::tool_progress(events, &tool_call.name);
+                    let result = cancel
+                        .run_until_cancelled(executor.execute(tool_call, progress))
+                        .await
+                        .ok_or(TurnCancelled)?;
+                    finish(tool_call, result);
                 }
             }
 
-            if history_manager.should_compact(&messages) {
-                let _ = history_manager.compact(&mut messages).await;
-                history_manager.trim(&mut messages);
+            self.record(&messages[turn_start..]).await;
+
+            if executor.too_many_malformed() {
+                anyhow::bail!(
+                    "The model sent malformed tool arguments {} times in this turn",
+                    executor.malformed_count()
+                );
+            }
+
+            if let Some(question) = pending_question {
+                events.emit(AgentEvent::question(&question.question, question.options.clone()));
+                let text = question.render();
+                self.record(&[ChatMessage::assistant(text.clone())]).await;
+                self.store_message("assistant", &text).await;
+                return Ok(TurnOutcome::Completed { text, thinking });
             }
+
+            let max_failures = self.recovery.max_consecutive_failures;
+            if max_failures > 0 && failures.consecutive() >= max_failures {
+                match self.recovery.on_repeated_failure {
+                    FailureEscalation::Continue => {}
+                    FailureEscalation::Ask => {
+                        let text = failures.question();
+                        events.emit(AgentEvent::question(&text, Vec::new()));
+                        self.record(&[ChatMessage::assistant(text.clone())]).await;
+                        self.store_message("assistant", &text).await;
+                        return Ok(TurnOutcome::Completed { text, thinking });
+                    }
+                    FailureEscalation::Abort => {
+                        anyhow::bail!(
+                            "Stopped after {} failed tool calls in a row",
+                            failures.consecutive()
+                        );
+                    }
+                }
+            }
+
+            if cancel.is_cancelled() {
+                return Err(TurnCancelled.into());
+            }
+        }
+
+        Ok(TurnOutcome::MaxIterations { partial })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockProvider;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    fn agent(workspace: &std::path::Path, delay: Duration) -> AgentLoop {
+        AgentLoop::new(
+            Arc::new(MockProvider::new().with_text("done").with_delay(delay)),
+            ContextBuilder::new(workspace),
+            Arc::new(ToolRegistry::new()),
+        )
+        .with_turn_timeout(Duration::from_millis(200))
+    }
+
+    #[tokio::test]
+    async fn summarizes_turns_and_enforces_timeout() {
+        let summaries = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let summaries = summaries.clone();
+            EventSink::new(move |event| {
+                if let AgentEvent::TurnSummary(summary) = event {
+                    summaries.lock().unwrap().push(summary);
+                }
+            })
+        };
+
+        let workspace = tempfile::TempDir::new().unwrap();
+        let text = agent(workspace.path(), Duration::ZERO)
+            .process_with_events("hi", vec![], sink.clone())
+            .await
+            .into_result()
+            .unwrap();
+        assert_eq!(text, "done");
+
+        let err = agent(workspace.path(), Duration::from_secs(5))
+            .process_with_events("hi", vec![], sink)
+            .await
+            .into_result()
+            .unwrap_err();
+        assert!(err.is::<TurnTimedOut>());
+
+        let summaries = summaries.lock().unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].iterations, 1);
+        assert_eq!(summaries[0].tool_calls, 0);
+        assert!(summaries[0].input_tokens > 0 && summaries[0].output_tokens > 0);
+        assert!(summaries[1].elapsed_ms >= 200);
+    }
+
+    #[tokio::test]
+    async fn reports_max_iterations_with_partial_text() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let provider = MockProvider::new();
+        for n in 0..2 {
+            provider.push_response(ChatResponse {
+                text: Some(format!("Checking page {n}")),
+                tool_calls: vec![ToolCall {
+                    id: n.to_string(),
+                    name: "fetch".to_string(),
+                    arguments: format!(r#"{{"page": {n}}}"#),
+                }],
+                reasoning: None,
+            });
+        }
+        let outcome = AgentLoop::new(
+            Arc::new(provider),
+            ContextBuilder::new(workspace.path()),
+            Arc::new(ToolRegistry::new()),
+        )
+        .with_max_iterations(2)
+        .process("read everything")
+        .await;
+
+        assert!(!outcome.is_completed());
+        assert_eq!(outcome.text(), Some("Checking page 1"));
+        assert!(matches!(outcome, TurnOutcome::MaxIterations { .. }));
+    }
+
+    #[tokio::test]
+    async fn retries_empty_responses_with_a_nudge() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        // `empty` replies with only reasoning, then an answer.
+        let run = |empty: usize| {
+            let provider = Arc::new(MockProvider::new());
+            for n in 0..=empty {
+                provider.push_response(ChatResponse {
+                    text: (n == empty).then(|| "done".to_string()),
+                    reasoning: Some(format!("attempt {n}")),
+                    ..Default::default()
+                });
+            }
+            let agent = AgentLoop::new(
+                provider.clone(),
+                ContextBuilder::new(workspace.path()),
+                Arc::new(ToolRegistry::new()),
+            );
+            async move { (agent.process("hi").await, provider) }
+        };
+
+        let (outcome, provider) = run(2).await;
+        assert_eq!(outcome.thinking(), ["attempt 0", "attempt 1", "attempt 2"]);
+        assert_eq!(outcome.into_result().unwrap(), "done");
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 3);
+        let last = requests[2].messages.last().unwrap();
+        assert_eq!(last.content, recovery::EMPTY_RESPONSE_NUDGE);
+
+        let (outcome, provider) = run(3).await;
+        assert!(outcome.into_result().is_err());
+        assert_eq!(provider.requests().len(), 3);
+    }
+
+    /// Echoes its text once all the turns sharing `Barrier` have called it.
+    struct Echo(tokio::sync::Barrier);
+
+    #[async_trait]
+    impl crate::traits::Tool for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echoes text"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<ToolResult> {
+            self.0.wait().await;
+            Ok(ToolResult::success(args["text"].as_str().unwrap_or_default()))
+        }
+    }
+
+    /// Records which turn made each call.
+    #[derive(Default)]
+    struct Turns(Mutex<Vec<(crate::agent::TurnId, String)>>);
+
+    #[async_trait]
+    impl crate::agent::ToolMiddleware for Turns {
+        async fn before_execute(&self, call: &mut crate::agent::ToolCall) -> Option<ToolResult> {
+            let text = call.args["text"].as_str().unwrap_or_default().to_string();
+            self.0.lock().unwrap().push((call.turn, text));
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_concurrent_turns_on_one_loop() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let turns = Arc::new(Turns::default());
+        let registry = ToolRegistry::new().with_middleware(turns.clone());
+        registry.register(Box::new(Echo(tokio::sync::Barrier::new(8))));
+        // Every turn calls the tool before any gets its answer.
+        let provider = MockProvider::new();
+        for i in 0..8 {
+            let args = serde_json::json!({ "text": format!("call {i}") });
+            provider.push_tool_calls(&[("echo", args)]);
+        }
+        for _ in 0..8 {
+            provider.push_text("done");
+        }
+        let agent = Arc::new(AgentLoop::new(
+            Arc::new(provider),
+            ContextBuilder::new(workspace.path()),
+            Arc::new(registry),
+        ));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let agent = agent.clone();
+                tokio::spawn(async move { agent.process(&format!("session {i}")).await })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().into_result().unwrap(), "done");
         }
 
-        Ok("Max iterations reached".to_string())
+        let calls = turns.0.lock().unwrap();
+        assert_eq!(calls.len(), 8);
+        let mut ids: Vec<_> = calls.iter().map(|(turn, _)| *turn).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 8);
+        assert!(!ids.contains(&0));
     }
 }
@@ -0,0 +1,62 @@
+use anyhow::Result;
+
+use super::TurnCancelled;
+
+/// How a turn ended.
+#[derive(Debug)]
+pub enum TurnOutcome {
+    /// The model gave a final answer or asked the user a question. `thinking` holds the
+    /// reasoning of each model call that returned any, in order.
+    Completed { text: String, thinking: Vec<String> },
+    /// The turn used all its iterations without a final answer. `partial` is the last
+    /// text the model wrote alongside its tool calls, if any.
+    MaxIterations { partial: Option<String> },
+    Cancelled,
+    Error(anyhow::Error),
+}
+
+impl TurnOutcome {
+    pub fn is_completed(&self) -> bool {
+        matches!(self, TurnOutcome::Completed { .. })
+    }
+
+    /// A short name for logs and traces.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TurnOutcome::Completed { .. } => "completed",
+            TurnOutcome::MaxIterations { .. } => "max_iterations",
+            TurnOutcome::Cancelled => "cancelled",
+            TurnOutcome::Error(_) => "error",
+        }
+    }
+
+    /// The answer, or the partial text when the turn ran out of iterations.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            TurnOutcome::Completed { text, .. } => Some(text),
+            TurnOutcome::MaxIterations { partial } => partial.as_deref(),
+            TurnOutcome::Cancelled | TurnOutcome::Error(_) => None,
+        }
+    }
+
+    /// The model's reasoning during a completed turn.
+    pub fn thinking(&self) -> &[String] {
+        match self {
+            TurnOutcome::Completed { thinking, .. } => thinking,
+            _ => &[],
+        }
+    }
+
+    /// The answer of a completed turn; every other outcome becomes an error.
+    /// Cancelled turns give a `TurnCancelled` error.
+    pub fn into_result(self) -> Result<String> {
+        match self {
+            TurnOutcome::Completed { text, .. } => Ok(text),
+            TurnOutcome::MaxIterations { .. } => {
+                anyhow::bail!("Max iterations reached without a final answer")
+            }
+            TurnOutcome::Cancelled => Err(TurnCancelled.into()),
+            TurnOutcome::Error(e) => Err(e),
+        }
+    }
+}
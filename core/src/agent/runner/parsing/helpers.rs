@@ -34,16 +34,37 @@ pub const SHELL_COMMAND_ALIASES: &[&str] = &[
 pub fn map_tool_name_alias(tool_name: &str) -> &str {
     match tool_name {
         "shell" | "bash" | "sh" | "exec" | "command" | "cmd" => "shell",
+        "runcode" | "run_code" | "python" | "execute_code" | "code_interpreter" => "run_code",
+        "envinfo" | "env_info" | "environment" | "system_info" | "sysinfo" => "env_info",
+        "calc" | "calculator" | "calculate" | "math" | "eval_math" | "bc" => "calc",
         "fileread" | "file_read" | "readfile" | "read_file" | "file" => "file_read",
         "filewrite" | "file_write" | "writefile" | "write_file" => "file_write",
         "fileedit" | "file_edit" | "editfile" | "edit_file" => "file_edit",
+        "diff" | "file_diff" | "compare" | "compare_files" => "diff",
+        "applypatch" | "apply_patch" | "patch" | "apply_diff" => "apply_patch",
         "memoryrecall" | "memory_recall" | "recall" | "memrecall" => "memory_read",
         "memorystore" | "memory_store" | "store" | "memstore" => "memory_write",
+        "memorylist" | "memory_list" | "list_memories" | "memlist" => "memory_list",
+        "memoryforget" | "memory_forget" | "forget" | "memory_delete" | "memforget" => "memory_forget",
+        "tree" | "list_dir" | "listdir" | "ls" | "list_directory" => "tree",
         "globsearch" | "glob_search" | "glob" | "findfiles" | "find_files" => "glob_search",
         "contentsearch" | "content_search" | "grep" | "search" => "content_search",
+        "dataquery" | "data_query" | "query_data" | "csv" => "data_query",
         "httprequest" | "http_request" | "http" | "fetch" | "curl" | "wget" => "http_request",
         "webfetch" | "web_fetch" | "fetchurl" | "fetch_url" => "web_fetch",
+        "download" | "download_file" | "downloadfile" => "download",
+        "archivecreate" | "archive_create" | "zip" | "tar" | "compress" => "archive_create",
+        "archiveextract" | "archive_extract" | "unzip" | "untar" | "extract" => "archive_extract",
+        "askuser" | "ask_user" | "ask" | "clarify" | "ask_question" => "ask_user",
+        "spawnagent" | "spawn_agent" | "subagent" | "sub_agent" | "delegate" => "spawn_agent",
+        "scheduletask" | "schedule_task" | "schedule" | "remind" | "cron" => "schedule_task",
+        "listschedules" | "list_schedules" | "schedules" => "list_schedules",
+        "todo" | "todos" | "todo_list" | "plan" | "task_list" => "todo",
         "gitoperations" | "git_operations" | "git" => "git_operations",
+        "gitstatus" | "git_status" => "git_status",
+        "gitdiff" | "git_diff" => "git_diff",
+        "gitlog" | "git_log" => "git_log",
+        "gitcommit" | "git_commit" => "git_commit",
         _ => tool_name,
     }
 }
@@ -54,6 +75,15 @@ pub fn default_param_for_tool(tool: &str) -> &'static str {
         "file_read" | "file_write" => "path",
         "memory_read" => "query",
         "memory_write" => "content",
+        "memory_forget" => "keys",
+        "run_code" => "code",
+        "calc" => "expression",
+        "apply_patch" => "patch",
+        "todo" => "action",
+        "ask_user" => "question",
+        "spawn_agent" => "task",
+        "schedule_task" => "prompt",
+        "tree" | "diff" => "path",
         _ => "input",
     }
 }
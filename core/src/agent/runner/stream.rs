@@ -0,0 +1,83 @@
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+
+use crate::agent::{AgentEvent, EventSink};
+use crate::traits::{ChatResponse, ProviderEvent};
+
+/// Collects a streamed reply into a response, forwarding text and reasoning as they arrive.
+pub async fn collect_stream(
+    mut stream: BoxStream<'static, ProviderEvent>,
+    events: &EventSink,
+) -> ChatResponse {
+    let mut text = String::new();
+    let mut reasoning = String::new();
+    let mut tool_calls = Vec::new();
+
+    while let Some(event) = stream.next().await {
+        match event {
+            ProviderEvent::Token(token) => {
+                text.push_str(&token);
+                events.emit(AgentEvent::Token { text: token });
+            }
+            ProviderEvent::Thinking(thought) => {
+                reasoning.push_str(&thought);
+                events.emit(AgentEvent::Thinking { text: thought });
+            }
+            ProviderEvent::ToolCall(call) => tool_calls.push(call),
+            ProviderEvent::Done => break,
+        }
+    }
+
+    // Like `chat`, fall back to the reasoning when the model gave no answer text.
+    let reasoning = (!reasoning.trim().is_empty()).then_some(reasoning);
+    let text = if !text.trim().is_empty() {
+        Some(text)
+    } else {
+        reasoning.clone()
+    };
+    ChatResponse {
+        text,
+        tool_calls,
+        reasoning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ToolCall;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn collects_tokens_and_tool_calls() {
+        let stream = futures_util::stream::iter([
+            ProviderEvent::Thinking("plan".to_string()),
+            ProviderEvent::Token("Hel".to_string()),
+            ProviderEvent::Token("lo".to_string()),
+            ProviderEvent::ToolCall(ToolCall {
+                id: "1".to_string(),
+                name: "calc".to_string(),
+                arguments: "{}".to_string(),
+            }),
+            ProviderEvent::Done,
+        ])
+        .boxed();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let seen = seen.clone();
+            EventSink::new(move |event| seen.lock().unwrap().push(event))
+        };
+        let response = collect_stream(stream, &sink).await;
+
+        assert_eq!(response.text.as_deref(), Some("Hello"));
+        assert_eq!(response.reasoning.as_deref(), Some("plan"));
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(
+            seen.lock().unwrap()[1],
+            AgentEvent::Token {
+                text: "Hel".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::traits::ChatMessage;
+use std::collections::BTreeMap;
+
+/// Per-message overhead of the chat format (role, separators).
+const MESSAGE_OVERHEAD: usize = 4;
+const DEFAULT_CONTEXT_WINDOW: usize = 32_768;
+
+/// Context window sizes by model name fragment; the first match wins.
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("gpt-4.1", 1_047_576),
+    ("gpt-5", 400_000),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5", 16_385),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    ("claude", 200_000),
+    ("gemini", 1_048_576),
+    ("glm-4", 128_000),
+    ("deepseek", 128_000),
+    ("llama3", 128_000),
+    ("llama-3", 128_000),
+    ("qwen", 32_768),
+    ("mistral", 32_768),
+];
+
+/// Tokens the model can attend to, by model name. Unknown models get 32k.
+pub fn context_window(model: &str) -> usize {
+    ContextWindows::default().window(model)
+}
+
+/// Context window sizes per model: user overrides first, then the built-in table.
+#[derive(Debug, Clone, Default)]
+pub struct ContextWindows {
+    overrides: Vec<(String, usize)>,
+}
+
+impl ContextWindows {
+    /// Keys are full model names (`openai/gpt-4o`) or name prefixes (`llama3`).
+    pub fn with_overrides(overrides: &BTreeMap<String, usize>) -> Self {
+        let mut overrides: Vec<(String, usize)> = overrides
+            .iter()
+            .map(|(model, window)| (model.to_lowercase(), *window))
+            .collect();
+        // Longer keys are more specific.
+        overrides.sort_by_key(|(model, _)| std::cmp::Reverse(model.len()));
+        Self { overrides }
+    }
+
+    pub fn window(&self, model: &str) -> usize {
+        let model = model.to_lowercase();
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        let matches = |fragment: &str| {
+            model == fragment
+                || name.starts_with(fragment)
+                || name.contains(&format!("-{fragment}"))
+        };
+        self.overrides
+            .iter()
+            .map(|(fragment, window)| (fragment.as_str(), *window))
+            .chain(CONTEXT_WINDOWS.iter().copied())
+            .find(|(fragment, _)| matches(fragment))
+            .map(|(_, window)| window)
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    }
+}
+
+/// Approximates a BPE tokenizer: short English words are one token, long ones a
+/// token per six letters, numbers a token per three digits, punctuation and CJK
+/// characters a token each.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut counter = Counter::default();
+    text.chars().for_each(|c| counter.push(c));
+    counter.total()
+}
+
+#[derive(Default)]
+struct Counter {
+    tokens: usize,
+    letters: usize,
+    digits: usize,
+}
+
+impl Counter {
+    fn push(&mut self, c: char) {
+        if c.is_ascii_alphabetic() {
+            self.letters += 1;
+        } else if c.is_ascii_digit() {
+            self.digits += 1;
+        } else {
+            self.tokens = self.total() + char_tokens(c);
+            self.letters = 0;
+            self.digits = 0;
+        }
+    }
+
+    fn total(&self) -> usize {
+        let word = if self.letters > 0 {
+            1 + (self.letters - 1) / 6
+        } else {
+            0
+        };
+        self.tokens + word + self.digits.div_ceil(3)
+    }
+}
+
+fn char_tokens(c: char) -> usize {
+    if c.is_whitespace() {
+        0
+    } else if c.is_ascii() || is_cjk(c) {
+        1
+    } else {
+        // Accented letters and other scripts usually take a token per one or two chars.
+        usize::from(c.len_utf8() > 2) + 1
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF)
+}
+
+pub fn message_tokens(message: &ChatMessage) -> usize {
+    let calls: usize = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|c| estimate_tokens(&c.name) + estimate_tokens(&c.arguments) + MESSAGE_OVERHEAD)
+        .sum();
+    estimate_tokens(&message.content) + calls + MESSAGE_OVERHEAD
+}
+
+pub fn messages_tokens(messages: &[ChatMessage]) -> usize {
+    messages.iter().map(message_tokens).sum()
+}
+
+/// The longest prefix of `text` that fits in `max_tokens`.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    let mut counter = Counter::default();
+    for (i, c) in text.char_indices() {
+        counter.push(c);
+        if counter.total() > max_tokens {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_are_close_to_bpe_counts() {
+        // cl100k_base: 10 tokens
+        assert_eq!(
+            estimate_tokens("The quick brown fox jumps over the lazy dog."),
+            10
+        );
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("你好世界"), 4);
+        assert_eq!(context_window("openai/gpt-4o-mini"), 128_000);
+        assert_eq!(context_window("claude-sonnet-4"), 200_000);
+        assert_eq!(context_window("some-local-model"), DEFAULT_CONTEXT_WINDOW);
+
+        let windows = ContextWindows::with_overrides(&BTreeMap::from([
+            ("llama3".to_string(), 8_192),
+            ("ollama/llama3.1:70b".to_string(), 65_536),
+        ]));
+        assert_eq!(windows.window("ollama/llama3:8b"), 8_192);
+        assert_eq!(windows.window("ollama/llama3.1:70b"), 65_536);
+        assert_eq!(windows.window("gpt-4o"), 128_000);
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        let text = "héllo wörld ".repeat(50);
+        let cut = truncate_to_tokens(&text, 20);
+        assert!(!cut.is_empty() && cut.len() < text.len());
+        assert!(estimate_tokens(cut) <= 20);
+        assert_eq!(truncate_to_tokens("short", 20), "short");
+    }
+}
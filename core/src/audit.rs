@@ -0,0 +1,238 @@
+use crate::agent::middleware::{ToolCall, ToolMiddleware};
+use crate::traits::ToolResult;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use ring::digest::{SHA256, digest};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+pub fn audit_dir() -> PathBuf {
+    crate::config::get_data_dir().join("audit")
+}
+
+/// One tool invocation. Arguments are stored as a SHA-256 of their JSON so the log
+/// shows what ran without keeping file contents or secrets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Local>,
+    pub session: String,
+    pub tool: String,
+    pub args_sha256: String,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+/// Appends a record per tool call to `<dir>/<session>.jsonl`, one file per run.
+pub struct AuditLog {
+    dir: PathBuf,
+    session: String,
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        let session = format!(
+            "{}-{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..6]
+        );
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            session,
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", self.session))
+    }
+
+    pub async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+fn args_sha256(args: &serde_json::Value) -> String {
+    digest(&SHA256, args.to_string().as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[async_trait]
+impl ToolMiddleware for AuditLog {
+    async fn after_execute(&self, call: &ToolCall, result: &mut ToolResult) {
+        let record = AuditRecord {
+            timestamp: Local::now(),
+            session: self.session.clone(),
+            tool: call.name.clone(),
+            args_sha256: args_sha256(&call.args),
+            duration_ms: call.started.elapsed().as_millis() as u64,
+            success: result.success,
+        };
+        if let Err(e) = self.record(&record).await {
+            tracing::warn!("Failed to write audit log: {}", e);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub session: Option<String>,
+    pub tool: Option<String>,
+    pub since: Option<DateTime<Local>>,
+    pub failed_only: bool,
+    /// Keep only the most recent records.
+    pub limit: Option<usize>,
+}
+
+/// Session ids in the audit directory, oldest first.
+pub fn sessions(dir: &Path) -> Result<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut sessions: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_suffix(".jsonl"))
+                .map(str::to_string)
+        })
+        .collect();
+    sessions.sort();
+    Ok(sessions)
+}
+
+/// Matching records in chronological order. Unreadable lines are skipped.
+pub fn query(dir: &Path, query: &AuditQuery) -> Result<Vec<AuditRecord>> {
+    let sessions = match &query.session {
+        Some(session) => vec![session.clone()],
+        None => sessions(dir)?,
+    };
+
+    let mut records = Vec::new();
+    for session in sessions {
+        let path = dir.join(format!("{session}.jsonl"));
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && query.session.is_some() => {
+                anyhow::bail!("No audit log for session '{session}'")
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        records.extend(
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<AuditRecord>(line).ok())
+                .filter(|r| query.tool.as_ref().is_none_or(|t| &r.tool == t))
+                .filter(|r| query.since.is_none_or(|since| r.timestamp >= since))
+                .filter(|r| !query.failed_only || !r.success),
+        );
+    }
+
+    records.sort_by_key(|r| r.timestamp);
+    if let Some(limit) = query.limit {
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+    }
+    Ok(records)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub total_duration_ms: u64,
+}
+
+/// Per-tool totals, most used first.
+pub fn stats(records: &[AuditRecord]) -> Vec<ToolStats> {
+    let mut by_tool: HashMap<&str, ToolStats> = HashMap::new();
+    for record in records {
+        let entry = by_tool.entry(&record.tool).or_insert_with(|| ToolStats {
+            tool: record.tool.clone(),
+            calls: 0,
+            failures: 0,
+            total_duration_ms: 0,
+        });
+        entry.calls += 1;
+        entry.failures += usize::from(!record.success);
+        entry.total_duration_ms += record.duration_ms;
+    }
+    let mut stats: Vec<ToolStats> = by_tool.into_values().collect();
+    stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::ToolRegistry;
+    use crate::tools::CalcTool;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn records_tool_calls_and_answers_queries() {
+        let tmp = TempDir::new().unwrap();
+        let log = Arc::new(AuditLog::new(tmp.path()));
+        let registry = ToolRegistry::new().with_middleware(log.clone());
+        registry.register(Box::new(CalcTool::new()));
+
+        registry
+            .execute("calc", json!({ "expression": "1 + 2" }))
+            .await;
+        registry
+            .execute("calc", json!({ "expression": "1 / 0" }))
+            .await;
+
+        let all = query(tmp.path(), &AuditQuery::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].session, log.session());
+        assert_eq!(all[0].args_sha256.len(), 64);
+        assert_ne!(all[0].args_sha256, all[1].args_sha256);
+
+        let failed = query(
+            tmp.path(),
+            &AuditQuery {
+                failed_only: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(failed.len(), 1);
+
+        assert_eq!(
+            stats(&all),
+            vec![ToolStats {
+                tool: "calc".to_string(),
+                calls: 2,
+                failures: 1,
+                total_duration_ms: all.iter().map(|r| r.duration_ms).sum(),
+            }]
+        );
+        assert_eq!(sessions(tmp.path()).unwrap(), vec![log.session()]);
+    }
+}
@@ -0,0 +1,452 @@
+use crate::agent::tokens::ContextWindows;
+use crate::agent::{
+    AgentLoop, ApprovalHandler, ContextBuilder, DenyApprover, EventSink, ToolRegistry, TurnOutcome,
+};
+use crate::audit::{AuditLog, audit_dir};
+use crate::checkpoint::{Checkpoints, checkpoints_dir};
+use crate::config::Config;
+use crate::guardrails::{Guardrails, RuleGuardrail};
+use crate::knowledge::KnowledgeIndex;
+use crate::memory::{RedactingMemory, create_embedder, create_memory_from_config};
+use crate::plugins::{load_plugin_tools, plugins_dir};
+use crate::redact::{RedactionMiddleware, Redactor};
+use crate::skills::{ReadSkillTool, SkillRegistry, skill_tools};
+use crate::tools::{
+    ApplyPatchTool, ArchiveCreateTool, ArchiveExtractTool, AskUserTool, CalcTool,
+    ContentSearchTool, DataQueryTool, DiffTool, DownloadTool, EnvInfoTool, FileEditTool,
+    FileReadTool, FileWriteTool, GitCommitTool, GitDiffTool, GitLogTool, GitOperationsTool,
+    GitStatusTool, GlobSearchTool, HttpRequestTool, ListSchedulesTool, MemoryForgetTool,
+    MemoryListTool, MemoryReadTool, MemoryWriteTool, ProcessKillTool, ProcessLogsTool,
+    ProcessStartTool, ProcessStatusTool, ProcessSupervisor, RunCodeTool, ScheduleTaskTool,
+    ShellTool, SpawnAgentTool, TodoTool, TreeTool, UpdateBootstrapTool, UserInputHandler,
+    WebFetchTool, WebSearchTool, load_command_tools, load_openapi_tools,
+};
+use crate::traits::{ChatMessage, Memory, Provider, Tool};
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Bootstrap files a project's `.dinoe/` directory or a prompt variant may replace.
+pub const PROJECT_BOOTSTRAP: [&str; 3] = ["SOUL.md", "TOOLS.md", "USER.md"];
+
+/// dinoe as a library: an agent with the built-in tools, memory, knowledge and skills,
+/// and the guardrails, redaction, audit log and checkpoints its config turns on, the
+/// same agent `dinoe chat` runs.
+///
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// use dinoe_core::{Dinoe, config::load_config};
+///
+/// let dinoe = Dinoe::builder()
+///     .with_config(load_config()?)
+///     .with_workspace("./notes")
+///     .build()?;
+/// let answer = dinoe.ask("Summarize todo.md").await?;
+/// println!("{answer}");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Dinoe {
+    agent: AgentLoop,
+    events: EventSink,
+}
+
+impl Dinoe {
+    pub fn builder() -> DinoeBuilder {
+        DinoeBuilder::default()
+    }
+
+    /// Runs one turn and returns the answer. Each call starts a new conversation; pass
+    /// the history to [`Dinoe::run`] to continue one.
+    pub async fn ask(&self, message: &str) -> Result<String> {
+        self.run(message, Vec::new()).await.into_result()
+    }
+
+    pub async fn run(&self, message: &str, history: Vec<ChatMessage>) -> TurnOutcome {
+        self.agent
+            .process_with_events(message, history, self.events.clone())
+            .await
+    }
+
+    pub fn agent(&self) -> &AgentLoop {
+        &self.agent
+    }
+
+    pub fn into_agent(self) -> AgentLoop {
+        self.agent
+    }
+}
+
+/// Sets up a [`Dinoe`]. Everything not set comes from the config, which is
+/// `Config::default()` unless [`DinoeBuilder::with_config`] gives another.
+#[derive(Default)]
+pub struct DinoeBuilder {
+    config: Option<Config>,
+    workspace: Option<PathBuf>,
+    model: Option<String>,
+    provider: Option<Arc<dyn Provider>>,
+    memory: Option<Arc<dyn Memory>>,
+    tools: Vec<Box<dyn Tool>>,
+    without_builtin_tools: bool,
+    approver: Option<Arc<dyn ApprovalHandler>>,
+    user_input: Option<Arc<dyn UserInputHandler>>,
+    events: Option<EventSink>,
+}
+
+impl DinoeBuilder {
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// The directory the agent works in, instead of the config's `workspace_dir`.
+    /// Created when missing.
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    pub fn with_memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// A tool of your own, next to the others. Its name must be unused.
+    pub fn with_tool(mut self, tool: impl Tool + 'static) -> Self {
+        self.tools.push(Box::new(tool));
+        self
+    }
+
+    /// Leaves out the built-in tools. Tools from the config, skills and plugins, and
+    /// those added with [`DinoeBuilder::with_tool`], are still offered.
+    pub fn without_builtin_tools(mut self) -> Self {
+        self.without_builtin_tools = true;
+        self
+    }
+
+    /// Answers approvals the config's `approval` mode asks for. Without one, calls that
+    /// need approval are refused.
+    pub fn with_approver(mut self, approver: Arc<dyn ApprovalHandler>) -> Self {
+        self.approver = Some(approver);
+        self
+    }
+
+    /// Answers the agent's `ask_user` questions. Without one, the agent is told that
+    /// nobody can answer.
+    pub fn with_user_input(mut self, handler: Arc<dyn UserInputHandler>) -> Self {
+        self.user_input = Some(handler);
+        self
+    }
+
+    pub fn with_events(mut self, events: impl Into<EventSink>) -> Self {
+        self.events = Some(events.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Dinoe> {
+        let mut config = self.config.unwrap_or_default();
+        if let Some(workspace) = self.workspace {
+            config.workspace_dir = workspace;
+        }
+        if let Some(model) = self.model {
+            config.model = model;
+        }
+        let workspace = config.workspace_dir.clone();
+        std::fs::create_dir_all(&workspace).with_context(|| {
+            format!("Could not create the workspace at {}", workspace.display())
+        })?;
+
+        let provider = match self.provider {
+            Some(provider) => provider,
+            None => Arc::from(crate::providers::create_provider(&config)?),
+        };
+        let redactor = if config.redaction.enabled {
+            Some(Arc::new(Redactor::from_config(&config)?))
+        } else {
+            None
+        };
+        let memory = match self.memory {
+            Some(memory) => memory,
+            None => create_memory_from_config(&config)?,
+        };
+        let memory: Arc<dyn Memory> = match &redactor {
+            Some(redactor) => Arc::new(RedactingMemory::new(memory, redactor.clone())),
+            None => memory,
+        };
+        let skills: Vec<_> = SkillRegistry::load_from_config(&config)?
+            .list()
+            .into_iter()
+            .filter(|skill| match skill.unavailable(&config.model) {
+                Some(reason) => {
+                    tracing::info!("Skill '{}' is not offered: {}", skill.name, reason);
+                    false
+                }
+                None => true,
+            })
+            .collect();
+
+        let approver = self.approver.unwrap_or_else(|| Arc::new(DenyApprover));
+        let guardrails = if config.guardrails.is_empty() {
+            None
+        } else {
+            let rules = RuleGuardrail::from_config(&config.guardrails, &workspace)?;
+            Some(Arc::new(
+                Guardrails::new()
+                    .with_guardrail(Arc::new(rules))
+                    .with_confirmation(approver.clone()),
+            ))
+        };
+        let mut registry = ToolRegistry::new().with_approval(config.approval, approver);
+        if let Some(guardrails) = &guardrails {
+            registry = registry.with_middleware(guardrails.clone());
+        }
+        if let Some(redactor) = redactor {
+            registry = registry.with_middleware(Arc::new(RedactionMiddleware::new(redactor)));
+        }
+        if config.audit {
+            registry = registry.with_middleware(Arc::new(AuditLog::new(audit_dir())));
+        }
+        let checkpoints = config
+            .checkpoints
+            .then(|| Arc::new(Checkpoints::new(checkpoints_dir(), &workspace)));
+        if let Some(checkpoints) = &checkpoints {
+            registry = registry.with_middleware(checkpoints.clone());
+        }
+        let registry = Arc::new(registry);
+
+        if !self.without_builtin_tools {
+            register_builtin_tools(&registry, &config, memory.clone(), self.user_input);
+        }
+        if !skills.is_empty() {
+            registry.register(Box::new(ReadSkillTool::new(skills.clone())));
+        }
+        for tool in self.tools {
+            if registry.tool_names().iter().any(|n| n == tool.name()) {
+                bail!("Another tool is already named '{}'", tool.name());
+            }
+            registry.register(tool);
+        }
+        let configured = load_command_tools(&config.tools, &workspace)
+            .into_iter()
+            .map(|tool| Box::new(tool) as Box<dyn Tool>)
+            .chain(
+                skill_tools(&skills, &workspace)
+                    .into_iter()
+                    .map(|tool| Box::new(tool) as Box<dyn Tool>),
+            )
+            .chain(
+                load_openapi_tools(&config.openapi, &workspace)
+                    .into_iter()
+                    .map(|tool| Box::new(tool) as Box<dyn Tool>),
+            )
+            .chain(load_plugin_tools(&plugins_dir(), &workspace));
+        for tool in configured {
+            if registry.tool_names().iter().any(|n| n == tool.name()) {
+                tracing::warn!(
+                    "Tool '{}' skipped: another tool has the same name",
+                    tool.name()
+                );
+                continue;
+            }
+            registry.register(tool);
+        }
+
+        let context_window =
+            ContextWindows::with_overrides(&config.context_windows).window(&config.model);
+        let allowed_tools = config.agent.as_ref().and_then(|p| p.tools.as_ref());
+        let registry = match allowed_tools {
+            Some(names) => {
+                let known = registry.tool_names();
+                for name in names
+                    .iter()
+                    .filter(|n| *n != "spawn_agent" && !known.contains(n))
+                {
+                    tracing::warn!("The agent profile lists an unknown tool '{}'", name);
+                }
+                Arc::new(registry.subset(names))
+            }
+            None => registry,
+        };
+        if allowed_tools.is_none_or(|names| names.iter().any(|n| n == "spawn_agent")) {
+            registry.register(Box::new(
+                SpawnAgentTool::new(provider.clone(), &registry, &workspace)
+                    .with_model_name(config.model.clone())
+                    .with_temperature(config.temperature)
+                    .with_max_iterations(config.max_iterations)
+                    .with_context_window(context_window),
+            ));
+        }
+        let tool_names = registry.tool_names();
+        for skill in &skills {
+            for tool in skill.missing_tools(&tool_names) {
+                tracing::warn!(
+                    "Skill '{}' needs the tool '{}', which the agent does not have",
+                    skill.name,
+                    tool
+                );
+            }
+        }
+
+        let knowledge = match create_embedder(&config) {
+            Some(embedder) => KnowledgeIndex::new(&workspace).with_embedder(embedder),
+            None => KnowledgeIndex::new(&workspace),
+        };
+        let context = ContextBuilder::new(&workspace)
+            .with_memory(memory)
+            .with_knowledge(Arc::new(knowledge))
+            .with_skills(skills)
+            .with_tool_specs(registry.get_specs())
+            .with_max_prompt_tokens(context_window / 3)
+            .with_budget(config.context_budget.clone());
+        // Bootstrap files in the project's `.dinoe/` replace the workspace's; a profile's
+        // `bootstrap` still wins over both.
+        let project_files = config.project_dir.iter().flat_map(|dir| {
+            PROJECT_BOOTSTRAP
+                .into_iter()
+                .map(|name| (name.to_string(), dir.join(name)))
+                .filter(|(_, path)| path.exists())
+        });
+        let profile_files = config
+            .agent
+            .iter()
+            .flat_map(|profile| profile.bootstrap.clone());
+        let context = project_files
+            .chain(profile_files)
+            .fold(context, |context, (name, path)| {
+                context.with_bootstrap_file(name, path)
+            });
+
+        let write_policy = crate::memory::create_write_policy(&config, provider.clone());
+        let mut agent = AgentLoop::new(provider, context, registry)
+            .with_max_iterations(config.max_iterations)
+            .with_model_name(config.model.clone())
+            .with_temperature(config.temperature)
+            .with_parallel_tools(config.parallel_tools)
+            .with_streaming(config.streaming)
+            .with_recovery(config.recovery)
+            .with_thinking_in_transcript(config.save_thinking)
+            .with_tool_output_budget(config.max_tool_output, config.max_turn_tool_output)
+            .with_write_policy(write_policy)
+            .with_context_window(context_window);
+        if let Some(secs) = config.turn_timeout_secs {
+            agent = agent.with_turn_timeout(std::time::Duration::from_secs(secs));
+        }
+        if let Some(guardrails) = guardrails {
+            agent = agent.with_guardrails(guardrails);
+        }
+        if let Some(checkpoints) = checkpoints {
+            agent = agent.with_checkpoints(checkpoints);
+        }
+        Ok(Dinoe {
+            agent,
+            events: self.events.unwrap_or_else(EventSink::none),
+        })
+    }
+}
+
+fn register_builtin_tools(
+    registry: &ToolRegistry,
+    config: &Config,
+    memory: Arc<dyn Memory>,
+    user_input: Option<Arc<dyn UserInputHandler>>,
+) {
+    let workspace = &config.workspace_dir;
+    registry.register(Box::new(FileReadTool::new(workspace)));
+    registry.register(Box::new(FileWriteTool::new(workspace)));
+    registry.register(Box::new(ShellTool::new(workspace)));
+    registry.register(Box::new(RunCodeTool::new()));
+    registry.register(Box::new(CalcTool::new()));
+    registry.register(Box::new(EnvInfoTool::new()));
+    registry.register(Box::new(TodoTool::new(workspace)));
+    registry.register(Box::new(ScheduleTaskTool::new(workspace)));
+    registry.register(Box::new(ListSchedulesTool::new(workspace)));
+    match user_input {
+        Some(handler) => registry.register(Box::new(AskUserTool::new().with_handler(handler))),
+        None => registry.register(Box::new(AskUserTool::new())),
+    }
+    registry.register(Box::new(MemoryReadTool::new(memory.clone())));
+    registry.register(Box::new(MemoryWriteTool::new(memory.clone())));
+    registry.register(Box::new(MemoryListTool::new(memory.clone())));
+    registry.register(Box::new(MemoryForgetTool::new(memory)));
+    registry.register(Box::new(UpdateBootstrapTool::soul(workspace)));
+    registry.register(Box::new(UpdateBootstrapTool::user_profile(workspace)));
+    registry.register(Box::new(WebFetchTool::new()));
+    registry.register(Box::new(HttpRequestTool::new()));
+    registry.register(Box::new(DownloadTool::new(workspace)));
+    match WebSearchTool::from_config(&config.search) {
+        Ok(tool) => registry.register(Box::new(tool)),
+        Err(e) => tracing::warn!("web_search disabled: {}", e),
+    }
+    registry.register(Box::new(TreeTool::new(workspace)));
+    registry.register(Box::new(GlobSearchTool::new(workspace)));
+    registry.register(Box::new(ContentSearchTool::new(workspace)));
+    registry.register(Box::new(DataQueryTool::new(workspace)));
+    registry.register(Box::new(FileEditTool::new(workspace)));
+    registry.register(Box::new(DiffTool::new(workspace)));
+    registry.register(Box::new(ApplyPatchTool::new(workspace)));
+    registry.register(Box::new(ArchiveCreateTool::new(workspace)));
+    registry.register(Box::new(ArchiveExtractTool::new(workspace)));
+    registry.register(Box::new(GitOperationsTool::new(workspace)));
+    registry.register(Box::new(GitStatusTool::new(workspace)));
+    registry.register(Box::new(GitDiffTool::new(workspace)));
+    registry.register(Box::new(GitLogTool::new(workspace)));
+    registry.register(Box::new(GitCommitTool::new(workspace)));
+
+    let supervisor = Arc::new(ProcessSupervisor::new(workspace));
+    registry.register(Box::new(ProcessStartTool::new(supervisor.clone())));
+    registry.register(Box::new(ProcessStatusTool::new(supervisor.clone())));
+    registry.register(Box::new(ProcessLogsTool::new(supervisor.clone())));
+    registry.register(Box::new(ProcessKillTool::new(supervisor)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{InMemoryMemory, MockProvider, TempWorkspace};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn builds_a_working_agent() {
+        let workspace = TempWorkspace::new();
+        let provider = Arc::new(
+            MockProvider::new()
+                .with_tool_call("file_write", json!({ "path": "a.txt", "content": "hi" }))
+                .with_text("Done"),
+        );
+        let config = Config {
+            audit: false,
+            checkpoints: false,
+            ..Default::default()
+        };
+        let dinoe = Dinoe::builder()
+            .with_config(config.clone())
+            .with_workspace(workspace.path())
+            .with_provider(provider.clone())
+            .with_memory(Arc::new(InMemoryMemory::new()))
+            .build()
+            .unwrap();
+
+        assert_eq!(dinoe.ask("Write a.txt").await.unwrap(), "Done");
+        assert_eq!(workspace.read("a.txt"), "hi");
+        let offered = &provider.requests()[0].tools;
+        assert!(offered.iter().any(|t| t == "memory_write"), "{offered:?}");
+
+        let duplicate = Dinoe::builder()
+            .with_config(config)
+            .with_workspace(workspace.path())
+            .with_provider(provider)
+            .with_tool(FileReadTool::new(workspace.path()))
+            .build();
+        assert!(duplicate.is_err());
+    }
+}
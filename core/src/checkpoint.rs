@@ -0,0 +1,297 @@
+use crate::agent::middleware::{ToolCall, ToolMiddleware, TurnId};
+use crate::traits::ToolResult;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use walkdir::WalkDir;
+
+/// Directories that are never snapshotted or touched by a rollback.
+const SKIPPED_DIRS: &[&str] = &[".git", "target", "node_modules", ".venv", "__pycache__"];
+/// Larger files are left out of snapshots, and left alone by rollbacks.
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Older checkpoints are deleted.
+const MAX_CHECKPOINTS: usize = 20;
+
+pub fn checkpoints_dir() -> PathBuf {
+    crate::config::get_data_dir().join("checkpoints")
+}
+
+/// The files of the workspace at one point in time. Contents live in a shared object
+/// store keyed by hash, so unchanged files cost nothing per checkpoint.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    created: DateTime<Local>,
+    /// Relative path to content hash.
+    files: BTreeMap<String, String>,
+    /// Files too large to snapshot.
+    skipped: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RollbackReport {
+    pub created: DateTime<Local>,
+    /// Files written back with their earlier content.
+    pub restored: Vec<String>,
+    /// Files created since the checkpoint, now deleted.
+    pub removed: Vec<String>,
+}
+
+/// Snapshots the workspace before the first mutating tool call of each turn, so the
+/// turn's changes can be rolled back. Add it to the tool registry as middleware and to
+/// the agent loop, which tells it when turns end.
+pub struct Checkpoints {
+    workspace: PathBuf,
+    dir: PathBuf,
+    /// Turns that already have their snapshot.
+    taken: Mutex<HashSet<TurnId>>,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl Checkpoints {
+    /// Checkpoints of `workspace` are kept under `root`, separately for each workspace.
+    pub fn new(root: impl AsRef<Path>, workspace: impl AsRef<Path>) -> Self {
+        let workspace = workspace.as_ref().to_path_buf();
+        let key = format!("{:x}", md5::compute(workspace.to_string_lossy().as_bytes()));
+        Self {
+            dir: root.as_ref().join(&key[..12]),
+            workspace,
+            taken: Mutex::new(HashSet::new()),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Forgets a finished turn.
+    pub fn end_turn(&self, turn: TurnId) {
+        self.taken.lock().unwrap().remove(&turn);
+    }
+
+    /// Snapshots the workspace now and returns the checkpoint id.
+    pub fn snapshot(&self) -> Result<String> {
+        snapshot(&self.workspace, &self.dir)
+    }
+
+    /// Checkpoint ids, oldest first.
+    pub fn list(&self) -> Result<Vec<String>> {
+        list(&self.dir)
+    }
+
+    /// Restores the workspace to the latest checkpoint and deletes it, so calling this
+    /// again goes back one more turn. `None` when there is no checkpoint left.
+    pub fn rollback(&self) -> Result<Option<RollbackReport>> {
+        let Some(id) = self.list()?.pop() else {
+            return Ok(None);
+        };
+        let path = self.dir.join(format!("{id}.json"));
+        let manifest: Manifest = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .with_context(|| format!("Failed to read checkpoint {id}"))?;
+
+        let mut report = RollbackReport {
+            created: manifest.created,
+            restored: Vec::new(),
+            removed: Vec::new(),
+        };
+        for (relative, file) in workspace_files(&self.workspace) {
+            if !manifest.files.contains_key(&relative) && !manifest.skipped.contains(&relative) {
+                std::fs::remove_file(&file)
+                    .with_context(|| format!("Failed to remove {}", file.display()))?;
+                report.removed.push(relative);
+            }
+        }
+        for (relative, hash) in &manifest.files {
+            let file = self.workspace.join(relative);
+            if std::fs::read(&file).is_ok_and(|content| content_hash(&content) == *hash) {
+                continue;
+            }
+            let content = std::fs::read(self.dir.join("objects").join(hash))
+                .with_context(|| format!("Checkpoint {id} is missing the content of {relative}"))?;
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file, content)
+                .with_context(|| format!("Failed to restore {}", file.display()))?;
+            report.restored.push(relative.clone());
+        }
+
+        std::fs::remove_file(&path)?;
+        prune(&self.dir, MAX_CHECKPOINTS)?;
+        Ok(Some(report))
+    }
+}
+
+fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", md5::compute(content))
+}
+
+fn snapshot(workspace: &Path, dir: &Path) -> Result<String> {
+    let mut manifest = Manifest {
+        created: Local::now(),
+        files: BTreeMap::new(),
+        skipped: BTreeSet::new(),
+    };
+    let objects = dir.join("objects");
+    std::fs::create_dir_all(&objects)
+        .with_context(|| format!("Failed to create {}", objects.display()))?;
+    for (relative, file) in workspace_files(workspace) {
+        if file.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            manifest.skipped.insert(relative);
+            continue;
+        }
+        let content =
+            std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let hash = content_hash(&content);
+        let object = objects.join(&hash);
+        if !object.exists() {
+            std::fs::write(&object, &content)?;
+        }
+        manifest.files.insert(relative, hash);
+    }
+
+    let id = format!(
+        "{}-{}",
+        manifest.created.format("%Y%m%d-%H%M%S%3f"),
+        &uuid::Uuid::new_v4().simple().to_string()[..6]
+    );
+    std::fs::write(
+        dir.join(format!("{id}.json")),
+        serde_json::to_string(&manifest)?,
+    )?;
+    prune(dir, MAX_CHECKPOINTS)?;
+    Ok(id)
+}
+
+fn list(dir: &Path) -> Result<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_suffix(".json"))
+                .map(str::to_string)
+        })
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Keeps the newest `keep` checkpoints and the objects they use.
+fn prune(dir: &Path, keep: usize) -> Result<()> {
+    let ids = list(dir)?;
+    let stale = ids.len().saturating_sub(keep);
+    for id in &ids[..stale] {
+        std::fs::remove_file(dir.join(format!("{id}.json")))?;
+    }
+
+    let mut used = BTreeSet::new();
+    for id in &ids[stale..] {
+        let content = std::fs::read_to_string(dir.join(format!("{id}.json")))?;
+        if let Ok(manifest) = serde_json::from_str::<Manifest>(&content) {
+            used.extend(manifest.files.into_values());
+        }
+    }
+    let Ok(objects) = std::fs::read_dir(dir.join("objects")) else {
+        return Ok(());
+    };
+    for object in objects.filter_map(|e| e.ok()) {
+        if !used.contains(object.file_name().to_string_lossy().as_ref()) {
+            let _ = std::fs::remove_file(object.path());
+        }
+    }
+    Ok(())
+}
+
+/// Regular files of the workspace as (relative path, path), outside skipped directories.
+fn workspace_files(workspace: &Path) -> Vec<(String, PathBuf)> {
+    WalkDir::new(workspace)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(workspace).ok()?;
+            Some((
+                relative.to_string_lossy().into_owned(),
+                e.path().to_path_buf(),
+            ))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl ToolMiddleware for Checkpoints {
+    async fn before_execute(&self, call: &mut ToolCall) -> Option<ToolResult> {
+        if !call.mutating {
+            return None;
+        }
+        // Held until the snapshot is written, so parallel calls don't change files first.
+        let _guard = self.lock.lock().await;
+        if self.taken.lock().unwrap().insert(call.turn) {
+            let (workspace, dir) = (self.workspace.clone(), self.dir.clone());
+            let snapshot = tokio::task::spawn_blocking(move || snapshot(&workspace, &dir)).await;
+            match snapshot {
+                Ok(Ok(id)) => tracing::debug!("Workspace checkpoint {}", id),
+                Ok(Err(e)) => tracing::warn!("Failed to checkpoint the workspace: {}", e),
+                Err(e) => tracing::warn!("Failed to checkpoint the workspace: {}", e),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn rolls_back_the_changes_of_a_turn() {
+        let root = TempDir::new().unwrap();
+        let workspace = TempDir::new().unwrap();
+        let ws = workspace.path();
+        std::fs::write(ws.join("notes.md"), "original").unwrap();
+        std::fs::create_dir(ws.join("src")).unwrap();
+        std::fs::write(ws.join("src/main.rs"), "fn main() {}").unwrap();
+        let checkpoints = Checkpoints::new(root.path(), ws);
+
+        let mut call = ToolCall {
+            name: "file_write".to_string(),
+            args: json!({ "path": "notes.md" }),
+            mutating: true,
+            always_needs_approval: false,
+            turn: 1,
+            started: Instant::now(),
+        };
+        assert!(checkpoints.before_execute(&mut call).await.is_none());
+        assert!(checkpoints.before_execute(&mut call).await.is_none());
+        assert_eq!(checkpoints.list().unwrap().len(), 1);
+
+        std::fs::write(ws.join("notes.md"), "overwritten").unwrap();
+        std::fs::remove_file(ws.join("src/main.rs")).unwrap();
+        std::fs::write(ws.join("new.txt"), "created").unwrap();
+        std::fs::create_dir(ws.join("node_modules")).unwrap();
+        std::fs::write(ws.join("node_modules/dep.js"), "").unwrap();
+
+        let report = checkpoints.rollback().unwrap().unwrap();
+        assert_eq!(report.restored, ["notes.md", "src/main.rs"]);
+        assert_eq!(report.removed, ["new.txt"]);
+        assert_eq!(
+            std::fs::read_to_string(ws.join("notes.md")).unwrap(),
+            "original"
+        );
+        assert!(ws.join("src/main.rs").exists());
+        assert!(ws.join("node_modules/dep.js").exists());
+        assert!(checkpoints.rollback().unwrap().is_none());
+    }
+}
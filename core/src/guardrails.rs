@@ -0,0 +1,332 @@
+use crate::agent::ApprovalHandler;
+use crate::agent::middleware::{ToolCall, ToolMiddleware, TurnId};
+use crate::config::GuardrailConfig;
+use crate::traits::ToolResult;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// What happens when a rule matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardAction {
+    #[default]
+    Block,
+    /// Ask the user; blocked when nobody can answer.
+    Confirm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    Confirm(String),
+    Block(String),
+}
+
+impl Verdict {
+    fn matched(action: GuardAction, reason: String) -> Self {
+        match action {
+            GuardAction::Block => Verdict::Block(reason),
+            GuardAction::Confirm => Verdict::Confirm(reason),
+        }
+    }
+}
+
+/// A check on what goes to and comes from the model. All methods default to allowing.
+pub trait Guardrail: Send + Sync {
+    /// The user's message, before the turn starts.
+    fn check_prompt(&self, _text: &str) -> Verdict {
+        Verdict::Allow
+    }
+
+    /// The model's final answer.
+    fn check_response(&self, _text: &str) -> Verdict {
+        Verdict::Allow
+    }
+
+    fn check_tool_call(&self, _call: &ToolCall) -> Verdict {
+        Verdict::Allow
+    }
+
+    /// Drops any state kept for a finished turn.
+    fn end_turn(&self, _turn: TurnId) {}
+}
+
+/// The rules from `[guardrails]` in the config.
+pub struct RuleGuardrail {
+    deny: Vec<Regex>,
+    blocked_paths: Vec<PathBuf>,
+    workspace: PathBuf,
+    max_mutations: Option<usize>,
+    mutations: Mutex<HashMap<TurnId, usize>>,
+    action: GuardAction,
+}
+
+impl RuleGuardrail {
+    pub fn from_config(config: &GuardrailConfig, workspace: &Path) -> Result<Self> {
+        let deny = config
+            .deny_patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid guardrail pattern '{p}'")))
+            .collect::<Result<_>>()?;
+        let blocked_paths = config
+            .blocked_paths
+            .iter()
+            .map(|p| normalize(&resolve(p, workspace)))
+            .collect();
+        Ok(Self {
+            deny,
+            blocked_paths,
+            workspace: workspace.to_path_buf(),
+            max_mutations: config.max_mutations_per_turn,
+            mutations: Mutex::new(HashMap::new()),
+            action: config.action,
+        })
+    }
+
+    fn check_text(&self, text: &str) -> Verdict {
+        match self.deny.iter().find(|re| re.is_match(text)) {
+            Some(re) => Verdict::matched(self.action, format!("matches '{}'", re.as_str())),
+            None => Verdict::Allow,
+        }
+    }
+
+    fn blocked_path(&self, value: &str) -> Option<&Path> {
+        let path = normalize(&resolve(value, &self.workspace));
+        self.blocked_paths
+            .iter()
+            .map(PathBuf::as_path)
+            .find(|blocked| {
+                path.starts_with(blocked) || value.contains(blocked.to_string_lossy().as_ref())
+            })
+    }
+}
+
+impl Guardrail for RuleGuardrail {
+    fn check_prompt(&self, text: &str) -> Verdict {
+        self.check_text(text)
+    }
+
+    fn check_response(&self, text: &str) -> Verdict {
+        self.check_text(text)
+    }
+
+    fn check_tool_call(&self, call: &ToolCall) -> Verdict {
+        if call.mutating
+            && let Some(max) = self.max_mutations
+            && {
+                let mut mutations = self.mutations.lock().unwrap();
+                let count = mutations.entry(call.turn).or_default();
+                *count += 1;
+                *count > max
+            }
+        {
+            return Verdict::matched(
+                self.action,
+                format!("more than {max} file or system changes in one turn"),
+            );
+        }
+        for value in strings(&call.args) {
+            if let Some(blocked) = self.blocked_path(value) {
+                return Verdict::matched(
+                    self.action,
+                    format!("touches blocked path {}", blocked.display()),
+                );
+            }
+            if let verdict @ (Verdict::Block(_) | Verdict::Confirm(_)) = self.check_text(value) {
+                return verdict;
+            }
+        }
+        Verdict::Allow
+    }
+
+    fn end_turn(&self, turn: TurnId) {
+        self.mutations.lock().unwrap().remove(&turn);
+    }
+}
+
+/// All string values in a JSON document.
+fn strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().flat_map(strings).collect(),
+        Value::Object(map) => map.values().flat_map(strings).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn resolve(path: &str, workspace: &Path) -> PathBuf {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(rest),
+        None => PathBuf::from(path),
+    };
+    if path.is_absolute() {
+        path
+    } else {
+        workspace.join(path)
+    }
+}
+
+/// Resolves `.` and `..` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// The guardrails of an agent. Checks tool calls as middleware; prompts and responses
+/// are checked by the agent loop.
+#[derive(Default)]
+pub struct Guardrails {
+    guardrails: Vec<Arc<dyn Guardrail>>,
+    confirm: Option<Arc<dyn ApprovalHandler>>,
+}
+
+impl Guardrails {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_guardrail(mut self, guardrail: Arc<dyn Guardrail>) -> Self {
+        self.guardrails.push(guardrail);
+        self
+    }
+
+    /// Who answers `Confirm` verdicts. Without a handler they block.
+    pub fn with_confirmation(mut self, handler: Arc<dyn ApprovalHandler>) -> Self {
+        self.confirm = Some(handler);
+        self
+    }
+
+    pub fn end_turn(&self, turn: TurnId) {
+        self.guardrails.iter().for_each(|g| g.end_turn(turn));
+    }
+
+    /// `Err` holds the reason the prompt was blocked.
+    pub async fn check_prompt(&self, text: &str) -> Result<(), String> {
+        let verdicts = self.guardrails.iter().map(|g| g.check_prompt(text));
+        self.resolve(verdicts, "message", &json!({ "text": text }))
+            .await
+    }
+
+    /// `Err` holds the reason the response was blocked.
+    pub async fn check_response(&self, text: &str) -> Result<(), String> {
+        let verdicts = self.guardrails.iter().map(|g| g.check_response(text));
+        self.resolve(verdicts, "response", &json!({ "text": text }))
+            .await
+    }
+
+    async fn resolve(
+        &self,
+        verdicts: impl Iterator<Item = Verdict>,
+        subject: &str,
+        args: &Value,
+    ) -> Result<(), String> {
+        for verdict in verdicts {
+            match verdict {
+                Verdict::Allow => {}
+                Verdict::Block(reason) => return Err(reason),
+                Verdict::Confirm(reason) => {
+                    let approved = match &self.confirm {
+                        Some(handler) => {
+                            handler
+                                .approve(&format!("{subject} ({reason})"), args)
+                                .await
+                        }
+                        None => false,
+                    };
+                    if !approved {
+                        return Err(reason);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ToolMiddleware for Guardrails {
+    async fn before_execute(&self, call: &mut ToolCall) -> Option<ToolResult> {
+        let verdicts = self.guardrails.iter().map(|g| g.check_tool_call(call));
+        match self.resolve(verdicts, &call.name, &call.args).await {
+            Ok(()) => None,
+            Err(reason) => Some(ToolResult::error(format!(
+                "Blocked by guardrail: {reason}. Do not retry this call; tell the user what was blocked."
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn call(turn: TurnId, name: &str, mutating: bool, args: Value) -> ToolCall {
+        ToolCall {
+            name: name.to_string(),
+            args,
+            mutating,
+            always_needs_approval: false,
+            turn,
+            started: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn enforces_patterns_paths_and_mutation_limits() {
+        let config = GuardrailConfig {
+            deny_patterns: vec![r"(?i)drop\s+table".to_string()],
+            blocked_paths: vec!["/etc".to_string(), "secrets".to_string()],
+            max_mutations_per_turn: Some(1),
+            action: GuardAction::Block,
+        };
+        let rules = RuleGuardrail::from_config(&config, Path::new("/work")).unwrap();
+        let guardrails = Guardrails::new().with_guardrail(Arc::new(rules));
+
+        assert!(
+            guardrails
+                .check_prompt("Please DROP TABLE users")
+                .await
+                .is_err()
+        );
+        assert!(guardrails.check_response("All done").await.is_ok());
+
+        let blocked = [
+            call(0, "file_read", false, json!({ "path": "secrets/key.pem" })),
+            call(
+                0,
+                "file_read",
+                false,
+                json!({ "path": "docs/../secrets/a" }),
+            ),
+            call(0, "shell", true, json!({ "command": "cat /etc/passwd" })),
+        ];
+        for mut call in blocked {
+            assert!(guardrails.before_execute(&mut call).await.is_some());
+        }
+
+        let mut first = call(1, "file_write", true, json!({ "path": "notes.md" }));
+        let mut second = call(2, "file_write", true, json!({ "path": "notes.md" }));
+        assert!(guardrails.before_execute(&mut first).await.is_none());
+        // A turn running at the same time has its own count.
+        assert!(guardrails.before_execute(&mut second).await.is_none());
+        assert!(guardrails.before_execute(&mut first).await.is_some());
+        guardrails.end_turn(1);
+        assert!(guardrails.before_execute(&mut first).await.is_none());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::Config;
+use anyhow::{Context, Result};
+use keyring::Entry;
+
+/// The service API keys are stored under; the account is the provider name.
+const SERVICE: &str = "dinoe";
+
+/// Where API keys are kept, one per provider.
+trait Store {
+    fn get(&self, provider: &str) -> Result<Option<String>>;
+    fn set(&self, provider: &str, key: &str) -> Result<()>;
+    /// `false` when there was no key.
+    fn remove(&self, provider: &str) -> Result<bool>;
+}
+
+/// The macOS Keychain, the Windows Credential Manager, or the Secret Service
+/// (GNOME Keyring, KWallet) on Linux.
+struct SystemKeychain;
+
+impl Store for SystemKeychain {
+    fn get(&self, provider: &str) -> Result<Option<String>> {
+        match Entry::new(SERVICE, provider)?.get_password() {
+            Ok(key) => Ok(Some(key)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, provider: &str, key: &str) -> Result<()> {
+        Entry::new(SERVICE, provider)?
+            .set_password(key)
+            .context("The keychain refused the key")
+    }
+
+    fn remove(&self, provider: &str) -> Result<bool> {
+        match Entry::new(SERVICE, provider)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).context("The keychain did not delete the key"),
+        }
+    }
+}
+
+/// The provider's API key from the system keychain. `None` when there is none or no
+/// keychain is available.
+pub fn get(provider: &str) -> Option<String> {
+    lookup(&SystemKeychain, provider)
+}
+
+fn lookup(store: &dyn Store, provider: &str) -> Option<String> {
+    match store.get(provider) {
+        Ok(key) => key.filter(|key| !key.is_empty()),
+        Err(e) => {
+            tracing::debug!("No {provider} key from the keychain: {e:#}");
+            None
+        }
+    }
+}
+
+pub fn set(provider: &str, key: &str) -> Result<()> {
+    SystemKeychain.set(provider, key)
+}
+
+/// Deletes the provider's key. `false` when there was none.
+pub fn remove(provider: &str) -> Result<bool> {
+    SystemKeychain.remove(provider)
+}
+
+/// The provider name keys are stored under for this config.
+pub fn provider_name(config: &Config) -> String {
+    config
+        .provider
+        .as_deref()
+        .unwrap_or("openai")
+        .to_lowercase()
+}
+
+/// `api_key` from the config, or else the chat provider's key from the keychain.
+pub fn api_key(config: &Config) -> Option<String> {
+    api_key_in(&SystemKeychain, config)
+}
+
+fn api_key_in(store: &dyn Store, config: &Config) -> Option<String> {
+    if !config.api_key.is_empty() {
+        return Some(config.api_key.clone());
+    }
+    lookup(store, &provider_name(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore(RefCell<HashMap<String, String>>);
+
+    impl Store for MemoryStore {
+        fn get(&self, provider: &str) -> Result<Option<String>> {
+            Ok(self.0.borrow().get(provider).cloned())
+        }
+
+        fn set(&self, provider: &str, key: &str) -> Result<()> {
+            self.0.borrow_mut().insert(provider.into(), key.into());
+            Ok(())
+        }
+
+        fn remove(&self, provider: &str) -> Result<bool> {
+            Ok(self.0.borrow_mut().remove(provider).is_some())
+        }
+    }
+
+    struct NoKeychain;
+
+    impl Store for NoKeychain {
+        fn get(&self, _provider: &str) -> Result<Option<String>> {
+            bail!("no D-Bus session")
+        }
+
+        fn set(&self, _provider: &str, _key: &str) -> Result<()> {
+            bail!("no D-Bus session")
+        }
+
+        fn remove(&self, _provider: &str) -> Result<bool> {
+            bail!("no D-Bus session")
+        }
+    }
+
+    #[test]
+    fn config_key_comes_before_the_keychain() {
+        let store = MemoryStore::default();
+        store.set("openrouter", "sk-or-keychain").unwrap();
+        store.set("openai", "").unwrap();
+
+        let mut config = Config {
+            provider: Some("OpenRouter".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            api_key_in(&store, &config).as_deref(),
+            Some("sk-or-keychain")
+        );
+        config.api_key = "sk-config".into();
+        assert_eq!(api_key_in(&store, &config).as_deref(), Some("sk-config"));
+
+        assert_eq!(lookup(&store, "openai"), None);
+        assert_eq!(lookup(&NoKeychain, "openai"), None);
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+/// Chunks grow to about this many characters and end at the next blank line.
+const CHUNK_CHARS: usize = 1500;
+/// Hard cap for text without blank lines, like minified files or long PDF pages.
+const MAX_CHUNK_CHARS: usize = 3000;
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "c", "h", "cc", "cpp", "hpp", "cs",
+    "rb", "php", "swift", "scala", "sh", "bash", "zsh", "lua", "sql", "toml", "yaml", "yml",
+    "json",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    Markdown,
+    Code,
+    Text,
+}
+
+impl DocKind {
+    /// `None` for files that are not ingested (binaries, PDFs are handled separately).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" | "mdx" => Some(Self::Markdown),
+            "txt" | "rst" | "adoc" | "org" => Some(Self::Text),
+            ext if CODE_EXTENSIONS.contains(&ext) => Some(Self::Code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// The enclosing markdown heading, or the line range.
+    pub location: String,
+    pub text: String,
+}
+
+/// Splits a document into chunks on paragraph boundaries, and also at headings for
+/// markdown so each chunk stays within one section.
+pub fn chunk(text: &str, kind: DocKind) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut size = 0;
+    let mut start = 0;
+    let mut heading: Option<String> = None;
+    let mut in_fence = false;
+
+    let mut flush = |current: &mut Vec<&str>,
+                     size: &mut usize,
+                     start: usize,
+                     end: usize,
+                     heading: &Option<String>| {
+        let text = current.join("\n").trim().to_string();
+        if !text.is_empty() {
+            let location = match heading {
+                Some(heading) => heading.clone(),
+                None => format!("lines {}-{}", start + 1, end),
+            };
+            chunks.push(Chunk { location, text });
+        }
+        current.clear();
+        *size = 0;
+    };
+
+    for (line_no, line) in text.lines().enumerate() {
+        if kind == DocKind::Markdown {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+            } else if !in_fence && line.starts_with('#') {
+                flush(&mut current, &mut size, start, line_no, &heading);
+                heading = Some(line.trim_start_matches('#').trim().to_string());
+            }
+        }
+
+        let boundary = line.trim().is_empty() && !in_fence;
+        if (boundary && size >= CHUNK_CHARS) || size + line.len() > MAX_CHUNK_CHARS {
+            flush(&mut current, &mut size, start, line_no, &heading);
+        }
+        if current.is_empty() {
+            if boundary {
+                continue;
+            }
+            start = line_no;
+        }
+        current.push(line);
+        size += line.len() + 1;
+    }
+    flush(
+        &mut current,
+        &mut size,
+        start,
+        text.lines().count(),
+        &heading,
+    );
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_chunks_follow_sections() {
+        let doc = "# Setup\n\nInstall it.\n\n```sh\n# not a heading\ncargo build\n```\n\n## Usage\n\nRun it.\n";
+        let chunks = chunk(doc, DocKind::Markdown);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].location, "Setup");
+        assert!(chunks[0].text.contains("# not a heading"));
+        assert_eq!(chunks[1].location, "Usage");
+        assert!(chunks[1].text.starts_with("## Usage"));
+    }
+
+    #[test]
+    fn long_text_splits_at_paragraphs() {
+        let paragraph = "word ".repeat(100);
+        let doc = [paragraph.trim(); 10].join("\n\n");
+        let chunks = chunk(&doc, DocKind::Text);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.text.len() <= MAX_CHUNK_CHARS));
+        assert_eq!(chunks[0].location, "lines 1-5");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::knowledge::chunk::chunk;
+use crate::knowledge::load::Document;
+use crate::memory::bm25;
+use crate::memory::hybrid::{cosine_similarity, reciprocal_rank_fusion};
+use crate::traits::Embedder;
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Source {
+    pub source: String,
+    pub chunks: usize,
+    pub ingested_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredChunk {
+    source: String,
+    location: String,
+    text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    vector: Vec<f32>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct IndexFile {
+    /// Embedding model of the stored vectors.
+    model: Option<String>,
+    sources: Vec<Source>,
+    chunks: Vec<StoredChunk>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KnowledgeHit {
+    pub source: String,
+    pub location: String,
+    pub text: String,
+    pub score: f64,
+}
+
+/// Chunks of ingested documents in `knowledge/index.json`, searched the same way as
+/// hybrid memory: BM25 fused with embedding similarity when an embedder is set.
+pub struct KnowledgeIndex {
+    path: PathBuf,
+    embedder: Option<Arc<dyn Embedder>>,
+    index: tokio::sync::Mutex<Option<IndexFile>>,
+}
+
+pub fn knowledge_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("knowledge")
+}
+
+impl KnowledgeIndex {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            path: knowledge_dir(workspace_dir).join(INDEX_FILE),
+            embedder: None,
+            index: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    async fn load(&self) -> Result<IndexFile> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(IndexFile::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, index: &IndexFile) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&self.path, serde_json::to_vec(index)?).await?;
+        Ok(())
+    }
+
+    /// Chunks and indexes a document, replacing an earlier version of the same source.
+    /// Returns the number of chunks.
+    pub async fn add(&self, document: &Document) -> Result<usize> {
+        let mut guard = self.index.lock().await;
+        let mut index = match guard.take() {
+            Some(index) => index,
+            None => self.load().await?,
+        };
+
+        index.chunks.retain(|c| c.source != document.source);
+        index.sources.retain(|s| s.source != document.source);
+        let chunks = chunk(&document.text, document.kind);
+        let count = chunks.len();
+        index
+            .chunks
+            .extend(chunks.into_iter().map(|chunk| StoredChunk {
+                source: document.source.clone(),
+                location: chunk.location,
+                text: chunk.text,
+                vector: Vec::new(),
+            }));
+        index.sources.push(Source {
+            source: document.source.clone(),
+            chunks: count,
+            ingested_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        });
+
+        if let Some(embedder) = &self.embedder {
+            // Vectors from another model are not comparable, so re-embed everything.
+            if index.model.as_deref() != Some(embedder.model()) {
+                index.chunks.iter_mut().for_each(|c| c.vector.clear());
+                index.model = Some(embedder.model().to_string());
+            }
+            let missing: Vec<usize> = (0..index.chunks.len())
+                .filter(|&i| index.chunks[i].vector.is_empty())
+                .collect();
+            let texts: Vec<String> = missing
+                .iter()
+                .map(|&i| embedding_text(&index.chunks[i]))
+                .collect();
+            if !texts.is_empty() {
+                let vectors = embedder.embed(&texts).await?;
+                for (i, vector) in missing.into_iter().zip(vectors) {
+                    index.chunks[i].vector = vector;
+                }
+            }
+        }
+
+        self.save(&index).await?;
+        *guard = Some(index);
+        Ok(count)
+    }
+
+    pub async fn remove(&self, source: &str) -> Result<bool> {
+        let mut guard = self.index.lock().await;
+        let mut index = match guard.take() {
+            Some(index) => index,
+            None => self.load().await?,
+        };
+        let before = index.sources.len();
+        index.sources.retain(|s| s.source != source);
+        index.chunks.retain(|c| c.source != source);
+        let removed = index.sources.len() != before;
+        if removed {
+            self.save(&index).await?;
+        }
+        *guard = Some(index);
+        Ok(removed)
+    }
+
+    pub async fn sources(&self) -> Result<Vec<Source>> {
+        let mut guard = self.index.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load().await?);
+        }
+        Ok(guard
+            .as_ref()
+            .map(|i| i.sources.clone())
+            .unwrap_or_default())
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<KnowledgeHit>> {
+        let mut guard = self.index.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load().await?);
+        }
+        let Some(index) = guard.as_ref() else {
+            return Ok(Vec::new());
+        };
+        if index.chunks.is_empty() || query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let documents: Vec<String> = index.chunks.iter().map(embedding_text).collect();
+        let refs: Vec<&str> = documents.iter().map(String::as_str).collect();
+        let mut rankings = vec![bm25::rank(query, &refs)];
+
+        if let Some(embedder) = &self.embedder
+            && index.model.as_deref() == Some(embedder.model())
+        {
+            match embedder.embed(&[query.to_string()]).await {
+                Ok(mut vectors) => {
+                    let query_vector = vectors.pop().unwrap_or_default();
+                    let mut ranking: Vec<(usize, f64)> = index
+                        .chunks
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, c)| !c.vector.is_empty())
+                        .map(|(i, c)| (i, cosine_similarity(&query_vector, &c.vector)))
+                        .collect();
+                    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    ranking.truncate((limit * 2).max(10));
+                    rankings.push(ranking);
+                }
+                Err(e) => tracing::warn!("Embedding search failed, using keywords only: {}", e),
+            }
+        }
+
+        Ok(reciprocal_rank_fusion(&rankings)
+            .into_iter()
+            .take(limit)
+            .map(|(i, score)| {
+                let chunk = &index.chunks[i];
+                KnowledgeHit {
+                    source: chunk.source.clone(),
+                    location: chunk.location.clone(),
+                    text: chunk.text.clone(),
+                    score,
+                }
+            })
+            .collect())
+    }
+}
+
+fn embedding_text(chunk: &StoredChunk) -> String {
+    format!("{}\n{}", chunk.location, chunk.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge::chunk::DocKind;
+    use tempfile::TempDir;
+
+    fn document(source: &str, text: &str) -> Document {
+        Document {
+            source: source.to_string(),
+            kind: DocKind::Markdown,
+            text: text.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn add_search_and_replace() {
+        let tmp = TempDir::new().unwrap();
+        let index = KnowledgeIndex::new(tmp.path());
+        index
+            .add(&document(
+                "/docs/deploy.md",
+                "# Deploy\n\nRun the release pipeline from the ops repo.\n\n# Rollback\n\nUse the previous image tag.",
+            ))
+            .await
+            .unwrap();
+        index
+            .add(&document("/docs/team.md", "# Team\n\nAda owns billing."))
+            .await
+            .unwrap();
+
+        let hits = index.search("how do I rollback", 3).await.unwrap();
+        assert_eq!(hits[0].source, "/docs/deploy.md");
+        assert_eq!(hits[0].location, "Rollback");
+
+        let reopened = KnowledgeIndex::new(tmp.path());
+        assert_eq!(reopened.sources().await.unwrap().len(), 2);
+        reopened
+            .add(&document("/docs/team.md", "# Team\n\nGrace owns billing."))
+            .await
+            .unwrap();
+        let hits = reopened.search("billing", 3).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].text.contains("Grace"));
+
+        assert!(reopened.remove("/docs/team.md").await.unwrap());
+        assert!(reopened.search("billing", 3).await.unwrap().is_empty());
+    }
+}
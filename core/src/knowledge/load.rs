@@ -0,0 +1,127 @@
+use crate::knowledge::chunk::DocKind;
+use crate::tools::html::html_to_markdown;
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use std::time::Duration;
+
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// Absolute path or URL, used to replace or remove the document later.
+    pub source: String,
+    pub kind: DocKind,
+    pub text: String,
+}
+
+/// Loads a file, or every supported file under a directory (respecting `.gitignore`).
+pub fn load_path(path: &Path) -> Result<Vec<Document>> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Cannot read {}", path.display()))?;
+    if path.is_file() {
+        return Ok(vec![load_file(&path)?]);
+    }
+
+    let mut documents = Vec::new();
+    for entry in ignore::WalkBuilder::new(&path).build().flatten() {
+        let file = entry.path();
+        if !file.is_file() || !is_supported(file) {
+            continue;
+        }
+        match load_file(file) {
+            Ok(document) => documents.push(document),
+            Err(e) => tracing::warn!("Skipping {}: {}", file.display(), e),
+        }
+    }
+    Ok(documents)
+}
+
+fn is_supported(path: &Path) -> bool {
+    DocKind::from_path(path).is_some() || is_pdf(path)
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+}
+
+fn load_file(path: &Path) -> Result<Document> {
+    let size = std::fs::metadata(path)?.len();
+    if size > MAX_FILE_BYTES {
+        bail!("file is larger than {} MB", MAX_FILE_BYTES / 1024 / 1024);
+    }
+
+    let source = path.display().to_string();
+    if is_pdf(path) {
+        return Ok(Document {
+            source,
+            kind: DocKind::Text,
+            text: pdf_text(&std::fs::read(path)?)?,
+        });
+    }
+
+    let bytes = std::fs::read(path)?;
+    let text = String::from_utf8(bytes).context("not a UTF-8 text file")?;
+    Ok(Document {
+        source,
+        kind: DocKind::from_path(path).unwrap_or(DocKind::Text),
+        text,
+    })
+}
+
+/// Fetches a page or file. HTML is converted to markdown.
+pub async fn load_url(url: &str) -> Result<Document> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .connect_timeout(Duration::from_secs(10))
+        .build()?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Failed to fetch {}: HTTP {}", url, status.as_u16());
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    let bytes = response.bytes().await?;
+    if bytes.len() as u64 > MAX_FILE_BYTES {
+        bail!("{} is larger than {} MB", url, MAX_FILE_BYTES / 1024 / 1024);
+    }
+
+    let path = Path::new(url.split(['?', '#']).next().unwrap_or(url));
+    let (kind, text) = if content_type.contains("application/pdf") || is_pdf(path) {
+        (DocKind::Text, pdf_text(&bytes)?)
+    } else {
+        let body = String::from_utf8_lossy(&bytes).into_owned();
+        if content_type.contains("html") {
+            (DocKind::Markdown, html_to_markdown(&body))
+        } else {
+            (DocKind::from_path(path).unwrap_or(DocKind::Text), body)
+        }
+    };
+    Ok(Document {
+        source: url.to_string(),
+        kind,
+        text,
+    })
+}
+
+#[cfg(feature = "pdf")]
+fn pdf_text(bytes: &[u8]) -> Result<String> {
+    pdf_extract::extract_text_from_mem(bytes).map_err(|e| anyhow::anyhow!("Invalid PDF: {e}"))
+}
+
+#[cfg(not(feature = "pdf"))]
+fn pdf_text(_bytes: &[u8]) -> Result<String> {
+    bail!("PDF support requires dinoe built with the 'pdf' feature")
+}
@@ -0,0 +1,7 @@
+pub mod chunk;
+pub mod index;
+pub mod load;
+
+pub use chunk::{Chunk, DocKind, chunk};
+pub use index::{KnowledgeHit, KnowledgeIndex, Source, knowledge_dir};
+pub use load::{Document, load_path, load_url};
@@ -1,12 +1,27 @@
 pub mod agent;
+pub mod audit;
+pub mod builder;
+pub mod checkpoint;
 pub mod config;
+pub mod guardrails;
+pub mod keychain;
+pub mod knowledge;
 pub mod memory;
+pub mod plugins;
 pub mod providers;
+pub mod redact;
+pub mod scheduler;
+pub mod search;
 pub mod skills;
+pub mod telemetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod tools;
 pub mod traits;
+pub mod transcript;
 
 pub use agent::{AgentLoop, ContextBuilder, ToolRegistry};
+pub use builder::{Dinoe, DinoeBuilder};
 pub use config::*;
 pub use memory::*;
 pub use providers::create_provider;
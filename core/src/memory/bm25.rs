@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Lowercased alphanumeric runs, so `INC-1042` matches both `inc-1042` and `1042`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Okapi BM25 scores of `query` against each document. Returns `(index, score)` for
+/// documents that share at least one term with the query, best first.
+pub fn rank(query: &str, documents: &[&str]) -> Vec<(usize, f64)> {
+    let mut terms = tokenize(query);
+    terms.sort();
+    terms.dedup();
+    if terms.is_empty() || documents.is_empty() {
+        return Vec::new();
+    }
+
+    let docs: Vec<Vec<String>> = documents.iter().map(|d| tokenize(d)).collect();
+    let avg_len = docs.iter().map(Vec::len).sum::<usize>() as f64 / docs.len() as f64;
+    let doc_count = docs.len() as f64;
+
+    let idf: HashMap<&str, f64> = terms
+        .iter()
+        .map(|term| {
+            let containing = docs.iter().filter(|d| d.contains(term)).count() as f64;
+            let idf = (1.0 + (doc_count - containing + 0.5) / (containing + 0.5)).ln();
+            (term.as_str(), idf)
+        })
+        .collect();
+
+    let mut scores: Vec<(usize, f64)> = docs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, doc)| {
+            let len_norm = 1.0 - B + B * doc.len() as f64 / avg_len.max(1.0);
+            let score: f64 = terms
+                .iter()
+                .map(|term| {
+                    let tf = doc.iter().filter(|t| *t == term).count() as f64;
+                    idf[term.as_str()] * tf * (K1 + 1.0) / (tf + K1 * len_norm)
+                })
+                .sum();
+            (score > 0.0).then_some((index, score))
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rare_terms_outrank_common_ones() {
+        let docs = [
+            "the deploy failed again",
+            "ticket INC-1042 tracks the deploy failure",
+            "the weather is nice",
+        ];
+        let ranked = rank("deploy INC-1042", &docs);
+        assert_eq!(ranked.iter().map(|r| r.0).collect::<Vec<_>>(), vec![1, 0]);
+        assert!(rank("unrelated", &docs).is_empty());
+    }
+}
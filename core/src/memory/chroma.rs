@@ -0,0 +1,319 @@
+use crate::memory::remote::{
+    http_client, parse_category, parse_meta, send, str_field, timestamp,
+};
+use crate::traits::{Embedder, EntryMeta, Memory, MemoryCategory, MemoryEntry};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+const DEFAULT_TENANT: &str = "default_tenant";
+const DEFAULT_DATABASE: &str = "default_database";
+
+/// Memories stored in a Chroma collection (v2 HTTP API), with embeddings computed by
+/// dinoe rather than by the server.
+pub struct ChromaMemory {
+    client: reqwest::Client,
+    url: String,
+    collection: String,
+    api_key: Option<String>,
+    embedder: Arc<dyn Embedder>,
+    collection_id: OnceCell<String>,
+}
+
+impl ChromaMemory {
+    pub fn new(url: &str, collection: &str, embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            client: http_client(),
+            url: url.trim_end_matches('/').to_string(),
+            collection: collection.to_string(),
+            api_key: None,
+            embedder,
+            collection_id: OnceCell::new(),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.url, path));
+        match &self.api_key {
+            Some(key) => request.header("x-chroma-token", key),
+            None => request,
+        }
+    }
+
+    fn collections_path() -> String {
+        format!("/api/v2/tenants/{DEFAULT_TENANT}/databases/{DEFAULT_DATABASE}/collections")
+    }
+
+    async fn collection_request(
+        &self,
+        method: reqwest::Method,
+        action: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        let id = self
+            .collection_id
+            .get_or_try_init(|| async {
+                let response = send(
+                    self.request(reqwest::Method::POST, &Self::collections_path())
+                        .json(&json!({
+                            "name": self.collection,
+                            "get_or_create": true,
+                            "metadata": { "hnsw:space": "cosine" },
+                        })),
+                )
+                .await?
+                .context("Chroma collections endpoint not found")?;
+                response["id"]
+                    .as_str()
+                    .map(str::to_string)
+                    .context("Chroma returned no collection id")
+            })
+            .await
+            .with_context(|| format!("Failed to open Chroma collection '{}'", self.collection))?;
+        Ok(self.request(
+            method,
+            &format!("{}/{}/{}", Self::collections_path(), id, action),
+        ))
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        self.embedder
+            .embed(&[text.to_string()])
+            .await?
+            .pop()
+            .context("Embedder returned no vector")
+    }
+
+    async fn get_where(
+        &self,
+        filter: Option<Value>,
+        limit: Option<usize>,
+    ) -> Result<Vec<MemoryEntry>> {
+        let body = json!({
+            "where": filter,
+            "limit": limit,
+            "include": ["documents", "metadatas"],
+        });
+        let response = send(
+            self.collection_request(reqwest::Method::POST, "get")
+                .await?
+                .json(&body),
+        )
+        .await?
+        .unwrap_or_default();
+        Ok(entries(
+            &response["ids"],
+            &response["documents"],
+            &response["metadatas"],
+            None,
+        ))
+    }
+}
+
+fn where_clause(conditions: &[(&str, String)]) -> Option<Value> {
+    let mut clauses: Vec<Value> = conditions
+        .iter()
+        .map(|(key, value)| json!({ *key: { "$eq": value } }))
+        .collect();
+    match clauses.len() {
+        0 => None,
+        1 => clauses.pop(),
+        _ => Some(json!({ "$and": clauses })),
+    }
+}
+
+fn entries(
+    ids: &Value,
+    documents: &Value,
+    metadatas: &Value,
+    distances: Option<&Value>,
+) -> Vec<MemoryEntry> {
+    let Some(ids) = ids.as_array() else {
+        return Vec::new();
+    };
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let metadata = &metadatas[i];
+            MemoryEntry {
+                id: id.as_str().unwrap_or_default().to_string(),
+                key: str_field(metadata, "key"),
+                content: documents[i].as_str().unwrap_or_default().to_string(),
+                category: parse_category(metadata["category"].as_str().unwrap_or("core")),
+                timestamp: str_field(metadata, "timestamp"),
+                session_id: metadata["session_id"].as_str().map(str::to_string),
+                score: distances
+                    .and_then(|d| d[i].as_f64())
+                    .map(|distance| 1.0 - distance),
+                meta: parse_meta(
+                    &metadata["tags"],
+                    &metadata["source"],
+                    &metadata["metadata"],
+                ),
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Memory for ChromaMemory {
+    fn name(&self) -> &str {
+        "chroma"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_meta(key, content, category, session_id, &EntryMeta::default())
+            .await
+    }
+
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        let vector = self.embed_one(content).await?;
+        // Chroma rejects null metadata values, so optional fields are only set when present.
+        let mut metadata = json!({
+            "key": key,
+            "category": category.to_string(),
+            "timestamp": timestamp(),
+        });
+        if let Some(session_id) = session_id {
+            metadata["session_id"] = json!(session_id);
+        }
+        if !meta.tags.is_empty() {
+            metadata["tags"] = json!(meta.tags.join(","));
+        }
+        if let Some(source) = meta.source {
+            metadata["source"] = json!(source.to_string());
+        }
+        if !meta.metadata.is_empty() {
+            metadata["metadata"] = json!(serde_json::to_string(&meta.metadata)?);
+        }
+        let body = json!({
+            "ids": [uuid::Uuid::new_v4().to_string()],
+            "embeddings": [vector],
+            "documents": [content],
+            "metadatas": [metadata],
+        });
+        send(
+            self.collection_request(reqwest::Method::POST, "add")
+                .await?
+                .json(&body),
+        )
+        .await
+        .context("Failed to store memory in Chroma")?;
+        Ok(())
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let session_filter = session_id
+            .map(|s| vec![("session_id", s.to_string())])
+            .unwrap_or_default();
+        if query.trim().is_empty() {
+            return self
+                .get_where(where_clause(&session_filter), Some(limit))
+                .await;
+        }
+
+        let vector = self.embed_one(query).await?;
+        let body = json!({
+            "query_embeddings": [vector],
+            "n_results": limit,
+            "where": where_clause(&session_filter),
+            "include": ["documents", "metadatas", "distances"],
+        });
+        let response = send(
+            self.collection_request(reqwest::Method::POST, "query")
+                .await?
+                .json(&body),
+        )
+        .await?
+        .unwrap_or_default();
+        Ok(entries(
+            &response["ids"][0],
+            &response["documents"][0],
+            &response["metadatas"][0],
+            Some(&response["distances"][0]),
+        ))
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let entries = self
+            .get_where(where_clause(&[("key", key.to_string())]), Some(1))
+            .await?;
+        Ok(entries.into_iter().next())
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut conditions = Vec::new();
+        if let Some(category) = category {
+            conditions.push(("category", category.to_string()));
+        }
+        if let Some(session_id) = session_id {
+            conditions.push(("session_id", session_id.to_string()));
+        }
+        let mut entries = self.get_where(where_clause(&conditions), None).await?;
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let ids: Vec<String> = self
+            .get_where(where_clause(&[("key", key.to_string())]), None)
+            .await?
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(false);
+        }
+        send(
+            self.collection_request(reqwest::Method::POST, "delete")
+                .await?
+                .json(&json!({ "ids": ids })),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        let response = send(
+            self.collection_request(reqwest::Method::GET, "count")
+                .await?,
+        )
+        .await?;
+        Ok(response.and_then(|r| r.as_u64()).unwrap_or(0) as usize)
+    }
+
+    async fn health_check(&self) -> bool {
+        matches!(
+            send(self.request(reqwest::Method::GET, "/api/v2/heartbeat")).await,
+            Ok(Some(_))
+        )
+    }
+}
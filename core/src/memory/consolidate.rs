@@ -0,0 +1,232 @@
+use crate::traits::{
+    ChatMessage, ChatRequest, EntryMeta, Memory, MemoryCategory, MemoryEntry, MemorySource,
+    Provider,
+};
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const PROMPT: &str = "You maintain an assistant's long-term memory. Below are daily notes from one day. \
+Extract the facts worth remembering for weeks: preferences, decisions, ongoing projects, people, \
+and outcomes. Skip chatter and anything only relevant that day. Reply with one fact per line, \
+each starting with \"- \", or with NONE if nothing is worth keeping.";
+
+#[derive(Debug, Default, Clone)]
+pub struct ConsolidationReport {
+    /// Days whose daily entries were summarized.
+    pub days: Vec<NaiveDate>,
+    /// Daily entries removed after being summarized.
+    pub entries: usize,
+    /// Core memories written.
+    pub facts: usize,
+}
+
+/// Summarizes old daily entries into `Core` memories, one day at a time, and removes
+/// the daily entries it summarized.
+pub struct Consolidator {
+    memory: Arc<dyn Memory>,
+    provider: Arc<dyn Provider>,
+    model: String,
+    older_than_days: u32,
+}
+
+impl Consolidator {
+    pub fn new(memory: Arc<dyn Memory>, provider: Arc<dyn Provider>, model: &str) -> Self {
+        Self {
+            memory,
+            provider,
+            model: model.to_string(),
+            older_than_days: 7,
+        }
+    }
+
+    /// Only days at least this old are consolidated, so recent context stays verbatim.
+    pub fn with_older_than_days(mut self, days: u32) -> Self {
+        self.older_than_days = days;
+        self
+    }
+
+    /// Daily entries old enough to consolidate, grouped by day.
+    pub async fn pending(&self) -> Result<BTreeMap<NaiveDate, Vec<MemoryEntry>>> {
+        let cutoff = Local::now().date_naive() - chrono::Days::new(u64::from(self.older_than_days));
+        let mut days: BTreeMap<NaiveDate, Vec<MemoryEntry>> = BTreeMap::new();
+        for entry in self.memory.list(Some(&MemoryCategory::Daily), None).await? {
+            let Some(date) = entry
+                .timestamp
+                .get(..10)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            if date < cutoff {
+                days.entry(date).or_default().push(entry);
+            }
+        }
+        Ok(days)
+    }
+
+    pub async fn run(&self) -> Result<ConsolidationReport> {
+        let mut report = ConsolidationReport::default();
+        for (date, mut entries) in self.pending().await? {
+            let Some(facts) = self.summarize(date, &entries).await? else {
+                tracing::warn!("Skipping memory consolidation for {date}: unexpected reply");
+                continue;
+            };
+            let meta = EntryMeta {
+                tags: vec!["consolidated".to_string()],
+                source: Some(MemorySource::Agent),
+                metadata: [("from".to_string(), date.to_string())].into(),
+            };
+            for (i, fact) in facts.iter().enumerate() {
+                let key = format!("{}-{}", date.format("%Y-%m-%d"), i + 1);
+                self.memory
+                    .store_with_meta(&key, fact, MemoryCategory::Core, None, &meta)
+                    .await?;
+            }
+
+            // Markdown keys are positions in the day's file, so remove from the end.
+            entries.sort_by_key(|e| position(&e.key));
+            for entry in entries.iter().rev() {
+                if self.memory.forget(&entry.key).await? {
+                    report.entries += 1;
+                }
+            }
+            report.facts += facts.len();
+            report.days.push(date);
+        }
+        Ok(report)
+    }
+
+    /// `None` when the reply has neither facts nor NONE, so nothing gets deleted.
+    async fn summarize(
+        &self,
+        date: NaiveDate,
+        entries: &[MemoryEntry],
+    ) -> Result<Option<Vec<String>>> {
+        let notes: String = entries
+            .iter()
+            .map(|e| format!("- {}\n", e.content))
+            .collect();
+        let messages = [
+            ChatMessage::system(PROMPT),
+            ChatMessage::user(format!("Notes from {date}:\n{notes}")),
+        ];
+        let response = self
+            .provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                },
+                &self.model,
+                0.2,
+            )
+            .await?;
+        let text = response.text_or_empty();
+        let facts = parse_facts(text);
+        if facts.is_empty() && !text.trim().eq_ignore_ascii_case("none") {
+            return Ok(None);
+        }
+        Ok(Some(facts))
+    }
+}
+
+fn position(key: &str) -> usize {
+    key.rsplit_once(':')
+        .and_then(|(_, index)| index.parse().ok())
+        .unwrap_or(0)
+}
+
+fn parse_facts(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .map(str::trim)
+        })
+        .filter(|fact| !fact.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MarkdownMemory;
+    use crate::traits::{ChatResponse, ProviderEvent};
+    use async_trait::async_trait;
+    use futures_util::stream::BoxStream;
+    use tempfile::TempDir;
+
+    struct FixedProvider(&'static str);
+
+    #[async_trait]
+    impl Provider for FixedProvider {
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            Ok(ChatResponse {
+                text: Some(self.0.to_string()),
+                tool_calls: Vec::new(),
+                reasoning: None,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<BoxStream<'static, ProviderEvent>> {
+            anyhow::bail!("not supported")
+        }
+    }
+
+    #[tokio::test]
+    async fn summarizes_old_days_into_core() {
+        let tmp = TempDir::new().unwrap();
+        let memory: Arc<dyn Memory> = Arc::new(MarkdownMemory::new(tmp.path()));
+        std::fs::create_dir_all(tmp.path().join("memory")).unwrap();
+        std::fs::write(
+            tmp.path().join("memory").join("2020-03-01.md"),
+            "# Daily Log — 2020-03-01\n\n- **a**: Chose Postgres for the billing service\n\n- **b**: Had lunch\n",
+        )
+        .unwrap();
+        memory
+            .store("today", "Still fresh", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+
+        let provider = Arc::new(FixedProvider(
+            "Here you go:\n- Billing service uses Postgres\n",
+        ));
+        let report = Consolidator::new(memory.clone(), provider, "test")
+            .with_older_than_days(1)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.facts, 1);
+        let core = memory
+            .list(Some(&MemoryCategory::Core), None)
+            .await
+            .unwrap();
+        assert!(
+            core.iter()
+                .any(|e| e.content.contains("Billing service uses Postgres"))
+        );
+        let daily = memory
+            .list(Some(&MemoryCategory::Daily), None)
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+        assert!(daily[0].content.contains("Still fresh"));
+    }
+}
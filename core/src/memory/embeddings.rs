@@ -0,0 +1,104 @@
+use crate::traits::Embedder;
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+const BATCH_SIZE: usize = 64;
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Client for OpenAI-compatible `/embeddings` endpoints (OpenAI, OpenRouter, Ollama's
+/// `/v1`, llama.cpp, vLLM, ...).
+pub struct OpenAIEmbedder {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl OpenAIEmbedder {
+    pub fn new(model: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            model: model.into(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into()).filter(|k| !k.is_empty());
+        self
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await.context("Embedding request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Embedding request failed with HTTP {}: {}",
+                status.as_u16(),
+                body
+            );
+        }
+
+        let mut parsed: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Invalid embedding response")?;
+        if parsed.data.len() != texts.len() {
+            bail!(
+                "Embedding response has {} vectors for {} inputs",
+                parsed.data.len(),
+                texts.len()
+            );
+        }
+        parsed.data.sort_by_key(|d| d.index);
+        Ok(parsed.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAIEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+}
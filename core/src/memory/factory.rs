@@ -1,13 +1,150 @@
-use crate::memory::MarkdownMemory;
-use crate::traits::Memory;
+use crate::config::{Config, MemoryBackend, RetrievalMode, WritePolicyMode};
+use crate::memory::{
+    HeuristicScorer, HybridMemory, MarkdownMemory, ModelScorer, OpenAIEmbedder, WritePolicy,
+};
+use crate::traits::{Embedder, Memory, Provider};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Arc;
 
+#[cfg(feature = "vector-store")]
+const DEFAULT_COLLECTION: &str = "dinoe_memory";
+
 pub fn create_memory(workspace_dir: &Path) -> Result<Arc<dyn Memory>> {
     Ok(Arc::new(MarkdownMemory::new(workspace_dir)))
 }
 
+pub fn create_memory_from_config(config: &Config) -> Result<Arc<dyn Memory>> {
+    if config.memory.backend != MemoryBackend::Markdown {
+        return create_vector_store(config);
+    }
+
+    let memory = create_memory(config.memory_root())?;
+    if config.memory.retrieval == RetrievalMode::Keyword {
+        return Ok(memory);
+    }
+
+    let mut hybrid = HybridMemory::new(memory, config.memory_root());
+    if let Some(embedder) = create_embedder(config) {
+        hybrid = hybrid.with_embedder(embedder);
+    }
+    Ok(Arc::new(hybrid))
+}
+
+#[cfg(feature = "vector-store")]
+fn create_vector_store(config: &Config) -> Result<Arc<dyn Memory>> {
+    use crate::memory::{ChromaMemory, QdrantMemory};
+
+    let memory_config = &config.memory;
+    let Some(embedder) = create_embedder(config) else {
+        anyhow::bail!(
+            "memory.backend = \"{}\" needs memory.embedding_model to be set",
+            memory_config.backend
+        );
+    };
+    // Projects get their own collection unless one is configured.
+    let collection = match (&memory_config.collection, config.project_name()) {
+        (Some(collection), _) => collection.clone(),
+        (None, Some(project)) => {
+            let project: String = project
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            format!("{DEFAULT_COLLECTION}_{project}")
+        }
+        (None, None) => DEFAULT_COLLECTION.to_string(),
+    };
+    let collection = collection.as_str();
+    let memory: Arc<dyn Memory> = match memory_config.backend {
+        MemoryBackend::Qdrant => {
+            let url = memory_config.url.as_deref().unwrap_or("http://localhost:6333");
+            let mut store = QdrantMemory::new(url, collection, embedder);
+            if let Some(key) = &memory_config.api_key {
+                store = store.with_api_key(key.clone());
+            }
+            Arc::new(store)
+        }
+        MemoryBackend::Chroma => {
+            let url = memory_config.url.as_deref().unwrap_or("http://localhost:8000");
+            let mut store = ChromaMemory::new(url, collection, embedder);
+            if let Some(key) = &memory_config.api_key {
+                store = store.with_api_key(key.clone());
+            }
+            Arc::new(store)
+        }
+        MemoryBackend::Markdown => create_memory(config.memory_root())?,
+    };
+    Ok(memory)
+}
+
+#[cfg(not(feature = "vector-store"))]
+fn create_vector_store(config: &Config) -> Result<Arc<dyn Memory>> {
+    anyhow::bail!(
+        "memory.backend = \"{}\" requires dinoe built with the 'vector-store' feature",
+        config.memory.backend
+    )
+}
+
+/// An OpenAI-compatible embedder when `memory.embedding_model` is set, reusing the
+/// chat provider's endpoint and key unless overridden.
+pub fn create_embedder(config: &Config) -> Option<Arc<dyn Embedder>> {
+    let model = config.memory.embedding_model.as_ref()?;
+    let provider = config.provider.as_deref().unwrap_or("openai").to_lowercase();
+    let base_url = match (&config.memory.embedding_base_url, &config.base_url) {
+        (Some(url), _) => url.clone(),
+        // Ollama's native API lives at the root; the OpenAI-compatible one under /v1.
+        (None, Some(url)) if provider == "ollama" => {
+            format!("{}/v1", url.trim_end_matches('/'))
+        }
+        (None, Some(url)) => url.clone(),
+        (None, None) => default_embedding_url(&provider).to_string(),
+    };
+    let api_key = config
+        .memory
+        .embedding_api_key
+        .clone()
+        .or_else(|| crate::keychain::api_key(config))
+        .unwrap_or_default();
+    Some(Arc::new(
+        OpenAIEmbedder::new(model.clone())
+            .with_base_url(base_url)
+            .with_api_key(api_key),
+    ))
+}
+
+fn default_embedding_url(provider: &str) -> &'static str {
+    match provider {
+        "ollama" => "http://localhost:11434/v1",
+        "openrouter" => "https://openrouter.ai/api/v1",
+        "zai" | "glm" => "https://api.z.ai/api/paas/v4",
+        _ => "https://api.openai.com/v1",
+    }
+}
+
+pub fn create_write_policy(config: &Config, provider: Arc<dyn Provider>) -> WritePolicy {
+    let threshold = config.memory.importance_threshold;
+    match config.memory.write_policy {
+        WritePolicyMode::All => WritePolicy::All,
+        WritePolicyMode::Summary => WritePolicy::SessionSummary,
+        WritePolicyMode::Off => WritePolicy::Off,
+        WritePolicyMode::Heuristic => WritePolicy::Scored {
+            scorer: Arc::new(HeuristicScorer),
+            threshold,
+        },
+        WritePolicyMode::Model => {
+            let model = config
+                .memory
+                .classifier_model
+                .as_deref()
+                .unwrap_or(&config.model);
+            WritePolicy::Scored {
+                scorer: Arc::new(ModelScorer::new(provider, model)),
+                threshold,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
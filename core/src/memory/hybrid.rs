@@ -0,0 +1,317 @@
+use crate::memory::bm25;
+use crate::traits::{Embedder, EntryMeta, Memory, MemoryCategory, MemoryEntry};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const RRF_K: f64 = 60.0;
+const CACHE_FILE: &str = ".embeddings.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct EmbeddingCache {
+    model: String,
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+/// Recalls entries by fusing a BM25 keyword ranking with an embedding similarity
+/// ranking (reciprocal rank fusion). Keywords catch exact names and IDs, embeddings
+/// catch paraphrases. Without an embedder, or when it fails, BM25 is used alone.
+pub struct HybridMemory {
+    inner: Arc<dyn Memory>,
+    embedder: Option<Arc<dyn Embedder>>,
+    cache_path: PathBuf,
+    cache: tokio::sync::Mutex<Option<EmbeddingCache>>,
+}
+
+impl HybridMemory {
+    pub fn new(inner: Arc<dyn Memory>, workspace_dir: &Path) -> Self {
+        Self {
+            inner,
+            embedder: None,
+            cache_path: workspace_dir.join("memory").join(CACHE_FILE),
+            cache: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    async fn load_cache(&self, model: &str) -> EmbeddingCache {
+        let cache = tokio::fs::read_to_string(&self.cache_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str::<EmbeddingCache>(&content).ok())
+            .filter(|cache| cache.model == model);
+        cache.unwrap_or_else(|| EmbeddingCache {
+            model: model.to_string(),
+            vectors: HashMap::new(),
+        })
+    }
+
+    /// Entry indices by cosine similarity to the query, best first. Vectors are cached
+    /// by content hash so each entry is embedded once.
+    async fn vector_ranking(
+        &self,
+        embedder: &dyn Embedder,
+        query: &str,
+        documents: &[String],
+        limit: usize,
+    ) -> Result<Vec<(usize, f64)>> {
+        let mut guard = self.cache.lock().await;
+        if guard.as_ref().is_none_or(|c| c.model != embedder.model()) {
+            *guard = Some(self.load_cache(embedder.model()).await);
+        }
+        let cache = guard.as_mut().expect("cache loaded above");
+
+        let keys: Vec<String> = documents
+            .iter()
+            .map(|d| format!("{:x}", md5::compute(d.as_bytes())))
+            .collect();
+        let mut missing: Vec<(String, String)> = Vec::new();
+        for (key, document) in keys.iter().zip(documents) {
+            if !cache.vectors.contains_key(key) && !missing.iter().any(|(k, _)| k == key) {
+                missing.push((key.clone(), document.clone()));
+            }
+        }
+
+        if !missing.is_empty() {
+            let texts: Vec<String> = missing.iter().map(|(_, text)| text.clone()).collect();
+            let vectors = embedder.embed(&texts).await?;
+            for ((key, _), vector) in missing.into_iter().zip(vectors) {
+                cache.vectors.insert(key, vector);
+            }
+            cache.vectors.retain(|key, _| keys.contains(key));
+            if let Some(dir) = self.cache_path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            tokio::fs::write(&self.cache_path, serde_json::to_vec(&*cache)?).await?;
+        }
+
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        let mut ranked: Vec<(usize, f64)> = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(index, key)| {
+                let vector = cache.vectors.get(key)?;
+                Some((index, cosine_similarity(&query_vector, vector)))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(limit);
+        Ok(ranked)
+    }
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Sums `1 / (k + rank)` across rankings, so items ranked well by either side rise.
+/// Scores are scaled so an item ranked first everywhere gets 1.0, which keeps them
+/// comparable with the relevance threshold applied to other backends' scores.
+pub(crate) fn reciprocal_rank_fusion(rankings: &[Vec<(usize, f64)>]) -> Vec<(usize, f64)> {
+    let best = rankings.len() as f64 / (RRF_K + 1.0);
+    let mut fused: HashMap<usize, f64> = HashMap::new();
+    for ranking in rankings {
+        for (rank, (index, _)) in ranking.iter().enumerate() {
+            *fused.entry(*index).or_default() += 1.0 / (RRF_K + rank as f64 + 1.0) / best;
+        }
+    }
+    let mut fused: Vec<(usize, f64)> = fused.into_iter().collect();
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    fused
+}
+
+#[async_trait]
+impl Memory for HybridMemory {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.inner.store(key, content, category, session_id).await
+    }
+
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .store_with_meta(key, content, category, session_id, meta)
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if query.trim().is_empty() {
+            return self.inner.recall(query, limit, session_id).await;
+        }
+
+        let entries = self.inner.list(None, session_id).await?;
+        let documents: Vec<String> = entries
+            .iter()
+            .map(|e| format!("{} {}", e.key, e.content))
+            .collect();
+        let refs: Vec<&str> = documents.iter().map(String::as_str).collect();
+
+        let mut rankings = vec![bm25::rank(query, &refs)];
+        if let Some(embedder) = &self.embedder {
+            // Keys can be positional, so vectors are keyed on content alone.
+            let contents: Vec<String> = entries.iter().map(|e| e.content.clone()).collect();
+            let candidates = (limit * 2).max(10);
+            match self
+                .vector_ranking(embedder.as_ref(), query, &contents, candidates)
+                .await
+            {
+                Ok(ranking) => rankings.push(ranking),
+                Err(e) => tracing::warn!("Embedding recall failed, using keywords only: {}", e),
+            }
+        }
+
+        let mut entries: Vec<Option<MemoryEntry>> = entries.into_iter().map(Some).collect();
+        Ok(reciprocal_rank_fusion(&rankings)
+            .into_iter()
+            .take(limit)
+            .filter_map(|(index, score)| {
+                let mut entry = entries[index].take()?;
+                entry.score = Some(score);
+                Some(entry)
+            })
+            .collect())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.inner.get(key).await
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.inner.list(category, session_id).await
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        self.inner.forget(key).await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        self.inner.count().await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+
+    async fn export(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.inner.export().await
+    }
+
+    async fn import(&self, entries: &[MemoryEntry]) -> anyhow::Result<usize> {
+        self.inner.import(entries).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MarkdownMemory;
+    use tempfile::TempDir;
+
+    /// Maps a few words onto shared concept dimensions, like a tiny embedding model.
+    struct ConceptEmbedder;
+
+    #[async_trait]
+    impl Embedder for ConceptEmbedder {
+        fn model(&self) -> &str {
+            "concepts"
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let concepts = [["cat", "feline", "kitten"], ["car", "vehicle", "truck"]];
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    let mut vector: Vec<f32> = concepts
+                        .iter()
+                        .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+                        .collect();
+                    vector.push(0.1);
+                    vector
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn fuses_keyword_and_semantic_matches() {
+        let tmp = TempDir::new().unwrap();
+        let inner: Arc<dyn Memory> = Arc::new(MarkdownMemory::new(tmp.path()));
+        for (key, content) in [
+            ("pet", "Mia adopted a kitten named Tofu"),
+            ("ticket", "Order INC-1042 shipped late"),
+            ("commute", "Drives a truck to work"),
+        ] {
+            inner
+                .store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let keyword_only = HybridMemory::new(inner.clone(), tmp.path());
+        let hits = keyword_only.recall("inc-1042", 5, None).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].content.contains("INC-1042"));
+        assert_eq!(hits[0].score, Some(1.0));
+        assert!(
+            keyword_only
+                .recall("feline", 5, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let hybrid = HybridMemory::new(inner, tmp.path()).with_embedder(Arc::new(ConceptEmbedder));
+        let hits = hybrid.recall("feline friends", 1, None).await.unwrap();
+        assert!(hits[0].content.contains("Tofu"));
+        assert!(tmp.path().join("memory").join(CACHE_FILE).exists());
+    }
+}
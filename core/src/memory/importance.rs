@@ -0,0 +1,295 @@
+use crate::traits::{ChatMessage, ChatRequest, MemoryCategory, Provider};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+const SMALL_TALK: &[&str] = &[
+    "hi",
+    "hello",
+    "hey",
+    "thanks",
+    "thank you",
+    "ok",
+    "okay",
+    "yes",
+    "no",
+    "sure",
+    "cool",
+    "great",
+    "nice",
+    "bye",
+    "got it",
+    "sounds good",
+];
+
+/// Marker phrases per tag. A message matching a tag is more likely worth keeping.
+const MARKERS: &[(&str, f64, &[&str])] = &[
+    (
+        "reminder",
+        0.3,
+        &[
+            "remember",
+            "don't forget",
+            "do not forget",
+            "note that",
+            "keep in mind",
+        ],
+    ),
+    (
+        "preference",
+        0.3,
+        &[
+            "i prefer",
+            "i like",
+            "i love",
+            "i hate",
+            "i don't like",
+            "i dislike",
+            "my favorite",
+            "always ",
+            "never ",
+            "call me",
+        ],
+    ),
+    (
+        "identity",
+        0.2,
+        &[
+            "my name",
+            "i am a",
+            "i'm a",
+            "i work",
+            "my job",
+            "my email",
+            "my birthday",
+            "i live",
+            "my team",
+            "my wife",
+            "my husband",
+            "my partner",
+        ],
+    ),
+    (
+        "decision",
+        0.2,
+        &[
+            "we decided",
+            "i decided",
+            "decided to",
+            "deadline",
+            "due on",
+            "plan to",
+            "we will",
+            "going forward",
+            "from now on",
+        ],
+    ),
+];
+
+const PROMPT: &str = "Rate how useful this chat message is to remember in later conversations \
+with the same user. Durable facts about the user, their preferences, decisions and commitments \
+score high; greetings, questions answered on the spot and generic explanations score low. \
+Reply with JSON only: {\"importance\": 0.0-1.0, \"category\": \"core\" for lasting facts or \
+\"daily\" for things relevant this week, \"tags\": [short lowercase words]}";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    /// 0.0 (noise) to 1.0 (must remember).
+    pub importance: f64,
+    pub category: MemoryCategory,
+    pub tags: Vec<String>,
+}
+
+impl Verdict {
+    fn keep_all() -> Self {
+        Self {
+            importance: 1.0,
+            category: MemoryCategory::Daily,
+            tags: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait ImportanceScorer: Send + Sync {
+    async fn score(&self, role: &str, content: &str) -> Result<Verdict>;
+}
+
+/// Keyword rules: no model call, so it can run on every message.
+pub struct HeuristicScorer;
+
+#[async_trait]
+impl ImportanceScorer for HeuristicScorer {
+    async fn score(&self, role: &str, content: &str) -> Result<Verdict> {
+        Ok(heuristic(role, content))
+    }
+}
+
+fn heuristic(role: &str, content: &str) -> Verdict {
+    let text = content.trim().to_lowercase();
+    let bare = text.trim_end_matches(['.', '!', '?']);
+    if text.len() < 12 || SMALL_TALK.contains(&bare) {
+        return Verdict {
+            importance: 0.0,
+            category: MemoryCategory::Daily,
+            tags: Vec::new(),
+        };
+    }
+
+    let mut importance: f64 = 0.2;
+    let mut tags = Vec::new();
+    for (tag, weight, phrases) in MARKERS {
+        if phrases.iter().any(|p| text.contains(p)) {
+            importance += weight;
+            tags.push(tag.to_string());
+        }
+    }
+    if text.chars().any(|c| c.is_ascii_digit()) {
+        importance += 0.1;
+    }
+    if role == "assistant" {
+        // Answers are mostly derived from context that is stored already.
+        importance -= 0.2;
+    } else if text.ends_with('?') {
+        importance -= 0.1;
+    }
+
+    let durable = tags.iter().any(|t| t == "preference" || t == "identity");
+    Verdict {
+        importance: importance.clamp(0.0, 1.0),
+        category: if durable && role == "user" {
+            MemoryCategory::Core
+        } else {
+            MemoryCategory::Daily
+        },
+        tags,
+    }
+}
+
+#[derive(Deserialize)]
+struct ModelVerdict {
+    importance: f64,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Asks a (preferably cheap) model to rate each message.
+pub struct ModelScorer {
+    provider: Arc<dyn Provider>,
+    model: String,
+}
+
+impl ModelScorer {
+    pub fn new(provider: Arc<dyn Provider>, model: &str) -> Self {
+        Self {
+            provider,
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ImportanceScorer for ModelScorer {
+    async fn score(&self, role: &str, content: &str) -> Result<Verdict> {
+        let messages = [
+            ChatMessage::system(PROMPT),
+            ChatMessage::user(format!("{role}: {content}")),
+        ];
+        let response = self
+            .provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                },
+                &self.model,
+                0.0,
+            )
+            .await?;
+        let text = response.text_or_empty();
+        let json = text
+            .find('{')
+            .zip(text.rfind('}'))
+            .and_then(|(start, end)| text.get(start..=end))
+            .context("Importance reply has no JSON object")?;
+        let verdict: ModelVerdict = serde_json::from_str(json)?;
+        Ok(Verdict {
+            importance: verdict.importance.clamp(0.0, 1.0),
+            category: match verdict.category.as_deref() {
+                Some("core") => MemoryCategory::Core,
+                _ => MemoryCategory::Daily,
+            },
+            tags: verdict.tags,
+        })
+    }
+}
+
+/// Decides which chat messages the agent stores in memory on its own.
+pub enum WritePolicy {
+    /// Every message, as daily entries.
+    All,
+    /// None; the session summary is stored when the session ends.
+    SessionSummary,
+    /// None; only explicit `memory_write` calls.
+    Off,
+    /// Messages scoring at least `threshold`. Scorer errors fall back to the heuristic.
+    Scored {
+        scorer: Arc<dyn ImportanceScorer>,
+        threshold: f64,
+    },
+}
+
+impl WritePolicy {
+    pub async fn evaluate(&self, role: &str, content: &str) -> Option<Verdict> {
+        match self {
+            Self::All => Some(Verdict::keep_all()),
+            Self::SessionSummary | Self::Off => None,
+            Self::Scored { scorer, threshold } => {
+                let verdict = match scorer.score(role, content).await {
+                    Ok(verdict) => verdict,
+                    Err(e) => {
+                        tracing::warn!("Importance scoring failed, using heuristic: {}", e);
+                        heuristic(role, content)
+                    }
+                };
+                (verdict.importance >= *threshold).then_some(verdict)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn heuristic_keeps_facts_and_drops_small_talk() {
+        let policy = WritePolicy::Scored {
+            scorer: Arc::new(HeuristicScorer),
+            threshold: 0.5,
+        };
+        assert!(policy.evaluate("user", "Thanks!").await.is_none());
+        assert!(
+            policy
+                .evaluate("user", "What's the capital of Peru?")
+                .await
+                .is_none()
+        );
+
+        let verdict = policy
+            .evaluate("user", "Remember that I prefer tabs over spaces")
+            .await
+            .unwrap();
+        assert_eq!(verdict.category, MemoryCategory::Core);
+        assert_eq!(verdict.tags, vec!["reminder", "preference"]);
+
+        let verdict = policy
+            .evaluate("user", "We decided the launch deadline is March 3")
+            .await
+            .unwrap();
+        assert_eq!(verdict.category, MemoryCategory::Daily);
+    }
+}
@@ -1,9 +1,72 @@
-use crate::traits::{Memory, MemoryCategory, MemoryEntry};
+use crate::traits::{EntryMeta, Memory, MemoryCategory, MemoryEntry, MemorySource};
 use async_trait::async_trait;
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+const CORE_HEADER: &str = "# Long-term Memory\n\nThis file stores important information that should persist across sessions.\n\n---\n*This file is automatically updated by dinoe when important information should be remembered.*\n\n";
+
+/// Source and metadata, kept in an HTML comment at the end of the entry's line.
+#[derive(Default, Serialize, Deserialize)]
+struct LineAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<MemorySource>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+}
+
+/// Renders `- **key** #tag: content <!-- {...} -->`. Plain entries stay `- **key**: content`.
+fn format_line(key: &str, content: &str, meta: &EntryMeta) -> String {
+    let mut line = format!("- **{key}**");
+    for tag in &meta.tags {
+        line.push_str(" #");
+        line.push_str(&tag.replace(|c: char| c.is_whitespace() || c == ':', "-"));
+    }
+    line.push_str(": ");
+    line.push_str(&content.replace('\n', " "));
+    if meta.source.is_some() || !meta.metadata.is_empty() {
+        let annotations = LineAnnotations {
+            source: meta.source,
+            metadata: meta.metadata.clone(),
+        };
+        let json = serde_json::to_string(&annotations).unwrap_or_default();
+        line.push_str(&format!(" <!-- {} -->", json.replace("-->", "--\\u003e")));
+    }
+    line
+}
+
+/// Splits an entry line into its key, `**key**: content` display text and annotations.
+fn parse_line(clean: &str) -> (Option<&str>, String, EntryMeta) {
+    let mut meta = EntryMeta::default();
+    let mut text = clean;
+    if let Some((rest, comment)) = clean.rsplit_once("<!-- ")
+        && let Some(json) = comment.strip_suffix("-->")
+        && let Ok(annotations) = serde_json::from_str::<LineAnnotations>(json.trim())
+    {
+        meta.source = annotations.source;
+        meta.metadata = annotations.metadata;
+        text = rest.trim_end();
+    }
+
+    let Some((key, rest)) = text.strip_prefix("**").and_then(|t| t.split_once("**")) else {
+        return (None, text.to_string(), meta);
+    };
+    let Some((tags, content)) = rest.split_once(": ") else {
+        return (None, text.to_string(), meta);
+    };
+    let tags: Vec<&str> = tags.split_whitespace().collect();
+    if !tags.iter().all(|t| t.starts_with('#')) {
+        return (None, text.to_string(), meta);
+    }
+    meta.tags = tags
+        .iter()
+        .map(|t| t.trim_start_matches('#').to_string())
+        .collect();
+    (Some(key), format!("**{key}**: {content}"), meta)
+}
+
 pub struct MarkdownMemory {
     workspace_dir: PathBuf,
 }
@@ -28,6 +91,17 @@ impl MarkdownMemory {
         self.memory_dir().join(format!("{date}.md"))
     }
 
+    /// The daily file for an entry's timestamp, or today's when it has no date.
+    fn daily_path_for(&self, timestamp: &str) -> PathBuf {
+        match timestamp
+            .get(..10)
+            .filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok())
+        {
+            Some(date) => self.memory_dir().join(format!("{date}.md")),
+            None => self.daily_path(),
+        }
+    }
+
     fn is_core_path(&self, path: &Path) -> bool {
         path == self.core_path()
     }
@@ -48,11 +122,9 @@ impl MarkdownMemory {
 
         let updated = if existing.is_empty() {
             let header = if self.is_core_path(path) {
-                String::from(
-                    "# Long-term Memory\n\nThis file stores important information that should persist across sessions.\n\n---\n*This file is automatically updated by dinoe when important information should be remembered.*\n\n",
-                )
+                String::from(CORE_HEADER)
             } else {
-                let date = Local::now().format("%Y-%m-%d").to_string();
+                let date = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
                 format!("# Daily Log — {date}\n\n")
             };
             format!("{header}{content}\n")
@@ -74,11 +146,29 @@ impl MarkdownMemory {
             .and_then(|s| s.to_str())
             .unwrap_or("unknown");
 
-        let lines: Vec<&str> = content.lines().collect();
+        Self::entry_lines(content)
+            .into_iter()
+            .enumerate()
+            .map(|(entry_index, (_, clean))| {
+                let (_, content, meta) = parse_line(clean);
+                MemoryEntry {
+                    id: format!("{filename}:{}", entry_index),
+                    key: format!("{filename}:{}", entry_index),
+                    content,
+                    category: category.clone(),
+                    timestamp: filename.to_string(),
+                    session_id: None,
+                    score: None,
+                    meta,
+                }
+            })
+            .collect()
+    }
+
+    fn entry_lines(content: &str) -> Vec<(usize, &str)> {
         let mut entries = Vec::new();
-        let mut entry_index = 0usize;
 
-        for line in lines {
+        for (line_no, line) in content.lines().enumerate() {
             let trimmed = line.trim();
             if trimmed.starts_with("##") {
                 continue;
@@ -93,22 +183,41 @@ impl MarkdownMemory {
                 .unwrap_or(trimmed);
 
             if !clean.is_empty() {
-                entries.push(MemoryEntry {
-                    id: format!("{filename}:{}", entry_index),
-                    key: format!("{filename}:{}", entry_index),
-                    content: clean.to_string(),
-                    category: category.clone(),
-                    timestamp: filename.to_string(),
-                    session_id: None,
-                    score: None,
-                });
-                entry_index += 1;
+                entries.push((line_no, clean));
             }
         }
 
         entries
     }
 
+    async fn memory_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mem_dir = self.memory_dir();
+        if mem_dir.exists() {
+            let mut dir = fs::read_dir(&mem_dir).await?;
+            while let Some(entry) = dir.next_entry().await? {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) == Some("md") {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    async fn remove_lines(path: &Path, content: &str, remove: &[usize]) -> anyhow::Result<()> {
+        let mut updated = content
+            .lines()
+            .enumerate()
+            .filter(|(line_no, _)| !remove.contains(line_no))
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        updated.push('\n');
+        fs::write(path, updated).await?;
+        Ok(())
+    }
+
     async fn read_all_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
         let mut entries = Vec::new();
 
@@ -156,13 +265,25 @@ impl Memory for MarkdownMemory {
     }
 
     async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_meta(key, content, category, session_id, &EntryMeta::default())
+            .await
+    }
+
+    async fn store_with_meta(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         _session_id: Option<&str>,
+        meta: &EntryMeta,
     ) -> anyhow::Result<()> {
-        let entry = format!("- **{key}**: {content}");
+        let entry = format_line(key, content, meta);
         let path = match category {
             MemoryCategory::Core => self.core_path(),
             _ => self.daily_path(),
@@ -234,8 +355,36 @@ impl Memory for MarkdownMemory {
         }
     }
 
-    async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
-        Ok(false)
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        if let Some((stem, index)) = key.rsplit_once(':')
+            && let Ok(index) = index.parse::<usize>()
+        {
+            let path = self.memory_dir().join(format!("{stem}.md"));
+            if !path.exists() {
+                return Ok(false);
+            }
+            let content = fs::read_to_string(&path).await?;
+            let Some((line_no, _)) = Self::entry_lines(&content).get(index).copied() else {
+                return Ok(false);
+            };
+            Self::remove_lines(&path, &content, &[line_no]).await?;
+            return Ok(true);
+        }
+
+        let mut removed = false;
+        for path in self.memory_files().await? {
+            let content = fs::read_to_string(&path).await?;
+            let matches: Vec<usize> = Self::entry_lines(&content)
+                .into_iter()
+                .filter(|(_, clean)| parse_line(clean).0 == Some(key))
+                .map(|(line_no, _)| line_no)
+                .collect();
+            if !matches.is_empty() {
+                Self::remove_lines(&path, &content, &matches).await?;
+                removed = true;
+            }
+        }
+        Ok(removed)
     }
 
     async fn count(&self) -> anyhow::Result<usize> {
@@ -246,6 +395,34 @@ impl Memory for MarkdownMemory {
     async fn health_check(&self) -> bool {
         self.workspace_dir.exists()
     }
+
+    async fn export(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut entries = self.read_all_entries().await?;
+        entries.retain(|e| !CORE_HEADER.contains(&e.content));
+        for entry in &mut entries {
+            if let Some((key, content)) = entry
+                .content
+                .strip_prefix("**")
+                .and_then(|rest| rest.split_once("**: "))
+            {
+                entry.key = key.to_string();
+                entry.content = content.to_string();
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn import(&self, entries: &[MemoryEntry]) -> anyhow::Result<usize> {
+        for entry in entries {
+            let path = match entry.category {
+                MemoryCategory::Core => self.core_path(),
+                _ => self.daily_path_for(&entry.timestamp),
+            };
+            let line = format_line(&entry.key, &entry.content, &entry.meta);
+            self.append_to_file(&path, &line).await?;
+        }
+        Ok(entries.len())
+    }
 }
 
 #[cfg(test)]
@@ -355,13 +532,55 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn markdown_forget_is_noop() {
+    async fn markdown_forget_by_key_and_position() {
+        let (_tmp, mem) = temp_workspace();
+        mem.store("a", "stale fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "fresh fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        assert!(mem.forget("a").await.unwrap());
+        assert!(!mem.forget("a").await.unwrap());
+        let remaining = mem.list(Some(&MemoryCategory::Core), None).await.unwrap();
+        assert!(remaining.iter().all(|e| !e.content.contains("stale fact")));
+
+        let fresh = remaining
+            .iter()
+            .find(|e| e.content.contains("fresh fact"))
+            .unwrap();
+        assert!(mem.forget(&fresh.key).await.unwrap());
+        assert!(mem.recall("fresh", 10, None).await.unwrap().is_empty());
+        assert!(!mem.forget("MEMORY:999").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn markdown_keeps_tags_source_and_metadata() {
         let (_tmp, mem) = temp_workspace();
-        mem.store("a", "permanent", MemoryCategory::Core, None)
+        let meta = EntryMeta {
+            tags: vec!["preference".into(), "editor".into()],
+            source: Some(MemorySource::User),
+            metadata: [("project".to_string(), "dinoe --> core".to_string())].into(),
+        };
+        mem.store_with_meta("editor", "Uses Helix", MemoryCategory::Core, None, &meta)
             .await
             .unwrap();
-        let removed = mem.forget("a").await.unwrap();
-        assert!(!removed, "Markdown memory is append-only");
+        mem.store("lunch", "Helix talk at noon", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let filter = crate::traits::MemoryFilter {
+            tags: vec!["preference".into()],
+            ..Default::default()
+        };
+        let hits = mem.recall_filtered("helix", 10, None, &filter).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].content, "**editor**: Uses Helix");
+        assert_eq!(hits[0].meta, meta);
+
+        assert!(mem.forget("editor").await.unwrap());
+        assert_eq!(mem.recall("helix", 10, None).await.unwrap().len(), 1);
     }
 
     #[tokio::test]
@@ -1,5 +1,28 @@
+pub mod bm25;
+#[cfg(feature = "vector-store")]
+pub mod chroma;
+pub mod consolidate;
+pub mod embeddings;
 pub mod factory;
+pub mod hybrid;
+pub mod importance;
 pub mod markdown;
+#[cfg(feature = "vector-store")]
+pub mod qdrant;
+pub mod redacting;
+pub mod transfer;
+#[cfg(feature = "vector-store")]
+mod remote;
 
-pub use factory::create_memory;
+#[cfg(feature = "vector-store")]
+pub use chroma::ChromaMemory;
+pub use consolidate::{ConsolidationReport, Consolidator};
+pub use embeddings::OpenAIEmbedder;
+pub use factory::{create_embedder, create_memory, create_memory_from_config, create_write_policy};
+pub use hybrid::HybridMemory;
+pub use importance::{HeuristicScorer, ImportanceScorer, ModelScorer, Verdict, WritePolicy};
 pub use markdown::MarkdownMemory;
+#[cfg(feature = "vector-store")]
+pub use qdrant::QdrantMemory;
+pub use redacting::RedactingMemory;
+pub use transfer::{ImportSummary, export_jsonl, import_jsonl};
@@ -0,0 +1,313 @@
+use crate::memory::remote::{
+    http_client, parse_category, parse_meta, send, str_field, timestamp,
+};
+use crate::traits::{Embedder, EntryMeta, Memory, MemoryCategory, MemoryEntry, MemoryFilter};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+const PAGE_SIZE: usize = 256;
+
+/// Memories stored as points in a Qdrant collection, one per entry, searched by
+/// embedding similarity. The collection is created on the first write.
+pub struct QdrantMemory {
+    client: reqwest::Client,
+    url: String,
+    collection: String,
+    api_key: Option<String>,
+    embedder: Arc<dyn Embedder>,
+}
+
+impl QdrantMemory {
+    pub fn new(url: &str, collection: &str, embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            client: http_client(),
+            url: url.trim_end_matches('/').to_string(),
+            collection: collection.to_string(),
+            api_key: None,
+            embedder,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/collections/{}{}", self.url, self.collection, path);
+        let request = self.client.request(method, url);
+        match &self.api_key {
+            Some(key) => request.header("api-key", key),
+            None => request,
+        }
+    }
+
+    async fn ensure_collection(&self, dimensions: usize) -> Result<()> {
+        if send(self.request(reqwest::Method::GET, ""))
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+        send(
+            self.request(reqwest::Method::PUT, "")
+                .json(&json!({ "vectors": { "size": dimensions, "distance": "Cosine" } })),
+        )
+        .await
+        .with_context(|| format!("Failed to create Qdrant collection '{}'", self.collection))?;
+        Ok(())
+    }
+
+    async fn embed_one(&self, text: &str) -> Result<Vec<f32>> {
+        self.embedder
+            .embed(&[text.to_string()])
+            .await?
+            .pop()
+            .context("Embedder returned no vector")
+    }
+
+    async fn scroll(
+        &self,
+        filter: Option<Value>,
+        limit: Option<usize>,
+    ) -> Result<Vec<MemoryEntry>> {
+        let mut entries = Vec::new();
+        let mut offset = Value::Null;
+        loop {
+            let body = json!({
+                "filter": filter,
+                "limit": limit.unwrap_or(PAGE_SIZE).min(PAGE_SIZE),
+                "offset": offset,
+                "with_payload": true,
+                "with_vector": false,
+            });
+            let Some(response) = send(
+                self.request(reqwest::Method::POST, "/points/scroll")
+                    .json(&body),
+            )
+            .await?
+            else {
+                return Ok(entries);
+            };
+            let result = &response["result"];
+            if let Some(points) = result["points"].as_array() {
+                entries.extend(points.iter().map(|p| to_entry(p, None)));
+            }
+            offset = result["next_page_offset"].clone();
+            if offset.is_null() || limit.is_some_and(|l| entries.len() >= l) {
+                return Ok(entries);
+            }
+        }
+    }
+}
+
+fn query_filter(filter: &MemoryFilter, session_id: Option<&str>) -> Option<Value> {
+    let mut conditions = Vec::new();
+    if let Some(category) = &filter.category {
+        conditions.push(("category", category.to_string()));
+    }
+    if let Some(source) = filter.source {
+        conditions.push(("source", source.to_string()));
+    }
+    if let Some(session_id) = session_id {
+        conditions.push(("session_id", session_id.to_string()));
+    }
+    for tag in &filter.tags {
+        conditions.push(("tags", tag.clone()));
+    }
+    self::filter(&conditions)
+}
+
+fn filter(conditions: &[(&str, String)]) -> Option<Value> {
+    if conditions.is_empty() {
+        return None;
+    }
+    let must: Vec<Value> = conditions
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "match": { "value": value } }))
+        .collect();
+    Some(json!({ "must": must }))
+}
+
+fn to_entry(point: &Value, score: Option<f64>) -> MemoryEntry {
+    let payload = &point["payload"];
+    let id = match &point["id"] {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    MemoryEntry {
+        id,
+        key: str_field(payload, "key"),
+        content: str_field(payload, "content"),
+        category: parse_category(payload["category"].as_str().unwrap_or("core")),
+        timestamp: str_field(payload, "timestamp"),
+        session_id: payload["session_id"].as_str().map(str::to_string),
+        score,
+        meta: parse_meta(&payload["tags"], &payload["source"], &payload["metadata"]),
+    }
+}
+
+#[async_trait]
+impl Memory for QdrantMemory {
+    fn name(&self) -> &str {
+        "qdrant"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_meta(key, content, category, session_id, &EntryMeta::default())
+            .await
+    }
+
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        let vector = self.embed_one(content).await?;
+        self.ensure_collection(vector.len()).await?;
+        let point = json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "vector": vector,
+            "payload": {
+                "key": key,
+                "content": content,
+                "category": category.to_string(),
+                "timestamp": timestamp(),
+                "session_id": session_id,
+                "tags": meta.tags,
+                "source": meta.source,
+                "metadata": meta.metadata,
+            },
+        });
+        send(
+            self.request(reqwest::Method::PUT, "/points?wait=true")
+                .json(&json!({ "points": [point] })),
+        )
+        .await
+        .context("Failed to store memory in Qdrant")?;
+        Ok(())
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, session_id, &MemoryFilter::default())
+            .await
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        filter: &MemoryFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let conditions = query_filter(filter, session_id);
+        if query.trim().is_empty() {
+            return self.scroll(conditions, Some(limit)).await;
+        }
+
+        let vector = self.embed_one(query).await?;
+        let body = json!({
+            "vector": vector,
+            "limit": limit,
+            "with_payload": true,
+            "filter": conditions,
+        });
+        let Some(response) = send(
+            self.request(reqwest::Method::POST, "/points/search")
+                .json(&body),
+        )
+        .await?
+        else {
+            return Ok(Vec::new());
+        };
+        Ok(response["result"]
+            .as_array()
+            .map(|points| {
+                points
+                    .iter()
+                    .map(|p| to_entry(p, p["score"].as_f64()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let entries = self
+            .scroll(filter(&[("key", key.to_string())]), Some(1))
+            .await?;
+        Ok(entries.into_iter().next())
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut conditions = Vec::new();
+        if let Some(category) = category {
+            conditions.push(("category", category.to_string()));
+        }
+        if let Some(session_id) = session_id {
+            conditions.push(("session_id", session_id.to_string()));
+        }
+        let mut entries = self.scroll(filter(&conditions), None).await?;
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let key_filter = filter(&[("key", key.to_string())]);
+        let Some(counted) = send(
+            self.request(reqwest::Method::POST, "/points/count")
+                .json(&json!({ "filter": key_filter, "exact": true })),
+        )
+        .await?
+        else {
+            return Ok(false);
+        };
+        if counted["result"]["count"].as_u64().unwrap_or(0) == 0 {
+            return Ok(false);
+        }
+        send(
+            self.request(reqwest::Method::POST, "/points/delete?wait=true")
+                .json(&json!({ "filter": key_filter })),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        let response = send(
+            self.request(reqwest::Method::POST, "/points/count")
+                .json(&json!({ "exact": true })),
+        )
+        .await?;
+        Ok(response
+            .and_then(|r| r["result"]["count"].as_u64())
+            .unwrap_or(0) as usize)
+    }
+
+    async fn health_check(&self) -> bool {
+        let mut request = self.client.get(format!("{}/healthz", self.url));
+        if let Some(key) = &self.api_key {
+            request = request.header("api-key", key);
+        }
+        matches!(send(request).await, Ok(Some(_)))
+    }
+}
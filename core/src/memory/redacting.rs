@@ -0,0 +1,118 @@
+use crate::redact::Redactor;
+use crate::traits::{EntryMeta, Memory, MemoryCategory, MemoryEntry, MemoryFilter};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Masks secrets before they are written, and in entries stored before redaction
+/// was enabled when they are read back.
+pub struct RedactingMemory {
+    inner: Arc<dyn Memory>,
+    redactor: Arc<Redactor>,
+}
+
+impl RedactingMemory {
+    pub fn new(inner: Arc<dyn Memory>, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+
+    fn redact_entry(&self, mut entry: MemoryEntry) -> MemoryEntry {
+        entry.content = self.redactor.redact(&entry.content).into_owned();
+        entry
+    }
+
+    fn redact_entries(&self, entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        entries.into_iter().map(|e| self.redact_entry(e)).collect()
+    }
+}
+
+#[async_trait]
+impl Memory for RedactingMemory {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let content = self.redactor.redact(content);
+        self.inner.store(key, &content, category, session_id).await
+    }
+
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        let content = self.redactor.redact(content);
+        self.inner
+            .store_with_meta(key, &content, category, session_id, meta)
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let entries = self.inner.recall(query, limit, session_id).await?;
+        Ok(self.redact_entries(entries))
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        filter: &MemoryFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let entries = self
+            .inner
+            .recall_filtered(query, limit, session_id, filter)
+            .await?;
+        Ok(self.redact_entries(entries))
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let entry = self.inner.get(key).await?;
+        Ok(entry.map(|e| self.redact_entry(e)))
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let entries = self.inner.list(category, session_id).await?;
+        Ok(self.redact_entries(entries))
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        self.inner.forget(key).await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        self.inner.count().await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+
+    async fn export(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        let entries = self.inner.export().await?;
+        Ok(self.redact_entries(entries))
+    }
+
+    async fn import(&self, entries: &[MemoryEntry]) -> anyhow::Result<usize> {
+        let entries = self.redact_entries(entries.to_vec());
+        self.inner.import(&entries).await
+    }
+}
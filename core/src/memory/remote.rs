@@ -0,0 +1,76 @@
+use crate::traits::{EntryMeta, MemoryCategory, MemorySource};
+use anyhow::{Result, bail};
+use chrono::Local;
+use serde_json::Value;
+use std::time::Duration;
+
+pub(crate) fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Sends the request and returns the JSON body, or `None` for a 404.
+pub(crate) async fn send(request: reqwest::RequestBuilder) -> Result<Option<Value>> {
+    let response = request.send().await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = response.text().await?;
+    if !status.is_success() {
+        bail!("HTTP {}: {}", status.as_u16(), body.trim());
+    }
+    if body.trim().is_empty() {
+        return Ok(Some(Value::Null));
+    }
+    Ok(Some(serde_json::from_str(&body)?))
+}
+
+pub(crate) fn parse_category(name: &str) -> MemoryCategory {
+    match name {
+        "core" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        other => MemoryCategory::Custom(other.to_string()),
+    }
+}
+
+pub(crate) fn timestamp() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+pub(crate) fn str_field(value: &Value, field: &str) -> String {
+    value[field].as_str().unwrap_or_default().to_string()
+}
+
+pub(crate) fn parse_source(value: &Value) -> Option<MemorySource> {
+    value.as_str().and_then(MemorySource::parse)
+}
+
+/// Reads metadata stored either as an object or as a JSON string (Chroma only allows
+/// scalar metadata values).
+pub(crate) fn parse_meta(tags: &Value, source: &Value, metadata: &Value) -> EntryMeta {
+    let tags = match tags {
+        Value::Array(tags) => tags
+            .iter()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect(),
+        Value::String(tags) => tags
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let metadata = match metadata {
+        Value::String(json) => serde_json::from_str(json).unwrap_or_default(),
+        other => serde_json::from_value(other.clone()).unwrap_or_default(),
+    };
+    EntryMeta {
+        tags,
+        source: parse_source(source),
+        metadata,
+    }
+}
@@ -0,0 +1,98 @@
+use crate::traits::{Memory, MemoryEntry};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Entries whose category and content already exist in the target.
+    pub skipped: usize,
+}
+
+/// Writes every entry as one JSON object per line and returns the number written.
+pub async fn export_jsonl(memory: &dyn Memory, out: &mut impl Write) -> Result<usize> {
+    let entries = memory.export().await?;
+    for entry in &entries {
+        let entry = MemoryEntry {
+            score: None,
+            ..entry.clone()
+        };
+        serde_json::to_writer(&mut *out, &entry)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(entries.len())
+}
+
+/// Reads JSONL written by [`export_jsonl`] and imports entries not already present,
+/// so the same file can be imported repeatedly to sync machines.
+pub async fn import_jsonl(memory: &dyn Memory, input: impl BufRead) -> Result<ImportSummary> {
+    let mut entries = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: MemoryEntry = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid memory entry on line {}", index + 1))?;
+        entries.push(entry);
+    }
+
+    let mut seen: HashSet<(String, String)> = memory
+        .export()
+        .await?
+        .into_iter()
+        .map(|e| (e.category.to_string(), e.content))
+        .collect();
+    let total = entries.len();
+    entries.retain(|e| seen.insert((e.category.to_string(), e.content.clone())));
+
+    let imported = memory.import(&entries).await?;
+    Ok(ImportSummary {
+        imported,
+        skipped: total - entries.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MarkdownMemory;
+    use crate::traits::MemoryCategory;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn round_trips_between_workspaces() {
+        let source_dir = TempDir::new().unwrap();
+        let source = MarkdownMemory::new(source_dir.path());
+        source
+            .store("editor", "Prefers Helix", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        source
+            .store("standup", "Demoed the parser", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+
+        let mut jsonl = Vec::new();
+        assert_eq!(export_jsonl(&source, &mut jsonl).await.unwrap(), 2);
+
+        let target_dir = TempDir::new().unwrap();
+        let target = MarkdownMemory::new(target_dir.path());
+        let summary = import_jsonl(&target, jsonl.as_slice()).await.unwrap();
+        assert_eq!(summary.imported, 2);
+        let again = import_jsonl(&target, jsonl.as_slice()).await.unwrap();
+        assert_eq!((again.imported, again.skipped), (0, 2));
+
+        let exported = target.export().await.unwrap();
+        let editor = exported.iter().find(|e| e.key == "editor").unwrap();
+        assert_eq!(editor.content, "Prefers Helix");
+        assert_eq!(editor.category, MemoryCategory::Core);
+        assert!(
+            exported
+                .iter()
+                .any(|e| e.key == "standup" && e.category == MemoryCategory::Daily)
+        );
+    }
+}
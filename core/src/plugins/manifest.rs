@@ -0,0 +1,108 @@
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "plugin.toml";
+const DEFAULT_MODULE: &str = "plugin.wasm";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_MAX_MEMORY_MB: u64 = 64;
+const MAX_TIMEOUT_SECS: u64 = 600;
+const MAX_MEMORY_MB: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceAccess {
+    #[default]
+    None,
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawManifest {
+    name: String,
+    description: String,
+    module: Option<String>,
+    #[serde(default = "default_parameters")]
+    parameters: serde_json::Value,
+    #[serde(default)]
+    mutating: bool,
+    #[serde(default)]
+    workspace: WorkspaceAccess,
+    timeout_secs: Option<u64>,
+    max_memory_mb: Option<u64>,
+}
+
+fn default_parameters() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginManifest {
+    pub name: String,
+    pub description: String,
+    pub module_path: PathBuf,
+    pub parameters: serde_json::Value,
+    pub mutating: bool,
+    pub workspace: WorkspaceAccess,
+    pub timeout_secs: u64,
+    pub max_memory_mb: u64,
+}
+
+pub fn load_manifest(plugin_dir: &Path) -> Result<PluginManifest> {
+    let path = plugin_dir.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let raw: RawManifest =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    if !is_valid_tool_name(&raw.name) {
+        bail!(
+            "Invalid plugin name '{}': use letters, digits, '_' and '-'",
+            raw.name
+        );
+    }
+    if raw.description.trim().is_empty() {
+        bail!("Plugin '{}' has an empty description", raw.name);
+    }
+    if raw.parameters.get("type").and_then(|t| t.as_str()) != Some("object") {
+        bail!("Plugin '{}' parameters must be an object schema", raw.name);
+    }
+
+    let module = raw.module.as_deref().unwrap_or(DEFAULT_MODULE);
+    if Path::new(module).is_absolute() || module.contains("..") {
+        bail!(
+            "Plugin '{}' module must be a path inside the plugin directory",
+            raw.name
+        );
+    }
+    let module_path = plugin_dir.join(module);
+    if !module_path.is_file() {
+        bail!("Plugin module not found: {}", module_path.display());
+    }
+
+    Ok(PluginManifest {
+        name: raw.name,
+        description: raw.description,
+        module_path,
+        parameters: raw.parameters,
+        mutating: raw.mutating || raw.workspace == WorkspaceAccess::Write,
+        workspace: raw.workspace,
+        timeout_secs: raw
+            .timeout_secs
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+        max_memory_mb: raw
+            .max_memory_mb
+            .unwrap_or(DEFAULT_MAX_MEMORY_MB)
+            .clamp(1, MAX_MEMORY_MB),
+    })
+}
+
+pub(crate) fn is_valid_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
@@ -0,0 +1,122 @@
+pub mod manifest;
+#[cfg(feature = "plugins")]
+pub mod wasm;
+
+pub use manifest::{MANIFEST_FILE, PluginManifest, WorkspaceAccess, load_manifest};
+#[cfg(feature = "plugins")]
+pub use wasm::WasmTool;
+
+use crate::traits::Tool;
+use std::path::{Path, PathBuf};
+
+pub fn plugins_dir() -> PathBuf {
+    crate::config::get_data_dir().join("plugins")
+}
+
+/// Reads every `<plugins_dir>/<name>/plugin.toml`, skipping (and logging) broken plugins.
+pub fn discover(plugins_dir: &Path) -> Vec<PluginManifest> {
+    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+        return Vec::new();
+    };
+
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.join(MANIFEST_FILE).is_file())
+        .collect();
+    dirs.sort();
+
+    let mut manifests: Vec<PluginManifest> = Vec::new();
+    for dir in dirs {
+        match load_manifest(&dir) {
+            Ok(manifest) if manifests.iter().any(|m| m.name == manifest.name) => {
+                tracing::warn!(
+                    "Skipping plugin at {}: duplicate name '{}'",
+                    dir.display(),
+                    manifest.name
+                );
+            }
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => tracing::warn!("Failed to load plugin at {}: {:#}", dir.display(), e),
+        }
+    }
+    manifests
+}
+
+pub fn load_plugin_tools(plugins_dir: &Path, workspace: &Path) -> Vec<Box<dyn Tool>> {
+    let manifests = discover(plugins_dir);
+
+    #[cfg(feature = "plugins")]
+    {
+        manifests
+            .into_iter()
+            .map(|manifest| Box::new(WasmTool::new(manifest, workspace)) as Box<dyn Tool>)
+            .collect()
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    {
+        let _ = workspace;
+        if !manifests.is_empty() {
+            tracing::warn!(
+                count = manifests.len(),
+                "WASM plugins found but dinoe was built without the 'plugins' feature"
+            );
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_plugin(root: &Path, dir: &str, manifest: &str) {
+        let dir = root.join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        std::fs::write(dir.join("plugin.wasm"), b"\0asm").unwrap();
+    }
+
+    #[test]
+    fn discover_loads_valid_manifests_and_skips_broken_ones() {
+        let tmp = TempDir::new().unwrap();
+        write_plugin(
+            tmp.path(),
+            "word_count",
+            r#"
+name = "word_count"
+description = "Count words in text"
+workspace = "write"
+timeout_secs = 5000
+
+[parameters]
+type = "object"
+required = ["text"]
+
+[parameters.properties.text]
+type = "string"
+"#,
+        );
+        write_plugin(
+            tmp.path(),
+            "bad_name",
+            "name = \"../evil\"\ndescription = \"x\"\n",
+        );
+        write_plugin(
+            tmp.path(),
+            "word_count_copy",
+            "name = \"word_count\"\ndescription = \"Another\"\n",
+        );
+
+        let manifests = discover(tmp.path());
+        assert_eq!(manifests.len(), 1);
+        let plugin = &manifests[0];
+        assert_eq!(plugin.name, "word_count");
+        assert_eq!(plugin.parameters["properties"]["text"]["type"], "string");
+        assert_eq!(plugin.workspace, WorkspaceAccess::Write);
+        assert!(plugin.mutating);
+        assert_eq!(plugin.timeout_secs, 600);
+    }
+}
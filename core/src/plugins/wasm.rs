@@ -0,0 +1,265 @@
+use crate::plugins::manifest::{PluginManifest, WorkspaceAccess};
+use crate::traits::{Tool, ToolResult};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+const EPOCH_TICK: Duration = Duration::from_millis(100);
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+const WORKSPACE_GUEST_DIR: &str = "/workspace";
+
+static ENGINE: OnceLock<Result<Engine, String>> = OnceLock::new();
+
+/// Shared engine whose epoch advances every tick, so each run can be given a wall-clock deadline.
+fn engine() -> Result<&'static Engine> {
+    ENGINE
+        .get_or_init(|| {
+            let mut config = Config::new();
+            config.epoch_interruption(true);
+            let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+            let ticker = engine.clone();
+            std::thread::spawn(move || {
+                loop {
+                    std::thread::sleep(EPOCH_TICK);
+                    ticker.increment_epoch();
+                }
+            });
+            Ok(engine)
+        })
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("Failed to start WASM engine: {e}"))
+}
+
+struct PluginState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+struct RunOutput {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+pub struct WasmTool {
+    manifest: Arc<PluginManifest>,
+    workspace: PathBuf,
+    module: Arc<Mutex<Option<Module>>>,
+}
+
+impl WasmTool {
+    pub fn new(manifest: PluginManifest, workspace: impl AsRef<Path>) -> Self {
+        Self {
+            manifest: Arc::new(manifest),
+            workspace: workspace.as_ref().to_path_buf(),
+            module: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Modules are compiled on first use to keep startup fast when plugins go unused.
+fn load_module(cache: &Mutex<Option<Module>>, path: &Path) -> Result<Module> {
+    let mut cached = cache.lock().unwrap();
+    if let Some(module) = cached.as_ref() {
+        return Ok(module.clone());
+    }
+    let module = Module::from_file(engine()?, path)
+        .with_context(|| format!("Failed to compile {}", path.display()))?;
+    *cached = Some(module.clone());
+    Ok(module)
+}
+
+fn run(
+    module: &Module,
+    manifest: &PluginManifest,
+    workspace: &Path,
+    input: Vec<u8>,
+) -> Result<RunOutput> {
+    let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .arg(&manifest.name)
+        .stdin(MemoryInputPipe::new(input))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone());
+    match manifest.workspace {
+        WorkspaceAccess::None => {}
+        WorkspaceAccess::Read => {
+            builder.preopened_dir(
+                workspace,
+                WORKSPACE_GUEST_DIR,
+                DirPerms::READ,
+                FilePerms::READ,
+            )?;
+        }
+        WorkspaceAccess::Write => {
+            builder.preopened_dir(
+                workspace,
+                WORKSPACE_GUEST_DIR,
+                DirPerms::all(),
+                FilePerms::all(),
+            )?;
+        }
+    }
+
+    let state = PluginState {
+        wasi: builder.build_p1(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size(manifest.max_memory_mb as usize * 1024 * 1024)
+            .instances(1)
+            .build(),
+    };
+    let mut store = Store::new(module.engine(), state);
+    store.limiter(|s| &mut s.limits);
+    store.set_epoch_deadline(manifest.timeout_secs * 1000 / EPOCH_TICK.as_millis() as u64);
+
+    let mut linker = Linker::new(module.engine());
+    preview1::add_to_linker_sync(&mut linker, |s: &mut PluginState| &mut s.wasi)?;
+    let instance = linker.instantiate(&mut store, module)?;
+    let start = instance
+        .get_typed_func::<(), ()>(&mut store, "_start")
+        .context("Plugin has no _start export; build it as a WASI command module")?;
+
+    let exit_code = match start.call(&mut store, ()) {
+        Ok(()) => 0,
+        Err(e) => {
+            if let Some(exit) = e.downcast_ref::<I32Exit>() {
+                exit.0
+            } else if matches!(e.downcast_ref::<Trap>(), Some(Trap::Interrupt)) {
+                bail!("Plugin timed out after {}s", manifest.timeout_secs);
+            } else {
+                return Err(e.context("Plugin crashed"));
+            }
+        }
+    };
+    drop(store);
+
+    Ok(RunOutput {
+        exit_code,
+        stdout: String::from_utf8_lossy(&stdout.contents()).to_string(),
+        stderr: String::from_utf8_lossy(&stderr.contents()).to_string(),
+    })
+}
+
+/// Plugins may answer with a `{"success", "output", "error"}` object; anything else is
+/// treated as plain text, with the exit code deciding success.
+fn to_tool_result(output: RunOutput) -> ToolResult {
+    if let Ok(result) = serde_json::from_str::<ToolResult>(output.stdout.trim()) {
+        return result;
+    }
+    if output.exit_code == 0 {
+        return ToolResult::success(output.stdout);
+    }
+    let message = if output.stderr.trim().is_empty() {
+        output.stdout
+    } else {
+        output.stderr
+    };
+    ToolResult::error(format!(
+        "Plugin exited with code {}: {}",
+        output.exit_code,
+        message.trim()
+    ))
+}
+
+#[async_trait]
+impl Tool for WasmTool {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.manifest.parameters.clone()
+    }
+
+    fn is_mutating(&self) -> bool {
+        self.manifest.mutating
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let input = serde_json::to_vec(&args)?;
+        let manifest = self.manifest.clone();
+        let workspace = self.workspace.clone();
+        let cache = self.module.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let module = load_module(&cache, &manifest.module_path)?;
+            run(&module, &manifest, &workspace, input)
+        })
+        .await?;
+
+        match result {
+            Ok(output) => Ok(to_tool_result(output)),
+            Err(e) => Ok(ToolResult::error(format!("{e:#}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ECHO_WAT: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 100))
+    (i32.store (i32.const 4) (i32.const 1000))
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.store (i32.const 4) (i32.load (i32.const 8)))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 12)))))"#;
+
+    const SPIN_WAT: &str = r#"(module (func (export "_start") (loop $l (br $l))))"#;
+
+    fn plugin(dir: &TempDir, wat: &str, timeout_secs: u64) -> WasmTool {
+        let module_path = dir.path().join("plugin.wat");
+        std::fs::write(&module_path, wat).unwrap();
+        let manifest = PluginManifest {
+            name: "test_plugin".to_string(),
+            description: "Test plugin".to_string(),
+            module_path,
+            parameters: serde_json::json!({ "type": "object" }),
+            mutating: false,
+            workspace: WorkspaceAccess::None,
+            timeout_secs,
+            max_memory_mb: 16,
+        };
+        WasmTool::new(manifest, dir.path())
+    }
+
+    #[tokio::test]
+    async fn plugin_receives_args_on_stdin_and_returns_stdout() {
+        let dir = TempDir::new().unwrap();
+        let tool = plugin(&dir, ECHO_WAT, 5);
+
+        let result = tool
+            .execute(serde_json::json!({ "text": "hello" }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, r#"{"text":"hello"}"#);
+    }
+
+    #[tokio::test]
+    async fn runaway_plugin_is_interrupted() {
+        let dir = TempDir::new().unwrap();
+        let tool = plugin(&dir, SPIN_WAT, 1);
+
+        let result = tool.execute(serde_json::json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out"));
+    }
+}
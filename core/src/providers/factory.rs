@@ -1,9 +1,23 @@
 use crate::config::Config;
+use crate::keychain;
 use crate::traits::Provider;
 use crate::providers::{GlmProvider, OllamaProvider, OpenAIProvider, OpenRouterProvider};
+use crate::providers::{Recording, RecordingProvider, ReplayProvider};
 use anyhow::{anyhow, Result};
 
+/// The configured provider, recording its calls or replaced by a recording when
+/// `config.recording` says so.
 pub fn create_provider(config: &Config) -> Result<Box<dyn Provider>> {
+    match &config.recording {
+        Some(Recording::Replay(path)) => Ok(Box::new(ReplayProvider::load(path)?)),
+        Some(Recording::Record(path)) => {
+            Ok(Box::new(RecordingProvider::new(configured_provider(config)?, path)?))
+        }
+        None => configured_provider(config),
+    }
+}
+
+fn configured_provider(config: &Config) -> Result<Box<dyn Provider>> {
     let provider_name = config.provider.as_deref().unwrap_or("openai");
 
     match provider_name.to_lowercase().as_str() {
@@ -18,7 +32,7 @@ pub fn create_provider(config: &Config) -> Result<Box<dyn Provider>> {
         "openai" => {
             let api_key = resolve_api_key_with_fallback(
                 &["OPENAI_API_KEY", "DINOE_OPENAI_API_KEY"],
-                &config.api_key,
+                config,
             )?;
             let mut provider = OpenAIProvider::new(api_key);
             provider = provider.with_model(config.model.clone());
@@ -30,7 +44,7 @@ pub fn create_provider(config: &Config) -> Result<Box<dyn Provider>> {
         "openrouter" => {
             let api_key = resolve_api_key_with_fallback(
                 &["OPENROUTER_API_KEY", "DINOE_OPENROUTER_API_KEY"],
-                &config.api_key,
+                config,
             )?;
             let mut provider = OpenRouterProvider::new(api_key);
             provider = provider.with_model(config.model.clone());
@@ -42,7 +56,7 @@ pub fn create_provider(config: &Config) -> Result<Box<dyn Provider>> {
         "zai" | "glm" => {
             let api_key = resolve_api_key_with_fallback(
                 &["ZAI_API_KEY", "GLM_API_KEY", "DINOE_ZAI_API_KEY", "DINOE_GLM_API_KEY"],
-                &config.api_key,
+                config,
             )?;
             let mut provider = GlmProvider::new(api_key);
             provider = provider.with_model(config.model.clone());
@@ -55,17 +69,19 @@ pub fn create_provider(config: &Config) -> Result<Box<dyn Provider>> {
     }
 }
 
-fn resolve_api_key_with_fallback(env_vars: &[&str], config_key: &str) -> Result<String> {
+fn resolve_api_key_with_fallback(env_vars: &[&str], config: &Config) -> Result<String> {
     for var_name in env_vars {
         if let Ok(key) = resolve_api_key_from_env(var_name) {
             return Ok(key);
         }
     }
-    if !config_key.is_empty() {
-        Ok(config_key.to_string())
-    } else {
-        Err(anyhow!("No API key found"))
-    }
+    keychain::api_key(config).ok_or_else(|| {
+        anyhow!(
+            "No API key found. Set {}, or store one with 'dinoe auth set {}'",
+            env_vars[0],
+            keychain::provider_name(config)
+        )
+    })
 }
 
 fn resolve_api_key_from_env(var_name: &str) -> Result<String> {
@@ -283,6 +283,11 @@ impl GlmProvider {
 
 #[async_trait]
 impl Provider for GlmProvider {
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "glm", model = %model)
+    )]
     async fn chat(
         &self,
         request: ChatRequest<'_>,
@@ -366,9 +371,15 @@ impl Provider for GlmProvider {
         Ok(ChatResponse {
             text,
             tool_calls,
+            reasoning: choice.message.reasoning_content.clone(),
         })
     }
 
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "glm", model = %model)
+    )]
     async fn chat_stream(
         &self,
         request: ChatRequest<'_>,
@@ -3,9 +3,11 @@ pub mod glm;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
+pub mod recording;
 
 pub use factory::create_provider;
 pub use glm::GlmProvider;
 pub use ollama::OllamaProvider;
 pub use openai::OpenAIProvider;
 pub use openrouter::OpenRouterProvider;
+pub use recording::{Recording, RecordingProvider, ReplayProvider};
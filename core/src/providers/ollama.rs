@@ -241,6 +241,11 @@ impl Default for OllamaProvider {
 
 #[async_trait]
 impl Provider for OllamaProvider {
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "ollama", model = %model)
+    )]
     async fn chat(
         &self,
         request: ChatRequest<'_>,
@@ -313,9 +318,18 @@ impl Provider for OllamaProvider {
             content
         };
 
-        Ok(ChatResponse { text, tool_calls })
+        Ok(ChatResponse {
+            text,
+            tool_calls,
+            reasoning: ollama_response.message.thinking,
+        })
     }
 
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "ollama", model = %model)
+    )]
     async fn chat_stream(
         &self,
         request: ChatRequest<'_>,
@@ -196,6 +196,11 @@ impl OpenAIProvider {
 
 #[async_trait]
 impl Provider for OpenAIProvider {
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "openai", model = %model)
+    )]
     async fn chat(
         &self,
         request: ChatRequest<'_>,
@@ -277,9 +282,15 @@ impl Provider for OpenAIProvider {
         Ok(ChatResponse {
             text,
             tool_calls,
+            reasoning: choice.message.reasoning_content.clone(),
         })
     }
 
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "openai", model = %model)
+    )]
     async fn chat_stream(
         &self,
         request: ChatRequest<'_>,
@@ -261,6 +261,11 @@ impl OpenRouterProvider {
 
 #[async_trait]
 impl Provider for OpenRouterProvider {
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "openrouter", model = %model)
+    )]
     async fn chat(
         &self,
         request: ChatRequest<'_>,
@@ -318,6 +323,7 @@ impl Provider for OpenRouterProvider {
             })
             .unwrap_or_default();
 
+        let reasoning = message.reasoning_content.clone();
         let text = match &message.content {
             Some(c) if !c.is_empty() => message.content,
             _ => message.reasoning_content,
@@ -326,9 +332,15 @@ impl Provider for OpenRouterProvider {
         Ok(ChatResponse {
             text,
             tool_calls,
+            reasoning,
         })
     }
 
+    #[tracing::instrument(
+        name = "provider",
+        skip_all,
+        fields(provider = "openrouter", model = %model)
+    )]
     async fn chat_stream(
         &self,
         request: ChatRequest<'_>,
@@ -0,0 +1,310 @@
+use crate::traits::{ChatMessage, ChatRequest, ChatResponse, Provider, ProviderEvent};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Characters of a message shown when a replay differs from its recording.
+const SHOWN_CHARS: usize = 80;
+
+/// Whether provider calls are recorded to a file or answered from one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recording {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// One provider call, a line of a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    /// Names of the tools offered with the request.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub response: ChatResponse,
+}
+
+impl Exchange {
+    fn new(request: ChatRequest<'_>, model: &str, response: ChatResponse) -> Self {
+        Self {
+            model: model.to_string(),
+            messages: request.messages.to_vec(),
+            tools: request
+                .tools
+                .unwrap_or_default()
+                .iter()
+                .map(|t| t.name.clone())
+                .collect(),
+            response,
+        }
+    }
+}
+
+/// Passes calls to another provider and appends each request with its response to a
+/// JSON Lines file, for [`ReplayProvider`].
+pub struct RecordingProvider {
+    inner: Box<dyn Provider>,
+    file: Arc<Mutex<std::fs::File>>,
+}
+
+impl RecordingProvider {
+    /// Records to `path`, after any calls already recorded there.
+    pub fn new(inner: Box<dyn Provider>, path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self {
+            inner,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+fn append(file: &Mutex<std::fs::File>, exchange: &Exchange) {
+    let mut file = file.lock().unwrap();
+    let result = serde_json::to_writer(&mut *file, exchange)
+        .map_err(std::io::Error::from)
+        .and_then(|()| writeln!(file));
+    if let Err(e) = result {
+        tracing::warn!("Failed to record a provider call: {}", e);
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let response = self.inner.chat(request, model, temperature).await?;
+        append(&self.file, &Exchange::new(request, model, response.clone()));
+        Ok(response)
+    }
+
+    /// Recorded once the stream is done, as the response its events add up to.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<BoxStream<'static, ProviderEvent>> {
+        let stream = self.inner.chat_stream(request, model, temperature).await?;
+        let file = self.file.clone();
+        let mut exchange = Some(Exchange::new(request, model, ChatResponse::default()));
+        Ok(stream
+            .inspect(move |event| {
+                let Some(current) = exchange.as_mut() else {
+                    return;
+                };
+                let response = &mut current.response;
+                match event {
+                    ProviderEvent::Token(text) => {
+                        response.text.get_or_insert_default().push_str(text)
+                    }
+                    ProviderEvent::Thinking(text) => {
+                        response.reasoning.get_or_insert_default().push_str(text)
+                    }
+                    ProviderEvent::ToolCall(call) => response.tool_calls.push(call.clone()),
+                    ProviderEvent::Done => {
+                        if let Some(done) = exchange.take() {
+                            append(&file, &done);
+                        }
+                    }
+                }
+            })
+            .boxed())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+}
+
+/// Answers with the responses of a recording, in order, without calling a model. The
+/// agent still runs its tools. Where a request differs from the recorded one, the
+/// difference is logged and kept in [`ReplayProvider::divergences`].
+pub struct ReplayProvider {
+    exchanges: Mutex<VecDeque<Exchange>>,
+    recorded: usize,
+    divergences: Mutex<Vec<String>>,
+}
+
+impl ReplayProvider {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut exchanges = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let exchange: Exchange = serde_json::from_str(&line).with_context(|| {
+                format!("{} line {}: not a recorded call", path.display(), i + 1)
+            })?;
+            exchanges.push(exchange);
+        }
+        Ok(Self::new(exchanges))
+    }
+
+    pub fn new(exchanges: Vec<Exchange>) -> Self {
+        Self {
+            recorded: exchanges.len(),
+            exchanges: Mutex::new(exchanges.into()),
+            divergences: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// How the requests so far differed from the recording, one line per call.
+    pub fn divergences(&self) -> Vec<String> {
+        self.divergences.lock().unwrap().clone()
+    }
+
+    fn next(&self, request: ChatRequest<'_>) -> Result<ChatResponse> {
+        let Some(exchange) = self.exchanges.lock().unwrap().pop_front() else {
+            anyhow::bail!(
+                "The recording has no more responses; it has {} calls",
+                self.recorded
+            );
+        };
+        let call = self.recorded - self.exchanges.lock().unwrap().len();
+        if let Some(difference) = difference(&exchange.messages, request.messages) {
+            let divergence = format!("Call {call}: {difference}");
+            tracing::warn!("Replay differs from the recording. {}", divergence);
+            self.divergences.lock().unwrap().push(divergence);
+        }
+        Ok(exchange.response)
+    }
+}
+
+/// The first message that differs between a recorded request and a new one. System
+/// messages are skipped, since they hold the date and time.
+fn difference(recorded: &[ChatMessage], now: &[ChatMessage]) -> Option<String> {
+    let recorded: Vec<&ChatMessage> = recorded.iter().filter(|m| m.role != "system").collect();
+    let now: Vec<&ChatMessage> = now.iter().filter(|m| m.role != "system").collect();
+    if let Some(i) = (0..recorded.len().min(now.len()))
+        .find(|&i| recorded[i].role != now[i].role || recorded[i].content != now[i].content)
+    {
+        return Some(format!(
+            "message {} was {} {:?}, now {} {:?}",
+            i + 1,
+            recorded[i].role,
+            shorten(&recorded[i].content),
+            now[i].role,
+            shorten(&now[i].content)
+        ));
+    }
+    (recorded.len() != now.len())
+        .then(|| format!("{} messages were sent, now {}", recorded.len(), now.len()))
+}
+
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(SHOWN_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// A response as the events a streaming provider would send for it.
+pub(crate) fn response_events(response: ChatResponse) -> BoxStream<'static, ProviderEvent> {
+    let mut events = Vec::new();
+    events.extend(response.reasoning.map(ProviderEvent::Thinking));
+    events.extend(response.text.map(ProviderEvent::Token));
+    events.extend(response.tool_calls.into_iter().map(ProviderEvent::ToolCall));
+    events.push(ProviderEvent::Done);
+    futures_util::stream::iter(events).boxed()
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.next(request)
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest<'_>,
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<BoxStream<'static, ProviderEvent>> {
+        Ok(response_events(self.next(request)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockProvider, TempWorkspace};
+    use crate::tools::FileWriteTool;
+    use crate::{AgentLoop, ContextBuilder, ToolRegistry};
+    use serde_json::json;
+
+    fn agent(provider: Arc<dyn Provider>, workspace: &TempWorkspace, streaming: bool) -> AgentLoop {
+        let tools = ToolRegistry::new();
+        tools.register(Box::new(FileWriteTool::new(workspace.path())));
+        AgentLoop::new(
+            provider,
+            ContextBuilder::new(workspace.path()),
+            Arc::new(tools),
+        )
+        .with_streaming(streaming)
+    }
+
+    #[tokio::test]
+    async fn replays_a_recorded_turn() {
+        for streaming in [false, true] {
+            let workspace = TempWorkspace::new();
+            let path = workspace.join("recording.jsonl");
+            let mock = MockProvider::new()
+                .with_tool_call("file_write", json!({ "path": "a.txt", "content": "hi" }))
+                .with_text("Wrote a.txt");
+            let recorder = RecordingProvider::new(Box::new(mock), &path).unwrap();
+            let sink = crate::agent::EventSink::new(|_| {});
+            let answer = agent(Arc::new(recorder), &workspace, streaming)
+                .process_with_events("Write a.txt", vec![], sink.clone())
+                .await
+                .into_result()
+                .unwrap();
+            assert_eq!(answer, "Wrote a.txt");
+
+            std::fs::remove_file(workspace.join("a.txt")).unwrap();
+            let replay = Arc::new(ReplayProvider::load(&path).unwrap());
+            let answer = agent(replay.clone(), &workspace, streaming)
+                .process_with_events("Write a.txt", vec![], sink.clone())
+                .await
+                .into_result()
+                .unwrap();
+            assert_eq!(answer, "Wrote a.txt");
+            assert_eq!(workspace.read("a.txt"), "hi");
+            assert!(
+                replay.divergences().is_empty(),
+                "{:?}",
+                replay.divergences()
+            );
+
+            let replay = Arc::new(ReplayProvider::load(&path).unwrap());
+            let _ = agent(replay.clone(), &workspace, streaming)
+                .process_with_events("Write b.txt", vec![], sink)
+                .await;
+            assert!(replay.divergences()[0].starts_with("Call 1: message 1 was user"));
+        }
+    }
+}
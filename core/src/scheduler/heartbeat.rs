@@ -0,0 +1,264 @@
+use crate::agent::AgentLoop;
+use crate::config::HeartbeatConfig;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const HEARTBEAT_FILE: &str = "HEARTBEAT.md";
+const STATE_FILE: &str = "heartbeat.json";
+/// What the agent answers when nothing on the checklist needs doing.
+pub const HEARTBEAT_OK: &str = "HEARTBEAT_OK";
+
+const PROMPT: &str = "This is a heartbeat: you woke up on your own and nobody is watching. \
+Go through the checklist below, from HEARTBEAT.md in the workspace, and do what is due now, \
+such as reminders or reports. Do not repeat what was already done at an earlier heartbeat. \
+If nothing needs doing, reply with HEARTBEAT_OK and nothing else.";
+
+/// The time of day the heartbeat runs in, such as `08:00-22:00`. It may wrap past
+/// midnight, as `22:00-06:00` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl ActiveHours {
+    pub fn parse(hours: &str) -> Result<Self> {
+        let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M");
+        let parsed = hours
+            .split_once('-')
+            .and_then(|(start, end)| Some((parse(start).ok()?, parse(end).ok()?)));
+        match parsed {
+            Some((start, end)) if start != end => Ok(Self { start, end }),
+            _ => bail!("Invalid active hours '{hours}', expected HH:MM-HH:MM"),
+        }
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// `time` when it is in these hours, otherwise when they next start.
+    fn next_from(&self, time: DateTime<Local>) -> DateTime<Local> {
+        if self.contains(time.time()) {
+            return time;
+        }
+        let start = |date: chrono::NaiveDate| {
+            Local
+                .from_local_datetime(&date.and_time(self.start))
+                .earliest()
+        };
+        let today = time.date_naive();
+        match start(today) {
+            Some(start) if start > time => start,
+            _ => today.succ_opt().and_then(start).unwrap_or(time),
+        }
+    }
+}
+
+/// How a heartbeat went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Beat {
+    /// HEARTBEAT.md is missing or has nothing to check, so the model was not asked.
+    Empty,
+    /// The agent found nothing to do.
+    Idle,
+    /// The agent did something; its answer.
+    Acted(String),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    last_run: Option<DateTime<Local>>,
+}
+
+/// Wakes the agent every `interval` to go through HEARTBEAT.md. When it last did is kept
+/// in `heartbeat.json` in the workspace, so a restarted daemon keeps the pace.
+pub struct Heartbeat {
+    workspace_dir: PathBuf,
+    interval: Duration,
+    active_hours: Option<ActiveHours>,
+}
+
+impl Heartbeat {
+    pub fn new(workspace_dir: &Path, interval: Duration) -> Self {
+        Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+            interval,
+            active_hours: None,
+        }
+    }
+
+    pub fn with_active_hours(mut self, hours: ActiveHours) -> Self {
+        self.active_hours = Some(hours);
+        self
+    }
+
+    /// The heartbeat `config` describes, or `None` when it is turned off.
+    pub fn from_config(config: &HeartbeatConfig, workspace_dir: &Path) -> Result<Option<Self>> {
+        if config.interval_minutes == 0 {
+            return Ok(None);
+        }
+        let interval = Duration::minutes(config.interval_minutes.min(i32::MAX as u64) as i64);
+        let mut heartbeat = Self::new(workspace_dir, interval);
+        if let Some(hours) = &config.active_hours {
+            heartbeat = heartbeat.with_active_hours(ActiveHours::parse(hours)?);
+        }
+        Ok(Some(heartbeat))
+    }
+
+    pub fn checklist_path(&self) -> PathBuf {
+        self.workspace_dir.join(HEARTBEAT_FILE)
+    }
+
+    /// The checklist, or `None` when the file is missing or holds only headings,
+    /// comments and empty items.
+    pub fn checklist(&self) -> Option<String> {
+        let text = std::fs::read_to_string(self.checklist_path()).ok()?;
+        has_items(&text).then_some(text)
+    }
+
+    /// When the next heartbeat is due, from `now` on: an interval after the last one,
+    /// within the active hours.
+    pub fn next_run(&self, now: DateTime<Local>) -> Result<DateTime<Local>> {
+        let next = match self.load()?.last_run {
+            Some(last) => (last + self.interval).max(now),
+            None => now,
+        };
+        Ok(match &self.active_hours {
+            Some(hours) => hours.next_from(next),
+            None => next,
+        })
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> Result<bool> {
+        Ok(self.next_run(now)? <= now)
+    }
+
+    /// Has `agent` go through the checklist. The run is recorded before the agent starts,
+    /// so a turn that fails is not retried before the next interval.
+    pub async fn beat(&self, agent: &AgentLoop, now: DateTime<Local>) -> Result<Beat> {
+        let last_run = self.load()?.last_run;
+        self.save(&State {
+            last_run: Some(now),
+        })?;
+        let Some(checklist) = self.checklist() else {
+            return Ok(Beat::Empty);
+        };
+        let text = agent
+            .process(&prompt(&checklist, now, last_run))
+            .await
+            .into_result()?;
+        Ok(if text.trim().starts_with(HEARTBEAT_OK) {
+            Beat::Idle
+        } else {
+            Beat::Acted(text)
+        })
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.workspace_dir.join(STATE_FILE)
+    }
+
+    fn load(&self) -> Result<State> {
+        let path = self.state_path();
+        if !path.exists() {
+            return Ok(State::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save(&self, state: &State) -> Result<()> {
+        let path = self.state_path();
+        std::fs::create_dir_all(&self.workspace_dir)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn prompt(checklist: &str, now: DateTime<Local>, last_run: Option<DateTime<Local>>) -> String {
+    let format = "%Y-%m-%d %H:%M (%A)";
+    let last = last_run.map_or("never".to_string(), |t| t.format(format).to_string());
+    format!(
+        "{PROMPT}\n\nNow: {}\nLast heartbeat: {}\n\n{}",
+        now.format(format),
+        last,
+        checklist.trim()
+    )
+}
+
+/// Whether `checklist` has a line other than a heading, an HTML comment or an empty
+/// list item.
+fn has_items(checklist: &str) -> bool {
+    let mut text = checklist.to_string();
+    while let Some(start) = text.find("<!--") {
+        let end = text[start..]
+            .find("-->")
+            .map_or(text.len(), |end| start + end + 3);
+        text.replace_range(start..end, "");
+    }
+    text.lines().map(str::trim).any(|line| {
+        !line.is_empty()
+            && !line.starts_with('#')
+            && !matches!(line, "-" | "*" | "- [ ]" | "* [ ]" | "- [x]" | "* [x]")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::ToolRegistry;
+    use crate::testing::{MockProvider, TempWorkspace};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn beats_only_with_a_checklist() {
+        let workspace = TempWorkspace::new().with_file(
+            HEARTBEAT_FILE,
+            "# Heartbeat\n\n<!-- One item per line.\n- like this -->\n- [ ]\n",
+        );
+        let provider = Arc::new(
+            MockProvider::new()
+                .with_text("HEARTBEAT_OK")
+                .with_text("Reminder: stand-up at 10"),
+        );
+        let agent = workspace.agent(provider.clone(), ToolRegistry::new());
+        let heartbeat = Heartbeat::new(workspace.path(), Duration::minutes(30));
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap();
+
+        assert!(heartbeat.is_due(now).unwrap());
+        assert_eq!(heartbeat.beat(&agent, now).await.unwrap(), Beat::Empty);
+        assert!(provider.requests().is_empty());
+        assert!(!heartbeat.is_due(now + Duration::minutes(29)).unwrap());
+
+        workspace.write(HEARTBEAT_FILE, "- Remind me of the 10:00 stand-up\n");
+        let later = now + Duration::minutes(30);
+        assert!(heartbeat.is_due(later).unwrap());
+        assert_eq!(heartbeat.beat(&agent, later).await.unwrap(), Beat::Idle);
+        let sent = &provider.requests()[0].messages;
+        assert!(sent.last().unwrap().content.contains("stand-up"));
+        assert!(sent.last().unwrap().content.contains("2025-03-10 09:00"));
+        let acted = heartbeat.beat(&agent, later).await.unwrap();
+        assert_eq!(acted, Beat::Acted("Reminder: stand-up at 10".into()));
+
+        let night = heartbeat.with_active_hours(ActiveHours::parse("08:00-22:00").unwrap());
+        let late = Local.with_ymd_and_hms(2025, 3, 10, 23, 0, 0).unwrap();
+        let morning = Local.with_ymd_and_hms(2025, 3, 11, 8, 0, 0).unwrap();
+        assert_eq!(night.next_run(late).unwrap(), morning);
+        assert!(
+            ActiveHours::parse("22:00-06:00")
+                .unwrap()
+                .contains(NaiveTime::MIN)
+        );
+        assert!(ActiveHours::parse("8-22").is_err());
+    }
+}
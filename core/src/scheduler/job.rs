@@ -0,0 +1,124 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+
+const MAX_RESULT_CHARS: usize = 2_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    Cron { expression: String },
+    Once { at: DateTime<Local> },
+}
+
+impl Schedule {
+    pub fn cron(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        if let Err(e) = Cron::new(expression).parse() {
+            bail!("Invalid cron expression '{expression}': {e}");
+        }
+        Ok(Schedule::Cron {
+            expression: expression.to_string(),
+        })
+    }
+
+    pub fn once(at: &str) -> Result<Self> {
+        let at = at.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(at) {
+            return Ok(Schedule::Once {
+                at: time.with_timezone(&Local),
+            });
+        }
+        for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"] {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(at, format)
+                && let Some(time) = Local.from_local_datetime(&naive).earliest()
+            {
+                return Ok(Schedule::Once { at: time });
+            }
+        }
+        bail!("Invalid time '{at}'. Use 'YYYY-MM-DD HH:MM' or RFC 3339")
+    }
+
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Schedule::Cron { expression } => Cron::new(expression)
+                .parse()
+                .ok()?
+                .find_next_occurrence(&after, false)
+                .ok(),
+            Schedule::Once { at } => (*at > after).then_some(*at),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Schedule::Cron { expression } => format!("cron '{expression}'"),
+            Schedule::Once { at } => format!("once at {}", at.format("%Y-%m-%d %H:%M")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub name: String,
+    pub prompt: String,
+    pub schedule: Schedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub next_run: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub last_result: Option<String>,
+    #[serde(default)]
+    pub runs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScheduledJob {
+    pub fn new(name: &str, prompt: &str, schedule: Schedule) -> Result<Self> {
+        let now = Local::now();
+        let Some(next_run) = schedule.next_after(now) else {
+            bail!("Schedule {} never runs in the future", schedule.describe());
+        };
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        Ok(Self {
+            id,
+            name: name.trim().to_string(),
+            prompt: prompt.trim().to_string(),
+            schedule,
+            enabled: true,
+            created_at: now,
+            next_run: Some(next_run),
+            last_run: None,
+            last_result: None,
+            runs: 0,
+        })
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        self.enabled && self.next_run.is_some_and(|next| next <= now)
+    }
+
+    pub fn record_run(&mut self, ran_at: DateTime<Local>, result: &str) {
+        self.last_run = Some(ran_at);
+        self.runs += 1;
+        self.last_result = Some(if result.chars().count() > MAX_RESULT_CHARS {
+            let truncated: String = result.chars().take(MAX_RESULT_CHARS).collect();
+            format!("{truncated}...")
+        } else {
+            result.to_string()
+        });
+        self.next_run = self.schedule.next_after(ran_at);
+        if self.next_run.is_none() {
+            self.enabled = false;
+        }
+    }
+}
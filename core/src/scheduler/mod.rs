@@ -0,0 +1,70 @@
+pub mod heartbeat;
+pub mod job;
+pub mod store;
+
+pub use heartbeat::{ActiveHours, Beat, Heartbeat};
+pub use job::{Schedule, ScheduledJob};
+pub use store::JobStore;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Local, TimeZone};
+    use tempfile::TempDir;
+
+    #[test]
+    fn cron_next_run_is_in_the_future() {
+        let schedule = Schedule::cron("0 9 * * *").unwrap();
+        let from = Local.with_ymd_and_hms(2025, 3, 10, 10, 0, 0).unwrap();
+        let next = schedule.next_after(from).unwrap();
+        assert_eq!(next, Local.with_ymd_and_hms(2025, 3, 11, 9, 0, 0).unwrap());
+        assert!(Schedule::cron("not a cron").is_err());
+    }
+
+    #[test]
+    fn one_off_job_runs_once_and_disables() {
+        let tmp = TempDir::new().unwrap();
+        let store = JobStore::new(tmp.path());
+        let at = (Local::now() + Duration::hours(1))
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        let job = ScheduledJob::new(
+            "reminder",
+            "Remind me to stretch",
+            Schedule::once(&at).unwrap(),
+        )
+        .unwrap();
+        let id = job.id.clone();
+        store.add(job).unwrap();
+
+        assert!(store.due(Local::now()).unwrap().is_empty());
+        let later = Local::now() + Duration::hours(2);
+        assert_eq!(store.due(later).unwrap().len(), 1);
+
+        store.record_run(&id, later, "done").unwrap();
+        let jobs = store.load().unwrap();
+        assert!(!jobs[0].enabled);
+        assert_eq!(jobs[0].runs, 1);
+        assert!(store.due(later).unwrap().is_empty());
+    }
+
+    #[test]
+    fn concurrent_updates_keep_every_job() {
+        let tmp = TempDir::new().unwrap();
+        std::thread::scope(|scope| {
+            for n in 0..8 {
+                let path = tmp.path();
+                scope.spawn(move || {
+                    let job = ScheduledJob::new(
+                        &format!("job {n}"),
+                        "Check the build",
+                        Schedule::cron("0 9 * * *").unwrap(),
+                    )
+                    .unwrap();
+                    JobStore::new(path).add(job).unwrap();
+                });
+            }
+        });
+        assert_eq!(JobStore::new(tmp.path()).load().unwrap().len(), 8);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::scheduler::job::ScheduledJob;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+pub const SCHEDULES_FILE: &str = "schedules.json";
+
+pub struct JobStore {
+    path: PathBuf,
+}
+
+impl JobStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            path: workspace_dir.join(SCHEDULES_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Vec<ScheduledJob>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
+    }
+
+    fn save(&self, jobs: &[ScheduledJob]) -> Result<()> {
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(jobs)?)?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Loads the jobs, applies `change` and saves them, holding a lock on the file so
+    /// the scheduler and the agent do not drop each other's changes.
+    pub fn update<T>(&self, change: impl FnOnce(&mut Vec<ScheduledJob>) -> T) -> Result<T> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock_path = self.path.with_extension("json.lock");
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        lock.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+        let mut jobs = self.load()?;
+        let result = change(&mut jobs);
+        self.save(&jobs)?;
+        Ok(result)
+    }
+
+    pub fn add(&self, job: ScheduledJob) -> Result<()> {
+        self.update(|jobs| jobs.push(job))
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        self.update(|jobs| {
+            let before = jobs.len();
+            jobs.retain(|j| j.id != id);
+            jobs.len() < before
+        })
+    }
+
+    pub fn due(&self, now: DateTime<Local>) -> Result<Vec<ScheduledJob>> {
+        Ok(self.load()?.into_iter().filter(|j| j.is_due(now)).collect())
+    }
+
+    pub fn record_run(&self, id: &str, ran_at: DateTime<Local>, result: &str) -> Result<()> {
+        self.update(|jobs| {
+            if let Some(job) = jobs.iter_mut().find(|j| j.id == id) {
+                job.record_run(ran_at, result);
+            }
+        })
+    }
+
+    pub fn next_wakeup(&self) -> Result<Option<DateTime<Local>>> {
+        Ok(self
+            .load()?
+            .iter()
+            .filter(|j| j.enabled)
+            .filter_map(|j| j.next_run)
+            .min())
+    }
+}
@@ -0,0 +1,332 @@
+use crate::memory::bm25;
+use crate::traits::Memory;
+use crate::transcript;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+/// Characters of text shown around the first match.
+const SNIPPET_CHARS: usize = 160;
+const MAX_NOTE_BYTES: u64 = 1024 * 1024;
+/// Workspace directories that hold something other than notes.
+const NOT_NOTES: [&str; 3] = ["memory", "knowledge", "skills"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Memory,
+    Session,
+    Note,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Memory => write!(f, "memory"),
+            Source::Session => write!(f, "session"),
+            Source::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A memory, a message of a session or a workspace note.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub source: Source,
+    /// Where the text is: a memory key, a session and role, or a path in the workspace.
+    pub location: String,
+    pub date: Option<NaiveDate>,
+    pub category: Option<String>,
+    pub session: Option<String>,
+    pub text: String,
+}
+
+/// Which documents to search. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// All sources when empty.
+    pub sources: Vec<Source>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub category: Option<String>,
+    pub session: Option<String>,
+}
+
+impl Filter {
+    pub fn includes(&self, source: Source) -> bool {
+        self.sources.is_empty() || self.sources.contains(&source)
+    }
+
+    /// Documents without a date never match a date range.
+    pub fn matches(&self, document: &Document) -> bool {
+        let in_range = match (self.since, self.until) {
+            (None, None) => true,
+            (since, until) => document.date.is_some_and(|date| {
+                since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until)
+            }),
+        };
+        self.includes(document.source)
+            && in_range
+            && self.category.as_ref().is_none_or(|category| {
+                document
+                    .category
+                    .as_ref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(category))
+            })
+            && self
+                .session
+                .as_ref()
+                .is_none_or(|session| document.session.as_ref() == Some(session))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub document: Document,
+    pub score: f64,
+    pub snippet: Snippet,
+}
+
+/// A short excerpt of a document on one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub text: String,
+    /// Byte ranges of `text` that match words of the query.
+    pub highlights: Vec<Range<usize>>,
+}
+
+/// The documents that match `filter`, ranked by BM25 against `query`, best first.
+pub fn search(documents: Vec<Document>, query: &str, filter: &Filter, limit: usize) -> Vec<Hit> {
+    let documents: Vec<Document> = documents
+        .into_iter()
+        .filter(|d| filter.matches(d))
+        .collect();
+    let texts: Vec<&str> = documents.iter().map(|d| d.text.as_str()).collect();
+    let ranked = bm25::rank(query, &texts);
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(index, score)| Hit {
+            snippet: snippet(&documents[index].text, query),
+            document: documents[index].clone(),
+            score,
+        })
+        .collect()
+}
+
+/// The text around the first word of `query` in `text`, with whitespace collapsed and
+/// the words of the query marked.
+pub fn snippet(text: &str, query: &str) -> Snippet {
+    let terms: HashSet<String> = bm25::tokenize(query).into_iter().collect();
+    let first = words(text)
+        .find(|range| terms.contains(&text[range.clone()].to_lowercase()))
+        .map_or(0, |range| range.start);
+    let start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CHARS / 4)
+        .map_or(0, |(i, _)| i);
+    // Starts at a word.
+    let start = match text[start..first].find(char::is_whitespace) {
+        Some(i) if start > 0 => start + i,
+        _ => start,
+    };
+    let end = text[start..]
+        .char_indices()
+        .nth(SNIPPET_CHARS)
+        .map_or(text.len(), |(i, _)| start + i);
+
+    let mut excerpt = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        excerpt.insert_str(0, "...");
+    }
+    if end < text.len() {
+        excerpt.push_str("...");
+    }
+    let highlights = words(&excerpt)
+        .filter(|range| terms.contains(&excerpt[range.clone()].to_lowercase()))
+        .collect();
+    Snippet {
+        text: excerpt,
+        highlights,
+    }
+}
+
+/// Byte ranges of the alphanumeric runs in `text`, the words `bm25::tokenize` finds.
+fn words(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (c.is_alphanumeric(), start) {
+            (true, None) => {
+                start = Some(i);
+                None
+            }
+            (false, Some(s)) => {
+                start = None;
+                Some(s..i)
+            }
+            _ => None,
+        })
+}
+
+/// Every entry of `memory`. Entries are dated by the start of their timestamp, which
+/// is a date or an RFC 3339 time depending on the backend.
+pub async fn memory_documents(memory: &dyn Memory) -> Result<Vec<Document>> {
+    Ok(memory
+        .list(None, None)
+        .await?
+        .into_iter()
+        .map(|entry| Document {
+            source: Source::Memory,
+            date: entry
+                .timestamp
+                .get(..10)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            location: entry.key,
+            category: Some(entry.category.to_string()),
+            session: entry.session_id,
+            text: entry.content,
+        })
+        .collect())
+}
+
+/// The messages of every session in `dir` written by the user or the model, one
+/// document each. Tool calls and results are left out.
+pub fn session_documents(dir: &Path) -> Result<Vec<Document>> {
+    let mut documents = Vec::new();
+    for session in transcript::sessions(dir)? {
+        let records = match transcript::load(dir, &session) {
+            Ok(records) => records,
+            Err(e) => {
+                tracing::warn!("Skipping session {}: {}", session, e);
+                continue;
+            }
+        };
+        for record in records {
+            let message = record.message;
+            if !matches!(message.role.as_str(), "user" | "assistant")
+                || message.content.trim().is_empty()
+            {
+                continue;
+            }
+            documents.push(Document {
+                source: Source::Session,
+                location: format!("{session} {}", message.role),
+                date: Some(record.timestamp.date_naive()),
+                category: None,
+                session: Some(session.clone()),
+                text: message.content,
+            });
+        }
+    }
+    Ok(documents)
+}
+
+/// Markdown and text files in the workspace, respecting `.gitignore`. Memory files,
+/// ingested documents and skills are left out. Notes are dated by when they changed.
+pub fn note_documents(workspace_dir: &Path) -> Vec<Document> {
+    let mut documents = Vec::new();
+    let walk = ignore::WalkBuilder::new(workspace_dir)
+        .filter_entry(|entry| {
+            entry.depth() != 1 || !NOT_NOTES.iter().any(|dir| entry.file_name() == *dir)
+        })
+        .build();
+    for entry in walk.flatten() {
+        let path = entry.path();
+        let is_note = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "md" | "txt"));
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !is_note || !metadata.is_file() || metadata.len() > MAX_NOTE_BYTES {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        documents.push(Document {
+            source: Source::Note,
+            location: path
+                .strip_prefix(workspace_dir)
+                .unwrap_or(path)
+                .display()
+                .to_string(),
+            date: metadata
+                .modified()
+                .ok()
+                .map(|time| DateTime::<Local>::from(time).date_naive()),
+            category: None,
+            session: None,
+            text,
+        });
+    }
+    documents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(source: Source, date: &str, text: &str) -> Document {
+        Document {
+            source,
+            location: String::new(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+            category: (source == Source::Memory).then(|| "core".to_string()),
+            session: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn ranks_filters_and_highlights() {
+        let documents = vec![
+            document(
+                Source::Memory,
+                "2025-01-10",
+                "Deploys go through the staging cluster",
+            ),
+            document(
+                Source::Session,
+                "2025-02-01",
+                "The staging deploy failed twice",
+            ),
+            document(Source::Note, "2025-03-01", "Groceries: milk, eggs"),
+        ];
+        let hits = search(documents.clone(), "staging deploy", &Filter::default(), 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].document.source, Source::Session);
+
+        let since = Filter {
+            since: NaiveDate::from_ymd_opt(2025, 1, 15),
+            ..Default::default()
+        };
+        assert_eq!(search(documents.clone(), "staging", &since, 10).len(), 1);
+        let category = Filter {
+            category: Some("Core".into()),
+            ..Default::default()
+        };
+        let hits = search(documents, "staging", &category, 10);
+        assert_eq!(hits[0].document.source, Source::Memory);
+
+        let marked: Vec<&str> = hits[0]
+            .snippet
+            .highlights
+            .iter()
+            .map(|range| &hits[0].snippet.text[range.clone()])
+            .collect();
+        assert_eq!(marked, ["staging"]);
+
+        let long = format!("{} the needle {}", "hay ".repeat(100), "hay ".repeat(100));
+        let snippet = snippet(&long, "needle");
+        assert!(snippet.text.starts_with("...") && snippet.text.ends_with("..."));
+        assert_eq!(&snippet.text[snippet.highlights[0].clone()], "needle");
+    }
+}
@@ -4,6 +4,24 @@ use serde_yaml;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::Triggers;
+
+/// Fields SKILL.md frontmatter may have.
+pub(crate) const FRONTMATTER_FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "version",
+    "author",
+    "license",
+    "tags",
+    "tools",
+    "requires",
+    "compatibility",
+    "examples",
+    "triggers",
+    "permissions",
+];
+
 #[derive(Debug, Deserialize)]
 struct FrontMatter {
     name: String,
@@ -14,22 +32,177 @@ struct FrontMatter {
     author: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
+    tools: Vec<SkillToolDef>,
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    requires: Requirements,
+    #[serde(default)]
+    compatibility: Compatibility,
+    #[serde(default)]
+    examples: Vec<String>,
+    #[serde(default)]
+    triggers: Triggers,
+    #[serde(default)]
+    permissions: Permissions,
+}
+
+/// What a skill needs to work, under `requires:` in the frontmatter.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Requirements {
+    pub tools: Vec<String>,
+    pub binaries: Vec<String>,
+}
+
+/// Where a skill works, under `compatibility:` in the frontmatter. Empty lists allow
+/// everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Compatibility {
+    /// Operating systems as Rust names them: `linux`, `macos`, `windows`, ...
+    pub os: Vec<String>,
+    /// Model names; one ending in `*` matches by prefix, e.g. `anthropic/*`.
+    pub models: Vec<String>,
+}
+
+/// What a skill's tools may reach, under `permissions:` in the frontmatter. By default
+/// they run in a sandbox that only lets them into the workspace.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Permissions {
+    pub network: bool,
+    /// Files and directories outside the workspace, absolute or starting with `~/`.
+    pub paths: Vec<String>,
+    /// Environment variables the tools get, besides PATH, HOME and the like.
+    pub env: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+impl Permissions {
+    /// What the skill asks for, for showing to the user; empty when it asks for nothing.
+    pub fn summary(&self) -> Vec<String> {
+        let mut asks = Vec::new();
+        if self.network {
+            asks.push("network".to_string());
+        }
+        asks.extend(self.paths.iter().cloned());
+        asks.extend(self.env.iter().map(|var| format!("${var}")));
+        asks
+    }
+}
+
+/// An executable tool shipped with a skill, declared under `tools:` in the frontmatter.
+/// Exactly one of `script` (a file in the skill directory) or `command` (a template,
+/// see [`crate::tools::CommandTool`]) is set.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillToolDef {
+    pub name: String,
+    pub description: String,
+    pub script: Option<String>,
+    pub command: Option<String>,
+    #[serde(default = "default_tool_parameters")]
+    pub parameters: serde_json::Value,
+    #[serde(default = "default_true")]
+    pub mutating: bool,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Skill {
     pub name: String,
     pub description: String,
     pub version: String,
     pub author: Option<String>,
+    pub license: Option<String>,
     pub tags: Vec<String>,
+    pub tools: Vec<SkillToolDef>,
+    pub requires: Requirements,
+    pub compatibility: Compatibility,
+    pub examples: Vec<String>,
+    pub triggers: Triggers,
+    pub permissions: Permissions,
     pub location: Option<PathBuf>,
+    /// Whether SKILL.md starts with frontmatter, rather than a heading.
+    pub has_frontmatter: bool,
+}
+
+impl Skill {
+    pub fn dir(&self) -> Option<&Path> {
+        self.location.as_deref().and_then(Path::parent)
+    }
+
+    /// Why the skill cannot be used on this machine with `model`, if it cannot.
+    pub fn unavailable(&self, model: &str) -> Option<String> {
+        let os = std::env::consts::OS;
+        let compatibility = &self.compatibility;
+        if !compatibility.os.is_empty() && !compatibility.os.iter().any(|o| o == os) {
+            return Some(format!("it does not support {os}"));
+        }
+        if !compatibility.models.is_empty()
+            && !compatibility.models.iter().any(|p| model_matches(p, model))
+        {
+            return Some(format!("it does not support the model {model}"));
+        }
+        let missing = self.missing_binaries();
+        if !missing.is_empty() {
+            return Some(format!("{} not found on PATH", missing.join(", ")));
+        }
+        None
+    }
+
+    /// Tools from `requires.tools` that are not among `tools`, the agent's.
+    pub fn missing_tools(&self, tools: &[String]) -> Vec<&str> {
+        self.requires
+            .tools
+            .iter()
+            .filter(|tool| !tools.contains(tool))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Programs from `requires.binaries` that are not on `PATH`.
+    pub fn missing_binaries(&self) -> Vec<&str> {
+        self.requires
+            .binaries
+            .iter()
+            .map(String::as_str)
+            .filter(|binary| !on_path(binary))
+            .collect()
+    }
+}
+
+fn model_matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+fn on_path(binary: &str) -> bool {
+    if binary.contains(['/', '\\']) {
+        return Path::new(binary).is_file();
+    }
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| {
+        dir.join(binary).is_file() || (cfg!(windows) && dir.join(format!("{binary}.exe")).is_file())
+    })
 }
 
 fn default_version() -> String {
     "0.1.0".to_string()
 }
 
+fn default_tool_parameters() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+fn default_true() -> bool {
+    true
+}
+
 pub fn load_skill(skill_dir: &Path) -> Result<Skill> {
     let md_path = skill_dir.join("SKILL.md");
 
@@ -40,29 +213,57 @@ pub fn load_skill(skill_dir: &Path) -> Result<Skill> {
     }
 }
 
+/// The YAML between the `---` lines that open SKILL.md, if there are any.
+pub(crate) fn frontmatter(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() < 3 || lines[0].trim() != "---" {
+        return None;
+    }
+    let end = lines[1..].iter().position(|l| l.trim() == "---")?;
+    Some(lines[1..=end].join("\n"))
+}
+
+/// A frontmatter error with its line in SKILL.md, which is one more than in the YAML.
+fn frontmatter_error(error: &serde_yaml::Error) -> String {
+    let message = error.to_string();
+    match error.location() {
+        Some(at) => {
+            let suffix = format!(" at line {} column {}", at.line(), at.column());
+            let message = message.strip_suffix(&suffix).unwrap_or(&message);
+            format!("line {}: {}", at.line() + 1, message)
+        }
+        None => message,
+    }
+}
+
 fn load_skill_md(path: &Path) -> Result<Skill> {
     let content =
         fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
 
-    let lines: Vec<&str> = content.lines().collect();
-
-    if lines.len() >= 3 && lines[0].trim() == "---" {
-        let closing_index = lines[1..].iter().position(|l| l.trim() == "---");
-
-        if let Some(pos) = closing_index {
-            let frontmatter_str = lines[1..=pos].join("\n");
-
-            if let Ok(frontmatter) = serde_yaml::from_str::<FrontMatter>(&frontmatter_str) {
-                return Ok(Skill {
-                    name: frontmatter.name,
-                    description: frontmatter.description,
-                    version: frontmatter.version,
-                    author: frontmatter.author,
-                    tags: frontmatter.tags,
-                    location: Some(path.to_path_buf()),
-                });
-            }
-        }
+    if let Some(yaml) = frontmatter(&content) {
+        let frontmatter: FrontMatter = serde_yaml::from_str(&yaml).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid frontmatter in {}: {}",
+                path.display(),
+                frontmatter_error(&e)
+            )
+        })?;
+        return Ok(Skill {
+            name: frontmatter.name,
+            description: frontmatter.description,
+            version: frontmatter.version,
+            author: frontmatter.author,
+            license: frontmatter.license,
+            tags: frontmatter.tags,
+            tools: frontmatter.tools,
+            requires: frontmatter.requires,
+            compatibility: frontmatter.compatibility,
+            examples: frontmatter.examples,
+            triggers: frontmatter.triggers,
+            permissions: frontmatter.permissions,
+            location: Some(path.to_path_buf()),
+            has_frontmatter: true,
+        });
     }
 
     let first_line = content.lines().next().unwrap_or("");
@@ -82,9 +283,8 @@ fn load_skill_md(path: &Path) -> Result<Skill> {
         },
         description,
         version: default_version(),
-        author: None,
-        tags: vec![],
         location: Some(path.to_path_buf()),
+        ..Default::default()
     })
 }
 
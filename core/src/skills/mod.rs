@@ -1,16 +1,46 @@
 pub mod manifest;
+pub mod package;
+pub mod read_skill;
 pub mod registry;
+pub mod state;
+pub mod tools;
+pub mod triggers;
+pub mod validate;
 
-pub use manifest::{Skill, load_skill};
+pub use manifest::{Compatibility, Permissions, Requirements, Skill, SkillToolDef, load_skill};
+pub use package::{PackageManifest, package_skill, signing_key, unpack_package};
+pub use read_skill::ReadSkillTool;
 pub use registry::SkillRegistry;
+pub use state::SkillState;
+pub use tools::skill_tools;
+pub use triggers::{Triggers, workspace_files};
+pub use validate::{Validation, validate_skill};
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
+use crate::config::Config;
+
 pub fn skills_dir(workspace_dir: &Path) -> PathBuf {
     workspace_dir.join("skills")
 }
 
+/// The directories skills are loaded from, lowest precedence first: the workspace's,
+/// then `skill_dirs` from the config, then the project's once the project is trusted,
+/// as its skills can define tools.
+pub fn skill_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![skills_dir(&config.workspace_dir)];
+    dirs.extend(config.skill_dirs.iter().cloned());
+    dirs.extend(
+        config
+            .project_dir
+            .as_deref()
+            .filter(|dir| config.trusts(dir))
+            .map(skills_dir),
+    );
+    dirs
+}
+
 pub fn init_skills_dir(workspace_dir: &Path) -> Result<()> {
     let dir = skills_dir(workspace_dir);
     std::fs::create_dir_all(&dir)?;
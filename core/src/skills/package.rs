@@ -0,0 +1,272 @@
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use ring::rand::SystemRandom;
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::{load_skill, validate_skill};
+
+const MANIFEST: &str = "manifest.json";
+const SIGNATURE: &str = "manifest.sig";
+/// Where the skill's files go inside a package.
+const SKILL_DIR: &str = "skill";
+
+/// What a package holds, signed by `manifest.sig`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub name: String,
+    pub version: String,
+    /// Hex SHA-256 of each file, by its path in the skill directory.
+    pub files: BTreeMap<String, String>,
+    /// Hex Ed25519 public key the manifest is signed with.
+    pub public_key: String,
+}
+
+/// The Ed25519 key in `path`, created on first use.
+pub fn signing_key(path: &Path) -> Result<Ed25519KeyPair> {
+    if !path.exists() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate a signing key"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, pkcs8.as_ref())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let pkcs8 =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ed25519KeyPair::from_pkcs8(&pkcs8)
+        .map_err(|_| anyhow::anyhow!("{} is not an Ed25519 key", path.display()))
+}
+
+pub fn public_key(key: &Ed25519KeyPair) -> String {
+    hex(key.public_key().as_ref())
+}
+
+/// Writes the skill in `skill_dir` to `<name>-<version>.tar.gz` in `out_dir`, with a
+/// manifest of checksums signed by `key`. Refuses a skill that does not validate.
+pub fn package_skill(skill_dir: &Path, out_dir: &Path, key: &Ed25519KeyPair) -> Result<PathBuf> {
+    let validation = validate_skill(skill_dir);
+    if !validation.is_valid() {
+        bail!(
+            "Fix the skill before packaging it: {}",
+            validation.errors.join("; ")
+        );
+    }
+    let skill = load_skill(skill_dir)?;
+
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(skill_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = file_name(entry.path(), skill_dir)?;
+        files.insert(name, sha256(&std::fs::read(entry.path())?));
+    }
+    let manifest = PackageManifest {
+        name: skill.name.clone(),
+        version: skill.version.clone(),
+        files,
+        public_key: public_key(key),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let signature = hex(key.sign(&manifest_json).as_ref());
+
+    std::fs::create_dir_all(out_dir)?;
+    let path = out_dir.join(format!("{}-{}.tar.gz", skill.name, skill.version));
+    let file =
+        File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    tar.follow_symlinks(false);
+    append_data(&mut tar, MANIFEST, &manifest_json)?;
+    append_data(&mut tar, SIGNATURE, signature.as_bytes())?;
+    for name in manifest.files.keys() {
+        tar.append_path_with_name(skill_dir.join(name), format!("{SKILL_DIR}/{name}"))?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(path)
+}
+
+/// Unpacks the package at `archive` into `dest` and checks its signature and every
+/// checksum. The skill ends up in `dest/skill`. Whether the signer is trusted is up to
+/// the caller.
+pub fn unpack_package(archive: &Path, dest: &Path) -> Result<PackageManifest> {
+    let file =
+        File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+    let mut manifest_json = None;
+    let mut signature = None;
+    std::fs::create_dir_all(dest)?;
+    for entry in tar.entries().context("Not a skill package")? {
+        let mut entry = entry?;
+        let kind = entry.header().entry_type();
+        let path = entry.path()?.into_owned();
+        if !(kind.is_file() || kind.is_dir())
+            || path
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            bail!("The package has an unsafe entry: {}", path.display());
+        }
+        if path == Path::new(MANIFEST) {
+            manifest_json = Some(read_entry(&mut entry)?);
+        } else if path == Path::new(SIGNATURE) {
+            signature = Some(read_entry(&mut entry)?);
+        } else if path.starts_with(SKILL_DIR) {
+            entry.unpack_in(dest)?;
+        } else {
+            bail!("Unexpected file in the package: {}", path.display());
+        }
+    }
+    let manifest_json = manifest_json.context("The package has no manifest")?;
+    let signature = signature.context("The package is not signed")?;
+
+    let manifest: PackageManifest =
+        serde_json::from_slice(&manifest_json).context("The package manifest is invalid")?;
+    let public_key = unhex(&manifest.public_key).context("The package's public key is invalid")?;
+    let signature = unhex(String::from_utf8_lossy(&signature).trim())
+        .context("The package's signature is invalid")?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(&manifest_json, &signature)
+        .map_err(|_| anyhow::anyhow!("The package's signature does not match its manifest"))?;
+    check_files(&manifest, &dest.join(SKILL_DIR))?;
+    Ok(manifest)
+}
+
+/// Checks that `dir` has exactly the files in the manifest, with their checksums.
+fn check_files(manifest: &PackageManifest, dir: &Path) -> Result<()> {
+    let mut found = 0;
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = file_name(entry.path(), dir)?;
+        let Some(expected) = manifest.files.get(&name) else {
+            bail!("{name} is in the package but not in its manifest");
+        };
+        if sha256(&std::fs::read(entry.path())?) != *expected {
+            bail!("{name} does not match its checksum");
+        }
+        found += 1;
+    }
+    if found != manifest.files.len() {
+        bail!("The package is missing files listed in its manifest");
+    }
+    Ok(())
+}
+
+/// `path` relative to `dir`, with `/` between its parts on every platform.
+fn file_name(path: &Path, dir: &Path) -> Result<String> {
+    Ok(path
+        .strip_prefix(dir)?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+fn append_data<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+fn read_entry(entry: &mut impl Read) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn sha256(data: &[u8]) -> String {
+    hex(ring::digest::digest(&ring::digest::SHA256, data).as_ref())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn packaged() -> (TempDir, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let skill = tmp.path().join("pdf");
+        std::fs::create_dir_all(skill.join("scripts")).unwrap();
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: pdf\ndescription: PDFs\nversion: 1.0.0\n---\n# PDF\n",
+        )
+        .unwrap();
+        std::fs::write(skill.join("scripts/run.sh"), "echo hi\n").unwrap();
+        let key = signing_key(&tmp.path().join("key")).unwrap();
+        let package = package_skill(&skill, &tmp.path().join("out"), &key).unwrap();
+        (tmp, package)
+    }
+
+    #[test]
+    fn unpacks_a_package_it_made() {
+        let (tmp, package) = packaged();
+        assert!(package.ends_with("pdf-1.0.0.tar.gz"));
+        let dest = tmp.path().join("unpacked");
+        let manifest = unpack_package(&package, &dest).unwrap();
+        assert_eq!(manifest.name, "pdf");
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            ["SKILL.md", "scripts/run.sh"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join("skill/scripts/run.sh")).unwrap(),
+            "echo hi\n"
+        );
+    }
+
+    #[test]
+    fn rejects_changed_files() {
+        let (tmp, package) = packaged();
+        let dest = tmp.path().join("unpacked");
+        let manifest = unpack_package(&package, &dest).unwrap();
+        std::fs::write(dest.join("skill/scripts/run.sh"), "rm -rf ~\n").unwrap();
+        let error = check_files(&manifest, &dest.join("skill")).unwrap_err();
+        assert!(error.to_string().contains("scripts/run.sh does not match"));
+
+        std::fs::write(dest.join("skill/extra.sh"), "").unwrap();
+        assert!(check_files(&manifest, &dest.join("skill")).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::skills::Skill;
+use crate::tools::{extract_string_arg, extract_string_arg_opt};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Component, Path};
+
+const MAX_LISTED_FILES: usize = 50;
+
+/// Loads a skill's full instructions on demand. The system prompt only lists skill
+/// names and descriptions, so their content costs nothing until a task needs it.
+pub struct ReadSkillTool {
+    skills: Vec<Skill>,
+}
+
+impl ReadSkillTool {
+    pub fn new(skills: Vec<Skill>) -> Self {
+        Self { skills }
+    }
+
+    fn find(&self, name: &str) -> Option<&Skill> {
+        self.skills.iter().find(|s| s.name == name).or_else(|| {
+            self.skills
+                .iter()
+                .find(|s| s.name.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// SKILL.md without its frontmatter.
+fn instructions(content: &str) -> &str {
+    let Some(rest) = content.strip_prefix("---") else {
+        return content;
+    };
+    match rest.find("\n---") {
+        Some(end) => rest[end + 4..].trim_start_matches(['\r', '\n']),
+        None => content,
+    }
+}
+
+/// Other files in the skill directory, relative to it.
+fn list_files(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir)
+                && relative != Path::new("SKILL.md")
+            {
+                files.push(relative.to_string_lossy().into_owned());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[async_trait]
+impl Tool for ReadSkillTool {
+    fn name(&self) -> &str {
+        "read_skill"
+    }
+
+    fn description(&self) -> &str {
+        "Load the full instructions of an installed skill, or one of the files it ships with"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let names: Vec<&str> = self.skills.iter().map(|s| s.name.as_str()).collect();
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "Skill name, as listed under Available Skills"
+                },
+                "file": {
+                    "type": "string",
+                    "description": "A file inside the skill directory, e.g. 'reference/api.md'. Omit to read SKILL.md"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let name = extract_string_arg(&args, "name")?;
+        let file = extract_string_arg_opt(&args, "file", "");
+
+        let Some(skill) = self.find(&name) else {
+            let names: Vec<&str> = self.skills.iter().map(|s| s.name.as_str()).collect();
+            return Ok(ToolResult::error(format!(
+                "Unknown skill '{}'. Installed skills: {}",
+                name,
+                names.join(", ")
+            )));
+        };
+        let (Some(location), Some(dir)) = (&skill.location, skill.dir()) else {
+            return Ok(ToolResult::error(format!(
+                "Skill '{}' has no files on disk",
+                skill.name
+            )));
+        };
+
+        if !file.is_empty() {
+            let relative = Path::new(&file);
+            if relative.is_absolute()
+                || relative
+                    .components()
+                    .any(|c| matches!(c, Component::ParentDir))
+            {
+                return Ok(ToolResult::error(
+                    "file must be a path inside the skill directory",
+                ));
+            }
+            return Ok(match tokio::fs::read_to_string(dir.join(relative)).await {
+                Ok(content) => ToolResult::success(content),
+                Err(e) => ToolResult::error(format!("Failed to read {}: {}", file, e)),
+            });
+        }
+
+        let content = match tokio::fs::read_to_string(location).await {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to read {}: {}",
+                    location.display(),
+                    e
+                )));
+            }
+        };
+
+        let mut output = format!(
+            "# Skill: {}\n\n{}",
+            skill.name,
+            instructions(&content).trim()
+        );
+        let files = list_files(dir);
+        if !files.is_empty() {
+            output.push_str("\n\n## Files\n\nRead these with `read_skill` and `file` when the instructions refer to them:\n");
+            for file in files.iter().take(MAX_LISTED_FILES) {
+                output.push_str(&format!("- {file}\n"));
+            }
+            if files.len() > MAX_LISTED_FILES {
+                output.push_str(&format!(
+                    "- ... and {} more\n",
+                    files.len() - MAX_LISTED_FILES
+                ));
+            }
+        }
+        Ok(ToolResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::load_skill;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn reads_instructions_and_skill_files() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("pdf");
+        std::fs::create_dir_all(dir.join("reference")).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Work with PDFs\n---\n\n# PDF\n\nUse pdftotext first.\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("reference/forms.md"), "Fill forms like this.").unwrap();
+        let tool = ReadSkillTool::new(vec![load_skill(&dir).unwrap()]);
+
+        let result = tool.execute(json!({ "name": "pdf" })).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Use pdftotext first."));
+        assert!(!result.output.contains("description:"));
+        assert!(result.output.contains("reference/forms.md"));
+
+        let result = tool
+            .execute(json!({ "name": "pdf", "file": "reference/forms.md" }))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "Fill forms like this.");
+
+        let result = tool
+            .execute(json!({ "name": "pdf", "file": "../secret" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(
+            !tool
+                .execute(json!({ "name": "docx" }))
+                .await
+                .unwrap()
+                .success
+        );
+    }
+}
@@ -1,20 +1,23 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use super::{Skill, load_skill, skills_dir};
+use super::{Skill, SkillState, load_skill, skill_dirs, skills_dir};
+use crate::config::Config;
 
 #[derive(Clone)]
 pub struct SkillRegistry {
     skills: Arc<Mutex<HashMap<String, Skill>>>,
+    disabled: Arc<Mutex<HashSet<String>>>,
 }
 
 impl SkillRegistry {
     pub fn new() -> Self {
         Self {
             skills: Arc::new(Mutex::new(HashMap::new())),
+            disabled: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
@@ -24,15 +27,49 @@ impl SkillRegistry {
         Ok(registry)
     }
 
+    /// Skills from every directory in `skill_dirs`, later ones replacing earlier ones
+    /// of the same name, with those disabled in the workspace or project left out.
+    pub fn load_from_config(config: &Config) -> Result<Self> {
+        let mut registry = Self::new();
+        registry.load_state(&config.workspace_dir);
+        if let Some(project_dir) = &config.project_dir {
+            registry.load_state(project_dir);
+        }
+        for dir in skill_dirs(config) {
+            registry.load_dir(&dir)?;
+        }
+        if let Some(project_dir) = &config.project_dir
+            && !config.trusts(project_dir)
+            && skills_dir(project_dir).is_dir()
+        {
+            tracing::warn!(
+                "Ignoring the skills in {} until the project is trusted (dinoe config trust)",
+                skills_dir(project_dir).display()
+            );
+        }
+        Ok(registry)
+    }
+
     pub fn load_skills(&mut self, workspace_dir: &Path) -> Result<()> {
-        let skills_path = skills_dir(workspace_dir);
+        self.load_state(workspace_dir);
+        self.load_dir(&skills_dir(workspace_dir))
+    }
+
+    fn load_state(&mut self, dir: &Path) {
+        match SkillState::load(dir) {
+            Ok(state) => self.disabled.lock().unwrap().extend(state.disabled),
+            Err(e) => tracing::warn!("Ignoring the skill state: {:#}", e),
+        }
+    }
 
+    /// Loads each skill in `skills_path`, replacing any already loaded of the same name.
+    pub fn load_dir(&mut self, skills_path: &Path) -> Result<()> {
         if !skills_path.exists() {
             tracing::debug!("Skills directory does not exist: {}", skills_path.display());
             return Ok(());
         }
 
-        let entries = fs::read_dir(&skills_path).with_context(|| {
+        let entries = fs::read_dir(skills_path).with_context(|| {
             format!("Failed to read skills directory: {}", skills_path.display())
         })?;
 
@@ -60,10 +97,19 @@ impl SkillRegistry {
 
             match load_skill(&path) {
                 Ok(skill) => {
-                    self.skills
+                    let replaced = self
+                        .skills
                         .lock()
                         .unwrap()
                         .insert(skill.name.clone(), skill);
+                    if let Some(replaced) = replaced {
+                        tracing::warn!(
+                            "Skill '{}' in {} replaces the one in {}",
+                            replaced.name,
+                            path.display(),
+                            replaced.dir().unwrap_or(Path::new("?")).display()
+                        );
+                    }
                     loaded += 1;
                 }
                 Err(e) => {
@@ -83,8 +129,20 @@ impl SkillRegistry {
         Ok(())
     }
 
+    /// The enabled skills.
     pub fn list(&self) -> Vec<Skill> {
-        self.skills.lock().unwrap().values().cloned().collect()
+        let disabled = self.disabled.lock().unwrap();
+        self.skills
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|skill| !disabled.contains(&skill.name))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.lock().unwrap().contains(name)
     }
 
     pub fn get(&self, name: &str) -> Option<Skill> {
@@ -173,6 +231,65 @@ mod tests {
         assert_eq!(registry.count(), 0);
     }
 
+    #[test]
+    fn registry_leaves_out_disabled_skills() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["on", "off"] {
+            let dir = tmp.path().join("skills").join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("SKILL.md"), format!("# {name}\nA skill\n")).unwrap();
+        }
+        let mut state = SkillState::default();
+        assert!(state.set_enabled("off", false));
+        state.save(tmp.path()).unwrap();
+
+        let registry = SkillRegistry::load_from_workspace(tmp.path()).unwrap();
+        let names: Vec<String> = registry.list().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["on"]);
+        assert!(!registry.is_enabled("off"));
+        assert!(registry.get("off").is_some());
+    }
+
+    #[test]
+    fn later_directories_take_precedence() {
+        let tmp = TempDir::new().unwrap();
+        let write = |dir: &Path, name: &str, description: &str| {
+            let dir = dir.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: {description}\n---\n"),
+            )
+            .unwrap();
+        };
+        let workspace = tmp.path().join("workspace");
+        let extra = tmp.path().join("team-skills");
+        let project = tmp.path().join(".dinoe");
+        write(&skills_dir(&workspace), "pdf", "Workspace");
+        write(&skills_dir(&workspace), "notes", "Workspace");
+        write(&extra, "pdf", "Team");
+        write(&extra, "deploy", "Team");
+        write(&skills_dir(&project), "deploy", "Project");
+        let mut config = Config {
+            workspace_dir: workspace,
+            skill_dirs: vec![extra],
+            project_dir: Some(project),
+            trusted_projects: vec![tmp.path().to_path_buf()],
+            ..Default::default()
+        };
+
+        let registry = SkillRegistry::load_from_config(&config).unwrap();
+        assert_eq!(registry.count(), 3);
+        assert_eq!(registry.get("notes").unwrap().description, "Workspace");
+        assert_eq!(registry.get("pdf").unwrap().description, "Team");
+        assert_eq!(registry.get("deploy").unwrap().description, "Project");
+
+        // Project skills can define tools, so an untrusted project's are left out.
+        config.trusted_projects.clear();
+        let registry = SkillRegistry::load_from_config(&config).unwrap();
+        assert_eq!(registry.get("deploy").unwrap().description, "Team");
+    }
+
     #[test]
     fn registry_get_skill() {
         let tmp = TempDir::new().unwrap();
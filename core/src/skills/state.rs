@@ -0,0 +1,51 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+pub const SKILLS_STATE_FILE: &str = "skills.json";
+
+/// Which installed skills are turned off, kept in the workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillState {
+    #[serde(default)]
+    pub disabled: BTreeSet<String>,
+}
+
+impl SkillState {
+    pub fn path(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join(SKILLS_STATE_FILE)
+    }
+
+    pub fn load(workspace_dir: &Path) -> Result<Self> {
+        let path = Self::path(workspace_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, workspace_dir: &Path) -> Result<()> {
+        let path = Self::path(workspace_dir);
+        std::fs::create_dir_all(workspace_dir)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.contains(name)
+    }
+
+    /// Turns the skill on or off. `false` when it already was.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        if enabled {
+            self.disabled.remove(name)
+        } else {
+            self.disabled.insert(name.to_string())
+        }
+    }
+}
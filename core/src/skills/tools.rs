@@ -0,0 +1,191 @@
+use crate::config::{CommandToolConfig, resolve_workspace_dir};
+use crate::skills::{Skill, SkillToolDef, skills_dir};
+use crate::tools::command::shell_quote;
+use crate::tools::{CommandTool, Sandbox};
+use anyhow::{Result, bail};
+use std::path::{Component, Path};
+
+fn interpreter_for(script: &Path) -> Option<&'static str> {
+    match script.extension()?.to_str()? {
+        "py" => Some("python3"),
+        "sh" => Some("sh"),
+        "bash" => Some("bash"),
+        "js" | "mjs" => Some("node"),
+        "rb" => Some("ruby"),
+        "pl" => Some("perl"),
+        _ => None,
+    }
+}
+
+pub(crate) fn command_for(skill_dir: &Path, def: &SkillToolDef) -> Result<String> {
+    match (&def.script, &def.command) {
+        (Some(script), None) => {
+            let relative = Path::new(script);
+            if relative.is_absolute()
+                || relative
+                    .components()
+                    .any(|c| matches!(c, Component::ParentDir))
+            {
+                bail!("script '{script}' must be a path inside the skill directory");
+            }
+            let path = skill_dir.join(relative);
+            if !path.is_file() {
+                bail!("script not found: {}", path.display());
+            }
+            let quoted = shell_quote(&path.to_string_lossy());
+            Ok(match interpreter_for(&path) {
+                Some(interpreter) => format!("{interpreter} {quoted}"),
+                None => quoted,
+            })
+        }
+        (None, Some(command)) => Ok(command.clone()),
+        _ => bail!("set exactly one of 'script' or 'command'"),
+    }
+}
+
+/// The sandbox for a tool of `skill`: the workspace and the paths the skill asks for,
+/// read-only when the tool says it changes nothing, and the skills themselves read-only.
+fn sandbox_for(skill: &Skill, def: &SkillToolDef, skill_dir: &Path, workspace: &Path) -> Sandbox {
+    let permissions = &skill.permissions;
+    let mut paths = vec![workspace.to_path_buf()];
+    paths.extend(
+        permissions
+            .paths
+            .iter()
+            .map(|path| resolve_workspace_dir(Path::new(path), workspace)),
+    );
+    let mut read = vec![skills_dir(workspace), skill_dir.to_path_buf()];
+    let write = if def.mutating {
+        paths
+    } else {
+        read.extend(paths);
+        Vec::new()
+    };
+    Sandbox {
+        read,
+        write,
+        network: permissions.network,
+        env: permissions.env.clone(),
+    }
+}
+
+/// Turns the tools declared by installed skills into command tools, run with `SKILL_DIR`
+/// pointing at their skill.
+pub fn skill_tools(skills: &[Skill], workspace: &Path) -> Vec<CommandTool> {
+    let mut tools = Vec::new();
+    for skill in skills {
+        let Some(skill_dir) = skill.dir() else {
+            continue;
+        };
+        for def in &skill.tools {
+            let tool = command_for(skill_dir, def).and_then(|command| {
+                let config = CommandToolConfig {
+                    name: def.name.clone(),
+                    description: def.description.clone(),
+                    command,
+                    parameters: def.parameters.clone(),
+                    mutating: def.mutating,
+                    timeout_secs: def.timeout_secs,
+                };
+                Ok(CommandTool::new(config, workspace)?
+                    .with_env("SKILL_DIR", skill_dir.to_string_lossy())
+                    .with_sandbox(sandbox_for(skill, def, skill_dir, workspace)))
+            });
+            match tool {
+                Ok(tool) => tools.push(tool),
+                Err(e) => tracing::warn!(
+                    "Skipping tool '{}' from skill '{}': {}",
+                    def.name,
+                    skill.name,
+                    e
+                ),
+            }
+        }
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{ApprovalMode, DenyApprover, ToolRegistry};
+    use crate::skills::load_skill;
+    use crate::traits::Tool;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn skill_script_is_registered_and_runs_in_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills/shout");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            r#"---
+name: shout
+description: Shout things
+tools:
+  - name: shout
+    description: Upper-case a text
+    script: scripts/shout.sh
+    mutating: false
+    parameters:
+      type: object
+      required: [text]
+      properties:
+        text: { type: string }
+  - name: escape
+    description: Broken
+    script: ../../outside.sh
+---
+# Shout
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            skill_dir.join("scripts/shout.sh"),
+            "printf '%s in %s' \"$DINOE_ARG_TEXT\" \"$(basename \"$PWD\")\" | tr a-z A-Z\n",
+        )
+        .unwrap();
+        let workspace = tmp.path().join("ws");
+        std::fs::create_dir_all(&workspace).unwrap();
+
+        let skill = load_skill(&skill_dir).unwrap();
+        let tools = skill_tools(&[skill], &workspace);
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "shout");
+
+        let result = tools[0]
+            .execute(serde_json::json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "HI IN WS");
+    }
+
+    #[tokio::test]
+    async fn unsandboxed_skill_tools_ask_whatever_the_approval_mode() {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills/touch");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: touch\ndescription: Touch a file\ntools:\n  - name: touch\n    \
+             description: Create a file\n    command: touch touched\n    mutating: false\n---\n",
+        )
+        .unwrap();
+        let workspace = tmp.path().join("ws");
+        std::fs::create_dir_all(&workspace).unwrap();
+
+        let skill = load_skill(&skill_dir).unwrap();
+        let tool = skill_tools(&[skill], &workspace).remove(0);
+        assert_eq!(tool.always_needs_approval(), !Sandbox::available());
+        let registry =
+            ToolRegistry::new().with_approval(ApprovalMode::Never, Arc::new(DenyApprover));
+        registry.register(Box::new(tool));
+
+        let result = registry.execute("touch", serde_json::json!({})).await;
+        assert_eq!(result.success, Sandbox::available(), "{:?}", result.error);
+        assert_eq!(workspace.join("touched").exists(), Sandbox::available());
+    }
+}
@@ -0,0 +1,128 @@
+use globset::{Glob, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// How deep `workspace_files` looks below the workspace.
+const MAX_DEPTH: usize = 4;
+/// Files `workspace_files` stops at, so a large workspace does not slow every turn.
+const MAX_FILES: usize = 5_000;
+
+/// When a skill is offered, under `triggers:` in the frontmatter. A skill without
+/// triggers is always offered; one with triggers only when one of them matches.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Triggers {
+    /// Words in the user's message, matched whole and ignoring case.
+    pub keywords: Vec<String>,
+    /// Globs for files in the workspace, e.g. `*.pdf` or `Cargo.toml`, matched against
+    /// the path from the workspace and the file name.
+    pub files: Vec<String>,
+    /// Slash commands the message starts with, e.g. `pdf` for `/pdf`.
+    pub commands: Vec<String>,
+}
+
+impl Triggers {
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty() && self.files.is_empty() && self.commands.is_empty()
+    }
+
+    /// Whether `message`, or one of `files` (relative to the workspace), matches.
+    pub fn matches(&self, message: &str, files: &[PathBuf]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let command = message
+            .split_whitespace()
+            .next()
+            .and_then(|word| word.strip_prefix('/'));
+        if let Some(command) = command
+            && self
+                .commands
+                .iter()
+                .any(|c| c.trim_start_matches('/').eq_ignore_ascii_case(command))
+        {
+            return true;
+        }
+        if self.keywords.iter().any(|k| has_word(message, k)) {
+            return true;
+        }
+        let mut globs = GlobSetBuilder::new();
+        for pattern in &self.files {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    globs.add(glob);
+                }
+                Err(e) => tracing::debug!("Ignoring the file trigger {:?}: {}", pattern, e),
+            }
+        }
+        globs.build().is_ok_and(|globs| {
+            files.iter().any(|file| {
+                globs.is_match(file) || file.file_name().is_some_and(|n| globs.is_match(n))
+            })
+        })
+    }
+}
+
+/// Whether `text` has `word` (which may be several words) with no letters or digits
+/// right before or after it.
+fn has_word(text: &str, word: &str) -> bool {
+    let word = word.trim().to_lowercase();
+    if word.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    text.match_indices(&word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Files in `workspace`, relative to it, for matching file triggers. Skips what
+/// `.gitignore` does, and stops at a few directories deep.
+pub fn workspace_files(workspace: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(workspace)
+        .max_depth(Some(MAX_DEPTH))
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(workspace)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .take(MAX_FILES)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triggers(yaml: &str) -> Triggers {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn matches_keywords_as_whole_words() {
+        let t = triggers("keywords: [pdf, pull request]");
+        assert!(t.matches("Fill in this PDF, please", &[]));
+        assert!(t.matches("review the Pull Request", &[]));
+        assert!(!t.matches("pdftotext is missing", &[]));
+        assert!(!t.matches("hello", &[]));
+    }
+
+    #[test]
+    fn matches_commands_and_files() {
+        let t = triggers("commands: [/deploy]\nfiles: [\"*.tf\", Cargo.toml]");
+        assert!(t.matches("/deploy staging", &[]));
+        assert!(!t.matches("deploy staging", &[]));
+        assert!(t.matches("hi", &[PathBuf::from("infra/main.tf")]));
+        assert!(t.matches("hi", &[PathBuf::from("crates/cli/Cargo.toml")]));
+        assert!(!t.matches("hi", &[PathBuf::from("README.md")]));
+        assert!(Triggers::default().matches("anything", &[]));
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::manifest::{FRONTMATTER_FIELDS, frontmatter};
+use super::tools::command_for;
+use super::{Skill, load_skill};
+
+/// Operating systems `compatibility.os` may name, as in `std::env::consts::OS`.
+const KNOWN_OS: &[&str] = &[
+    "linux", "macos", "windows", "freebsd", "openbsd", "netbsd", "android", "ios",
+];
+
+/// What `validate_skill` found. Errors stop the skill from working; warnings are worth
+/// a look.
+#[derive(Debug, Default)]
+pub struct Validation {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Validation {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Checks the skill in `skill_dir` more strictly than loading it does.
+pub fn validate_skill(skill_dir: &Path) -> Validation {
+    let mut result = Validation::default();
+    let skill = match load_skill(skill_dir) {
+        Ok(skill) => skill,
+        Err(e) => {
+            result.errors.push(e.to_string());
+            return result;
+        }
+    };
+    if !skill.has_frontmatter {
+        result.warnings.push(
+            "SKILL.md has no frontmatter, so the name and description come from its first \
+             lines. Start it with a `---` block that sets `name` and `description`"
+                .to_string(),
+        );
+    } else {
+        check_fields(skill_dir, &mut result);
+    }
+    check_metadata(&skill, &mut result);
+    check_tools(&skill, skill_dir, &mut result);
+    check_requirements(&skill, &mut result);
+    check_triggers(&skill, &mut result);
+    check_permissions(&skill, &mut result);
+    result
+}
+
+fn check_fields(skill_dir: &Path, result: &mut Validation) {
+    let Some(yaml) = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .ok()
+        .and_then(|content| frontmatter(&content))
+    else {
+        return;
+    };
+    let Ok(serde_yaml::Value::Mapping(fields)) = serde_yaml::from_str(&yaml) else {
+        return;
+    };
+    for key in fields.keys() {
+        let key = key.as_str().unwrap_or_default();
+        if !FRONTMATTER_FIELDS.contains(&key) {
+            result.warnings.push(format!(
+                "Unknown field `{key}` is ignored. Known fields: {}",
+                FRONTMATTER_FIELDS.join(", ")
+            ));
+        }
+    }
+}
+
+fn check_metadata(skill: &Skill, result: &mut Validation) {
+    if skill.name.trim().is_empty() {
+        result.errors.push("`name` is empty".to_string());
+    }
+    if skill.description.trim().is_empty() {
+        result.errors.push(
+            "`description` is empty; the agent picks skills by their description".to_string(),
+        );
+    }
+    if !is_version(&skill.version) {
+        result.errors.push(format!(
+            "`version` {:?} is not MAJOR.MINOR.PATCH, e.g. 1.0.0",
+            skill.version
+        ));
+    }
+    if skill
+        .license
+        .as_deref()
+        .is_some_and(|l| l.trim().is_empty())
+    {
+        result
+            .errors
+            .push("`license` is empty; use an SPDX name such as MIT, or leave it out".to_string());
+    }
+    if skill.examples.iter().any(|e| e.trim().is_empty()) {
+        result
+            .errors
+            .push("`examples` has an empty entry".to_string());
+    }
+    for os in &skill.compatibility.os {
+        if !KNOWN_OS.contains(&os.as_str()) {
+            result.errors.push(format!(
+                "`compatibility.os` has unknown {os:?}. Use one of: {}",
+                KNOWN_OS.join(", ")
+            ));
+        }
+    }
+}
+
+fn check_tools(skill: &Skill, skill_dir: &Path, result: &mut Validation) {
+    let mut names = HashSet::new();
+    for tool in &skill.tools {
+        let name = &tool.name;
+        if !is_identifier(name) {
+            result.errors.push(format!(
+                "Tool {name:?}: names may only have letters, digits, `_` and `-`"
+            ));
+        }
+        if !names.insert(name) {
+            result
+                .errors
+                .push(format!("Tool {name:?} is declared more than once"));
+        }
+        if tool.description.trim().is_empty() {
+            result
+                .errors
+                .push(format!("Tool {name:?} has an empty `description`"));
+        }
+        if let Err(e) = command_for(skill_dir, tool) {
+            result.errors.push(format!("Tool {name:?}: {e}"));
+        }
+        if tool.parameters.get("type").and_then(|t| t.as_str()) != Some("object") {
+            result.errors.push(format!(
+                "Tool {name:?}: `parameters` must be a JSON schema with `type: object`"
+            ));
+        }
+    }
+}
+
+fn check_requirements(skill: &Skill, result: &mut Validation) {
+    for tool in &skill.requires.tools {
+        if !is_identifier(tool) {
+            result.errors.push(format!(
+                "`requires.tools` has an invalid tool name {tool:?}"
+            ));
+        }
+    }
+    for binary in skill.missing_binaries() {
+        result.warnings.push(format!(
+            "Requires `{binary}`, which is not on PATH, so the agent is not offered this skill"
+        ));
+    }
+    let os = std::env::consts::OS;
+    if !skill.compatibility.os.is_empty() && !skill.compatibility.os.iter().any(|o| o == os) {
+        result
+            .warnings
+            .push(format!("Not available on this machine, which runs {os}"));
+    }
+}
+
+fn check_triggers(skill: &Skill, result: &mut Validation) {
+    let triggers = &skill.triggers;
+    if triggers.keywords.iter().any(|k| k.trim().is_empty()) {
+        result
+            .errors
+            .push("`triggers.keywords` has an empty entry".to_string());
+    }
+    for pattern in &triggers.files {
+        if let Err(e) = globset::Glob::new(pattern) {
+            result.errors.push(format!(
+                "`triggers.files` has an invalid glob {pattern:?}: {e}"
+            ));
+        }
+    }
+    for command in &triggers.commands {
+        if !is_identifier(command.trim_start_matches('/')) {
+            result.errors.push(format!(
+                "`triggers.commands` has {command:?}; use a word such as `deploy` for /deploy"
+            ));
+        }
+    }
+}
+
+fn check_permissions(skill: &Skill, result: &mut Validation) {
+    for path in &skill.permissions.paths {
+        if !(Path::new(path).is_absolute() || path.starts_with("~/")) {
+            result.errors.push(format!(
+                "`permissions.paths` has {path:?}; use an absolute path or one starting with ~/"
+            ));
+        }
+    }
+    for var in &skill.permissions.env {
+        if var.is_empty() || !var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            result.errors.push(format!(
+                "`permissions.env` has an invalid variable name {var:?}"
+            ));
+        }
+    }
+}
+
+fn is_version(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill_dir(frontmatter: &str) -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("SKILL.md"),
+            format!("---\n{frontmatter}---\n# Instructions\n"),
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn accepts_a_complete_skill() {
+        let dir = skill_dir(
+            "name: pdf\ndescription: Fill in PDF forms\nversion: 1.2.0\nlicense: MIT\n\
+             requires:\n  tools: [shell]\n  binaries: [sh]\n\
+             compatibility:\n  models: [\"gpt-*\"]\n\
+             examples:\n  - Fill in this tax form\n\
+             triggers:\n  keywords: [pdf]\n  files: [\"*.pdf\"]\n  commands: [/pdf]\n\
+             permissions:\n  network: true\n  paths: [~/Documents]\n  env: [PDF_TOKEN]\n",
+        );
+        let result = validate_skill(dir.path());
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[test]
+    fn reports_mistakes() {
+        let dir = skill_dir(
+            "name: pdf\ndescription: \"\"\nversion: \"1.0\"\nrequries: [jq]\n\
+             compatibility:\n  os: [mac]\n\
+             tools:\n  - name: fill form\n    description: Fills\n",
+        );
+        let result = validate_skill(dir.path());
+        assert_eq!(result.errors.len(), 5, "{:?}", result.errors);
+        assert!(result.errors[0].contains("`description` is empty"));
+        assert!(result.errors[1].contains("\"1.0\""));
+        assert!(result.errors[2].contains("unknown \"mac\""));
+        assert!(result.warnings[0].contains("`requries`"));
+    }
+
+    #[test]
+    fn reports_the_line_of_a_frontmatter_error() {
+        let dir = skill_dir("name: pdf\ndescription: PDFs\nrequires:\n  binary: [jq]\n");
+        let result = validate_skill(dir.path());
+        assert!(
+            result.errors[0].contains("line 5: requires: unknown field `binary`"),
+            "{:?}",
+            result.errors
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::config::TelemetryConfig;
+use anyhow::Result;
+use tracing_subscriber::{Layer, Registry};
+
+/// A layer of the tracing subscriber, which the CLI installs.
+pub type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Keeps spans flowing to the collector; pending spans are flushed when it is dropped.
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: opentelemetry_sdk::trace::SdkTracerProvider,
+}
+
+#[cfg(feature = "otel")]
+fn endpoint(config: &TelemetryConfig) -> Option<String> {
+    config
+        .otlp_endpoint
+        .clone()
+        .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+        .filter(|e| !e.trim().is_empty())
+}
+
+/// Exports the spans of turns, model calls and tool calls over OTLP/HTTP, when an
+/// endpoint is configured. The layer must be added to the tracing subscriber.
+#[cfg(feature = "otel")]
+pub fn layer(config: &TelemetryConfig) -> Result<Option<(Telemetry, BoxedLayer)>> {
+    use anyhow::Context;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+
+    let Some(endpoint) = endpoint(config) else {
+        return Ok(None);
+    };
+    let endpoint = endpoint.trim_end_matches('/');
+    let endpoint = if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/v1/traces")
+    };
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .context("Failed to create the OTLP exporter")?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("dinoe"));
+    let targets = Targets::new()
+        .with_target("dinoe_core", LevelFilter::INFO)
+        .with_target("dinoe", LevelFilter::INFO);
+    Ok(Some((
+        Telemetry { provider },
+        layer.with_filter(targets).boxed(),
+    )))
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn layer(config: &TelemetryConfig) -> Result<Option<(Telemetry, BoxedLayer)>> {
+    if config.otlp_endpoint.is_some() {
+        anyhow::bail!("telemetry.otlp_endpoint requires dinoe built with the 'otel' feature");
+    }
+    Ok(None)
+}
+
+#[cfg(feature = "otel")]
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush telemetry: {e}");
+        }
+    }
+}
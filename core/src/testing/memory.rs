@@ -0,0 +1,131 @@
+use crate::traits::{EntryMeta, Memory, MemoryCategory, MemoryEntry};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// Memory kept in a `Vec`, for tests. Storing a key again replaces its entry. Recall
+/// scores entries by how many words of the query they contain, like the markdown backend.
+#[derive(Default)]
+pub struct InMemoryMemory {
+    entries: Mutex<Vec<MemoryEntry>>,
+}
+
+impl InMemoryMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every entry, in the order they were stored.
+    pub fn entries(&self) -> Vec<MemoryEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+fn in_session(entry: &MemoryEntry, session_id: Option<&str>) -> bool {
+    session_id.is_none_or(|id| entry.session_id.as_deref() == Some(id))
+}
+
+#[async_trait]
+impl Memory for InMemoryMemory {
+    fn name(&self) -> &str {
+        "in_memory"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_meta(key, content, category, session_id, &EntryMeta::default())
+            .await
+    }
+
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|e| e.key != key);
+        entries.push(MemoryEntry {
+            id: key.to_string(),
+            key: key.to_string(),
+            content: content.to_string(),
+            category,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            session_id: session_id.map(str::to_string),
+            score: None,
+            meta: meta.clone(),
+        });
+        Ok(())
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let query = query.to_lowercase();
+        let keywords: Vec<&str> = query.split_whitespace().collect();
+        let mut found: Vec<MemoryEntry> = self
+            .entries()
+            .into_iter()
+            .rev()
+            .filter(|e| in_session(e, session_id))
+            .filter_map(|mut entry| {
+                if keywords.is_empty() {
+                    return Some(entry);
+                }
+                let content = entry.content.to_lowercase();
+                let matched = keywords.iter().filter(|k| content.contains(**k)).count();
+                #[allow(clippy::cast_precision_loss)]
+                let score = matched as f64 / keywords.len() as f64;
+                entry.score = Some(score);
+                (matched > 0).then_some(entry)
+            })
+            .collect();
+        found.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(self.entries().into_iter().find(|e| e.key == key))
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(self
+            .entries()
+            .into_iter()
+            .filter(|e| category.is_none_or(|c| &e.category == c) && in_session(e, session_id))
+            .collect())
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|e| e.key != key);
+        Ok(entries.len() < before)
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        Ok(self.entries.lock().unwrap().len())
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
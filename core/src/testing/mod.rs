@@ -0,0 +1,139 @@
+mod memory;
+mod provider;
+
+pub use memory::InMemoryMemory;
+pub use provider::{MockProvider, RecordedRequest};
+
+use crate::agent::{AgentLoop, ContextBuilder, ToolRegistry};
+use crate::traits::Provider;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A workspace in a temporary directory, deleted when dropped. Panics when the
+/// filesystem fails, as a test should.
+pub struct TempWorkspace {
+    dir: tempfile::TempDir,
+}
+
+impl TempWorkspace {
+    pub fn new() -> Self {
+        Self {
+            dir: tempfile::TempDir::new().expect("Failed to create a temporary workspace"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Writes `content` to `path` in the workspace, creating its directories.
+    pub fn with_file(self, path: &str, content: &str) -> Self {
+        self.write(path, content);
+        self
+    }
+
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create a directory");
+        }
+        std::fs::write(&path, content)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        let path = self.join(path);
+        std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+    }
+
+    pub fn join(&self, path: &str) -> PathBuf {
+        self.path().join(path)
+    }
+
+    /// An agent working here with `provider` and `tools`, and the defaults otherwise.
+    pub fn agent(&self, provider: Arc<dyn Provider>, tools: ToolRegistry) -> AgentLoop {
+        AgentLoop::new(provider, ContextBuilder::new(self.path()), Arc::new(tools))
+    }
+}
+
+impl Default for TempWorkspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::FileWriteTool;
+    use crate::traits::{Memory, MemoryCategory};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn runs_a_scripted_turn() {
+        let workspace = TempWorkspace::new().with_file("notes/todo.md", "- old\n");
+        let provider = Arc::new(
+            MockProvider::new()
+                .with_tool_call(
+                    "file_write",
+                    json!({ "path": "notes/todo.md", "content": "- new\n" }),
+                )
+                .with_text("Updated the list"),
+        );
+        let tools = ToolRegistry::new();
+        tools.register(Box::new(FileWriteTool::new(workspace.path())));
+
+        let text = workspace
+            .agent(provider.clone(), tools)
+            .process("Replace my todo list")
+            .await
+            .into_result()
+            .unwrap();
+        assert_eq!(text, "Updated the list");
+        assert_eq!(workspace.read("notes/todo.md"), "- new\n");
+
+        let requests = provider.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].tools, ["file_write"]);
+        let last = requests[1].messages.last().unwrap();
+        assert_eq!(last.role, "tool");
+        assert_eq!(last.tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(provider.remaining(), 0);
+
+        let error = workspace
+            .agent(provider, ToolRegistry::new())
+            .process("Again")
+            .await
+            .into_result()
+            .unwrap_err();
+        assert!(error.to_string().contains("no more responses"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn recalls_stored_memories() {
+        let memory = InMemoryMemory::new();
+        memory
+            .store("lang", "Prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        memory
+            .store("tz", "Lives in Berlin", MemoryCategory::Core, Some("s1"))
+            .await
+            .unwrap();
+        memory
+            .store("lang", "Prefers Rust and Go", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        assert_eq!(memory.count().await.unwrap(), 2);
+        let found = memory.recall("rust", 5, None).await.unwrap();
+        assert_eq!(found[0].content, "Prefers Rust and Go");
+        assert_eq!(
+            memory.recall("berlin", 5, Some("s2")).await.unwrap().len(),
+            0
+        );
+        assert!(memory.forget("tz").await.unwrap());
+        assert!(memory.get("tz").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::providers::recording::response_events;
+use crate::traits::{ChatMessage, ChatRequest, ChatResponse, Provider, ProviderEvent, ToolCall};
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A request [`MockProvider`] was sent.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub messages: Vec<ChatMessage>,
+    /// Names of the tools offered with the request.
+    pub tools: Vec<String>,
+    pub model: String,
+}
+
+/// A provider that answers with responses queued in advance, in order, and keeps every
+/// request it was sent. Runs out with an error, so a test that makes more calls than
+/// expected fails.
+#[derive(Default)]
+pub struct MockProvider {
+    responses: Mutex<VecDeque<ChatResponse>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+    next_call_id: Mutex<usize>,
+    delay: Duration,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a final answer.
+    pub fn with_text(self, text: impl Into<String>) -> Self {
+        self.push_text(text);
+        self
+    }
+
+    /// Queues a reply that calls one tool.
+    pub fn with_tool_call(self, name: &str, arguments: serde_json::Value) -> Self {
+        self.push_tool_calls(&[(name, arguments)]);
+        self
+    }
+
+    pub fn with_response(self, response: ChatResponse) -> Self {
+        self.push_response(response);
+        self
+    }
+
+    /// Waits `delay` before each answer, like a slow model.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn push_text(&self, text: impl Into<String>) {
+        self.push_response(ChatResponse {
+            text: Some(text.into()),
+            ..Default::default()
+        });
+    }
+
+    /// Queues a reply that calls several tools at once. Calls get the ids `call_1`,
+    /// `call_2` and so on.
+    pub fn push_tool_calls(&self, calls: &[(&str, serde_json::Value)]) {
+        let mut next_id = self.next_call_id.lock().unwrap();
+        let tool_calls = calls
+            .iter()
+            .map(|(name, arguments)| {
+                *next_id += 1;
+                ToolCall {
+                    id: format!("call_{next_id}"),
+                    name: name.to_string(),
+                    arguments: arguments.to_string(),
+                }
+            })
+            .collect();
+        self.push_response(ChatResponse {
+            tool_calls,
+            ..Default::default()
+        });
+    }
+
+    pub fn push_response(&self, response: ChatResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    /// Every request so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Responses queued but not used yet.
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+
+    async fn next(&self, request: ChatRequest<'_>, model: &str) -> anyhow::Result<ChatResponse> {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            messages: request.messages.to_vec(),
+            tools: request
+                .tools
+                .unwrap_or_default()
+                .iter()
+                .map(|t| t.name.clone())
+                .collect(),
+            model: model.to_string(),
+        });
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("MockProvider has no more responses queued"))
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.next(request, model).await
+    }
+
+    /// The queued response as events: its reasoning, its text and its tool calls.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<BoxStream<'static, ProviderEvent>> {
+        Ok(response_events(self.next(request, model).await?))
+    }
+}
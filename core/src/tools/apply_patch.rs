@@ -0,0 +1,712 @@
+use crate::tools::extract_string_arg;
+use crate::tools::file_write::write_atomic;
+use crate::tools::security::{RateLimiter, resolve_workspace_target};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+const RATE_LIMIT_MAX: u64 = 60;
+const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
+const DEFAULT_FUZZ: usize = 2;
+const MAX_FUZZ: usize = 3;
+
+static GLOBAL_RATE_LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Default)]
+struct Hunk {
+    old_start: usize,
+    lines: Vec<HunkLine>,
+    no_newline_at_end: bool,
+}
+
+#[derive(Debug, Default)]
+struct FilePatch {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    fn action(&self) -> &'static str {
+        match (&self.old_path, &self.new_path) {
+            (None, Some(_)) => "create",
+            (Some(_), None) => "delete",
+            (Some(old), Some(new)) if old != new => "rename",
+            _ => "modify",
+        }
+    }
+
+    fn display_path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or("")
+    }
+}
+
+fn parse_header_path(raw: &str, prefix: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or("").trim();
+    if path == "/dev/null" || path.is_empty() {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// The old start line of a hunk, and its old and new line counts when the header has
+/// both ranges.
+fn parse_hunk_header(header: &str) -> Result<(usize, Option<(usize, usize)>), String> {
+    let invalid = || format!("Invalid hunk header: {header}");
+    let range = |prefix: char| -> Result<Option<(usize, usize)>, String> {
+        let Some(range) = header
+            .trim_start_matches("@@")
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix(prefix))
+        else {
+            return Ok(None);
+        };
+        let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+        let start = start.parse().map_err(|_| invalid())?;
+        let count = count.parse().map_err(|_| invalid())?;
+        Ok(Some((start, count)))
+    };
+
+    let Some((old_start, old_count)) = range('-')? else {
+        return Ok((0, None));
+    };
+    let counts = range('+')?.map(|(_, new_count)| (old_count, new_count));
+    Ok((old_start, counts))
+}
+
+fn parse_patch(patch: &str) -> Result<Vec<FilePatch>, String> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut lines = patch.lines().peekable();
+    // Old and new lines still due in the current hunk. Until they are all read, a line
+    // such as `--- ` is the hunk's, not a file header.
+    let mut remaining: Option<(usize, usize)> = None;
+
+    while let Some(line) = lines.next() {
+        let in_body = remaining.is_some_and(|(old, new)| old > 0 || new > 0);
+        if !in_body
+            && let Some(old) = line.strip_prefix("--- ")
+            && let Some(new) = lines.peek().and_then(|l| l.strip_prefix("+++ "))
+        {
+            files.push(FilePatch {
+                old_path: parse_header_path(old, "a/"),
+                new_path: parse_header_path(new, "b/"),
+                hunks: Vec::new(),
+            });
+            lines.next();
+        } else if !in_body && line.starts_with("@@") {
+            let file = files
+                .last_mut()
+                .ok_or_else(|| "Hunk found before any '---'/'+++' file header".to_string())?;
+            let (old_start, counts) = parse_hunk_header(line)?;
+            remaining = counts;
+            file.hunks.push(Hunk {
+                old_start,
+                ..Hunk::default()
+            });
+        } else if let Some(hunk) = files.last_mut().and_then(|f| f.hunks.last_mut()) {
+            if line.starts_with("\\") {
+                if !matches!(hunk.lines.last(), Some(HunkLine::Remove(_))) {
+                    hunk.no_newline_at_end = true;
+                }
+                continue;
+            }
+            let parsed = match line.chars().next() {
+                Some('+') => HunkLine::Add(line[1..].to_string()),
+                Some('-') => HunkLine::Remove(line[1..].to_string()),
+                Some(' ') => HunkLine::Context(line[1..].to_string()),
+                None => HunkLine::Context(String::new()),
+                // Between files: `diff --git`, `index`, `new file mode` and the like.
+                Some(_) if !in_body => continue,
+                Some(_) => return Err(format!("Unexpected line in hunk: {line}")),
+            };
+            if let Some((old, new)) = &mut remaining {
+                if !matches!(parsed, HunkLine::Add(_)) {
+                    *old = old.saturating_sub(1);
+                }
+                if !matches!(parsed, HunkLine::Remove(_)) {
+                    *new = new.saturating_sub(1);
+                }
+            }
+            hunk.lines.push(parsed);
+        }
+    }
+
+    if files.is_empty() {
+        return Err("No file headers ('--- a/path' / '+++ b/path') found in patch".into());
+    }
+    if let Some(empty) = files.iter().find(|f| f.hunks.is_empty()) {
+        return Err(format!("No hunks for {}", empty.display_path()));
+    }
+    // Each file is planned against what is on disk, so a second patch of the same file
+    // would replace the first.
+    let mut seen = HashSet::new();
+    for file in &files {
+        let paths: HashSet<&str> = [&file.old_path, &file.new_path]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if let Some(path) = paths.into_iter().find(|path| !seen.insert(*path)) {
+            return Err(format!(
+                "{path} is patched more than once; put all its hunks under one header"
+            ));
+        }
+    }
+    Ok(files)
+}
+
+fn trim_context(lines: &[HunkLine], fuzz: usize) -> Option<(&[HunkLine], usize)> {
+    let leading = lines
+        .iter()
+        .take_while(|l| matches!(l, HunkLine::Context(_)))
+        .count();
+    let trailing = lines
+        .iter()
+        .rev()
+        .take_while(|l| matches!(l, HunkLine::Context(_)))
+        .count();
+
+    let drop_front = fuzz.min(leading);
+    let drop_back = fuzz.min(trailing);
+    if fuzz > 0 && drop_front < fuzz && drop_back < fuzz {
+        return None;
+    }
+    if drop_front + drop_back >= lines.len() {
+        return None;
+    }
+    Some((&lines[drop_front..lines.len() - drop_back], drop_front))
+}
+
+fn lines_match(file: &[String], pos: usize, old: &[&str], loose: bool) -> bool {
+    old.iter().enumerate().all(|(i, expected)| {
+        let actual = file[pos + i].as_str();
+        if loose {
+            actual.trim_end() == expected.trim_end()
+        } else {
+            actual == *expected
+        }
+    })
+}
+
+fn find_position(
+    file: &[String],
+    old: &[&str],
+    expected: usize,
+    min: usize,
+    loose: bool,
+) -> Option<usize> {
+    if file.len() < old.len() {
+        return None;
+    }
+    let max = file.len() - old.len();
+    if min > max {
+        return None;
+    }
+    let expected = expected.clamp(min, max);
+    let reach = (expected - min).max(max - expected);
+
+    (0..=reach).find_map(|distance| {
+        [
+            expected.checked_add(distance),
+            expected.checked_sub(distance),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|pos| (min..=max).contains(pos))
+        .find(|pos| lines_match(file, *pos, old, loose))
+    })
+}
+
+fn apply_hunks(
+    content: &str,
+    hunks: &[Hunk],
+    max_fuzz: usize,
+) -> (Option<String>, Vec<serde_json::Value>) {
+    let mut file: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut trailing_newline = content.is_empty() || content.ends_with('\n');
+    let mut reports = Vec::with_capacity(hunks.len());
+    let mut cursor = 0usize;
+    let mut delta = 0isize;
+    let mut all_applied = true;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let mut applied = None;
+
+        'search: for fuzz in 0..=max_fuzz {
+            let Some((lines, dropped)) = trim_context(&hunk.lines, fuzz) else {
+                continue;
+            };
+            let old: Vec<&str> = lines
+                .iter()
+                .filter_map(|l| match l {
+                    HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                    HunkLine::Add(_) => None,
+                })
+                .collect();
+            let new: Vec<String> = lines
+                .iter()
+                .filter_map(|l| match l {
+                    HunkLine::Context(s) | HunkLine::Add(s) => Some(s.clone()),
+                    HunkLine::Remove(_) => None,
+                })
+                .collect();
+
+            let expected = if hunk.old_start == 0 {
+                cursor
+            } else {
+                (hunk.old_start as isize - 1 + dropped as isize + delta).max(0) as usize
+            };
+
+            for loose in [false, true] {
+                if let Some(pos) = find_position(&file, &old, expected, cursor, loose) {
+                    applied = Some((pos, expected, fuzz, loose, old.len(), new));
+                    break 'search;
+                }
+            }
+        }
+
+        match applied {
+            Some((pos, expected, fuzz, loose, old_len, new)) => {
+                let new_len = new.len();
+                file.splice(pos..pos + old_len, new);
+                delta += new_len as isize - old_len as isize;
+                cursor = pos + new_len;
+                if hunk.no_newline_at_end && cursor == file.len() {
+                    trailing_newline = false;
+                }
+                reports.push(json!({
+                    "hunk": index + 1,
+                    "applied": true,
+                    "line": pos + 1,
+                    "offset": pos as isize - expected as isize,
+                    "fuzz": fuzz,
+                    "whitespace_insensitive": loose,
+                }));
+            }
+            None => {
+                all_applied = false;
+                reports.push(json!({
+                    "hunk": index + 1,
+                    "applied": false,
+                    "error": "Context lines not found in file; re-read the file and regenerate this hunk",
+                }));
+            }
+        }
+    }
+
+    if !all_applied {
+        return (None, reports);
+    }
+
+    let mut result = file.join("\n");
+    if trailing_newline && !file.is_empty() {
+        result.push('\n');
+    }
+    (Some(result), reports)
+}
+
+struct PlannedWrite {
+    target: PathBuf,
+    content: Option<String>,
+    remove: Option<PathBuf>,
+}
+
+pub struct ApplyPatchTool {
+    workspace: PathBuf,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl ApplyPatchTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        let rate_limiter = GLOBAL_RATE_LIMITER
+            .get_or_init(|| Arc::new(RateLimiter::new(RATE_LIMIT_MAX, RATE_LIMIT_WINDOW_SECS)))
+            .clone();
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter,
+        }
+    }
+
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        resolve_workspace_target(path, &self.workspace)
+    }
+
+    async fn plan(
+        &self,
+        file: &FilePatch,
+        fuzz: usize,
+    ) -> (serde_json::Value, Option<PlannedWrite>) {
+        let path = file.display_path().to_string();
+        let action = file.action();
+        let fail = |error: String| {
+            (
+                json!({"path": path, "action": action, "error": error}),
+                None,
+            )
+        };
+
+        let source = match file
+            .old_path
+            .as_deref()
+            .map(|p| self.resolve(p))
+            .transpose()
+        {
+            Ok(p) => p,
+            Err(e) => return fail(e),
+        };
+        let target = match file
+            .new_path
+            .as_deref()
+            .map(|p| self.resolve(p))
+            .transpose()
+        {
+            Ok(p) => p,
+            Err(e) => return fail(e),
+        };
+
+        let original = match &source {
+            Some(source) => match tokio::fs::read_to_string(source).await {
+                Ok(c) => c,
+                Err(e) => return fail(format!("Failed to read file: {e}")),
+            },
+            None => String::new(),
+        };
+
+        if let (None, Some(target)) = (&source, &target)
+            && target.exists()
+        {
+            return fail("Patch creates a file that already exists".into());
+        }
+
+        let (patched, hunks) = apply_hunks(&original, &file.hunks, fuzz);
+        let report = json!({
+            "path": path,
+            "action": action,
+            "applied": patched.is_some(),
+            "hunks": hunks,
+        });
+
+        let Some(patched) = patched else {
+            return (report, None);
+        };
+
+        let planned = match (source, target) {
+            (Some(source), None) => {
+                if !patched.trim().is_empty() {
+                    return fail("Deletion patch does not remove the whole file".into());
+                }
+                PlannedWrite {
+                    target: source.clone(),
+                    content: None,
+                    remove: Some(source),
+                }
+            }
+            (source, Some(target)) => PlannedWrite {
+                remove: source.filter(|s| *s != target),
+                target,
+                content: Some(patched),
+            },
+            (None, None) => return fail("Patch has neither an old nor a new path".into()),
+        };
+
+        (report, Some(planned))
+    }
+}
+
+#[async_trait]
+impl Tool for ApplyPatchTool {
+    fn name(&self) -> &str {
+        "apply_patch"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Apply a unified diff (--- a/file, +++ b/file, @@ hunks) to workspace files. Tolerates shifted line \
+         numbers and small context drift, reports per-hunk results, and changes nothing unless every hunk applies"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff text; may touch several files. Use /dev/null to create or delete a file"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Validate the patch and report hunk results without writing (default: false)"
+                },
+                "fuzz": {
+                    "type": "integer",
+                    "description": "Max context lines that may be ignored at each hunk edge (default: 2, max: 3)"
+                }
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many edits. Please wait a moment.",
+            ));
+        }
+
+        let patch = extract_string_arg(&args, "patch")?;
+        let dry_run = args
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let fuzz = args
+            .get("fuzz")
+            .and_then(|v| v.as_u64())
+            .map(|v| (v as usize).min(MAX_FUZZ))
+            .unwrap_or(DEFAULT_FUZZ);
+
+        let files = match parse_patch(&patch) {
+            Ok(f) => f,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid patch: {e}"))),
+        };
+
+        let mut reports = Vec::with_capacity(files.len());
+        let mut writes = Vec::with_capacity(files.len());
+        for file in &files {
+            let (report, planned) = self.plan(file, fuzz).await;
+            reports.push(report);
+            writes.extend(planned);
+        }
+
+        let all_applied = writes.len() == files.len();
+        let summary = json!({
+            "dry_run": dry_run,
+            "applied": all_applied && !dry_run,
+            "files": reports,
+        });
+        let summary = serde_json::to_string_pretty(&summary).unwrap_or_default();
+
+        if !all_applied {
+            return Ok(ToolResult::error(format!(
+                "Patch does not apply; no files were changed.\n{summary}"
+            )));
+        }
+
+        if dry_run {
+            return Ok(ToolResult::success(summary));
+        }
+
+        let backups = match back_up(&writes).await {
+            Ok(backups) => backups,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        if let Err(e) = apply_writes(&writes).await {
+            restore(backups).await;
+            return Ok(ToolResult::error(format!("{e}; no files were changed")));
+        }
+
+        Ok(ToolResult::success(summary))
+    }
+}
+
+/// The current content of every file `writes` touch, `None` for those that do not
+/// exist yet.
+async fn back_up(writes: &[PlannedWrite]) -> Result<Vec<(PathBuf, Option<Vec<u8>>)>, String> {
+    let paths: Vec<PathBuf> = writes
+        .iter()
+        .flat_map(|write| [Some(&write.target), write.remove.as_ref()])
+        .flatten()
+        .cloned()
+        .collect();
+    let mut backups = Vec::new();
+    for path in paths {
+        let content = match tokio::fs::read(&path).await {
+            Ok(content) => Some(content),
+            Err(e) if is_missing(&e) => None,
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        backups.push((path, content));
+    }
+    Ok(backups)
+}
+
+fn is_missing(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+    )
+}
+
+async fn apply_writes(writes: &[PlannedWrite]) -> Result<(), String> {
+    for write in writes {
+        if let Some(content) = &write.content {
+            if let Some(parent) = write.target.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| format!("Failed to create directory {}: {e}", parent.display()))?;
+            }
+            write_atomic(&write.target, content)
+                .await
+                .map_err(|e| format!("Failed to write {}: {e}", write.target.display()))?;
+        }
+        if let Some(remove) = &write.remove {
+            tokio::fs::remove_file(remove)
+                .await
+                .map_err(|e| format!("Failed to remove {}: {e}", remove.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Puts back the files `back_up` saw, after a write failed part way.
+async fn restore(backups: Vec<(PathBuf, Option<Vec<u8>>)>) {
+    for (path, content) in backups {
+        let restored = match content {
+            Some(content) => tokio::fs::write(&path, content).await,
+            None => match tokio::fs::remove_file(&path).await {
+                Err(e) if is_missing(&e) => Ok(()),
+                result => result,
+            },
+        };
+        if let Err(e) = restored {
+            tracing::warn!("Could not restore {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn applies_hunks_with_offset_whitespace_and_fuzz() {
+        let original = "header\nextra\none\ntwo  \nthree\n";
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n";
+        let files = parse_patch(patch).unwrap();
+        let (patched, reports) = apply_hunks(original, &files[0].hunks, 2);
+        assert_eq!(patched.as_deref(), Some("header\nextra\none\nTWO\nthree\n"));
+        assert_eq!(reports[0]["offset"], 2);
+        assert_eq!(reports[0]["fuzz"], 0);
+        assert_eq!(reports[0]["whitespace_insensitive"], true);
+
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -2,4 +2,4 @@\n stale\n one\n-two\n+TWO\n three\n";
+        let files = parse_patch(patch).unwrap();
+        let (patched, reports) = apply_hunks(original, &files[0].hunks, 2);
+        assert_eq!(patched.as_deref(), Some("header\nextra\none\nTWO\nthree\n"));
+        assert_eq!(reports[0]["fuzz"], 1);
+
+        let (patched, reports) = apply_hunks(original, &files[0].hunks, 0);
+        assert!(patched.is_none());
+        assert_eq!(reports[0]["applied"], false);
+    }
+
+    #[test]
+    fn hunk_lines_that_look_like_file_headers_stay_in_the_hunk() {
+        let original = "intro\n-- old\nend\n";
+        let patch = "--- a/notes.md\n+++ b/notes.md\n@@ -1,3 +1,3 @@\n intro\n--- old\n+++ new\n \
+                     end\n--- a/other.md\n+++ b/other.md\n@@ -1 +1 @@\n-a\n+b\n";
+        let files = parse_patch(patch).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].hunks[0].lines.len(), 4);
+        assert_eq!(files[1].new_path.as_deref(), Some("other.md"));
+
+        let (patched, _) = apply_hunks(original, &files[0].hunks, 0);
+        assert_eq!(patched.as_deref(), Some("intro\n++ new\nend\n"));
+    }
+
+    #[tokio::test]
+    async fn failed_hunk_leaves_all_files_untouched() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "alpha\nbeta\n").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "gamma\n").unwrap();
+        let tool = ApplyPatchTool::new(tmp.path());
+
+        let patch = "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n alpha\n-beta\n+BETA\n\
+                     --- a/b.txt\n+++ b/b.txt\n@@ -1 +1 @@\n-delta\n+DELTA\n";
+        let result = tool.execute(json!({"patch": patch})).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("\"applied\": false"));
+        let a = std::fs::read_to_string(tmp.path().join("a.txt")).unwrap();
+        assert_eq!(a, "alpha\nbeta\n");
+    }
+
+    #[tokio::test]
+    async fn failed_write_restores_files_already_written() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "alpha\nbeta\n").unwrap();
+        std::fs::write(tmp.path().join("blocker"), "not a directory\n").unwrap();
+        let tool = ApplyPatchTool::new(tmp.path());
+
+        let patch = "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n alpha\n-beta\n+BETA\n\
+                     --- /dev/null\n+++ b/blocker/new.txt\n@@ -0,0 +1 @@\n+new\n";
+        let result = tool.execute(json!({"patch": patch})).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("no files were changed"));
+        let a = std::fs::read_to_string(tmp.path().join("a.txt")).unwrap();
+        assert_eq!(a, "alpha\nbeta\n");
+    }
+
+    #[tokio::test]
+    async fn applies_a_git_diff_with_extended_headers() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        let tool = ApplyPatchTool::new(tmp.path());
+
+        let patch = "diff --git a/lib.rs b/lib.rs\nindex 1111111..2222222 100644\n\
+                     --- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1 @@\n-fn a() {}\n+fn b() {}\n\
+                     diff --git a/new.rs b/new.rs\nnew file mode 100644\nindex 0000000..3333333\n\
+                     --- /dev/null\n+++ b/new.rs\n@@ -0,0 +1 @@\n+fn c() {}\n";
+        let result = tool.execute(json!({"patch": patch})).await.unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        let lib = std::fs::read_to_string(tmp.path().join("lib.rs")).unwrap();
+        assert_eq!(lib, "fn b() {}\n");
+        let new = std::fs::read_to_string(tmp.path().join("new.rs")).unwrap();
+        assert_eq!(new, "fn c() {}\n");
+    }
+
+    #[test]
+    fn rejects_two_patches_of_the_same_file() {
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -1 +1 @@\n-a\n+b\n\
+                     --- a/f.txt\n+++ b/f.txt\n@@ -2 +2 @@\n-c\n+d\n";
+        let error = parse_patch(patch).unwrap_err();
+        assert!(error.contains("f.txt is patched more than once"));
+    }
+
+    #[tokio::test]
+    async fn creates_new_file_and_respects_dry_run() {
+        let tmp = TempDir::new().unwrap();
+        let tool = ApplyPatchTool::new(tmp.path());
+        let patch = "--- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1,2 @@\n+fn main() {}\n+// end\n";
+
+        let dry = tool
+            .execute(json!({"patch": patch, "dry_run": true}))
+            .await
+            .unwrap();
+        assert!(dry.success);
+        assert!(!tmp.path().join("src/new.rs").exists());
+
+        let result = tool.execute(json!({"patch": patch})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let created = std::fs::read_to_string(tmp.path().join("src/new.rs")).unwrap();
+        assert_eq!(created, "fn main() {}\n// end\n");
+    }
+}
@@ -0,0 +1,718 @@
+use crate::tools::security::{RateLimiter, resolve_workspace_target, validate_workspace_path};
+use crate::tools::{extract_string_arg, extract_string_list_arg};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::json;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+const MAX_ENTRIES: usize = 50_000;
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+const LISTED_ENTRIES: usize = 50;
+const RATE_LIMIT_MAX: u64 = 60;
+const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
+
+static GLOBAL_RATE_LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+fn rate_limiter() -> Arc<RateLimiter> {
+    GLOBAL_RATE_LIMITER
+        .get_or_init(|| Arc::new(RateLimiter::new(RATE_LIMIT_MAX, RATE_LIMIT_WINDOW_SECS)))
+        .clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+struct Summary {
+    entries: usize,
+    bytes: u64,
+    listed: Vec<String>,
+}
+
+impl Summary {
+    fn new() -> Self {
+        Self {
+            entries: 0,
+            bytes: 0,
+            listed: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, name: &str, bytes: u64) -> Result<(), String> {
+        self.entries += 1;
+        self.bytes += bytes;
+        if self.entries > MAX_ENTRIES {
+            return Err(format!("Archive has more than {MAX_ENTRIES} entries"));
+        }
+        if self.bytes > MAX_TOTAL_BYTES {
+            return Err(format!(
+                "Archive contents exceed the {MAX_TOTAL_BYTES} byte limit"
+            ));
+        }
+        if self.listed.len() < LISTED_ENTRIES {
+            self.listed.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    fn to_json(&self, archive: &str, extra: (&str, &str)) -> String {
+        let mut value = json!({
+            "archive": archive,
+            "entries": self.entries,
+            "bytes": self.bytes,
+            "files": self.listed,
+            "truncated_listing": self.entries > self.listed.len(),
+        });
+        value[extra.0] = json!(extra.1);
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    }
+}
+
+fn safe_entry_path(destination: &Path, name: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(name);
+    let mut clean = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("Unsafe path in archive: {name}")),
+        }
+    }
+    if clean.as_os_str().is_empty() {
+        return Err(format!("Empty path in archive: {name}"));
+    }
+    Ok(destination.join(clean))
+}
+
+fn copy_limited(reader: &mut impl Read, target: &Path, remaining: u64) -> Result<u64, String> {
+    let mut file =
+        File::create(target).map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+    let written = io::copy(&mut reader.take(remaining + 1), &mut file)
+        .map_err(|e| format!("Failed to write {}: {e}", target.display()))?;
+    if written > remaining {
+        return Err(format!(
+            "Archive contents exceed the {MAX_TOTAL_BYTES} byte limit"
+        ));
+    }
+    Ok(written)
+}
+
+struct PlannedEntry {
+    target: PathBuf,
+    is_dir: bool,
+}
+
+fn check_conflict(
+    destination: &Path,
+    target: &Path,
+    is_dir: bool,
+    overwrite: bool,
+) -> Result<(), String> {
+    // A symlinked directory anywhere below the destination would redirect the write.
+    for path in target
+        .ancestors()
+        .take_while(|path| path.starts_with(destination) && *path != destination)
+    {
+        if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            return Err(format!(
+                "Refusing to extract through symlink: {}",
+                path.display()
+            ));
+        }
+    }
+    match std::fs::symlink_metadata(target) {
+        Ok(meta) if meta.is_dir() && is_dir => Ok(()),
+        Ok(_) if !overwrite => Err(format!(
+            "{} already exists. Pass overwrite=true to replace files",
+            target.display()
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn open_tar(path: &Path, format: ArchiveFormat) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {e}"))?;
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+fn plan_extraction(
+    archive: &Path,
+    format: ArchiveFormat,
+    destination: &Path,
+    overwrite: bool,
+) -> Result<Vec<PlannedEntry>, String> {
+    let mut planned = Vec::new();
+    let mut declared = 0u64;
+
+    let mut add = |name: &str, is_dir: bool, is_link: bool, size: u64| -> Result<(), String> {
+        if is_link {
+            return Err(format!("Archive contains a link entry, refusing: {name}"));
+        }
+        let target = safe_entry_path(destination, name)?;
+        check_conflict(destination, &target, is_dir, overwrite)?;
+        declared += size;
+        if planned.len() >= MAX_ENTRIES {
+            return Err(format!("Archive has more than {MAX_ENTRIES} entries"));
+        }
+        if declared > MAX_TOTAL_BYTES {
+            return Err(format!(
+                "Archive contents exceed the {MAX_TOTAL_BYTES} byte limit"
+            ));
+        }
+        planned.push(PlannedEntry { target, is_dir });
+        Ok(())
+    };
+
+    match format {
+        ArchiveFormat::Zip => {
+            let file = File::open(archive).map_err(|e| format!("Failed to open archive: {e}"))?;
+            let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+            for i in 0..zip.len() {
+                let entry = zip
+                    .by_index(i)
+                    .map_err(|e| format!("Invalid zip entry: {e}"))?;
+                let is_link = entry
+                    .unix_mode()
+                    .is_some_and(|mode| mode & 0o170000 == 0o120000);
+                add(entry.name(), entry.is_dir(), is_link, entry.size())?;
+            }
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let mut tar = open_tar(archive, format)?;
+            for entry in tar.entries().map_err(|e| format!("Invalid tar: {e}"))? {
+                let entry = entry.map_err(|e| format!("Invalid tar entry: {e}"))?;
+                let kind = entry.header().entry_type();
+                let name = entry
+                    .path()
+                    .map_err(|e| format!("Invalid tar entry path: {e}"))?
+                    .to_string_lossy()
+                    .to_string();
+                if !(kind.is_file() || kind.is_dir() || kind.is_symlink() || kind.is_hard_link()) {
+                    continue;
+                }
+                add(
+                    &name,
+                    kind.is_dir(),
+                    kind.is_symlink() || kind.is_hard_link(),
+                    entry.size(),
+                )?;
+            }
+        }
+    }
+    Ok(planned)
+}
+
+fn extract_archive(
+    archive: &Path,
+    format: ArchiveFormat,
+    destination: &Path,
+    overwrite: bool,
+) -> Result<Summary, String> {
+    let planned = plan_extraction(archive, format, destination, overwrite)?;
+    std::fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create destination: {e}"))?;
+
+    let mut summary = Summary::new();
+    let mut write_entry = |index: usize, reader: &mut dyn Read| -> Result<(), String> {
+        let Some(entry) = planned.get(index) else {
+            return Ok(());
+        };
+        let display = entry
+            .target
+            .strip_prefix(destination)
+            .unwrap_or(&entry.target)
+            .display()
+            .to_string();
+        if entry.is_dir {
+            std::fs::create_dir_all(&entry.target)
+                .map_err(|e| format!("Failed to create {display}: {e}"))?;
+            return Ok(());
+        }
+        if let Some(parent) = entry.target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let written = copy_limited(
+            &mut { reader },
+            &entry.target,
+            MAX_TOTAL_BYTES - summary.bytes,
+        )?;
+        summary.record(&display, written)
+    };
+
+    match format {
+        ArchiveFormat::Zip => {
+            let file = File::open(archive).map_err(|e| format!("Failed to open archive: {e}"))?;
+            let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+            for i in 0..zip.len() {
+                let mut entry = zip
+                    .by_index(i)
+                    .map_err(|e| format!("Invalid zip entry: {e}"))?;
+                write_entry(i, &mut entry)?;
+            }
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let mut tar = open_tar(archive, format)?;
+            let mut index = 0;
+            for entry in tar.entries().map_err(|e| format!("Invalid tar: {e}"))? {
+                let mut entry = entry.map_err(|e| format!("Invalid tar entry: {e}"))?;
+                let kind = entry.header().entry_type();
+                if !(kind.is_file() || kind.is_dir()) {
+                    continue;
+                }
+                write_entry(index, &mut entry)?;
+                index += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn collect_sources(
+    workspace: &Path,
+    sources: &[PathBuf],
+) -> Result<Vec<(PathBuf, String, bool)>, String> {
+    let mut files = Vec::new();
+    for source in sources {
+        for entry in walkdir::WalkDir::new(source)
+            .follow_links(false)
+            .sort_by_file_name()
+        {
+            let entry = entry.map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+            let file_type = entry.file_type();
+            if file_type.is_symlink() {
+                continue;
+            }
+            let name = entry
+                .path()
+                .strip_prefix(workspace)
+                .map_err(|_| format!("{} is outside the workspace", entry.path().display()))?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            if name.is_empty() {
+                continue;
+            }
+            files.push((entry.path().to_path_buf(), name, file_type.is_dir()));
+            if files.len() > MAX_ENTRIES {
+                return Err(format!("More than {MAX_ENTRIES} files to archive"));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn create_archive(
+    workspace: &Path,
+    output: &Path,
+    format: ArchiveFormat,
+    sources: &[PathBuf],
+) -> Result<Summary, String> {
+    let files = collect_sources(workspace, sources)?;
+    let output_canon = output.to_path_buf();
+    let mut summary = Summary::new();
+
+    let file = File::create(output).map_err(|e| format!("Failed to create archive: {e}"))?;
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .large_file(true);
+            for (path, name, is_dir) in &files {
+                if *path == output_canon {
+                    continue;
+                }
+                if *is_dir {
+                    zip.add_directory(name.as_str(), options)
+                        .map_err(|e| format!("Failed to add {name}: {e}"))?;
+                    continue;
+                }
+                zip.start_file(name.as_str(), options)
+                    .map_err(|e| format!("Failed to add {name}: {e}"))?;
+                let mut source =
+                    File::open(path).map_err(|e| format!("Failed to read {name}: {e}"))?;
+                let written = io::copy(&mut source, &mut zip)
+                    .map_err(|e| format!("Failed to add {name}: {e}"))?;
+                summary.record(name, written)?;
+            }
+            zip.finish()
+                .map_err(|e| format!("Failed to finish archive: {e}"))?
+                .flush()
+                .map_err(|e| format!("Failed to finish archive: {e}"))?;
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let writer: Box<dyn Write> = match format {
+                ArchiveFormat::TarGz => Box::new(GzEncoder::new(file, Compression::default())),
+                _ => Box::new(file),
+            };
+            let mut tar = tar::Builder::new(writer);
+            tar.follow_symlinks(false);
+            for (path, name, is_dir) in &files {
+                if *path == output_canon {
+                    continue;
+                }
+                if *is_dir {
+                    tar.append_dir(name, path)
+                        .map_err(|e| format!("Failed to add {name}: {e}"))?;
+                    continue;
+                }
+                let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                summary.record(name, size)?;
+                tar.append_path_with_name(path, name)
+                    .map_err(|e| format!("Failed to add {name}: {e}"))?;
+            }
+            tar.into_inner()
+                .map_err(|e| format!("Failed to finish archive: {e}"))?
+                .flush()
+                .map_err(|e| format!("Failed to finish archive: {e}"))?;
+        }
+    }
+    Ok(summary)
+}
+
+pub struct ArchiveCreateTool {
+    workspace: PathBuf,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl ArchiveCreateTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter: rate_limiter(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ArchiveCreateTool {
+    fn name(&self) -> &str {
+        "archive_create"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Package workspace files and directories into a .zip, .tar or .tar.gz archive (format from the file extension)"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Archive to create, relative to the workspace (e.g. dist/build.zip)"
+                },
+                "sources": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Workspace-relative files or directories to include"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace an existing archive (default: false)"
+                }
+            },
+            "required": ["path", "sources"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many archive operations. Please wait a moment.",
+            ));
+        }
+
+        let path = extract_string_arg(&args, "path")?;
+        let overwrite = args
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let output = match resolve_workspace_target(&path, &self.workspace) {
+            Ok(p) => p,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let Some(format) = ArchiveFormat::from_path(&output) else {
+            return Ok(ToolResult::error(
+                "Unsupported archive type. Use .zip, .tar, .tar.gz or .tgz",
+            ));
+        };
+        if output.exists() && !overwrite {
+            return Ok(ToolResult::error(format!(
+                "{path} already exists. Pass overwrite=true to replace it"
+            )));
+        }
+
+        let source_names = extract_string_list_arg(&args, "sources");
+        if source_names.is_empty() {
+            return Ok(ToolResult::error("'sources' must list at least one path"));
+        }
+        let mut sources = Vec::with_capacity(source_names.len());
+        for name in &source_names {
+            match validate_workspace_path(name, &self.workspace) {
+                Ok(p) if p.exists() => sources.push(p),
+                Ok(_) => return Ok(ToolResult::error(format!("Source not found: {name}"))),
+                Err(e) => return Ok(ToolResult::error(e)),
+            }
+        }
+
+        let workspace = match self.workspace.canonicalize() {
+            Ok(w) => w,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Cannot resolve workspace directory: {e}"
+                )));
+            }
+        };
+        if let Some(parent) = output.parent()
+            && let Err(e) = tokio::fs::create_dir_all(parent).await
+        {
+            return Ok(ToolResult::error(format!(
+                "Failed to create directory: {e}"
+            )));
+        }
+
+        let output_path = output.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            create_archive(&workspace, &output_path, format, &sources)
+        })
+        .await?;
+
+        match result {
+            Ok(summary) => Ok(ToolResult::success(
+                summary.to_json(&path, ("action", "created")),
+            )),
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&output).await;
+                Ok(ToolResult::error(e))
+            }
+        }
+    }
+}
+
+pub struct ArchiveExtractTool {
+    workspace: PathBuf,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl ArchiveExtractTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter: rate_limiter(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ArchiveExtractTool {
+    fn name(&self) -> &str {
+        "archive_extract"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Extract a .zip, .tar or .tar.gz archive into a workspace directory. Entries escaping the destination, \
+         links and oversized archives are rejected before anything is written"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Archive to extract, relative to the workspace"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Directory to extract into (default: archive name without extension)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace existing files (default: false)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many archive operations. Please wait a moment.",
+            ));
+        }
+
+        let path = extract_string_arg(&args, "path")?;
+        let overwrite = args
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let archive = match validate_workspace_path(&path, &self.workspace) {
+            Ok(p) => p,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let Some(format) = ArchiveFormat::from_path(&archive) else {
+            return Ok(ToolResult::error(
+                "Unsupported archive type. Use .zip, .tar, .tar.gz or .tgz",
+            ));
+        };
+
+        let destination_name = match args.get("destination").and_then(|v| v.as_str()) {
+            Some(d) => d.to_string(),
+            None => {
+                let lower = path.to_lowercase();
+                let stem_len = [".tar.gz", ".tgz", ".tar", ".zip"]
+                    .iter()
+                    .find(|ext| lower.ends_with(*ext))
+                    .map(|ext| path.len() - ext.len())
+                    .unwrap_or(path.len());
+                path[..stem_len].to_string()
+            }
+        };
+        let destination = match resolve_workspace_target(&destination_name, &self.workspace) {
+            Ok(d) => d,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let result = tokio::task::spawn_blocking(move || {
+            extract_archive(&archive, format, &destination, overwrite)
+        })
+        .await?;
+
+        match result {
+            Ok(summary) => Ok(ToolResult::success(
+                summary.to_json(&path, ("destination", &destination_name)),
+            )),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn zip_and_tar_gz_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("project/src")).unwrap();
+        std::fs::write(tmp.path().join("project/src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(tmp.path().join("project/README.md"), "# hi").unwrap();
+
+        let create = ArchiveCreateTool::new(tmp.path());
+        let extract = ArchiveExtractTool::new(tmp.path());
+
+        for archive in ["out/project.zip", "out/project.tar.gz"] {
+            let result = create
+                .execute(json!({"path": archive, "sources": ["project"]}))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+
+            let destination = format!("unpacked-{}", archive.rsplit('.').next().unwrap());
+            let result = extract
+                .execute(json!({"path": archive, "destination": destination}))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+
+            let main = tmp.path().join(&destination).join("project/src/main.rs");
+            assert_eq!(std::fs::read_to_string(main).unwrap(), "fn main() {}");
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_zip_slip_entries_before_writing() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("evil.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("safe.txt", options).unwrap();
+            zip.write_all(b"ok").unwrap();
+            zip.start_file("../escaped.txt", options).unwrap();
+            zip.write_all(b"pwned").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let tool = ArchiveExtractTool::new(tmp.path());
+        let result = tool.execute(json!({"path": "evil.zip"})).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Unsafe path"));
+        assert!(!tmp.path().join("evil/safe.txt").exists());
+        assert!(!tmp.path().parent().unwrap().join("escaped.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refuses_to_extract_under_a_symlinked_directory() {
+        let tmp = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("out")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), tmp.path().join("out/docs")).unwrap();
+        let archive = tmp.path().join("docs.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+            zip.start_file(
+                "docs/nested/notes.txt",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.write_all(b"pwned").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let tool = ArchiveExtractTool::new(tmp.path());
+        let result = tool
+            .execute(json!({"path": "docs.zip", "destination": "out"}))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("symlink"));
+        assert!(!outside.path().join("nested").exists());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::tools::extract_string_arg;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+pub const AWAITING_USER: &str = "awaiting_user";
+const MAX_OPTIONS: usize = 10;
+
+#[async_trait]
+pub trait UserInputHandler: Send + Sync {
+    async fn ask(&self, question: &str, options: &[String]) -> Option<String>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PendingQuestion {
+    pub status: String,
+    pub question: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+impl PendingQuestion {
+    pub fn new(question: impl Into<String>, options: Vec<String>) -> Self {
+        Self {
+            status: AWAITING_USER.to_string(),
+            question: question.into(),
+            options,
+        }
+    }
+
+    pub fn from_output(output: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(output)
+            .ok()
+            .filter(|q| q.status == AWAITING_USER)
+    }
+
+    pub fn render(&self) -> String {
+        if self.options.is_empty() {
+            return self.question.clone();
+        }
+        let options = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| format!("{}. {option}", i + 1))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{}\n\n{options}", self.question)
+    }
+}
+
+pub struct AskUserTool {
+    handler: Option<Arc<dyn UserInputHandler>>,
+}
+
+impl AskUserTool {
+    pub fn new() -> Self {
+        Self { handler: None }
+    }
+
+    pub fn with_handler(mut self, handler: Arc<dyn UserInputHandler>) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+}
+
+impl Default for AskUserTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for AskUserTool {
+    fn name(&self) -> &str {
+        "ask_user"
+    }
+
+    fn description(&self) -> &str {
+        "Ask the user a clarifying question and wait for the answer. Use when the request is ambiguous \
+         or a decision needs their input, instead of guessing"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "question": {
+                    "type": "string",
+                    "description": "The question to ask, self-contained and specific"
+                },
+                "options": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional choices to offer (the user may still answer freely)"
+                }
+            },
+            "required": ["question"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let question = extract_string_arg(&args, "question")?;
+        let question = question.trim();
+        if question.is_empty() {
+            return Ok(ToolResult::error("'question' cannot be empty"));
+        }
+        let options: Vec<String> = args
+            .get("options")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .take(MAX_OPTIONS)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let Some(handler) = &self.handler else {
+            let pending = PendingQuestion::new(question, options);
+            return Ok(ToolResult::success(serde_json::to_string(&pending)?));
+        };
+
+        match handler.ask(question, &options).await {
+            Some(answer) if !answer.trim().is_empty() => Ok(ToolResult::success(format!(
+                "User answered: {}",
+                answer.trim()
+            ))),
+            _ => Ok(ToolResult::error(
+                "The user did not answer. Proceed with your best judgment and state the assumptions you made.",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedAnswer;
+
+    #[async_trait]
+    impl UserInputHandler for FixedAnswer {
+        async fn ask(&self, _question: &str, options: &[String]) -> Option<String> {
+            options.first().cloned()
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_pending_question_without_handler() {
+        let tool = AskUserTool::new();
+        let result = tool
+            .execute(json!({"question": "Which database?", "options": ["sqlite", "postgres"]}))
+            .await
+            .unwrap();
+
+        let pending = PendingQuestion::from_output(&result.output).unwrap();
+        assert_eq!(pending.question, "Which database?");
+        assert_eq!(
+            pending.render(),
+            "Which database?\n\n1. sqlite\n2. postgres"
+        );
+    }
+
+    #[tokio::test]
+    async fn uses_handler_answer() {
+        let tool = AskUserTool::new().with_handler(Arc::new(FixedAnswer));
+        let result = tool
+            .execute(json!({"question": "Which database?", "options": ["sqlite"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "User answered: sqlite");
+        assert!(PendingQuestion::from_output(&result.output).is_none());
+    }
+}
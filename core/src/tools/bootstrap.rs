@@ -0,0 +1,380 @@
+use crate::tools::diff::unified_diff;
+use crate::tools::security::RateLimiter;
+use crate::tools::{extract_string_arg, extract_string_arg_opt, get_global_rate_limiter};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The most a bootstrap file may grow to. All of them share the prompt's bootstrap
+/// budget, so a runaway file would crowd out the others.
+const MAX_FILE_BYTES: usize = 12 * 1024;
+const MAX_CONTENT_BYTES: usize = 2 * 1024;
+const DIFF_CONTEXT: usize = 2;
+
+/// Changes one section of a bootstrap file, so the agent can record what it learns
+/// without rewriting the whole file. Every change comes back as a diff.
+pub struct UpdateBootstrapTool {
+    workspace: PathBuf,
+    file: &'static str,
+    name: &'static str,
+    description: &'static str,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl UpdateBootstrapTool {
+    /// `update_soul`, for SOUL.md.
+    pub fn soul(workspace: impl AsRef<Path>) -> Self {
+        Self::new(
+            workspace,
+            "SOUL.md",
+            "update_soul",
+            "Add to or rewrite one section of SOUL.md, your identity and how you behave. \
+             Use it when the user asks you to change how you work, e.g. section 'Communication Style' \
+             with '- Answer in Indonesian'. Prefer this over file_write for SOUL.md",
+        )
+    }
+
+    /// `update_user_profile`, for USER.md.
+    pub fn user_profile(workspace: impl AsRef<Path>) -> Self {
+        Self::new(
+            workspace,
+            "USER.md",
+            "update_user_profile",
+            "Record facts about the user in one section of USER.md, e.g. section 'User Profile' \
+             with '- **Name**: Ana'. A line with the same 'key:' as an existing one replaces it. \
+             Prefer this over file_write for USER.md",
+        )
+    }
+
+    fn new(
+        workspace: impl AsRef<Path>,
+        file: &'static str,
+        name: &'static str,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            file,
+            name,
+            description,
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for UpdateBootstrapTool {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "section": {
+                    "type": "string",
+                    "description": format!("Heading of the section in {}, without '#'; added when missing", self.file)
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Lines to add, one fact per line; each becomes a list item. No headings"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["append", "replace"],
+                    "description": "append: add the lines to the section (default); replace: make them the whole section"
+                },
+                "preview": {
+                    "type": "boolean",
+                    "description": "Only show the diff, without writing (default: false)"
+                }
+            },
+            "required": ["section", "content"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let section = extract_string_arg(&args, "section")?;
+        let content = extract_string_arg(&args, "content")?;
+        let mode = extract_string_arg_opt(&args, "mode", "append");
+        let preview = args
+            .get("preview")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let section = section.trim().trim_start_matches('#').trim();
+        if section.is_empty() || section.contains('\n') {
+            return Ok(ToolResult::error("section must be a single heading"));
+        }
+        let replace = match mode.as_str() {
+            "append" => false,
+            "replace" => true,
+            _ => {
+                return Ok(ToolResult::error(format!(
+                    "Unknown mode '{mode}': use append or replace"
+                )));
+            }
+        };
+        if content.len() > MAX_CONTENT_BYTES {
+            return Ok(ToolResult::error(format!(
+                "content is {} bytes, over the {MAX_CONTENT_BYTES} limit; add fewer lines at a time",
+                content.len()
+            )));
+        }
+        let lines = match items(&content) {
+            Ok(lines) => lines,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        if lines.is_empty() && !replace {
+            return Ok(ToolResult::error("content has no lines to add"));
+        }
+        if !preview && !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many edits. Please wait a moment.",
+            ));
+        }
+
+        let path = self.workspace.join(self.file);
+        if let Ok(meta) = tokio::fs::symlink_metadata(&path).await
+            && meta.file_type().is_symlink()
+        {
+            return Ok(ToolResult::error(format!(
+                "Refusing to edit {} through a symlink",
+                self.file
+            )));
+        }
+        let old = match tokio::fs::read_to_string(&path).await {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to read {}: {e}",
+                    self.file
+                )));
+            }
+        };
+        let new = update_section(&old, section, &lines, replace);
+        if new.len() > MAX_FILE_BYTES && new.len() > old.len() {
+            return Ok(ToolResult::error(format!(
+                "{} would grow to {} bytes, over the {MAX_FILE_BYTES} limit. \
+                 Condense a section with mode 'replace' first",
+                self.file,
+                new.len()
+            )));
+        }
+        let label = |side: &str| format!("{side}/{}", self.file);
+        let Some(diff) = unified_diff(&old, &new, &label("a"), &label("b"), DIFF_CONTEXT) else {
+            return Ok(ToolResult::success(format!(
+                "{} already has this in '{section}'; nothing changed",
+                self.file
+            )));
+        };
+        if preview {
+            return Ok(ToolResult::success(format!(
+                "Preview, nothing was written:\n\n{diff}"
+            )));
+        }
+
+        let tmp = path.with_extension("md.tmp");
+        let written = async {
+            tokio::fs::write(&tmp, &new).await?;
+            tokio::fs::rename(&tmp, &path).await
+        };
+        match written.await {
+            Ok(()) => Ok(ToolResult::success(format!(
+                "Updated '{section}' in {}:\n\n{diff}",
+                self.file
+            ))),
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&tmp).await;
+                Ok(ToolResult::error(format!(
+                    "Failed to write {}: {e}",
+                    self.file
+                )))
+            }
+        }
+    }
+}
+
+/// The lines of `content` as list items. Headings and rules are refused, as they would
+/// break the file's sections.
+fn items(content: &str) -> Result<Vec<String>, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if line.starts_with('#') || line.starts_with("---") {
+                return Err(format!(
+                    "'{line}' would break the file's sections; give the section as 'section' instead"
+                ));
+            }
+            let is_item = line.starts_with("- ")
+                || line.starts_with("* ")
+                || line.split_once(". ").is_some_and(|(n, _)| {
+                    !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+                });
+            Ok(if is_item {
+                line.to_string()
+            } else {
+                format!("- {line}")
+            })
+        })
+        .collect()
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// The text of an item before its first `:`, without the list marker and bold, e.g.
+/// `name` for `- **Name**: Ana`.
+fn item_key(line: &str) -> Option<String> {
+    let text = line.trim().trim_start_matches(['-', '*']).trim();
+    let (key, _) = text.split_once(':')?;
+    let key = key.trim().trim_matches('*').trim();
+    (!key.is_empty() && key.len() <= 40).then(|| key.to_lowercase())
+}
+
+/// A list item that only holds a `[placeholder]`, as the default files do.
+fn is_placeholder(line: &str) -> bool {
+    let text = line.trim().trim_start_matches(['-', '*']).trim();
+    text.starts_with('[') && text.ends_with(']')
+}
+
+/// `text` with `lines` added to the section headed `section`, or in place of its body
+/// with `replace`. A line whose `key:` is already in the section replaces that line, and
+/// placeholders go once there is something else. A missing section is added before the
+/// closing `---` of the file, or at its end.
+fn update_section(text: &str, section: &str, lines: &[String], replace: bool) -> String {
+    let mut out: Vec<String> = text.lines().map(str::to_string).collect();
+    let heading = out.iter().position(|line| {
+        heading_level(line).is_some_and(|level| line[level..].trim().eq_ignore_ascii_case(section))
+    });
+    let (start, end) = match heading {
+        Some(start) => {
+            let level = heading_level(&out[start]).unwrap_or(2);
+            let end = out[start + 1..]
+                .iter()
+                .position(|line| {
+                    line.trim() == "---" || heading_level(line).is_some_and(|l| l <= level)
+                })
+                .map_or(out.len(), |i| start + 1 + i);
+            (start, end)
+        }
+        None => {
+            let at = out
+                .iter()
+                .rposition(|line| line.trim() == "---")
+                .unwrap_or(out.len());
+            out.insert(at, format!("## {section}"));
+            (at, at + 1)
+        }
+    };
+
+    let mut body: Vec<String> = if replace {
+        Vec::new()
+    } else {
+        out[start + 1..end]
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .cloned()
+            .collect()
+    };
+    for line in lines {
+        let key = item_key(line);
+        let same = body.iter().position(|existing| {
+            existing.trim() == line || (key.is_some() && item_key(existing) == key)
+        });
+        match same {
+            Some(i) => body[i] = line.clone(),
+            None => body.push(line.clone()),
+        }
+    }
+    if body.iter().any(|line| !is_placeholder(line)) {
+        body.retain(|line| !is_placeholder(line));
+    }
+
+    let mut section = vec![String::new()];
+    section.extend(body);
+    section.push(String::new());
+    out.splice(start + 1..end, section);
+    if start > 0 && !out[start - 1].trim().is_empty() {
+        out.insert(start, String::new());
+    }
+    while out.last().is_some_and(|line| line.trim().is_empty()) {
+        out.pop();
+    }
+    out.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempWorkspace;
+
+    const USER: &str = "# USER.md\n\n## User Profile\n\n- **Name**: [User's name]\n\n\
+                        ## Things to Remember\n\n- [Important preference or habit]\n\n---\n\n*Edit me*\n";
+
+    #[tokio::test]
+    async fn updates_one_section() {
+        let workspace = TempWorkspace::new().with_file("USER.md", USER);
+        let tool = UpdateBootstrapTool::user_profile(workspace.path());
+
+        let result = tool
+            .execute(
+                json!({ "section": "user profile", "content": "**Name**: Ana", "preview": true }),
+            )
+            .await
+            .unwrap();
+        assert!(result.success && result.output.contains("+- **Name**: Ana"));
+        assert_eq!(workspace.read("USER.md"), USER);
+
+        tool.execute(json!({ "section": "User Profile", "content": "**Name**: Ana" }))
+            .await
+            .unwrap();
+        tool.execute(
+            json!({ "section": "Things to Remember", "content": "Prefers tea\nWorks late" }),
+        )
+        .await
+        .unwrap();
+        tool.execute(json!({ "section": "Projects", "content": "- dinoe" }))
+            .await
+            .unwrap();
+        assert_eq!(
+            workspace.read("USER.md"),
+            "# USER.md\n\n## User Profile\n\n- **Name**: Ana\n\n## Things to Remember\n\n\
+             - Prefers tea\n- Works late\n\n## Projects\n\n- dinoe\n\n---\n\n*Edit me*\n"
+        );
+
+        let again = tool
+            .execute(json!({ "section": "Projects", "content": "dinoe" }))
+            .await
+            .unwrap();
+        assert!(again.output.contains("nothing changed"));
+        let heading = tool
+            .execute(json!({ "section": "Projects", "content": "## Sneaky" }))
+            .await
+            .unwrap();
+        assert!(!heading.success);
+
+        workspace.write("USER.md", &"- a fact\n".repeat(MAX_FILE_BYTES / 9 + 1));
+        let full = tool
+            .execute(json!({ "section": "Notes", "content": "One more" }))
+            .await
+            .unwrap();
+        assert!(full.error.unwrap().contains("limit"));
+    }
+}
@@ -0,0 +1,1072 @@
+use crate::tools::extract_string_arg;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use chrono::{Datelike, Local, Months, NaiveDate};
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use serde_json::json;
+
+const MAX_INPUT_CHARS: usize = 1_000;
+const MAX_DEPTH: usize = 64;
+const MAX_RESULT_BITS: u64 = 200_000;
+const MAX_FACTORIAL: u64 = 5_000;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+type Dim = [i8; 5];
+
+const NONE: Dim = [0, 0, 0, 0, 0];
+const LENGTH: Dim = [1, 0, 0, 0, 0];
+const MASS: Dim = [0, 1, 0, 0, 0];
+const TIME: Dim = [0, 0, 1, 0, 0];
+const DATA: Dim = [0, 0, 0, 1, 0];
+const TEMPERATURE: Dim = [0, 0, 0, 0, 1];
+const AREA: Dim = [2, 0, 0, 0, 0];
+const VOLUME: Dim = [3, 0, 0, 0, 0];
+const SPEED: Dim = [1, 0, -1, 0, 0];
+const FORCE: Dim = [1, 1, -2, 0, 0];
+const ENERGY: Dim = [2, 1, -2, 0, 0];
+const POWER: Dim = [2, 1, -3, 0, 0];
+const PRESSURE: Dim = [-1, 1, -2, 0, 0];
+
+const CALENDAR_UNITS: &[&str] = &["month", "months", "mo", "year", "years", "yr", "y"];
+
+#[rustfmt::skip]
+const UNITS: &[(&[&str], f64, f64, Dim)] = &[
+    (&["m", "meter", "meters", "metre", "metres"], 1.0, 0.0, LENGTH),
+    (&["km", "kilometer", "kilometers"], 1_000.0, 0.0, LENGTH),
+    (&["cm", "centimeter", "centimeters"], 0.01, 0.0, LENGTH),
+    (&["mm", "millimeter", "millimeters"], 0.001, 0.0, LENGTH),
+    (&["um", "micron", "microns"], 1e-6, 0.0, LENGTH),
+    (&["nm", "nanometer", "nanometers"], 1e-9, 0.0, LENGTH),
+    (&["mi", "mile", "miles"], 1_609.344, 0.0, LENGTH),
+    (&["yd", "yard", "yards"], 0.9144, 0.0, LENGTH),
+    (&["ft", "foot", "feet"], 0.3048, 0.0, LENGTH),
+    (&["in", "inch", "inches"], 0.0254, 0.0, LENGTH),
+    (&["nmi"], 1_852.0, 0.0, LENGTH),
+    (&["kg", "kilogram", "kilograms"], 1.0, 0.0, MASS),
+    (&["g", "gram", "grams"], 0.001, 0.0, MASS),
+    (&["mg", "milligram", "milligrams"], 1e-6, 0.0, MASS),
+    (&["t", "tonne", "tonnes"], 1_000.0, 0.0, MASS),
+    (&["lb", "lbs", "pound", "pounds"], 0.453_592_37, 0.0, MASS),
+    (&["oz", "ounce", "ounces"], 0.028_349_523_125, 0.0, MASS),
+    (&["s", "sec", "secs", "second", "seconds"], 1.0, 0.0, TIME),
+    (&["ms", "millisecond", "milliseconds"], 0.001, 0.0, TIME),
+    (&["us", "microsecond", "microseconds"], 1e-6, 0.0, TIME),
+    (&["min", "mins", "minute", "minutes"], 60.0, 0.0, TIME),
+    (&["h", "hr", "hrs", "hour", "hours"], 3_600.0, 0.0, TIME),
+    (&["d", "day", "days"], SECONDS_PER_DAY, 0.0, TIME),
+    (&["wk", "week", "weeks"], 7.0 * SECONDS_PER_DAY, 0.0, TIME),
+    (&["mo", "month", "months"], 30.436_875 * SECONDS_PER_DAY, 0.0, TIME),
+    (&["y", "yr", "year", "years"], 365.2425 * SECONDS_PER_DAY, 0.0, TIME),
+    (&["bit", "bits"], 0.125, 0.0, DATA),
+    (&["B", "byte", "bytes"], 1.0, 0.0, DATA),
+    (&["KB", "kB"], 1e3, 0.0, DATA),
+    (&["MB"], 1e6, 0.0, DATA),
+    (&["GB"], 1e9, 0.0, DATA),
+    (&["TB"], 1e12, 0.0, DATA),
+    (&["PB"], 1e15, 0.0, DATA),
+    (&["KiB"], 1_024.0, 0.0, DATA),
+    (&["MiB"], 1_048_576.0, 0.0, DATA),
+    (&["GiB"], 1_073_741_824.0, 0.0, DATA),
+    (&["TiB"], 1_099_511_627_776.0, 0.0, DATA),
+    (&["K", "kelvin"], 1.0, 0.0, TEMPERATURE),
+    (&["degC", "°C", "celsius"], 1.0, 273.15, TEMPERATURE),
+    (&["degF", "°F", "fahrenheit"], 5.0 / 9.0, 459.67 * 5.0 / 9.0, TEMPERATURE),
+    (&["ha", "hectare", "hectares"], 10_000.0, 0.0, AREA),
+    (&["acre", "acres"], 4_046.856_422_4, 0.0, AREA),
+    (&["L", "l", "liter", "liters", "litre", "litres"], 0.001, 0.0, VOLUME),
+    (&["mL", "ml", "milliliter", "milliliters"], 1e-6, 0.0, VOLUME),
+    (&["gal", "gallon", "gallons"], 0.003_785_411_784, 0.0, VOLUME),
+    (&["mph"], 0.447_04, 0.0, SPEED),
+    (&["kph", "kmh"], 1_000.0 / 3_600.0, 0.0, SPEED),
+    (&["knot", "knots", "kn"], 1_852.0 / 3_600.0, 0.0, SPEED),
+    (&["N", "newton", "newtons"], 1.0, 0.0, FORCE),
+    (&["J", "joule", "joules"], 1.0, 0.0, ENERGY),
+    (&["kJ"], 1_000.0, 0.0, ENERGY),
+    (&["cal"], 4.184, 0.0, ENERGY),
+    (&["kcal"], 4_184.0, 0.0, ENERGY),
+    (&["Wh"], 3_600.0, 0.0, ENERGY),
+    (&["kWh"], 3_600_000.0, 0.0, ENERGY),
+    (&["W", "watt", "watts"], 1.0, 0.0, POWER),
+    (&["kW"], 1_000.0, 0.0, POWER),
+    (&["Pa"], 1.0, 0.0, PRESSURE),
+    (&["kPa"], 1_000.0, 0.0, PRESSURE),
+    (&["bar"], 100_000.0, 0.0, PRESSURE),
+    (&["psi"], 6_894.757_293_168, 0.0, PRESSURE),
+];
+
+#[derive(Debug, Clone)]
+struct Unit {
+    name: String,
+    factor: f64,
+    offset: f64,
+    dim: Dim,
+}
+
+impl Unit {
+    fn lookup(name: &str) -> Option<Self> {
+        UNITS
+            .iter()
+            .find(|(aliases, ..)| aliases.contains(&name))
+            .map(|(_, factor, offset, dim)| Self {
+                name: name.to_string(),
+                factor: *factor,
+                offset: *offset,
+                dim: *dim,
+            })
+    }
+
+    fn combine(&self, other: &Unit, sign: i8, op: &str) -> Self {
+        let mut dim = self.dim;
+        for (d, o) in dim.iter_mut().zip(other.dim) {
+            *d += sign * o;
+        }
+        let factor = if sign > 0 {
+            self.factor * other.factor
+        } else {
+            self.factor / other.factor
+        };
+        Self {
+            name: format!("{}{op}{}", self.name, other.name),
+            factor,
+            offset: 0.0,
+            dim,
+        }
+    }
+
+    fn powi(&self, n: i32) -> Self {
+        Self {
+            name: format!("{}^{n}", self.name),
+            factor: self.factor.powi(n),
+            offset: 0.0,
+            dim: self.dim.map(|d| d * n as i8),
+        }
+    }
+
+    fn is_calendar(&self) -> bool {
+        CALENDAR_UNITS.contains(&self.name.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Int(BigInt),
+    Float(f64),
+    Quantity { base: f64, unit: Unit },
+    Date(NaiveDate),
+}
+
+impl Value {
+    fn quantity(value: f64, unit: Unit) -> Self {
+        if unit.dim == NONE {
+            return Value::Float(value * unit.factor);
+        }
+        Value::Quantity {
+            base: value * unit.factor + unit.offset,
+            unit,
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => i.to_f64(),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Int(_) | Value::Float(_) => "a number",
+            Value::Quantity { .. } => "a quantity",
+            Value::Date(_) => "a date",
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Value::Int(i) => i.to_string(),
+            Value::Float(f) => format_float(*f),
+            Value::Quantity { base, unit } => {
+                format!(
+                    "{} {}",
+                    format_float((base - unit.offset) / unit.factor),
+                    unit.name
+                )
+            }
+            Value::Date(d) => format!("{} ({})", d.format("%Y-%m-%d"), d.format("%A")),
+        }
+    }
+}
+
+fn format_float(f: f64) -> String {
+    if !f.is_finite() {
+        return f.to_string();
+    }
+    if f == 0.0 {
+        return "0".to_string();
+    }
+    let abs = f.abs();
+    if !(1e-6..1e15).contains(&abs) {
+        let text = format!("{f:.10e}");
+        let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{mantissa}e{exponent}");
+    }
+    let decimals = (11 - abs.log10().floor() as i32).clamp(0, 15) as usize;
+    let text = format!("{f:.decimals$}");
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+fn check_bits(bits: u64) -> Result<(), String> {
+    if bits > MAX_RESULT_BITS {
+        Err("Result is too large".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn add_to_date(date: NaiveDate, base: f64, unit: &Unit, sign: f64) -> Result<Value, String> {
+    let value = (base - unit.offset) / unit.factor * sign;
+    if unit.is_calendar() && value.fract() == 0.0 {
+        let months = if unit.name.starts_with('y') {
+            value * 12.0
+        } else {
+            value
+        };
+        let shifted = if months >= 0.0 {
+            date.checked_add_months(Months::new(months as u32))
+        } else {
+            date.checked_sub_months(Months::new((-months) as u32))
+        };
+        return shifted
+            .map(Value::Date)
+            .ok_or_else(|| "Date out of range".to_string());
+    }
+    let days = base * sign / SECONDS_PER_DAY;
+    if (days - days.round()).abs() > 1e-9 {
+        return Err("Date arithmetic needs a whole number of days".to_string());
+    }
+    date.checked_add_signed(chrono::Duration::days(days.round() as i64))
+        .map(Value::Date)
+        .ok_or_else(|| "Date out of range".to_string())
+}
+
+fn add(a: Value, b: Value, sign: f64) -> Result<Value, String> {
+    let op = if sign > 0.0 { "add" } else { "subtract" };
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => Ok(Value::Int(if sign > 0.0 { x + y } else { x - y })),
+        (Value::Quantity { base: x, unit: ux }, Value::Quantity { base: y, unit: uy }) => {
+            if ux.dim != uy.dim {
+                return Err(format!("Incompatible units: {} and {}", ux.name, uy.name));
+            }
+            let base = if sign > 0.0 {
+                x + y - ux.offset
+            } else {
+                x - y + ux.offset
+            };
+            Ok(Value::Quantity { base, unit: ux })
+        }
+        (Value::Date(d), Value::Quantity { base, unit }) if unit.dim == TIME => {
+            add_to_date(d, base, &unit, sign)
+        }
+        (Value::Quantity { base, unit }, Value::Date(d)) if unit.dim == TIME && sign > 0.0 => {
+            add_to_date(d, base, &unit, sign)
+        }
+        (Value::Date(d), n @ (Value::Int(_) | Value::Float(_))) => {
+            let days = Unit::lookup("days").expect("day unit");
+            add_to_date(d, n.number().unwrap_or(0.0) * SECONDS_PER_DAY, &days, sign)
+        }
+        (Value::Date(x), Value::Date(y)) if sign < 0.0 => Ok(Value::quantity(
+            (x - y).num_days() as f64,
+            Unit::lookup("days").expect("day unit"),
+        )),
+        (a, b) => match (a.number(), b.number()) {
+            (Some(x), Some(y)) => Ok(Value::Float(x + sign * y)),
+            _ => Err(format!("Cannot {op} {} and {}", a.kind(), b.kind())),
+        },
+    }
+}
+
+fn scale(value: Value, factor: f64) -> Result<Value, String> {
+    match value {
+        Value::Quantity { base, unit } => Ok(Value::quantity(
+            (base - unit.offset) / unit.factor * factor,
+            unit,
+        )),
+        other => Err(format!("Cannot scale {}", other.kind())),
+    }
+}
+
+fn mul(a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => {
+            check_bits(x.bits() + y.bits())?;
+            Ok(Value::Int(x * y))
+        }
+        (q @ Value::Quantity { .. }, n) | (n, q @ Value::Quantity { .. })
+            if n.number().is_some() =>
+        {
+            scale(q, n.number().unwrap_or(1.0))
+        }
+        (Value::Quantity { base: x, unit: ux }, Value::Quantity { base: y, unit: uy }) => {
+            let unit = ux.combine(&uy, 1, "*");
+            Ok(Value::quantity(x * y / unit.factor, unit))
+        }
+        (a, b) => match (a.number(), b.number()) {
+            (Some(x), Some(y)) => Ok(Value::Float(x * y)),
+            _ => Err(format!("Cannot multiply {} and {}", a.kind(), b.kind())),
+        },
+    }
+}
+
+fn div(a: Value, b: Value) -> Result<Value, String> {
+    if b.number() == Some(0.0) {
+        return Err("Division by zero".to_string());
+    }
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => {
+            if (&x % &y).is_zero() {
+                Ok(Value::Int(x / y))
+            } else {
+                Ok(Value::Float(
+                    x.to_f64().unwrap_or(f64::NAN) / y.to_f64().unwrap_or(f64::NAN),
+                ))
+            }
+        }
+        (q @ Value::Quantity { .. }, n) if n.number().is_some() => {
+            scale(q, 1.0 / n.number().unwrap_or(1.0))
+        }
+        (n, Value::Quantity { base, unit }) if n.number().is_some() => {
+            let one = Unit {
+                name: "1".to_string(),
+                factor: 1.0,
+                offset: 0.0,
+                dim: NONE,
+            };
+            let inverse = one.combine(&unit, -1, "/");
+            Ok(Value::quantity(
+                n.number().unwrap_or(1.0) / base / inverse.factor,
+                inverse,
+            ))
+        }
+        (Value::Quantity { base: x, unit: ux }, Value::Quantity { base: y, unit: uy }) => {
+            if y == 0.0 {
+                return Err("Division by zero".to_string());
+            }
+            if ux.dim == uy.dim {
+                return Ok(Value::Float(x / y));
+            }
+            let unit = ux.combine(&uy, -1, "/");
+            Ok(Value::quantity(x / y / unit.factor, unit))
+        }
+        (a, b) => match (a.number(), b.number()) {
+            (Some(x), Some(y)) => Ok(Value::Float(x / y)),
+            _ => Err(format!("Cannot divide {} by {}", a.kind(), b.kind())),
+        },
+    }
+}
+
+fn rem(a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => {
+            if y.is_zero() {
+                return Err("Division by zero".to_string());
+            }
+            Ok(Value::Int(x % y))
+        }
+        (a, b) => match (a.number(), b.number()) {
+            (Some(_), Some(0.0)) => Err("Division by zero".to_string()),
+            (Some(x), Some(y)) => Ok(Value::Float(x % y)),
+            _ => Err(format!("Cannot take {} modulo {}", a.kind(), b.kind())),
+        },
+    }
+}
+
+fn pow(a: Value, b: Value) -> Result<Value, String> {
+    let integer_exponent = match &b {
+        Value::Int(e) => e.to_i64(),
+        Value::Float(f) if f.fract() == 0.0 && f.abs() < 1e6 => Some(*f as i64),
+        _ => None,
+    };
+    match (a, integer_exponent) {
+        (Value::Int(x), Some(e)) if e >= 0 => {
+            check_bits(x.bits().saturating_mul(e as u64))?;
+            Ok(Value::Int(x.pow(e as u32)))
+        }
+        (Value::Quantity { base, unit }, Some(e)) if e.abs() <= 16 => {
+            let unit = unit.powi(e as i32);
+            Ok(Value::quantity(base.powi(e as i32) / unit.factor, unit))
+        }
+        (a, _) => match (a.number(), b.number()) {
+            (Some(x), Some(y)) => Ok(Value::Float(x.powf(y))),
+            _ => Err(format!("Cannot raise {} to {}", a.kind(), b.kind())),
+        },
+    }
+}
+
+fn negate(value: Value) -> Result<Value, String> {
+    match value {
+        Value::Int(i) => Ok(Value::Int(-i)),
+        Value::Float(f) => Ok(Value::Float(-f)),
+        q @ Value::Quantity { .. } => scale(q, -1.0),
+        Value::Date(_) => Err("Cannot negate a date".to_string()),
+    }
+}
+
+fn factorial(value: Value) -> Result<Value, String> {
+    let n = match &value {
+        Value::Int(i) if !i.is_negative() => i.to_u64().unwrap_or(u64::MAX),
+        _ => return Err("Factorial needs a non-negative integer".to_string()),
+    };
+    if n > MAX_FACTORIAL {
+        return Err(format!("Factorial is limited to n <= {MAX_FACTORIAL}"));
+    }
+    Ok(Value::Int((2..=n).fold(BigInt::one(), |acc, k| acc * k)))
+}
+
+fn gcd(mut a: BigInt, mut b: BigInt) -> BigInt {
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a.abs()
+}
+
+fn call_function(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    let arity = |n: usize| -> Result<(), String> {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(format!("{name}() takes {n} argument(s)"))
+        }
+    };
+    let float = |v: &Value| -> Result<f64, String> {
+        v.number()
+            .ok_or_else(|| format!("{name}() needs a number, got {}", v.kind()))
+    };
+
+    match name {
+        "gcd" | "lcm" => {
+            arity(2)?;
+            let (Value::Int(a), Value::Int(b)) = (&args[0], &args[1]) else {
+                return Err(format!("{name}() needs integers"));
+            };
+            let g = gcd(a.clone(), b.clone());
+            if name == "gcd" {
+                return Ok(Value::Int(g));
+            }
+            if g.is_zero() {
+                return Ok(Value::Int(BigInt::zero()));
+            }
+            Ok(Value::Int((a * b).abs() / g))
+        }
+        "fact" | "factorial" => {
+            arity(1)?;
+            factorial(args.into_iter().next().expect("one argument"))
+        }
+        "abs" => {
+            arity(1)?;
+            match args.into_iter().next().expect("one argument") {
+                Value::Int(i) => Ok(Value::Int(i.abs())),
+                Value::Float(f) => Ok(Value::Float(f.abs())),
+                Value::Quantity { base, unit } if base - unit.offset < 0.0 => {
+                    negate(Value::Quantity { base, unit })
+                }
+                other => Ok(other),
+            }
+        }
+        "min" | "max" => {
+            if args.is_empty() {
+                return Err(format!("{name}() needs at least one argument"));
+            }
+            let mut best: Option<(f64, Value)> = None;
+            for arg in args {
+                let key = match &arg {
+                    Value::Quantity { base, .. } => *base,
+                    Value::Date(d) => d.num_days_from_ce() as f64,
+                    other => float(other)?,
+                };
+                let better = match &best {
+                    None => true,
+                    Some((k, _)) => (name == "min" && key < *k) || (name == "max" && key > *k),
+                };
+                if better {
+                    best = Some((key, arg));
+                }
+            }
+            Ok(best.expect("non-empty").1)
+        }
+        "round" | "floor" | "ceil" | "trunc" => {
+            if args.is_empty() || args.len() > 2 {
+                return Err(format!("{name}() takes 1 or 2 arguments"));
+            }
+            let digits = match args.get(1) {
+                Some(d) => float(d)? as i32,
+                None => 0,
+            };
+            if let Value::Int(i) = &args[0] {
+                return Ok(Value::Int(i.clone()));
+            }
+            let x = float(&args[0])?;
+            let factor = 10f64.powi(digits);
+            let scaled = x * factor;
+            let rounded = match name {
+                "round" => scaled.round(),
+                "floor" => scaled.floor(),
+                "ceil" => scaled.ceil(),
+                _ => scaled.trunc(),
+            } / factor;
+            if digits <= 0 && rounded.abs() < 9.0e15 {
+                Ok(Value::Int(BigInt::from(rounded as i64)))
+            } else {
+                Ok(Value::Float(rounded))
+            }
+        }
+        "log" => {
+            if args.is_empty() || args.len() > 2 {
+                return Err("log() takes 1 or 2 arguments".to_string());
+            }
+            let x = float(&args[0])?;
+            match args.get(1) {
+                Some(base) => Ok(Value::Float(x.ln() / float(base)?.ln())),
+                None => Ok(Value::Float(x.log10())),
+            }
+        }
+        _ => {
+            arity(1)?;
+            let x = float(&args[0])?;
+            let result = match name {
+                "sqrt" => x.sqrt(),
+                "cbrt" => x.cbrt(),
+                "ln" => x.ln(),
+                "log2" => x.log2(),
+                "log10" => x.log10(),
+                "exp" => x.exp(),
+                "sin" => x.sin(),
+                "cos" => x.cos(),
+                "tan" => x.tan(),
+                "asin" => x.asin(),
+                "acos" => x.acos(),
+                "atan" => x.atan(),
+                "sinh" => x.sinh(),
+                "cosh" => x.cosh(),
+                "tanh" => x.tanh(),
+                "deg" => x.to_degrees(),
+                "rad" => x.to_radians(),
+                _ => return Err(format!("Unknown function: {name}")),
+            };
+            if result.is_nan() {
+                return Err(format!("{name}({}) is undefined", format_float(x)));
+            }
+            Ok(Value::Float(result))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Ident(String),
+    Date(NaiveDate),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            if i + 10 <= chars.len() {
+                let candidate: String = chars[i..i + 10].iter().collect();
+                if candidate.as_bytes()[4] == b'-'
+                    && candidate.as_bytes()[7] == b'-'
+                    && let Ok(date) = NaiveDate::parse_from_str(&candidate, "%Y-%m-%d")
+                {
+                    tokens.push(Token::Date(date));
+                    i += 10;
+                    continue;
+                }
+            }
+
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == '_')
+            {
+                i += 1;
+            }
+            if i < chars.len()
+                && (chars[i] == 'e' || chars[i] == 'E')
+                && chars.get(i + 1).is_some_and(|n| {
+                    n.is_ascii_digit()
+                        || ((*n == '-' || *n == '+')
+                            && chars.get(i + 2).is_some_and(|d| d.is_ascii_digit()))
+                })
+            {
+                i += 2;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            tokens.push(Token::Number(text));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' || c == '°' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '°')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        let token = match c {
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                i += 1;
+                Token::Op('^')
+            }
+            '+' | '-' | '*' | '/' | '^' | '%' | '!' => Token::Op(c),
+            '×' => Token::Op('*'),
+            '÷' => Token::Op('/'),
+            '−' => Token::Op('-'),
+            _ => return Err(format!("Unexpected character: '{c}'")),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_op(&self, op: char) -> bool {
+        self.peek() == Some(&Token::Op(op))
+    }
+
+    fn is_unit_at(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some(Token::Ident(name)) => {
+                Unit::lookup(name).is_some() && self.peek_at(offset + 1) != Some(&Token::LParen)
+            }
+            _ => false,
+        }
+    }
+
+    fn starts_operand_at(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some(Token::Ident(name)) => name != "of",
+            Some(Token::Number(_) | Token::Date(_) | Token::LParen) => true,
+            _ => false,
+        }
+    }
+
+    fn parse(&mut self) -> Result<Value, String> {
+        let value = self.parse_expr()?;
+        match self.peek() {
+            None => Ok(value),
+            Some(token) => Err(format!("Unexpected {token:?}")),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Value, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        let mut value = self.parse_additive()?;
+
+        if let Some(Token::Ident(word)) = self.peek()
+            && matches!(word.as_str(), "to" | "in" | "as" | "into")
+            && self.is_unit_at(1)
+        {
+            self.pos += 1;
+            let target = self.parse_unit()?;
+            value = match value {
+                Value::Quantity { base, unit } if unit.dim == target.dim => {
+                    Value::Quantity { base, unit: target }
+                }
+                Value::Quantity { unit, .. } => {
+                    return Err(format!("Cannot convert {} to {}", unit.name, target.name));
+                }
+                other => Value::quantity(other.number().ok_or("Cannot convert a date")?, target),
+            };
+        }
+
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn parse_additive(&mut self) -> Result<Value, String> {
+        let mut value = self.parse_multiplicative()?;
+        loop {
+            if self.is_op('+') {
+                self.pos += 1;
+                value = add(value, self.parse_multiplicative()?, 1.0)?;
+            } else if self.is_op('-') {
+                self.pos += 1;
+                value = add(value, self.parse_multiplicative()?, -1.0)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Value, String> {
+        let mut value = self.parse_unary()?;
+        loop {
+            if self.is_op('*') {
+                self.pos += 1;
+                value = mul(value, self.parse_unary()?)?;
+            } else if self.is_op('/') {
+                self.pos += 1;
+                value = div(value, self.parse_unary()?)?;
+            } else if self.is_op('%') && self.starts_operand_at(1) {
+                self.pos += 1;
+                value = rem(value, self.parse_unary()?)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Value, String> {
+        if self.is_op('-') {
+            self.pos += 1;
+            return negate(self.parse_unary()?);
+        }
+        if self.is_op('+') {
+            self.pos += 1;
+            return self.parse_unary();
+        }
+        self.parse_power()
+    }
+
+    fn parse_power(&mut self) -> Result<Value, String> {
+        let base = self.parse_postfix()?;
+        if self.is_op('^') {
+            self.pos += 1;
+            let exponent = self.parse_unary()?;
+            return pow(base, exponent);
+        }
+        Ok(base)
+    }
+
+    fn parse_postfix(&mut self) -> Result<Value, String> {
+        let mut value = self.parse_primary()?;
+
+        if value.number().is_some() && self.is_unit_at(0) {
+            let is_conversion =
+                matches!(self.peek(), Some(Token::Ident(w)) if w == "in") && self.is_unit_at(1);
+            if !is_conversion {
+                let unit = self.parse_unit()?;
+                value = Value::quantity(value.number().unwrap_or(0.0), unit);
+
+                // "1 h 30 min", "5 ft 11 in"
+                while matches!(self.peek(), Some(Token::Number(_))) && self.is_unit_at(1) {
+                    let amount = self.parse_primary()?;
+                    let unit = self.parse_unit()?;
+                    value = add(
+                        value,
+                        Value::quantity(amount.number().unwrap_or(0.0), unit),
+                        1.0,
+                    )?;
+                }
+            }
+        }
+
+        loop {
+            if self.is_op('!') {
+                self.pos += 1;
+                value = factorial(value)?;
+            } else if self.is_op('%') && !self.starts_operand_at(1) {
+                self.pos += 1;
+                value = div(value, Value::Int(BigInt::from(100)))?;
+                if matches!(self.peek(), Some(Token::Ident(w)) if w == "of") {
+                    self.pos += 1;
+                    value = mul(value, self.parse_power()?)?;
+                }
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_unit(&mut self) -> Result<Unit, String> {
+        let mut unit = match self.next() {
+            Some(Token::Ident(name)) => {
+                Unit::lookup(&name).ok_or_else(|| format!("Unknown unit: {name}"))?
+            }
+            other => return Err(format!("Expected a unit, got {other:?}")),
+        };
+
+        loop {
+            if self.is_op('^') {
+                let negative = self.peek_at(1) == Some(&Token::Op('-'));
+                let index = if negative { 2 } else { 1 };
+                let Some(Token::Number(n)) = self.peek_at(index) else {
+                    return Ok(unit);
+                };
+                let n: i32 = n.parse().map_err(|_| format!("Invalid unit power: {n}"))?;
+                self.pos += index + 1;
+                unit = unit.powi(if negative { -n } else { n });
+            } else if (self.is_op('/') || self.is_op('*')) && self.is_unit_at(1) {
+                let op = if self.is_op('/') { "/" } else { "*" };
+                self.pos += 1;
+                let other = self.parse_unit_atom()?;
+                unit = unit.combine(&other, if op == "/" { -1 } else { 1 }, op);
+            } else {
+                return Ok(unit);
+            }
+        }
+    }
+
+    fn parse_unit_atom(&mut self) -> Result<Unit, String> {
+        let mut unit = match self.next() {
+            Some(Token::Ident(name)) => {
+                Unit::lookup(&name).ok_or_else(|| format!("Unknown unit: {name}"))?
+            }
+            other => return Err(format!("Expected a unit, got {other:?}")),
+        };
+        if self.is_op('^')
+            && let Some(Token::Number(n)) = self.peek_at(1)
+            && let Ok(n) = n.parse::<i32>()
+        {
+            self.pos += 2;
+            unit = unit.powi(n);
+        }
+        Ok(unit)
+    }
+
+    fn parse_primary(&mut self) -> Result<Value, String> {
+        match self.next() {
+            Some(Token::Number(text)) => {
+                if text.contains(['.', 'e', 'E']) {
+                    text.parse::<f64>()
+                        .map(Value::Float)
+                        .map_err(|_| format!("Invalid number: {text}"))
+                } else {
+                    text.parse::<BigInt>()
+                        .map(Value::Int)
+                        .map_err(|_| format!("Invalid number: {text}"))
+                }
+            }
+            Some(Token::Date(date)) => Ok(Value::Date(date)),
+            Some(Token::LParen) => {
+                let value = self.parse_expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    if self.peek() != Some(&Token::RParen) {
+                        loop {
+                            args.push(self.parse_expr()?);
+                            match self.next() {
+                                Some(Token::Comma) => continue,
+                                Some(Token::RParen) => break,
+                                _ => return Err(format!("Missing ')' after {name}(")),
+                            }
+                        }
+                    } else {
+                        self.pos += 1;
+                    }
+                    return call_function(&name.to_lowercase(), args);
+                }
+
+                let today = Local::now().date_naive();
+                match name.as_str() {
+                    "pi" | "π" => Ok(Value::Float(std::f64::consts::PI)),
+                    "e" => Ok(Value::Float(std::f64::consts::E)),
+                    "tau" => Ok(Value::Float(std::f64::consts::TAU)),
+                    "today" | "now" => Ok(Value::Date(today)),
+                    "tomorrow" => Ok(Value::Date(today + chrono::Duration::days(1))),
+                    "yesterday" => Ok(Value::Date(today - chrono::Duration::days(1))),
+                    _ => match Unit::lookup(&name) {
+                        Some(_) => {
+                            self.pos -= 1;
+                            Ok(Value::quantity(1.0, self.parse_unit()?))
+                        }
+                        None => Err(format!("Unknown name: {name}")),
+                    },
+                }
+            }
+            Some(token) => Err(format!("Unexpected {token:?}")),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn evaluate(expression: &str) -> Result<String, String> {
+    if expression.chars().count() > MAX_INPUT_CHARS {
+        return Err(format!(
+            "Expression is longer than {MAX_INPUT_CHARS} characters"
+        ));
+    }
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err("Expression is empty".to_string());
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    parser.parse().map(|value| value.display())
+}
+
+pub struct CalcTool;
+
+impl CalcTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CalcTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for CalcTool {
+    fn name(&self) -> &str {
+        "calc"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluate a math expression exactly instead of estimating or calling bc. Supports big integers (2^200, 50!), \
+         functions (sqrt, ln, log, sin, round, gcd, min, max), percentages (15% of 80), units with conversion \
+         (5 km to mi, 3 GiB in MB, 72 degF to degC, 60 mph * 2 h) and dates (2025-03-01 + 90 days, 2025-12-25 - today)"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "Expression to evaluate; separate several with ';'"
+                }
+            },
+            "required": ["expression"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let expression = extract_string_arg(&args, "expression")?;
+        let parts: Vec<&str> = expression
+            .split([';', '\n'])
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.is_empty() {
+            return Ok(ToolResult::error("Expression is empty"));
+        }
+
+        let mut lines = Vec::with_capacity(parts.len());
+        for part in parts {
+            match evaluate(part) {
+                Ok(result) => lines.push(format!("{part} = {result}")),
+                Err(e) => return Ok(ToolResult::error(format!("{part}: {e}"))),
+            }
+        }
+        Ok(ToolResult::success(lines.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_integer_arithmetic() {
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), "14");
+        assert_eq!(evaluate("-2^2").unwrap(), "-4");
+        assert_eq!(
+            evaluate("2^100").unwrap(),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(evaluate("25!").unwrap(), "15511210043330985984000000");
+        assert_eq!(evaluate("7 / 2").unwrap(), "3.5");
+        assert_eq!(evaluate("0.1 + 0.2").unwrap(), "0.3");
+        assert_eq!(evaluate("15% of 80").unwrap(), "12");
+        assert_eq!(evaluate("17 % 5").unwrap(), "2");
+        assert_eq!(evaluate("gcd(48, 18)").unwrap(), "6");
+        assert!(evaluate("1 / 0").is_err());
+    }
+
+    #[test]
+    fn unit_conversion() {
+        assert_eq!(evaluate("5 km to m").unwrap(), "5000 m");
+        assert_eq!(evaluate("1 mi in km").unwrap(), "1.609344 km");
+        assert_eq!(evaluate("212 degF to degC").unwrap(), "100 degC");
+        assert_eq!(evaluate("1 GiB to MB").unwrap(), "1073.741824 MB");
+        assert_eq!(evaluate("60 mph * 2 h to km").unwrap(), "193.12128 km");
+        assert_eq!(evaluate("1 h 30 min to min").unwrap(), "90 min");
+        assert_eq!(evaluate("5 ft 11 in to cm").unwrap(), "180.34 cm");
+        assert!(evaluate("5 kg to m").is_err());
+    }
+
+    #[test]
+    fn date_arithmetic() {
+        assert_eq!(
+            evaluate("2024-01-31 + 1 month").unwrap(),
+            "2024-02-29 (Thursday)"
+        );
+        assert_eq!(
+            evaluate("2024-03-01 + 2 weeks").unwrap(),
+            "2024-03-15 (Friday)"
+        );
+        assert_eq!(evaluate("2025-01-01 - 2024-01-01").unwrap(), "366 days");
+        assert!(evaluate("2024-03-01 + 5 kg").is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::config::CommandToolConfig;
+use crate::plugins::manifest::is_valid_tool_name;
+use crate::tools::Sandbox;
+use crate::traits::{Tool, ToolResult};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+pub const WORKSPACE_TOOLS_FILE: &str = "tools.toml";
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const MAX_TIMEOUT_SECS: u64 = 3600;
+const MAX_OUTPUT_CHARS: usize = 50_000;
+
+#[derive(Deserialize)]
+struct ToolsFile {
+    #[serde(default)]
+    tools: Vec<CommandToolConfig>,
+}
+
+pub struct CommandTool {
+    def: CommandToolConfig,
+    workspace: PathBuf,
+    extra_env: Vec<(String, String)>,
+    sandbox: Option<Sandbox>,
+}
+
+impl CommandTool {
+    pub fn new(def: CommandToolConfig, workspace: impl AsRef<Path>) -> Result<Self> {
+        if !is_valid_tool_name(&def.name) {
+            bail!(
+                "Invalid tool name '{}': use letters, digits, '_' and '-'",
+                def.name
+            );
+        }
+        if def.command.trim().is_empty() {
+            bail!("Tool '{}' has an empty command", def.name);
+        }
+        if def.parameters.get("type").and_then(|t| t.as_str()) != Some("object") {
+            bail!("Tool '{}' parameters must be an object schema", def.name);
+        }
+        Ok(Self {
+            def,
+            workspace: workspace.as_ref().to_path_buf(),
+            extra_env: Vec::new(),
+            sandbox: None,
+        })
+    }
+
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Runs the command in `sandbox`. Where no sandbox is available, the tool counts as
+    /// mutating and asks before every run, whatever the approval mode.
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
+}
+
+/// Tools from `config.tools` followed by the workspace `tools.toml`; invalid or
+/// duplicate definitions are logged and skipped.
+pub fn load_command_tools(configured: &[CommandToolConfig], workspace: &Path) -> Vec<CommandTool> {
+    let mut defs = configured.to_vec();
+    match load_workspace_tools(workspace) {
+        Ok(workspace_defs) => defs.extend(workspace_defs),
+        Err(e) => tracing::warn!("{:#}", e),
+    }
+
+    let mut tools: Vec<CommandTool> = Vec::new();
+    for def in defs {
+        if tools.iter().any(|t| t.def.name == def.name) {
+            tracing::warn!("Skipping duplicate command tool '{}'", def.name);
+            continue;
+        }
+        match CommandTool::new(def, workspace) {
+            Ok(tool) => tools.push(tool),
+            Err(e) => tracing::warn!("Skipping command tool: {}", e),
+        }
+    }
+    tools
+}
+
+fn load_workspace_tools(workspace: &Path) -> Result<Vec<CommandToolConfig>> {
+    let path = workspace.join(WORKSPACE_TOOLS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: ToolsFile =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(file.tools)
+}
+
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn arg_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Replaces `{param}` placeholders with shell-quoted argument values. `${VAR}` is left
+/// for the shell, as are braces that don't name a parameter.
+fn render_command(template: &str, args: &serde_json::Value, schema: &serde_json::Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..name_len];
+        let closed = after[name_len..].starts_with('}');
+        let is_param = args.get(name).is_some() || schema["properties"].get(name).is_some();
+
+        if !name.is_empty() && closed && is_param && !out.ends_with('$') {
+            let value = args.get(name).map(arg_to_string).unwrap_or_default();
+            out.push_str(&shell_quote(&value));
+            rest = &after[name_len + 1..];
+        } else {
+            out.push('{');
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn env_vars(args: &serde_json::Value) -> Vec<(String, String)> {
+    let mut vars = vec![("DINOE_ARGS".to_string(), args.to_string())];
+    if let Some(map) = args.as_object() {
+        for (key, value) in map {
+            if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                vars.push((
+                    format!("DINOE_ARG_{}", key.to_ascii_uppercase()),
+                    arg_to_string(value),
+                ));
+            }
+        }
+    }
+    vars
+}
+
+fn truncate(text: String) -> String {
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text;
+    }
+    let truncated: String = text.chars().take(MAX_OUTPUT_CHARS).collect();
+    format!("{truncated}\n[... output truncated at {MAX_OUTPUT_CHARS} characters]")
+}
+
+#[async_trait]
+impl Tool for CommandTool {
+    fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    fn description(&self) -> &str {
+        &self.def.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.def.parameters.clone()
+    }
+
+    fn is_mutating(&self) -> bool {
+        self.def.mutating || self.always_needs_approval()
+    }
+
+    fn always_needs_approval(&self) -> bool {
+        self.sandbox.is_some() && !Sandbox::available()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let command = render_command(&self.def.command, &args, &self.def.parameters);
+        let timeout = Duration::from_secs(
+            self.def
+                .timeout_secs
+                .unwrap_or(DEFAULT_TIMEOUT_SECS)
+                .clamp(1, MAX_TIMEOUT_SECS),
+        );
+
+        let mut child = match &self.sandbox {
+            Some(sandbox) => sandbox.command(&self.workspace, &command),
+            None => {
+                let mut child = Command::new("sh");
+                child.arg("-c").arg(&command).current_dir(&self.workspace);
+                child
+            }
+        };
+        let mut child = match child
+            .envs(env_vars(&args))
+            .envs(self.extra_env.iter().cloned())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to run '{}': {}",
+                    self.def.name, e
+                )));
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // Commands that ignore stdin may exit before reading it; that is not an error.
+            let _ = stdin.write_all(args.to_string().as_bytes()).await;
+        }
+
+        let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to run '{}': {}",
+                    self.def.name, e
+                )));
+            }
+            Err(_) => {
+                return Ok(ToolResult::error(format!(
+                    "'{}' timed out after {}s",
+                    self.def.name,
+                    timeout.as_secs()
+                )));
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if output.status.success() {
+            let result = if stdout.is_empty() { stderr } else { stdout };
+            Ok(ToolResult::success(truncate(result)))
+        } else {
+            let error = if stderr.trim().is_empty() {
+                format!("Command failed with status: {}\n{}", output.status, stdout)
+            } else {
+                stderr
+            };
+            Ok(ToolResult::error(truncate(error)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn render_command_quotes_params_and_leaves_shell_syntax() {
+        let schema = json!({ "type": "object", "properties": { "id": {}, "flag": {} } });
+        let rendered = render_command(
+            "lookup --id {id} --flag {flag} --home ${HOME} {other}",
+            &json!({ "id": "x'; rm -rf /" }),
+            &schema,
+        );
+        assert_eq!(
+            rendered,
+            r"lookup --id 'x'\''; rm -rf /' --flag '' --home ${HOME} {other}"
+        );
+    }
+
+    #[tokio::test]
+    async fn workspace_tool_receives_args_via_template_env_and_stdin() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(WORKSPACE_TOOLS_FILE),
+            r#"
+[[tools]]
+name = "greet"
+description = "Greet someone"
+command = "printf '%s|%s|' {name} \"$DINOE_ARG_NAME\"; cat"
+mutating = false
+
+[tools.parameters]
+type = "object"
+required = ["name"]
+properties = { name = { type = "string" } }
+"#,
+        )
+        .unwrap();
+
+        let tools = load_command_tools(&[], dir.path());
+        assert_eq!(tools.len(), 1);
+        assert!(!tools[0].is_mutating());
+
+        let result = tools[0].execute(json!({ "name": "Ada" })).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, r#"Ada|Ada|{"name":"Ada"}"#);
+    }
+}
@@ -0,0 +1,745 @@
+use crate::tools::security::validate_workspace_path;
+use crate::tools::{
+    extract_string_arg, extract_string_arg_opt, extract_usize_arg_opt, get_global_rate_limiter,
+};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::{Map, Value, json};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+const MAX_FILE_BYTES: u64 = 100 * 1024 * 1024;
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+const MAX_CELL_CHARS: usize = 200;
+const SAMPLE_VALUES: usize = 3;
+
+struct Dataset {
+    columns: Vec<String>,
+    rows: Vec<Map<String, Value>>,
+}
+
+fn parse_cell(raw: &str) -> Value {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(i) = trimmed.parse::<i64>() {
+        return json!(i);
+    }
+    if let Ok(f) = trimmed.parse::<f64>()
+        && f.is_finite()
+    {
+        return json!(f);
+    }
+    match trimmed.to_lowercase().as_str() {
+        "true" => json!(true),
+        "false" => json!(false),
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+fn load_csv(content: &str, delimiter: u8) -> anyhow::Result<Dataset> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let columns: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let row = columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                (
+                    col.clone(),
+                    record.get(i).map(parse_cell).unwrap_or(Value::Null),
+                )
+            })
+            .collect();
+        rows.push(row);
+    }
+    Ok(Dataset { columns, rows })
+}
+
+fn load_json_values(values: Vec<Value>) -> Dataset {
+    let mut columns = Vec::new();
+    let mut seen = BTreeSet::new();
+    let rows = values
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(map) => map,
+            other => Map::from_iter([("value".to_string(), other)]),
+        })
+        .inspect(|row| {
+            for key in row.keys() {
+                if seen.insert(key.clone()) {
+                    columns.push(key.clone());
+                }
+            }
+        })
+        .collect();
+    Dataset { columns, rows }
+}
+
+fn load_json(content: &str) -> anyhow::Result<Dataset> {
+    let value: Value = serde_json::from_str(content)?;
+    let values = match value {
+        Value::Array(items) => items,
+        Value::Object(map) => match map.iter().find(|(_, v)| v.is_array()) {
+            Some((_, Value::Array(items))) => items.clone(),
+            _ => vec![Value::Object(map)],
+        },
+        other => vec![other],
+    };
+    Ok(load_json_values(values))
+}
+
+fn load_json_lines(content: &str) -> anyhow::Result<Dataset> {
+    let values = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Value>, _>>()?;
+    Ok(load_json_values(values))
+}
+
+fn load_dataset(path: &Path, content: &str) -> anyhow::Result<Dataset> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "csv" => load_csv(content, b','),
+        "tsv" | "tab" => load_csv(content, b'\t'),
+        "json" => load_json(content),
+        "jsonl" | "ndjson" => load_json_lines(content),
+        _ => anyhow::bail!("Unsupported file type '.{extension}'. Use csv, tsv, json or jsonl"),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => match (a.is_null(), b.is_null()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            _ => as_text(a).cmp(&as_text(b)),
+        },
+    }
+}
+
+fn truncate_cell(value: &Value) -> Value {
+    match value {
+        Value::String(s) if s.chars().count() > MAX_CELL_CHARS => Value::String(format!(
+            "{}…",
+            s.chars().take(MAX_CELL_CHARS).collect::<String>()
+        )),
+        Value::Array(_) | Value::Object(_) => {
+            let text = value.to_string();
+            if text.chars().count() > MAX_CELL_CHARS {
+                Value::String(format!(
+                    "{}…",
+                    text.chars().take(MAX_CELL_CHARS).collect::<String>()
+                ))
+            } else {
+                value.clone()
+            }
+        }
+        other => other.clone(),
+    }
+}
+
+fn matches_filter(row: &Map<String, Value>, filter: &Value) -> Result<bool, String> {
+    let column = filter
+        .get("column")
+        .and_then(|v| v.as_str())
+        .ok_or("Each filter needs a 'column'")?;
+    let op = filter.get("op").and_then(|v| v.as_str()).unwrap_or("eq");
+    let expected = filter.get("value").unwrap_or(&Value::Null);
+    let actual = row.get(column).unwrap_or(&Value::Null);
+
+    Ok(match op {
+        "eq" | "==" | "=" => compare_values(actual, expected) == Ordering::Equal,
+        "ne" | "!=" => compare_values(actual, expected) != Ordering::Equal,
+        "gt" | ">" => !actual.is_null() && compare_values(actual, expected) == Ordering::Greater,
+        "gte" | ">=" => !actual.is_null() && compare_values(actual, expected) != Ordering::Less,
+        "lt" | "<" => !actual.is_null() && compare_values(actual, expected) == Ordering::Less,
+        "lte" | "<=" => !actual.is_null() && compare_values(actual, expected) != Ordering::Greater,
+        "contains" => as_text(actual)
+            .to_lowercase()
+            .contains(&as_text(expected).to_lowercase()),
+        "starts_with" => as_text(actual).starts_with(&as_text(expected)),
+        "is_null" => actual.is_null(),
+        "not_null" => !actual.is_null(),
+        _ => return Err(format!("Unknown filter op: {op}")),
+    })
+}
+
+fn apply_filters<'a>(
+    rows: &'a [Map<String, Value>],
+    filters: &[Value],
+) -> Result<Vec<&'a Map<String, Value>>, String> {
+    let mut matched = Vec::new();
+    for row in rows {
+        let mut keep = true;
+        for filter in filters {
+            if !matches_filter(row, filter)? {
+                keep = false;
+                break;
+            }
+        }
+        if keep {
+            matched.push(row);
+        }
+    }
+    Ok(matched)
+}
+
+fn project(row: &Map<String, Value>, columns: &[String]) -> Value {
+    Value::Object(
+        columns
+            .iter()
+            .map(|c| (c.clone(), truncate_cell(row.get(c).unwrap_or(&Value::Null))))
+            .collect(),
+    )
+}
+
+fn schema(dataset: &Dataset) -> Value {
+    let columns: Vec<Value> = dataset
+        .columns
+        .iter()
+        .map(|column| {
+            let mut types = BTreeSet::new();
+            let mut non_null = 0;
+            let mut samples = Vec::new();
+            for value in dataset.rows.iter().filter_map(|r| r.get(column)) {
+                if value.is_null() {
+                    continue;
+                }
+                non_null += 1;
+                types.insert(type_name(value));
+                if samples.len() < SAMPLE_VALUES && !samples.contains(value) {
+                    samples.push(value.clone());
+                }
+            }
+            let kind = match types.len() {
+                0 => "null",
+                1 => types.iter().next().copied().unwrap_or("null"),
+                _ => "mixed",
+            };
+            json!({
+                "name": column,
+                "type": kind,
+                "non_null": non_null,
+                "samples": samples.iter().map(truncate_cell).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    json!({ "rows": dataset.rows.len(), "columns": columns })
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: usize,
+    sum: f64,
+    numeric: usize,
+    min: Option<Value>,
+    max: Option<Value>,
+    distinct: BTreeSet<String>,
+}
+
+impl Accumulator {
+    fn add(&mut self, value: &Value) {
+        if value.is_null() {
+            return;
+        }
+        self.count += 1;
+        if let Some(n) = as_number(value) {
+            self.sum += n;
+            self.numeric += 1;
+        }
+        if self
+            .min
+            .as_ref()
+            .is_none_or(|m| compare_values(value, m) == Ordering::Less)
+        {
+            self.min = Some(value.clone());
+        }
+        if self
+            .max
+            .as_ref()
+            .is_none_or(|m| compare_values(value, m) == Ordering::Greater)
+        {
+            self.max = Some(value.clone());
+        }
+        self.distinct.insert(as_text(value));
+    }
+
+    fn result(&self, func: &str, rows: usize) -> Value {
+        match func {
+            "count" => json!(rows),
+            "count_non_null" => json!(self.count),
+            "sum" => json!(self.sum),
+            "avg" | "mean" if self.numeric > 0 => json!(self.sum / self.numeric as f64),
+            "min" => self.min.clone().unwrap_or(Value::Null),
+            "max" => self.max.clone().unwrap_or(Value::Null),
+            "distinct" => json!(self.distinct.len()),
+            _ => Value::Null,
+        }
+    }
+}
+
+struct Aggregation {
+    column: Option<String>,
+    func: String,
+}
+
+impl Aggregation {
+    fn parse(spec: &Value) -> Result<Self, String> {
+        let func = spec
+            .get("func")
+            .and_then(|v| v.as_str())
+            .ok_or("Each aggregation needs a 'func'")?
+            .to_lowercase();
+        if !matches!(
+            func.as_str(),
+            "count" | "count_non_null" | "sum" | "avg" | "mean" | "min" | "max" | "distinct"
+        ) {
+            return Err(format!("Unknown aggregation func: {func}"));
+        }
+        let column = spec
+            .get("column")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if column.is_none() && func != "count" {
+            return Err(format!("Aggregation '{func}' needs a 'column'"));
+        }
+        Ok(Self { column, func })
+    }
+
+    fn output_name(&self) -> String {
+        match &self.column {
+            Some(column) => format!("{}_{}", self.func, column),
+            None => self.func.clone(),
+        }
+    }
+}
+
+fn aggregate(
+    rows: &[&Map<String, Value>],
+    group_by: &[String],
+    aggregations: &[Aggregation],
+) -> Vec<Map<String, Value>> {
+    let mut groups: BTreeMap<Vec<String>, (Vec<Value>, usize, Vec<Accumulator>)> = BTreeMap::new();
+
+    for row in rows {
+        let key_values: Vec<Value> = group_by
+            .iter()
+            .map(|c| row.get(c).cloned().unwrap_or(Value::Null))
+            .collect();
+        let key = key_values.iter().map(as_text).collect();
+        let entry = groups.entry(key).or_insert_with(|| {
+            (
+                key_values,
+                0,
+                aggregations
+                    .iter()
+                    .map(|_| Accumulator::default())
+                    .collect(),
+            )
+        });
+        entry.1 += 1;
+        for (agg, acc) in aggregations.iter().zip(entry.2.iter_mut()) {
+            if let Some(column) = &agg.column {
+                acc.add(row.get(column).unwrap_or(&Value::Null));
+            }
+        }
+    }
+
+    groups
+        .into_values()
+        .map(|(key_values, count, accumulators)| {
+            let mut out: Map<String, Value> = group_by.iter().cloned().zip(key_values).collect();
+            for (agg, acc) in aggregations.iter().zip(accumulators.iter()) {
+                out.insert(agg.output_name(), acc.result(&agg.func, count));
+            }
+            out
+        })
+        .collect()
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    match args.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn sort_rows<T: std::borrow::Borrow<Map<String, Value>>>(
+    rows: &mut [T],
+    column: &str,
+    descending: bool,
+) {
+    rows.sort_by(|a, b| {
+        let ordering = compare_values(
+            a.borrow().get(column).unwrap_or(&Value::Null),
+            b.borrow().get(column).unwrap_or(&Value::Null),
+        );
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn run_query(dataset: &Dataset, args: &Value) -> Result<Value, String> {
+    let operation = extract_string_arg_opt(args, "operation", "schema");
+    let limit = extract_usize_arg_opt(args, "limit", DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let sort_by = args.get("sort_by").and_then(|v| v.as_str());
+    let descending = args
+        .get("descending")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let filters = args
+        .get("filters")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    if operation != "aggregate"
+        && let Some(missing) = string_list(args, "columns")
+            .into_iter()
+            .chain(sort_by.map(str::to_string))
+            .find(|c| !dataset.columns.contains(c))
+    {
+        return Err(format!(
+            "Unknown column '{missing}'. Available: {}",
+            dataset.columns.join(", ")
+        ));
+    }
+
+    match operation.as_str() {
+        "schema" => Ok(schema(dataset)),
+        "head" | "filter" => {
+            let mut rows = apply_filters(&dataset.rows, &filters)?;
+            if let Some(column) = sort_by {
+                sort_rows(&mut rows, column, descending);
+            }
+            let mut columns = string_list(args, "columns");
+            if columns.is_empty() {
+                columns = dataset.columns.clone();
+            }
+            let shown: Vec<Value> = rows
+                .iter()
+                .take(limit)
+                .map(|r| project(r, &columns))
+                .collect();
+            Ok(json!({
+                "matched": rows.len(),
+                "returned": shown.len(),
+                "truncated": rows.len() > shown.len(),
+                "rows": shown,
+            }))
+        }
+        "aggregate" => {
+            let group_by = string_list(args, "group_by");
+            if let Some(missing) = group_by.iter().find(|c| !dataset.columns.contains(c)) {
+                return Err(format!(
+                    "Unknown column '{missing}'. Available: {}",
+                    dataset.columns.join(", ")
+                ));
+            }
+            let aggregations = match args.get("aggregations").and_then(|v| v.as_array()) {
+                Some(specs) if !specs.is_empty() => specs
+                    .iter()
+                    .map(Aggregation::parse)
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => vec![Aggregation {
+                    column: None,
+                    func: "count".into(),
+                }],
+            };
+            if let Some(missing) = aggregations
+                .iter()
+                .filter_map(|a| a.column.as_ref())
+                .find(|c| !dataset.columns.contains(c))
+            {
+                return Err(format!(
+                    "Unknown column '{missing}'. Available: {}",
+                    dataset.columns.join(", ")
+                ));
+            }
+
+            let rows = apply_filters(&dataset.rows, &filters)?;
+            let mut groups = aggregate(&rows, &group_by, &aggregations);
+            if let Some(column) = sort_by {
+                sort_rows(&mut groups, column, descending);
+            }
+            let total = groups.len();
+            groups.truncate(limit);
+            Ok(json!({
+                "matched": rows.len(),
+                "groups": total,
+                "truncated": total > groups.len(),
+                "results": groups,
+            }))
+        }
+        other => Err(format!(
+            "Unknown operation: {other}. Use schema, head, filter or aggregate"
+        )),
+    }
+}
+
+pub struct DataQueryTool {
+    workspace: std::path::PathBuf,
+    rate_limiter: std::sync::Arc<crate::tools::security::RateLimiter>,
+}
+
+impl DataQueryTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DataQueryTool {
+    fn name(&self) -> &str {
+        "data_query"
+    }
+
+    fn description(&self) -> &str {
+        "Query a CSV, TSV, JSON or JSONL file in the workspace without reading it whole: \
+         'schema' lists columns and types, 'head'/'filter' return matching rows, 'aggregate' computes \
+         count/sum/avg/min/max/distinct with optional group_by. Output is capped to 'limit' rows"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the data file within the workspace"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["schema", "head", "filter", "aggregate"],
+                    "description": "Query to run (default: schema)"
+                },
+                "columns": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Columns to return for head/filter (default: all)"
+                },
+                "filters": {
+                    "type": "array",
+                    "description": "Row conditions combined with AND",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "column": { "type": "string" },
+                            "op": {
+                                "type": "string",
+                                "enum": ["eq", "ne", "gt", "gte", "lt", "lte", "contains", "starts_with", "is_null", "not_null"]
+                            },
+                            "value": {}
+                        },
+                        "required": ["column", "op"]
+                    }
+                },
+                "group_by": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Columns to group by for aggregate"
+                },
+                "aggregations": {
+                    "type": "array",
+                    "description": "Aggregations for aggregate (default: count). Output keys are func_column",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "func": {
+                                "type": "string",
+                                "enum": ["count", "count_non_null", "sum", "avg", "min", "max", "distinct"]
+                            },
+                            "column": { "type": "string" }
+                        },
+                        "required": ["func"]
+                    }
+                },
+                "sort_by": {
+                    "type": "string",
+                    "description": "Column (or aggregate output key) to sort by"
+                },
+                "descending": {
+                    "type": "boolean",
+                    "description": "Sort descending (default: false)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum rows or groups to return (default: 20, max: 200)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many file reads. Please wait a moment.",
+            ));
+        }
+
+        let path = extract_string_arg(&args, "path")?;
+        let full_path = match validate_workspace_path(&path, &self.workspace) {
+            Ok(p) => p,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        match tokio::fs::metadata(&full_path).await {
+            Ok(meta) if meta.len() > MAX_FILE_BYTES => {
+                return Ok(ToolResult::error(format!(
+                    "File is too large to query ({} bytes, max {MAX_FILE_BYTES})",
+                    meta.len()
+                )));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(ToolResult::error(format!("Failed to read file: {e}"))),
+        }
+
+        let content = match tokio::fs::read_to_string(&full_path).await {
+            Ok(c) => c,
+            Err(e) => return Ok(ToolResult::error(format!("Failed to read file: {e}"))),
+        };
+
+        let dataset = match load_dataset(&full_path, &content) {
+            Ok(d) => d,
+            Err(e) => return Ok(ToolResult::error(format!("Failed to parse {path}: {e}"))),
+        };
+
+        match run_query(&dataset, &args) {
+            Ok(result) => Ok(ToolResult::success(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SALES: &str =
+        "region,product,amount\nnorth,apple,10\nsouth,pear,4.5\nnorth,pear,7\nwest,apple,\n";
+
+    #[test]
+    fn schema_infers_types_and_nulls() {
+        let dataset = load_csv(SALES, b',').unwrap();
+        let result = run_query(&dataset, &json!({"operation": "schema"})).unwrap();
+        assert_eq!(result["rows"], 4);
+        assert_eq!(result["columns"][2]["type"], "number");
+        assert_eq!(result["columns"][2]["non_null"], 3);
+    }
+
+    #[test]
+    fn filter_and_aggregate() {
+        let dataset = load_csv(SALES, b',').unwrap();
+        let result = run_query(
+            &dataset,
+            &json!({
+                "operation": "filter",
+                "filters": [{"column": "amount", "op": "gt", "value": 5}],
+                "columns": ["product"]
+            }),
+        )
+        .unwrap();
+        assert_eq!(result["matched"], 2);
+        assert_eq!(result["rows"][0], json!({"product": "apple"}));
+
+        let result = run_query(
+            &dataset,
+            &json!({
+                "operation": "aggregate",
+                "group_by": ["region"],
+                "aggregations": [{"func": "sum", "column": "amount"}, {"func": "count"}],
+                "sort_by": "sum_amount",
+                "descending": true,
+                "limit": 1
+            }),
+        )
+        .unwrap();
+        assert_eq!(result["groups"], 3);
+        assert_eq!(result["results"][0]["region"], "north");
+        assert_eq!(result["results"][0]["sum_amount"], 17.0);
+        assert_eq!(result["results"][0]["count"], 2);
+    }
+
+    #[tokio::test]
+    async fn queries_json_lines_file() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("events.jsonl"),
+            "{\"kind\":\"click\",\"ms\":12}\n{\"kind\":\"view\",\"ms\":30}\n",
+        )
+        .unwrap();
+
+        let tool = DataQueryTool::new(tmp.path());
+        let result = tool
+            .execute(json!({"path": "events.jsonl", "operation": "head", "limit": 1}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let parsed: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["truncated"], true);
+        assert_eq!(parsed["rows"][0]["kind"], "click");
+    }
+}
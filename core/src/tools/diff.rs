@@ -0,0 +1,248 @@
+use crate::tools::get_global_rate_limiter;
+use crate::tools::security::validate_workspace_path;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use similar::{ChangeTag, TextDiff};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_CONTEXT: usize = 3;
+const MAX_CONTEXT: usize = 20;
+const MAX_INPUT_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_OUTPUT_CHARS: usize = 50_000;
+
+pub struct DiffTool {
+    workspace: PathBuf,
+    rate_limiter: std::sync::Arc<crate::tools::security::RateLimiter>,
+}
+
+impl DiffTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+
+    /// Resolves one side of the diff from either a workspace file or inline content.
+    /// A file that does not exist yet diffs as empty, so proposed new files show up as additions.
+    async fn side(
+        &self,
+        args: &serde_json::Value,
+        path_key: &str,
+        content_key: &str,
+    ) -> Result<Option<(String, String)>, String> {
+        let path = args.get(path_key).and_then(|v| v.as_str());
+        let content = args.get(content_key).and_then(|v| v.as_str());
+
+        match (path, content) {
+            (Some(_), Some(_)) => Err(format!(
+                "Provide either '{path_key}' or '{content_key}', not both"
+            )),
+            (None, Some(content)) => Ok(Some((String::new(), content.to_string()))),
+            (None, None) => Ok(None),
+            (Some(path), None) => {
+                let full_path = validate_workspace_path(path, &self.workspace)?;
+                if !full_path.exists() {
+                    return Ok(Some((path.to_string(), String::new())));
+                }
+                let metadata = tokio::fs::metadata(&full_path)
+                    .await
+                    .map_err(|e| format!("Failed to read {path}: {e}"))?;
+                if !metadata.is_file() {
+                    return Err(format!("Not a file: {path}"));
+                }
+                if metadata.len() > MAX_INPUT_BYTES {
+                    return Err(format!(
+                        "{path} is too large to diff ({} bytes, max {MAX_INPUT_BYTES})",
+                        metadata.len()
+                    ));
+                }
+                let bytes = tokio::fs::read(&full_path)
+                    .await
+                    .map_err(|e| format!("Failed to read {path}: {e}"))?;
+                let text = String::from_utf8(bytes)
+                    .map_err(|_| format!("{path} is not a UTF-8 text file"))?;
+                Ok(Some((path.to_string(), text)))
+            }
+        }
+    }
+}
+
+pub(crate) fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> Option<String> {
+    let diff = TextDiff::from_lines(old, new);
+    let (mut added, mut removed) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    if added == 0 && removed == 0 {
+        return None;
+    }
+
+    let patch = diff
+        .unified_diff()
+        .context_radius(context)
+        .header(old_label, new_label)
+        .to_string();
+    Some(format!(
+        "{patch}\n{added} insertions(+), {removed} deletions(-)"
+    ))
+}
+
+#[async_trait]
+impl Tool for DiffTool {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn description(&self) -> &str {
+        "Show a unified diff between two workspace files, a file and proposed new content, or two strings. \
+         Use it to show the user exactly what a change would do before writing it"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Original workspace file (a missing file counts as empty)"
+                },
+                "old_content": {
+                    "type": "string",
+                    "description": "Original text, instead of 'path'"
+                },
+                "new_path": {
+                    "type": "string",
+                    "description": "Workspace file to compare against"
+                },
+                "new_content": {
+                    "type": "string",
+                    "description": "Proposed text, instead of 'new_path'"
+                },
+                "context": {
+                    "type": "integer",
+                    "description": "Unchanged lines shown around each change (default: 3)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many file operations. Please wait a moment.",
+            ));
+        }
+
+        let old = match self.side(&args, "path", "old_content").await {
+            Ok(Some(side)) => side,
+            Ok(None) => {
+                return Ok(ToolResult::error(
+                    "Missing original: provide 'path' or 'old_content'",
+                ));
+            }
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let new = match self.side(&args, "new_path", "new_content").await {
+            Ok(Some(side)) => side,
+            Ok(None) => {
+                return Ok(ToolResult::error(
+                    "Missing comparison: provide 'new_path' or 'new_content'",
+                ));
+            }
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let context =
+            crate::tools::extract_usize_arg_opt(&args, "context", DEFAULT_CONTEXT).min(MAX_CONTEXT);
+
+        // Proposed content for a file keeps the file name on both sides, like `git diff`.
+        let old_name = if old.0.is_empty() { "old" } else { &old.0 };
+        let new_name = if !new.0.is_empty() {
+            &new.0
+        } else if !old.0.is_empty() {
+            &old.0
+        } else {
+            "new"
+        };
+        let old_label = format!("a/{old_name}");
+        let new_label = format!("b/{new_name}");
+
+        match unified_diff(&old.1, &new.1, &old_label, &new_label, context) {
+            None => Ok(ToolResult::success("No differences.")),
+            Some(diff) if diff.chars().count() > MAX_OUTPUT_CHARS => {
+                let truncated: String = diff.chars().take(MAX_OUTPUT_CHARS).collect();
+                Ok(ToolResult::success(format!(
+                    "{truncated}\n[... diff truncated at {MAX_OUTPUT_CHARS} characters]"
+                )))
+            }
+            Some(diff) => Ok(ToolResult::success(diff)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reports_insertions_deletions_and_identical_inputs() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "a/f", "b/f", 3), None);
+
+        let diff = unified_diff("a\nc\n", "a\nb\nc\n", "a/f", "b/f", 3).unwrap();
+        assert!(diff.starts_with("--- a/f\n+++ b/f\n@@ -1,2 +1,3 @@\n"));
+        assert!(diff.contains("\n+b\n"));
+        assert!(diff.ends_with("1 insertions(+), 0 deletions(-)"));
+
+        let diff = unified_diff("a\nb\nc\n", "a\nc\n", "a/f", "b/f", 3).unwrap();
+        assert!(diff.contains("\n-b\n"));
+        assert!(diff.ends_with("0 insertions(+), 1 deletions(-)"));
+    }
+
+    #[test]
+    fn trims_context_to_the_radius() {
+        let old: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+        let new = old.replace("line 10\n", "line ten\n");
+
+        let diff = unified_diff(&old, &new, "a/f", "b/f", 1).unwrap();
+        assert!(diff.contains("@@ -9,3 +9,3 @@"));
+        assert!(diff.contains(" line 9\n-line 10\n+line ten\n line 11\n"));
+        assert!(!diff.contains("line 8\n"));
+        assert!(!diff.contains("line 12\n"));
+    }
+
+    #[tokio::test]
+    async fn a_missing_file_diffs_as_empty() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("same.txt"), "x\n").unwrap();
+        let tool = DiffTool::new(tmp.path());
+
+        let result = tool
+            .execute(json!({"path": "new.txt", "new_content": "hello\n"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("--- a/new.txt\n+++ b/new.txt\n"));
+        assert!(result.output.contains("+hello\n"));
+
+        let result = tool
+            .execute(json!({"path": "same.txt", "new_path": "same.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "No differences.");
+
+        let result = tool.execute(json!({"path": "same.txt"})).await.unwrap();
+        assert!(result.error.unwrap().contains("Missing comparison"));
+    }
+}
@@ -0,0 +1,798 @@
+use crate::tools::html::{attribute, decode_entities, html_to_markdown};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+const MAX_ARCHIVE_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DocumentKind {
+    Pdf,
+    Docx,
+    Epub,
+}
+
+impl DocumentKind {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("pdf") => Some(Self::Pdf),
+            Some("docx") => Some(Self::Docx),
+            Some("epub") => Some(Self::Epub),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn unit(&self) -> &'static str {
+        match self {
+            Self::Pdf | Self::Docx => "page",
+            Self::Epub => "chapter",
+        }
+    }
+}
+
+impl std::fmt::Display for DocumentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pdf => write!(f, "PDF"),
+            Self::Docx => write!(f, "DOCX"),
+            Self::Epub => write!(f, "EPUB"),
+        }
+    }
+}
+
+pub(crate) fn extract_pages(kind: DocumentKind, bytes: &[u8]) -> anyhow::Result<Vec<String>> {
+    match kind {
+        DocumentKind::Pdf => extract_pdf(bytes),
+        DocumentKind::Docx => extract_docx(bytes),
+        DocumentKind::Epub => extract_epub(bytes),
+    }
+}
+
+pub(crate) fn parse_page_range(spec: &str, total: usize) -> Result<Vec<usize>, String> {
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => {
+                let start = if start.trim().is_empty() {
+                    1
+                } else {
+                    parse_page_number(start)?
+                };
+                let end = if end.trim().is_empty() {
+                    total
+                } else {
+                    parse_page_number(end)?
+                };
+                (start, end)
+            }
+            None => {
+                let page = parse_page_number(part)?;
+                (page, page)
+            }
+        };
+
+        if start > end {
+            return Err(format!("Invalid page range: {part}"));
+        }
+        if end > total {
+            return Err(format!("Page {end} is out of range (document has {total})"));
+        }
+        pages.extend(start..=end);
+    }
+
+    if pages.is_empty() {
+        return Err(format!("Invalid page range: {spec}"));
+    }
+    pages.dedup();
+    Ok(pages)
+}
+
+fn parse_page_number(raw: &str) -> Result<usize, String> {
+    match raw.trim().parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!("Invalid page number: {raw}")),
+        Ok(n) => Ok(n),
+    }
+}
+
+fn read_zip_entry(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> anyhow::Result<String> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|_| anyhow::anyhow!("Missing '{name}' in document"))?;
+    let mut content = String::new();
+    entry
+        .take(MAX_ARCHIVE_ENTRY_BYTES)
+        .read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn extract_docx(bytes: &[u8]) -> anyhow::Result<Vec<String>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let xml = read_zip_entry(&mut archive, "word/document.xml")?;
+
+    let mut pages = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut rest = xml.as_str();
+
+    while let Some(lt) = rest.find('<') {
+        if in_text {
+            current.push_str(&decode_entities(&rest[..lt]));
+        }
+        let Some(gt) = rest[lt..].find('>') else {
+            break;
+        };
+        let tag = &rest[lt + 1..lt + gt];
+        rest = &rest[lt + gt + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        let closing = tag.starts_with('/');
+
+        match name {
+            "w:t" => in_text = !closing && !tag.ends_with('/'),
+            "w:tab" if !closing => current.push('\t'),
+            "w:br" if !closing => {
+                if attribute(tag, "w:type").as_deref() == Some("page") {
+                    pages.push(std::mem::take(&mut current));
+                } else {
+                    current.push('\n');
+                }
+            }
+            "w:lastRenderedPageBreak" if !current.trim().is_empty() => {
+                pages.push(std::mem::take(&mut current));
+            }
+            "w:p" if closing => current.push('\n'),
+            _ => {}
+        }
+    }
+    pages.push(current);
+
+    Ok(pages.into_iter().map(|p| p.trim().to_string()).collect())
+}
+
+fn extract_epub(bytes: &[u8]) -> anyhow::Result<Vec<String>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let container = read_zip_entry(&mut archive, "META-INF/container.xml")?;
+    let opf_path = find_tags(&container, "rootfile")
+        .into_iter()
+        .find_map(|attrs| attribute(attrs, "full-path"))
+        .ok_or_else(|| anyhow::anyhow!("EPUB container has no rootfile"))?;
+    let opf = read_zip_entry(&mut archive, &opf_path)?;
+    let base = match opf_path.rfind('/') {
+        Some(pos) => &opf_path[..=pos],
+        None => "",
+    };
+
+    let manifest: BTreeMap<String, String> = find_tags(&opf, "item")
+        .into_iter()
+        .filter_map(|attrs| Some((attribute(attrs, "id")?, attribute(attrs, "href")?)))
+        .collect();
+
+    let mut chapters = Vec::new();
+    for idref in find_tags(&opf, "itemref")
+        .into_iter()
+        .filter_map(|attrs| attribute(attrs, "idref"))
+    {
+        let Some(href) = manifest.get(&idref) else {
+            continue;
+        };
+        let path = format!("{base}{}", decode_entities(href));
+        let Ok(html) = read_zip_entry(&mut archive, &path) else {
+            continue;
+        };
+        let text = html_to_markdown(&html);
+        if !text.trim().is_empty() {
+            chapters.push(text);
+        }
+    }
+
+    if chapters.is_empty() {
+        anyhow::bail!("EPUB contains no readable chapters");
+    }
+    Ok(chapters)
+}
+
+fn find_tags<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{name}");
+    let mut tags = Vec::new();
+    let mut rest = xml;
+    while let Some(pos) = rest.find(&open) {
+        rest = &rest[pos + open.len()..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        if rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            tags.push(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    tags
+}
+
+struct PdfObject {
+    dict: Vec<u8>,
+    stream: Option<Vec<u8>>,
+}
+
+fn extract_pdf(bytes: &[u8]) -> anyhow::Result<Vec<String>> {
+    if !bytes.starts_with(b"%PDF") {
+        anyhow::bail!("Not a PDF file");
+    }
+    if find(bytes, b"/Encrypt", 0).is_some() {
+        anyhow::bail!("Encrypted PDFs are not supported");
+    }
+
+    let objects = parse_pdf_objects(bytes);
+    let mut page_ids = Vec::new();
+    if let Some(root) = objects
+        .values()
+        .find(|o| dict_type(&o.dict).as_deref() == Some("Catalog"))
+        .and_then(|catalog| dict_ref(&catalog.dict, b"/Pages"))
+    {
+        collect_pages(&objects, root, &mut page_ids, &mut HashSet::new(), 0);
+    }
+    if page_ids.is_empty() {
+        page_ids = objects
+            .iter()
+            .filter(|(_, o)| dict_type(&o.dict).as_deref() == Some("Page"))
+            .map(|(id, _)| *id)
+            .collect();
+    }
+
+    let pages: Vec<String> = page_ids
+        .iter()
+        .filter_map(|id| objects.get(id))
+        .map(|page| {
+            let mut text = String::new();
+            for content_id in dict_refs(&page.dict, b"/Contents") {
+                if let Some(stream) = objects.get(&content_id).and_then(|o| o.stream.as_ref()) {
+                    text.push_str(&content_stream_text(stream));
+                    text.push('\n');
+                }
+            }
+            text.trim().to_string()
+        })
+        .collect();
+
+    if pages.is_empty() {
+        anyhow::bail!("No pages found in PDF");
+    }
+    if pages.iter().all(|p| p.is_empty()) {
+        anyhow::bail!(
+            "PDF has {} pages but no extractable text (it may be scanned or use embedded font encodings)",
+            pages.len()
+        );
+    }
+    Ok(pages)
+}
+
+fn collect_pages(
+    objects: &BTreeMap<u32, PdfObject>,
+    id: u32,
+    pages: &mut Vec<u32>,
+    visited: &mut HashSet<u32>,
+    depth: usize,
+) {
+    if depth > MAX_PAGE_TREE_DEPTH || !visited.insert(id) {
+        return;
+    }
+    let Some(node) = objects.get(&id) else {
+        return;
+    };
+    match dict_type(&node.dict).as_deref() {
+        Some("Pages") => {
+            for kid in dict_refs(&node.dict, b"/Kids") {
+                collect_pages(objects, kid, pages, visited, depth + 1);
+            }
+        }
+        Some("Page") => pages.push(id),
+        _ => {}
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from >= haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+fn parse_pdf_objects(bytes: &[u8]) -> BTreeMap<u32, PdfObject> {
+    let mut objects = BTreeMap::new();
+    let mut pos = 0;
+
+    while let Some(obj_pos) = find(bytes, b" obj", pos) {
+        pos = obj_pos + 4;
+        let Some(id) = object_number_before(bytes, obj_pos) else {
+            continue;
+        };
+        let end = find(bytes, b"endobj", pos).unwrap_or(bytes.len());
+        let body = &bytes[pos..end];
+
+        let object = match find(body, b"stream", 0) {
+            Some(stream_pos) if !body[..stream_pos].ends_with(b"end") => {
+                let mut start = stream_pos + 6;
+                if body.get(start) == Some(&b'\r') {
+                    start += 1;
+                }
+                if body.get(start) == Some(&b'\n') {
+                    start += 1;
+                }
+                let stream_end = find(body, b"endstream", start).unwrap_or(body.len());
+                let dict = body[..stream_pos].to_vec();
+                let raw = &body[start..stream_end];
+                PdfObject {
+                    stream: decode_stream(&dict, raw),
+                    dict,
+                }
+            }
+            _ => PdfObject {
+                dict: body.to_vec(),
+                stream: None,
+            },
+        };
+
+        if dict_type(&object.dict).as_deref() == Some("ObjStm")
+            && let Some(stream) = &object.stream
+        {
+            for (inner_id, inner) in parse_object_stream(&object.dict, stream) {
+                objects.entry(inner_id).or_insert(inner);
+            }
+        }
+        objects.insert(id, object);
+        pos = end;
+    }
+    objects
+}
+
+fn object_number_before(bytes: &[u8], obj_pos: usize) -> Option<u32> {
+    let head = &bytes[obj_pos.saturating_sub(24)..obj_pos];
+    let head = String::from_utf8_lossy(head);
+    let mut parts = head.split_whitespace().rev();
+    let generation = parts.next()?;
+    let number = parts.next()?;
+    generation.parse::<u32>().ok()?;
+    number
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+fn parse_object_stream(dict: &[u8], stream: &[u8]) -> Vec<(u32, PdfObject)> {
+    let (Some(count), Some(first)) = (dict_number(dict, b"/N"), dict_number(dict, b"/First"))
+    else {
+        return Vec::new();
+    };
+    let header = String::from_utf8_lossy(&stream[..first.min(stream.len())]).to_string();
+    let numbers: Vec<usize> = header
+        .split_whitespace()
+        .filter_map(|n| n.parse().ok())
+        .collect();
+
+    let entries: Vec<(u32, usize)> = numbers
+        .chunks(2)
+        .take(count)
+        .filter_map(|pair| match pair {
+            [id, offset] => Some((*id as u32, first + offset)),
+            _ => None,
+        })
+        .collect();
+
+    entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (id, start))| {
+            let end = entries
+                .get(i + 1)
+                .map(|(_, next)| *next)
+                .unwrap_or(stream.len());
+            let body = stream.get(*start..end.min(stream.len()))?;
+            Some((
+                *id,
+                PdfObject {
+                    dict: body.to_vec(),
+                    stream: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn decode_stream(dict: &[u8], raw: &[u8]) -> Option<Vec<u8>> {
+    if find(dict, b"/Filter", 0).is_none() {
+        return Some(raw.to_vec());
+    }
+    find(dict, b"/FlateDecode", 0)?;
+    let mut decoded = Vec::new();
+    let mut decoder = flate2::read::ZlibDecoder::new(raw);
+    match decoder.read_to_end(&mut decoded) {
+        Ok(_) => Some(decoded),
+        Err(_) if !decoded.is_empty() => Some(decoded),
+        Err(_) => None,
+    }
+}
+
+fn dict_type(dict: &[u8]) -> Option<String> {
+    let pos = find(dict, b"/Type", 0)? + 5;
+    let rest = String::from_utf8_lossy(&dict[pos..dict.len().min(pos + 32)]).to_string();
+    let rest = rest.trim_start().strip_prefix('/')?;
+    Some(
+        rest.chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect(),
+    )
+}
+
+fn dict_value(dict: &[u8], key: &[u8]) -> Option<String> {
+    let mut from = 0;
+    loop {
+        let pos = find(dict, key, from)? + key.len();
+        from = pos;
+        if dict.get(pos).is_some_and(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+        let end = dict.len().min(pos + 4096);
+        return Some(String::from_utf8_lossy(&dict[pos..end]).to_string());
+    }
+}
+
+fn dict_number(dict: &[u8], key: &[u8]) -> Option<usize> {
+    dict_value(dict, key)?
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+fn dict_ref(dict: &[u8], key: &[u8]) -> Option<u32> {
+    dict_refs(dict, key).into_iter().next()
+}
+
+fn dict_refs(dict: &[u8], key: &[u8]) -> Vec<u32> {
+    let Some(value) = dict_value(dict, key) else {
+        return Vec::new();
+    };
+    let value = value.trim_start();
+    let body = if let Some(array) = value.strip_prefix('[') {
+        array.split(']').next().unwrap_or("")
+    } else {
+        value.split(['/', '>']).next().unwrap_or("")
+    };
+
+    let tokens: Vec<&str> = body.split_whitespace().collect();
+    let refs: Vec<u32> = tokens
+        .windows(3)
+        .filter(|w| w[2] == "R")
+        .filter_map(|w| w[0].parse().ok())
+        .collect();
+    if value.starts_with('[') {
+        refs
+    } else {
+        refs.into_iter().take(1).collect()
+    }
+}
+
+#[derive(Debug)]
+enum Operand {
+    Number(f64),
+    Text(String),
+    Array(Vec<Operand>),
+    Other,
+}
+
+fn content_stream_text(stream: &[u8]) -> String {
+    let mut out = String::new();
+    let mut operands: Vec<Operand> = Vec::new();
+    let mut array_stack: Vec<Vec<Operand>> = Vec::new();
+    let mut i = 0;
+
+    let push = |operand: Operand, operands: &mut Vec<Operand>, arrays: &mut Vec<Vec<Operand>>| {
+        match arrays.last_mut() {
+            Some(array) => array.push(operand),
+            None => operands.push(operand),
+        }
+    };
+
+    while i < stream.len() {
+        let c = stream[i];
+        match c {
+            b'(' => {
+                let (text, next) = read_literal_string(stream, i + 1);
+                push(Operand::Text(text), &mut operands, &mut array_stack);
+                i = next;
+            }
+            b'<' if stream.get(i + 1) == Some(&b'<') => i += 2,
+            b'>' if stream.get(i + 1) == Some(&b'>') => i += 2,
+            b'<' => {
+                let end = stream[i..]
+                    .iter()
+                    .position(|b| *b == b'>')
+                    .map(|p| p + i)
+                    .unwrap_or(stream.len());
+                let text = decode_hex_string(&stream[i + 1..end]);
+                push(Operand::Text(text), &mut operands, &mut array_stack);
+                i = end + 1;
+            }
+            b'[' => {
+                array_stack.push(Vec::new());
+                i += 1;
+            }
+            b']' => {
+                let array = array_stack.pop().unwrap_or_default();
+                push(Operand::Array(array), &mut operands, &mut array_stack);
+                i += 1;
+            }
+            b'%' => {
+                while i < stream.len() && stream[i] != b'\n' && stream[i] != b'\r' {
+                    i += 1;
+                }
+            }
+            b'/' => {
+                i += 1;
+                while i < stream.len() && !is_delimiter(stream[i]) {
+                    i += 1;
+                }
+                push(Operand::Other, &mut operands, &mut array_stack);
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                let start = i;
+                while i < stream.len() && !is_delimiter(stream[i]) {
+                    i += 1;
+                }
+                if i == start {
+                    i += 1;
+                    continue;
+                }
+                let token = String::from_utf8_lossy(&stream[start..i]).to_string();
+                if let Ok(n) = token.parse::<f64>() {
+                    push(Operand::Number(n), &mut operands, &mut array_stack);
+                    continue;
+                }
+                if !array_stack.is_empty() {
+                    continue;
+                }
+                apply_text_operator(&token, &operands, &mut out);
+                operands.clear();
+            }
+        }
+    }
+
+    out.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn apply_text_operator(op: &str, operands: &[Operand], out: &mut String) {
+    let newline = |out: &mut String| {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    };
+
+    match op {
+        "Tj" => {
+            if let Some(Operand::Text(t)) = operands.last() {
+                out.push_str(t);
+            }
+        }
+        "'" | "\"" => {
+            newline(out);
+            if let Some(Operand::Text(t)) = operands.last() {
+                out.push_str(t);
+            }
+        }
+        "TJ" => {
+            if let Some(Operand::Array(items)) = operands.last() {
+                for item in items {
+                    match item {
+                        Operand::Text(t) => out.push_str(t),
+                        Operand::Number(n) if *n < -200.0 && !out.ends_with(' ') => out.push(' '),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        "Td" | "TD" => match operands {
+            [.., Operand::Number(_), Operand::Number(ty)] if *ty != 0.0 => newline(out),
+            [.., Operand::Number(tx), Operand::Number(_)]
+                if *tx > 0.0 && !out.ends_with([' ', '\n']) =>
+            {
+                out.push(' ')
+            }
+            _ => {}
+        },
+        "T*" | "Tm" | "ET" => newline(out),
+        _ => {}
+    }
+}
+
+fn is_delimiter(c: u8) -> bool {
+    c.is_ascii_whitespace() || b"()<>[]{}/%".contains(&c)
+}
+
+fn read_literal_string(stream: &[u8], mut i: usize) -> (String, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 1;
+
+    while i < stream.len() {
+        let c = stream[i];
+        i += 1;
+        match c {
+            b'\\' => {
+                let Some(&next) = stream.get(i) else {
+                    break;
+                };
+                i += 1;
+                match next {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'b' | b'f' => {}
+                    b'\r' => {
+                        if stream.get(i) == Some(&b'\n') {
+                            i += 1;
+                        }
+                    }
+                    b'\n' => {}
+                    b'0'..=b'7' => {
+                        let mut value = (next - b'0') as u32;
+                        for _ in 0..2 {
+                            match stream.get(i) {
+                                Some(d @ b'0'..=b'7') => {
+                                    value = value * 8 + (d - b'0') as u32;
+                                    i += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    other => bytes.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(c);
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                bytes.push(c);
+            }
+            _ => bytes.push(c),
+        }
+    }
+
+    (decode_text_bytes(&bytes), i)
+}
+
+fn decode_hex_string(hex: &[u8]) -> String {
+    let digits: Vec<u8> = hex
+        .iter()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| (*c as char).to_digit(16).unwrap_or(0) as u8)
+        .collect();
+    let bytes: Vec<u8> = digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+        .collect();
+    decode_text_bytes(&bytes)
+}
+
+fn decode_text_bytes(bytes: &[u8]) -> String {
+    let utf16 = bytes.starts_with(&[0xFE, 0xFF])
+        || (bytes.len() >= 2
+            && bytes.len().is_multiple_of(2)
+            && bytes.iter().step_by(2).all(|b| *b == 0));
+    if utf16 {
+        let body = bytes.strip_prefix(&[0xFE, 0xFF]).unwrap_or(bytes);
+        let units: Vec<u16> = body
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    bytes.iter().map(|b| *b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn build_pdf(pages: &[&str]) -> Vec<u8> {
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..pages.len())
+                    .map(|i| format!("{} 0 R", 3 + i * 2))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                pages.len()
+            ),
+        ];
+        for (i, text) in pages.iter().enumerate() {
+            let content =
+                format!("BT /F1 12 Tf 72 712 Td ({text}) Tj 0 -14 Td [(sec) -300 (ond)] TJ ET");
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /Contents {} 0 R >>",
+                4 + i * 2
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}\nendstream",
+                content.len()
+            ));
+        }
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        for (i, body) in objects.iter().enumerate() {
+            pdf.extend_from_slice(format!("{} 0 obj\n{body}\nendobj\n", i + 1).as_bytes());
+        }
+        pdf.extend_from_slice(b"trailer\n<< /Root 1 0 R >>\n%%EOF\n");
+        pdf
+    }
+
+    #[test]
+    fn parses_page_ranges() {
+        assert_eq!(parse_page_range("2,4-5", 6).unwrap(), vec![2, 4, 5]);
+        assert_eq!(parse_page_range("5-", 6).unwrap(), vec![5, 6]);
+        assert!(parse_page_range("0", 3).is_err());
+        assert!(parse_page_range("2-9", 3).is_err());
+    }
+
+    #[test]
+    fn extracts_pdf_text_per_page() {
+        let pdf = build_pdf(&["Hello \\(PDF\\)", "Page two"]);
+        let pages = extract_pdf(&pdf).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0], "Hello (PDF)\nsec ond");
+        assert!(pages[1].starts_with("Page two"));
+    }
+
+    #[test]
+    fn extracts_docx_paragraphs_and_page_breaks() {
+        let xml = r#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:r><w:t>First &amp; foremost</w:t></w:r></w:p>
+            <w:p><w:r><w:t xml:space="preserve">Tab</w:t><w:tab/><w:t>bed</w:t></w:r></w:p>
+            <w:p><w:r><w:br w:type="page"/><w:t>Second page</w:t></w:r></w:p>
+            </w:body></w:document>"#;
+
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buffer);
+            writer
+                .start_file(
+                    "word/document.xml",
+                    zip::write::SimpleFileOptions::default()
+                        .compression_method(zip::CompressionMethod::Stored),
+                )
+                .unwrap();
+            writer.write_all(xml.as_bytes()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let pages = extract_docx(buffer.get_ref()).unwrap();
+        assert_eq!(pages, vec!["First & foremost\nTab\tbed", "Second page"]);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::tools::security::{RateLimiter, resolve_workspace_target};
+use crate::tools::web_fetch::{safe_redirect_policy, validate_public_url};
+use crate::tools::{extract_string_arg, extract_usize_arg_opt};
+use crate::traits::{Tool, ToolProgress, ToolResult};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use ring::digest::{Context, SHA256};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+const DEFAULT_MAX_MB: usize = 100;
+const MAX_MB_LIMIT: usize = 2048;
+const TIMEOUT_SECS: u64 = 600;
+const PROGRESS_STEP_BYTES: u64 = 5 * 1024 * 1024;
+const RATE_LIMIT_MAX: u64 = 30;
+const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
+
+static GLOBAL_RATE_LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+pub struct DownloadTool {
+    workspace: PathBuf,
+    client: reqwest::Client,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl DownloadTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .connect_timeout(Duration::from_secs(10))
+            .redirect(safe_redirect_policy())
+            .user_agent("Dinoe/0.2 (download)")
+            .build()
+            .expect("Failed to build HTTP client");
+
+        let rate_limiter = GLOBAL_RATE_LIMITER
+            .get_or_init(|| Arc::new(RateLimiter::new(RATE_LIMIT_MAX, RATE_LIMIT_WINDOW_SECS)))
+            .clone();
+
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            client,
+            rate_limiter,
+        }
+    }
+
+    async fn fetch_to(
+        &self,
+        url: &str,
+        part_path: &Path,
+        max_bytes: u64,
+        progress: &ToolProgress,
+    ) -> Result<(u64, String, String, Option<String>), String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request failed: {e}"))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!(
+                "HTTP {} {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown")
+            ));
+        }
+
+        let total = response.content_length();
+        if let Some(total) = total
+            && total > max_bytes
+        {
+            return Err(format!(
+                "File is {total} bytes, which exceeds the {max_bytes} byte limit"
+            ));
+        }
+
+        let final_url = response.url().to_string();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        let mut file = tokio::fs::File::create(part_path)
+            .await
+            .map_err(|e| format!("Failed to create file: {e}"))?;
+        let mut hasher = Context::new(&SHA256);
+        let mut downloaded = 0u64;
+        let mut next_report = PROGRESS_STEP_BYTES;
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Download interrupted: {e}"))?;
+            downloaded += chunk.len() as u64;
+            if downloaded > max_bytes {
+                return Err(format!("Download exceeded the {max_bytes} byte limit"));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write file: {e}"))?;
+
+            if downloaded >= next_report {
+                next_report += PROGRESS_STEP_BYTES;
+                match total {
+                    Some(total) => progress.report(format!(
+                        "{} of {} MB ({}%)",
+                        downloaded / (1024 * 1024),
+                        total / (1024 * 1024),
+                        downloaded * 100 / total.max(1)
+                    )),
+                    None => progress.report(format!("{} MB", downloaded / (1024 * 1024))),
+                }
+            }
+        }
+
+        file.sync_all()
+            .await
+            .map_err(|e| format!("Failed to write file: {e}"))?;
+
+        let digest = hasher
+            .finish()
+            .as_ref()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        Ok((downloaded, digest, final_url, content_type))
+    }
+
+    /// Saves `url`, already checked to be public, to `path` in the workspace.
+    async fn download(
+        &self,
+        url: &str,
+        path: &str,
+        expected_sha: Option<String>,
+        max_bytes: u64,
+        overwrite: bool,
+        progress: &ToolProgress,
+    ) -> ToolResult {
+        let target = match resolve_workspace_target(path, &self.workspace) {
+            Ok(t) => t,
+            Err(e) => return ToolResult::error(e),
+        };
+        if target.is_dir() {
+            return ToolResult::error(format!("{path} is a directory"));
+        }
+        if target.exists() && !overwrite {
+            return ToolResult::error(format!(
+                "{path} already exists. Pass overwrite=true to replace it"
+            ));
+        }
+        if let Some(parent) = target.parent()
+            && let Err(e) = tokio::fs::create_dir_all(parent).await
+        {
+            return ToolResult::error(format!("Failed to create directory: {e}"));
+        }
+
+        let file_name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let part_path = target.with_file_name(format!(
+            ".{file_name}.{}.part",
+            uuid::Uuid::new_v4().simple()
+        ));
+
+        let started = Instant::now();
+        let (bytes, digest, final_url, content_type) =
+            match self.fetch_to(url, &part_path, max_bytes, progress).await {
+                Ok(result) => result,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(&part_path).await;
+                    return ToolResult::error(e);
+                }
+            };
+
+        if let Some(expected) = &expected_sha
+            && *expected != digest
+        {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return ToolResult::error(format!(
+                "SHA-256 mismatch: expected {expected}, got {digest}. The download was discarded"
+            ));
+        }
+
+        if let Err(e) = tokio::fs::rename(&part_path, &target).await {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return ToolResult::error(format!("Failed to save file: {e}"));
+        }
+
+        ToolResult::success(
+            serde_json::to_string_pretty(&json!({
+                "path": path,
+                "bytes": bytes,
+                "sha256": digest,
+                "verified": expected_sha.is_some(),
+                "content_type": content_type,
+                "final_url": final_url,
+                "elapsed_ms": started.elapsed().as_millis() as u64,
+            }))
+            .unwrap_or_default(),
+        )
+    }
+}
+
+#[async_trait]
+impl Tool for DownloadTool {
+    fn name(&self) -> &str {
+        "download"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Download a URL to a file in the workspace with a size limit and optional SHA-256 verification. \
+         Use this for binaries, archives and other assets instead of curl"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "HTTP or HTTPS URL to download"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Destination path relative to the workspace (parent directories are created)"
+                },
+                "sha256": {
+                    "type": "string",
+                    "description": "Expected SHA-256 hex digest; the file is discarded on mismatch"
+                },
+                "max_mb": {
+                    "type": "integer",
+                    "description": "Maximum download size in megabytes (default: 100, max: 2048)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace an existing file at path (default: false)"
+                }
+            },
+            "required": ["url", "path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.execute_with_progress(args, ToolProgress::none()).await
+    }
+
+    async fn execute_with_progress(
+        &self,
+        args: serde_json::Value,
+        progress: ToolProgress,
+    ) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many downloads. Please wait a moment.",
+            ));
+        }
+
+        let url = extract_string_arg(&args, "url")?;
+        let path = extract_string_arg(&args, "path")?;
+        let expected_sha = args
+            .get("sha256")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty());
+        let max_bytes = extract_usize_arg_opt(&args, "max_mb", DEFAULT_MAX_MB)
+            .clamp(1, MAX_MB_LIMIT) as u64
+            * 1024
+            * 1024;
+        let overwrite = args
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if let Some(sha) = &expected_sha
+            && (sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            return Ok(ToolResult::error(
+                "sha256 must be a 64-character hex digest",
+            ));
+        }
+
+        let url = match validate_public_url(&url) {
+            Ok(u) => u,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        Ok(self
+            .download(&url, &path, expected_sha, max_bytes, overwrite, &progress)
+            .await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use tokio::io::AsyncReadExt;
+
+    /// A local server answering every request with `body`, without a Content-Length so
+    /// the size is only known as it streams.
+    async fn serving(body: &'static [u8]) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n")
+                    .await;
+                let _ = stream.write_all(body).await;
+            }
+        });
+        format!("http://{address}/file.bin")
+    }
+
+    fn files_in(dir: &Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn keeps_the_file_only_when_the_checksum_matches() {
+        let tmp = TempDir::new().unwrap();
+        let tool = DownloadTool::new(tmp.path());
+        let url = serving(b"hello").await;
+        let progress = ToolProgress::none();
+
+        let result = tool
+            .download(&url, "a.bin", Some("0".repeat(64)), 1024, false, &progress)
+            .await;
+        assert!(result.error.unwrap().contains("SHA-256 mismatch"));
+        assert!(files_in(tmp.path()).is_empty());
+
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let result = tool
+            .download(&url, "a.bin", Some(hello.into()), 1024, false, &progress)
+            .await;
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(std::fs::read(tmp.path().join("a.bin")).unwrap(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn removes_the_partial_file_past_the_size_limit() {
+        let tmp = TempDir::new().unwrap();
+        let tool = DownloadTool::new(tmp.path());
+        let url = serving(&[b'x'; 4096]).await;
+
+        let result = tool
+            .download(&url, "big.bin", None, 100, false, &ToolProgress::none())
+            .await;
+        assert!(result.error.unwrap().contains("100 byte limit"));
+        assert!(files_in(tmp.path()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn refuses_to_overwrite_or_fetch_private_hosts() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("kept.txt"), "original").unwrap();
+        let tool = DownloadTool::new(tmp.path());
+
+        let result = tool
+            .execute(json!({"url": "https://example.com/new.txt", "path": "kept.txt"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("already exists"));
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("kept.txt")).unwrap(),
+            "original"
+        );
+
+        let result = tool
+            .execute(json!({"url": "http://127.0.0.1:8080/secret", "path": "secret.txt"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Blocked local/private host"));
+        assert!(!tmp.path().join("secret.txt").exists());
+    }
+}
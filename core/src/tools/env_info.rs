@@ -0,0 +1,188 @@
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, System};
+use tokio::process::Command;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// (name, program, version flag). The first candidate that runs wins for each name.
+const RUNTIMES: &[(&str, &[&str], &str)] = &[
+    ("node", &["node"], "--version"),
+    ("npm", &["npm"], "--version"),
+    ("deno", &["deno"], "--version"),
+    ("bun", &["bun"], "--version"),
+    ("python", &["python3", "python"], "--version"),
+    ("pip", &["pip3", "pip"], "--version"),
+    ("uv", &["uv"], "--version"),
+    ("rustc", &["rustc"], "--version"),
+    ("cargo", &["cargo"], "--version"),
+    ("go", &["go"], "version"),
+    ("java", &["java"], "-version"),
+    ("ruby", &["ruby"], "--version"),
+    ("php", &["php"], "--version"),
+    ("gcc", &["gcc"], "--version"),
+    ("clang", &["clang"], "--version"),
+    ("make", &["make"], "--version"),
+    ("git", &["git"], "--version"),
+    ("docker", &["docker"], "--version"),
+];
+
+pub struct EnvInfoTool;
+
+impl EnvInfoTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for EnvInfoTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn probe(programs: &[&str], flag: &str) -> Option<String> {
+    for program in programs {
+        let output = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            Command::new(program)
+                .arg(flag)
+                .stdin(std::process::Stdio::null())
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await;
+        let Ok(Ok(output)) = output else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        // Some tools (java, older pythons) print their version on stderr.
+        let text = if output.stdout.is_empty() {
+            output.stderr
+        } else {
+            output.stdout
+        };
+        let first_line = String::from_utf8_lossy(&text)
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .map(str::to_string);
+        if first_line.is_some() {
+            return first_line;
+        }
+    }
+    None
+}
+
+async fn runtimes() -> Value {
+    let versions = join_all(
+        RUNTIMES
+            .iter()
+            .map(|(_, programs, flag)| probe(programs, flag)),
+    )
+    .await;
+    let mut found = Map::new();
+    let mut missing = Vec::new();
+    for ((name, _, _), version) in RUNTIMES.iter().zip(versions) {
+        match version {
+            Some(version) => {
+                found.insert(name.to_string(), Value::String(version));
+            }
+            None => missing.push(*name),
+        }
+    }
+    json!({ "installed": found, "not_found": missing })
+}
+
+fn system() -> (Value, Value) {
+    let mut sys = System::new();
+    sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+    sys.refresh_cpu_list(CpuRefreshKind::nothing());
+
+    let os = json!({
+        "family": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "name": System::long_os_version(),
+        "kernel": System::kernel_version(),
+        "hostname": System::host_name(),
+    });
+    let mb = |bytes: u64| bytes / (1024 * 1024);
+    let hardware = json!({
+        "cpu": sys.cpus().first().map(|c| c.brand().trim().to_string()),
+        "logical_cores": std::thread::available_parallelism().map(|n| n.get()).ok(),
+        "physical_cores": sys.physical_core_count(),
+        "memory_total_mb": mb(sys.total_memory()),
+        "memory_available_mb": mb(sys.available_memory()),
+    });
+    (os, hardware)
+}
+
+fn shell() -> Option<String> {
+    std::env::var("SHELL")
+        .or_else(|_| std::env::var("COMSPEC"))
+        .ok()
+}
+
+fn path_entries() -> Vec<String> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let mut entries: Vec<String> = Vec::new();
+    for entry in std::env::split_paths(&path) {
+        let entry = entry.display().to_string();
+        if !entry.is_empty() && !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+#[async_trait]
+impl Tool for EnvInfoTool {
+    fn name(&self) -> &str {
+        "env_info"
+    }
+
+    fn description(&self) -> &str {
+        "Report the environment as JSON: OS, shell, CPU and RAM, installed runtimes and toolchains \
+         (node, python, rust, go, ...) with versions, and PATH entries. Use it instead of several shell calls"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "include_path": {
+                    "type": "boolean",
+                    "description": "Include PATH entries (default: true)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let include_path = args
+            .get("include_path")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let (system, runtimes) = tokio::join!(tokio::task::spawn_blocking(system), runtimes());
+        let (os, hardware) = system?;
+
+        let mut info = json!({
+            "os": os,
+            "shell": shell(),
+            "hardware": hardware,
+            "runtimes": runtimes,
+        });
+        if include_path {
+            info["path"] = json!(path_entries());
+        }
+        Ok(ToolResult::success(serde_json::to_string_pretty(&info)?))
+    }
+}
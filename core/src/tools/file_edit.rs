@@ -34,6 +34,10 @@ impl Tool for FileEditTool {
         "file_edit"
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Edit a file by replacing an exact string match with new content"
     }
@@ -1,5 +1,6 @@
-use crate::tools::{extract_string_arg, get_global_rate_limiter};
+use crate::tools::documents::{DocumentKind, extract_pages, parse_page_range};
 use crate::tools::security::validate_workspace_path;
+use crate::tools::{extract_string_arg, get_global_rate_limiter};
 use crate::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
@@ -26,7 +27,7 @@ impl Tool for FileReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read the contents of a file from the workspace"
+        "Read the contents of a file from the workspace. PDF, DOCX and EPUB files are returned as extracted text"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -36,6 +37,20 @@ impl Tool for FileReadTool {
                 "path": {
                     "type": "string",
                     "description": "Path to the file to read"
+                },
+                "pages": {
+                    "type": "string",
+                    "description": "Pages to extract from PDF/DOCX (chapters for EPUB), e.g. '1-3,7' or '5-' (default: all)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Line to start reading text files from, 1-based (default: 1)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Maximum number of lines to read (default: all)"
                 }
             },
             "required": ["path"]
@@ -56,9 +71,91 @@ impl Tool for FileReadTool {
             Err(e) => return Ok(ToolResult::error(e)),
         };
 
-        match fs::read_to_string(&full_path).await {
-            Ok(content) => Ok(ToolResult::success(content)),
-            Err(e) => Ok(ToolResult::error(format!("Failed to read file: {}", e))),
+        if let Some(kind) = DocumentKind::from_path(&full_path) {
+            let pages = args.get("pages").and_then(|v| v.as_str());
+            return read_document(&full_path, kind, pages).await;
+        }
+
+        let content = match fs::read_to_string(&full_path).await {
+            Ok(content) => content,
+            Err(e) => return Ok(ToolResult::error(format!("Failed to read file: {}", e))),
+        };
+
+        let offset = args.get("offset").and_then(|v| v.as_u64());
+        let limit = args.get("limit").and_then(|v| v.as_u64());
+        if offset.is_none() && limit.is_none() {
+            return Ok(ToolResult::success(content));
         }
+        Ok(ToolResult::success(line_range(
+            &content,
+            offset.unwrap_or(1).max(1) as usize,
+            limit.map(|l| l as usize),
+        )))
+    }
+}
+
+fn line_range(content: &str, offset: usize, limit: Option<usize>) -> String {
+    let total = content.lines().count();
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(offset - 1)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    if lines.is_empty() {
+        return format!("[no lines: file has {} lines]", total);
     }
+    format!(
+        "[lines {}-{} of {}]\n{}",
+        offset,
+        offset + lines.len() - 1,
+        total,
+        lines.join("\n")
+    )
+}
+
+async fn read_document(
+    path: &std::path::Path,
+    kind: DocumentKind,
+    pages: Option<&str>,
+) -> anyhow::Result<ToolResult> {
+    let bytes = match fs::read(path).await {
+        Ok(b) => b,
+        Err(e) => return Ok(ToolResult::error(format!("Failed to read file: {}", e))),
+    };
+
+    let extracted = match tokio::task::spawn_blocking(move || extract_pages(kind, &bytes)).await? {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(ToolResult::error(format!(
+                "Failed to extract text from {}: {}",
+                kind, e
+            )));
+        }
+    };
+
+    let total = extracted.len();
+    let selected = match pages {
+        Some(spec) => match parse_page_range(spec, total) {
+            Ok(s) => s,
+            Err(e) => return Ok(ToolResult::error(e)),
+        },
+        None => (1..=total).collect(),
+    };
+
+    let unit = kind.unit();
+    let mut output = format!(
+        "[{}: {} {}{}, showing {}]\n",
+        kind,
+        total,
+        unit,
+        if total == 1 { "" } else { "s" },
+        pages.unwrap_or("all")
+    );
+    for number in selected {
+        output.push_str(&format!("\n--- {} {} ---\n", unit, number));
+        output.push_str(&extracted[number - 1]);
+        output.push('\n');
+    }
+
+    Ok(ToolResult::success(output))
 }
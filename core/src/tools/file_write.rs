@@ -1,9 +1,11 @@
-use crate::tools::{extract_string_arg, get_global_rate_limiter};
+use crate::tools::{extract_string_arg, extract_string_arg_opt, get_global_rate_limiter};
 use crate::tools::security::validate_workspace_path;
 use crate::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 
 pub struct FileWriteTool {
     workspace: std::path::PathBuf,
@@ -25,8 +27,12 @@ impl Tool for FileWriteTool {
         "file_write"
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
-        "Write content to a file in the workspace"
+        "Write content to a file in the workspace. Overwrites are atomic; use mode 'append' to add to the end of a file"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -40,6 +46,15 @@ impl Tool for FileWriteTool {
                 "content": {
                     "type": "string",
                     "description": "Content to write to the file"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["overwrite", "append"],
+                    "description": "'overwrite' replaces the file atomically, 'append' adds to the end (default: 'overwrite')"
+                },
+                "create_only": {
+                    "type": "boolean",
+                    "description": "Fail instead of touching the file if it already exists (default: false)"
                 }
             },
             "required": ["path", "content"]
@@ -55,19 +70,179 @@ impl Tool for FileWriteTool {
 
         let path = extract_string_arg(&args, "path")?;
         let content = extract_string_arg(&args, "content")?;
+        let mode = extract_string_arg_opt(&args, "mode", "overwrite");
+        let create_only = args
+            .get("create_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if mode != "overwrite" && mode != "append" {
+            return Ok(ToolResult::error(format!(
+                "Unknown mode: {mode}. Use 'overwrite' or 'append'"
+            )));
+        }
 
         let full_path = match validate_workspace_path(&path, &self.workspace) {
             Ok(p) => p,
             Err(e) => return Ok(ToolResult::error(e)),
         };
 
+        if let Ok(meta) = fs::symlink_metadata(&full_path).await
+            && meta.file_type().is_symlink()
+        {
+            return Ok(ToolResult::error(format!(
+                "Refusing to write through symlink: {}",
+                full_path.display()
+            )));
+        }
+
         if let Some(parent) = full_path.parent() {
             fs::create_dir_all(parent).await?;
         }
 
-        match fs::write(&full_path, content).await {
-            Ok(_) => Ok(ToolResult::success("File written successfully")),
+        let result = if create_only {
+            create_new(&full_path, &content).await
+        } else if mode == "append" {
+            append_file(&full_path, &content).await
+        } else {
+            write_atomic(&full_path, &content).await
+        };
+
+        match result {
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(ToolResult::error(
+                format!("File already exists: {path}. Set create_only to false to modify it"),
+            )),
+            Ok(()) if mode == "append" => Ok(ToolResult::success(format!(
+                "Appended {} bytes to {path}",
+                content.len()
+            ))),
+            Ok(()) => Ok(ToolResult::success("File written successfully")),
             Err(e) => Ok(ToolResult::error(format!("Failed to write file: {}", e))),
         }
     }
 }
+
+/// Fails with `AlreadyExists` when there is a file at `path`, checked as the file is
+/// created so nothing written in between is overwritten.
+async fn create_new(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await?;
+    let written = async {
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await
+    };
+    if let Err(e) = written.await {
+        let _ = fs::remove_file(path).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+async fn append_file(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    file.flush().await
+}
+
+pub(crate) async fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp_path = temp_sibling(path);
+
+    let write_result = async {
+        let mut file = fs::File::create(&tmp_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        if let Ok(meta) = fs::metadata(path).await {
+            fs::set_permissions(&tmp_path, meta.permissions()).await?;
+        }
+        fs::rename(&tmp_path, path).await
+    }
+    .await;
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&tmp_path).await;
+    }
+    write_result
+}
+
+fn temp_sibling(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.{}.tmp", uuid::Uuid::new_v4().simple()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn append_mode_keeps_existing_content() {
+        let tmp = TempDir::new().unwrap();
+        let tool = FileWriteTool::new(tmp.path());
+        std::fs::write(tmp.path().join("log.txt"), "first\n").unwrap();
+
+        let result = tool
+            .execute(json!({"path": "log.txt", "content": "second\n", "mode": "append"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let content = std::fs::read_to_string(tmp.path().join("log.txt")).unwrap();
+        assert_eq!(content, "first\nsecond\n");
+    }
+
+    #[tokio::test]
+    async fn create_only_refuses_existing_file() {
+        let tmp = TempDir::new().unwrap();
+        let tool = FileWriteTool::new(tmp.path());
+        std::fs::write(tmp.path().join("keep.txt"), "original").unwrap();
+
+        let result = tool
+            .execute(json!({"path": "keep.txt", "content": "new", "create_only": true}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("already exists"));
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("keep.txt")).unwrap(),
+            "original"
+        );
+
+        let result = tool
+            .execute(json!({"path": "fresh.txt", "content": "new", "create_only": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("fresh.txt")).unwrap(),
+            "new"
+        );
+    }
+
+    #[tokio::test]
+    async fn overwrite_leaves_no_temp_files() {
+        let tmp = TempDir::new().unwrap();
+        let tool = FileWriteTool::new(tmp.path());
+
+        let result = tool
+            .execute(json!({"path": "out.txt", "content": "hello"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let names: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["out.txt".to_string()]);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::tools::git_operations::GitOperationsTool;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::Path;
+
+pub struct GitStatusTool {
+    git: GitOperationsTool,
+}
+
+impl GitStatusTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            git: GitOperationsTool::new(workspace),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for GitStatusTool {
+    fn name(&self) -> &str {
+        "git_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show the current branch and staged, unstaged and untracked files of the workspace repository as JSON"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.git.run("status", &args).await
+    }
+}
+
+pub struct GitDiffTool {
+    git: GitOperationsTool,
+}
+
+impl GitDiffTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            git: GitOperationsTool::new(workspace),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for GitDiffTool {
+    fn name(&self) -> &str {
+        "git_diff"
+    }
+
+    fn description(&self) -> &str {
+        "Show working tree or staged changes as structured hunks with added, deleted and context lines"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "files": {
+                    "type": "string",
+                    "description": "File or path to diff (default: '.')"
+                },
+                "cached": {
+                    "type": "boolean",
+                    "description": "Show staged changes instead of unstaged ones"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.git.run("diff", &args).await
+    }
+}
+
+pub struct GitLogTool {
+    git: GitOperationsTool,
+}
+
+impl GitLogTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            git: GitOperationsTool::new(workspace),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for GitLogTool {
+    fn name(&self) -> &str {
+        "git_log"
+    }
+
+    fn description(&self) -> &str {
+        "List recent commits with hash, author, date and subject"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of commits to return (default: 10, max: 1000)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only show commits touching this path"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.git.run("log", &args).await
+    }
+}
+
+pub struct GitCommitTool {
+    git: GitOperationsTool,
+}
+
+impl GitCommitTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            git: GitOperationsTool::new(workspace),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for GitCommitTool {
+    fn name(&self) -> &str {
+        "git_commit"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Commit staged changes in the workspace repository, optionally staging the given paths first. \
+         Refuses to commit files outside the workspace"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Commit message"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Workspace-relative paths to stage before committing"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.git.run("commit", &args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn init_repo() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        git(tmp.path(), &["init", "-q"]);
+        git(tmp.path(), &["config", "user.email", "dev@example.com"]);
+        git(tmp.path(), &["config", "user.name", "Dev"]);
+        git(tmp.path(), &["config", "commit.gpgsign", "false"]);
+        tmp
+    }
+
+    #[tokio::test]
+    async fn commit_stages_paths_and_reports_hash() {
+        let repo = init_repo();
+        std::fs::write(repo.path().join("notes.txt"), "hello").unwrap();
+
+        let tool = GitCommitTool::new(repo.path());
+        let result = tool
+            .execute(json!({"message": "Add notes", "paths": ["notes.txt"]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["files"], json!(["notes.txt"]));
+        assert_eq!(parsed["commit"].as_str().unwrap().len(), 40);
+
+        let log = GitLogTool::new(repo.path())
+            .execute(json!({"limit": 1}))
+            .await
+            .unwrap();
+        assert!(log.output.contains("Add notes"));
+    }
+
+    #[tokio::test]
+    async fn commit_refuses_files_outside_workspace() {
+        let repo = init_repo();
+        let workspace = repo.path().join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        std::fs::write(repo.path().join("secret.txt"), "outside").unwrap();
+        git(repo.path(), &["add", "secret.txt"]);
+
+        let tool = GitCommitTool::new(&workspace);
+        let result = tool.execute(json!({"message": "Sneaky"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("secret.txt"));
+    }
+}
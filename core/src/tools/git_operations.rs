@@ -1,4 +1,4 @@
-use crate::tools::security::RateLimiter;
+use crate::tools::security::{RateLimiter, validate_workspace_path};
 use crate::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
@@ -201,18 +201,30 @@ impl GitOperationsTool {
         let limit = usize::try_from(limit_raw).unwrap_or(usize::MAX).min(1000);
         let limit_str = limit.to_string();
 
-        let output = self.run_git_command(&[
+        let path = args.get("path").and_then(|v| v.as_str());
+        if let Some(path) = path {
+            self.sanitize_git_args(path)?;
+        }
+
+        let limit_arg = format!("-{limit_str}");
+        let mut git_args = vec![
             "log",
-            &format!("-{limit_str}"),
-            "--pretty=format:%H|%an|%ae|%ad|%s",
+            limit_arg.as_str(),
+            "--pretty=format:%H%x1f%an%x1f%ae%x1f%ad%x1f%s",
             "--date=iso",
-        ])?;
+        ];
+        if let Some(path) = path {
+            git_args.push("--");
+            git_args.push(path);
+        }
+
+        let output = self.run_git_command(&git_args)?;
 
         let mut commits = Vec::new();
 
         for line in output.lines() {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 5 {
+            let parts: Vec<&str> = line.splitn(5, '\x1f').collect();
+            if parts.len() == 5 {
                 commits.push(json!({
                     "hash": parts[0],
                     "author": parts[1],
@@ -264,6 +276,30 @@ impl GitOperationsTool {
         }
     }
 
+    fn repo_root(&self) -> anyhow::Result<std::path::PathBuf> {
+        let toplevel = self.run_git_command(&["rev-parse", "--show-toplevel"])?;
+        Ok(Path::new(toplevel.trim()).canonicalize()?)
+    }
+
+    fn staged_files(&self) -> anyhow::Result<Vec<String>> {
+        let output = self.run_git_command(&["diff", "--cached", "--name-only", "-z"])?;
+        Ok(output
+            .split('\0')
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn files_outside_workspace(&self, files: &[String]) -> anyhow::Result<Vec<String>> {
+        let root = self.repo_root()?;
+        let workspace = self.workspace.canonicalize()?;
+        Ok(files
+            .iter()
+            .filter(|f| !root.join(f).starts_with(&workspace))
+            .cloned()
+            .collect())
+    }
+
     async fn git_commit(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
         let message = args
             .get("message")
@@ -283,10 +319,56 @@ impl GitOperationsTool {
 
         let message = Self::truncate_commit_message(&sanitized);
 
-        match self.run_git_command(&["commit", "-m", &message]) {
-            Ok(_) => Ok(ToolResult::success(format!("Committed: {message}"))),
-            Err(e) => Ok(ToolResult::error(format!("Commit failed: {e}"))),
+        if let Some(paths) = args.get("paths").and_then(|v| v.as_array()) {
+            let mut to_stage = Vec::new();
+            for path in paths.iter().filter_map(|p| p.as_str()) {
+                if let Err(e) = validate_workspace_path(path, &self.workspace) {
+                    return Ok(ToolResult::error(e));
+                }
+                self.sanitize_git_args(path)?;
+                to_stage.push(path);
+            }
+            if !to_stage.is_empty() {
+                let mut add_args = vec!["add", "--"];
+                add_args.extend(to_stage);
+                if let Err(e) = self.run_git_command(&add_args) {
+                    return Ok(ToolResult::error(format!("Add failed: {e}")));
+                }
+            }
         }
+
+        let staged = self.staged_files()?;
+        if staged.is_empty() {
+            return Ok(ToolResult::error(
+                "Nothing staged to commit. Stage files first or pass 'paths'",
+            ));
+        }
+
+        let outside = self.files_outside_workspace(&staged)?;
+        if !outside.is_empty() {
+            return Ok(ToolResult::error(format!(
+                "Refusing to commit files outside the workspace: {}",
+                outside.join(", ")
+            )));
+        }
+
+        if let Err(e) = self.run_git_command(&["commit", "-m", &message]) {
+            return Ok(ToolResult::error(format!("Commit failed: {e}")));
+        }
+
+        let hash = self
+            .run_git_command(&["rev-parse", "HEAD"])
+            .map(|h| h.trim().to_string())
+            .unwrap_or_default();
+
+        Ok(ToolResult::success(
+            serde_json::to_string_pretty(&json!({
+                "commit": hash,
+                "message": message,
+                "files": staged
+            }))
+            .unwrap_or_default(),
+        ))
     }
 
     async fn git_add(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -348,6 +430,34 @@ impl GitOperationsTool {
             Err(e) => Ok(ToolResult::error(format!("Stash {action} failed: {e}"))),
         }
     }
+
+    pub(crate) async fn run(
+        &self,
+        operation: &str,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many git operations. Please wait a moment.",
+            ));
+        }
+
+        if self.find_git_root().is_none() {
+            return Ok(ToolResult::error("Not in a git repository"));
+        }
+
+        match operation {
+            "status" => self.git_status().await,
+            "diff" => self.git_diff(args).await,
+            "log" => self.git_log(args).await,
+            "branch" => self.git_branch().await,
+            "commit" => self.git_commit(args).await,
+            "add" => self.git_add(args).await,
+            "checkout" => self.git_checkout(args).await,
+            "stash" => self.git_stash(args).await,
+            _ => Ok(ToolResult::error(format!("Unknown operation: {operation}"))),
+        }
+    }
 }
 
 #[async_trait]
@@ -356,6 +466,10 @@ impl Tool for GitOperationsTool {
         "git_operations"
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Perform structured Git operations (status, diff, log, branch, commit, add, checkout, stash) with parsed JSON output"
     }
@@ -393,6 +507,10 @@ impl Tool for GitOperationsTool {
                     "type": "integer",
                     "description": "Number of log entries (for 'log' operation, default: 10)"
                 },
+                "path": {
+                    "type": "string",
+                    "description": "Only show commits touching this path (for 'log' operation)"
+                },
                 "action": {
                     "type": "string",
                     "enum": ["push", "pop", "list", "drop"],
@@ -408,31 +526,11 @@ impl Tool for GitOperationsTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        if !self.rate_limiter.check_and_record() {
-            return Ok(ToolResult::error(
-                "Rate limit exceeded: too many git operations. Please wait a moment.",
-            ));
-        }
-
         let operation = match args.get("operation").and_then(|v| v.as_str()) {
             Some(op) => op,
             None => return Ok(ToolResult::error("Missing 'operation' parameter")),
         };
 
-        if self.find_git_root().is_none() {
-            return Ok(ToolResult::error("Not in a git repository"));
-        }
-
-        match operation {
-            "status" => self.git_status().await,
-            "diff" => self.git_diff(&args).await,
-            "log" => self.git_log(&args).await,
-            "branch" => self.git_branch().await,
-            "commit" => self.git_commit(&args).await,
-            "add" => self.git_add(&args).await,
-            "checkout" => self.git_checkout(&args).await,
-            "stash" => self.git_stash(&args).await,
-            _ => Ok(ToolResult::error(format!("Unknown operation: {operation}"))),
-        }
+        self.run(operation, &args).await
     }
 }
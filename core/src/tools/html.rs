@@ -0,0 +1,439 @@
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "head", "nav", "footer", "svg", "form", "iframe", "template",
+];
+
+struct MarkdownWriter {
+    out: String,
+    pending_links: Vec<Option<String>>,
+    list_depth: usize,
+    in_pre: bool,
+}
+
+impl MarkdownWriter {
+    fn new() -> Self {
+        Self {
+            out: String::new(),
+            pending_links: Vec::new(),
+            list_depth: 0,
+            in_pre: false,
+        }
+    }
+
+    fn block_break(&mut self) {
+        let trimmed_len = self.out.trim_end_matches([' ', '\n']).len();
+        self.out.truncate(trimmed_len);
+        if !self.out.is_empty() {
+            self.out.push_str("\n\n");
+        }
+    }
+
+    fn line_break(&mut self) {
+        let trimmed_len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed_len);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.in_pre {
+            self.out.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+                    self.out.push(' ');
+                }
+            } else {
+                self.out.push(c);
+            }
+        }
+    }
+
+    fn open_tag(&mut self, name: &str, attrs: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block_break();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "table" | "blockquote" => {
+                self.block_break()
+            }
+            "ul" | "ol" => {
+                self.line_break();
+                self.list_depth += 1;
+            }
+            "li" => {
+                self.line_break();
+                self.out
+                    .push_str(&"  ".repeat(self.list_depth.saturating_sub(1)));
+                self.out.push_str("- ");
+            }
+            "br" => self.line_break(),
+            "hr" => {
+                self.block_break();
+                self.out.push_str("---");
+                self.block_break();
+            }
+            "tr" => self.line_break(),
+            "td" | "th" => self.out.push_str("| "),
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('_'),
+            "code" if !self.in_pre => self.out.push('`'),
+            "pre" => {
+                self.block_break();
+                self.out.push_str("```\n");
+                self.in_pre = true;
+            }
+            "a" => {
+                let href = attribute(attrs, "href").filter(|h| {
+                    !h.is_empty() && !h.starts_with('#') && !h.starts_with("javascript:")
+                });
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.pending_links.push(href);
+            }
+            "img" => {
+                if let Some(alt) = attribute(attrs, "alt").filter(|a| !a.is_empty()) {
+                    self.out
+                        .push_str(&format!("[image: {}]", decode_entities(&alt)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close_tag(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "section" | "article"
+            | "main" | "header" | "table" | "blockquote" => self.block_break(),
+            "ul" | "ol" => {
+                self.list_depth = self.list_depth.saturating_sub(1);
+                self.line_break();
+            }
+            "td" | "th" => self.out.push(' '),
+            "tr" => self.out.push('|'),
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('_'),
+            "code" if !self.in_pre => self.out.push('`'),
+            "pre" => {
+                self.in_pre = false;
+                self.line_break();
+                self.out.push_str("```");
+                self.block_break();
+            }
+            "a" => {
+                if let Some(Some(href)) = self.pending_links.pop() {
+                    self.out.push_str(&format!("]({})", decode_entities(&href)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut result = String::new();
+        let mut blank_run = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_run += 1;
+                if blank_run > 1 {
+                    continue;
+                }
+            } else {
+                blank_run = 0;
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+        result.trim().to_string()
+    }
+}
+
+pub fn html_to_markdown(html: &str) -> String {
+    let mut writer = MarkdownWriter::new();
+    let title = extract_title(html);
+    let mut skip_until: Option<String> = None;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if skip_until.is_none() {
+                writer.push_text(&decode_entities(rest));
+            }
+            break;
+        };
+
+        if lt > 0 && skip_until.is_none() {
+            writer.push_text(&decode_entities(&rest[..lt]));
+        }
+        rest = &rest[lt..];
+
+        if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+
+        let Some(gt) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        let closing = tag.starts_with('/');
+        let tag_body = tag.trim_start_matches('/').trim_end_matches('/');
+        let (name, attrs) = match tag_body.find(|c: char| c.is_whitespace()) {
+            Some(pos) => (&tag_body[..pos], &tag_body[pos..]),
+            None => (tag_body, ""),
+        };
+        let name = name.to_ascii_lowercase();
+
+        if let Some(skipped) = &skip_until {
+            if closing && &name == skipped {
+                skip_until = None;
+            }
+            continue;
+        }
+
+        if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            if !closing && !tag.ends_with('/') {
+                skip_until = Some(name);
+            }
+            continue;
+        }
+
+        if closing {
+            writer.close_tag(&name);
+        } else {
+            writer.open_tag(&name, attrs);
+        }
+    }
+
+    let body = writer.finish();
+    match title {
+        Some(title) if !body.starts_with("# ") => format!("# {}\n\n{}", title, body),
+        _ => body,
+    }
+}
+
+fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let open_end = start + lower[start..].find('>')? + 1;
+    let close = open_end + lower[open_end..].find("</title>")?;
+    let title = decode_entities(html[open_end..close].trim());
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() { None } else { Some(title) }
+}
+
+pub(crate) fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut search_from = 0;
+    while let Some(pos) = lower[search_from..].find(name) {
+        let start = search_from + pos;
+        let after = lower[start + name.len()..].trim_start();
+        let preceded_ok = start == 0
+            || lower[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_whitespace);
+        if preceded_ok && after.starts_with('=') {
+            let value_start = attrs.len() - after.len() + 1;
+            let value = attrs[value_start..].trim_start();
+            return match value.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    value[1..].find(q).map(|end| value[1..1 + end].to_string())
+                }
+                _ => Some(
+                    value
+                        .split(|c: char| c.is_whitespace() || c == '>')
+                        .next()
+                        .unwrap_or("")
+                        .to_string(),
+                ),
+            };
+        }
+        search_from = start + name.len();
+    }
+    None
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                "copy" => Some('©'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    let mut in_script = false;
+    let mut in_style = false;
+    let mut tag_name = String::new();
+    let mut prev_char = ' ';
+
+    for c in html.chars() {
+        if c == '<' {
+            in_tag = true;
+            tag_name.clear();
+            continue;
+        }
+
+        if c == '>' && in_tag {
+            in_tag = false;
+            let tag = tag_name.to_lowercase();
+
+            if tag == "script" || tag.starts_with("script ") {
+                in_script = true;
+            } else if tag == "/script" {
+                in_script = false;
+            } else if tag == "style" || tag.starts_with("style ") {
+                in_style = true;
+            } else if tag == "/style" {
+                in_style = false;
+            } else if tag == "br" || tag == "br/" || tag == "/p" || tag == "/div" || tag == "/li" {
+                if !text.ends_with('\n') && !text.is_empty() {
+                    text.push('\n');
+                }
+            } else if tag == "li" || tag.starts_with("li ") {
+                if !text.ends_with('\n') && !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str("- ");
+            }
+            continue;
+        }
+
+        if in_tag {
+            tag_name.push(c);
+            continue;
+        }
+
+        if in_script || in_style {
+            continue;
+        }
+
+        if c == '&' {
+            continue;
+        }
+
+        if c == ';' && prev_char == '&' {
+            continue;
+        }
+
+        if c.is_whitespace() {
+            if !text.is_empty() && !text.ends_with(' ') && !text.ends_with('\n') {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+
+        prev_char = c;
+    }
+
+    text.lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_structure_to_markdown() {
+        let html = r#"<html><head><title>Docs &amp; Guides</title><style>p{}</style></head>
+            <body><nav><a href="/">Home</a></nav>
+            <h2>Install</h2>
+            <p>Run the <code>cargo</code> command, see <a href="https://example.com/x">the guide</a>.</p>
+            <ul><li>First</li><li><strong>Second</strong></li></ul>
+            <pre>fn main() {
+    println!("hi");
+}</pre>
+            <script>alert(1)</script>
+            </body></html>"#;
+
+        let md = html_to_markdown(html);
+        assert!(md.starts_with("# Docs & Guides"));
+        assert!(md.contains("## Install"));
+        assert!(md.contains("Run the `cargo` command, see [the guide](https://example.com/x)."));
+        assert!(md.contains("- First\n- **Second**"));
+        assert!(md.contains("```\nfn main() {\n    println!(\"hi\");\n}\n```"));
+        assert!(!md.contains("alert"));
+        assert!(!md.contains("Home"));
+    }
+
+    #[test]
+    fn decodes_numeric_entities() {
+        assert_eq!(
+            decode_entities("a&#38;b &#x3C;c&gt; &unknown;"),
+            "a&b <c> &unknown;"
+        );
+    }
+
+    #[test]
+    fn reads_quoted_and_bare_attributes() {
+        assert_eq!(
+            attribute(r#" class="x" href="/a b""#, "href").as_deref(),
+            Some("/a b")
+        );
+        assert_eq!(
+            attribute(" data-href=1 href=/bare", "href").as_deref(),
+            Some("/bare")
+        );
+        assert_eq!(attribute(" class='x'", "href"), None);
+    }
+}
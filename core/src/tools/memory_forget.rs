@@ -0,0 +1,136 @@
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+
+pub struct MemoryForgetTool {
+    memory: std::sync::Arc<dyn crate::traits::Memory>,
+}
+
+impl MemoryForgetTool {
+    pub fn new(memory: std::sync::Arc<dyn crate::traits::Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+fn positional_index(key: &str) -> Option<(&str, usize)> {
+    let (file, index) = key.rsplit_once(':')?;
+    index.parse().ok().map(|index| (file, index))
+}
+
+#[async_trait]
+impl Tool for MemoryForgetTool {
+    fn name(&self) -> &str {
+        "memory_forget"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Delete stale or wrong memories by key. Use keys shown by memory_list (e.g. 'MEMORY:3') or the key given to memory_write"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "keys": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Keys of the memories to delete"
+                }
+            },
+            "required": ["keys"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let mut keys: Vec<String> = match args.get("keys").or_else(|| args.get("key")) {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            Some(serde_json::Value::String(s)) => vec![s.clone()],
+            _ => Vec::new(),
+        };
+        keys.retain(|k| !k.trim().is_empty());
+        if keys.is_empty() {
+            return Ok(ToolResult::error("Keys parameter is required"));
+        }
+
+        // Positional keys shift when an earlier line in the same file is removed,
+        // so delete from the bottom of each file up.
+        keys.sort_by_key(|k| {
+            positional_index(k).map(|(file, index)| (file.to_string(), std::cmp::Reverse(index)))
+        });
+        keys.dedup();
+
+        let mut removed = Vec::new();
+        let mut missing = Vec::new();
+        for key in &keys {
+            match self.memory.forget(key).await {
+                Ok(true) => removed.push(key.as_str()),
+                Ok(false) => missing.push(key.as_str()),
+                Err(e) => {
+                    return Ok(ToolResult::error(format!(
+                        "Failed to forget '{}': {}",
+                        key, e
+                    )));
+                }
+            }
+        }
+
+        if removed.is_empty() {
+            return Ok(ToolResult::error(format!(
+                "No memories found for: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let mut output = format!("Forgot {} memories: {}", removed.len(), removed.join(", "));
+        if !missing.is_empty() {
+            output.push_str(&format!("\nNot found: {}", missing.join(", ")));
+        }
+        Ok(ToolResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::InMemoryMemory;
+    use crate::traits::{Memory, MemoryCategory};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn forgets_existing_keys_and_reports_unknown_ones() {
+        let memory = Arc::new(InMemoryMemory::new());
+        for key in ["lang", "tz"] {
+            memory
+                .store(key, "something", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let tool = MemoryForgetTool::new(memory.clone());
+
+        let result = tool
+            .execute(json!({"keys": ["lang", "pets"]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("Forgot 1 memories: lang"));
+        assert!(result.output.contains("Not found: pets"));
+        assert!(memory.get("lang").await.unwrap().is_none());
+
+        let result = tool.execute(json!({"keys": ["pets"]})).await.unwrap();
+        assert!(!result.success);
+        assert!(
+            result
+                .error
+                .unwrap()
+                .contains("No memories found for: pets")
+        );
+        assert_eq!(memory.count().await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::tools::{extract_string_arg_opt, extract_usize_arg_opt};
+use crate::traits::{MemoryCategory, Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+pub struct MemoryListTool {
+    memory: std::sync::Arc<dyn crate::traits::Memory>,
+}
+
+impl MemoryListTool {
+    pub fn new(memory: std::sync::Arc<dyn crate::traits::Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryListTool {
+    fn name(&self) -> &str {
+        "memory_list"
+    }
+
+    fn description(&self) -> &str {
+        "List stored memories with their keys, newest first. Use this to review what is remembered before pruning with memory_forget"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "category": {
+                    "type": "string",
+                    "description": "Only list this category: 'core', 'daily' or a custom name (default: all)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of entries to return (default: 50)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Number of entries to skip, for paging (default: 0)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let category_str = extract_string_arg_opt(&args, "category", "");
+        let limit = extract_usize_arg_opt(&args, "limit", DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let offset = extract_usize_arg_opt(&args, "offset", 0);
+
+        let category = match category_str.as_str() {
+            "" => None,
+            "core" => Some(MemoryCategory::Core),
+            "daily" => Some(MemoryCategory::Daily),
+            _ => Some(MemoryCategory::Custom(category_str)),
+        };
+
+        match self.memory.list(category.as_ref(), None).await {
+            Ok(entries) => {
+                if entries.is_empty() {
+                    return Ok(ToolResult::success("No memories stored.".to_string()));
+                }
+
+                let total = entries.len();
+                let formatted: Vec<String> = entries
+                    .iter()
+                    .skip(offset)
+                    .take(limit)
+                    .map(|e| {
+                        let tags = if e.meta.tags.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" #{}", e.meta.tags.join(" #"))
+                        };
+                        format!(
+                            "- [{}] ({}, {}){} {}",
+                            e.key, e.category, e.timestamp, tags, e.content
+                        )
+                    })
+                    .collect();
+
+                let mut output = format!(
+                    "Showing {} of {} memories:\n{}",
+                    formatted.len(),
+                    total,
+                    formatted.join("\n")
+                );
+                if offset + formatted.len() < total {
+                    output.push_str(&format!(
+                        "\n\n[{} more, use offset={}]",
+                        total - offset - formatted.len(),
+                        offset + formatted.len()
+                    ));
+                }
+                Ok(ToolResult::success(output))
+            }
+            Err(e) => Ok(ToolResult::error(format!("Failed to list memory: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::InMemoryMemory;
+    use crate::traits::Memory;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn lists_by_category_and_pages() {
+        let memory = Arc::new(InMemoryMemory::new());
+        let tool = MemoryListTool::new(memory.clone());
+        let result = tool.execute(json!({})).await.unwrap();
+        assert_eq!(result.output, "No memories stored.");
+
+        for (key, category) in [
+            ("lang", MemoryCategory::Core),
+            ("standup", MemoryCategory::Daily),
+            ("tz", MemoryCategory::Core),
+        ] {
+            memory.store(key, key, category, None).await.unwrap();
+        }
+
+        let result = tool.execute(json!({"category": "core"})).await.unwrap();
+        assert!(result.output.starts_with("Showing 2 of 2 memories:"));
+        assert!(!result.output.contains("[standup]"));
+
+        let result = tool
+            .execute(json!({"limit": 1, "offset": 1}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("Showing 1 of 3 memories:"));
+        assert!(result.output.contains("[1 more, use offset=2]"));
+    }
+}
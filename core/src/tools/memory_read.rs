@@ -1,5 +1,5 @@
-use crate::tools::{extract_string_arg_opt, extract_usize_arg_opt};
-use crate::traits::{Tool, ToolResult};
+use crate::tools::{extract_string_arg_opt, extract_string_list_arg, extract_usize_arg_opt};
+use crate::traits::{MemoryCategory, MemoryFilter, MemorySource, Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
 
@@ -20,7 +20,7 @@ impl Tool for MemoryReadTool {
     }
 
     fn description(&self) -> &str {
-        "Retrieve memories from the memory store using a search query"
+        "Retrieve memories from the memory store using a search query, optionally only those with given tags, source or category"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -34,21 +34,52 @@ impl Tool for MemoryReadTool {
                 "limit": {
                     "type": "integer",
                     "description": "Maximum number of results to return (default: 10)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only memories with all of these tags, e.g. ['preference']"
+                },
+                "source": {
+                    "type": "string",
+                    "enum": ["user", "agent", "tool"],
+                    "description": "Only memories from this source"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Only this category: 'core', 'daily' or a custom name"
                 }
-            },
-            "required": ["query"]
+            }
         })
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let query = extract_string_arg_opt(&args, "query", "");
         let limit = extract_usize_arg_opt(&args, "limit", 10);
+        let source = extract_string_arg_opt(&args, "source", "");
+        let category = extract_string_arg_opt(&args, "category", "");
+        let filter = MemoryFilter {
+            category: match category.as_str() {
+                "" => None,
+                "core" => Some(MemoryCategory::Core),
+                "daily" => Some(MemoryCategory::Daily),
+                _ => Some(MemoryCategory::Custom(category)),
+            },
+            tags: extract_string_list_arg(&args, "tags"),
+            source: MemorySource::parse(&source),
+        };
 
-        if query.is_empty() {
-            return Ok(ToolResult::error("Query parameter is required"));
+        if query.is_empty() && filter.is_empty() {
+            return Ok(ToolResult::error(
+                "Provide a query, or tags/source/category to filter by",
+            ));
         }
 
-        match self.memory.recall(&query, limit, None).await {
+        match self
+            .memory
+            .recall_filtered(&query, limit, None, &filter)
+            .await
+        {
             Ok(entries) => {
                 if entries.is_empty() {
                     Ok(ToolResult::success(
@@ -62,7 +93,12 @@ impl Tool for MemoryReadTool {
                                 .score
                                 .map(|s| format!(" (score: {:.2})", s))
                                 .unwrap_or_default();
-                            format!("- {}{}", e.content, score)
+                            let tags = if e.meta.tags.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" [{}]", e.meta.tags.join(", "))
+                            };
+                            format!("- {}{}{}", e.content, tags, score)
                         })
                         .collect();
                     Ok(ToolResult::success(format!(
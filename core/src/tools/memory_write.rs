@@ -1,5 +1,5 @@
-use crate::tools::{extract_string_arg, extract_string_arg_opt};
-use crate::traits::{MemoryCategory, Tool, ToolResult};
+use crate::tools::{extract_string_arg, extract_string_arg_opt, extract_string_list_arg};
+use crate::traits::{EntryMeta, MemoryCategory, MemorySource, Tool, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
 
@@ -38,6 +38,21 @@ impl Tool for MemoryWriteTool {
                 "category": {
                     "type": "string",
                     "description": "Category: 'core' for long-term facts, 'daily' for logs (default: 'core')"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Short labels for filtered recall, e.g. ['preference', 'editor']"
+                },
+                "source": {
+                    "type": "string",
+                    "enum": ["user", "agent", "tool"],
+                    "description": "Where the information came from: the user said it, you concluded it, or a tool returned it (default: 'agent')"
+                },
+                "metadata": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Extra key/value details, e.g. {\"project\": \"dinoe\"}"
                 }
             },
             "required": ["key", "content"]
@@ -59,7 +74,36 @@ impl Tool for MemoryWriteTool {
             _ => MemoryCategory::Custom(category_str),
         };
 
-        match self.memory.store(&key, &content, category, None).await {
+        let source = extract_string_arg_opt(&args, "source", "agent");
+        let Some(source) = MemorySource::parse(&source) else {
+            return Ok(ToolResult::error(format!(
+                "Unknown source '{}': use user, agent or tool",
+                source
+            )));
+        };
+        let metadata = args
+            .get("metadata")
+            .and_then(|m| m.as_object())
+            .map(|m| {
+                m.iter()
+                    .map(|(k, v)| {
+                        let value = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                        (k.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let meta = EntryMeta {
+            tags: extract_string_list_arg(&args, "tags"),
+            source: Some(source),
+            metadata,
+        };
+
+        match self
+            .memory
+            .store_with_meta(&key, &content, category, None, &meta)
+            .await
+        {
             Ok(()) => Ok(ToolResult::success(format!(
                 "Stored memory with key: {}",
                 key
@@ -1,18 +1,42 @@
 use serde_json::Value;
 use std::sync::{Arc, OnceLock};
 
+pub mod apply_patch;
+pub mod archive;
+pub mod ask_user;
+pub mod bootstrap;
+pub mod calc;
+pub mod command;
 pub mod content_search;
+pub mod data_query;
+pub mod diff;
+mod documents;
+pub mod download;
+pub mod env_info;
 pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
+pub mod git;
 pub mod git_operations;
 pub mod glob_search;
+pub mod html;
 pub mod http_request;
+pub mod memory_forget;
+pub mod memory_list;
 pub mod memory_read;
 pub mod memory_write;
+pub mod openapi;
+pub mod process;
+pub mod run_code;
+pub mod sandbox;
+pub mod schedule;
 pub mod security;
 pub mod shell;
+pub mod spawn_agent;
+pub mod todo;
+pub mod tree;
 pub mod web_fetch;
+pub mod web_search;
 
 use security::RateLimiter;
 
@@ -24,17 +48,41 @@ pub fn get_global_rate_limiter() -> Arc<RateLimiter> {
         .clone()
 }
 
+pub use apply_patch::ApplyPatchTool;
+pub use archive::{ArchiveCreateTool, ArchiveExtractTool};
+pub use ask_user::{AskUserTool, PendingQuestion, UserInputHandler};
+pub use bootstrap::UpdateBootstrapTool;
+pub use calc::CalcTool;
+pub use command::{CommandTool, load_command_tools};
 pub use content_search::ContentSearchTool;
+pub use data_query::DataQueryTool;
+pub use diff::DiffTool;
+pub use download::DownloadTool;
+pub use env_info::EnvInfoTool;
 pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
+pub use git::{GitCommitTool, GitDiffTool, GitLogTool, GitStatusTool};
 pub use git_operations::GitOperationsTool;
 pub use glob_search::GlobSearchTool;
 pub use http_request::HttpRequestTool;
+pub use memory_forget::MemoryForgetTool;
+pub use memory_list::MemoryListTool;
 pub use memory_read::MemoryReadTool;
 pub use memory_write::MemoryWriteTool;
+pub use openapi::{OpenApiTool, load_openapi_tools};
+pub use process::{
+    ProcessKillTool, ProcessLogsTool, ProcessStartTool, ProcessStatusTool, ProcessSupervisor,
+};
+pub use run_code::RunCodeTool;
+pub use sandbox::Sandbox;
+pub use schedule::{ListSchedulesTool, ScheduleTaskTool};
 pub use shell::ShellTool;
+pub use spawn_agent::SpawnAgentTool;
+pub use todo::{TodoList, TodoTool};
+pub use tree::TreeTool;
 pub use web_fetch::WebFetchTool;
+pub use web_search::{SearchEngine, SearchResult, WebSearchTool, create_search_engine};
 
 pub fn extract_string_arg(args: &Value, key: &str) -> anyhow::Result<String> {
     args.get(key)
@@ -56,3 +104,21 @@ pub fn extract_usize_arg_opt(args: &Value, key: &str, default: usize) -> usize {
         .map(|v| v as usize)
         .unwrap_or(default)
 }
+
+/// A list of strings, given either as a JSON array or as a comma-separated string.
+pub fn extract_string_list_arg(args: &Value, key: &str) -> Vec<String> {
+    match args.get(key) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
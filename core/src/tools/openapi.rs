@@ -0,0 +1,560 @@
+use crate::config::OpenApiConfig;
+use crate::traits::{Tool, ToolResult};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT_SECS: u64 = 30;
+const MAX_TOOLS_PER_SPEC: usize = 200;
+const MAX_REF_DEPTH: usize = 16;
+const MAX_DESCRIPTION_CHARS: usize = 1_000;
+const MAX_RESPONSE_CHARS: usize = 50_000;
+const METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum ParamLocation {
+    Path,
+    Query,
+    Header,
+}
+
+#[derive(Debug, Clone)]
+struct OperationParam {
+    name: String,
+    location: ParamLocation,
+}
+
+struct Api {
+    client: reqwest::Client,
+    base_url: String,
+    headers: BTreeMap<String, String>,
+}
+
+pub struct OpenApiTool {
+    api: Arc<Api>,
+    name: String,
+    description: String,
+    method: Method,
+    path: String,
+    params: Vec<OperationParam>,
+    has_body: bool,
+    schema: Value,
+}
+
+#[derive(Debug)]
+struct PreparedRequest {
+    url: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<Value>,
+}
+
+/// Loads every configured spec; specs that fail to load are logged and skipped.
+pub fn load_openapi_tools(configs: &[OpenApiConfig], workspace: &Path) -> Vec<OpenApiTool> {
+    let mut tools = Vec::new();
+    for config in configs {
+        match tools_from_config(config, workspace) {
+            Ok(generated) => tools.extend(generated),
+            Err(e) => tracing::warn!("Skipping OpenAPI spec {}: {:#}", config.spec.display(), e),
+        }
+    }
+    tools
+}
+
+fn tools_from_config(config: &OpenApiConfig, workspace: &Path) -> Result<Vec<OpenApiTool>> {
+    let path = if config.spec.is_absolute() {
+        config.spec.clone()
+    } else {
+        workspace.join(&config.spec)
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let doc: Value = if content.trim_start().starts_with('{') {
+        serde_json::from_str(&content).context("Invalid JSON")?
+    } else {
+        serde_yaml::from_str(&content).context("Invalid YAML")?
+    };
+    generate_tools(&doc, config)
+}
+
+fn generate_tools(doc: &Value, config: &OpenApiConfig) -> Result<Vec<OpenApiTool>> {
+    if !doc["openapi"].as_str().is_some_and(|v| v.starts_with('3')) {
+        bail!("Only OpenAPI 3.x documents are supported");
+    }
+    let base_url = config
+        .base_url
+        .clone()
+        .or_else(|| doc["servers"][0]["url"].as_str().map(str::to_string))
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .context("No absolute server URL in the spec; set base_url")?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .connect_timeout(Duration::from_secs(10))
+        .user_agent("Dinoe/0.2 (openapi)")
+        .build()?;
+    let api = Arc::new(Api {
+        client,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        headers: config.headers.clone(),
+    });
+
+    let Some(paths) = doc["paths"].as_object() else {
+        bail!("Spec has no paths");
+    };
+
+    let mut tools: Vec<OpenApiTool> = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(doc, item, 0);
+        for method in METHODS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let operation_id = operation["operationId"].as_str();
+            if !config.operations.is_empty()
+                && !operation_id.is_some_and(|id| config.operations.iter().any(|o| o == id))
+            {
+                continue;
+            }
+            if tools.len() >= MAX_TOOLS_PER_SPEC {
+                tracing::warn!(
+                    "OpenAPI spec has more than {MAX_TOOLS_PER_SPEC} operations; the rest are skipped. \
+                     Use 'operations' to pick the ones you need"
+                );
+                return Ok(tools);
+            }
+
+            let base_name = operation_id
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{method}_{path}"));
+            let name = tool_name(config.prefix.as_deref(), &base_name);
+            if tools.iter().any(|t| t.name == name) {
+                tracing::warn!("Skipping duplicate OpenAPI tool name '{}'", name);
+                continue;
+            }
+
+            let (schema, params, has_body) = operation_schema(doc, &item, operation);
+            tools.push(OpenApiTool {
+                api: api.clone(),
+                name,
+                description: operation_description(operation, method, path),
+                method: Method::from_bytes(method.to_uppercase().as_bytes())?,
+                path: path.clone(),
+                params,
+                has_body,
+                schema,
+            });
+        }
+    }
+    Ok(tools)
+}
+
+fn tool_name(prefix: Option<&str>, base: &str) -> String {
+    let mut name = String::new();
+    let mut prev_lower = false;
+    for c in base.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            name.push('_');
+        }
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !name.ends_with('_') {
+                name.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let name = name.trim_matches('_');
+    let name = match prefix.filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{prefix}_{name}"),
+        None => name.to_string(),
+    };
+    name.chars().take(64).collect()
+}
+
+fn operation_description(operation: &Value, method: &str, path: &str) -> String {
+    let summary = operation["summary"]
+        .as_str()
+        .or_else(|| operation["description"].as_str())
+        .unwrap_or("")
+        .trim();
+    let summary: String = summary.chars().take(MAX_DESCRIPTION_CHARS).collect();
+    let endpoint = format!("{} {}", method.to_uppercase(), path);
+    if summary.is_empty() {
+        endpoint
+    } else {
+        format!("{summary} ({endpoint})")
+    }
+}
+
+fn operation_schema(
+    doc: &Value,
+    item: &Value,
+    operation: &Value,
+) -> (Value, Vec<OperationParam>, bool) {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut params: Vec<OperationParam> = Vec::new();
+
+    // Operation-level parameters override path-level ones with the same name.
+    let declared = item["parameters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .chain(operation["parameters"].as_array().into_iter().flatten());
+    for param in declared {
+        let param = resolve(doc, param, 0);
+        let Some(name) = param["name"].as_str() else {
+            continue;
+        };
+        let location = match param["in"].as_str() {
+            Some("path") => ParamLocation::Path,
+            Some("query") => ParamLocation::Query,
+            Some("header") => ParamLocation::Header,
+            _ => continue,
+        };
+
+        let mut schema = param
+            .get("schema")
+            .map(|s| resolve_schema(doc, s, 0))
+            .unwrap_or_else(|| json!({ "type": "string" }));
+        if let (Some(obj), Some(description)) =
+            (schema.as_object_mut(), param["description"].as_str())
+        {
+            obj.insert("description".into(), description.into());
+        }
+        properties.insert(name.to_string(), schema);
+
+        let is_required = location == ParamLocation::Path || param["required"] == true;
+        params.retain(|p| p.name != name);
+        required.retain(|r: &String| r != name);
+        if is_required {
+            required.push(name.to_string());
+        }
+        params.push(OperationParam {
+            name: name.to_string(),
+            location,
+        });
+    }
+
+    let body = resolve(doc, &operation["requestBody"], 0);
+    let body_schema = body["content"]
+        .as_object()
+        .and_then(|content| {
+            content
+                .iter()
+                .find(|(media_type, _)| media_type.contains("json"))
+                .map(|(_, media)| media)
+        })
+        .map(|media| {
+            media
+                .get("schema")
+                .map(|s| resolve_schema(doc, s, 0))
+                .unwrap_or_else(|| json!({}))
+        });
+    let has_body = body_schema.is_some();
+    if let Some(mut schema) = body_schema {
+        if let Some(obj) = schema.as_object_mut() {
+            obj.entry("description")
+                .or_insert_with(|| "JSON request body".into());
+        }
+        properties.insert("body".into(), schema);
+        if body["required"] == true {
+            required.push("body".into());
+        }
+    }
+
+    let schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    });
+    (schema, params, has_body)
+}
+
+fn lookup_ref<'a>(doc: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    doc.pointer(pointer)
+}
+
+/// Follows a top-level `$ref` (parameters, request bodies, path items).
+fn resolve(doc: &Value, value: &Value, depth: usize) -> Value {
+    match value["$ref"].as_str() {
+        Some(reference) if depth < MAX_REF_DEPTH => match lookup_ref(doc, reference) {
+            Some(target) => resolve(doc, target, depth + 1),
+            None => Value::Null,
+        },
+        Some(_) => Value::Null,
+        None => value.clone(),
+    }
+}
+
+/// Inlines `$ref`s throughout a schema and maps OpenAPI 3.0 `nullable` onto JSON Schema
+/// types. Recursive schemas are cut off after a fixed number of refs with an empty schema.
+fn resolve_schema(doc: &Value, schema: &Value, depth: usize) -> Value {
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    if let Some(reference) = obj.get("$ref").and_then(|r| r.as_str()) {
+        if depth >= MAX_REF_DEPTH {
+            return json!({});
+        }
+        return match lookup_ref(doc, reference) {
+            Some(target) => resolve_schema(doc, target, depth + 1),
+            None => json!({}),
+        };
+    }
+
+    let mut out = Map::new();
+    for (key, value) in obj {
+        let resolved = match key.as_str() {
+            "nullable" | "example" | "xml" | "discriminator" => continue,
+            "properties" | "patternProperties" | "$defs" | "definitions" => match value {
+                Value::Object(children) => Value::Object(
+                    children
+                        .iter()
+                        .map(|(name, child)| (name.clone(), resolve_schema(doc, child, depth)))
+                        .collect(),
+                ),
+                other => other.clone(),
+            },
+            "allOf" | "anyOf" | "oneOf" | "prefixItems" => match value {
+                Value::Array(children) => Value::Array(
+                    children
+                        .iter()
+                        .map(|child| resolve_schema(doc, child, depth))
+                        .collect(),
+                ),
+                other => other.clone(),
+            },
+            "items" | "additionalProperties" | "not" => resolve_schema(doc, value, depth),
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), resolved);
+    }
+    if obj.get("nullable") == Some(&Value::Bool(true))
+        && let Some(Value::String(t)) = out.get("type").cloned()
+    {
+        out.insert("type".into(), json!([t, "null"]));
+    }
+    Value::Object(out)
+}
+
+fn encode_path_segment(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn scalar_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl OpenApiTool {
+    fn prepare(&self, args: &Value) -> Result<PreparedRequest, String> {
+        let mut path = self.path.clone();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+
+        for param in &self.params {
+            let Some(value) = args.get(&param.name).filter(|v| !v.is_null()) else {
+                if param.location == ParamLocation::Path {
+                    return Err(format!("Missing path parameter '{}'", param.name));
+                }
+                continue;
+            };
+            match param.location {
+                ParamLocation::Path => {
+                    path = path.replace(
+                        &format!("{{{}}}", param.name),
+                        &encode_path_segment(&scalar_string(value)),
+                    );
+                }
+                ParamLocation::Query => match value {
+                    Value::Array(items) => {
+                        query.extend(items.iter().map(|v| (param.name.clone(), scalar_string(v))))
+                    }
+                    other => query.push((param.name.clone(), scalar_string(other))),
+                },
+                ParamLocation::Header => headers.push((param.name.clone(), scalar_string(value))),
+            }
+        }
+
+        let body = if self.has_body {
+            args.get("body").filter(|v| !v.is_null()).cloned()
+        } else {
+            None
+        };
+
+        Ok(PreparedRequest {
+            url: format!("{}{}", self.api.base_url, path),
+            query,
+            headers,
+            body,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for OpenApiTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.schema.clone()
+    }
+
+    fn is_mutating(&self) -> bool {
+        !matches!(self.method, Method::GET | Method::HEAD | Method::OPTIONS)
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let request = match self.prepare(&args) {
+            Ok(request) => request,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let mut builder = self
+            .api
+            .client
+            .request(self.method.clone(), &request.url)
+            .query(&request.query);
+        for (name, value) in &self.api.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        for (name, value) in &request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+
+        let response = match builder.send().await {
+            Ok(response) => response,
+            Err(e) => return Ok(ToolResult::error(format!("Request failed: {e}"))),
+        };
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        let text = if text.chars().count() > MAX_RESPONSE_CHARS {
+            let truncated: String = text.chars().take(MAX_RESPONSE_CHARS).collect();
+            format!("{truncated}\n[... response truncated]")
+        } else {
+            text
+        };
+
+        let output = format!("HTTP {status}\n{text}");
+        if status.is_success() {
+            Ok(ToolResult::success(output))
+        } else {
+            Ok(ToolResult::error(output))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const SPEC: &str = r#"
+openapi: 3.0.3
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /invoices/{invoiceId}:
+    parameters:
+      - $ref: '#/components/parameters/InvoiceId'
+    get:
+      operationId: getInvoice
+      summary: Fetch one invoice
+      parameters:
+        - name: expand
+          in: query
+          schema: { type: array, items: { type: string } }
+    patch:
+      operationId: updateInvoice
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: '#/components/schemas/InvoicePatch' }
+components:
+  parameters:
+    InvoiceId:
+      name: invoiceId
+      in: path
+      required: true
+      schema: { type: string }
+  schemas:
+    InvoicePatch:
+      type: object
+      properties:
+        memo: { type: string, nullable: true }
+"#;
+
+    fn config() -> OpenApiConfig {
+        OpenApiConfig {
+            spec: PathBuf::from("spec.yaml"),
+            base_url: None,
+            prefix: Some("billing".into()),
+            headers: BTreeMap::new(),
+            operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn generates_tools_with_resolved_schemas_and_builds_requests() {
+        let doc: Value = serde_yaml::from_str(SPEC).unwrap();
+        let tools = generate_tools(&doc, &config()).unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["billing_get_invoice", "billing_update_invoice"]);
+
+        let get = &tools[0];
+        assert!(!get.is_mutating());
+        assert_eq!(get.schema["required"], json!(["invoiceId"]));
+        assert_eq!(
+            get.description,
+            "Fetch one invoice (GET /invoices/{invoiceId})"
+        );
+        let request = get
+            .prepare(&json!({ "invoiceId": "in/42", "expand": ["lines", "customer"] }))
+            .unwrap();
+        assert_eq!(request.url, "https://api.example.com/v1/invoices/in%2F42");
+        assert_eq!(
+            request.query,
+            [
+                ("expand".to_string(), "lines".to_string()),
+                ("expand".to_string(), "customer".to_string())
+            ]
+        );
+
+        let patch = &tools[1];
+        assert!(patch.is_mutating());
+        assert_eq!(
+            patch.schema["properties"]["body"]["properties"]["memo"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(patch.schema["required"], json!(["invoiceId", "body"]));
+    }
+}
@@ -0,0 +1,529 @@
+use crate::tools::security::{RateLimiter, validate_command};
+use crate::tools::{extract_string_arg, extract_usize_arg_opt, get_global_rate_limiter};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+const MAX_PROCESSES: usize = 8;
+/// Exited processes kept, with their logs, for status and logs calls.
+const MAX_EXITED: usize = 8;
+const MAX_LOG_LINES: usize = 2_000;
+const DEFAULT_TAIL_LINES: usize = 50;
+
+type LogBuffer = Arc<Mutex<VecDeque<String>>>;
+
+struct ManagedProcess {
+    command: String,
+    pid: Option<u32>,
+    started_at: Instant,
+    child: tokio::sync::Mutex<Child>,
+    logs: LogBuffer,
+}
+
+pub struct ProcessSupervisor {
+    workspace: PathBuf,
+    next_id: AtomicU32,
+    processes: Mutex<BTreeMap<u32, Arc<ManagedProcess>>>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl ProcessSupervisor {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            next_id: AtomicU32::new(1),
+            processes: Mutex::new(BTreeMap::new()),
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+
+    pub fn start(&self, command: &str) -> Result<u32, String> {
+        validate_command(command, &self.rate_limiter)?;
+
+        let running = self.prune();
+        if running >= MAX_PROCESSES {
+            return Err(format!(
+                "Too many managed processes ({running}). Kill one with process_kill first."
+            ));
+        }
+
+        let mut command_line = Command::new("sh");
+        command_line
+            .arg("-c")
+            .arg(command)
+            .current_dir(&self.workspace)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // In its own process group, so that killing it also kills what `sh` started.
+        #[cfg(unix)]
+        command_line.process_group(0);
+        let mut child = command_line
+            .spawn()
+            .map_err(|e| format!("Failed to start process: {e}"))?;
+
+        let logs: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        if let Some(stdout) = child.stdout.take() {
+            spawn_log_reader(stdout, logs.clone(), "");
+        }
+        if let Some(stderr) = child.stderr.take() {
+            spawn_log_reader(stderr, logs.clone(), "[stderr] ");
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let process = ManagedProcess {
+            command: command.to_string(),
+            pid: child.id(),
+            started_at: Instant::now(),
+            child: tokio::sync::Mutex::new(child),
+            logs,
+        };
+
+        self.processes
+            .lock()
+            .unwrap()
+            .insert(id, Arc::new(process));
+        Ok(id)
+    }
+
+    /// Forgets the oldest exited processes beyond `MAX_EXITED` and returns how many have
+    /// not exited. Exited ones do not count against the limit.
+    fn prune(&self) -> usize {
+        let mut processes = self.processes.lock().unwrap();
+        let exited: Vec<u32> = processes
+            .iter()
+            .filter(|(_, process)| process.has_exited())
+            .map(|(id, _)| *id)
+            .collect();
+        let surplus = exited.len().saturating_sub(MAX_EXITED);
+        for id in &exited[..surplus] {
+            processes.remove(id);
+        }
+        processes.len() - (exited.len() - surplus)
+    }
+
+    fn get(&self, id: u32) -> Option<Arc<ManagedProcess>> {
+        self.processes.lock().unwrap().get(&id).cloned()
+    }
+
+    pub async fn status(&self, id: Option<u32>) -> Result<serde_json::Value, String> {
+        let targets: Vec<(u32, Arc<ManagedProcess>)> = match id {
+            Some(id) => {
+                let process = self.get(id).ok_or_else(|| format!("No process with id {id}"))?;
+                vec![(id, process)]
+            }
+            None => self
+                .processes
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(id, p)| (*id, p.clone()))
+                .collect(),
+        };
+
+        let mut entries = Vec::with_capacity(targets.len());
+        for (id, process) in targets {
+            let state = match process.child.lock().await.try_wait() {
+                Ok(Some(status)) => match status.code() {
+                    Some(code) => format!("exited ({code})"),
+                    None => "terminated".to_string(),
+                },
+                Ok(None) => "running".to_string(),
+                Err(e) => format!("unknown ({e})"),
+            };
+            entries.push(json!({
+                "id": id,
+                "pid": process.pid,
+                "command": process.command,
+                "state": state,
+                "uptime_secs": process.started_at.elapsed().as_secs(),
+                "log_lines": process.logs.lock().unwrap().len(),
+            }));
+        }
+
+        Ok(json!({ "processes": entries }))
+    }
+
+    pub fn logs(&self, id: u32, tail: usize) -> Result<String, String> {
+        let process = self.get(id).ok_or_else(|| format!("No process with id {id}"))?;
+        let logs = process.logs.lock().unwrap();
+        let skip = logs.len().saturating_sub(tail);
+        Ok(logs.iter().skip(skip).cloned().collect::<Vec<_>>().join("\n"))
+    }
+
+    pub async fn kill(&self, id: u32) -> Result<(), String> {
+        let process = self
+            .processes
+            .lock()
+            .unwrap()
+            .remove(&id)
+            .ok_or_else(|| format!("No process with id {id}"))?;
+
+        // The group may outlive `sh` when it started something in the background.
+        #[cfg(unix)]
+        if let Some(pid) = process.pid.and_then(|pid| i32::try_from(pid).ok()) {
+            use nix::sys::signal::{Signal, killpg};
+            use nix::unistd::Pid;
+            let _ = killpg(Pid::from_raw(pid), Signal::SIGKILL);
+        }
+
+        let mut child = process.child.lock().await;
+        if let Ok(Some(_)) = child.try_wait() {
+            return Ok(());
+        }
+        child
+            .kill()
+            .await
+            .map_err(|e| format!("Failed to kill process {id}: {e}"))
+    }
+}
+
+impl ManagedProcess {
+    fn has_exited(&self) -> bool {
+        match self.child.try_lock() {
+            Ok(mut child) => matches!(child.try_wait(), Ok(Some(_))),
+            Err(_) => false,
+        }
+    }
+}
+
+fn spawn_log_reader<R>(reader: R, logs: LogBuffer, prefix: &'static str)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut logs = logs.lock().unwrap();
+            if logs.len() >= MAX_LOG_LINES {
+                logs.pop_front();
+            }
+            logs.push_back(format!("{prefix}{line}"));
+        }
+    });
+}
+
+fn extract_id(args: &serde_json::Value) -> anyhow::Result<u32> {
+    args.get("id")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))
+}
+
+pub struct ProcessStartTool {
+    supervisor: Arc<ProcessSupervisor>,
+}
+
+impl ProcessStartTool {
+    pub fn new(supervisor: Arc<ProcessSupervisor>) -> Self {
+        Self { supervisor }
+    }
+}
+
+#[async_trait]
+impl Tool for ProcessStartTool {
+    fn name(&self) -> &str {
+        "process_start"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Start a long-running command (dev server, watcher) in the background and return its id. Use process_logs to read output"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "Shell command to run in the workspace directory"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let command = extract_string_arg(&args, "command")?;
+
+        match self.supervisor.start(&command) {
+            Ok(id) => Ok(ToolResult::success(format!(
+                "Started process {id}: {command}"
+            ))),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+pub struct ProcessStatusTool {
+    supervisor: Arc<ProcessSupervisor>,
+}
+
+impl ProcessStatusTool {
+    pub fn new(supervisor: Arc<ProcessSupervisor>) -> Self {
+        Self { supervisor }
+    }
+}
+
+#[async_trait]
+impl Tool for ProcessStatusTool {
+    fn name(&self) -> &str {
+        "process_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show the state of background processes started with process_start"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "Process id (omit to list all processes)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok());
+
+        match self.supervisor.status(id).await {
+            Ok(status) => Ok(ToolResult::success(
+                serde_json::to_string_pretty(&status).unwrap_or_default(),
+            )),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+pub struct ProcessLogsTool {
+    supervisor: Arc<ProcessSupervisor>,
+}
+
+impl ProcessLogsTool {
+    pub fn new(supervisor: Arc<ProcessSupervisor>) -> Self {
+        Self { supervisor }
+    }
+}
+
+#[async_trait]
+impl Tool for ProcessLogsTool {
+    fn name(&self) -> &str {
+        "process_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Read the most recent output lines of a background process"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "Process id returned by process_start"
+                },
+                "tail": {
+                    "type": "integer",
+                    "description": "Number of lines to return from the end (default: 50)"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = extract_id(&args)?;
+        let tail = extract_usize_arg_opt(&args, "tail", DEFAULT_TAIL_LINES).min(MAX_LOG_LINES);
+
+        match self.supervisor.logs(id, tail) {
+            Ok(logs) if logs.is_empty() => Ok(ToolResult::success("(no output yet)")),
+            Ok(logs) => Ok(ToolResult::success(logs)),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+pub struct ProcessKillTool {
+    supervisor: Arc<ProcessSupervisor>,
+}
+
+impl ProcessKillTool {
+    pub fn new(supervisor: Arc<ProcessSupervisor>) -> Self {
+        Self { supervisor }
+    }
+}
+
+#[async_trait]
+impl Tool for ProcessKillTool {
+    fn name(&self) -> &str {
+        "process_kill"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Stop a background process started with process_start"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "Process id returned by process_start"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = extract_id(&args)?;
+
+        match self.supervisor.kill(id).await {
+            Ok(()) => Ok(ToolResult::success(format!("Process {id} stopped"))),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Polls `done` until it holds, failing the test after five seconds.
+    async fn wait_until(mut done: impl AsyncFnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done().await {
+            assert!(Instant::now() < deadline, "timed out");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    async fn all_exited(supervisor: &ProcessSupervisor) -> bool {
+        let status = supervisor.status(None).await.unwrap();
+        status["processes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|p| p["state"].as_str().unwrap().starts_with("exited"))
+    }
+
+    #[tokio::test]
+    async fn supervisor_captures_output_and_exit() {
+        let tmp = TempDir::new().unwrap();
+        let supervisor = ProcessSupervisor::new(tmp.path());
+
+        let id = supervisor.start("echo hello; echo oops 1>&2").unwrap();
+        wait_until(async || {
+            let logs = supervisor.logs(id, 10).unwrap();
+            logs.contains("hello") && logs.contains("[stderr] oops")
+        })
+        .await;
+
+        wait_until(async || all_exited(&supervisor).await).await;
+        let status = supervisor.status(Some(id)).await.unwrap();
+        assert_eq!(status["processes"][0]["state"], "exited (0)");
+    }
+
+    #[tokio::test]
+    async fn supervisor_kills_running_process() {
+        let tmp = TempDir::new().unwrap();
+        let supervisor = ProcessSupervisor::new(tmp.path());
+
+        let id = supervisor.start("sleep 30").unwrap();
+        supervisor.kill(id).await.unwrap();
+
+        assert!(supervisor.logs(id, 10).is_err());
+        let status = supervisor.status(None).await.unwrap();
+        assert!(status["processes"].as_array().unwrap().is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn kill_also_stops_what_the_shell_started() {
+        let tmp = TempDir::new().unwrap();
+        let supervisor = ProcessSupervisor::new(tmp.path());
+
+        let id = supervisor.start("sleep 30 & echo $!; wait").unwrap();
+        wait_until(async || !supervisor.logs(id, 1).unwrap().is_empty()).await;
+        let pid = supervisor.logs(id, 1).unwrap();
+        supervisor.kill(id).await.unwrap();
+
+        // Gone, or a zombie waiting to be reaped.
+        let stat = format!("/proc/{}/stat", pid.trim());
+        wait_until(async || {
+            std::fs::read_to_string(&stat)
+                .map(|stat| stat.contains(") Z "))
+                .unwrap_or(true)
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn exited_processes_do_not_count_against_the_limit() {
+        let tmp = TempDir::new().unwrap();
+        let supervisor = ProcessSupervisor::new(tmp.path());
+
+        for _ in 0..MAX_PROCESSES {
+            supervisor.start("true").unwrap();
+        }
+        wait_until(async || all_exited(&supervisor).await).await;
+        for _ in 0..MAX_PROCESSES {
+            supervisor.start("sleep 30").unwrap();
+        }
+        assert!(supervisor.start("sleep 30").is_err());
+
+        let status = supervisor.status(None).await.unwrap();
+        assert_eq!(
+            status["processes"].as_array().unwrap().len(),
+            MAX_PROCESSES * 2
+        );
+    }
+
+    #[tokio::test]
+    async fn forgets_the_oldest_exited_processes() {
+        let tmp = TempDir::new().unwrap();
+        let supervisor = ProcessSupervisor::new(tmp.path());
+
+        let first = supervisor.start("true").unwrap();
+        for _ in 0..MAX_EXITED {
+            supervisor.start("true").unwrap();
+        }
+        wait_until(async || all_exited(&supervisor).await).await;
+        supervisor.start("sleep 30").unwrap();
+
+        assert!(supervisor.logs(first, 10).is_err());
+        let status = supervisor.status(None).await.unwrap();
+        assert_eq!(
+            status["processes"].as_array().unwrap().len(),
+            MAX_EXITED + 1
+        );
+    }
+}
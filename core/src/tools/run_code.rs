@@ -0,0 +1,379 @@
+use crate::tools::sandbox::Sandbox;
+use crate::tools::security::{RateLimiter, sanitize_env_vars, validate_command};
+use crate::tools::{extract_string_arg, extract_usize_arg_opt, get_global_rate_limiter};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::process::Command;
+
+const DEFAULT_TIMEOUT_SECS: usize = 10;
+const MAX_TIMEOUT_SECS: usize = 120;
+const DEFAULT_MEMORY_MB: usize = 512;
+const MAX_MEMORY_MB: usize = 4096;
+const MAX_OUTPUT_BYTES: usize = 50_000;
+
+static NETWORK_ISOLATION: OnceLock<bool> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Python,
+    JavaScript,
+    Bash,
+}
+
+impl Language {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "python" | "python3" | "py" => Some(Self::Python),
+            "javascript" | "js" | "node" => Some(Self::JavaScript),
+            "bash" | "sh" | "shell" => Some(Self::Bash),
+            _ => None,
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Python => "main.py",
+            Self::JavaScript => "main.js",
+            Self::Bash => "main.sh",
+        }
+    }
+
+    fn interpreter(&self, memory_mb: usize) -> Vec<String> {
+        match self {
+            Self::Python => vec!["python3".into(), "-I".into()],
+            Self::JavaScript => vec!["node".into(), format!("--max-old-space-size={memory_mb}")],
+            Self::Bash => vec!["bash".into()],
+        }
+    }
+
+    fn limits_virtual_memory(&self) -> bool {
+        !matches!(self, Self::JavaScript)
+    }
+}
+
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create() -> std::io::Result<Self> {
+        let path =
+            std::env::temp_dir().join(format!("dinoe-run-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn network_isolation_available() -> bool {
+    *NETWORK_ISOLATION.get_or_init(|| {
+        std::process::Command::new("unshare")
+            .args(["-r", "-n", "true"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    })
+}
+
+fn truncate_output(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text.to_string();
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n[... truncated {} bytes]",
+        &text[..end],
+        text.len() - end
+    )
+}
+
+pub struct RunCodeTool {
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RunCodeTool {
+    pub fn new() -> Self {
+        Self {
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+}
+
+impl Default for RunCodeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for RunCodeTool {
+    fn name(&self) -> &str {
+        "run_code"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    /// Without bubblewrap the snippet can read and write anything the user can.
+    fn always_needs_approval(&self) -> bool {
+        !Sandbox::available()
+    }
+
+    fn description(&self) -> &str {
+        "Run a Python, JavaScript or bash snippet in a throwaway temp directory with time and memory limits. \
+         Network is disabled by default. With bubblewrap installed the snippet can only write to the temp directory \
+         and cannot see the home directory; without it, it runs as the user and needs approval. \
+         Use for quick calculations and data checks instead of writing files"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "language": {
+                    "type": "string",
+                    "enum": ["python", "javascript", "bash"],
+                    "description": "Language of the snippet"
+                },
+                "code": {
+                    "type": "string",
+                    "description": "Source code to execute; print results to stdout"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Wall-clock limit in seconds (default: 10, max: 120)"
+                },
+                "memory_mb": {
+                    "type": "integer",
+                    "description": "Memory limit in megabytes (default: 512, max: 4096)"
+                },
+                "network": {
+                    "type": "boolean",
+                    "description": "Allow network access (default: false)"
+                }
+            },
+            "required": ["language", "code"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let language_name = extract_string_arg(&args, "language")?;
+        let code = extract_string_arg(&args, "code")?;
+        let timeout_secs = extract_usize_arg_opt(&args, "timeout_secs", DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS);
+        let memory_mb =
+            extract_usize_arg_opt(&args, "memory_mb", DEFAULT_MEMORY_MB).clamp(16, MAX_MEMORY_MB);
+        let network = args
+            .get("network")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let Some(language) = Language::parse(&language_name) else {
+            return Ok(ToolResult::error(format!(
+                "Unsupported language: {language_name}. Use python, javascript or bash"
+            )));
+        };
+
+        if language == Language::Bash {
+            if let Err(e) = validate_command(&code, &self.rate_limiter) {
+                return Ok(ToolResult::error(e));
+            }
+        } else if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many code runs. Please wait a moment.",
+            ));
+        }
+
+        let sandboxed = Sandbox::available();
+        if !network && !sandboxed && !network_isolation_available() {
+            return Ok(ToolResult::error(
+                "Network isolation (unshare) is not available on this system. \
+                 Pass network=true to run without it",
+            ));
+        }
+
+        let scratch = match ScratchDir::create() {
+            Ok(dir) => dir,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to create temp directory: {e}"
+                )));
+            }
+        };
+        let script = scratch.path().join(language.file_name());
+        if let Err(e) = tokio::fs::write(&script, &code).await {
+            return Ok(ToolResult::error(format!("Failed to write snippet: {e}")));
+        }
+
+        let mut limits = format!("ulimit -t {timeout_secs}; ulimit -f {};", 100 * 1024);
+        if language.limits_virtual_memory() {
+            limits.push_str(&format!(" ulimit -v {};", memory_mb * 1024));
+        }
+
+        let mut command = if sandboxed {
+            let sandbox = Sandbox {
+                write: vec![scratch.path().to_path_buf()],
+                network,
+                ..Default::default()
+            };
+            let run = format!(
+                "{limits} exec {} {}",
+                language.interpreter(memory_mb).join(" "),
+                language.file_name()
+            );
+            sandbox.command(scratch.path(), &run)
+        } else {
+            let mut c = if network {
+                Command::new("sh")
+            } else {
+                let mut c = Command::new("unshare");
+                c.args(["-r", "-n", "sh"]);
+                c
+            };
+            c.arg("-c")
+                .arg(format!("{limits} exec \"$@\""))
+                .arg("sh")
+                .args(language.interpreter(memory_mb))
+                .arg(language.file_name())
+                .current_dir(scratch.path())
+                .env_clear()
+                .envs(sanitize_env_vars(&std::env::vars().collect::<Vec<_>>()));
+            c
+        };
+        command
+            .env("TMPDIR", scratch.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let child = match command.spawn() {
+            Ok(c) => c,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to start interpreter: {e}"
+                )));
+            }
+        };
+
+        let output = match tokio::time::timeout(
+            Duration::from_secs(timeout_secs as u64),
+            child.wait_with_output(),
+        )
+        .await
+        {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => return Ok(ToolResult::error(format!("Execution failed: {e}"))),
+            Err(_) => {
+                return Ok(ToolResult::error(format!(
+                    "Execution timed out after {timeout_secs}s"
+                )));
+            }
+        };
+
+        let stdout = truncate_output(&output.stdout);
+        let stderr = truncate_output(&output.stderr);
+
+        if output.status.success() {
+            let mut result = stdout;
+            if !stderr.trim().is_empty() {
+                result.push_str(&format!("\n[stderr]\n{stderr}"));
+            }
+            if result.trim().is_empty() {
+                result = "(no output)".to_string();
+            }
+            Ok(ToolResult::success(result))
+        } else {
+            let status = match output.status.code() {
+                Some(code) => format!("exit code {code}"),
+                None => "killed by signal (time or memory limit)".to_string(),
+            };
+            let mut error = format!("Snippet failed ({status})");
+            if !stderr.trim().is_empty() {
+                error.push_str(&format!("\n[stderr]\n{stderr}"));
+            }
+            if !stdout.trim().is_empty() {
+                error.push_str(&format!("\n[stdout]\n{stdout}"));
+            }
+            Ok(ToolResult::error(error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn asks_first_unless_sandboxed() {
+        let tool = RunCodeTool::new();
+        assert_eq!(tool.always_needs_approval(), !Sandbox::available());
+        if !Sandbox::available() {
+            return;
+        }
+
+        let outside = tempfile::TempDir::new().unwrap();
+        let result = tool
+            .execute(json!({
+                "language": "bash",
+                "code": format!("touch {}/escaped", outside.path().display()),
+                "network": true
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(!outside.path().join("escaped").exists());
+    }
+
+    #[tokio::test]
+    async fn runs_bash_snippet_in_scratch_dir() {
+        let tool = RunCodeTool::new();
+        let result = tool
+            .execute(json!({
+                "language": "bash",
+                "code": "echo $((6 * 7)); touch made.txt; ls",
+                "network": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("42"));
+        assert!(result.output.contains("made.txt"));
+    }
+
+    #[tokio::test]
+    async fn enforces_wall_clock_timeout() {
+        let tool = RunCodeTool::new();
+        let result = tool
+            .execute(json!({
+                "language": "bash",
+                "code": "sleep 5",
+                "timeout_secs": 1,
+                "network": true
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out"));
+    }
+}
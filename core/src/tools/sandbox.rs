@@ -0,0 +1,125 @@
+use crate::tools::security::sanitize_env_vars;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use tokio::process::Command;
+
+static BUBBLEWRAP: OnceLock<bool> = OnceLock::new();
+
+/// What a command run in a sandbox may reach. It sees the filesystem read-only and
+/// without the home directory, except for the paths listed here, and gets a clean
+/// environment. Needs bubblewrap (`bwrap`); see [`Sandbox::available`].
+#[derive(Debug, Clone, Default)]
+pub struct Sandbox {
+    /// Paths it may read, even inside one of `write`.
+    pub read: Vec<PathBuf>,
+    /// Paths it may read and write.
+    pub write: Vec<PathBuf>,
+    pub network: bool,
+    /// Environment variables kept besides PATH, HOME and the like.
+    pub env: Vec<String>,
+}
+
+impl Sandbox {
+    /// Whether this system can run commands in a sandbox. Without it, [`Sandbox::command`]
+    /// only cleans the environment.
+    pub fn available() -> bool {
+        *BUBBLEWRAP.get_or_init(|| {
+            std::process::Command::new("bwrap")
+                .args(["--ro-bind", "/", "/", "--unshare-net", "true"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        })
+    }
+
+    /// `sh -c command`, run in `dir` inside the sandbox when it is available.
+    pub fn command(&self, dir: &Path, command: &str) -> Command {
+        let mut child = if Self::available() {
+            let mut child = Command::new("bwrap");
+            child.args(self.bwrap_args(dir));
+            child
+        } else {
+            Command::new("sh")
+        };
+        let env: Vec<(String, String)> = std::env::vars().collect();
+        child
+            .arg("-c")
+            .arg(command)
+            .current_dir(dir)
+            .env_clear()
+            .envs(sanitize_env_vars(&env))
+            .envs(env.into_iter().filter(|(key, _)| self.env.contains(key)));
+        child
+    }
+
+    /// Arguments to `bwrap` up to the `sh` it runs.
+    fn bwrap_args(&self, dir: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = ["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        // Hidden first, so the paths below can be bound back inside them.
+        args.extend(["--tmpfs".into(), "/tmp".into()]);
+        if let Some(home) = dirs::home_dir() {
+            args.extend(["--tmpfs".into(), home.into_os_string()]);
+        }
+        // Read-only last, so a directory inside a writable one stays read-only.
+        for (flag, paths) in [("--bind-try", &self.write), ("--ro-bind-try", &self.read)] {
+            for path in paths {
+                args.extend([flag.into(), path.into(), path.into()]);
+            }
+        }
+        if !self.network {
+            args.push("--unshare-net".into());
+        }
+        args.extend(["--die-with-parent", "--new-session", "--chdir"].map(OsString::from));
+        args.extend([dir.into(), "sh".into()]);
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_only_the_listed_paths() {
+        let sandbox = Sandbox {
+            read: vec![PathBuf::from("/work/skills")],
+            write: vec![PathBuf::from("/work")],
+            ..Default::default()
+        };
+        let args: Vec<String> = sandbox
+            .bwrap_args(Path::new("/work"))
+            .into_iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        let args = args.join(" ");
+        assert!(args.starts_with("--ro-bind / / --dev /dev --proc /proc --tmpfs /tmp"));
+        assert!(args.contains("--bind-try /work /work --ro-bind-try /work/skills /work/skills"));
+        assert!(args.ends_with("--unshare-net --die-with-parent --new-session --chdir /work sh"));
+    }
+
+    #[tokio::test]
+    async fn keeps_only_listed_environment_variables() {
+        let sandbox = Sandbox {
+            write: vec![std::env::temp_dir()],
+            env: vec!["CARGO_PKG_NAME".into()],
+            network: true,
+            ..Default::default()
+        };
+        let output = sandbox
+            .command(&std::env::temp_dir(), "env")
+            .output()
+            .await
+            .unwrap();
+        let env = String::from_utf8_lossy(&output.stdout);
+        assert!(env.contains("CARGO_PKG_NAME=dinoe-core"), "{env}");
+        assert!(!env.contains("CARGO_PKG_VERSION="), "{env}");
+    }
+}
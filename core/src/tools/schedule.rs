@@ -0,0 +1,284 @@
+use crate::scheduler::{JobStore, Schedule, ScheduledJob};
+use crate::tools::extract_string_arg_opt;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::Path;
+
+const MAX_JOBS: usize = 100;
+
+pub struct ScheduleTaskTool {
+    store: JobStore,
+}
+
+impl ScheduleTaskTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            store: JobStore::new(workspace.as_ref()),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleTaskTool {
+    fn name(&self) -> &str {
+        "schedule_task"
+    }
+
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a prompt to run later, once ('at') or repeatedly ('cron', e.g. '0 8 * * *' for every morning at 8). \
+         Jobs run while `dinoe scheduler run` is active. Use action=cancel with an id to remove a job"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "cancel"],
+                    "description": "add a job (default) or cancel one by id"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Short label for the job"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "Instruction the agent runs at each trigger, e.g. 'Remind the user to review open PRs'"
+                },
+                "cron": {
+                    "type": "string",
+                    "description": "Cron expression in local time: minute hour day-of-month month day-of-week"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "One-off run time in local time, 'YYYY-MM-DD HH:MM'"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Job id to cancel (see list_schedules)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = extract_string_arg_opt(&args, "action", "add");
+
+        if action == "cancel" {
+            let id = extract_string_arg_opt(&args, "id", "");
+            if id.is_empty() {
+                return Ok(ToolResult::error("Missing 'id' parameter"));
+            }
+            return match self.store.remove(&id) {
+                Ok(true) => Ok(ToolResult::success(format!("Cancelled job {id}"))),
+                Ok(false) => Ok(ToolResult::error(format!("No scheduled job with id {id}"))),
+                Err(e) => Ok(ToolResult::error(format!(
+                    "Failed to update schedules: {e}"
+                ))),
+            };
+        }
+        if action != "add" {
+            return Ok(ToolResult::error(format!(
+                "Unknown action: {action}. Use add or cancel"
+            )));
+        }
+
+        let prompt = extract_string_arg_opt(&args, "prompt", "");
+        if prompt.trim().is_empty() {
+            return Ok(ToolResult::error("Missing 'prompt' parameter"));
+        }
+        let cron = extract_string_arg_opt(&args, "cron", "");
+        let at = extract_string_arg_opt(&args, "at", "");
+
+        let schedule = match (cron.trim().is_empty(), at.trim().is_empty()) {
+            (false, true) => Schedule::cron(&cron),
+            (true, false) => Schedule::once(&at),
+            _ => {
+                return Ok(ToolResult::error(
+                    "Provide exactly one of 'cron' (recurring) or 'at' (one-off)",
+                ));
+            }
+        };
+        let schedule = match schedule {
+            Ok(s) => s,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
+
+        let mut name = extract_string_arg_opt(&args, "name", "");
+        if name.trim().is_empty() {
+            name = prompt.chars().take(40).collect();
+        }
+        let job = match ScheduledJob::new(&name, &prompt, schedule) {
+            Ok(job) => job,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
+
+        let summary = format!(
+            "Scheduled job {} '{}' ({}), next run {}",
+            job.id,
+            job.name,
+            job.schedule.describe(),
+            job.next_run
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
+        );
+        let added = self.store.update(|jobs| {
+            if jobs.len() >= MAX_JOBS {
+                return false;
+            }
+            jobs.push(job);
+            true
+        });
+        match added {
+            Ok(true) => Ok(ToolResult::success(format!(
+                "{summary}. Jobs run while `dinoe scheduler run` is active."
+            ))),
+            Ok(false) => Ok(ToolResult::error(format!(
+                "Too many scheduled jobs ({MAX_JOBS}). Cancel some first"
+            ))),
+            Err(e) => Ok(ToolResult::error(format!("Failed to save schedule: {e}"))),
+        }
+    }
+}
+
+pub struct ListSchedulesTool {
+    store: JobStore,
+}
+
+impl ListSchedulesTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            store: JobStore::new(workspace.as_ref()),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ListSchedulesTool {
+    fn name(&self) -> &str {
+        "list_schedules"
+    }
+
+    fn description(&self) -> &str {
+        "List scheduled jobs with their ids, schedules, next and last runs"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let jobs = match self.store.load() {
+            Ok(jobs) => jobs,
+            Err(e) => return Ok(ToolResult::error(format!("Failed to read schedules: {e}"))),
+        };
+        if jobs.is_empty() {
+            return Ok(ToolResult::success("No scheduled jobs."));
+        }
+
+        let format_time = |t: Option<chrono::DateTime<chrono::Local>>| {
+            t.map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let lines: Vec<String> = jobs
+            .iter()
+            .map(|job| {
+                format!(
+                    "- {} '{}' ({}){}\n  next: {}, last: {}, runs: {}\n  prompt: {}",
+                    job.id,
+                    job.name,
+                    job.schedule.describe(),
+                    if job.enabled { "" } else { " [finished]" },
+                    format_time(job.next_run),
+                    format_time(job.last_run),
+                    job.runs,
+                    job.prompt
+                )
+            })
+            .collect();
+        Ok(ToolResult::success(format!(
+            "{} scheduled jobs:\n{}",
+            jobs.len(),
+            lines.join("\n")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn schedules_lists_and_cancels_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let schedule = ScheduleTaskTool::new(tmp.path());
+        let list = ListSchedulesTool::new(tmp.path());
+
+        let result = schedule
+            .execute(json!({"prompt": "Summarize my TODOs", "cron": "0 9 * * 1-5"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let jobs = JobStore::new(tmp.path()).load().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].name, "Summarize my TODOs");
+
+        let listed = list.execute(json!({})).await.unwrap();
+        assert!(listed.output.starts_with("1 scheduled jobs:"));
+        assert!(listed.output.contains(&jobs[0].id));
+
+        let result = schedule
+            .execute(json!({"action": "cancel", "id": jobs[0].id}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let result = schedule
+            .execute(json!({"action": "cancel", "id": jobs[0].id}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("No scheduled job"));
+        assert_eq!(
+            list.execute(json!({})).await.unwrap().output,
+            "No scheduled jobs."
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_schedules_and_too_many_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let schedule = ScheduleTaskTool::new(tmp.path());
+
+        let result = schedule
+            .execute(json!({"prompt": "Ping", "cron": "0 9 * * *", "at": "2030-01-01 09:00"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("exactly one"));
+        let result = schedule
+            .execute(json!({"prompt": "Ping", "cron": "whenever"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        for _ in 0..MAX_JOBS {
+            JobStore::new(tmp.path())
+                .add(ScheduledJob::new("n", "Ping", Schedule::cron("0 9 * * *").unwrap()).unwrap())
+                .unwrap();
+        }
+        let result = schedule
+            .execute(json!({"prompt": "Ping", "cron": "0 9 * * *"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Too many scheduled jobs"));
+        assert_eq!(JobStore::new(tmp.path()).load().unwrap().len(), MAX_JOBS);
+    }
+}
@@ -266,6 +266,49 @@ pub fn validate_workspace_path(path: &str, workspace: &Path) -> Result<PathBuf,
     Ok(canonical_full)
 }
 
+pub fn resolve_workspace_target(path: &str, workspace: &Path) -> Result<PathBuf, String> {
+    if Path::new(path).is_absolute() {
+        return Err(format!("Absolute paths are not allowed: {path}"));
+    }
+    if Path::new(path)
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(format!("Path traversal ('..') is not allowed: {path}"));
+    }
+    if !is_path_allowed(path) {
+        return Err(format!("Path contains forbidden patterns: {path}"));
+    }
+
+    let workspace = workspace
+        .canonicalize()
+        .map_err(|e| format!("Cannot resolve workspace directory: {e}"))?;
+    let full_path = workspace.join(path);
+
+    let mut existing = full_path.parent();
+    while let Some(dir) = existing {
+        if dir.exists() {
+            break;
+        }
+        existing = dir.parent();
+    }
+    let resolved_parent = existing
+        .ok_or_else(|| format!("Invalid path: {path}"))?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {path}: {e}"))?;
+    if !resolved_parent.starts_with(&workspace) {
+        return Err(format!("Path is outside the workspace: {path}"));
+    }
+
+    if let Ok(meta) = std::fs::symlink_metadata(&full_path)
+        && meta.file_type().is_symlink()
+    {
+        return Err(format!("Refusing to write through symlink: {path}"));
+    }
+
+    Ok(full_path)
+}
+
 fn expand_home(path: &str) -> String {
     if (path == "~" || path.starts_with("~/"))
         && let Some(home) = std::env::var_os("HOME")
@@ -379,4 +422,13 @@ mod tests {
         assert!(validate_command("ls -la", &limiter).is_ok());
         assert!(validate_command("git status", &limiter).is_ok());
     }
+
+    #[test]
+    fn test_resolve_workspace_target() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let resolved = resolve_workspace_target("new/dir/file.bin", tmp.path()).unwrap();
+        assert!(resolved.ends_with("new/dir/file.bin"));
+        assert!(resolve_workspace_target("../escape.txt", tmp.path()).is_err());
+        assert!(resolve_workspace_target("/tmp/abs.txt", tmp.path()).is_err());
+    }
 }
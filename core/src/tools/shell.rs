@@ -1,10 +1,15 @@
 use crate::tools::{extract_string_arg, get_global_rate_limiter};
 use crate::tools::security::validate_command;
-use crate::traits::{Tool, ToolResult};
+use crate::traits::{Tool, ToolProgress, ToolResult};
 use async_trait::async_trait;
 use serde_json::json;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
 
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
 pub struct ShellTool {
     workspace: std::path::PathBuf,
     rate_limiter: std::sync::Arc<crate::tools::security::RateLimiter>,
@@ -19,12 +24,40 @@ impl ShellTool {
     }
 }
 
+/// Collects a pipe's output, reporting its latest line at most once per interval.
+async fn read_reporting(pipe: Option<impl AsyncRead + Unpin>, progress: &ToolProgress) -> Vec<u8> {
+    let Some(pipe) = pipe else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(pipe);
+    let mut output = Vec::new();
+    let mut last_report: Option<Instant> = None;
+    loop {
+        let start = output.len();
+        match reader.read_until(b'\n', &mut output).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = String::from_utf8_lossy(&output[start..]);
+        let line = line.trim();
+        if !line.is_empty() && last_report.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            progress.report(line);
+            last_report = Some(Instant::now());
+        }
+    }
+    output
+}
+
 #[async_trait]
 impl Tool for ShellTool {
     fn name(&self) -> &str {
         "shell"
     }
 
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
     fn description(&self) -> &str {
         "Execute a shell command in the workspace directory"
     }
@@ -43,30 +76,55 @@ impl Tool for ShellTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.execute_with_progress(args, ToolProgress::none()).await
+    }
+
+    async fn execute_with_progress(
+        &self,
+        args: serde_json::Value,
+        progress: ToolProgress,
+    ) -> anyhow::Result<ToolResult> {
         let command = extract_string_arg(&args, "command")?;
 
         if let Err(e) = validate_command(&command, &self.rate_limiter) {
             return Ok(ToolResult::error(e));
         }
 
-        let output = Command::new("sh")
+        let child = Command::new("sh")
             .arg("-c")
             .arg(&command)
             .current_dir(&self.workspace)
-            .output()
-            .await;
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+
+        let output = match child {
+            Ok(mut child) => {
+                let stdout = child.stdout.take();
+                let stderr = child.stderr.take();
+                let (stdout, stderr, status) = tokio::join!(
+                    read_reporting(stdout, &progress),
+                    read_reporting(stderr, &progress),
+                    child.wait()
+                );
+                status.map(|status| (status, stdout, stderr))
+            }
+            Err(e) => Err(e),
+        };
 
         match output {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            Ok((status, stdout, stderr)) => {
+                let stdout = String::from_utf8_lossy(&stdout).to_string();
+                let stderr = String::from_utf8_lossy(&stderr).to_string();
 
-                if output.status.success() {
+                if status.success() {
                     let result = if stdout.is_empty() { stderr } else { stdout };
                     Ok(ToolResult::success(result))
                 } else {
                     let error = if stderr.is_empty() {
-                        format!("Command failed with status: {}", output.status)
+                        format!("Command failed with status: {}", status)
                     } else {
                         stderr
                     };
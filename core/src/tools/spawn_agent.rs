@@ -0,0 +1,223 @@
+use crate::agent::{AgentEvent, AgentLoop, ContextBuilder, EventSink, ToolRegistry, TurnOutcome};
+use crate::tools::{extract_string_arg, extract_usize_arg_opt};
+use crate::traits::{Provider, Tool, ToolProgress, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+
+const DEFAULT_MAX_ITERATIONS: usize = 10;
+const MAX_SUMMARY_CHARS: usize = 8_000;
+const EXCLUDED_TOOLS: &[&str] = &["spawn_agent", "ask_user", "todo"];
+
+pub struct SpawnAgentTool {
+    provider: Arc<dyn Provider>,
+    registry: Weak<ToolRegistry>,
+    workspace: PathBuf,
+    model_name: String,
+    temperature: f64,
+    max_iterations: usize,
+    context_window: Option<usize>,
+}
+
+impl SpawnAgentTool {
+    pub fn new(
+        provider: Arc<dyn Provider>,
+        registry: &Arc<ToolRegistry>,
+        workspace: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            provider,
+            registry: Arc::downgrade(registry),
+            workspace: workspace.as_ref().to_path_buf(),
+            model_name: "openai/gpt-5-mini".to_string(),
+            temperature: 1.0,
+            max_iterations: 20,
+            context_window: None,
+        }
+    }
+
+    pub fn with_model_name(mut self, model_name: String) -> Self {
+        self.model_name = model_name;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max: usize) -> Self {
+        self.max_iterations = max;
+        self
+    }
+
+    pub fn with_context_window(mut self, tokens: usize) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    fn select_tools(
+        &self,
+        parent: &ToolRegistry,
+        requested: Option<Vec<String>>,
+    ) -> Result<Vec<String>, String> {
+        let available: Vec<String> = parent
+            .tool_names()
+            .into_iter()
+            .filter(|n| !EXCLUDED_TOOLS.contains(&n.as_str()))
+            .collect();
+
+        let Some(requested) = requested else {
+            return Ok(available
+                .into_iter()
+                .filter(|n| parent.is_mutating(n) == Some(false))
+                .collect());
+        };
+
+        let unknown: Vec<&str> = requested
+            .iter()
+            .filter(|n| !available.contains(n))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Tools not available to sub-agents: {}. Choose from: {}",
+                unknown.join(", "),
+                available.join(", ")
+            ));
+        }
+        Ok(requested)
+    }
+}
+
+fn sub_agent_prompt(task: &str, context: Option<&str>) -> String {
+    let mut prompt = String::from(
+        "You are a sub-agent working on one focused task for another agent. \
+         Use your tools as needed, then reply with a concise summary of what you found or did, \
+         including file paths, commands and facts the parent needs. Do not ask questions.\n\n",
+    );
+    if let Some(context) = context {
+        prompt.push_str(&format!("## Context\n\n{context}\n\n"));
+    }
+    prompt.push_str(&format!("## Task\n\n{task}"));
+    prompt
+}
+
+#[async_trait]
+impl Tool for SpawnAgentTool {
+    fn name(&self) -> &str {
+        "spawn_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Delegate a focused sub-task (e.g. research a question, survey a codebase) to a sub-agent with a fresh context \
+         and a restricted tool set. Only its final summary is returned, keeping your context small"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "task": {
+                    "type": "string",
+                    "description": "What the sub-agent should do and what its summary should contain"
+                },
+                "context": {
+                    "type": "string",
+                    "description": "Background the sub-agent needs (it does not see this conversation)"
+                },
+                "tools": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tools the sub-agent may use (default: all read-only tools)"
+                },
+                "max_iterations": {
+                    "type": "integer",
+                    "description": "Maximum tool-use rounds for the sub-agent (default: 10)"
+                }
+            },
+            "required": ["task"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.execute_with_progress(args, ToolProgress::none()).await
+    }
+
+    async fn execute_with_progress(
+        &self,
+        args: serde_json::Value,
+        progress: ToolProgress,
+    ) -> anyhow::Result<ToolResult> {
+        let task = extract_string_arg(&args, "task")?;
+        if task.trim().is_empty() {
+            return Ok(ToolResult::error("'task' cannot be empty"));
+        }
+        let context = args
+            .get("context")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty());
+        let requested = args.get("tools").and_then(|v| v.as_array()).map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        });
+        let max_iterations = extract_usize_arg_opt(&args, "max_iterations", DEFAULT_MAX_ITERATIONS)
+            .clamp(1, self.max_iterations.max(1));
+
+        let Some(parent) = self.registry.upgrade() else {
+            return Ok(ToolResult::error("Tool registry is no longer available"));
+        };
+        let tool_names = match self.select_tools(&parent, requested) {
+            Ok(names) => names,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let registry = Arc::new(parent.subset(&tool_names));
+        let context_builder =
+            ContextBuilder::new(&self.workspace).with_tool_specs(registry.get_specs());
+        let mut agent = AgentLoop::new(self.provider.clone(), context_builder, registry)
+            .with_max_iterations(max_iterations)
+            .with_model_name(self.model_name.clone())
+            .with_temperature(self.temperature);
+        if let Some(tokens) = self.context_window {
+            agent = agent.with_context_window(tokens);
+        }
+
+        let events = EventSink::new(move |event| {
+            if let AgentEvent::ToolStarted { name } = event {
+                tracing::debug!(tool = %name, "sub-agent tool call");
+                progress.report(format!("running {name}"));
+            }
+        });
+
+        let outcome = agent
+            .process_with_events(&sub_agent_prompt(&task, context), vec![], events)
+            .await;
+
+        match outcome {
+            TurnOutcome::MaxIterations { partial } => {
+                let mut message = format!(
+                    "Sub-agent did not finish within {max_iterations} iterations. Narrow the task or raise max_iterations"
+                );
+                if let Some(partial) = partial {
+                    message.push_str(&format!("\nLast progress: {partial}"));
+                }
+                Ok(ToolResult::error(message))
+            }
+            TurnOutcome::Completed { text: summary, .. } => {
+                let summary = if summary.chars().count() > MAX_SUMMARY_CHARS {
+                    let truncated: String = summary.chars().take(MAX_SUMMARY_CHARS).collect();
+                    format!("{truncated}\n[... summary truncated]")
+                } else {
+                    summary
+                };
+                Ok(ToolResult::success(summary))
+            }
+            TurnOutcome::Cancelled => Ok(ToolResult::error("Sub-agent was cancelled")),
+            TurnOutcome::Error(e) => Ok(ToolResult::error(format!("Sub-agent failed: {e}"))),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::tools::extract_string_arg;
+use crate::tools::file_write::write_atomic;
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+pub const TODO_FILE: &str = "todo.json";
+const MAX_ITEMS: usize = 100;
+const MAX_TEXT_CHARS: usize = 500;
+
+static TODO_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TodoItem {
+    pub id: usize,
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TodoList {
+    #[serde(default)]
+    pub items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    pub fn path(workspace: &Path) -> PathBuf {
+        workspace.join(TODO_FILE)
+    }
+
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(Self::path(workspace))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    async fn save(&self, workspace: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        write_atomic(&Self::path(workspace), &content).await
+    }
+
+    fn add(&mut self, text: &str) -> Result<usize, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Todo text cannot be empty".to_string());
+        }
+        if self.items.len() >= MAX_ITEMS {
+            return Err(format!(
+                "Todo list is full ({MAX_ITEMS} items). Clear completed items first"
+            ));
+        }
+        let id = self.items.iter().map(|i| i.id).max().unwrap_or(0) + 1;
+        self.items.push(TodoItem {
+            id,
+            text: text.chars().take(MAX_TEXT_CHARS).collect(),
+            done: false,
+        });
+        Ok(id)
+    }
+
+    fn complete(&mut self, id: usize) -> Result<(), String> {
+        match self.items.iter_mut().find(|i| i.id == id) {
+            Some(item) => {
+                item.done = true;
+                Ok(())
+            }
+            None => Err(format!("No todo item with id {id}")),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        if self.items.is_empty() {
+            return "(no tasks)".to_string();
+        }
+        let done = self.items.iter().filter(|i| i.done).count();
+        let mut lines = vec![format!("{done}/{} done", self.items.len())];
+        for item in &self.items {
+            let mark = if item.done { "x" } else { " " };
+            lines.push(format!("- [{mark}] {}. {}", item.id, item.text));
+        }
+        lines.join("\n")
+    }
+}
+
+pub struct TodoTool {
+    workspace: PathBuf,
+}
+
+impl TodoTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for TodoTool {
+    fn name(&self) -> &str {
+        "todo"
+    }
+
+    fn description(&self) -> &str {
+        "Maintain the task plan for multi-step work. Add the steps up front, complete them as you go and list to review. \
+         The plan is saved in the workspace and shown in the system prompt"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "complete", "list", "clear"],
+                    "description": "add: append tasks, complete: mark a task done, list: show the plan, clear: remove tasks (completed only unless all=true)"
+                },
+                "items": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Task descriptions to add (for add)"
+                },
+                "id": {
+                    "type": "integer",
+                    "description": "Task id to mark done (for complete)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "For clear: remove every task, not just completed ones"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = extract_string_arg(&args, "action")?;
+        let _guard = TODO_LOCK.lock().await;
+        let mut list = TodoList::load(&self.workspace);
+
+        let message = match action.as_str() {
+            "list" => return Ok(ToolResult::success(list.render())),
+            "add" => {
+                let texts: Vec<String> = match args.get("items") {
+                    Some(serde_json::Value::Array(items)) => items
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect(),
+                    Some(serde_json::Value::String(s)) => vec![s.clone()],
+                    _ => args
+                        .get("text")
+                        .and_then(|v| v.as_str())
+                        .map(|s| vec![s.to_string()])
+                        .unwrap_or_default(),
+                };
+                if texts.is_empty() {
+                    return Ok(ToolResult::error("'items' must list at least one task"));
+                }
+                let mut ids = Vec::new();
+                for text in &texts {
+                    match list.add(text) {
+                        Ok(id) => ids.push(id.to_string()),
+                        Err(e) => return Ok(ToolResult::error(e)),
+                    }
+                }
+                format!("Added task(s) {}", ids.join(", "))
+            }
+            "complete" => {
+                let Some(id) = args.get("id").and_then(|v| v.as_u64()) else {
+                    return Ok(ToolResult::error("Missing 'id' parameter"));
+                };
+                if let Err(e) = list.complete(id as usize) {
+                    return Ok(ToolResult::error(e));
+                }
+                format!("Completed task {id}")
+            }
+            "clear" => {
+                let all = args.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+                let before = list.items.len();
+                list.items.retain(|i| !all && !i.done);
+                format!("Removed {} task(s)", before - list.items.len())
+            }
+            other => {
+                return Ok(ToolResult::error(format!(
+                    "Unknown action: {other}. Use add, complete, list or clear"
+                )));
+            }
+        };
+
+        if let Err(e) = list.save(&self.workspace).await {
+            return Ok(ToolResult::error(format!("Failed to save todo list: {e}")));
+        }
+        Ok(ToolResult::success(format!(
+            "{message}\n\n{}",
+            list.render()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn add_complete_and_persist() {
+        let tmp = TempDir::new().unwrap();
+        let tool = TodoTool::new(tmp.path());
+
+        let result = tool
+            .execute(json!({"action": "add", "items": ["Read config", "Write tests"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let result = tool
+            .execute(json!({"action": "complete", "id": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("- [x] 1. Read config"));
+        assert!(result.output.contains("- [ ] 2. Write tests"));
+
+        let list = TodoList::load(tmp.path());
+        assert_eq!(list.items.len(), 2);
+        assert!(list.items[0].done);
+
+        tool.execute(json!({"action": "clear"})).await.unwrap();
+        assert_eq!(TodoList::load(tmp.path()).items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn complete_unknown_id_fails() {
+        let tmp = TempDir::new().unwrap();
+        let tool = TodoTool::new(tmp.path());
+        let result = tool
+            .execute(json!({"action": "complete", "id": 7}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::tools::security::validate_workspace_path;
+use crate::tools::{extract_string_arg_opt, extract_usize_arg_opt, get_global_rate_limiter};
+use crate::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_DEPTH: usize = 3;
+const MAX_DEPTH: usize = 10;
+const MAX_ENTRIES_PER_DIR: usize = 40;
+const MAX_LINES: usize = 500;
+const MAX_SCANNED: usize = 50_000;
+
+pub struct TreeTool {
+    workspace: PathBuf,
+    rate_limiter: std::sync::Arc<crate::tools::security::RateLimiter>,
+}
+
+impl TreeTool {
+    pub fn new(workspace: impl AsRef<Path>) -> Self {
+        Self {
+            workspace: workspace.as_ref().to_path_buf(),
+            rate_limiter: get_global_rate_limiter(),
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+#[derive(Default)]
+struct Listing {
+    children: HashMap<PathBuf, Vec<Entry>>,
+    dirs: usize,
+    files: usize,
+    scan_truncated: bool,
+}
+
+fn scan(root: &Path, depth: usize, show_hidden: bool) -> Listing {
+    let mut listing = Listing::default();
+    // Walk one level deeper than displayed so collapsed directories can show a count.
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(depth + 1))
+        .hidden(!show_hidden)
+        .require_git(false)
+        .follow_links(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .build();
+
+    for (scanned, entry) in walker.filter_map(|e| e.ok()).enumerate() {
+        if scanned >= MAX_SCANNED {
+            listing.scan_truncated = true;
+            break;
+        }
+        if entry.depth() == 0 {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        if entry.depth() <= depth {
+            if is_dir {
+                listing.dirs += 1;
+            } else {
+                listing.files += 1;
+            }
+        }
+        let parent = rel.parent().unwrap_or(Path::new("")).to_path_buf();
+        listing.children.entry(parent).or_default().push(Entry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            is_dir,
+        });
+    }
+
+    for entries in listing.children.values_mut() {
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    }
+    listing
+}
+
+fn render(
+    listing: &Listing,
+    dir: &Path,
+    level: usize,
+    depth: usize,
+    dirs_only: bool,
+    lines: &mut Vec<String>,
+) {
+    let Some(entries) = listing.children.get(dir) else {
+        return;
+    };
+    let indent = "  ".repeat(level);
+    let mut shown = 0;
+    for entry in entries.iter().filter(|e| e.is_dir || !dirs_only) {
+        if shown >= MAX_ENTRIES_PER_DIR || lines.len() >= MAX_LINES {
+            break;
+        }
+        shown += 1;
+        if !entry.is_dir {
+            lines.push(format!("{indent}{}", entry.name));
+            continue;
+        }
+
+        let path = dir.join(&entry.name);
+        if level + 1 < depth {
+            lines.push(format!("{indent}{}/", entry.name));
+            render(listing, &path, level + 1, depth, dirs_only, lines);
+        } else {
+            let count = listing.children.get(&path).map_or(0, |c| c.len());
+            if count == 0 {
+                lines.push(format!("{indent}{}/", entry.name));
+            } else {
+                lines.push(format!("{indent}{}/ ({count} entries)", entry.name));
+            }
+        }
+    }
+
+    let total = entries.iter().filter(|e| e.is_dir || !dirs_only).count();
+    if shown < total {
+        lines.push(format!("{indent}... {} more", total - shown));
+    }
+}
+
+fn build_tree(
+    root: &Path,
+    label: &str,
+    depth: usize,
+    show_hidden: bool,
+    dirs_only: bool,
+) -> String {
+    let listing = scan(root, depth, show_hidden);
+    let mut lines = Vec::new();
+    render(&listing, Path::new(""), 0, depth, dirs_only, &mut lines);
+
+    let mut output = format!("{label}/\n");
+    if lines.is_empty() {
+        output.push_str("(empty)\n");
+    } else {
+        for line in &lines {
+            output.push_str("  ");
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    if lines.len() >= MAX_LINES || listing.scan_truncated {
+        output.push_str("[Tree truncated: narrow 'path' or lower 'depth']\n");
+    }
+    output.push_str(&format!(
+        "\n{} directories, {} files (depth {depth}, .gitignore respected)",
+        listing.dirs, listing.files
+    ));
+    output
+}
+
+#[async_trait]
+impl Tool for TreeTool {
+    fn name(&self) -> &str {
+        "tree"
+    }
+
+    fn description(&self) -> &str {
+        "Show a compact, depth-limited directory tree of the workspace (respects .gitignore). \
+         Use it first to get an overview of a project before reading files"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to show, relative to the workspace (default: workspace root)"
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "How many levels to expand (default: 3, max: 10). Deeper directories show an entry count"
+                },
+                "show_hidden": {
+                    "type": "boolean",
+                    "description": "Include dotfiles and dot-directories (default: false)"
+                },
+                "dirs_only": {
+                    "type": "boolean",
+                    "description": "List directories only (default: false)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many file operations. Please wait a moment.",
+            ));
+        }
+
+        let path = extract_string_arg_opt(&args, "path", ".");
+        let path = if path.trim().is_empty() {
+            ".".to_string()
+        } else {
+            path
+        };
+        let depth = extract_usize_arg_opt(&args, "depth", DEFAULT_DEPTH).clamp(1, MAX_DEPTH);
+        let show_hidden = args
+            .get("show_hidden")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let dirs_only = args
+            .get("dirs_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let root = match validate_workspace_path(&path, &self.workspace) {
+            Ok(p) => p,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        if !root.is_dir() {
+            return Ok(ToolResult::error(format!("Not a directory: {path}")));
+        }
+
+        let label = path.trim_end_matches('/').to_string();
+        let output = tokio::task::spawn_blocking(move || {
+            build_tree(&root, &label, depth, show_hidden, dirs_only)
+        })
+        .await?;
+        Ok(ToolResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn tree_respects_gitignore_and_collapses_deep_dirs() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/tools/inner")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "").unwrap();
+        std::fs::write(root.join("debug.log"), "").unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("src/tools/a.rs"), "").unwrap();
+        std::fs::write(root.join("src/tools/b.rs"), "").unwrap();
+        std::fs::write(root.join("target/debug/app"), "").unwrap();
+
+        let output = build_tree(root, ".", 2, false, false);
+
+        assert!(output.contains("  src/\n    tools/ (3 entries)\n    main.rs\n"));
+        assert!(output.contains("  Cargo.toml\n"));
+        assert!(!output.contains("target"));
+        assert!(!output.contains("debug.log"));
+        assert!(!output.contains("  .gitignore"));
+        assert!(output.contains("2 directories, 2 files"));
+    }
+}
@@ -1,13 +1,19 @@
-use crate::tools::extract_string_arg;
+use crate::tools::html::{html_to_markdown, html_to_text};
 use crate::tools::security::RateLimiter;
+use crate::tools::{extract_string_arg, extract_string_arg_opt, extract_usize_arg_opt};
 use crate::traits::{Tool, ToolResult};
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use reqwest::redirect::Policy;
 use serde_json::json;
+use std::net::Ipv4Addr;
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
+use url::{Host, Url};
 
-const MAX_RESPONSE_SIZE: usize = 500_000;
+const MAX_DOWNLOAD_BYTES: usize = 2_000_000;
+const DEFAULT_MAX_LENGTH: usize = 20_000;
+const MAX_LENGTH_LIMIT: usize = 100_000;
 const TIMEOUT_SECS: u64 = 30;
 const MAX_REDIRECTS: usize = 10;
 const RATE_LIMIT_MAX: u64 = 60;
@@ -15,9 +21,53 @@ const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
 
 static GLOBAL_RATE_LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
 
+/// Follows up to `MAX_REDIRECTS` redirects, none of them to a local or private host.
+pub(crate) fn safe_redirect_policy() -> Policy {
+    Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        let blocked = attempt
+            .url()
+            .host()
+            .is_none_or(|host| is_private_host(&host));
+        if blocked {
+            attempt.error("redirect to a local/private host was blocked")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+pub(crate) fn validate_public_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+
+    if url.is_empty() {
+        return Err("URL cannot be empty".into());
+    }
+
+    if url.chars().any(char::is_whitespace) {
+        return Err("URL cannot contain whitespace".into());
+    }
+
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Only http:// and https:// URLs are allowed".into());
+    }
+
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err("URL userinfo is not allowed".into());
+    }
+    let host = parsed.host().ok_or("URL must include a host")?;
+    if is_private_host(&host) {
+        return Err(format!("Blocked local/private host: {}", host));
+    }
+
+    Ok(url.to_string())
+}
+
 pub struct WebFetchTool {
     client: reqwest::Client,
-    max_size: usize,
     rate_limiter: Arc<RateLimiter>,
 }
 
@@ -26,8 +76,8 @@ impl WebFetchTool {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(TIMEOUT_SECS))
             .connect_timeout(Duration::from_secs(10))
-            .redirect(Policy::limited(MAX_REDIRECTS))
-            .user_agent("Dinoe/0.2 (web_fetch)")
+            .redirect(safe_redirect_policy())
+            .user_agent("Mozilla/5.0 (compatible; Dinoe/0.2; web_fetch)")
             .build()
             .expect("Failed to build HTTP client");
 
@@ -37,43 +87,9 @@ impl WebFetchTool {
 
         Self {
             client,
-            max_size: MAX_RESPONSE_SIZE,
             rate_limiter,
         }
     }
-
-    fn validate_url(&self, url: &str) -> Result<String, String> {
-        let url = url.trim();
-
-        if url.is_empty() {
-            return Err("URL cannot be empty".into());
-        }
-
-        if url.chars().any(char::is_whitespace) {
-            return Err("URL cannot contain whitespace".into());
-        }
-
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            return Err("Only http:// and https:// URLs are allowed".into());
-        }
-
-        let host = extract_host(url)?;
-        if is_private_host(&host) {
-            return Err(format!("Blocked local/private host: {}", host));
-        }
-
-        Ok(url.to_string())
-    }
-
-    fn truncate(&self, text: &str) -> String {
-        if text.len() > self.max_size {
-            let mut truncated: String = text.chars().take(self.max_size).collect();
-            truncated.push_str("\n\n... [truncated]");
-            truncated
-        } else {
-            text.to_string()
-        }
-    }
 }
 
 impl Default for WebFetchTool {
@@ -89,9 +105,8 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch a web page and convert HTML to clean plain text. \
-         Supports text/html, text/plain, text/markdown, application/json. \
-         Blocks local/private hosts for security."
+        "Fetch a web page and extract its readable content as markdown (headings, links, lists, code), \
+         or as plain text. Follows redirects, rejects binary content and local/private hosts."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -101,6 +116,15 @@ impl Tool for WebFetchTool {
                 "url": {
                     "type": "string",
                     "description": "The HTTP or HTTPS URL to fetch"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["markdown", "text"],
+                    "description": "markdown keeps headings, links, lists and code (default); text is plain text only"
+                },
+                "max_length": {
+                    "type": "integer",
+                    "description": "Maximum characters to return (default: 20000, max: 100000)"
                 }
             },
             "required": ["url"]
@@ -115,8 +139,20 @@ impl Tool for WebFetchTool {
         }
 
         let url = extract_string_arg(&args, "url")?;
+        let format = extract_string_arg_opt(&args, "format", "markdown");
+        let max_length = extract_usize_arg_opt(&args, "max_length", DEFAULT_MAX_LENGTH)
+            .clamp(1, MAX_LENGTH_LIMIT);
+        let convert: fn(&str) -> String = match format.as_str() {
+            "markdown" => html_to_markdown,
+            "text" => html_to_text,
+            _ => {
+                return Ok(ToolResult::error(format!(
+                    "Unknown format '{format}': use markdown or text"
+                )));
+            }
+        };
 
-        let url = match self.validate_url(&url) {
+        let url = match validate_public_url(&url) {
             Ok(u) => u,
             Err(e) => return Ok(ToolResult::error(e)),
         };
@@ -135,6 +171,7 @@ impl Tool for WebFetchTool {
             )));
         }
 
+        let final_url = response.url().to_string();
         let content_type = response
             .headers()
             .get(reqwest::header::CONTENT_TYPE)
@@ -142,159 +179,142 @@ impl Tool for WebFetchTool {
             .unwrap_or("")
             .to_lowercase();
 
-        let is_html =
-            content_type.contains("text/html") || content_type.is_empty() || content_type.contains("application/xhtml");
+        let is_html = content_type.is_empty()
+            || content_type.contains("text/html")
+            || content_type.contains("application/xhtml");
+        let is_text = content_type.starts_with("text/")
+            || content_type.contains("json")
+            || content_type.contains("xml");
 
-        if !is_html
-            && !content_type.contains("text/plain")
-            && !content_type.contains("text/markdown")
-            && !content_type.contains("application/json")
-        {
+        if !is_html && !is_text {
             return Ok(ToolResult::error(format!(
-                "Unsupported content type: {}",
+                "Unsupported content type: {}. web_fetch only extracts text pages",
                 content_type
             )));
         }
 
-        let body = match response.text().await {
-            Ok(t) => t,
-            Err(e) => return Ok(ToolResult::error(format!("Failed to read response: {}", e))),
-        };
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(c) => c,
+                Err(e) => return Ok(ToolResult::error(format!("Failed to read response: {}", e))),
+            };
+            if body.len() + chunk.len() > MAX_DOWNLOAD_BYTES {
+                return Ok(ToolResult::error(format!(
+                    "Response exceeds {} bytes; refusing to download",
+                    MAX_DOWNLOAD_BYTES
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let body = String::from_utf8_lossy(&body);
 
         let text = if is_html {
-            html_to_text(&body)
+            convert(&body)
         } else {
-            body
+            body.to_string()
         };
 
-        Ok(ToolResult::success(self.truncate(&text)))
-    }
-}
-
-fn extract_host(url: &str) -> Result<String, String> {
-    let rest = url
-        .strip_prefix("http://")
-        .or_else(|| url.strip_prefix("https://"))
-        .ok_or_else(|| "Invalid URL scheme".to_string())?;
-
-    let authority = rest
-        .split(['/', '?', '#'])
-        .next()
-        .ok_or_else(|| "Invalid URL".to_string())?;
-
-    if authority.is_empty() {
-        return Err("URL must include a host".into());
-    }
-
-    if authority.contains('@') {
-        return Err("URL userinfo is not allowed".into());
-    }
+        let mut output = String::new();
+        if final_url.trim_end_matches('/') != url.trim_end_matches('/') {
+            output.push_str(&format!("Redirected to: {}\n\n", final_url));
+        }
 
-    let host = authority
-        .split(':')
-        .next()
-        .unwrap_or_default()
-        .trim()
-        .trim_end_matches('.')
-        .to_lowercase();
+        let total = text.chars().count();
+        if total > max_length {
+            output.extend(text.chars().take(max_length));
+            output.push_str(&format!(
+                "\n\n[... truncated: showing {} of {} chars]",
+                max_length, total
+            ));
+        } else {
+            output.push_str(&text);
+        }
 
-    if host.is_empty() {
-        return Err("URL must include a valid host".into());
+        Ok(ToolResult::success(output))
     }
-
-    Ok(host)
 }
 
-fn is_private_host(host: &str) -> bool {
-    if host == "localhost" || host.ends_with(".localhost") {
-        return true;
-    }
-
-    if host.ends_with(".local") || host == "local" {
-        return true;
-    }
-
-    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
-        return match ip {
-            std::net::IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
-            std::net::IpAddr::V6(v6) => v6.is_loopback(),
-        };
+fn is_private_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain == "local"
+                || domain.ends_with(".local")
+        }
+        Host::Ipv4(v4) => is_private_ipv4(v4),
+        Host::Ipv6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_private_ipv4(&v4),
+            None => {
+                v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_unique_local()
+                    || v6.is_unicast_link_local()
+            }
+        },
     }
-
-    false
 }
 
-fn html_to_text(html: &str) -> String {
-    let mut text = String::new();
-    let mut in_tag = false;
-    let mut in_script = false;
-    let mut in_style = false;
-    let mut tag_name = String::new();
-    let mut prev_char = ' ';
-
-    for c in html.chars() {
-        if c == '<' {
-            in_tag = true;
-            tag_name.clear();
-            continue;
-        }
+fn is_private_ipv4(v4: &Ipv4Addr) -> bool {
+    v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified()
+}
 
-        if c == '>' && in_tag {
-            in_tag = false;
-            let tag = tag_name.to_lowercase();
-
-            if tag == "script" || tag.starts_with("script ") {
-                in_script = true;
-            } else if tag == "/script" {
-                in_script = false;
-            } else if tag == "style" || tag.starts_with("style ") {
-                in_style = true;
-            } else if tag == "/style" {
-                in_style = false;
-            } else if tag == "br" || tag == "br/" || tag == "/p" || tag == "/div" || tag == "/li" {
-                if !text.ends_with('\n') && !text.is_empty() {
-                    text.push('\n');
-                }
-            } else if tag == "li" || tag.starts_with("li ") {
-                if !text.ends_with('\n') && !text.is_empty() {
-                    text.push('\n');
-                }
-                text.push_str("- ");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// A local server answering every request with a redirect to `location`.
+    async fn redirecting_to(location: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {location}\r\nContent-Length: 0\r\n\r\n"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
             }
-            continue;
-        }
-
-        if in_tag {
-            tag_name.push(c);
-            continue;
-        }
-
-        if in_script || in_style {
-            continue;
-        }
-
-        if c == '&' {
-            continue;
-        }
+        });
+        format!("http://{address}/")
+    }
 
-        if c == ';' && prev_char == '&' {
-            continue;
+    #[test]
+    fn blocks_loopback_unspecified_and_local_ipv6_hosts() {
+        for url in [
+            "http://localhost/",
+            "http://127.0.0.1/",
+            "http://0.0.0.0/",
+            "http://10.0.0.1/",
+            "http://[::1]/",
+            "http://[::1]:8080/x",
+            "http://[::]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://user@example.com/",
+        ] {
+            assert!(validate_public_url(url).is_err(), "{url}");
         }
+        assert!(validate_public_url("https://example.com/page").is_ok());
+        assert!(validate_public_url("http://[2606:4700::1111]/").is_ok());
+    }
 
-        if c.is_whitespace() {
-            if !text.is_empty() && !text.ends_with(' ') && !text.ends_with('\n') {
-                text.push(' ');
-            }
-        } else {
-            text.push(c);
+    #[tokio::test]
+    async fn redirects_to_private_hosts_are_blocked() {
+        let client = reqwest::Client::builder()
+            .redirect(safe_redirect_policy())
+            .build()
+            .unwrap();
+        for location in ["http://[::1]/", "http://0.0.0.0/"] {
+            let url = redirecting_to(location).await;
+            let error = client.get(&url).send().await.unwrap_err();
+            assert!(error.is_redirect(), "{location}: {error}");
         }
-
-        prev_char = c;
     }
-
-    text.lines()
-        .map(|line| line.trim_end())
-        .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
 }
@@ -0,0 +1,508 @@
+use crate::config::SearchConfig;
+use crate::tools::security::RateLimiter;
+use crate::tools::{extract_string_arg, extract_usize_arg_opt};
+use crate::traits::{Tool, ToolResult};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+const TIMEOUT_SECS: u64 = 20;
+const MAX_RESULTS_LIMIT: usize = 20;
+const RATE_LIMIT_MAX: u64 = 60;
+const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
+
+static GLOBAL_RATE_LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+#[async_trait]
+pub trait SearchEngine: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>>;
+}
+
+pub fn create_search_engine(config: &SearchConfig) -> Result<Box<dyn SearchEngine>> {
+    match config.engine.to_lowercase().as_str() {
+        "duckduckgo" | "ddg" => Ok(Box::new(DuckDuckGoSearch)),
+        "brave" => Ok(Box::new(BraveSearch {
+            api_key: resolve_search_key(&["BRAVE_API_KEY", "DINOE_BRAVE_API_KEY"], config)?,
+        })),
+        "tavily" => Ok(Box::new(TavilySearch {
+            api_key: resolve_search_key(&["TAVILY_API_KEY", "DINOE_TAVILY_API_KEY"], config)?,
+        })),
+        "searxng" | "searx" => {
+            let base_url = config
+                .base_url
+                .clone()
+                .ok_or_else(|| anyhow!("SearXNG requires search.base_url"))?;
+            Ok(Box::new(SearxngSearch {
+                base_url: base_url.trim_end_matches('/').to_string(),
+            }))
+        }
+        other => Err(anyhow!(
+            "Unknown search engine: {}. Available: duckduckgo, brave, tavily, searxng",
+            other
+        )),
+    }
+}
+
+fn resolve_search_key(env_vars: &[&str], config: &SearchConfig) -> Result<String> {
+    for var_name in env_vars {
+        if let Ok(key) = std::env::var(var_name)
+            && !key.is_empty()
+        {
+            return Ok(key);
+        }
+    }
+    config
+        .api_key
+        .clone()
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| anyhow!("No API key found for search engine '{}'", config.engine))
+}
+
+pub struct DuckDuckGoSearch;
+
+#[async_trait]
+impl SearchEngine for DuckDuckGoSearch {
+    fn name(&self) -> &str {
+        "duckduckgo"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let response = client
+            .get("https://html.duckduckgo.com/html/")
+            .query(&[("q", query)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("DuckDuckGo returned HTTP {}", response.status()));
+        }
+
+        let html = response.text().await?;
+        let mut results = parse_duckduckgo_html(&html);
+        results.truncate(limit);
+        Ok(results)
+    }
+}
+
+pub struct BraveSearch {
+    api_key: String,
+}
+
+#[async_trait]
+impl SearchEngine for BraveSearch {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let response = client
+            .get("https://api.search.brave.com/res/v1/web/search")
+            .header("X-Subscription-Token", &self.api_key)
+            .header("Accept", "application/json")
+            .query(&[("q", query), ("count", &limit.to_string())])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Brave Search returned HTTP {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        Ok(collect_results(
+            body.pointer("/web/results"),
+            "description",
+            limit,
+        ))
+    }
+}
+
+pub struct TavilySearch {
+    api_key: String,
+}
+
+#[async_trait]
+impl SearchEngine for TavilySearch {
+    fn name(&self) -> &str {
+        "tavily"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let response = client
+            .post("https://api.tavily.com/search")
+            .json(&json!({
+                "api_key": self.api_key,
+                "query": query,
+                "max_results": limit,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Tavily returned HTTP {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        Ok(collect_results(body.get("results"), "content", limit))
+    }
+}
+
+pub struct SearxngSearch {
+    base_url: String,
+}
+
+#[async_trait]
+impl SearchEngine for SearxngSearch {
+    fn name(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let response = client
+            .get(format!("{}/search", self.base_url))
+            .query(&[("q", query), ("format", "json")])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("SearXNG returned HTTP {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        Ok(collect_results(body.get("results"), "content", limit))
+    }
+}
+
+fn collect_results(
+    items: Option<&serde_json::Value>,
+    snippet_key: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let Some(items) = items.and_then(|v| v.as_array()) else {
+        return vec![];
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let url = item.get("url")?.as_str()?.to_string();
+            let title = item
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or(&url)
+                .to_string();
+            let snippet = item
+                .get(snippet_key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            Some(SearchResult {
+                title: strip_tags(&title),
+                url,
+                snippet: strip_tags(&snippet),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+fn parse_duckduckgo_html(html: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    for block in html.split("class=\"result__a\"").skip(1) {
+        let Some(href) = attr_value(block, "href=\"") else {
+            continue;
+        };
+        let title = block
+            .split_once('>')
+            .and_then(|(_, rest)| rest.split_once("</a>"))
+            .map(|(title, _)| strip_tags(title))
+            .unwrap_or_default();
+
+        let snippet = block
+            .split_once("class=\"result__snippet\"")
+            .and_then(|(_, rest)| rest.split_once('>'))
+            .and_then(|(_, rest)| rest.split_once("</a>"))
+            .map(|(snippet, _)| strip_tags(snippet))
+            .unwrap_or_default();
+
+        let url = resolve_duckduckgo_redirect(&decode_entities(href));
+        if url.is_empty() || title.is_empty() {
+            continue;
+        }
+
+        results.push(SearchResult {
+            title,
+            url,
+            snippet,
+        });
+    }
+
+    results
+}
+
+fn attr_value<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = text.find(prefix)? + prefix.len();
+    let end = text[start..].find('"')?;
+    Some(&text[start..start + end])
+}
+
+fn resolve_duckduckgo_redirect(href: &str) -> String {
+    if let Some((_, query)) = href.split_once('?') {
+        for pair in query.split('&') {
+            if let Some(encoded) = pair.strip_prefix("uddg=") {
+                return percent_decode(encoded);
+            }
+        }
+    }
+    if href.starts_with("//") {
+        format!("https:{href}")
+    } else {
+        href.to_string()
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = if bytes[i] == b'%' {
+            bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+
+        match (decoded, bytes[i]) {
+            (Some(b), _) => {
+                out.push(b);
+                i += 3;
+            }
+            (None, b'+') => {
+                out.push(b' ');
+                i += 1;
+            }
+            (None, b) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(text.split_whitespace().collect::<Vec<_>>().join(" ").as_str())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+}
+
+pub struct WebSearchTool {
+    client: reqwest::Client,
+    engine: Box<dyn SearchEngine>,
+    default_limit: usize,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl WebSearchTool {
+    pub fn new(engine: Box<dyn SearchEngine>, default_limit: usize) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .connect_timeout(Duration::from_secs(10))
+            .user_agent("Mozilla/5.0 (compatible; Dinoe/0.2; web_search)")
+            .build()
+            .expect("Failed to build HTTP client");
+
+        let rate_limiter = GLOBAL_RATE_LIMITER
+            .get_or_init(|| Arc::new(RateLimiter::new(RATE_LIMIT_MAX, RATE_LIMIT_WINDOW_SECS)))
+            .clone();
+
+        Self {
+            client,
+            engine,
+            default_limit: default_limit.clamp(1, MAX_RESULTS_LIMIT),
+            rate_limiter,
+        }
+    }
+
+    pub fn from_config(config: &SearchConfig) -> Result<Self> {
+        Ok(Self::new(create_search_engine(config)?, config.max_results))
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web and return titles, URLs and snippets. Use web_fetch to read a result in full"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Search query"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default from config, max 20)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.rate_limiter.check_and_record() {
+            return Ok(ToolResult::error(
+                "Rate limit exceeded: too many web searches. Please wait a moment.",
+            ));
+        }
+
+        let query = extract_string_arg(&args, "query")?;
+        if query.trim().is_empty() {
+            return Ok(ToolResult::error("Query cannot be empty"));
+        }
+        let limit = extract_usize_arg_opt(&args, "limit", self.default_limit)
+            .clamp(1, MAX_RESULTS_LIMIT);
+
+        let results = match self.engine.search(&self.client, &query, limit).await {
+            Ok(r) => r,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Search via {} failed: {}",
+                    self.engine.name(),
+                    e
+                )));
+            }
+        };
+
+        if results.is_empty() {
+            return Ok(ToolResult::success(format!("No results found for '{query}'.")));
+        }
+
+        let mut output = String::new();
+        for (i, result) in results.iter().enumerate() {
+            let _ = writeln!(output, "{}. {}\n   {}", i + 1, result.title, result.url);
+            if !result.snippet.is_empty() {
+                let _ = writeln!(output, "   {}", result.snippet);
+            }
+        }
+
+        Ok(ToolResult::success(output.trim_end().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_duckduckgo_results() {
+        let html = r#"
+            <div class="result">
+              <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F&amp;rut=abc">The <b>Rust</b> Language</a>
+              <a class="result__snippet" href="x">A language empowering <b>everyone</b>.</a>
+            </div>
+            <div class="result">
+              <a rel="nofollow" class="result__a" href="https://example.com/direct">Example</a>
+            </div>
+        "#;
+
+        let results = parse_duckduckgo_html(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].snippet, "A language empowering everyone.");
+        assert_eq!(results[1].url, "https://example.com/direct");
+        assert!(results[1].snippet.is_empty());
+    }
+
+    #[test]
+    fn collects_json_results() {
+        let body = json!({"results": [
+            {"title": "A", "url": "https://a.test", "content": "alpha"},
+            {"title": "no url"},
+            {"title": "B", "url": "https://b.test", "content": "beta"}
+        ]});
+        let results = collect_results(body.get("results"), "content", 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "alpha");
+    }
+
+    #[test]
+    fn factory_requires_keys_and_urls() {
+        let mut config = SearchConfig::default();
+        assert!(create_search_engine(&config).is_ok());
+
+        config.engine = "searxng".to_string();
+        assert!(create_search_engine(&config).is_err());
+        config.base_url = Some("http://search.local/".to_string());
+        assert_eq!(create_search_engine(&config).unwrap().name(), "searxng");
+
+        config.engine = "bing".to_string();
+        assert!(create_search_engine(&config).is_err());
+    }
+}
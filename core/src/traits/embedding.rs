@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Identifies the model, so cached vectors from another model are not reused.
+    fn model(&self) -> &str;
+
+    /// One vector per input text, in order.
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>>;
+}
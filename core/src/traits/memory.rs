@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -10,6 +11,81 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    #[serde(flatten)]
+    pub meta: EntryMeta,
+}
+
+/// Who a memory came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemorySource {
+    User,
+    Agent,
+    Tool,
+}
+
+impl MemorySource {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "user" => Some(Self::User),
+            "agent" => Some(Self::Agent),
+            "tool" => Some(Self::Tool),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MemorySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::User => write!(f, "user"),
+            Self::Agent => write!(f, "agent"),
+            Self::Tool => write!(f, "tool"),
+        }
+    }
+}
+
+/// Optional annotations kept alongside an entry's content.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntryMeta {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<MemorySource>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl EntryMeta {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.source.is_none() && self.metadata.is_empty()
+    }
+}
+
+/// Narrows recall to entries with a category, source and every listed tag.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFilter {
+    pub category: Option<MemoryCategory>,
+    pub tags: Vec<String>,
+    pub source: Option<MemorySource>,
+}
+
+impl MemoryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.tags.is_empty() && self.source.is_none()
+    }
+
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        self.category.as_ref().is_none_or(|c| &entry.category == c)
+            && self.source.is_none_or(|s| entry.meta.source == Some(s))
+            && self.tags.iter().all(|tag| {
+                entry
+                    .meta
+                    .tags
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(tag))
+            })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -42,6 +118,19 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<()>;
 
+    /// Like `store`, with tags, source and metadata. Every built-in backend keeps them;
+    /// the default drops them.
+    async fn store_with_meta(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        _meta: &EntryMeta,
+    ) -> anyhow::Result<()> {
+        self.store(key, content, category, session_id).await
+    }
+
     async fn recall(
         &self,
         query: &str,
@@ -49,6 +138,29 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// `recall` restricted to entries matching `filter`. The default over-fetches and
+    /// filters; backends with native filtering override it.
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        filter: &MemoryFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if filter.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        let mut entries = if query.trim().is_empty() {
+            self.list(filter.category.as_ref(), session_id).await?
+        } else {
+            self.recall(query, limit.saturating_mul(10).max(50), session_id)
+                .await?
+        };
+        entries.retain(|e| filter.matches(e));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
     async fn list(
@@ -62,4 +174,24 @@ pub trait Memory: Send + Sync {
     async fn count(&self) -> anyhow::Result<usize>;
 
     async fn health_check(&self) -> bool;
+
+    /// Every entry, with the caller's original keys where the backend can recover them.
+    async fn export(&self) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.list(None, None).await
+    }
+
+    /// Stores previously exported entries and returns how many were written.
+    async fn import(&self, entries: &[MemoryEntry]) -> anyhow::Result<usize> {
+        for entry in entries {
+            self.store_with_meta(
+                &entry.key,
+                &entry.content,
+                entry.category.clone(),
+                entry.session_id.as_deref(),
+                &entry.meta,
+            )
+            .await?;
+        }
+        Ok(entries.len())
+    }
 }
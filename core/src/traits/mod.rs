@@ -1,7 +1,9 @@
+pub mod embedding;
 pub mod memory;
 pub mod provider;
 pub mod tool;
 
-pub use memory::{Memory, MemoryCategory, MemoryEntry};
+pub use embedding::Embedder;
+pub use memory::{EntryMeta, Memory, MemoryCategory, MemoryEntry, MemoryFilter, MemorySource};
 pub use provider::{ChatMessage, ChatRequest, ChatResponse, Provider, ProviderEvent, ToolCall};
-pub use tool::{Tool, ToolResult, ToolSpec};
+pub use tool::{Tool, ToolProgress, ToolResult, ToolSpec};
@@ -70,10 +70,13 @@ pub struct ToolCall {
     pub arguments: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChatResponse {
     pub text: Option<String>,
     pub tool_calls: Vec<ToolCall>,
+    /// The model's reasoning, for providers that return it separately.
+    pub reasoning: Option<String>,
 }
 
 impl ChatResponse {
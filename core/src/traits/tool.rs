@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResult {
@@ -33,6 +34,30 @@ pub struct ToolSpec {
     pub parameters_schema: serde_json::Value,
 }
 
+/// Receives status lines from a running tool, e.g. to show them in the CLI.
+#[derive(Clone, Default)]
+pub struct ToolProgress {
+    sink: Option<Arc<dyn Fn(String) + Send + Sync>>,
+}
+
+impl ToolProgress {
+    pub fn new(sink: impl Fn(String) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Some(Arc::new(sink)),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&self, message: impl Into<String>) {
+        if let Some(sink) = &self.sink {
+            sink(message.into());
+        }
+    }
+}
+
 #[async_trait]
 pub trait Tool: Send + Sync + 'static {
     fn name(&self) -> &str;
@@ -43,6 +68,26 @@ pub trait Tool: Send + Sync + 'static {
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Long-running tools override this to report progress while they work, and
+    /// implement `execute` by calling it with `ToolProgress::none()`.
+    async fn execute_with_progress(
+        &self,
+        args: serde_json::Value,
+        progress: ToolProgress,
+    ) -> anyhow::Result<ToolResult> {
+        let _ = progress;
+        self.execute(args).await
+    }
+
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
+    /// Asks before every call, whatever the approval mode.
+    fn always_needs_approval(&self) -> bool {
+        false
+    }
+
     fn spec(&self) -> ToolSpec {
         ToolSpec {
             name: self.name().to_string(),
@@ -0,0 +1,518 @@
+use crate::traits::{ChatMessage, ChatRequest, Provider};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Characters of conversation sent to the model for a summary; the end is kept.
+const MAX_SUMMARY_INPUT: usize = 24_000;
+const MAX_SUMMARY_MESSAGE: usize = 2_000;
+
+const SUMMARY_PROMPT: &str = "Below is a conversation between a user and an assistant. \
+Reply with exactly two lines:\n\
+Title: a title of at most eight words\n\
+Summary: two or three sentences on what was asked, decided and done, including anything \
+learned about the user that is worth remembering.";
+
+/// The role of records holding the model's reasoning. They are never sent back to it.
+pub const THINKING_ROLE: &str = "thinking";
+
+pub fn sessions_dir() -> PathBuf {
+    crate::config::get_data_dir().join("sessions")
+}
+
+/// One message of a conversation, as sent to or received from the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptRecord {
+    pub timestamp: DateTime<Local>,
+    #[serde(flatten)]
+    pub message: ChatMessage,
+}
+
+/// The full message history of one chat session in `<dir>/<session>.jsonl`.
+/// Unlike memory it keeps every message, including tool calls and results, in order.
+pub struct Transcript {
+    dir: PathBuf,
+    session: String,
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl Transcript {
+    /// Starts a new session.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        let session = format!(
+            "{}-{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..6]
+        );
+        Self::open(dir, &session)
+    }
+
+    /// Continues an existing session; new messages are appended to it.
+    pub fn open(dir: impl AsRef<Path>, session: &str) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            session: session.to_string(),
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn path(&self) -> PathBuf {
+        session_path(&self.dir, &self.session)
+    }
+
+    /// Every record so far; empty before the first message.
+    pub fn records(&self) -> Result<Vec<TranscriptRecord>> {
+        if !self.path().exists() {
+            return Ok(Vec::new());
+        }
+        load(&self.dir, &self.session)
+    }
+
+    /// Saves the title and summary alongside the transcript, replacing earlier ones.
+    pub fn write_meta(&self, meta: &SessionMeta) -> Result<()> {
+        let path = meta_path(&self.dir, &self.session);
+        std::fs::write(&path, serde_json::to_string_pretty(meta)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub async fn append(&self, messages: &[ChatMessage]) -> Result<()> {
+        let mut lines = String::new();
+        for message in messages {
+            let record = TranscriptRecord {
+                timestamp: Local::now(),
+                message: message.clone(),
+            };
+            lines.push_str(&serde_json::to_string(&record)?);
+            lines.push('\n');
+        }
+
+        let _guard = self.write_lock.lock().await;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())
+            .await?;
+        file.write_all(lines.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// The last `limit` user and assistant messages, to continue the conversation.
+    /// Tool calls and results are left out so the history never starts mid-exchange.
+    pub fn history(&self, limit: usize) -> Result<Vec<ChatMessage>> {
+        if !self.path().exists() {
+            return Ok(Vec::new());
+        }
+        let mut history: Vec<ChatMessage> = load(&self.dir, &self.session)?
+            .into_iter()
+            .map(|r| r.message)
+            .filter(|m| (m.role == "user" || m.role == "assistant") && !m.content.trim().is_empty())
+            .map(|m| ChatMessage {
+                tool_calls: None,
+                ..m
+            })
+            .collect();
+        let skip = history.len().saturating_sub(limit);
+        history.drain(..skip);
+        Ok(history)
+    }
+
+    /// Rewinds the conversation by `turns` turns, each starting at a user message. The
+    /// earlier messages are copied into a new branch session, which is returned; this
+    /// session is left as it was, so the abandoned turns can still be resumed.
+    pub fn branch(&self, turns: usize) -> Result<Transcript> {
+        let records = if self.path().exists() {
+            load(&self.dir, &self.session)?
+        } else {
+            Vec::new()
+        };
+        let starts: Vec<usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.message.role == "user")
+            .map(|(i, _)| i)
+            .collect();
+        if starts.is_empty() {
+            bail!("Nothing to rewind yet");
+        }
+        if turns == 0 || turns > starts.len() {
+            bail!("Can rewind 1 to {} turns", starts.len());
+        }
+        let keep = starts[starts.len() - turns];
+
+        let mut lines = String::new();
+        for record in &records[..keep] {
+            lines.push_str(&serde_json::to_string(record)?);
+            lines.push('\n');
+        }
+        let branch = Transcript::open(&self.dir, &branch_id(&self.dir, &self.session));
+        std::fs::write(branch.path(), lines)
+            .with_context(|| format!("Failed to write {}", branch.path().display()))?;
+        Ok(branch)
+    }
+}
+
+/// The next free branch of a session: `<root>.1`, `<root>.2`, ...
+fn branch_id(dir: &Path, session: &str) -> String {
+    let root = session.split('.').next().unwrap_or(session);
+    (1..)
+        .map(|n| format!("{root}.{n}"))
+        .find(|id| !session_path(dir, id).exists())
+        .unwrap_or_default()
+}
+
+fn session_path(dir: &Path, session: &str) -> PathBuf {
+    dir.join(format!("{session}.jsonl"))
+}
+
+fn meta_path(dir: &Path, session: &str) -> PathBuf {
+    dir.join(format!("{session}.meta.json"))
+}
+
+/// A generated title and summary of a session, kept in `<session>.meta.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionMeta {
+    pub title: String,
+    pub summary: String,
+}
+
+/// The saved title and summary of a session, if it has been summarized.
+pub fn read_meta(dir: &Path, session: &str) -> Option<SessionMeta> {
+    let content = std::fs::read_to_string(meta_path(dir, session)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Asks the model for a title and summary of the conversation in `records`.
+pub async fn summarize(
+    provider: &dyn Provider,
+    model: &str,
+    records: &[TranscriptRecord],
+) -> Result<SessionMeta> {
+    let mut conversation = String::new();
+    for record in records.iter().rev() {
+        let message = &record.message;
+        if !matches!(message.role.as_str(), "user" | "assistant")
+            || message.content.trim().is_empty()
+        {
+            continue;
+        }
+        let line = format!(
+            "{}: {}\n",
+            message.role,
+            shorten(&message.content, MAX_SUMMARY_MESSAGE)
+        );
+        if conversation.len() + line.len() > MAX_SUMMARY_INPUT {
+            break;
+        }
+        conversation.insert_str(0, &line);
+    }
+    if conversation.is_empty() {
+        bail!("Nothing to summarize");
+    }
+
+    let messages = [
+        ChatMessage::system(SUMMARY_PROMPT),
+        ChatMessage::user(conversation),
+    ];
+    let response = provider
+        .chat(
+            ChatRequest {
+                messages: &messages,
+                tools: None,
+            },
+            model,
+            0.2,
+        )
+        .await?;
+    parse_meta(response.text_or_empty()).context("Unexpected reply when summarizing the session")
+}
+
+/// Reads `Title:` and `Summary:` lines; the summary may run over several lines.
+fn parse_meta(text: &str) -> Option<SessionMeta> {
+    let mut title = None;
+    let mut summary: Option<String> = None;
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches(['*', '#', ' ']);
+        if let Some(rest) = strip_label(line, "title:") {
+            title = Some(rest.trim_matches(['*', '"', ' ']).to_string());
+        } else if let Some(rest) = strip_label(line, "summary:") {
+            summary = Some(rest.trim_start_matches(['*', ' ']).to_string());
+        } else if let Some(summary) = &mut summary
+            && !line.is_empty()
+        {
+            summary.push(' ');
+            summary.push_str(line);
+        }
+    }
+    let summary = summary.filter(|s| !s.is_empty())?;
+    Some(SessionMeta {
+        title: title
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| shorten(&summary, 60)),
+        summary,
+    })
+}
+
+fn strip_label<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    line.get(..label.len())
+        .filter(|head| head.eq_ignore_ascii_case(label))
+        .map(|_| &line[label.len()..])
+}
+
+fn check_id(session: &str) -> Result<()> {
+    if session.is_empty() || session.contains(['/', '\\']) || session.starts_with('.') {
+        bail!("Invalid session id '{session}'");
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session: String,
+    pub started: Option<DateTime<Local>>,
+    pub updated: Option<DateTime<Local>>,
+    pub messages: usize,
+    /// The generated title, or else the first user message, shortened.
+    pub title: String,
+    pub summary: Option<String>,
+}
+
+/// Sessions with their message counts, most recently updated first.
+pub fn list(dir: &Path) -> Result<Vec<SessionSummary>> {
+    let mut summaries = Vec::new();
+    for session in sessions(dir)? {
+        let records = load(dir, &session)?;
+        let meta = read_meta(dir, &session);
+        let title = match &meta {
+            Some(meta) => meta.title.clone(),
+            None => records
+                .iter()
+                .find(|r| r.message.role == "user")
+                .map(|r| shorten(&r.message.content, 60))
+                .unwrap_or_default(),
+        };
+        summaries.push(SessionSummary {
+            started: records.first().map(|r| r.timestamp),
+            updated: records.last().map(|r| r.timestamp),
+            messages: records.len(),
+            title,
+            summary: meta.map(|m| m.summary),
+            session,
+        });
+    }
+    summaries.sort_by(|a, b| {
+        b.updated
+            .cmp(&a.updated)
+            .then_with(|| b.session.cmp(&a.session))
+    });
+    Ok(summaries)
+}
+
+fn shorten(text: &str, max_chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line,
+    }
+}
+
+pub fn delete(dir: &Path, session: &str) -> Result<()> {
+    check_id(session)?;
+    let _ = std::fs::remove_file(meta_path(dir, session));
+    match std::fs::remove_file(session_path(dir, session)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!("No session '{session}'"),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Renders a session as a readable markdown document.
+pub fn to_markdown(session: &str, records: &[TranscriptRecord]) -> String {
+    let mut out = format!("# Session {session}\n");
+    for record in records {
+        let message = &record.message;
+        let time = record.timestamp.format("%Y-%m-%d %H:%M:%S");
+        match message.role.as_str() {
+            "tool" => {
+                out.push_str(&format!(
+                    "\n**Tool result** _{time}_\n\n```\n{}\n```\n",
+                    message.content
+                ));
+            }
+            role => {
+                let mut heading = role.to_string();
+                if let Some(first) = heading.get_mut(..1) {
+                    first.make_ascii_uppercase();
+                }
+                out.push_str(&format!("\n## {heading} _{time}_\n"));
+                if !message.content.trim().is_empty() {
+                    out.push_str(&format!("\n{}\n", message.content.trim()));
+                }
+                for call in message.tool_calls.iter().flatten() {
+                    out.push_str(&format!(
+                        "\n**Tool call** `{}`\n\n```json\n{}\n```\n",
+                        call.name, call.arguments
+                    ));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Session ids in the sessions directory, oldest first.
+pub fn sessions(dir: &Path) -> Result<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut sessions: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_suffix(".jsonl"))
+                .map(str::to_string)
+        })
+        .collect();
+    sessions.sort();
+    Ok(sessions)
+}
+
+/// All records of a session in order. Unreadable lines are skipped.
+pub fn load(dir: &Path, session: &str) -> Result<Vec<TranscriptRecord>> {
+    check_id(session)?;
+    let path = session_path(dir, session);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("No session '{session}'")
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ToolCall;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn appends_and_resumes_a_session() {
+        let tmp = TempDir::new().unwrap();
+        let transcript = Transcript::new(tmp.path());
+        transcript
+            .append(&[
+                ChatMessage::user("What is 2 + 2?"),
+                ChatMessage::assistant_with_tool_calls(
+                    "",
+                    vec![ToolCall {
+                        id: "call_1".to_string(),
+                        name: "calc".to_string(),
+                        arguments: r#"{"expression":"2 + 2"}"#.to_string(),
+                    }],
+                ),
+                ChatMessage::tool_result("call_1".to_string(), "4"),
+                ChatMessage::assistant("4"),
+            ])
+            .await
+            .unwrap();
+
+        let records = load(tmp.path(), transcript.session()).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[2].message.tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(sessions(tmp.path()).unwrap(), vec![transcript.session()]);
+
+        let resumed = Transcript::open(tmp.path(), transcript.session());
+        resumed
+            .append(&[ChatMessage::user("And 3 + 3?")])
+            .await
+            .unwrap();
+        let history = resumed.history(2).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, "4");
+        assert_eq!(history[1].content, "And 3 + 3?");
+
+        assert!(Transcript::new(tmp.path()).history(10).unwrap().is_empty());
+        assert!(load(tmp.path(), "missing").is_err());
+        assert!(load(tmp.path(), "../secrets").is_err());
+
+        let summaries = list(tmp.path()).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].messages, 5);
+        assert_eq!(summaries[0].title, "What is 2 + 2?");
+
+        let markdown = to_markdown(
+            transcript.session(),
+            &load(tmp.path(), transcript.session()).unwrap(),
+        );
+        assert!(markdown.contains("## User"));
+        assert!(markdown.contains("**Tool call** `calc`"));
+
+        delete(tmp.path(), transcript.session()).unwrap();
+        assert!(sessions(tmp.path()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn branches_before_earlier_turns() {
+        let tmp = TempDir::new().unwrap();
+        let transcript = Transcript::new(tmp.path());
+        for turn in ["one", "two", "three"] {
+            transcript
+                .append(&[
+                    ChatMessage::user(format!("question {turn}")),
+                    ChatMessage::assistant(format!("answer {turn}")),
+                ])
+                .await
+                .unwrap();
+        }
+
+        let branch = transcript.branch(2).unwrap();
+        assert_eq!(branch.session(), format!("{}.1", transcript.session()));
+        let history = branch.history(10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "answer one");
+        assert_eq!(load(tmp.path(), transcript.session()).unwrap().len(), 6);
+
+        let again = branch.branch(1).unwrap();
+        assert_eq!(again.session(), format!("{}.2", transcript.session()));
+        assert!(again.history(10).unwrap().is_empty());
+        assert!(again.branch(1).is_err());
+        assert!(transcript.branch(4).is_err());
+    }
+
+    #[test]
+    fn titles_sessions_from_the_summary_reply() {
+        let tmp = TempDir::new().unwrap();
+        let meta = parse_meta(
+            "**Title:** Planning a trip to Lisbon\nSummary: The user is planning a trip.\nThey prefer trains.",
+        )
+        .unwrap();
+        assert_eq!(meta.title, "Planning a trip to Lisbon");
+        assert_eq!(
+            meta.summary,
+            "The user is planning a trip. They prefer trains."
+        );
+        assert!(parse_meta("I can't help with that.").is_none());
+
+        let transcript = Transcript::new(tmp.path());
+        std::fs::write(transcript.path(), "").unwrap();
+        transcript.write_meta(&meta).unwrap();
+        let summaries = list(tmp.path()).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].title, "Planning a trip to Lisbon");
+        delete(tmp.path(), transcript.session()).unwrap();
+        assert!(read_meta(tmp.path(), transcript.session()).is_none());
+    }
+}